@@ -0,0 +1,83 @@
+use std::process::{Command, Stdio};
+
+/// Shows an OS notification with the platform's own tool: `osascript` on
+/// macOS, PowerShell on Windows and `notify-send` elsewhere.
+pub fn notify(title: &str, body: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            quoted(body, '"'),
+            quoted(title, '"')
+        ));
+        command
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command"]).arg(format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $icon = New-Object System.Windows.Forms.NotifyIcon; \
+             $icon.Icon = [System.Drawing.SystemIcons]::Warning; $icon.Visible = $true; \
+             $icon.ShowBalloonTip(10000, {}, {}, 'Warning'); Start-Sleep -Seconds 10",
+            quoted(title, '\''),
+            quoted(body, '\'')
+        ));
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=zagel", "--urgency=critical", title, body]);
+        command
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|mut child| {
+            std::thread::spawn(move || child.wait());
+        })
+}
+
+/// A string literal for `osascript` (double quotes) or PowerShell (single quotes).
+fn quoted(text: &str, quote: char) -> String {
+    let escaped = if quote == '"' {
+        text.replace('\\', "\\\\").replace('"', "\\\"")
+    } else {
+        text.replace('\'', "''")
+    };
+    format!("{quote}{escaped}{quote}")
+}
+
+/// Notification text for the high-priority requests that failed in a run,
+/// or `None` when there are none.
+pub fn failure_summary(source: &str, failed: &[String]) -> Option<(String, String)> {
+    let (first, rest) = failed.split_first()?;
+    let title = match rest.len() {
+        0 => format!("High-priority request failed in {source}"),
+        more => format!("{} high-priority requests failed in {source}", more + 1),
+    };
+    let mut body = first.clone();
+    for label in rest {
+        body.push('\n');
+        body.push_str(label);
+    }
+    Some((title, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{failure_summary, quoted};
+
+    #[test]
+    fn summaries_and_quoting() {
+        assert_eq!(failure_summary("api.http", &[]), None);
+        assert_eq!(
+            failure_summary("api.http", &["login".to_string(), "pay".to_string()]),
+            Some((
+                "2 high-priority requests failed in api.http".to_string(),
+                "login\npay".to_string()
+            ))
+        );
+        assert_eq!(quoted("say \"hi\"", '"'), "\"say \\\"hi\\\"\"");
+        assert_eq!(quoted("it's", '\''), "'it''s'");
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use iced::widget::{button, column, container, pick_list, row, text_input};
+use iced::{Element, Length, Task};
+
+use super::{Message, Zagel};
+use crate::jsonpath;
+use crate::model::{Assertion, AssertionKind, RequestDraft, RequestId, ResponsePreview};
+
+/// Result of one assertion against a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionOutcome {
+    pub label: String,
+    pub failure: Option<String>,
+}
+
+/// Assertions of sends still waiting for their response.
+#[derive(Debug, Default)]
+pub struct PendingAssertions {
+    by_request: HashMap<Option<RequestId>, Vec<Assertion>>,
+}
+
+/// `"text"` and `text` mean the same thing in header and body checks.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn check(assertion: &Assertion, response: &ResponsePreview) -> Result<(), String> {
+    if let Some(err) = &response.error {
+        return Err(format!("request failed: {err}"));
+    }
+    let body = || String::from_utf8_lossy(response.body.as_deref().unwrap_or_default());
+    match assertion.kind {
+        AssertionKind::Status => {
+            let actual = response.status.map(|status| status.to_string());
+            if actual.as_deref() == Some(assertion.expected.trim()) {
+                Ok(())
+            } else {
+                Err(format!("got {}", actual.as_deref().unwrap_or("no status")))
+            }
+        }
+        AssertionKind::Header => {
+            let name = assertion.target.trim();
+            let actual = response
+                .headers
+                .iter()
+                .find(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str());
+            match actual {
+                Some(value) if value.trim() == unquote(&assertion.expected) => Ok(()),
+                Some(value) => Err(format!("got {value}")),
+                None => Err(format!("no {name} header")),
+            }
+        }
+        AssertionKind::BodyContains => {
+            if body().contains(unquote(&assertion.expected)) {
+                Ok(())
+            } else {
+                Err("not found in body".to_string())
+            }
+        }
+        AssertionKind::JsonPath => {
+            let value = serde_json::from_str::<serde_json::Value>(&body())
+                .map_err(|_| "body is not JSON".to_string())?;
+            let actual = jsonpath::apply(&value, &assertion.target)?;
+            let expected = assertion.expected.trim();
+            let matches = serde_json::from_str::<serde_json::Value>(expected).map_or_else(
+                |_| actual.as_str() == Some(expected),
+                |expected| actual == expected,
+            );
+            if matches {
+                Ok(())
+            } else {
+                Err(format!("got {actual}"))
+            }
+        }
+    }
+}
+
+pub fn evaluate(assertions: &[Assertion], response: &ResponsePreview) -> Vec<AssertionOutcome> {
+    assertions
+        .iter()
+        .map(|assertion| AssertionOutcome {
+            label: assertion.to_string(),
+            failure: check(assertion, response).err(),
+        })
+        .collect()
+}
+
+pub fn summary(outcomes: &[AssertionOutcome]) -> String {
+    let passed = outcomes
+        .iter()
+        .filter(|outcome| outcome.failure.is_none())
+        .count();
+    format!("assertions {passed}/{} passed", outcomes.len())
+}
+
+pub fn editor(assertions: &[Assertion]) -> Element<'_, Message> {
+    let mut list = column![];
+    for (idx, assertion) in assertions.iter().enumerate() {
+        let kind = container(
+            pick_list(
+                AssertionKind::ALL.to_vec(),
+                Some(assertion.kind),
+                move |kind| Message::AssertionKindChanged(idx, kind),
+            )
+            .width(Length::Fill),
+        )
+        .width(Length::FillPortion(2))
+        .max_width(170.0);
+        let mut line = row![kind].spacing(6);
+        let target_placeholder = match assertion.kind {
+            AssertionKind::Header => Some("Header name"),
+            AssertionKind::JsonPath => Some("$.path"),
+            AssertionKind::Status | AssertionKind::BodyContains => None,
+        };
+        if let Some(placeholder) = target_placeholder {
+            line = line.push(
+                text_input(placeholder, &assertion.target)
+                    .on_input(move |val| Message::AssertionTargetChanged(idx, val))
+                    .padding(6)
+                    .width(Length::FillPortion(2)),
+            );
+        }
+        let expected_placeholder = match assertion.kind {
+            AssertionKind::Status => "200",
+            AssertionKind::BodyContains => "Text",
+            AssertionKind::Header | AssertionKind::JsonPath => "Expected value",
+        };
+        line = line
+            .push(
+                text_input(expected_placeholder, &assertion.expected)
+                    .on_input(move |val| Message::AssertionExpectedChanged(idx, val))
+                    .padding(6)
+                    .width(Length::FillPortion(3)),
+            )
+            .push(button("✕").on_press(Message::AssertionRemoved(idx)));
+        list = list.push(line);
+    }
+    list = list.push(button("Add assertion").on_press(Message::AssertionAdded));
+    list.spacing(6).into()
+}
+
+impl Zagel {
+    pub(super) fn arm_assertions(&mut self, request_id: Option<RequestId>, draft: &RequestDraft) {
+        if draft.assertions.is_empty() {
+            self.assertions.by_request.remove(&request_id);
+        } else {
+            self.assertions
+                .by_request
+                .insert(request_id, draft.assertions.clone());
+        }
+    }
+
+    pub(super) fn apply_assertions(
+        &mut self,
+        request_id: Option<&RequestId>,
+        response: &ResponsePreview,
+    ) -> Vec<AssertionOutcome> {
+        self.assertions
+            .by_request
+            .remove(&request_id.cloned())
+            .map(|assertions| evaluate(&assertions, response))
+            .unwrap_or_default()
+    }
+
+    pub(super) fn handle_assertion_added(&mut self) -> Task<Message> {
+        self.draft.assertions.push(Assertion {
+            expected: "200".to_string(),
+            ..Assertion::default()
+        });
+        Task::none()
+    }
+
+    pub(super) fn handle_assertion_removed(&mut self, idx: usize) -> Task<Message> {
+        if idx < self.draft.assertions.len() {
+            self.draft.assertions.remove(idx);
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_assertion_kind_changed(
+        &mut self,
+        idx: usize,
+        kind: AssertionKind,
+    ) -> Task<Message> {
+        if let Some(assertion) = self.draft.assertions.get_mut(idx) {
+            assertion.kind = kind;
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_assertion_target_changed(
+        &mut self,
+        idx: usize,
+        target: String,
+    ) -> Task<Message> {
+        if let Some(assertion) = self.draft.assertions.get_mut(idx) {
+            assertion.target = target;
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_assertion_expected_changed(
+        &mut self,
+        idx: usize,
+        expected: String,
+    ) -> Task<Message> {
+        if let Some(assertion) = self.draft.assertions.get_mut(idx) {
+            assertion.expected = expected;
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, summary};
+    use crate::model::{Assertion, ResponsePreview};
+
+    fn response() -> ResponsePreview {
+        ResponsePreview {
+            status: Some(200),
+            duration: None,
+            body: Some(br#"{"items":[{"id":7,"name":"ok"}]}"#.to_vec()),
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            error: None,
+            version: None,
+            url: None,
+        }
+    }
+
+    fn outcomes(directives: &[&str], response: &ResponsePreview) -> Vec<Option<String>> {
+        let assertions = directives
+            .iter()
+            .map(|directive| Assertion::parse(directive).unwrap())
+            .collect::<Vec<_>>();
+        evaluate(&assertions, response)
+            .into_iter()
+            .map(|outcome| outcome.failure)
+            .collect()
+    }
+
+    #[test]
+    fn assertions_check_status_headers_and_body() {
+        let failures = outcomes(
+            &[
+                "status == 200",
+                "header.Content-Type == \"application/json\"",
+                "body contains \"name\"",
+                "body.$.items[0].id == 7",
+                "body.$.items[0].name == ok",
+                "status == 201",
+                "body.$.items[0].id == \"7\"",
+                "header.ETag == x",
+            ],
+            &response(),
+        );
+        assert_eq!(
+            failures,
+            vec![
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("got 200".to_string()),
+                Some("got 7".to_string()),
+                Some("no ETag header".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn transport_errors_fail_every_assertion() {
+        let failed = ResponsePreview::error("timed out".to_string());
+        let assertions = vec![Assertion::parse("status == 200").unwrap()];
+        let results = evaluate(&assertions, &failed);
+        assert_eq!(
+            results[0].failure.as_deref(),
+            Some("request failed: timed out")
+        );
+        assert_eq!(summary(&results), "assertions 0/1 passed");
+    }
+}
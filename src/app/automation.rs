@@ -3,12 +3,14 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
-use iced::{Subscription, Task, time, window};
+use iced::advanced::widget::{self, Operation, operation};
+use iced::{Rectangle, Size, Subscription, Task, time, window};
 use image::RgbaImage;
 use serde::{Deserialize, Serialize};
 
 use crate::launch::AutomationOptions;
 use crate::model::RequestId;
+use crate::pathing::ProjectRoot;
 
 use super::{Message, Zagel};
 
@@ -19,13 +21,20 @@ const DEFAULT_WAIT_TIMEOUT_MS: u64 = 20_000;
 pub(super) struct AutomationRuntime {
     scenario_name: String,
     steps: Vec<ScenarioStep>,
+    /// Index of the first `[teardown]` step; equals `steps.len()` without teardown.
+    teardown_start: usize,
+    /// Failure from setup or main steps, reported once teardown has run.
+    deferred_failure: Option<String>,
     current_step: usize,
     pending_wait: Option<PendingWait>,
-    pending_screenshot_name: Option<String>,
+    pending_screenshot: Option<PendingScreenshot>,
     screenshot_dir: PathBuf,
     state_output_path: Option<PathBuf>,
     window_id: Option<window::Id>,
     exit_when_done: bool,
+    /// Watchdog for the whole scenario, from `--automation-timeout`.
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
     done: bool,
 }
 
@@ -41,23 +50,37 @@ impl AutomationRuntime {
             )
         })?;
 
-        let mut all_steps = parsed.step;
-        all_steps.extend(parsed.steps);
-        if all_steps.is_empty() {
+        let main_steps = StepList {
+            step: parsed.step,
+            steps: parsed.steps,
+        }
+        .into_raw();
+        if main_steps.is_empty() {
             return Err(format!(
                 "scenario {} has no [[step]] entries",
                 scenario_path.display()
             ));
         }
 
-        let mut steps = Vec::with_capacity(all_steps.len());
-        for (index, raw_step) in all_steps.iter().enumerate() {
-            steps.push(ScenarioStep::from_raw(raw_step).map_err(|err| {
-                format!(
-                    "invalid scenario step #{index} in {}: {err}",
-                    scenario_path.display()
-                )
-            })?);
+        let sections = [
+            ("setup", parsed.setup.into_raw()),
+            ("step", main_steps),
+            ("teardown", parsed.teardown.into_raw()),
+        ];
+        let mut steps = Vec::new();
+        let mut teardown_start = 0;
+        for (section, raw_steps) in sections {
+            if section == "teardown" {
+                teardown_start = steps.len();
+            }
+            for (index, raw_step) in raw_steps.iter().enumerate() {
+                steps.push(ScenarioStep::from_raw(raw_step).map_err(|err| {
+                    format!(
+                        "invalid scenario {section} #{index} in {}: {err}",
+                        scenario_path.display()
+                    )
+                })?);
+            }
         }
 
         fs::create_dir_all(&options.screenshot_dir).map_err(|err| {
@@ -89,19 +112,40 @@ impl AutomationRuntime {
         Ok(Self {
             scenario_name,
             steps,
+            teardown_start,
+            deferred_failure: None,
             current_step: 0,
             pending_wait: None,
-            pending_screenshot_name: None,
+            pending_screenshot: None,
             screenshot_dir: options.screenshot_dir,
             state_output_path: options.state_output_path,
             window_id: None,
             exit_when_done: options.exit_when_done,
+            timeout: options.timeout,
+            deadline: options.timeout.map(|timeout| Instant::now() + timeout),
             done: false,
         })
     }
 
     const fn should_poll(&self) -> bool {
-        self.pending_wait.is_some() && !self.done
+        (self.pending_wait.is_some() || self.deadline.is_some()) && !self.done
+    }
+
+    /// The failure reason once the scenario has outlived its deadline.
+    fn watchdog_expired(&self, now: Instant) -> Option<String> {
+        let deadline = self.deadline?;
+        let timeout = self.timeout?;
+        if now < deadline {
+            return None;
+        }
+        let step = self.steps.get(self.current_step).map_or_else(
+            || "after the last step".to_string(),
+            |step| format!("at step #{} ({})", self.current_step, step.action_name()),
+        );
+        Some(format!(
+            "scenario exceeded --automation-timeout of {}s {step}",
+            timeout.as_secs()
+        ))
     }
 }
 
@@ -123,10 +167,74 @@ enum ScenarioStep {
     WaitForMillis(Duration),
     Screenshot {
         name: String,
+        region: Option<ScreenshotRegion>,
     },
+    CreateDir(PathBuf),
+    DeletePath(PathBuf),
+    AddProject(String),
+    RemoveProject(String),
+}
+
+/// Pane a `screenshot` step can be cropped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ScreenshotRegion {
+    Sidebar,
+    Builder,
+    Response,
+}
+
+impl ScreenshotRegion {
+    fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "sidebar" => Ok(Self::Sidebar),
+            "builder" => Ok(Self::Builder),
+            "response" => Ok(Self::Response),
+            other => Err(format!(
+                "unsupported screenshot region '{other}' (expected sidebar, builder, or response)"
+            )),
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Sidebar => "sidebar",
+            Self::Builder => "builder",
+            Self::Response => "response",
+        }
+    }
+
+    pub(super) const fn widget_id(self) -> widget::Id {
+        widget::Id::new(match self {
+            Self::Sidebar => "automation-region-sidebar",
+            Self::Builder => "automation-region-builder",
+            Self::Response => "automation-region-response",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingScreenshot {
+    name: String,
+    region: Option<ScreenshotRegion>,
+    bounds: Option<Rectangle>,
 }
 
 impl ScenarioStep {
+    const fn action_name(&self) -> &'static str {
+        match self {
+            Self::SelectRequest { .. } => "select_request",
+            Self::Send => "send",
+            Self::WaitForStatus { .. } => "wait_for_status",
+            Self::WaitForText { .. } => "wait_for_text",
+            Self::WaitForMillis(_) => "wait_for_millis",
+            Self::Screenshot { .. } => "screenshot",
+            Self::CreateDir(_) => "create_dir",
+            Self::DeletePath(_) => "delete_path",
+            Self::AddProject(_) => "add_project",
+            Self::RemoveProject(_) => "remove_project",
+        }
+    }
+
     fn from_raw(raw: &RawStep) -> Result<Self, String> {
         let timeout = Duration::from_millis(raw.timeout_ms.unwrap_or(DEFAULT_WAIT_TIMEOUT_MS));
         match raw.action.trim().to_ascii_lowercase().as_str() {
@@ -150,8 +258,25 @@ impl ScenarioStep {
             }
             "screenshot" => {
                 let name = raw.required_string("screenshot")?.to_string();
-                Ok(Self::Screenshot { name })
+                let region = raw
+                    .region
+                    .as_deref()
+                    .map(ScreenshotRegion::parse)
+                    .transpose()?;
+                Ok(Self::Screenshot { name, region })
             }
+            "create_dir" => Ok(Self::CreateDir(PathBuf::from(
+                raw.required_string("create_dir")?,
+            ))),
+            "delete_path" => Ok(Self::DeletePath(PathBuf::from(
+                raw.required_string("delete_path")?,
+            ))),
+            "add_project" => Ok(Self::AddProject(
+                raw.required_string("add_project")?.to_string(),
+            )),
+            "remove_project" => Ok(Self::RemoveProject(
+                raw.required_string("remove_project")?.to_string(),
+            )),
             other => Err(format!("unsupported action '{other}'")),
         }
     }
@@ -208,17 +333,40 @@ impl RequestSelector {
 struct ScenarioFile {
     #[serde(default)]
     name: Option<String>,
+    #[serde(default)]
+    setup: StepList,
+    #[serde(default)]
+    step: Vec<RawStep>,
+    #[serde(default)]
+    steps: Vec<RawStep>,
+    #[serde(default)]
+    teardown: StepList,
+}
+
+/// `[setup]`/`[teardown]` sections accept the same `step`/`steps` arrays as the scenario root.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct StepList {
     #[serde(default)]
     step: Vec<RawStep>,
     #[serde(default)]
     steps: Vec<RawStep>,
 }
 
+impl StepList {
+    fn into_raw(self) -> Vec<RawStep> {
+        let mut all = self.step;
+        all.extend(self.steps);
+        all
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct RawStep {
     action: String,
     value: Option<StepValue>,
     timeout_ms: Option<u64>,
+    #[serde(default)]
+    region: Option<String>,
 }
 
 impl RawStep {
@@ -308,6 +456,7 @@ struct RequestDraftSnapshot {
     url: String,
     headers: String,
     body: String,
+    step: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -360,6 +509,53 @@ fn immediate(message: Message) -> Task<Message> {
     Task::perform(async move { message }, |message| message)
 }
 
+/// Converts logical widget bounds into a physical crop rectangle clamped to the image.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn physical_crop(bounds: Rectangle, scale_factor: f32, size: Size<u32>) -> Option<Rectangle<u32>> {
+    let to_physical = |value: f32| (value * scale_factor).round().max(0.0) as u32;
+    let x = to_physical(bounds.x).min(size.width);
+    let y = to_physical(bounds.y).min(size.height);
+    let width = to_physical(bounds.width).min(size.width - x);
+    let height = to_physical(bounds.height).min(size.height - y);
+    (width > 0 && height > 0).then_some(Rectangle {
+        x,
+        y,
+        width,
+        height,
+    })
+}
+
+/// Finds the layout bounds of the container with the given id.
+fn find_container_bounds(target: widget::Id) -> impl Operation<Option<Rectangle>> {
+    struct FindBounds {
+        target: widget::Id,
+        found: Option<Rectangle>,
+    }
+
+    impl Operation<Option<Rectangle>> for FindBounds {
+        fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation<Option<Rectangle>>)) {
+            if self.found.is_none() {
+                operate(self);
+            }
+        }
+
+        fn container(&mut self, id: Option<&widget::Id>, bounds: Rectangle) {
+            if id == Some(&self.target) {
+                self.found = Some(bounds);
+            }
+        }
+
+        fn finish(&self) -> operation::Outcome<Option<Rectangle>> {
+            operation::Outcome::Some(self.found)
+        }
+    }
+
+    FindBounds {
+        target,
+        found: None,
+    }
+}
+
 fn save_png(path: &Path, screenshot: window::Screenshot) -> Result<(), String> {
     let window::Screenshot { rgba, size, .. } = screenshot;
     let Some(image) = RgbaImage::from_raw(size.width, size.height, rgba.to_vec()) else {
@@ -377,6 +573,7 @@ fn snapshot_request_draft(draft: &crate::model::RequestDraft) -> RequestDraftSna
         url: draft.url.clone(),
         headers: draft.headers.clone(),
         body: draft.body.clone(),
+        step: draft.step.clone(),
     }
 }
 
@@ -462,12 +659,28 @@ impl Zagel {
             return Task::none();
         };
 
-        let task = if let Some(name) = runtime.pending_screenshot_name.take() {
-            let stem = sanitize_screenshot_name(&name);
+        // A capture can still arrive after the watchdog finished the scenario.
+        let task = if runtime.done {
+            Task::none()
+        } else if let Some(pending) = runtime.pending_screenshot.take() {
+            let stem = sanitize_screenshot_name(&pending.name);
             let path = runtime
                 .screenshot_dir
                 .join(format!("{:02}-{stem}.png", runtime.current_step + 1));
-            match save_png(&path, screenshot) {
+            let cropped = match (pending.region, pending.bounds) {
+                (Some(region), Some(bounds)) => {
+                    physical_crop(bounds, screenshot.scale_factor, screenshot.size)
+                        .and_then(|crop| screenshot.crop(crop).ok())
+                        .ok_or_else(|| {
+                            format!(
+                                "screenshot region '{}' is outside the captured window",
+                                region.as_str()
+                            )
+                        })
+                }
+                _ => Ok(screenshot),
+            };
+            match cropped.and_then(|screenshot| save_png(&path, screenshot)) {
                 Ok(()) => {
                     runtime.current_step += 1;
                     self.update_status_with_missing(&format!(
@@ -489,6 +702,36 @@ impl Zagel {
         task
     }
 
+    pub(super) fn handle_automation_region_bounds(
+        &mut self,
+        bounds: Option<Rectangle>,
+    ) -> Task<Message> {
+        let Some(mut runtime) = self.automation.take() else {
+            return Task::none();
+        };
+
+        let task = match (runtime.pending_screenshot.as_mut(), runtime.window_id) {
+            _ if runtime.done => Task::none(),
+            (Some(pending), Some(window_id)) => match (pending.region, bounds) {
+                (Some(region), None) => {
+                    let reason = format!("screenshot region '{}' is not visible", region.as_str());
+                    self.fail_automation(&mut runtime, &reason)
+                }
+                (_, bounds) => {
+                    pending.bounds = bounds;
+                    window::screenshot(window_id).map(Message::AutomationScreenshotCaptured)
+                }
+            },
+            _ => self.fail_automation(
+                &mut runtime,
+                "received region bounds but no screenshot step is pending",
+            ),
+        };
+
+        self.automation = Some(runtime);
+        task
+    }
+
     fn write_automation_state_snapshot(
         &self,
         runtime: &AutomationRuntime,
@@ -595,7 +838,7 @@ impl Zagel {
                 .collect(),
             environments,
             draft: snapshot_request_draft(&self.draft),
-            graphql_query: self.graphql_query.text(),
+            graphql_query: self.graphql_query_text(),
             graphql_variables: self.graphql_variables.text(),
             header_rows: self
                 .header_rows
@@ -695,6 +938,9 @@ impl Zagel {
     }
 
     fn complete_automation(&mut self, runtime: &mut AutomationRuntime) -> Task<Message> {
+        if let Some(reason) = runtime.deferred_failure.take() {
+            return self.finish_failed_automation(runtime, &reason);
+        }
         runtime.done = true;
         self.update_status_with_missing(&format!(
             "Automation '{}' completed",
@@ -726,6 +972,30 @@ impl Zagel {
     }
 
     fn fail_automation(&mut self, runtime: &mut AutomationRuntime, reason: &str) -> Task<Message> {
+        if runtime.current_step < runtime.teardown_start
+            && runtime.teardown_start < runtime.steps.len()
+        {
+            runtime.deferred_failure = Some(reason.to_string());
+            runtime.pending_wait = None;
+            runtime.pending_screenshot = None;
+            runtime.current_step = runtime.teardown_start;
+            self.update_status_with_missing(&format!(
+                "Automation failed: {reason} (running teardown)"
+            ));
+            return self.drive_automation(runtime);
+        }
+        let reason = runtime.deferred_failure.take().map_or_else(
+            || reason.to_string(),
+            |original| format!("{original}; teardown failed: {reason}"),
+        );
+        self.finish_failed_automation(runtime, &reason)
+    }
+
+    fn finish_failed_automation(
+        &mut self,
+        runtime: &mut AutomationRuntime,
+        reason: &str,
+    ) -> Task<Message> {
         runtime.done = true;
         self.update_status_with_missing(&format!("Automation failed: {reason}"));
         let state_path = match self
@@ -754,11 +1024,23 @@ impl Zagel {
         }
     }
 
+    #[allow(clippy::too_many_lines)]
     fn drive_automation(&mut self, runtime: &mut AutomationRuntime) -> Task<Message> {
         if runtime.done {
             return Task::none();
         }
 
+        if let Some(reason) = runtime.watchdog_expired(Instant::now()) {
+            // Skip teardown: a scenario that is already stuck may hang there too.
+            let reason = runtime.deferred_failure.take().map_or_else(
+                || reason.clone(),
+                |original| format!("{original}; {reason}"),
+            );
+            runtime.pending_wait = None;
+            runtime.pending_screenshot = None;
+            return self.finish_failed_automation(runtime, &reason);
+        }
+
         if let Some(wait) = runtime.pending_wait.as_ref() {
             if self.wait_satisfied(wait) {
                 runtime.pending_wait = None;
@@ -829,15 +1111,189 @@ impl Zagel {
                     });
                     return Task::none();
                 }
-                ScenarioStep::Screenshot { name } => {
+                ScenarioStep::Screenshot { name, region } => {
                     let Some(window_id) = runtime.window_id else {
                         return window::latest().map(Message::AutomationWindowResolved);
                     };
-                    runtime.pending_screenshot_name = Some(name);
+                    runtime.pending_screenshot = Some(PendingScreenshot {
+                        name,
+                        region,
+                        bounds: None,
+                    });
+                    if let Some(region) = region {
+                        return widget::operate(find_container_bounds(region.widget_id()))
+                            .map(Message::AutomationRegionBounds);
+                    }
                     return window::screenshot(window_id)
                         .map(Message::AutomationScreenshotCaptured);
                 }
+                ScenarioStep::CreateDir(path) => {
+                    if let Err(err) = fs::create_dir_all(&path) {
+                        let reason = format!("failed to create {}: {err}", path.display());
+                        return self.fail_automation(runtime, &reason);
+                    }
+                    runtime.current_step += 1;
+                }
+                ScenarioStep::DeletePath(path) => {
+                    if let Err(err) = delete_path(&path) {
+                        let reason = format!("failed to delete {}: {err}", path.display());
+                        return self.fail_automation(runtime, &reason);
+                    }
+                    runtime.current_step += 1;
+                }
+                ScenarioStep::AddProject(path) => {
+                    runtime.current_step += 1;
+                    self.project_path_input = path;
+                    return immediate(Message::AddProject);
+                }
+                ScenarioStep::RemoveProject(path) => match ProjectRoot::parse_user_input(&path) {
+                    Ok(root) => {
+                        runtime.current_step += 1;
+                        return immediate(Message::RemoveProject(root));
+                    }
+                    Err(err) => {
+                        let reason = format!("invalid project folder {path}: {err}");
+                        return self.fail_automation(runtime, &reason);
+                    }
+                },
             }
         }
     }
 }
+
+/// Removes a file or directory tree; a missing path is not an error.
+fn delete_path(path: &Path) -> std::io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    use iced::{Rectangle, Size};
+    use tempfile::tempdir;
+
+    use super::{AutomationRuntime, ScenarioStep, ScreenshotRegion, delete_path, physical_crop};
+    use crate::launch::AutomationOptions;
+
+    #[test]
+    fn physical_crop_scales_and_clamps_to_image() {
+        let bounds = Rectangle {
+            x: 10.0,
+            y: 20.0,
+            width: 300.0,
+            height: 400.0,
+        };
+        let crop = physical_crop(bounds, 2.0, Size::new(500, 600)).expect("crop");
+
+        assert_eq!((crop.x, crop.y), (20, 40));
+        assert_eq!((crop.width, crop.height), (480, 560));
+    }
+
+    #[test]
+    fn physical_crop_rejects_regions_outside_image() {
+        let bounds = Rectangle {
+            x: 900.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+        };
+        assert!(physical_crop(bounds, 1.0, Size::new(800, 600)).is_none());
+    }
+
+    #[test]
+    fn screenshot_region_parses_known_panes() {
+        assert_eq!(
+            ScreenshotRegion::parse(" Response ").expect("region"),
+            ScreenshotRegion::Response
+        );
+        assert!(ScreenshotRegion::parse("toolbar").is_err());
+    }
+
+    #[test]
+    fn setup_and_teardown_wrap_main_steps() {
+        let dir = tempdir().unwrap();
+        let scenario = dir.path().join("hermetic.toml");
+        fs::write(
+            &scenario,
+            r#"
+[[setup.step]]
+action = "create_dir"
+value = "tmp/project"
+
+[[step]]
+action = "send"
+
+[[teardown.step]]
+action = "delete_path"
+value = "tmp/project"
+"#,
+        )
+        .unwrap();
+
+        let runtime = AutomationRuntime::load(AutomationOptions {
+            scenario_path: scenario,
+            screenshot_dir: dir.path().join("shots"),
+            state_output_path: None,
+            exit_when_done: false,
+            timeout: None,
+        })
+        .expect("scenario loads");
+
+        assert_eq!(runtime.steps.len(), 3);
+        assert!(matches!(runtime.steps[0], ScenarioStep::CreateDir(_)));
+        assert!(matches!(runtime.steps[1], ScenarioStep::Send));
+        assert!(matches!(runtime.steps[2], ScenarioStep::DeletePath(_)));
+        assert_eq!(runtime.teardown_start, 2);
+    }
+
+    #[test]
+    fn watchdog_reports_the_step_it_stopped_at() {
+        let dir = tempdir().unwrap();
+        let scenario = dir.path().join("stuck.toml");
+        fs::write(
+            &scenario,
+            "[[step]]\naction = \"send\"\n\n[[step]]\naction = \"wait_for_text\"\nvalue = \"never\"\n",
+        )
+        .unwrap();
+
+        let mut runtime = AutomationRuntime::load(AutomationOptions {
+            scenario_path: scenario,
+            screenshot_dir: dir.path().join("shots"),
+            state_output_path: None,
+            exit_when_done: false,
+            timeout: Some(Duration::from_secs(5)),
+        })
+        .expect("scenario loads");
+        assert!(runtime.should_poll());
+
+        let deadline = runtime.deadline.expect("timeout sets a deadline");
+        assert_eq!(runtime.watchdog_expired(Instant::now()), None);
+
+        runtime.current_step = 1;
+        assert_eq!(
+            runtime
+                .watchdog_expired(deadline + Duration::from_secs(1))
+                .as_deref(),
+            Some("scenario exceeded --automation-timeout of 5s at step #1 (wait_for_text)")
+        );
+    }
+
+    #[test]
+    fn delete_path_removes_trees_and_ignores_missing_paths() {
+        let dir = tempdir().unwrap();
+        let tree = dir.path().join("generated");
+        fs::create_dir_all(tree.join("nested")).unwrap();
+        fs::write(tree.join("nested/file.txt"), "x").unwrap();
+
+        delete_path(&tree).expect("delete tree");
+        assert!(!tree.exists());
+        delete_path(&tree).expect("missing path is fine");
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::backup::{self, Backup};
+use crate::state::AppState;
+
+use super::lifecycle::load_configured_roots;
+use super::missing_roots::MissingRoots;
+use super::{Message, Zagel};
+
+/// Backup controls in the settings panel.
+#[derive(Debug, Clone)]
+pub struct BackupForm {
+    pub path: String,
+    pub secrets: bool,
+    /// Archive read for restoring with its decoded state, waiting for
+    /// confirmation.
+    pub pending: Option<(Backup, AppState)>,
+}
+
+impl Default for BackupForm {
+    fn default() -> Self {
+        let path = dirs::home_dir()
+            .unwrap_or_default()
+            .join("zagel-backup.json");
+        Self {
+            path: path.display().to_string(),
+            secrets: false,
+            pending: None,
+        }
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_backup_path_changed(&mut self, path: String) -> Task<Message> {
+        self.backup.path = path;
+        self.backup.pending = None;
+        Task::none()
+    }
+
+    pub(super) fn handle_backup_secrets_toggled(&mut self, secrets: bool) -> Task<Message> {
+        self.backup.secrets = secrets;
+        Task::none()
+    }
+
+    pub(super) fn handle_export_backup(&mut self) -> Task<Message> {
+        self.persist_state();
+        let path = PathBuf::from(self.backup.path.trim());
+        let status = backup::create(&self.state, self.backup.secrets)
+            .and_then(|backup| {
+                backup::write(&backup, &path)?;
+                Ok(backup.files.len())
+            })
+            .map_or_else(
+                |err| format!("Backup failed: {err}"),
+                |count| format!("Backed up {count} files to {}", path.display()),
+            );
+        self.update_status_with_missing(&status);
+        Task::none()
+    }
+
+    /// Reads the archive and asks for confirmation before anything is written.
+    pub(super) fn handle_restore_backup(&mut self) -> Task<Message> {
+        let read = backup::read(&PathBuf::from(self.backup.path.trim()))
+            .and_then(|backup| Ok((backup.state()?, backup)));
+        match read {
+            Ok((state, backup)) => self.backup.pending = Some((backup, state)),
+            Err(err) => self.update_status_with_missing(&format!("Restore failed: {err}")),
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_restore_backup_cancelled(&mut self) -> Task<Message> {
+        self.backup.pending = None;
+        Task::none()
+    }
+
+    /// Writes the files back and reloads the project folders and settings
+    /// from the backed up state.
+    pub(super) fn handle_restore_backup_confirmed(&mut self) -> Task<Message> {
+        let Some((backup, state)) = self.backup.pending.take() else {
+            return Task::none();
+        };
+        let refused = backup
+            .refused(&state)
+            .map(|file| file.path.display().to_string())
+            .collect::<Vec<_>>();
+        let written = match backup.restore(&state) {
+            Ok(restored) => restored,
+            Err(err) => {
+                self.update_status_with_missing(&format!("Restore failed: {err}"));
+                return Task::none();
+            }
+        };
+        let (configuration, missing) = load_configured_roots(&state);
+        self.ca_certificate_input = state
+            .ca_certificate
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        self.state = state;
+        super::editor::ensure_token(&mut self.state);
+        self.configuration = configuration;
+        self.missing_roots = if self.state.forget_missing_roots {
+            MissingRoots::default()
+        } else {
+            MissingRoots::new(missing.clone())
+        };
+        self.workspace.clear_selection();
+        self.workspace.sync_with_configuration(&self.configuration);
+        self.persist_state();
+        let mut status = format!("Restored {written} files from the backup");
+        if !refused.is_empty() {
+            let _ = write!(
+                status,
+                "; refused {} files outside the backed up folders: {}",
+                refused.len(),
+                refused.join(", ")
+            );
+        }
+        for root in missing {
+            status.push_str("; ");
+            status.push_str(&root.to_string());
+        }
+        self.update_status_with_missing(&status);
+        self.rescan_files()
+    }
+}
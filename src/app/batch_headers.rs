@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::model::{HttpFile, RequestId};
+use crate::parser::write_http_file;
+
+use super::headers::{has_header, push_header};
+use super::{EditState, EditTarget, Message, Zagel};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderOp {
+    /// Adds the header to requests that do not set it yet.
+    #[default]
+    Add,
+    /// Changes the value of requests that already set it.
+    Replace,
+    Remove,
+}
+
+impl HeaderOp {
+    pub const ALL: [Self; 3] = [Self::Add, Self::Replace, Self::Remove];
+}
+
+impl std::fmt::Display for HeaderOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Add => "Add header",
+            Self::Replace => "Replace header",
+            Self::Remove => "Remove header",
+        })
+    }
+}
+
+/// Header operation applied to every request selected in edit mode.
+#[derive(Debug, Clone, Default)]
+pub struct BatchHeaders {
+    pub op: HeaderOp,
+    pub name: String,
+    pub value: String,
+}
+
+/// `headers` after the operation, or `None` when it leaves them unchanged.
+fn apply_op(headers: &str, op: HeaderOp, name: &str, value: &str) -> Option<String> {
+    let matches = |line: &str| {
+        line.split_once(':')
+            .is_some_and(|(existing, _)| existing.trim().eq_ignore_ascii_case(name))
+    };
+    let updated = match op {
+        HeaderOp::Add if has_header(headers, name) => return None,
+        HeaderOp::Add => {
+            let mut updated = headers.to_string();
+            push_header(&mut updated, name, value);
+            updated
+        }
+        HeaderOp::Replace => headers
+            .lines()
+            .map(|line| {
+                if matches(line) {
+                    format!("{name}: {value}")
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        HeaderOp::Remove => headers
+            .lines()
+            .filter(|line| !matches(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    (updated != headers).then_some(updated)
+}
+
+/// Request indices per file covered by the edit selection; a selected
+/// collection stands for all of its requests.
+fn selected_requests(
+    selection: &HashSet<EditTarget>,
+    files: &HashMap<PathBuf, HttpFile>,
+) -> BTreeMap<PathBuf, Vec<usize>> {
+    let mut selected: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+    for target in selection {
+        match target {
+            EditTarget::Collection(path) => {
+                if let Some(file) = files.get(path) {
+                    selected
+                        .entry(path.clone())
+                        .or_default()
+                        .extend(0..file.requests.len());
+                }
+            }
+            EditTarget::Request(RequestId::HttpFile { path, index }) => {
+                selected.entry(path.clone()).or_default().push(*index);
+            }
+        }
+    }
+    for indices in selected.values_mut() {
+        indices.sort_unstable();
+        indices.dedup();
+    }
+    selected
+}
+
+impl Zagel {
+    pub(super) fn handle_batch_header_op_selected(&mut self, op: HeaderOp) -> Task<Message> {
+        self.batch_headers.op = op;
+        Task::none()
+    }
+
+    pub(super) fn handle_batch_header_name_changed(&mut self, name: String) -> Task<Message> {
+        self.batch_headers.name = name;
+        Task::none()
+    }
+
+    pub(super) fn handle_batch_header_value_changed(&mut self, value: String) -> Task<Message> {
+        self.batch_headers.value = value;
+        Task::none()
+    }
+
+    /// Rewrites each affected file once; a file that fails to write keeps
+    /// its requests unchanged.
+    pub(super) fn handle_batch_headers_applied(&mut self) -> Task<Message> {
+        let EditState::On { selection } = &self.edit_state else {
+            return Task::none();
+        };
+        let selected = selected_requests(selection, self.workspace.http_files());
+        let BatchHeaders { op, name, value } = self.batch_headers.clone();
+        let name = name.trim();
+        if name.is_empty() || name.contains(':') {
+            self.update_status_with_missing("Enter a header name");
+            return Task::none();
+        }
+        let mut changed = Vec::new();
+        let mut errors = Vec::new();
+        if let Some(mut workspace) = self.workspace.configured_state() {
+            for (path, indices) in selected {
+                let Some(file) = workspace.http_files_mut().get_mut(&path) else {
+                    continue;
+                };
+                let mut requests = file.requests.clone();
+                let mut file_changed = Vec::new();
+                for index in indices {
+                    let Some(request) = requests.get_mut(index) else {
+                        continue;
+                    };
+                    if let Some(headers) = apply_op(&request.headers, op, name, value.trim()) {
+                        request.headers = headers;
+                        file_changed.push(RequestId::HttpFile {
+                            path: path.clone(),
+                            index,
+                        });
+                    }
+                }
+                if file_changed.is_empty() {
+                    continue;
+                }
+                match write_http_file(&path, &requests) {
+                    Ok(()) => {
+                        file.requests = requests;
+                        changed.extend(file_changed);
+                    }
+                    Err(err) => errors.push(format!("Failed to update {}: {err}", path.display())),
+                }
+            }
+        }
+        if let Some(id) = self.workspace.selection_cloned()
+            && changed.contains(&id)
+        {
+            self.apply_selection(&id);
+        }
+        let status = if errors.is_empty() {
+            format!("Updated headers of {} requests", changed.len())
+        } else {
+            errors.join("; ")
+        };
+        self.update_status_with_missing(&status);
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HeaderOp, apply_op};
+
+    #[test]
+    fn operations_only_touch_requests_they_change() {
+        let headers = "Accept: */*\nx-api-version: 1";
+        assert_eq!(apply_op(headers, HeaderOp::Add, "X-Api-Version", "2"), None);
+        assert_eq!(
+            apply_op("Accept: */*", HeaderOp::Add, "X-Api-Version", "2").as_deref(),
+            Some("Accept: */*\nX-Api-Version: 2")
+        );
+        assert_eq!(
+            apply_op(headers, HeaderOp::Replace, "X-Api-Version", "2").as_deref(),
+            Some("Accept: */*\nX-Api-Version: 2")
+        );
+        assert_eq!(
+            apply_op(headers, HeaderOp::Remove, "X-API-VERSION", "").as_deref(),
+            Some("Accept: */*")
+        );
+        assert_eq!(
+            apply_op("Accept: */*", HeaderOp::Remove, "X-Api-Version", ""),
+            None
+        );
+    }
+}
@@ -0,0 +1,601 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use iced::Task;
+
+use crate::model::{RequestDraft, RequestId};
+use crate::net::{SendOutcome, send_request};
+use crate::timings::{TimingRecord, to_csv};
+
+use super::{EditState, EditTarget, Message, Zagel};
+
+const DEFAULT_ITERATIONS: usize = 20;
+const MAX_ITERATIONS: usize = 1_000;
+const HISTOGRAM_BUCKETS: usize = 8;
+const DEFAULT_EXPORT_FILE: &str = "benchmark-timings.csv";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchmarkVariant {
+    A,
+    B,
+}
+
+impl BenchmarkVariant {
+    pub const ALL: [Self; 2] = [Self::A, Self::B];
+
+    const fn for_step(step: usize) -> Self {
+        if step.is_multiple_of(2) {
+            Self::A
+        } else {
+            Self::B
+        }
+    }
+}
+
+impl std::fmt::Display for BenchmarkVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::A => f.write_str("A"),
+            Self::B => f.write_str("B"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkTarget {
+    pub id: RequestId,
+    pub draft: RequestDraft,
+}
+
+#[derive(Debug, Clone)]
+pub enum BenchmarkSample {
+    Response { status: u16, duration: Duration },
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchmarkPhase {
+    Configuring,
+    Running {
+        run_id: u64,
+        step: usize,
+        total: usize,
+    },
+    Finished,
+    Stopped,
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkSession {
+    pub a: BenchmarkTarget,
+    pub b: BenchmarkTarget,
+    pub iterations_input: String,
+    pub export_path_input: String,
+    pub phase: BenchmarkPhase,
+    samples_a: Vec<BenchmarkSample>,
+    samples_b: Vec<BenchmarkSample>,
+    timeline: Vec<TimingRecord>,
+    last_run_id: u64,
+}
+
+impl BenchmarkSession {
+    fn new(a: BenchmarkTarget, b: BenchmarkTarget) -> Self {
+        Self {
+            a,
+            b,
+            iterations_input: DEFAULT_ITERATIONS.to_string(),
+            export_path_input: DEFAULT_EXPORT_FILE.to_string(),
+            phase: BenchmarkPhase::Configuring,
+            samples_a: Vec::new(),
+            samples_b: Vec::new(),
+            timeline: Vec::new(),
+            last_run_id: 0,
+        }
+    }
+
+    pub const fn target(&self, variant: BenchmarkVariant) -> &BenchmarkTarget {
+        match variant {
+            BenchmarkVariant::A => &self.a,
+            BenchmarkVariant::B => &self.b,
+        }
+    }
+
+    pub fn samples(&self, variant: BenchmarkVariant) -> &[BenchmarkSample] {
+        match variant {
+            BenchmarkVariant::A => &self.samples_a,
+            BenchmarkVariant::B => &self.samples_b,
+        }
+    }
+
+    pub fn last_error(&self, variant: BenchmarkVariant) -> Option<&str> {
+        self.samples(variant)
+            .iter()
+            .rev()
+            .find_map(|sample| match sample {
+                BenchmarkSample::Failed(err) => Some(err.as_str()),
+                BenchmarkSample::Response { .. } => None,
+            })
+    }
+
+    pub fn summary(&self, variant: BenchmarkVariant) -> LatencySummary {
+        LatencySummary::from_samples(self.samples(variant))
+    }
+
+    pub fn histogram(&self) -> Option<Histogram> {
+        Histogram::build(&self.samples_a, &self.samples_b)
+    }
+
+    pub const fn has_timings(&self) -> bool {
+        !self.timeline.is_empty()
+    }
+
+    pub const fn is_running(&self) -> bool {
+        matches!(self.phase, BenchmarkPhase::Running { .. })
+    }
+
+    fn parse_iterations(&self) -> Result<usize, String> {
+        let iterations = self
+            .iterations_input
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid iteration count '{}'", self.iterations_input))?;
+        if iterations == 0 || iterations > MAX_ITERATIONS {
+            return Err(format!("Iterations must be between 1 and {MAX_ITERATIONS}"));
+        }
+        Ok(iterations)
+    }
+
+    fn record(&mut self, variant: BenchmarkVariant, sample: BenchmarkSample) {
+        let (status, duration, error) = match &sample {
+            BenchmarkSample::Response { status, duration } => {
+                (Some(*status), Some(*duration), None)
+            }
+            BenchmarkSample::Failed(err) => (None, None, Some(err.clone())),
+        };
+        self.timeline.push(TimingRecord {
+            timestamp: SystemTime::now(),
+            request: format!("{variant}: {}", self.target(variant).draft.step_label()),
+            status,
+            duration,
+            error,
+        });
+        match variant {
+            BenchmarkVariant::A => self.samples_a.push(sample),
+            BenchmarkVariant::B => self.samples_b.push(sample),
+        }
+    }
+}
+
+/// Latency statistics for the successful samples of one benchmark variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub responses: usize,
+    pub failures: usize,
+    pub non_success: usize,
+    pub latency: Option<LatencyStats>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub min: Duration,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl LatencySummary {
+    fn from_samples(samples: &[BenchmarkSample]) -> Self {
+        let mut durations = Vec::with_capacity(samples.len());
+        let mut failures = 0;
+        let mut non_success = 0;
+        for sample in samples {
+            match sample {
+                BenchmarkSample::Response { status, duration } => {
+                    if !(200..300).contains(status) {
+                        non_success += 1;
+                    }
+                    durations.push(*duration);
+                }
+                BenchmarkSample::Failed(_) => failures += 1,
+            }
+        }
+        durations.sort_unstable();
+
+        let latency = match (durations.first(), durations.last()) {
+            (Some(min), Some(max)) => {
+                let count = u32::try_from(durations.len()).unwrap_or(u32::MAX);
+                Some(LatencyStats {
+                    min: *min,
+                    mean: durations.iter().sum::<Duration>() / count,
+                    p50: percentile(&durations, 50),
+                    p90: percentile(&durations, 90),
+                    p99: percentile(&durations, 99),
+                    max: *max,
+                })
+            }
+            _ => None,
+        };
+
+        Self {
+            responses: durations.len(),
+            failures,
+            non_success,
+            latency,
+        }
+    }
+}
+
+/// Nearest-rank percentile over an ascending slice.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+/// Shared-bucket latency histogram so both variants can be compared row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    pub buckets: Vec<HistogramBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistogramBucket {
+    pub upper: Duration,
+    pub count_a: usize,
+    pub count_b: usize,
+}
+
+impl Histogram {
+    fn build(samples_a: &[BenchmarkSample], samples_b: &[BenchmarkSample]) -> Option<Self> {
+        let durations = |samples: &[BenchmarkSample]| {
+            samples
+                .iter()
+                .filter_map(|sample| match sample {
+                    BenchmarkSample::Response { duration, .. } => Some(duration.as_nanos()),
+                    BenchmarkSample::Failed(_) => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let a = durations(samples_a);
+        let b = durations(samples_b);
+        let min = a.iter().chain(&b).min().copied()?;
+        let max = a.iter().chain(&b).max().copied()?;
+        let span = max - min + 1;
+        let bucket_count = u128::try_from(HISTOGRAM_BUCKETS).unwrap_or(1);
+
+        let mut buckets = (1..=bucket_count)
+            .map(|bucket| HistogramBucket {
+                upper: duration_from_nanos(min + span * bucket / bucket_count),
+                count_a: 0,
+                count_b: 0,
+            })
+            .collect::<Vec<_>>();
+        let bucket_index = |nanos: u128| {
+            usize::try_from((nanos - min) * bucket_count / span)
+                .unwrap_or(HISTOGRAM_BUCKETS - 1)
+                .min(HISTOGRAM_BUCKETS - 1)
+        };
+        for nanos in a {
+            buckets[bucket_index(nanos)].count_a += 1;
+        }
+        for nanos in b {
+            buckets[bucket_index(nanos)].count_b += 1;
+        }
+
+        Some(Self { buckets })
+    }
+
+    pub fn max_count(&self) -> usize {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.count_a.max(bucket.count_b))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+/// The two requests selected in edit mode, in file order, for `action`.
+pub(super) fn selected_request_pair(
+    edit_state: &EditState,
+    action: &str,
+) -> Result<(RequestId, RequestId), String> {
+    let EditState::On { selection } = edit_state else {
+        return Err(format!(
+            "Enter edit mode and select two requests for {action}"
+        ));
+    };
+    let mut ids = selection
+        .iter()
+        .filter_map(|target| match target {
+            EditTarget::Request(id) => Some(id.clone()),
+            EditTarget::Collection(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if ids.len() != 2 {
+        return Err(format!(
+            "{action} needs exactly two selected requests ({} selected)",
+            ids.len()
+        ));
+    }
+    ids.sort_by(|left, right| {
+        let RequestId::HttpFile {
+            path: left_path,
+            index: left_index,
+        } = left;
+        let RequestId::HttpFile {
+            path: right_path,
+            index: right_index,
+        } = right;
+        left_path.cmp(right_path).then(left_index.cmp(right_index))
+    });
+    let b = ids.pop().expect("two ids");
+    let a = ids.pop().expect("two ids");
+    Ok((a, b))
+}
+
+impl Zagel {
+    pub(super) fn handle_benchmark_open(&mut self) -> Task<Message> {
+        let (a, b) = match selected_request_pair(&self.edit_state, "Benchmark A/B") {
+            Ok(pair) => pair,
+            Err(err) => {
+                self.update_status_with_missing(&err);
+                return Task::none();
+            }
+        };
+        let (Some(draft_a), Some(draft_b)) = (self.request_draft(&a), self.request_draft(&b))
+        else {
+            self.update_status_with_missing("Selected requests are no longer loaded");
+            return Task::none();
+        };
+
+        self.benchmark = Some(BenchmarkSession::new(
+            BenchmarkTarget {
+                id: a,
+                draft: draft_a,
+            },
+            BenchmarkTarget {
+                id: b,
+                draft: draft_b,
+            },
+        ));
+        Task::none()
+    }
+
+    pub(super) fn handle_benchmark_start(&mut self) -> Task<Message> {
+        let Some(session) = self.benchmark.as_mut() else {
+            return Task::none();
+        };
+        if session.is_running() {
+            return Task::none();
+        }
+        let iterations = match session.parse_iterations() {
+            Ok(iterations) => iterations,
+            Err(err) => {
+                self.update_status_with_missing(&err);
+                return Task::none();
+            }
+        };
+
+        session.last_run_id += 1;
+        session.samples_a.clear();
+        session.samples_b.clear();
+        session.timeline.clear();
+        session.phase = BenchmarkPhase::Running {
+            run_id: session.last_run_id,
+            step: 0,
+            total: iterations * 2,
+        };
+        self.update_status_with_missing("Benchmark running...");
+        self.benchmark_send_task()
+    }
+
+    pub(super) fn handle_benchmark_stop(&mut self) -> Task<Message> {
+        if let Some(session) = self.benchmark.as_mut()
+            && session.is_running()
+        {
+            session.phase = BenchmarkPhase::Stopped;
+            self.update_status_with_missing("Benchmark stopped");
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_benchmark_sample(
+        &mut self,
+        run_id: u64,
+        result: Result<SendOutcome, String>,
+    ) -> Task<Message> {
+        let Some(session) = self.benchmark.as_mut() else {
+            return Task::none();
+        };
+        let BenchmarkPhase::Running {
+            run_id: active_run,
+            step,
+            total,
+        } = session.phase
+        else {
+            return Task::none();
+        };
+        if active_run != run_id {
+            return Task::none();
+        }
+
+        let sample = match result {
+            Ok(outcome) => {
+                if outcome.oauth2_cache.is_some() {
+                    self.oauth2_token_cache.clone_from(&outcome.oauth2_cache);
+                }
+                match (outcome.response.status, outcome.response.duration) {
+                    (Some(status), Some(duration)) => {
+                        BenchmarkSample::Response { status, duration }
+                    }
+                    _ => BenchmarkSample::Failed(
+                        outcome
+                            .response
+                            .error
+                            .unwrap_or_else(|| "No response".to_string()),
+                    ),
+                }
+            }
+            Err(err) => BenchmarkSample::Failed(err),
+        };
+        session.record(BenchmarkVariant::for_step(step), sample);
+
+        let next = step + 1;
+        if next >= total {
+            session.phase = BenchmarkPhase::Finished;
+            self.update_status_with_missing("Benchmark finished");
+            return Task::none();
+        }
+        session.phase = BenchmarkPhase::Running {
+            run_id,
+            step: next,
+            total,
+        };
+        self.benchmark_send_task()
+    }
+
+    pub(super) fn handle_benchmark_export(&mut self) -> Task<Message> {
+        let Some(session) = self.benchmark.as_ref() else {
+            return Task::none();
+        };
+        let input = session.export_path_input.trim();
+        if input.is_empty() {
+            self.update_status_with_missing("Enter a file path for the CSV export");
+            return Task::none();
+        }
+        let mut path = PathBuf::from(input);
+        if path.is_relative()
+            && let Some(root) = self.default_project_root()
+        {
+            path = root.as_path().join(path);
+        }
+        let count = session.timeline.len();
+        let csv = to_csv(&session.timeline);
+        self.update_status_with_missing("Exporting timings...");
+        Task::perform(
+            async move {
+                fs::write(&path, csv)
+                    .map(|()| (path.clone(), count))
+                    .map_err(|err| format!("Failed to export timings to {}: {err}", path.display()))
+            },
+            Message::BenchmarkExported,
+        )
+    }
+
+    pub(super) fn handle_benchmark_exported(
+        &mut self,
+        result: Result<(PathBuf, usize), String>,
+    ) -> Task<Message> {
+        match result {
+            Ok((path, count)) => self.update_status_with_missing(&format!(
+                "Exported {count} timings to {}",
+                path.display()
+            )),
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+
+    fn benchmark_send_task(&mut self) -> Task<Message> {
+        let Some(session) = self.benchmark.as_ref() else {
+            return Task::none();
+        };
+        let BenchmarkPhase::Running { run_id, step, .. } = session.phase else {
+            return Task::none();
+        };
+        let target = session.target(BenchmarkVariant::for_step(step));
+        let draft = target.draft.clone();
+        let env = self.request_environment(Some(&target.id));
+        let auth = self.effective_auth(Some(&target.id), &draft.headers);
+        let client = match self.http_client(&draft) {
+            Ok(client) => client,
+            Err(err) => {
+                return Task::done(Message::BenchmarkSampleReady {
+                    run_id,
+                    result: Err(err),
+                });
+            }
+        };
+        Task::perform(
+            send_request(
+                client,
+                draft,
+                env,
+                auth,
+                self.oauth2_token_cache.clone(),
+                None,
+            ),
+            move |result| Message::BenchmarkSampleReady { run_id, result },
+        )
+    }
+
+    pub(super) fn request_draft(&self, id: &RequestId) -> Option<RequestDraft> {
+        let RequestId::HttpFile { path, index } = id;
+        self.workspace
+            .http_files()
+            .get(path)
+            .and_then(|file| file.requests.get(*index))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{BenchmarkSample, Histogram, LatencySummary, percentile};
+
+    fn ok(ms: u64) -> BenchmarkSample {
+        BenchmarkSample::Response {
+            status: 200,
+            duration: Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn summary_ignores_transport_failures_in_latency() {
+        let samples = vec![
+            ok(10),
+            ok(30),
+            BenchmarkSample::Failed("timeout".to_string()),
+            ok(20),
+        ];
+        let summary = LatencySummary::from_samples(&samples);
+        let latency = summary.latency.expect("latency stats");
+
+        assert_eq!(summary.responses, 3);
+        assert_eq!(summary.failures, 1);
+        assert_eq!(latency.min, Duration::from_millis(10));
+        assert_eq!(latency.p50, Duration::from_millis(20));
+        assert_eq!(latency.mean, Duration::from_millis(20));
+        assert_eq!(latency.max, Duration::from_millis(30));
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let sorted = (1..=10).map(Duration::from_millis).collect::<Vec<_>>();
+        assert_eq!(percentile(&sorted, 90), Duration::from_millis(9));
+        assert_eq!(percentile(&sorted, 99), Duration::from_millis(10));
+        assert_eq!(percentile(&sorted, 1), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn histogram_shares_buckets_between_variants() {
+        let histogram = Histogram::build(&[ok(10), ok(10)], &[ok(90)]).expect("histogram");
+        let first = histogram.buckets.first().expect("first bucket");
+        let last = histogram.buckets.last().expect("last bucket");
+
+        assert_eq!((first.count_a, first.count_b), (2, 0));
+        assert_eq!((last.count_a, last.count_b), (0, 1));
+        assert_eq!(histogram.max_count(), 2);
+    }
+}
@@ -0,0 +1,95 @@
+use iced::Task;
+
+use super::headers::has_header;
+use super::{Message, Zagel};
+
+/// Likely media type of a body, judged from its first characters.
+fn sniff_content_type(body: &str) -> &'static str {
+    let trimmed = body.trim();
+    if trimmed.starts_with(['{', '[']) && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return "application/json";
+    }
+    if trimmed.starts_with('<') {
+        let head = trimmed
+            .get(..trimmed.len().min(256))
+            .unwrap_or(trimmed)
+            .to_ascii_lowercase();
+        return if head.starts_with("<!doctype html") || head.contains("<html") {
+            "text/html"
+        } else {
+            "application/xml"
+        };
+    }
+    let is_form = !trimmed.contains(char::is_whitespace)
+        && trimmed.split('&').all(|pair| {
+            pair.split_once('=')
+                .is_some_and(|(name, _)| !name.is_empty())
+        });
+    if is_form {
+        "application/x-www-form-urlencoded"
+    } else {
+        "text/plain"
+    }
+}
+
+/// Content type to offer when a body is sent without a `Content-Type`
+/// header, which many servers reject or parse as the wrong format.
+pub(super) fn content_type_hint(headers: &str, body: &str) -> Option<&'static str> {
+    (!body.trim().is_empty() && !has_header(headers, "content-type"))
+        .then(|| sniff_content_type(body))
+}
+
+/// An explicit `Content-Length` that disagrees with the body, which makes
+/// servers cut the body short or wait for bytes that never come.
+pub(super) fn content_length_mismatch(headers: &str, body: &str) -> Option<usize> {
+    headers.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        let declared = value.trim().parse::<usize>().ok()?;
+        (name.trim().eq_ignore_ascii_case("content-length") && declared != body.len())
+            .then_some(declared)
+    })
+}
+
+impl Zagel {
+    /// Sets `Content-Type`.
+    pub(super) fn handle_content_type_added(&mut self, content_type: &str) -> Task<Message> {
+        self.add_header_row("Content-Type", content_type);
+        self.update_status_with_missing(&format!("Added Content-Type: {content_type}"));
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{content_length_mismatch, content_type_hint};
+
+    #[test]
+    fn bodies_without_a_content_type_get_a_sniffed_hint() {
+        assert_eq!(
+            content_type_hint("Accept: */*", "{\"a\": 1}"),
+            Some("application/json")
+        );
+        assert_eq!(
+            content_type_hint("", "<?xml version=\"1.0\"?><a/>"),
+            Some("application/xml")
+        );
+        assert_eq!(
+            content_type_hint("", "<!DOCTYPE html><html></html>"),
+            Some("text/html")
+        );
+        assert_eq!(
+            content_type_hint("", "name=zagel&page=2"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(content_type_hint("", "{not json"), Some("text/plain"));
+        assert_eq!(content_type_hint("content-type: text/plain", "{}"), None);
+        assert_eq!(content_type_hint("", "  "), None);
+
+        assert_eq!(
+            content_length_mismatch("Content-Length: 10", "{}"),
+            Some(10)
+        );
+        assert_eq!(content_length_mismatch("Content-Length: 2", "{}"), None);
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use super::Zagel;
+use crate::jsonpath;
+use crate::model::{Capture, CaptureSource, RequestDraft, RequestId, ResponsePreview};
+
+/// Captures of sends still waiting for their response.
+#[derive(Debug, Default)]
+pub struct PendingCaptures {
+    by_request: HashMap<Option<RequestId>, Vec<Capture>>,
+}
+
+/// Reads the captured value; JSON strings are unquoted, other JSON values are
+/// kept as compact JSON.
+pub fn capture_value(source: &CaptureSource, response: &ResponsePreview) -> Result<String, String> {
+    match source {
+        CaptureSource::Status => response
+            .status
+            .map(|status| status.to_string())
+            .ok_or_else(|| "no status".to_string()),
+        CaptureSource::Header(name) => response
+            .headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| format!("no {name} header")),
+        CaptureSource::Body(path) => {
+            let body = String::from_utf8_lossy(response.body.as_deref().unwrap_or_default());
+            if path.is_empty() {
+                return Ok(body.into_owned());
+            }
+            let value = serde_json::from_str::<serde_json::Value>(&body)
+                .map_err(|_| "body is not JSON".to_string())?;
+            Ok(match jsonpath::apply(&value, path)? {
+                serde_json::Value::String(text) => text,
+                other => other.to_string(),
+            })
+        }
+    }
+}
+
+impl Zagel {
+    pub(super) fn arm_captures(&mut self, request_id: Option<RequestId>, draft: &RequestDraft) {
+        if draft.captures.is_empty() {
+            self.captures.by_request.remove(&request_id);
+        } else {
+            self.captures
+                .by_request
+                .insert(request_id, draft.captures.clone());
+        }
+    }
+
+    /// Stores captured values as session variables after a 2xx response and
+    /// returns a summary for the status line.
+    pub(super) fn apply_captures(
+        &mut self,
+        request_id: Option<&RequestId>,
+        response: &ResponsePreview,
+    ) -> Option<String> {
+        let captures = self.captures.by_request.remove(&request_id.cloned())?;
+        if response.error.is_some() || !response.status.is_some_and(|s| (200..300).contains(&s)) {
+            return None;
+        }
+        let mut stored = Vec::new();
+        let mut failed = Vec::new();
+        for capture in captures {
+            match capture_value(&capture.source, response) {
+                Ok(value) => {
+                    self.session_variables.insert(capture.name.clone(), value);
+                    stored.push(capture.name);
+                }
+                Err(err) => failed.push(format!("{}: {err}", capture.name)),
+            }
+        }
+        let mut summary = Vec::new();
+        if !stored.is_empty() {
+            summary.push(format!("captured {}", stored.join(", ")));
+        }
+        if !failed.is_empty() {
+            summary.push(format!("capture failed for {}", failed.join("; ")));
+        }
+        Some(summary.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capture_value;
+    use crate::model::{Capture, CaptureSource, ResponsePreview};
+
+    fn response(body: &str) -> ResponsePreview {
+        ResponsePreview {
+            status: Some(201),
+            duration: None,
+            body: Some(body.as_bytes().to_vec()),
+            headers: vec![("location".to_string(), "/users/7".to_string())],
+            error: None,
+            version: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn captures_read_body_paths_headers_and_status() {
+        let response = response(r#"{"access_token":"abc","user":{"id":7}}"#);
+        let value = |directive: &str| {
+            let capture = Capture::parse(directive).unwrap();
+            capture_value(&capture.source, &response)
+        };
+        assert_eq!(value("token = body.$.access_token").unwrap(), "abc");
+        assert_eq!(value("user = body.$.user").unwrap(), r#"{"id":7}"#);
+        assert_eq!(value("next = header.Location").unwrap(), "/users/7");
+        assert_eq!(value("code = status").unwrap(), "201");
+        assert!(value("missing = body.$.nope").is_err());
+        assert!(value("raw = body").unwrap().starts_with('{'));
+    }
+
+    #[test]
+    fn capture_directives_need_a_name_and_a_known_source() {
+        let capture = Capture::parse(" token =  body.$.a ").unwrap();
+        assert_eq!(capture.source, CaptureSource::Body("$.a".to_string()));
+        assert_eq!(capture.to_string(), "token = body.$.a");
+        assert!(Capture::parse("token body.$.a").is_none());
+        assert!(Capture::parse("my token = status").is_none());
+        assert!(Capture::parse("token = cookie.session").is_none());
+    }
+}
@@ -0,0 +1,251 @@
+use iced::Task;
+use iced::widget::{pane_grid, text_editor};
+
+use super::benchmark::selected_request_pair;
+use super::pin::body_text;
+use super::view::ResponseData;
+use super::{Message, Zagel};
+use crate::model::{RequestDraft, RequestId, ResponsePreview};
+use crate::net::{SendOutcome, send_request};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareSide {
+    A,
+    B,
+}
+
+impl CompareSide {
+    pub const ALL: [Self; 2] = [Self::A, Self::B];
+}
+
+impl std::fmt::Display for CompareSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::A => f.write_str("A"),
+            Self::B => f.write_str("B"),
+        }
+    }
+}
+
+/// One request of the comparison and its latest response.
+#[derive(Debug)]
+pub struct CompareSlot {
+    pub id: RequestId,
+    pub draft: RequestDraft,
+    pub response: Option<ResponseData>,
+    /// Read-only view of the response body.
+    pub viewer: text_editor::Content,
+    pub sending: bool,
+}
+
+impl CompareSlot {
+    fn new(id: RequestId, draft: RequestDraft) -> Self {
+        Self {
+            id,
+            draft,
+            response: None,
+            viewer: text_editor::Content::new(),
+            sending: false,
+        }
+    }
+}
+
+/// Two requests side by side in split panes, replacing the workspace until
+/// closed.
+pub struct Comparison {
+    pub panes: pane_grid::State<CompareSide>,
+    pub a: CompareSlot,
+    pub b: CompareSlot,
+    send_id: u64,
+}
+
+impl Comparison {
+    fn new(a: CompareSlot, b: CompareSlot) -> Self {
+        let (mut panes, left) = pane_grid::State::new(CompareSide::A);
+        panes.split(pane_grid::Axis::Vertical, left, CompareSide::B);
+        Self {
+            panes,
+            a,
+            b,
+            send_id: 0,
+        }
+    }
+
+    pub const fn slot(&self, side: CompareSide) -> &CompareSlot {
+        match side {
+            CompareSide::A => &self.a,
+            CompareSide::B => &self.b,
+        }
+    }
+
+    const fn slot_mut(&mut self, side: CompareSide) -> &mut CompareSlot {
+        match side {
+            CompareSide::A => &mut self.a,
+            CompareSide::B => &mut self.b,
+        }
+    }
+
+    /// Starts a new send of both sides; responses of earlier sends are
+    /// ignored from now on.
+    fn begin_send(&mut self) -> u64 {
+        self.send_id += 1;
+        for side in CompareSide::ALL {
+            self.slot_mut(side).sending = true;
+        }
+        self.send_id
+    }
+
+    /// Shows `preview` on `side` unless a newer send started since.
+    fn record(&mut self, send_id: u64, side: CompareSide, preview: ResponsePreview) -> bool {
+        if send_id != self.send_id {
+            return false;
+        }
+        let slot = self.slot_mut(side);
+        let response = ResponseData::from_preview(preview);
+        slot.viewer = text_editor::Content::with_text(body_text(&response));
+        slot.response = Some(response);
+        slot.sending = false;
+        true
+    }
+
+    pub const fn is_sending(&self) -> bool {
+        self.a.sending || self.b.sending
+    }
+}
+
+impl Zagel {
+    /// Opens the two requests selected in edit mode side by side and sends
+    /// both.
+    pub(super) fn handle_compare_open(&mut self) -> Task<Message> {
+        let (a, b) = match selected_request_pair(&self.edit_state, "Compare") {
+            Ok(pair) => pair,
+            Err(err) => {
+                self.update_status_with_missing(&err);
+                return Task::none();
+            }
+        };
+        let (Some(draft_a), Some(draft_b)) = (self.request_draft(&a), self.request_draft(&b))
+        else {
+            self.update_status_with_missing("Selected requests are no longer loaded");
+            return Task::none();
+        };
+        self.comparison = Some(Comparison::new(
+            CompareSlot::new(a, draft_a),
+            CompareSlot::new(b, draft_b),
+        ));
+        self.handle_compare_send()
+    }
+
+    pub(super) fn handle_compare_send(&mut self) -> Task<Message> {
+        let Some(comparison) = self.comparison.as_mut() else {
+            return Task::none();
+        };
+        let send_id = comparison.begin_send();
+        let targets = CompareSide::ALL.map(|side| {
+            let slot = comparison.slot(side);
+            (side, slot.id.clone(), slot.draft.clone())
+        });
+        Task::batch(
+            targets.map(|(side, id, draft)| self.compare_send_task(send_id, side, &id, draft)),
+        )
+    }
+
+    fn compare_send_task(
+        &mut self,
+        send_id: u64,
+        side: CompareSide,
+        id: &RequestId,
+        mut draft: RequestDraft,
+    ) -> Task<Message> {
+        draft.resolve_dynamic();
+        let env = self.request_environment(Some(id));
+        let auth = self.effective_auth(Some(id), &draft.headers);
+        let client = match self.http_client(&draft) {
+            Ok(client) => client,
+            Err(err) => {
+                return Task::done(Message::CompareResponseReady {
+                    send_id,
+                    side,
+                    result: Err(err),
+                });
+            }
+        };
+        Task::perform(
+            send_request(
+                client,
+                draft,
+                env,
+                auth,
+                self.oauth2_token_cache.clone(),
+                None,
+            ),
+            move |result| Message::CompareResponseReady {
+                send_id,
+                side,
+                result,
+            },
+        )
+    }
+
+    pub(super) fn handle_compare_response_ready(
+        &mut self,
+        send_id: u64,
+        side: CompareSide,
+        result: Result<SendOutcome, String>,
+    ) -> Task<Message> {
+        let Some(comparison) = self.comparison.as_mut() else {
+            return Task::none();
+        };
+        let preview = match result {
+            Ok(outcome) => {
+                if outcome.oauth2_cache.is_some() {
+                    self.oauth2_token_cache = outcome.oauth2_cache;
+                }
+                outcome.response
+            }
+            Err(err) => ResponsePreview::error(err),
+        };
+        if comparison.record(send_id, side, preview) && !comparison.is_sending() {
+            self.update_status_with_missing("Compared responses");
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{CompareSide, CompareSlot, Comparison};
+    use crate::model::{RequestDraft, RequestId, ResponsePreview};
+
+    #[test]
+    fn only_responses_of_the_latest_send_are_shown() {
+        let slot = |index| {
+            let id = RequestId::HttpFile {
+                path: PathBuf::from("api.http"),
+                index,
+            };
+            CompareSlot::new(id, RequestDraft::default())
+        };
+        let mut comparison = Comparison::new(slot(0), slot(1));
+        let stale = comparison.begin_send();
+        let current = comparison.begin_send();
+        let preview = || ResponsePreview {
+            status: Some(200),
+            body: Some(b"ok".to_vec()),
+            error: None,
+            ..ResponsePreview::error(String::new())
+        };
+
+        assert!(!comparison.record(stale, CompareSide::A, preview()));
+        assert!(comparison.is_sending());
+        assert!(comparison.record(current, CompareSide::A, preview()));
+        assert!(comparison.record(current, CompareSide::B, preview()));
+        assert!(!comparison.is_sending());
+        assert_eq!(
+            comparison.slot(CompareSide::B).viewer.text().trim_end(),
+            "ok"
+        );
+    }
+}
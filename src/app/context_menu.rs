@@ -0,0 +1,468 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use iced::{Task, clipboard};
+
+use crate::curl::{to_curl, to_curl_script};
+use uuid::Uuid;
+
+use crate::model::{RequestDraft, RequestId, RequestRef, apply_environment};
+use crate::parser::write_http_file;
+
+use super::favorites::move_favorites;
+use super::{EditState, EditTarget, Message, Zagel};
+
+/// Row a context menu was opened on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuTarget {
+    Request(RequestId),
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Send,
+    Duplicate,
+    Rename,
+    Delete,
+    CopyAsCurl,
+    AddId,
+    NewRequest,
+    Reveal,
+    Export,
+}
+
+impl MenuAction {
+    pub const REQUEST: [Self; 6] = [
+        Self::Send,
+        Self::Duplicate,
+        Self::Rename,
+        Self::Delete,
+        Self::CopyAsCurl,
+        Self::AddId,
+    ];
+    pub const FILE: [Self; 4] = [Self::NewRequest, Self::Reveal, Self::Rename, Self::Export];
+}
+
+impl std::fmt::Display for MenuAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Send => "Send",
+            Self::Duplicate => "Duplicate",
+            Self::Rename => "Rename",
+            Self::Delete => "Delete",
+            Self::CopyAsCurl => "Copy as curl",
+            Self::AddId => "Add stable id",
+            Self::NewRequest => "New request",
+            Self::Reveal => "Reveal",
+            Self::Export => "Export curl script",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ContextMenu {
+    pub target: MenuTarget,
+    /// New name being typed after choosing Rename.
+    pub rename: Option<String>,
+}
+
+/// Index of `id` after request `removed` of the same file is deleted, or
+/// `None` when `id` itself was removed.
+fn shift_after_removal(id: &RequestId, path: &Path, removed: usize) -> Option<RequestId> {
+    let RequestId::HttpFile {
+        path: id_path,
+        index,
+    } = id;
+    if id_path != path || *index < removed {
+        return Some(id.clone());
+    }
+    (*index > removed).then(|| RequestId::HttpFile {
+        path: id_path.clone(),
+        index: index - 1,
+    })
+}
+
+/// `orders` and `orders.http` both become `<dir>/orders.http`.
+fn renamed_path(path: &Path, name: &str) -> Option<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return None;
+    }
+    let file_name = if Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("http"))
+    {
+        name.to_string()
+    } else {
+        format!("{name}.http")
+    };
+    Some(path.with_file_name(file_name))
+}
+
+fn reveal(dir: &Path) -> std::io::Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(opener)
+        .arg(dir)
+        .spawn()
+        .map(drop)
+}
+
+impl Zagel {
+    pub(super) fn handle_context_menu_opened(&mut self, target: MenuTarget) -> Task<Message> {
+        let reopened = self
+            .context_menu
+            .as_ref()
+            .is_some_and(|menu| menu.target == target);
+        self.context_menu = (!reopened).then_some(ContextMenu {
+            target,
+            rename: None,
+        });
+        Task::none()
+    }
+
+    pub(super) fn handle_context_menu_rename_changed(&mut self, name: String) -> Task<Message> {
+        if let Some(menu) = self.context_menu.as_mut() {
+            menu.rename = Some(name);
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_context_menu_action(&mut self, action: MenuAction) -> Task<Message> {
+        let Some(menu) = self.context_menu.clone() else {
+            return Task::none();
+        };
+        if action == MenuAction::Rename {
+            let current = match &menu.target {
+                MenuTarget::Request(id) => self.request_draft(id).map(|draft| draft.title),
+                MenuTarget::File(path) => path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string()),
+            };
+            return self.handle_context_menu_rename_changed(current.unwrap_or_default());
+        }
+        self.context_menu = None;
+        match (menu.target, action) {
+            (MenuTarget::Request(id), MenuAction::Send) => {
+                Task::done(Message::Select(id)).chain(Task::done(Message::Send))
+            }
+            (MenuTarget::Request(id), MenuAction::Duplicate) => self.duplicate_request(&id),
+            (MenuTarget::Request(id), MenuAction::Delete) => self.delete_request(&id),
+            (MenuTarget::Request(id), MenuAction::CopyAsCurl) => self.copy_request_as_curl(&id),
+            (MenuTarget::Request(id), MenuAction::AddId) => self.add_request_id(&id),
+            (MenuTarget::File(path), MenuAction::NewRequest) => {
+                Task::done(Message::Select(RequestId::HttpFile { path, index: 0 }))
+                    .chain(Task::done(Message::AddRequest))
+            }
+            (MenuTarget::File(path), MenuAction::Reveal) => {
+                let dir = path.parent().unwrap_or(&path);
+                if let Err(err) = reveal(dir) {
+                    self.update_status_with_missing(&format!(
+                        "Failed to open {}: {err}",
+                        dir.display()
+                    ));
+                }
+                Task::none()
+            }
+            (MenuTarget::File(path), MenuAction::Export) => self.export_curl_script(&path),
+            _ => Task::none(),
+        }
+    }
+
+    pub(super) fn handle_context_menu_rename_submitted(&mut self) -> Task<Message> {
+        let Some(ContextMenu {
+            target,
+            rename: Some(name),
+        }) = self.context_menu.take()
+        else {
+            return Task::none();
+        };
+        match target {
+            MenuTarget::Request(id) => self.rename_request(&id, &name),
+            MenuTarget::File(path) => self.rename_file(&path, &name),
+        }
+        Task::none()
+    }
+
+    /// Applies `change` to the requests of `path` and writes the file,
+    /// keeping the loaded requests unchanged when the write fails.
+    pub(super) fn rewrite_requests(
+        &mut self,
+        path: &Path,
+        change: impl FnOnce(&mut Vec<RequestDraft>),
+    ) -> Result<(), String> {
+        let Some(mut workspace) = self.workspace.configured_state() else {
+            return Err("No workspace loaded".to_string());
+        };
+        let Some(file) = workspace.http_files_mut().get_mut(path) else {
+            return Err(format!("{} is no longer loaded", path.display()));
+        };
+        let mut requests = file.requests.clone();
+        change(&mut requests);
+        write_http_file(path, &requests)
+            .map_err(|err| format!("Failed to update {}: {err}", path.display()))?;
+        file.requests = requests;
+        Ok(())
+    }
+
+    fn duplicate_request(&mut self, id: &RequestId) -> Task<Message> {
+        let RequestId::HttpFile { path, index } = id;
+        let Some(mut copy) = self.request_draft(id) else {
+            return Task::none();
+        };
+        copy.title = format!("{} copy", copy.title);
+        copy.id = None;
+        if let Err(err) = self.rewrite_requests(path, |requests| requests.insert(index + 1, copy)) {
+            self.update_status_with_missing(&err);
+            return Task::none();
+        }
+        self.update_status_with_missing("Duplicated request");
+        Task::done(Message::Select(RequestId::HttpFile {
+            path: path.clone(),
+            index: index + 1,
+        }))
+    }
+
+    /// Writes a `# @id` into the request so history, favorites and response
+    /// filters follow it when its file is reordered, and moves the ones kept
+    /// under its old reference over.
+    fn add_request_id(&mut self, id: &RequestId) -> Task<Message> {
+        let RequestId::HttpFile { path, index } = id;
+        let Some(mut draft) = self.request_draft(id) else {
+            return Task::none();
+        };
+        if draft.id.is_some() {
+            self.update_status_with_missing("The request already has an id");
+            return Task::none();
+        }
+        let old = RequestRef::new(path, *index, &draft).to_string();
+        draft.id = Some(Uuid::new_v4().to_string());
+        let new = RequestRef::new(path, *index, &draft).to_string();
+        let new_id = draft.id;
+        if let Err(err) = self.rewrite_requests(path, |requests| {
+            if let Some(request) = requests.get_mut(*index) {
+                request.id.clone_from(&new_id);
+            }
+        }) {
+            self.update_status_with_missing(&err);
+            return Task::none();
+        }
+        if self.workspace.selection() == Some(id) {
+            self.draft.id = new_id;
+        }
+        for raw in self.state.favorites.values_mut().flatten() {
+            if *raw == old {
+                raw.clone_from(&new);
+            }
+        }
+        if let Some(filter) = self.state.response_filters.remove(&old) {
+            self.state.response_filters.insert(new, filter);
+        }
+        self.persist_state();
+        self.update_status_with_missing("Added a stable id to the request");
+        Task::none()
+    }
+
+    pub(super) fn delete_request(&mut self, id: &RequestId) -> Task<Message> {
+        let RequestId::HttpFile { path, index } = id;
+        if let Err(err) = self.rewrite_requests(path, |requests| {
+            if *index < requests.len() {
+                requests.remove(*index);
+            }
+        }) {
+            self.update_status_with_missing(&err);
+            return Task::none();
+        }
+        if let Some(mut workspace) = self.workspace.configured_state() {
+            let selection = workspace
+                .selection_cloned()
+                .and_then(|selected| shift_after_removal(&selected, path, *index));
+            workspace.set_selection(selection);
+        }
+        self.responses = std::mem::take(&mut self.responses)
+            .into_iter()
+            .filter_map(|(id, response)| Some((shift_after_removal(&id, path, *index)?, response)))
+            .collect();
+        if let EditState::On { selection } = &mut self.edit_state {
+            *selection = std::mem::take(selection)
+                .into_iter()
+                .filter_map(|target| match target {
+                    EditTarget::Request(id) => {
+                        shift_after_removal(&id, path, *index).map(EditTarget::Request)
+                    }
+                    other @ EditTarget::Collection(_) => Some(other),
+                })
+                .collect();
+        }
+        self.update_status_with_missing("Deleted request");
+        Task::none()
+    }
+
+    fn rename_request(&mut self, id: &RequestId, title: &str) {
+        let RequestId::HttpFile { path, index } = id;
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return;
+        }
+        let renamed = title.clone();
+        if let Err(err) = self.rewrite_requests(path, |requests| {
+            if let Some(request) = requests.get_mut(*index) {
+                request.title = renamed;
+            }
+        }) {
+            self.update_status_with_missing(&err);
+            return;
+        }
+        if self.workspace.selection() == Some(id) {
+            self.draft.title = title;
+        }
+        self.update_status_with_missing("Renamed request");
+    }
+
+    fn rename_file(&mut self, path: &Path, name: &str) {
+        let Some(target) = renamed_path(path, name) else {
+            self.update_status_with_missing("Enter a file name without folders");
+            return;
+        };
+        if target == path {
+            return;
+        }
+        if target.exists() {
+            self.update_status_with_missing(&format!("{} already exists", target.display()));
+            return;
+        }
+        if let Err(err) = fs::rename(path, &target) {
+            self.update_status_with_missing(&format!("Failed to rename {}: {err}", path.display()));
+            return;
+        }
+        let remap = |id: RequestId| {
+            let RequestId::HttpFile {
+                path: id_path,
+                index,
+            } = id;
+            RequestId::HttpFile {
+                path: if id_path == path {
+                    target.clone()
+                } else {
+                    id_path
+                },
+                index,
+            }
+        };
+        if let Some(mut workspace) = self.workspace.configured_state() {
+            if let Some(mut file) = workspace.http_files_mut().remove(path) {
+                file.path.clone_from(&target);
+                workspace.http_files_mut().insert(target.clone(), file);
+            }
+            for entry in workspace.http_file_order_mut() {
+                if entry == path {
+                    entry.clone_from(&target);
+                }
+            }
+            let selection = workspace.selection_cloned().map(remap);
+            workspace.set_selection(selection);
+        }
+        self.responses = std::mem::take(&mut self.responses)
+            .into_iter()
+            .map(|(id, response)| (remap(id), response))
+            .collect();
+        if let EditState::On { selection } = &mut self.edit_state {
+            *selection = std::mem::take(selection)
+                .into_iter()
+                .map(|edit_target| match edit_target {
+                    EditTarget::Collection(p) if p == path => {
+                        EditTarget::Collection(target.clone())
+                    }
+                    EditTarget::Request(id) => EditTarget::Request(remap(id)),
+                    other @ EditTarget::Collection(_) => other,
+                })
+                .collect();
+        }
+        if let Some(RequestId::HttpFile { path, .. }) = self.workspace.selection() {
+            self.save_path = path.display().to_string();
+        }
+        move_favorites(&mut self.state.favorites, path, &target);
+        self.persist_state();
+        self.update_status_with_missing(&format!("Renamed to {}", target.display()));
+    }
+
+    fn copy_request_as_curl(&mut self, id: &RequestId) -> Task<Message> {
+        let Some(mut draft) = self.request_draft(id) else {
+            return Task::none();
+        };
+        if let Some(env) = self.request_environment(Some(id)) {
+            for field in [&mut draft.url, &mut draft.headers, &mut draft.body] {
+                *field = apply_environment(field, &env.vars);
+            }
+        }
+        self.update_status_with_missing("Copied request as curl");
+        clipboard::write(to_curl(&draft)).map(|()| Message::CopyComplete)
+    }
+
+    fn export_curl_script(&mut self, path: &Path) -> Task<Message> {
+        let Some(file) = self.workspace.http_files().get(path) else {
+            return Task::none();
+        };
+        let script_path = path.with_extension("sh");
+        let status = match fs::write(&script_path, to_curl_script(&file.requests)) {
+            Ok(()) => format!("Exported {}", script_path.display()),
+            Err(err) => format!("Failed to write {}: {err}", script_path.display()),
+        };
+        self.update_status_with_missing(&status);
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{renamed_path, shift_after_removal};
+    use crate::model::RequestId;
+
+    fn id(path: &str, index: usize) -> RequestId {
+        RequestId::HttpFile {
+            path: PathBuf::from(path),
+            index,
+        }
+    }
+
+    #[test]
+    fn removing_a_request_shifts_later_ones_in_the_same_file() {
+        let path = Path::new("api.http");
+        assert_eq!(
+            shift_after_removal(&id("api.http", 0), path, 1),
+            Some(id("api.http", 0))
+        );
+        assert_eq!(shift_after_removal(&id("api.http", 1), path, 1), None);
+        assert_eq!(
+            shift_after_removal(&id("api.http", 3), path, 1),
+            Some(id("api.http", 2))
+        );
+        assert_eq!(
+            shift_after_removal(&id("other.http", 3), path, 1),
+            Some(id("other.http", 3))
+        );
+    }
+
+    #[test]
+    fn file_renames_stay_in_the_folder_with_an_http_extension() {
+        let path = Path::new("/work/api.http");
+        assert_eq!(
+            renamed_path(path, "orders"),
+            Some(PathBuf::from("/work/orders.http"))
+        );
+        assert_eq!(
+            renamed_path(path, " orders.HTTP "),
+            Some(PathBuf::from("/work/orders.HTTP"))
+        );
+        assert_eq!(renamed_path(path, "../orders"), None);
+        assert_eq!(renamed_path(path, "  "), None);
+    }
+}
@@ -0,0 +1,496 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use iced::Task;
+use reqwest::Url;
+
+use super::headers::{has_header, push_header};
+use super::{Message, Zagel};
+use crate::model::{Environment, RequestDraft, RequestId, apply_environment};
+use crate::state::StoredCookie;
+
+const SHARED_JAR: &str = "shared";
+
+/// Which cookie jar sends read from and responses write to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JarChoice {
+    /// One jar per environment, so staging and prod sessions never mix.
+    #[default]
+    PerEnvironment,
+    Shared,
+    Disabled,
+}
+
+impl JarChoice {
+    pub const ALL: [Self; 3] = [Self::PerEnvironment, Self::Shared, Self::Disabled];
+}
+
+impl fmt::Display for JarChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::PerEnvironment => "Cookies: per environment",
+            Self::Shared => "Cookies: shared jar",
+            Self::Disabled => "Cookies: off",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cookie {
+    name: String,
+    value: String,
+    domain: String,
+    /// Set without a `Domain` attribute, so subdomains do not receive it.
+    host_only: bool,
+    path: String,
+    secure: bool,
+    expires: Option<SystemTime>,
+}
+
+impl Cookie {
+    fn to_stored(&self) -> StoredCookie {
+        StoredCookie {
+            name: self.name.clone(),
+            value: self.value.clone(),
+            domain: self.domain.clone(),
+            host_only: self.host_only,
+            path: self.path.clone(),
+            secure: self.secure,
+            expires: self.expires.map(|expires| {
+                expires
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |since| since.as_secs())
+            }),
+        }
+    }
+
+    fn from_stored(stored: &StoredCookie) -> Self {
+        Self {
+            name: stored.name.clone(),
+            value: stored.value.clone(),
+            domain: stored.domain.clone(),
+            host_only: stored.host_only,
+            path: stored.path.clone(),
+            secure: stored.secure,
+            expires: stored
+                .expires
+                .map(|seconds| SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)),
+        }
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, url: &Url, now: SystemTime) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let domain_matches = if self.host_only {
+            host == self.domain
+        } else {
+            host == self.domain || host.ends_with(&format!(".{}", self.domain))
+        };
+        domain_matches
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && !self.is_expired(now)
+    }
+}
+
+/// In-memory cookie jars for the session, keyed by environment or `shared`.
+#[derive(Debug, Clone, Default)]
+pub struct CookieJars {
+    pub choice: JarChoice,
+    jars: HashMap<String, Vec<Cookie>>,
+    /// Jar and resolved URL of each in-flight send, so its response lands in the
+    /// jar it was sent from even if the environment changes meanwhile.
+    pending: HashMap<Option<RequestId>, (String, Url)>,
+}
+
+impl CookieJars {
+    fn jar_key(&self, env: Option<&Environment>) -> Option<String> {
+        match self.choice {
+            JarChoice::PerEnvironment => Some(format!(
+                "env:{}",
+                env.map_or("default", |env| env.name.as_str())
+            )),
+            JarChoice::Shared => Some(SHARED_JAR.to_string()),
+            JarChoice::Disabled => None,
+        }
+    }
+
+    /// Adds a `Cookie` header from the active jar unless the request sets one,
+    /// and remembers where the response's `Set-Cookie` headers should go.
+    /// `# @no-cookie-jar` requests bypass the jar both ways.
+    pub fn apply(
+        &mut self,
+        draft: &mut RequestDraft,
+        env: Option<&Environment>,
+        id: Option<RequestId>,
+        now: SystemTime,
+    ) {
+        self.pending.remove(&id);
+        if draft.options.no_cookie_jar {
+            return;
+        }
+        let Some(key) = self.jar_key(env) else {
+            return;
+        };
+        let url = env.map_or_else(
+            || draft.url.clone(),
+            |env| apply_environment(&draft.url, &env.vars),
+        );
+        let Ok(url) = Url::parse(url.trim()) else {
+            return;
+        };
+        if !has_header(&draft.headers, "cookie") {
+            let pairs = self
+                .jars
+                .get(&key)
+                .into_iter()
+                .flatten()
+                .filter(|cookie| cookie.matches(&url, now))
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>();
+            if !pairs.is_empty() {
+                push_header(&mut draft.headers, "Cookie", &pairs.join("; "));
+            }
+        }
+        self.pending.insert(id, (key, url));
+    }
+
+    /// Stores the `Set-Cookie` headers of a finished send; pass no headers for a
+    /// failed one.
+    pub fn store(&mut self, id: Option<&RequestId>, headers: &[(String, String)], now: SystemTime) {
+        let Some((key, url)) = self.pending.remove(&id.cloned()) else {
+            return;
+        };
+        let jar = self.jars.entry(key).or_default();
+        for (_, value) in headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+        {
+            let Some(cookie) = parse_set_cookie(value, &url, now) else {
+                continue;
+            };
+            jar.retain(|existing| {
+                (&existing.name, &existing.domain, &existing.path)
+                    != (&cookie.name, &cookie.domain, &cookie.path)
+            });
+            if !cookie.is_expired(now) {
+                jar.push(cookie);
+            }
+        }
+        jar.retain(|cookie| !cookie.is_expired(now));
+    }
+
+    pub fn count(&self, env: Option<&Environment>) -> usize {
+        self.jar_key(env)
+            .and_then(|key| self.jars.get(&key))
+            .map_or(0, Vec::len)
+    }
+
+    pub fn clear(&mut self, env: Option<&Environment>) {
+        if let Some(key) = self.jar_key(env) {
+            self.jars.remove(&key);
+        }
+    }
+
+    /// Unexpired cookies of every jar, for saving into a session.
+    pub fn snapshot(&self, now: SystemTime) -> BTreeMap<String, Vec<StoredCookie>> {
+        self.jars
+            .iter()
+            .map(|(key, jar)| {
+                let cookies = jar
+                    .iter()
+                    .filter(|cookie| !cookie.is_expired(now))
+                    .map(Cookie::to_stored)
+                    .collect::<Vec<_>>();
+                (key.clone(), cookies)
+            })
+            .filter(|(_, cookies)| !cookies.is_empty())
+            .collect()
+    }
+
+    /// Replaces every jar with the saved ones; the jar choice is kept.
+    pub fn restore(&mut self, saved: &BTreeMap<String, Vec<StoredCookie>>, now: SystemTime) {
+        self.jars = saved
+            .iter()
+            .map(|(key, cookies)| {
+                let jar = cookies
+                    .iter()
+                    .map(Cookie::from_stored)
+                    .filter(|cookie| !cookie.is_expired(now))
+                    .collect();
+                (key.clone(), jar)
+            })
+            .collect();
+    }
+}
+
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// RFC 6265 default path: the request path up to, not including, its last `/`.
+fn default_path(url: &Url) -> String {
+    let path = url.path();
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(idx) => path[..idx].to_string(),
+    }
+}
+
+fn parse_set_cookie(header: &str, url: &Url, now: SystemTime) -> Option<Cookie> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut cookie = Cookie {
+        name: name.to_string(),
+        value: value.trim().to_string(),
+        domain: host.clone(),
+        host_only: true,
+        path: default_path(url),
+        secure: false,
+        expires: None,
+    };
+    let mut max_age = None;
+    for attribute in parts {
+        let (key, value) = attribute.split_once('=').map_or_else(
+            || (attribute.trim(), ""),
+            |(key, value)| (key.trim(), value.trim()),
+        );
+        match key.to_ascii_lowercase().as_str() {
+            "domain" => {
+                let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                // A server may only widen a cookie to a domain it belongs to.
+                if host != domain && !host.ends_with(&format!(".{domain}")) {
+                    return None;
+                }
+                cookie.domain = domain;
+                cookie.host_only = false;
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "secure" => cookie.secure = true,
+            "max-age" => max_age = value.parse::<i64>().ok(),
+            "expires" => {
+                if let Some(expires) = parse_http_date(value) {
+                    cookie.expires = Some(expires);
+                }
+            }
+            _ => {}
+        }
+    }
+    // Max-Age wins over Expires when both are present.
+    if let Some(seconds) = max_age {
+        cookie.expires = Some(
+            u64::try_from(seconds)
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .map_or(SystemTime::UNIX_EPOCH, |seconds| {
+                    now + Duration::from_secs(seconds)
+                }),
+        );
+    }
+    Some(cookie)
+}
+
+/// Parses the IMF-fixdate form, e.g. `Wed, 21 Oct 2015 07:28:00 GMT`; dashes
+/// between the date parts (`21-Oct-2015`) are accepted too.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let (_, rest) = value.split_once(',')?;
+    let normalized = rest.replace('-', " ");
+    let mut fields = normalized.split_whitespace();
+    let day = fields.next()?.parse::<u32>().ok()?;
+    let month = fields.next()?.to_ascii_lowercase();
+    let month = u32::try_from(MONTHS.iter().position(|name| *name == month)? + 1).ok()?;
+    let mut year = fields.next()?.parse::<i64>().ok()?;
+    if year < 100 {
+        year += if year < 70 { 2000 } else { 1900 };
+    }
+    let mut time = fields.next()?.split(':').map(str::parse::<u64>);
+    let (hours, minutes, seconds) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some(
+        SystemTime::UNIX_EPOCH
+            + Duration::from_secs(days * 86_400 + hours * 3_600 + minutes * 60 + seconds),
+    )
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+impl Zagel {
+    pub(super) fn handle_cookie_jar_selected(&mut self, choice: JarChoice) -> Task<Message> {
+        self.cookies.choice = choice;
+        Task::none()
+    }
+
+    pub(super) fn handle_clear_cookies(&mut self) -> Task<Message> {
+        let env = self.environments.get(self.active_environment);
+        self.cookies.clear(env);
+        self.update_status_with_missing("Cleared cookies");
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::{Duration, SystemTime};
+
+    use super::{CookieJars, JarChoice, parse_http_date};
+    use crate::model::{Environment, EnvironmentScope, RequestDraft, RequestId};
+
+    fn env(name: &str) -> Environment {
+        Environment {
+            name: name.to_string(),
+            vars: BTreeMap::from([("host".to_string(), format!("{name}.example.com"))]),
+            scope: EnvironmentScope::Global,
+        }
+    }
+
+    fn draft(url: &str) -> RequestDraft {
+        RequestDraft {
+            url: url.to_string(),
+            headers: String::new(),
+            ..Default::default()
+        }
+    }
+
+    fn send(
+        jars: &mut CookieJars,
+        env: &Environment,
+        url: &str,
+        set_cookie: &[&str],
+        now: SystemTime,
+    ) -> String {
+        let mut request = draft(url);
+        let id = Some(RequestId::HttpFile {
+            path: "api.http".into(),
+            index: 0,
+        });
+        jars.apply(&mut request, Some(env), id.clone(), now);
+        let headers = set_cookie
+            .iter()
+            .map(|value| ("set-cookie".to_string(), (*value).to_string()))
+            .collect::<Vec<_>>();
+        jars.store(id.as_ref(), &headers, now);
+        request.headers
+    }
+
+    #[test]
+    fn environments_keep_separate_jars() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let (staging, prod) = (env("staging"), env("prod"));
+        let mut jars = CookieJars::default();
+
+        send(
+            &mut jars,
+            &staging,
+            "https://{{host}}/login",
+            &["sid=s1; Path=/"],
+            now,
+        );
+        assert_eq!(
+            send(&mut jars, &staging, "https://{{host}}/me", &[], now),
+            "Cookie: sid=s1"
+        );
+        assert_eq!(send(&mut jars, &prod, "https://{{host}}/me", &[], now), "");
+        assert_eq!(jars.count(Some(&prod)), 0);
+
+        jars.choice = JarChoice::Shared;
+        send(&mut jars, &prod, "https://shared.test/", &["a=1"], now);
+        assert_eq!(
+            send(&mut jars, &staging, "https://shared.test/", &[], now),
+            "Cookie: a=1"
+        );
+        jars.choice = JarChoice::Disabled;
+        assert_eq!(
+            send(&mut jars, &staging, "https://shared.test/", &[], now),
+            ""
+        );
+    }
+
+    #[test]
+    fn domain_path_secure_and_expiry_rules() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let env = env("dev");
+        let mut jars = CookieJars::default();
+        send(
+            &mut jars,
+            &env,
+            "https://api.example.com/v1/login",
+            &[
+                "wide=1; Domain=.example.com; Path=/",
+                "narrow=2",
+                "secret=3; Secure; Path=/",
+                "gone=4; Max-Age=0",
+                "foreign=5; Domain=other.com",
+            ],
+            now,
+        );
+        assert_eq!(jars.count(Some(&env)), 3);
+        assert_eq!(
+            send(
+                &mut jars,
+                &env,
+                "https://api.example.com/v1/items",
+                &[],
+                now
+            ),
+            "Cookie: wide=1; narrow=2; secret=3"
+        );
+        assert_eq!(
+            send(&mut jars, &env, "http://www.example.com/v2", &[], now),
+            "Cookie: wide=1"
+        );
+        send(
+            &mut jars,
+            &env,
+            "https://api.example.com/",
+            &["wide=; Domain=example.com; Path=/; Expires=Thu, 01 Jan 1970 00:00:00 GMT"],
+            now,
+        );
+        assert_eq!(jars.count(Some(&env)), 2);
+    }
+
+    #[test]
+    fn parses_http_dates() {
+        let seconds = |value| {
+            parse_http_date(value)
+                .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map(|since| since.as_secs())
+        };
+        assert_eq!(
+            seconds("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(1_445_412_480)
+        );
+        assert_eq!(
+            seconds("Wednesday, 21-Oct-15 07:28:00 GMT"),
+            Some(1_445_412_480)
+        );
+        assert_eq!(seconds("not a date"), None);
+    }
+}
@@ -290,8 +290,12 @@ impl ConfiguredWorkspaceState<'_> {
         &mut self.workspace.http_files
     }
 
-    pub fn replace_http_files(&mut self, files: HashMap<PathBuf, HttpFile>) {
-        self.workspace.http_files = files;
+    /// Returns the files it replaced.
+    pub const fn replace_http_files(
+        &mut self,
+        files: HashMap<PathBuf, HttpFile>,
+    ) -> HashMap<PathBuf, HttpFile> {
+        std::mem::replace(&mut self.workspace.http_files, files)
     }
 
     pub const fn http_file_order(&self) -> &Vec<PathBuf> {
@@ -306,10 +310,6 @@ impl ConfiguredWorkspaceState<'_> {
         self.workspace.selection.clone()
     }
 
-    pub const fn selection_mut(&mut self) -> &mut Option<RequestId> {
-        &mut self.workspace.selection
-    }
-
     pub fn set_selection(&mut self, selection: Option<RequestId>) {
         self.workspace.selection = selection;
     }
@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use iced::Task;
+
+use crate::model::{Environment, HttpFile, Method, RequestId, apply_environment};
+
+use super::{Message, Zagel};
+
+/// Requests sharing a method and URL once variables are substituted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    pub method: Method,
+    pub url: String,
+    /// In sidebar order.
+    pub requests: Vec<RequestId>,
+}
+
+/// Groups with more than one request, ordered by their first request.
+pub fn find_duplicates(
+    files: &HashMap<PathBuf, HttpFile>,
+    order: &[PathBuf],
+    env: Option<&Environment>,
+) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+    let mut by_key = BTreeMap::new();
+    for path in order {
+        let Some(file) = files.get(path) else {
+            continue;
+        };
+        for (index, draft) in file.requests.iter().enumerate() {
+            let url = env.map_or_else(
+                || draft.url.trim().to_string(),
+                |env| apply_environment(draft.url.trim(), &env.vars),
+            );
+            let id = RequestId::HttpFile {
+                path: path.clone(),
+                index,
+            };
+            let slot = *by_key
+                .entry((draft.method.as_str(), url.clone()))
+                .or_insert_with(|| {
+                    groups.push(DuplicateGroup {
+                        method: draft.method.clone(),
+                        url,
+                        requests: Vec::new(),
+                    });
+                    groups.len() - 1
+                });
+            groups[slot].requests.push(id);
+        }
+    }
+    groups.retain(|group| group.requests.len() > 1);
+    groups
+}
+
+impl Zagel {
+    pub(super) fn handle_duplicates_toggled(&mut self) -> Task<Message> {
+        self.duplicates = if self.duplicates.is_some() {
+            None
+        } else {
+            Some(self.current_duplicates())
+        };
+        Task::none()
+    }
+
+    /// Keeps `keep` and deletes the other requests of its group.
+    pub(super) fn handle_duplicate_keep(&mut self, keep: &RequestId) -> Task<Message> {
+        let Some(group) = self
+            .current_duplicates()
+            .into_iter()
+            .find(|group| group.requests.contains(keep))
+        else {
+            return Task::none();
+        };
+        let mut removed = group
+            .requests
+            .into_iter()
+            .filter(|id| id != keep)
+            .collect::<Vec<_>>();
+        // Later indices first, so earlier ones stay valid within a file.
+        removed.sort_by(|a, b| {
+            let (
+                RequestId::HttpFile {
+                    path: a_path,
+                    index: a_index,
+                },
+                RequestId::HttpFile {
+                    path: b_path,
+                    index: b_index,
+                },
+            ) = (a, b);
+            a_path.cmp(b_path).then(b_index.cmp(a_index))
+        });
+        let count = removed.len();
+        for id in &removed {
+            let _ = self.delete_request(id);
+        }
+        self.update_status_with_missing(&format!(
+            "Merged {} duplicates into one request",
+            count + 1
+        ));
+        self.duplicates = Some(self.current_duplicates());
+        Task::none()
+    }
+
+    fn current_duplicates(&self) -> Vec<DuplicateGroup> {
+        find_duplicates(
+            self.workspace.http_files(),
+            self.workspace.http_file_order(),
+            self.environments.get(self.active_environment),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::path::PathBuf;
+
+    use super::find_duplicates;
+    use crate::model::{Environment, EnvironmentScope, HttpFile, Method, RequestDraft, RequestId};
+
+    fn file(path: &str, requests: &[(Method, &str)]) -> HttpFile {
+        HttpFile {
+            path: PathBuf::from(path),
+            variables: Vec::new(),
+            auth: None,
+            requests: requests
+                .iter()
+                .map(|(method, url)| RequestDraft {
+                    method: method.clone(),
+                    url: (*url).to_string(),
+                    ..RequestDraft::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn duplicates_match_on_method_and_substituted_url() {
+        let files = [
+            file(
+                "a.http",
+                &[
+                    (Method::Get, "{{base}}/users"),
+                    (Method::Post, "{{base}}/users"),
+                ],
+            ),
+            file(
+                "b.http",
+                &[
+                    (Method::Get, "https://api.test/users "),
+                    (Method::Get, "https://api.test/orders"),
+                ],
+            ),
+        ];
+        let order = files
+            .iter()
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+        let files = files
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect::<HashMap<_, _>>();
+        let env = Environment {
+            name: "dev".to_string(),
+            vars: BTreeMap::from([("base".to_string(), "https://api.test".to_string())]),
+            scope: EnvironmentScope::Default,
+        };
+
+        let groups = find_duplicates(&files, &order, Some(&env));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].url, "https://api.test/users");
+        assert_eq!(
+            groups[0].requests,
+            vec![
+                RequestId::HttpFile {
+                    path: PathBuf::from("a.http"),
+                    index: 0
+                },
+                RequestId::HttpFile {
+                    path: PathBuf::from("b.http"),
+                    index: 0
+                },
+            ]
+        );
+        assert!(find_duplicates(&files, &order, None).is_empty());
+    }
+}
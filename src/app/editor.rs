@@ -0,0 +1,342 @@
+use std::fs;
+use std::io::{Read as _, Write as _};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use iced::futures::{StreamExt, channel::mpsc, stream::BoxStream};
+use iced::{Subscription, Task};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{Message, Zagel};
+use crate::model::RequestId;
+use crate::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(150);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+const TOKEN_HEADER: &str = "x-zagel-token";
+
+/// Body of `POST /open`: selects request `index` of the `.http` file at
+/// `path` and sends it when `send` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OpenRequest {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub index: usize,
+    #[serde(default)]
+    pub send: bool,
+}
+
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    fn new(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "message": body }).to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Hash)]
+struct Endpoint {
+    port: u16,
+    token: String,
+}
+
+/// Gives the install an editor token once the endpoint is enabled.
+pub fn ensure_token(state: &mut AppState) {
+    if state.editor_port.is_some() && state.editor_token.is_none() {
+        state.editor_token = Some(Uuid::new_v4().simple().to_string());
+    }
+}
+
+/// Listens on `127.0.0.1:<port>` while `editor_port` is configured.
+pub fn subscription(state: &AppState) -> Subscription<Message> {
+    match (state.editor_port, &state.editor_token) {
+        (Some(port), Some(token)) => Subscription::run_with(
+            Endpoint {
+                port,
+                token: token.clone(),
+            },
+            listen,
+        ),
+        _ => Subscription::none(),
+    }
+}
+
+fn listen(endpoint: &Endpoint) -> BoxStream<'static, Message> {
+    let endpoint = endpoint.clone();
+    let (sender, receiver) = mpsc::channel(16);
+    thread::spawn(move || serve(&endpoint, sender));
+    receiver.boxed()
+}
+
+fn serve(endpoint: &Endpoint, mut sender: mpsc::Sender<Message>) {
+    let port = endpoint.port;
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    {
+        Ok(listener) => listener,
+        Err(err) => {
+            let _ = sender.try_send(Message::EditorEndpointFailed(format!(
+                "Editor endpoint unavailable on port {port}: {err}"
+            )));
+            return;
+        }
+    };
+    // The subscription drops its receiver when the port changes or is removed.
+    while !sender.is_closed() {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(err) = handle_connection(stream, &endpoint.token, &mut sender) {
+                    eprintln!("editor endpoint: {err}");
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(err) => {
+                eprintln!("editor endpoint: {err}");
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    token: &str,
+    sender: &mut mpsc::Sender<Message>,
+) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let raw = read_request(&mut stream)?;
+    let (reply, open) = route(&raw, token);
+    if let Some(open) = open {
+        let _ = sender.try_send(Message::EditorOpen(open));
+    }
+    let reason = match reply.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        415 => "Unsupported Media Type",
+        _ => "Method Not Allowed",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        reply.status,
+        reply.body.len(),
+        reply.body
+    )
+}
+
+/// Reads the head and, per `Content-Length`, the body of one request.
+fn read_request(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut raw = Vec::new();
+    let mut chunk = [0_u8; 4096];
+    loop {
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(raw);
+        }
+        raw.extend_from_slice(&chunk[..read]);
+        if let Some(head_end) = find_head_end(&raw) {
+            let head = String::from_utf8_lossy(&raw[..head_end]);
+            let length = header(&head, "content-length")
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(0);
+            if raw.len() >= head_end + 4 + length {
+                return Ok(raw);
+            }
+        }
+        if raw.len() > MAX_REQUEST_BYTES {
+            return Ok(raw);
+        }
+    }
+}
+
+fn find_head_end(raw: &[u8]) -> Option<usize> {
+    raw.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+/// Trimmed value of the first header called `name`, ignoring case.
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(header, _)| header.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Why `POST /open` is refused, if it is. Browsers add `Origin` to
+/// cross-site requests and cannot send a JSON content type or a custom
+/// header without a CORS preflight, which the endpoint never answers.
+fn open_rejection(head: &str, token: &str) -> Option<Reply> {
+    if header(head, TOKEN_HEADER) != Some(token) {
+        return Some(Reply::new(401, "missing or wrong X-Zagel-Token"));
+    }
+    let json = header(head, "content-type").is_some_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+    });
+    (!json).then(|| Reply::new(415, "Content-Type must be application/json"))
+}
+
+fn route(raw: &[u8], token: &str) -> (Reply, Option<OpenRequest>) {
+    let Some(head_end) = find_head_end(raw) else {
+        return (Reply::new(400, "incomplete request"), None);
+    };
+    let head = String::from_utf8_lossy(&raw[..head_end]);
+    if header(&head, "origin").is_some() {
+        return (Reply::new(403, "requests from web pages are refused"), None);
+    }
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or_default();
+    match (method, target) {
+        ("GET", "/health") => (Reply::new(200, "zagel"), None),
+        ("POST", "/open") => {
+            if let Some(rejection) = open_rejection(&head, token) {
+                return (rejection, None);
+            }
+            match serde_json::from_slice::<OpenRequest>(&raw[head_end + 4..]) {
+                Ok(open) => (Reply::new(202, "accepted"), Some(open)),
+                Err(err) => (Reply::new(400, &format!("invalid body: {err}")), None),
+            }
+        }
+        (_, "/health" | "/open") => (Reply::new(405, "method not allowed"), None),
+        _ => (Reply::new(404, "not found"), None),
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_editor_open(&mut self, open: &OpenRequest) -> Task<Message> {
+        let wanted = fs::canonicalize(&open.path).unwrap_or_else(|_| open.path.clone());
+        let file = self.workspace.http_files().values().find(|file| {
+            file.path == wanted || fs::canonicalize(&file.path).is_ok_and(|path| path == wanted)
+        });
+        let Some(file) = file else {
+            self.update_status_with_missing(&format!(
+                "Editor asked for {}, which is not in a project",
+                open.path.display()
+            ));
+            return Task::none();
+        };
+        if open.index >= file.requests.len() {
+            self.update_status_with_missing(&format!(
+                "Editor asked for request #{} of {}, which has {}",
+                open.index,
+                open.path.display(),
+                file.requests.len()
+            ));
+            return Task::none();
+        }
+        let id = RequestId::HttpFile {
+            path: file.path.clone(),
+            index: open.index,
+        };
+        self.apply_selection(&id);
+        let pretty = self.ensure_pretty();
+        if open.send {
+            Task::batch([pretty, Task::done(Message::Send)])
+        } else {
+            pretty
+        }
+    }
+
+    pub(super) fn handle_editor_endpoint_failed(&mut self, err: &str) -> Task<Message> {
+        eprintln!("editor endpoint: {err}");
+        self.update_status_with_missing(err);
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{OpenRequest, route};
+
+    const TOKEN: &str = "s3cret";
+
+    fn open_request(headers: &str, body: &str) -> Vec<u8> {
+        format!(
+            "POST /open HTTP/1.1\r\nHost: localhost\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn open_requests_are_accepted_with_a_json_body() {
+        let raw = open_request(
+            "Content-Type: application/json\r\nX-Zagel-Token: s3cret\r\n",
+            r#"{"path":"/proj/api.http","index":2,"send":true}"#,
+        );
+        let (reply, open) = route(&raw, TOKEN);
+        assert_eq!(reply.status, 202);
+        assert_eq!(
+            open,
+            Some(OpenRequest {
+                path: PathBuf::from("/proj/api.http"),
+                index: 2,
+                send: true,
+            })
+        );
+
+        let (reply, open) = route(
+            b"POST /open HTTP/1.1\r\nContent-Type: application/json; charset=utf-8\r\nx-zagel-token: s3cret\r\n\r\n{\"index\":2}",
+            TOKEN,
+        );
+        assert_eq!(reply.status, 400);
+        assert!(open.is_none());
+    }
+
+    #[test]
+    fn open_requests_from_browsers_or_without_the_token_are_refused() {
+        let body = r#"{"path":"/proj/api.http","send":true}"#;
+        let refused = |headers: &str| {
+            let (reply, open) = route(&open_request(headers, body), TOKEN);
+            assert!(open.is_none(), "{headers}");
+            reply.status
+        };
+        assert_eq!(
+            refused(
+                "Origin: https://evil.test\r\nContent-Type: application/json\r\nX-Zagel-Token: s3cret\r\n"
+            ),
+            403
+        );
+        assert_eq!(refused("Content-Type: application/json\r\n"), 401);
+        assert_eq!(
+            refused("Content-Type: application/json\r\nX-Zagel-Token: guess\r\n"),
+            401
+        );
+        assert_eq!(
+            refused("Content-Type: text/plain\r\nX-Zagel-Token: s3cret\r\n"),
+            415
+        );
+        assert_eq!(refused("X-Zagel-Token: s3cret\r\n"), 415);
+    }
+
+    #[test]
+    fn other_routes_are_rejected() {
+        let status = |raw: &[u8]| route(raw, TOKEN).0.status;
+        assert_eq!(status(b"GET /health HTTP/1.1\r\n\r\n"), 200);
+        assert_eq!(status(b"GET /open HTTP/1.1\r\n\r\n"), 405);
+        assert_eq!(status(b"GET /secrets HTTP/1.1\r\n\r\n"), 404);
+        assert_eq!(status(b"GET /open HTTP/1.1"), 400);
+    }
+}
@@ -0,0 +1,198 @@
+use std::time::SystemTime;
+
+use iced::Task;
+
+use crate::env_audit;
+use crate::model::RequestDraft;
+use crate::parser::{env_file_path, set_env_var};
+
+use super::{Message, Zagel};
+
+/// Literal being moved out of the draft into an environment variable.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractVariable {
+    pub value: String,
+    pub name: String,
+    /// Name of the environment the value is written to.
+    pub environment: Option<String>,
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// `authorization` for a value found in an `Authorization:` header, so the
+/// usual case needs no typing.
+fn suggested_name(draft: &RequestDraft, value: &str) -> String {
+    draft
+        .headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(_, header_value)| !value.is_empty() && header_value.contains(value))
+        .map(|(name, _)| name.trim().to_ascii_lowercase().replace('-', "_"))
+        .unwrap_or_default()
+}
+
+/// Replaces every occurrence of `value` in the URL, headers and body with
+/// `{{name}}` and returns how many were replaced.
+fn replace_literal(draft: &mut RequestDraft, value: &str, name: &str) -> usize {
+    let placeholder = format!("{{{{{name}}}}}");
+    let mut replaced = 0;
+    for field in [&mut draft.url, &mut draft.headers, &mut draft.body] {
+        let found = field.matches(value).count();
+        if found > 0 {
+            replaced += found;
+            *field = field.replace(value, &placeholder);
+        }
+    }
+    replaced
+}
+
+impl Zagel {
+    /// Opens the form with the body selection, if any, as the value.
+    pub(super) fn handle_extract_variable_toggled(&mut self) -> Task<Message> {
+        if self.extract_variable.take().is_some() {
+            return Task::none();
+        }
+        let value = self.body_editor.selection().unwrap_or_default();
+        let environment = self
+            .environments
+            .get(self.active_environment)
+            .filter(|env| env_file_path(env).is_some())
+            .or_else(|| {
+                self.environments
+                    .iter()
+                    .find(|env| env_file_path(env).is_some())
+            })
+            .map(|env| env.name.clone());
+        self.extract_variable = Some(ExtractVariable {
+            name: suggested_name(&self.draft, &value),
+            value,
+            environment,
+        });
+        Task::none()
+    }
+
+    pub(super) fn handle_extract_value_changed(&mut self, value: String) -> Task<Message> {
+        if let Some(extract) = self.extract_variable.as_mut() {
+            if extract.name.is_empty() {
+                extract.name = suggested_name(&self.draft, &value);
+            }
+            extract.value = value;
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_extract_name_changed(&mut self, name: String) -> Task<Message> {
+        if let Some(extract) = self.extract_variable.as_mut() {
+            extract.name = name;
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_extract_environment_selected(
+        &mut self,
+        environment: String,
+    ) -> Task<Message> {
+        if let Some(extract) = self.extract_variable.as_mut() {
+            extract.environment = Some(environment);
+        }
+        Task::none()
+    }
+
+    /// Writes the value to the environment file first, so a failed write
+    /// leaves the draft untouched.
+    pub(super) fn handle_extract_variable_applied(&mut self) -> Task<Message> {
+        let Some(extract) = self.extract_variable.clone() else {
+            return Task::none();
+        };
+        let name = extract.name.trim();
+        if extract.value.is_empty() {
+            self.update_status_with_missing("Enter the value to extract");
+            return Task::none();
+        }
+        if !valid_name(name) {
+            self.update_status_with_missing(
+                "Variable names may only use letters, digits, '_', '-' and '.'",
+            );
+            return Task::none();
+        }
+        let Some((index, path)) = self
+            .workspace
+            .all_environments()
+            .iter()
+            .enumerate()
+            .find_map(|(index, env)| {
+                (Some(&env.name) == extract.environment.as_ref())
+                    .then(|| env_file_path(env))
+                    .flatten()
+                    .map(|path| (index, path))
+            })
+        else {
+            self.update_status_with_missing("Choose an environment loaded from a file");
+            return Task::none();
+        };
+
+        let mut draft = self.draft.clone();
+        if replace_literal(&mut draft, &extract.value, name) == 0 {
+            self.update_status_with_missing(
+                "The value does not appear in the URL, headers or body",
+            );
+            return Task::none();
+        }
+        if let Err(err) = set_env_var(&path, name, &extract.value) {
+            self.update_status_with_missing(&format!("Failed to update {}: {err}", path.display()));
+            return Task::none();
+        }
+
+        let before = self.workspace.all_environments().clone();
+        let mut after = before.clone();
+        after[index]
+            .vars
+            .insert(name.to_string(), extract.value.clone());
+        self.env_audit.record(env_audit::diff(
+            &before,
+            &after,
+            "extract",
+            SystemTime::now(),
+        ));
+        self.workspace.set_all_environments(after);
+        self.refresh_visible_environments();
+
+        self.body_editor = iced::widget::text_editor::Content::with_text(&draft.body);
+        self.draft = draft;
+        self.set_header_rows_from_draft();
+        self.extract_variable = None;
+        self.update_status_with_missing(&format!("Extracted {{{{{name}}}}} to {}", path.display()));
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace_literal, suggested_name, valid_name};
+    use crate::model::RequestDraft;
+
+    #[test]
+    fn literals_become_placeholders_everywhere() {
+        let mut draft = RequestDraft {
+            url: "https://api.test/users?token=abc123".to_string(),
+            headers: "Accept: */*\nX-Api-Token: abc123".to_string(),
+            body: "{\"token\":\"abc123\"}".to_string(),
+            ..RequestDraft::default()
+        };
+        assert_eq!(suggested_name(&draft, "abc123"), "x_api_token");
+        assert_eq!(replace_literal(&mut draft, "abc123", "token"), 3);
+        assert_eq!(draft.url, "https://api.test/users?token={{token}}");
+        assert_eq!(draft.headers, "Accept: */*\nX-Api-Token: {{token}}");
+        assert_eq!(draft.body, "{\"token\":\"{{token}}\"}");
+        assert_eq!(replace_literal(&mut draft, "missing", "token"), 0);
+
+        assert!(valid_name("api.host_2"));
+        assert!(!valid_name("has space"));
+        assert!(!valid_name(""));
+    }
+}
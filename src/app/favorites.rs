@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::model::{HttpFile, RequestId, RequestRef};
+
+/// Starred requests of every project, in the order they were starred.
+pub fn favorite_requests(
+    favorites: &BTreeMap<PathBuf, Vec<String>>,
+    http_files: &HashMap<PathBuf, HttpFile>,
+) -> Vec<RequestId> {
+    favorites
+        .values()
+        .flatten()
+        .filter_map(|raw| {
+            let reference = RequestRef::parse(raw)?;
+            reference.resolve(http_files.get(&reference.path)?)
+        })
+        .collect()
+}
+
+/// Points the favorites of the file at `from` to `to` after a rename.
+pub fn move_favorites(favorites: &mut BTreeMap<PathBuf, Vec<String>>, from: &Path, to: &Path) {
+    for raw in favorites.values_mut().flatten() {
+        if let Some(mut reference) = RequestRef::parse(raw)
+            && reference.path == from
+        {
+            reference.path = to.to_path_buf();
+            *raw = reference.to_string();
+        }
+    }
+}
+
+impl Zagel {
+    /// Stars or unstars a request, by its `# @id` or `# @name` when it has
+    /// one and by its position otherwise.
+    pub(super) fn handle_favorite_toggled(&mut self, id: &RequestId) -> Task<Message> {
+        let RequestId::HttpFile { path, index } = id;
+        let Some(project) = self
+            .project_root_for_path(path)
+            .map(|root| root.as_path().to_path_buf())
+        else {
+            self.update_status_with_missing("Only requests of a project can be starred");
+            return Task::none();
+        };
+        let Some(draft) = self.request_draft(id) else {
+            return Task::none();
+        };
+
+        let key = RequestRef::new(path, *index, &draft).to_string();
+        let starred = self.state.favorites.entry(project.clone()).or_default();
+        let status = if let Some(position) = starred.iter().position(|raw| *raw == key) {
+            starred.remove(position);
+            "Removed from favorites"
+        } else {
+            starred.push(key);
+            "Added to favorites"
+        };
+        if starred.is_empty() {
+            self.state.favorites.remove(&project);
+        }
+        self.persist_state();
+        self.update_status_with_missing(status);
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::path::{Path, PathBuf};
+
+    use super::{favorite_requests, move_favorites};
+    use crate::model::{HttpFile, RequestDraft, RequestId};
+
+    #[test]
+    fn favorites_follow_their_request_across_reorders_and_renames() {
+        let named = |name: &str| RequestDraft {
+            name: Some(name.to_string()),
+            ..RequestDraft::default()
+        };
+        let path = PathBuf::from("/p/api.http");
+        let file = |requests| HttpFile {
+            path: path.clone(),
+            variables: Vec::new(),
+            auth: None,
+            requests,
+        };
+        let mut favorites = BTreeMap::from([(
+            PathBuf::from("/p"),
+            vec!["/p/api.http#name=login".to_string()],
+        )]);
+
+        let files = HashMap::from([(path.clone(), file(vec![named("login"), named("me")]))]);
+        let starred = favorite_requests(&favorites, &files);
+        assert_eq!(
+            starred,
+            [RequestId::HttpFile {
+                path: path.clone(),
+                index: 0
+            }]
+        );
+        let files = HashMap::from([(path.clone(), file(vec![named("me"), named("login")]))]);
+        assert_eq!(
+            favorite_requests(&favorites, &files),
+            [RequestId::HttpFile {
+                path: path.clone(),
+                index: 1
+            }]
+        );
+
+        move_favorites(&mut favorites, &path, Path::new("/p/auth.http"));
+        assert_eq!(favorites[Path::new("/p")], ["/p/auth.http#name=login"]);
+        assert!(favorite_requests(&favorites, &files).is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+use iced::Task;
+
+use super::options::{AuthState, inherited_auth};
+use super::{Message, Zagel};
+use crate::model::{FileAuth, RequestId};
+use crate::parser::write_http_file_with_header;
+
+impl Zagel {
+    /// `# @auth` of the file holding `request`.
+    pub(super) fn file_auth(&self, request: Option<&RequestId>) -> Option<&FileAuth> {
+        request
+            .and_then(|RequestId::HttpFile { path, .. }| self.workspace.http_files().get(path))
+            .and_then(|file| file.auth.as_ref())
+    }
+
+    /// Auth picked in the editor, or else the one inherited from the file.
+    pub(super) fn effective_auth(&self, request: Option<&RequestId>, headers: &str) -> AuthState {
+        if self.auth == AuthState::None {
+            inherited_auth(self.file_auth(request), headers)
+        } else {
+            self.auth.clone()
+        }
+    }
+
+    /// Writes `auth` as the `# @auth` of the selected request's file.
+    fn store_file_auth(&mut self, auth: Option<FileAuth>) -> Result<String, String> {
+        let Some(RequestId::HttpFile { path, .. }) = self.workspace.selection_cloned() else {
+            return Err("Select a saved request first".to_string());
+        };
+        let Some(file) = self.workspace.http_files().get(&path) else {
+            return Err(format!("{} is not loaded", path.display()));
+        };
+        write_http_file_with_header(&path, &file.variables, auth.as_ref(), &file.requests)
+            .map_err(|err| err.to_string())?;
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        if let Some(mut workspace) = self.workspace.configured_state()
+            && let Some(file) = workspace.http_files_mut().get_mut(&path)
+        {
+            file.auth = auth;
+        }
+        Ok(name)
+    }
+
+    pub(super) fn handle_file_auth_saved(&mut self) -> Task<Message> {
+        let Some(auth) = self.auth.to_file_auth() else {
+            self.update_status_with_missing(
+                "Only Bearer, Basic and API key auth can be a file default",
+            );
+            return Task::none();
+        };
+        match self.store_file_auth(Some(auth)) {
+            Ok(name) => {
+                // The request now inherits what it used to set itself.
+                self.auth = AuthState::None;
+                self.update_status_with_missing(&format!("Saved auth as the default for {name}"));
+            }
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_file_auth_cleared(&mut self) -> Task<Message> {
+        match self.store_file_auth(None) {
+            Ok(name) => {
+                self.update_status_with_missing(&format!("Removed the default auth of {name}"));
+            }
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+}
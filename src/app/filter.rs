@@ -0,0 +1,45 @@
+use iced::Task;
+
+use crate::jsonpath;
+
+use super::{Message, Zagel};
+
+/// Pretty-printed result of `expr` over a JSON body, or the reason it failed.
+/// `None` when the body is not JSON.
+pub(super) fn filtered_body(raw: &str, expr: &str) -> Option<String> {
+    let value = serde_json::from_str::<serde_json::Value>(raw).ok()?;
+    Some(match jsonpath::apply(&value, expr) {
+        Ok(matched) => serde_json::to_string_pretty(&matched).unwrap_or_default(),
+        Err(err) => format!("Filter error: {err}"),
+    })
+}
+
+impl Zagel {
+    pub(super) fn handle_response_filter_changed(&mut self, expr: String) -> Task<Message> {
+        if let Some(id) = self.workspace.selection() {
+            let key = self.request_ref(id).to_string();
+            if expr.trim().is_empty() {
+                self.state.response_filters.remove(&key);
+            } else {
+                self.state.response_filters.insert(key, expr.clone());
+            }
+            self.persist_state();
+        }
+        self.response_filter = expr;
+        self.update_response_viewer();
+        Task::none()
+    }
+
+    pub(super) fn restore_response_filter(&mut self) {
+        self.response_filter = self
+            .workspace
+            .selection()
+            .and_then(|id| {
+                self.state
+                    .response_filters
+                    .get(&self.request_ref(id).to_string())
+            })
+            .cloned()
+            .unwrap_or_default();
+    }
+}
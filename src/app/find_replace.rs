@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use iced::Task;
+use regex::Regex;
+
+use crate::model::{HttpFile, RequestDraft, RequestId};
+use crate::parser::write_http_file;
+
+use super::{Message, Zagel};
+
+/// One request the current inputs would change.
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub id: RequestId,
+    pub before: RequestDraft,
+    pub after: RequestDraft,
+    pub matches: usize,
+}
+
+/// Workspace-wide find and replace over request URLs, headers and bodies.
+#[derive(Debug, Clone)]
+pub struct FindReplace {
+    pub open: bool,
+    pub query: String,
+    pub replacement: String,
+    pub regex: bool,
+    /// Requests that Apply would change, or why the query is invalid.
+    pub preview: Result<Vec<Replacement>, String>,
+}
+
+impl Default for FindReplace {
+    fn default() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            replacement: String::new(),
+            regex: false,
+            preview: Ok(Vec::new()),
+        }
+    }
+}
+
+/// Plain queries match literally; in regex mode the replacement may refer
+/// to groups as `$1` or `${name}`.
+fn pattern(query: &str, regex: bool) -> Result<Regex, String> {
+    let source = if regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    Regex::new(&source).map_err(|err| format!("Invalid pattern: {err}"))
+}
+
+/// Requests in `order` whose URL, headers or body match, with the text
+/// after replacing every match.
+pub fn plan(
+    files: &HashMap<PathBuf, HttpFile>,
+    order: &[PathBuf],
+    query: &str,
+    replacement: &str,
+    regex: bool,
+) -> Result<Vec<Replacement>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = pattern(query, regex)?;
+    let replacement = if regex {
+        replacement.to_string()
+    } else {
+        replacement.replace('$', "$$")
+    };
+    let mut planned = Vec::new();
+    for path in order {
+        let Some(file) = files.get(path) else {
+            continue;
+        };
+        for (index, before) in file.requests.iter().enumerate() {
+            let mut after = before.clone();
+            let mut matches = 0;
+            for field in [&mut after.url, &mut after.headers, &mut after.body] {
+                let found = pattern.find_iter(field).count();
+                if found > 0 {
+                    matches += found;
+                    *field = pattern
+                        .replace_all(field, replacement.as_str())
+                        .into_owned();
+                }
+            }
+            if matches > 0 {
+                planned.push(Replacement {
+                    id: RequestId::HttpFile {
+                        path: path.clone(),
+                        index,
+                    },
+                    before: before.clone(),
+                    after,
+                    matches,
+                });
+            }
+        }
+    }
+    Ok(planned)
+}
+
+impl Zagel {
+    pub(super) fn handle_find_replace_toggled(&mut self) -> Task<Message> {
+        self.find_replace.open = !self.find_replace.open;
+        self.refresh_find_replace();
+        Task::none()
+    }
+
+    pub(super) fn handle_find_query_changed(&mut self, query: String) -> Task<Message> {
+        self.find_replace.query = query;
+        self.refresh_find_replace();
+        Task::none()
+    }
+
+    pub(super) fn handle_replace_text_changed(&mut self, replacement: String) -> Task<Message> {
+        self.find_replace.replacement = replacement;
+        self.refresh_find_replace();
+        Task::none()
+    }
+
+    pub(super) fn handle_find_regex_toggled(&mut self, regex: bool) -> Task<Message> {
+        self.find_replace.regex = regex;
+        self.refresh_find_replace();
+        Task::none()
+    }
+
+    /// Writes the previewed changes file by file; a file that fails to
+    /// write keeps its requests unchanged.
+    pub(super) fn handle_find_replace_apply(&mut self) -> Task<Message> {
+        self.refresh_find_replace();
+        let Ok(planned) = self.find_replace.preview.clone() else {
+            return Task::none();
+        };
+        if planned.is_empty() {
+            return Task::none();
+        }
+        let selection = self.workspace.selection_cloned();
+        let mut changed = 0;
+        let mut errors = Vec::new();
+        if let Some(mut workspace) = self.workspace.configured_state() {
+            let mut by_file: HashMap<PathBuf, Vec<&Replacement>> = HashMap::new();
+            for replacement in &planned {
+                let RequestId::HttpFile { path, .. } = &replacement.id;
+                by_file.entry(path.clone()).or_default().push(replacement);
+            }
+            for (path, replacements) in by_file {
+                let Some(file) = workspace.http_files_mut().get_mut(&path) else {
+                    continue;
+                };
+                let mut requests = file.requests.clone();
+                for replacement in &replacements {
+                    let RequestId::HttpFile { index, .. } = replacement.id;
+                    if let Some(request) = requests.get_mut(index) {
+                        *request = replacement.after.clone();
+                    }
+                }
+                match write_http_file(&path, &requests) {
+                    Ok(()) => {
+                        file.requests = requests;
+                        changed += replacements.len();
+                    }
+                    Err(err) => errors.push(format!("Failed to update {}: {err}", path.display())),
+                }
+            }
+        }
+        if let Some(id) = selection
+            && planned.iter().any(|replacement| replacement.id == id)
+        {
+            self.apply_selection(&id);
+        }
+        let status = if errors.is_empty() {
+            format!("Replaced matches in {changed} requests")
+        } else {
+            errors.join("; ")
+        };
+        self.update_status_with_missing(&status);
+        self.refresh_find_replace();
+        Task::none()
+    }
+
+    fn refresh_find_replace(&mut self) {
+        let find = &self.find_replace;
+        let preview = plan(
+            self.workspace.http_files(),
+            self.workspace.http_file_order(),
+            &find.query,
+            &find.replacement,
+            find.regex,
+        );
+        self.find_replace.preview = preview;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::plan;
+    use crate::model::{HttpFile, RequestDraft};
+
+    fn files() -> (HashMap<PathBuf, HttpFile>, Vec<PathBuf>) {
+        let path = PathBuf::from("api.http");
+        let file = HttpFile {
+            path: path.clone(),
+            variables: Vec::new(),
+            auth: None,
+            requests: vec![
+                RequestDraft {
+                    url: "https://old.example.com/v1/users".to_string(),
+                    headers: "Host: old.example.com".to_string(),
+                    ..RequestDraft::default()
+                },
+                RequestDraft {
+                    url: "{{base}}/v1/orders".to_string(),
+                    body: "{\"price\": \"$5\"}".to_string(),
+                    ..RequestDraft::default()
+                },
+            ],
+        };
+        (HashMap::from([(path.clone(), file)]), vec![path])
+    }
+
+    #[test]
+    fn plain_queries_replace_literally_in_every_field() {
+        let (files, order) = files();
+        let planned = plan(&files, &order, "old.example.com", "new.example.com", false).unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].matches, 2);
+        assert_eq!(planned[0].after.url, "https://new.example.com/v1/users");
+        assert_eq!(planned[0].after.headers, "Host: new.example.com");
+
+        let planned = plan(&files, &order, "$5", "$6", false).unwrap();
+        assert_eq!(planned[0].after.body, "{\"price\": \"$6\"}");
+        assert!(plan(&files, &order, "", "x", false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn regex_queries_expand_groups_and_report_bad_patterns() {
+        let (files, order) = files();
+        let planned = plan(&files, &order, r"/v1/(\w+)", "/v2/$1", true).unwrap();
+        assert_eq!(planned.len(), 2);
+        assert_eq!(planned[1].after.url, "{{base}}/v2/orders");
+        assert!(plan(&files, &order, "(", "", true).is_err());
+    }
+}
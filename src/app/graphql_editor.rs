@@ -0,0 +1,392 @@
+//! Folding, soft wrap and bracket matching for the GraphQL query editor.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use iced::advanced::text::highlighter::{self, Highlighter};
+use iced::widget::text_editor::{self, Cursor, Position};
+use iced::{Font, Task, Theme, font};
+
+use super::placeholders::{PlaceholderHighlight, placeholder_format, placeholder_spans};
+use super::{Message, Zagel};
+
+/// Stands for a folded selection set in the editor, followed by its index
+/// and a closing brace: `{⋯0}`.
+const FOLD_MARKER: &str = "{⋯";
+
+#[derive(Debug)]
+pub struct GraphqlEditorState {
+    /// Folded selection sets; `{⋯N}` in the editor stands for `folds[N]`.
+    folds: Vec<String>,
+    /// `(line, column)` of the bracket at the cursor and its partner.
+    brackets: Option<[(usize, usize); 2]>,
+    pub wrap: bool,
+}
+
+impl Default for GraphqlEditorState {
+    fn default() -> Self {
+        Self {
+            folds: Vec::new(),
+            brackets: None,
+            wrap: true,
+        }
+    }
+}
+
+impl GraphqlEditorState {
+    pub const fn has_folds(&self) -> bool {
+        !self.folds.is_empty()
+    }
+
+    /// `text` with every fold marker replaced by the selection set it hides.
+    pub fn expand(&self, text: &str) -> String {
+        let mut expanded = text.to_string();
+        // Folds only ever hide earlier markers, so this many passes suffice.
+        for _ in 0..=self.folds.len() {
+            let markers = fold_markers(&expanded, self.folds.len());
+            if markers.is_empty() {
+                break;
+            }
+            for (range, index) in markers.into_iter().rev() {
+                expanded.replace_range(range, &self.folds[index]);
+            }
+        }
+        expanded
+    }
+
+    /// Folds the innermost `{ ... }` around `offset`, returning the new
+    /// text and where the marker starts.
+    fn fold(&mut self, text: &str, offset: usize) -> Option<(String, usize)> {
+        let (open, close) = bracket_pairs(text)
+            .into_iter()
+            .filter(|&(open, close)| {
+                text[open..].starts_with('{') && open <= offset && offset <= close
+            })
+            .min_by_key(|&(open, close)| close - open)?;
+        let marker = format!("{FOLD_MARKER}{}}}", self.folds.len());
+        self.folds.push(text[open..=close].to_string());
+        let mut folded = text.to_string();
+        folded.replace_range(open..=close, &marker);
+        Some((folded, open))
+    }
+
+    /// Restores the fold marker at `offset`.
+    fn unfold(&self, text: &str, offset: usize) -> Option<String> {
+        let (range, index) = fold_markers(text, self.folds.len())
+            .into_iter()
+            .find(|(range, _)| range.start <= offset && offset <= range.end)?;
+        let mut unfolded = text.to_string();
+        unfolded.replace_range(range, &self.folds[index]);
+        Some(unfolded)
+    }
+}
+
+/// Fold markers in `text` whose index is below `count`.
+fn fold_markers(text: &str, count: usize) -> Vec<(Range<usize>, usize)> {
+    let mut markers = Vec::new();
+    let mut from = 0;
+    while let Some(start) = text[from..].find(FOLD_MARKER).map(|at| from + at) {
+        let digits_start = start + FOLD_MARKER.len();
+        let digits = text[digits_start..]
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(text.len() - digits_start);
+        let end = digits_start + digits;
+        from = digits_start;
+        if text[end..].starts_with('}')
+            && let Ok(index) = text[digits_start..end].parse::<usize>()
+            && index < count
+        {
+            markers.push((start..end + 1, index));
+            from = end + 1;
+        }
+    }
+    markers
+}
+
+/// Byte offsets of matching `()`, `[]` and `{}` pairs, skipping strings,
+/// comments and `{{placeholders}}`.
+fn bracket_pairs(text: &str) -> Vec<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut pairs = Vec::new();
+    let mut open = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'#' => {
+                index = text[index..]
+                    .find('\n')
+                    .map_or(bytes.len(), |at| index + at);
+            }
+            b'"' => {
+                index += 1;
+                while index < bytes.len() && bytes[index] != b'"' {
+                    index += if bytes[index] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'{' if bytes.get(index + 1) == Some(&b'{') => {
+                index = text[index..]
+                    .find("}}")
+                    .map_or(bytes.len(), |at| index + at + 1);
+            }
+            b'(' | b'[' | b'{' => open.push(index),
+            closing @ (b')' | b']' | b'}') => {
+                let expected = match closing {
+                    b')' => b'(',
+                    b']' => b'[',
+                    _ => b'{',
+                };
+                if let Some(start) = open.pop()
+                    && bytes[start] == expected
+                {
+                    pairs.push((start, index));
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    pairs
+}
+
+/// The bracket at `offset`, or just before it, and its partner.
+fn matching_brackets(text: &str, offset: usize) -> Option<[usize; 2]> {
+    let pairs = bracket_pairs(text);
+    [Some(offset), offset.checked_sub(1)]
+        .into_iter()
+        .flatten()
+        .find_map(|at| {
+            pairs
+                .iter()
+                .find_map(|&(open, close)| (open == at || close == at).then_some([open, close]))
+        })
+}
+
+fn offset_of(text: &str, position: Position) -> usize {
+    text.split('\n')
+        .take(position.line)
+        .map(|line| line.len() + 1)
+        .sum::<usize>()
+        + position.column
+}
+
+fn position_of(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |at| at + 1);
+    Position {
+        line: before.matches('\n').count(),
+        column: before.len() - line_start,
+    }
+}
+
+impl Zagel {
+    /// The query as it is sent, with folded selection sets restored.
+    pub(super) fn graphql_query_text(&self) -> String {
+        self.graphql_editor.expand(&self.graphql_query.text())
+    }
+
+    /// Replaces the query editor, forgetting folds it no longer holds.
+    pub(super) fn set_graphql_query(&mut self, query: &str) {
+        self.graphql_query = text_editor::Content::with_text(query);
+        self.graphql_editor.folds.clear();
+        self.graphql_editor.brackets = None;
+    }
+
+    pub(super) fn handle_graphql_query_edited(
+        &mut self,
+        action: text_editor::Action,
+    ) -> Task<Message> {
+        let edited = action.is_edit();
+        self.graphql_query.perform(action);
+        self.refresh_graphql_brackets();
+        if edited {
+            self.refresh_graphql_check();
+        }
+        self.update_status_with_missing("Ready");
+        Task::none()
+    }
+
+    /// Unfolds the marker at the cursor, or folds the selection set around it.
+    pub(super) fn handle_graphql_fold_toggled(&mut self) -> Task<Message> {
+        let text = self.graphql_query.text();
+        let offset = offset_of(&text, self.graphql_query.cursor().position);
+        let toggled = self
+            .graphql_editor
+            .unfold(&text, offset)
+            .map(|unfolded| (unfolded, offset))
+            .or_else(|| self.graphql_editor.fold(&text, offset));
+        let Some((updated, cursor)) = toggled else {
+            self.update_status_with_missing("Place the cursor inside { } to fold");
+            return Task::none();
+        };
+        self.replace_graphql_display(&updated, cursor);
+        Task::none()
+    }
+
+    pub(super) fn handle_graphql_unfold_all(&mut self) -> Task<Message> {
+        let query = self.graphql_query_text();
+        self.set_graphql_query(&query);
+        Task::none()
+    }
+
+    fn replace_graphql_display(&mut self, text: &str, cursor: usize) {
+        self.graphql_query = text_editor::Content::with_text(text);
+        self.graphql_query.move_to(Cursor {
+            position: position_of(text, cursor),
+            selection: None,
+        });
+        self.refresh_graphql_brackets();
+    }
+
+    fn refresh_graphql_brackets(&mut self) {
+        let text = self.graphql_query.text();
+        let offset = offset_of(&text, self.graphql_query.cursor().position);
+        self.graphql_editor.brackets = matching_brackets(&text, offset).map(|pair| {
+            pair.map(|at| {
+                let position = position_of(&text, at);
+                (position.line, position.column)
+            })
+        });
+    }
+
+    pub(super) fn graphql_highlight_settings(&self) -> GraphqlHighlightSettings {
+        GraphqlHighlightSettings {
+            defined: self.defined_variables(),
+            brackets: self.graphql_editor.brackets,
+            folds: self.graphql_editor.folds.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphqlHighlightSettings {
+    defined: BTreeSet<String>,
+    brackets: Option<[(usize, usize); 2]>,
+    folds: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphqlHighlight {
+    Placeholder(PlaceholderHighlight),
+    Bracket,
+    Fold,
+}
+
+/// Colours placeholders, fold markers and the brackets at the cursor.
+pub struct GraphqlHighlighter {
+    settings: GraphqlHighlightSettings,
+    line: usize,
+}
+
+impl Highlighter for GraphqlHighlighter {
+    type Settings = GraphqlHighlightSettings;
+    type Highlight = GraphqlHighlight;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, GraphqlHighlight)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            settings: settings.clone(),
+            line: 0,
+        }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        self.settings.clone_from(new_settings);
+        self.line = 0;
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.line = self.line.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        let number = self.line;
+        self.line += 1;
+        let mut spans: Vec<_> = placeholder_spans(line, &self.settings.defined)
+            .into_iter()
+            .map(|(range, highlight)| (range, GraphqlHighlight::Placeholder(highlight)))
+            .collect();
+        spans.extend(
+            fold_markers(line, self.settings.folds)
+                .into_iter()
+                .map(|(range, _)| (range, GraphqlHighlight::Fold)),
+        );
+        spans.extend(
+            self.settings
+                .brackets
+                .into_iter()
+                .flatten()
+                .filter(|&(bracket_line, _)| bracket_line == number)
+                .map(|(_, column)| (column..column + 1, GraphqlHighlight::Bracket)),
+        );
+        spans.sort_by_key(|(range, _)| range.start);
+        // A bracket of a fold marker is already coloured as the marker.
+        let mut end = 0;
+        spans.retain(|(range, _)| {
+            let keep = range.start >= end;
+            end = end.max(range.end);
+            keep
+        });
+        spans.into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.line
+    }
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // signature required by `highlight_with`
+pub fn graphql_format(highlight: &GraphqlHighlight, theme: &Theme) -> highlighter::Format<Font> {
+    let palette = theme.extended_palette();
+    match *highlight {
+        GraphqlHighlight::Placeholder(placeholder) => placeholder_format(&placeholder, theme),
+        GraphqlHighlight::Bracket => highlighter::Format {
+            color: Some(palette.primary.strong.color),
+            font: Some(Font {
+                weight: font::Weight::Bold,
+                ..Font::DEFAULT
+            }),
+        },
+        GraphqlHighlight::Fold => highlighter::Format {
+            color: Some(palette.secondary.strong.color),
+            font: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GraphqlEditorState, matching_brackets};
+
+    const QUERY: &str = "query {\n  user(id: \"}\") {\n    name\n  }\n}";
+
+    #[test]
+    fn folding_hides_a_selection_set_until_it_is_expanded() {
+        let mut state = GraphqlEditorState::default();
+        let inside_user = QUERY.find("name").unwrap();
+        let (folded, marker) = state.fold(QUERY, inside_user).unwrap();
+        assert_eq!(folded, "query {\n  user(id: \"}\") {⋯0}\n}");
+        assert_eq!(state.expand(&folded), QUERY);
+
+        let (outer, _) = state.fold(&folded, 2 + folded.find('{').unwrap()).unwrap();
+        assert_eq!(outer, "query {⋯1}");
+        assert_eq!(state.expand(&outer), QUERY);
+
+        let unfolded = state.unfold(&outer, outer.len()).unwrap();
+        assert_eq!(unfolded, folded);
+        assert_eq!(state.unfold(&unfolded, marker).unwrap(), QUERY);
+    }
+
+    #[test]
+    fn brackets_match_outside_strings_and_placeholders() {
+        let open = QUERY.find("{\n    name").unwrap();
+        let close = QUERY.rfind("}\n}").unwrap();
+        assert_eq!(matching_brackets(QUERY, open), Some([open, close]));
+        assert_eq!(matching_brackets(QUERY, close + 1), Some([open, close]));
+
+        let with_placeholder = "{ user(id: {{id}}) }";
+        assert_eq!(
+            matching_brackets(with_placeholder, 0),
+            Some([0, with_placeholder.len() - 1])
+        );
+    }
+}
@@ -0,0 +1,775 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use iced::Task;
+use iced::widget::text_editor;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::options::{RequestMode, build_graphql_body, split_graphql_body};
+use super::{Message, Zagel};
+use crate::graphql::{self, Document, OperationKind, Selection};
+use crate::model::{Method, RequestDraft, ResponsePreview, apply_environment};
+use crate::net::send_request;
+
+pub const INTROSPECTION_QUERY: &str = "query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind name description
+      fields(includeDeprecated: true) {
+        name description isDeprecated deprecationReason
+        args { name description defaultValue type { ...TypeRef } }
+        type { ...TypeRef }
+      }
+      inputFields { name description defaultValue type { ...TypeRef } }
+      enumValues(includeDeprecated: true) { name description }
+    }
+  }
+}
+fragment TypeRef on __Type {
+  kind name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } } }
+}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeRef {
+    Named(String),
+    List(Box<Self>),
+    NonNull(Box<Self>),
+}
+
+impl TypeRef {
+    /// The named type under any list and non-null wrappers.
+    pub fn named(&self) -> &str {
+        match self {
+            Self::Named(name) => name,
+            Self::List(inner) | Self::NonNull(inner) => inner.named(),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Named(name) => f.write_str(name),
+            Self::List(inner) => write!(f, "[{inner}]"),
+            Self::NonNull(inner) => write!(f, "{inner}!"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaArg {
+    pub name: String,
+    pub ty: TypeRef,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaField {
+    pub name: String,
+    pub description: Option<String>,
+    pub ty: TypeRef,
+    pub args: Vec<SchemaArg>,
+    pub deprecation: Option<String>,
+}
+
+impl SchemaField {
+    /// `name(arg: Type = default): Type`
+    pub fn signature(&self) -> String {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| {
+                arg.default.as_ref().map_or_else(
+                    || format!("{}: {}", arg.name, arg.ty),
+                    |default| format!("{}: {} = {default}", arg.name, arg.ty),
+                )
+            })
+            .collect::<Vec<_>>();
+        if args.is_empty() {
+            format!("{}: {}", self.name, self.ty)
+        } else {
+            format!("{}({}): {}", self.name, args.join(", "), self.ty)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaType {
+    pub name: String,
+    /// Introspection kind, e.g. `OBJECT`, `INPUT_OBJECT`, `ENUM`.
+    pub kind: String,
+    pub description: Option<String>,
+    /// Output fields, or input fields for input objects.
+    pub fields: Vec<SchemaField>,
+    pub enum_values: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphqlSchema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    pub types: Vec<SchemaType>,
+}
+
+#[derive(Deserialize)]
+struct RawResponse {
+    data: Option<RawData>,
+    #[serde(default)]
+    errors: Vec<RawError>,
+}
+
+#[derive(Deserialize)]
+struct RawError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RawData {
+    #[serde(rename = "__schema")]
+    schema: RawSchema,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSchema {
+    query_type: Option<RawName>,
+    mutation_type: Option<RawName>,
+    subscription_type: Option<RawName>,
+    types: Vec<RawType>,
+}
+
+#[derive(Deserialize)]
+struct RawName {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawType {
+    kind: String,
+    name: String,
+    description: Option<String>,
+    fields: Option<Vec<RawField>>,
+    input_fields: Option<Vec<RawField>>,
+    enum_values: Option<Vec<RawName>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawField {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    ty: RawTypeRef,
+    #[serde(default)]
+    args: Vec<RawArg>,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawArg {
+    name: String,
+    #[serde(rename = "type")]
+    ty: RawTypeRef,
+    default_value: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTypeRef {
+    kind: String,
+    name: Option<String>,
+    of_type: Option<Box<Self>>,
+}
+
+impl RawTypeRef {
+    fn into_type_ref(self) -> Result<TypeRef, String> {
+        let inner = |of_type: Option<Box<Self>>| {
+            of_type
+                .ok_or_else(|| format!("{} type without an inner type", self.kind))
+                .and_then(|inner| inner.into_type_ref())
+                .map(Box::new)
+        };
+        match self.kind.as_str() {
+            "LIST" => Ok(TypeRef::List(inner(self.of_type)?)),
+            "NON_NULL" => Ok(TypeRef::NonNull(inner(self.of_type)?)),
+            _ => self
+                .name
+                .map(TypeRef::Named)
+                .ok_or_else(|| "Type reference without a name".to_string()),
+        }
+    }
+}
+
+impl RawField {
+    fn into_field(self) -> Result<SchemaField, String> {
+        Ok(SchemaField {
+            name: self.name,
+            description: self.description.filter(|text| !text.trim().is_empty()),
+            ty: self.ty.into_type_ref()?,
+            args: self
+                .args
+                .into_iter()
+                .map(|arg| {
+                    Ok(SchemaArg {
+                        name: arg.name,
+                        ty: arg.ty.into_type_ref()?,
+                        default: arg.default_value,
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+            deprecation: self
+                .is_deprecated
+                .then(|| self.deprecation_reason.unwrap_or_default()),
+        })
+    }
+}
+
+impl GraphqlSchema {
+    /// Parses the result of [`INTROSPECTION_QUERY`].
+    pub fn from_response(body: &[u8]) -> Result<Self, String> {
+        let raw: RawResponse = serde_json::from_slice(body)
+            .map_err(|err| format!("Introspection response is not valid JSON: {err}"))?;
+        let Some(data) = raw.data else {
+            let messages = raw
+                .errors
+                .into_iter()
+                .map(|error| error.message)
+                .collect::<Vec<_>>();
+            return Err(if messages.is_empty() {
+                "Introspection response has no data".to_string()
+            } else {
+                format!("Introspection failed: {}", messages.join("; "))
+            });
+        };
+        let schema = data.schema;
+        let types = schema
+            .types
+            .into_iter()
+            .map(|ty| {
+                let fields = ty
+                    .fields
+                    .or(ty.input_fields)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(RawField::into_field)
+                    .collect::<Result<_, _>>()?;
+                Ok(SchemaType {
+                    name: ty.name,
+                    kind: ty.kind,
+                    description: ty.description.filter(|text| !text.trim().is_empty()),
+                    fields,
+                    enum_values: ty
+                        .enum_values
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|value| value.name)
+                        .collect(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self {
+            query_type: schema.query_type.map(|ty| ty.name),
+            mutation_type: schema.mutation_type.map(|ty| ty.name),
+            subscription_type: schema.subscription_type.map(|ty| ty.name),
+            types,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SchemaType> {
+        self.types.iter().find(|ty| ty.name == name)
+    }
+
+    /// Root fields of the query, mutation and subscription types, in that order.
+    pub fn operations(&self) -> Vec<(&'static str, &SchemaType)> {
+        [
+            ("Queries", &self.query_type),
+            ("Mutations", &self.mutation_type),
+            ("Subscriptions", &self.subscription_type),
+        ]
+        .into_iter()
+        .filter_map(|(label, name)| Some((label, self.get(name.as_deref()?)?)))
+        .collect()
+    }
+
+    /// Named types worth browsing: everything but the root operation types and
+    /// the `__` introspection types.
+    pub fn browsable_types(&self) -> impl Iterator<Item = &SchemaType> {
+        self.types.iter().filter(|ty| {
+            !ty.name.starts_with("__")
+                && Some(&ty.name) != self.query_type.as_ref()
+                && Some(&ty.name) != self.mutation_type.as_ref()
+                && Some(&ty.name) != self.subscription_type.as_ref()
+        })
+    }
+}
+
+impl GraphqlSchema {
+    /// Checks `document` against the schema: operation types, fields, arguments,
+    /// subfield selections, fragments and type conditions. Each problem is
+    /// reported as `line:column: message`.
+    pub fn validate(&self, document: &Document) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut names = HashSet::new();
+        let several = document.operations.len() > 1;
+        for operation in &document.operations {
+            let position = operation.position;
+            match &operation.name {
+                None if several => errors.push(format!(
+                    "{position}: an anonymous operation must be the only operation in the document"
+                )),
+                Some(name) if !names.insert(name) => errors.push(format!(
+                    "{position}: there is more than one operation named \"{name}\""
+                )),
+                _ => {}
+            }
+            let root = match operation.kind {
+                OperationKind::Query => &self.query_type,
+                OperationKind::Mutation => &self.mutation_type,
+                OperationKind::Subscription => &self.subscription_type,
+            };
+            match root.as_deref() {
+                Some(root) => {
+                    self.validate_selections(root, &operation.selections, document, &mut errors);
+                }
+                None => errors.push(format!(
+                    "{position}: the schema does not support {} operations",
+                    operation.kind
+                )),
+            }
+        }
+        for fragment in &document.fragments {
+            if self.get(&fragment.type_condition).is_some() {
+                self.validate_selections(
+                    &fragment.type_condition,
+                    &fragment.selections,
+                    document,
+                    &mut errors,
+                );
+            } else {
+                errors.push(format!(
+                    "{}: unknown type \"{}\"",
+                    fragment.position, fragment.type_condition
+                ));
+            }
+        }
+        errors
+    }
+
+    fn validate_selections(
+        &self,
+        parent: &str,
+        selections: &[Selection],
+        document: &Document,
+        errors: &mut Vec<String>,
+    ) {
+        let is_root_query = self.query_type.as_deref() == Some(parent);
+        for selection in selections {
+            match selection {
+                Selection::Field {
+                    name,
+                    arguments,
+                    selections,
+                    position,
+                } => {
+                    if name == "__typename"
+                        || is_root_query && (name == "__schema" || name == "__type")
+                    {
+                        continue;
+                    }
+                    let Some(field) = self
+                        .get(parent)
+                        .and_then(|ty| ty.fields.iter().find(|field| field.name == *name))
+                    else {
+                        errors.push(format!(
+                            "{position}: cannot query field \"{name}\" on type \"{parent}\""
+                        ));
+                        continue;
+                    };
+                    for argument in arguments {
+                        if !field.args.iter().any(|arg| arg.name == *argument) {
+                            errors.push(format!(
+                                "{position}: unknown argument \"{argument}\" on field \"{parent}.{name}\""
+                            ));
+                        }
+                    }
+                    for arg in &field.args {
+                        if matches!(arg.ty, TypeRef::NonNull(_))
+                            && arg.default.is_none()
+                            && !arguments.contains(&arg.name)
+                        {
+                            errors.push(format!(
+                                "{position}: field \"{name}\" requires argument \"{}\" of type {}",
+                                arg.name, arg.ty
+                            ));
+                        }
+                    }
+                    let target = field.ty.named();
+                    let leaf = self
+                        .get(target)
+                        .is_none_or(|ty| matches!(ty.kind.as_str(), "SCALAR" | "ENUM"));
+                    match (leaf, selections.is_empty()) {
+                        (true, false) => errors.push(format!(
+                            "{position}: field \"{name}\" returns {} and cannot have subfields",
+                            field.ty
+                        )),
+                        (false, true) => errors.push(format!(
+                            "{position}: field \"{name}\" of type {} needs a selection of subfields",
+                            field.ty
+                        )),
+                        (false, false) => {
+                            self.validate_selections(target, selections, document, errors);
+                        }
+                        (true, true) => {}
+                    }
+                }
+                Selection::FragmentSpread { name, position } => {
+                    // Fragment bodies are checked once, on their own type.
+                    if !document
+                        .fragments
+                        .iter()
+                        .any(|fragment| fragment.name == *name)
+                    {
+                        errors.push(format!("{position}: unknown fragment \"{name}\""));
+                    }
+                }
+                Selection::InlineFragment {
+                    type_condition,
+                    selections,
+                    position,
+                } => {
+                    let target = type_condition.as_deref().unwrap_or(parent);
+                    if self.get(target).is_some() {
+                        self.validate_selections(target, selections, document, errors);
+                    } else {
+                        errors.push(format!("{position}: unknown type \"{target}\""));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Problems with the query in the editor and the operations it defines.
+#[derive(Debug, Clone, Default)]
+pub struct QueryCheck {
+    pub diagnostics: Vec<String>,
+    /// Named operations; the picker is shown when there is more than one.
+    pub operations: Vec<String>,
+    pub operation: Option<String>,
+}
+
+impl QueryCheck {
+    /// `operationName` to send, when the document needs one.
+    pub fn operation_name(&self) -> Option<&str> {
+        self.operation
+            .as_deref()
+            .filter(|_| self.operations.len() > 1)
+    }
+}
+
+/// Fetched schemas, keyed by the endpoint URL after substitution.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaExplorer {
+    pub schemas: HashMap<String, Arc<GraphqlSchema>>,
+    /// Type whose fields are expanded in the explorer.
+    pub expanded: Option<String>,
+    pub fetching: bool,
+}
+
+fn introspection_error(preview: &ResponsePreview) -> Option<String> {
+    if let Some(error) = &preview.error {
+        return Some(error.clone());
+    }
+    preview
+        .status
+        .filter(|status| !(200..300).contains(status))
+        .map(|status| format!("Introspection request failed with HTTP {status}"))
+}
+
+impl Zagel {
+    /// The GraphQL endpoint as it will be requested, used as the schema cache key.
+    pub(super) fn graphql_endpoint(&self) -> String {
+        let url = self.environments.get(self.active_environment).map_or_else(
+            || self.draft.url.clone(),
+            |env| apply_environment(&self.draft.url, &env.vars),
+        );
+        url.trim().to_string()
+    }
+
+    pub(super) fn graphql_schema(&self) -> Option<&GraphqlSchema> {
+        self.graphql_schemas
+            .schemas
+            .get(&self.graphql_endpoint())
+            .map(Arc::as_ref)
+    }
+
+    /// The draft as it is sent and saved; in GraphQL mode the body is composed
+    /// from the query and variables editors.
+    pub(super) fn composed_draft(&self) -> RequestDraft {
+        let mut draft = self.draft.clone();
+        draft.graphql = self.mode == RequestMode::GraphQl;
+        if draft.graphql {
+            draft.method = Method::Post;
+            draft.body = build_graphql_body(
+                &self.graphql_query_text(),
+                &self.graphql_variables.text(),
+                self.graphql_check.operation_name(),
+            );
+            if !draft.headers.contains("Content-Type") {
+                if !draft.headers.trim().is_empty() {
+                    draft.headers.push('\n');
+                }
+                draft.headers.push_str("Content-Type: application/json");
+            }
+        }
+        draft
+    }
+
+    /// Reopens a saved `# @graphql` request in GraphQL mode.
+    pub(super) fn load_graphql_draft(&mut self, body: &str) {
+        self.mode = RequestMode::GraphQl;
+        let Some((query, variables, operation)) = split_graphql_body(body) else {
+            return;
+        };
+        self.set_graphql_query(&query);
+        self.graphql_variables = text_editor::Content::with_text(&variables);
+        self.graphql_check.operation = operation;
+        self.refresh_graphql_check();
+    }
+
+    /// Re-parses the query and validates it against the endpoint's schema.
+    pub(super) fn refresh_graphql_check(&mut self) {
+        let query = self.graphql_query_text();
+        if query.trim().is_empty() {
+            self.graphql_check = QueryCheck::default();
+            return;
+        }
+        match graphql::parse(&query) {
+            Ok(document) => {
+                let diagnostics = self
+                    .graphql_schema()
+                    .map(|schema| schema.validate(&document))
+                    .unwrap_or_default();
+                let check = &mut self.graphql_check;
+                check.diagnostics = diagnostics;
+                check.operations = document.operation_names();
+                if !check
+                    .operation
+                    .as_ref()
+                    .is_some_and(|name| check.operations.contains(name))
+                {
+                    check.operation = check.operations.first().cloned();
+                }
+            }
+            // Keep the operation picker steady while the query is mid-edit.
+            Err(err) => self.graphql_check.diagnostics = vec![err],
+        }
+    }
+
+    pub(super) fn handle_graphql_operation_selected(&mut self, name: String) -> Task<Message> {
+        self.graphql_check.operation = Some(name);
+        Task::none()
+    }
+
+    pub(super) fn handle_fetch_graphql_schema(&mut self) -> Task<Message> {
+        let endpoint = self.graphql_endpoint();
+        if endpoint.is_empty() {
+            self.update_status_with_missing("Enter the GraphQL endpoint URL first");
+            return Task::none();
+        }
+        let env = self.request_environment(self.workspace.selection());
+        let mut draft = self.draft.clone();
+        draft.method = Method::Post;
+        draft.body = json!({ "query": INTROSPECTION_QUERY }).to_string();
+        if !draft.headers.contains("Content-Type") {
+            draft.headers.push_str("\nContent-Type: application/json");
+        }
+        let auth = self.effective_auth(self.workspace.selection(), &draft.headers);
+        let client = match self.http_client(&draft) {
+            Ok(client) => client,
+            Err(err) => {
+                self.update_status_with_missing(&err);
+                return Task::none();
+            }
+        };
+        self.graphql_schemas.fetching = true;
+        self.update_status_with_missing("Fetching GraphQL schema...");
+        Task::perform(
+            send_request(
+                client,
+                draft,
+                env,
+                auth,
+                self.oauth2_token_cache.clone(),
+                None,
+            ),
+            move |result| {
+                let schema = result.and_then(|outcome| {
+                    let preview = outcome.response;
+                    if let Some(err) = introspection_error(&preview) {
+                        return Err(err);
+                    }
+                    GraphqlSchema::from_response(preview.body.as_deref().unwrap_or_default())
+                });
+                Message::GraphqlSchemaFetched(endpoint, schema.map(Arc::new))
+            },
+        )
+    }
+
+    pub(super) fn handle_graphql_schema_fetched(
+        &mut self,
+        endpoint: String,
+        result: Result<Arc<GraphqlSchema>, String>,
+    ) -> Task<Message> {
+        self.graphql_schemas.fetching = false;
+        match result {
+            Ok(schema) => {
+                let count = schema.browsable_types().count();
+                self.graphql_schemas.schemas.insert(endpoint, schema);
+                self.refresh_graphql_check();
+                self.update_status_with_missing(&format!("Fetched GraphQL schema ({count} types)"));
+            }
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_graphql_schema_type_toggled(&mut self, name: String) -> Task<Message> {
+        let expanded = &mut self.graphql_schemas.expanded;
+        *expanded = (expanded.as_ref() != Some(&name)).then_some(name);
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{GraphqlSchema, TypeRef};
+
+    fn named(kind: &str, name: &str) -> serde_json::Value {
+        json!({"kind": kind, "name": name, "ofType": null})
+    }
+
+    #[test]
+    fn parses_introspection_results() {
+        let body = json!({"data": {"__schema": {
+            "queryType": {"name": "Query"},
+            "mutationType": null,
+            "subscriptionType": null,
+            "types": [
+                {"kind": "OBJECT", "name": "Query", "description": null, "fields": [{
+                    "name": "users",
+                    "description": "All users",
+                    "isDeprecated": false,
+                    "deprecationReason": null,
+                    "args": [{"name": "first", "description": null, "defaultValue": "10",
+                              "type": named("SCALAR", "Int")}],
+                    "type": {"kind": "NON_NULL", "name": null, "ofType":
+                        {"kind": "LIST", "name": null, "ofType":
+                            {"kind": "NON_NULL", "name": null, "ofType": named("OBJECT", "User")}}}
+                }], "inputFields": null, "enumValues": null},
+                {"kind": "ENUM", "name": "Role", "description": "", "fields": null,
+                 "inputFields": null, "enumValues": [{"name": "ADMIN", "description": null}]},
+                {"kind": "OBJECT", "name": "__Type", "description": null, "fields": [],
+                 "inputFields": null, "enumValues": null}
+            ]
+        }}});
+        let schema = GraphqlSchema::from_response(body.to_string().as_bytes()).unwrap();
+
+        let operations = schema.operations();
+        assert_eq!(operations.len(), 1);
+        let (label, query) = operations[0];
+        assert_eq!(label, "Queries");
+        assert_eq!(
+            query.fields[0].signature(),
+            "users(first: Int = 10): [User!]!"
+        );
+        assert_eq!(
+            schema
+                .browsable_types()
+                .map(|ty| ty.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Role"]
+        );
+        assert_eq!(schema.get("Role").unwrap().enum_values, vec!["ADMIN"]);
+        assert_eq!(schema.get("Role").unwrap().description, None);
+    }
+
+    #[test]
+    fn validates_queries_against_the_schema() {
+        let query = |fields: serde_json::Value| {
+            json!({"kind": "OBJECT", "name": "Query", "description": null, "fields": fields,
+                   "inputFields": null, "enumValues": null})
+        };
+        let field = |name: &str, ty: serde_json::Value, args: serde_json::Value| {
+            json!({"name": name, "description": null, "isDeprecated": false,
+                   "deprecationReason": null, "args": args, "type": ty})
+        };
+        let non_null =
+            |inner: serde_json::Value| json!({"kind": "NON_NULL", "name": null, "ofType": inner});
+        let body = json!({"data": {"__schema": {
+            "queryType": {"name": "Query"}, "mutationType": null, "subscriptionType": null,
+            "types": [
+                query(json!([field("user", named("OBJECT", "User"), json!([
+                    {"name": "id", "description": null, "defaultValue": null,
+                     "type": non_null(named("SCALAR", "ID"))}
+                ]))])),
+                {"kind": "OBJECT", "name": "User", "description": null, "inputFields": null,
+                 "enumValues": null, "fields": [field("name", named("SCALAR", "String"), json!([]))]},
+                {"kind": "SCALAR", "name": "String", "description": null, "fields": null,
+                 "inputFields": null, "enumValues": null}
+            ]
+        }}});
+        let schema = GraphqlSchema::from_response(body.to_string().as_bytes()).unwrap();
+        let check = |source: &str| schema.validate(&crate::graphql::parse(source).unwrap());
+
+        assert!(
+            check("{ user(id: 1) { name __typename } __schema { types { name } } }").is_empty()
+        );
+        assert_eq!(
+            check("query {\n  user(first: 2) { name { x } age }\n}"),
+            vec![
+                "2:3: unknown argument \"first\" on field \"Query.user\"",
+                "2:3: field \"user\" requires argument \"id\" of type ID!",
+                "2:20: field \"name\" returns String and cannot have subfields",
+                "2:31: cannot query field \"age\" on type \"User\"",
+            ]
+        );
+        assert_eq!(
+            check("mutation M { x } query M { user(id: 1) { ...F } }"),
+            vec![
+                "1:1: the schema does not support mutation operations",
+                "1:18: there is more than one operation named \"M\"",
+                "1:42: unknown fragment \"F\"",
+            ]
+        );
+        assert_eq!(
+            check("{ user(id: 1) }"),
+            vec!["1:3: field \"user\" of type User needs a selection of subfields"]
+        );
+    }
+
+    #[test]
+    fn reports_graphql_errors() {
+        let body = json!({"errors": [{"message": "introspection is disabled"}]});
+        assert_eq!(
+            GraphqlSchema::from_response(body.to_string().as_bytes()),
+            Err("Introspection failed: introspection is disabled".to_string())
+        );
+        assert_eq!(
+            TypeRef::List(Box::new(TypeRef::Named("ID".to_string()))).to_string(),
+            "[ID]"
+        );
+    }
+}
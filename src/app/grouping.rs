@@ -0,0 +1,62 @@
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::model::{Environment, apply_environment};
+use crate::state::SidebarGrouping;
+
+/// Shown for requests whose URL has no host after substitution, e.g. because a
+/// variable is missing from the active environment.
+pub const NO_HOST: &str = "(no host)";
+
+/// `host[:port]` the request goes to with `env` applied.
+pub fn request_host(url: &str, env: Option<&Environment>) -> String {
+    let resolved = env.map_or_else(|| url.to_string(), |env| apply_environment(url, &env.vars));
+    reqwest::Url::parse(resolved.trim())
+        .ok()
+        .and_then(|url| {
+            let host = url.host_str()?.to_string();
+            Some(match url.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| NO_HOST.to_string())
+}
+
+impl Zagel {
+    pub(super) fn handle_sidebar_grouping_changed(
+        &mut self,
+        grouping: SidebarGrouping,
+    ) -> Task<Message> {
+        self.state.sidebar_grouping = grouping;
+        self.persist_state();
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{NO_HOST, request_host};
+    use crate::model::{Environment, EnvironmentScope};
+
+    #[test]
+    fn hosts_are_taken_after_substitution() {
+        let env = Environment {
+            name: "staging".to_string(),
+            vars: BTreeMap::from([("base".to_string(), "https://api.staging.io".to_string())]),
+            scope: EnvironmentScope::Default,
+        };
+        assert_eq!(request_host("{{base}}/users", Some(&env)), "api.staging.io");
+        assert_eq!(
+            request_host("http://localhost:8080/health", None),
+            "localhost:8080"
+        );
+        assert_eq!(
+            request_host("https://example.com:443/", None),
+            "example.com"
+        );
+        assert_eq!(request_host("{{base}}/users", None), NO_HOST);
+    }
+}
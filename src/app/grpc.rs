@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use iced::Task;
+use iced::widget::text_editor;
+
+use super::status::status_with_missing;
+use super::{Message, Zagel};
+use crate::model::HttpVersion;
+use crate::net::grpc::{self, GrpcCall};
+use crate::proto::{self, ProtoSchema};
+
+/// Builder state for gRPC mode. The URL field holds the server address, the
+/// headers are sent as metadata and the body editor holds the JSON message.
+#[derive(Debug, Clone, Default)]
+pub struct GrpcState {
+    /// Comma-separated `.proto` files; relative paths start at the project root.
+    pub proto_paths: String,
+    pub schema: Option<Arc<ProtoSchema>>,
+    pub service: Option<String>,
+    pub method: Option<String>,
+}
+
+impl GrpcState {
+    pub fn services(&self) -> Vec<String> {
+        self.schema
+            .iter()
+            .flat_map(|schema| schema.services.iter().map(|service| service.name.clone()))
+            .collect()
+    }
+
+    pub fn methods(&self) -> Vec<String> {
+        self.schema
+            .iter()
+            .flat_map(|schema| &schema.services)
+            .filter(|service| Some(&service.name) == self.service.as_ref())
+            .flat_map(|service| service.methods.iter().map(|method| method.name.clone()))
+            .collect()
+    }
+
+    fn select_service(&mut self, service: Option<String>) {
+        self.service = service;
+        self.method = self.methods().into_iter().next();
+    }
+
+    fn input_type(&self) -> Option<&str> {
+        let schema = self.schema.as_deref()?;
+        let method = schema.method(self.service.as_deref()?, self.method.as_deref()?)?;
+        Some(&method.input)
+    }
+}
+
+fn proto_paths(input: &str, root: Option<&Path>) -> Vec<PathBuf> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(|path| {
+            let path = PathBuf::from(path);
+            match root {
+                Some(root) if path.is_relative() => root.join(path),
+                _ => path,
+            }
+        })
+        .collect()
+}
+
+impl Zagel {
+    pub(super) fn handle_grpc_proto_paths_changed(&mut self, paths: String) -> Task<Message> {
+        self.grpc.proto_paths = paths;
+        Task::none()
+    }
+
+    pub(super) fn handle_load_protos(&mut self) -> Task<Message> {
+        let root = self.project_root_paths().into_iter().next();
+        let paths = proto_paths(&self.grpc.proto_paths, root.as_deref());
+        self.update_status_with_missing("Loading .proto files...");
+        Task::perform(
+            async move { proto::load(&paths).map(Arc::new) },
+            Message::ProtosLoaded,
+        )
+    }
+
+    pub(super) fn handle_protos_loaded(
+        &mut self,
+        result: Result<Arc<ProtoSchema>, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(schema) => {
+                let count = schema.services.len();
+                let first = schema.services.first().map(|service| service.name.clone());
+                self.grpc.schema = Some(schema);
+                self.grpc.select_service(first);
+                self.update_status_with_missing(&format!("Loaded {count} gRPC service(s)"));
+            }
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_grpc_service_selected(&mut self, service: String) -> Task<Message> {
+        self.grpc.select_service(Some(service));
+        Task::none()
+    }
+
+    pub(super) fn handle_grpc_method_selected(&mut self, method: String) -> Task<Message> {
+        self.grpc.method = Some(method);
+        Task::none()
+    }
+
+    /// Replaces the body with a JSON skeleton of the method's request message.
+    pub(super) fn handle_grpc_template(&mut self) -> Task<Message> {
+        let Some(template) = self
+            .grpc
+            .input_type()
+            .zip(self.grpc.schema.as_deref())
+            .map(|(input, schema)| schema.template(input))
+        else {
+            return Task::none();
+        };
+        let text = serde_json::to_string_pretty(&template).unwrap_or_default();
+        self.body_editor = text_editor::Content::with_text(&text);
+        self.draft.body = text;
+        self.update_status_with_missing("Ready");
+        Task::none()
+    }
+
+    pub(super) fn send_grpc(&mut self) -> Task<Message> {
+        let request_id = self.workspace.selection_cloned();
+        let (Some(schema), Some(service), Some(method)) = (
+            self.grpc.schema.clone(),
+            self.grpc.service.clone(),
+            self.grpc.method.clone(),
+        ) else {
+            return Task::done(Message::ResponseReady(
+                request_id,
+                Err("Load a .proto file and pick a method first".to_string()),
+            ));
+        };
+        let env = self.request_environment(request_id.as_ref());
+        let mut draft = self.draft.clone();
+        // gRPC only runs over HTTP/2; plain-text servers need prior knowledge.
+        draft.http_version = HttpVersion::Http2;
+        self.trace_context.apply(&mut draft);
+        draft.resolve_dynamic();
+        self.begin_history(request_id.clone(), &draft, env.as_ref());
+        self.arm_captures(request_id.clone(), &draft);
+        self.arm_assertions(request_id.clone(), &draft);
+        let client = match self.http_client(&draft) {
+            Ok(client) => client,
+            Err(err) => return Task::done(Message::ResponseReady(request_id, Err(err))),
+        };
+        self.status_line = status_with_missing("Calling...", &draft, env.as_ref(), &[]);
+        let auth = self.effective_auth(request_id.as_ref(), &draft.headers);
+        let call = GrpcCall {
+            base_url: draft.url,
+            service,
+            method,
+            message: draft.body,
+            metadata: draft.headers,
+        };
+        let send = Task::perform(grpc::call(client, schema, call, env, auth), move |result| {
+            Message::ResponseReady(request_id, result)
+        });
+        Task::batch([send, self.automation_pulse_task()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use super::{GrpcState, proto_paths};
+    use crate::proto::ProtoSchema;
+
+    #[test]
+    fn relative_proto_paths_start_at_the_project_root() {
+        assert_eq!(
+            proto_paths(
+                " api/greeter.proto, /abs/other.proto ,",
+                Some(Path::new("/proj"))
+            ),
+            vec![
+                PathBuf::from("/proj/api/greeter.proto"),
+                PathBuf::from("/abs/other.proto")
+            ]
+        );
+        assert!(proto_paths("", None).is_empty());
+    }
+
+    #[test]
+    fn picking_a_service_selects_its_first_method() {
+        let mut schema = ProtoSchema::default();
+        schema
+            .add_source(
+                "package a; service One { rpc Get (M) returns (M); rpc Put (M) returns (M); }
+                 service Two { rpc List (M) returns (M); } message M {}",
+            )
+            .unwrap();
+        let mut state = GrpcState {
+            schema: Some(Arc::new(schema)),
+            ..GrpcState::default()
+        };
+        assert_eq!(state.services(), vec!["a.One", "a.Two"]);
+        state.select_service(Some("a.Two".to_string()));
+        assert_eq!(state.method.as_deref(), Some("List"));
+        state.select_service(Some("a.One".to_string()));
+        assert_eq!(state.methods(), vec!["Get", "Put"]);
+        assert_eq!(state.method.as_deref(), Some("Get"));
+    }
+}
@@ -1,9 +1,14 @@
+use std::collections::BTreeSet;
+
 use iced::widget::{button, column, container, row, text_input};
 use iced::{Element, Length};
 
+use super::placeholders::{field_highlight, input_style};
 use super::{HeaderRow, Message};
 
-pub fn editor(rows: &[HeaderRow]) -> Element<'_, Message> {
+/// Header rows; values with `{{placeholders}}` get a border showing whether
+/// `defined` resolves them.
+pub fn editor<'a>(rows: &'a [HeaderRow], defined: &BTreeSet<String>) -> Element<'a, Message> {
     let mut list = column![];
     for (idx, row_data) in rows.iter().enumerate() {
         let idx_name = idx;
@@ -21,6 +26,7 @@ pub fn editor(rows: &[HeaderRow]) -> Element<'_, Message> {
                 name_input,
                 text_input("Value", &row_data.value)
                     .on_input(move |val| Message::HeaderValueChanged(idx_value, val))
+                    .style(input_style(field_highlight(&row_data.value, defined)))
                     .padding(6)
                     .width(Length::FillPortion(5)),
                 button("✕").on_press(Message::HeaderRemoved(idx)),
@@ -31,3 +37,20 @@ pub fn editor(rows: &[HeaderRow]) -> Element<'_, Message> {
     list = list.push(button("Add header").on_press(Message::HeaderAdded));
     list.spacing(6).into()
 }
+
+/// Whether the `Name: value` lines in `headers` set `name` (case-insensitive).
+pub(super) fn has_header(headers: &str, name: &str) -> bool {
+    headers.lines().any(|line| {
+        line.split_once(':')
+            .is_some_and(|(existing, _)| existing.trim().eq_ignore_ascii_case(name))
+    })
+}
+
+pub(super) fn push_header(headers: &mut String, name: &str, value: &str) {
+    if !headers.is_empty() && !headers.ends_with('\n') {
+        headers.push('\n');
+    }
+    headers.push_str(name);
+    headers.push_str(": ");
+    headers.push_str(value);
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use iced::Task;
+
+use super::assertions::AssertionOutcome;
+use super::pager::floor_char_boundary;
+use super::{Message, Zagel};
+use crate::history::{FailureSnapshot, History, HistoryEntry, MASK, SentRequest, masked_header};
+use crate::model::{
+    Environment, Method, RequestDraft, RequestId, RequestRef, ResponsePreview, apply_environment,
+};
+
+/// Persistent send history plus the sends still waiting for a response.
+#[derive(Debug, Default)]
+pub struct SendHistory {
+    pub store: History,
+    in_flight: HashMap<Option<RequestId>, HistoryEntry>,
+    /// Limits the sidebar to requests whose latest send failed.
+    pub failed_only: bool,
+    /// Latest send that left a failure snapshot, linked from the status bar.
+    pub last_failure: Option<HistoryEntry>,
+    pub show_snapshot: bool,
+    /// Whether the panel listing recent sends is open.
+    pub show_recent: bool,
+}
+
+impl SendHistory {
+    pub fn load() -> Self {
+        Self {
+            store: History::load(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether the sidebar should list `id` under the current filter.
+    pub fn shows(&self, request: &RequestRef) -> bool {
+        !self.failed_only || self.store.last_failed(&request.to_string())
+    }
+}
+
+/// Unsaved draft with the literal values of a past send. Masked headers
+/// from earlier sessions are left out and returned by name.
+fn replay_draft(entry: &HistoryEntry) -> (RequestDraft, Vec<String>) {
+    let sent = entry.sent.clone().unwrap_or_default();
+    let mut masked = Vec::new();
+    let headers = sent
+        .headers
+        .lines()
+        .filter(|line| match line.split_once(':') {
+            Some((name, value)) if value.trim() == MASK => {
+                masked.push(name.trim().to_string());
+                false
+            }
+            _ => true,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let draft = RequestDraft {
+        title: format!("Replay of {} {}", entry.method, entry.url),
+        method: Method::from(entry.method.as_str()),
+        url: entry.url.clone(),
+        headers,
+        body: sent.body,
+        ..RequestDraft::default()
+    };
+    (draft, masked)
+}
+
+/// Entry for a send that is about to go out; the outcome is filled in later.
+/// The snapshot is dropped again unless the send fails.
+fn pending_entry(
+    request: Option<&RequestRef>,
+    draft: &RequestDraft,
+    env: Option<&Environment>,
+    now: SystemTime,
+) -> HistoryEntry {
+    let resolve = |input: &str| {
+        env.map_or_else(
+            || input.to_string(),
+            |env| apply_environment(input, &env.vars),
+        )
+    };
+    let sent = SentRequest {
+        headers: resolve(&draft.headers),
+        body: resolve(&draft.body),
+        truncated: false,
+    };
+    let mut body = sent.body.clone();
+    body.truncate(floor_char_boundary(
+        &body,
+        body.len().min(FailureSnapshot::MAX_BODY_BYTES),
+    ));
+    HistoryEntry {
+        request: request.map(RequestRef::to_string),
+        sent_at: now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs()),
+        method: draft.method.as_str().to_string(),
+        url: resolve(&draft.url),
+        environment: env.map(|env| env.name.clone()),
+        status: None,
+        error: None,
+        duration_ms: None,
+        snapshot: Some(FailureSnapshot {
+            headers: sent
+                .headers
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(masked_header)
+                .collect(),
+            body,
+            assertion_failures: Vec::new(),
+        }),
+        sent: Some(sent),
+    }
+}
+
+impl Zagel {
+    pub(super) fn begin_history(
+        &mut self,
+        request_id: Option<RequestId>,
+        draft: &RequestDraft,
+        env: Option<&Environment>,
+    ) {
+        let request = request_id.as_ref().map(|id| self.request_ref(id));
+        let entry = pending_entry(request.as_ref(), draft, env, SystemTime::now());
+        self.history.in_flight.insert(request_id, entry);
+    }
+
+    /// Records the outcome; transport errors and failed assertions keep the
+    /// resolved request as a snapshot and return `true`.
+    pub(super) fn record_history(
+        &mut self,
+        request_id: Option<&RequestId>,
+        preview: &ResponsePreview,
+        assertions: &[AssertionOutcome],
+    ) -> bool {
+        let Some(mut entry) = self.history.in_flight.remove(&request_id.cloned()) else {
+            return false;
+        };
+        entry.status = preview.status;
+        entry.error.clone_from(&preview.error);
+        entry.duration_ms = preview
+            .duration
+            .and_then(|duration| u64::try_from(duration.as_millis()).ok());
+        let assertion_failures = assertions
+            .iter()
+            .filter_map(|outcome| Some(format!("{}: {}", outcome.label, outcome.failure.as_ref()?)))
+            .collect::<Vec<_>>();
+        let snapshotted = preview.error.is_some() || !assertion_failures.is_empty();
+        if snapshotted {
+            if let Some(snapshot) = entry.snapshot.as_mut() {
+                snapshot.assertion_failures = assertion_failures;
+            }
+            self.history.last_failure = Some(entry.clone());
+        } else {
+            entry.snapshot = None;
+        }
+        self.history.store.record(entry);
+        snapshotted
+    }
+
+    pub(super) fn handle_failure_snapshot_toggled(&mut self) -> Task<Message> {
+        self.history.show_snapshot =
+            !self.history.show_snapshot && self.history.last_failure.is_some();
+        Task::none()
+    }
+
+    pub(super) fn handle_failure_snapshot_dismissed(&mut self) -> Task<Message> {
+        self.history.show_snapshot = false;
+        self.history.last_failure = None;
+        Task::none()
+    }
+
+    pub(super) fn handle_recent_sends_toggled(&mut self) -> Task<Message> {
+        self.history.show_recent = !self.history.show_recent;
+        Task::none()
+    }
+
+    /// Opens a past send as an unsaved request, independent of the
+    /// environment it was resolved with.
+    pub(super) fn handle_history_entry_replayed(&mut self, entry: &HistoryEntry) -> Task<Message> {
+        if entry.sent.is_none() {
+            self.update_status_with_missing("This send was recorded without its headers and body");
+            return Task::none();
+        }
+        let (draft, masked) = replay_draft(entry);
+        self.open_unsaved_draft(draft);
+        self.history.show_recent = false;
+        self.history.show_snapshot = false;
+        let mut status = "Loaded the request as sent; edit it and press Send".to_string();
+        if entry.sent.as_ref().is_some_and(|sent| sent.truncated) {
+            status.push_str(". The body was cut when it was saved to history");
+        }
+        if !masked.is_empty() {
+            status = format!(
+                "{status}. Masked headers were left out: {}",
+                masked.join(", ")
+            );
+        }
+        self.update_status_with_missing(&status);
+        Task::none()
+    }
+
+    pub(super) fn handle_failed_filter_toggled(&mut self, failed_only: bool) -> Task<Message> {
+        self.history.failed_only = failed_only;
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use super::{pending_entry, replay_draft};
+    use crate::history::{FailureSnapshot, HistoryEntry, SentRequest};
+    use crate::model::{Environment, EnvironmentScope, Method, RequestDraft, RequestRef};
+
+    #[test]
+    fn entries_record_the_request_after_substitution() {
+        let env = Environment {
+            name: "staging".to_string(),
+            vars: BTreeMap::from([("base".to_string(), "https://api.staging.io".to_string())]),
+            scope: EnvironmentScope::Default,
+        };
+        let draft = RequestDraft {
+            url: "{{base}}/users".to_string(),
+            headers: "Authorization: Bearer {{base}}\nAccept: */*".to_string(),
+            body: "x".repeat(FailureSnapshot::MAX_BODY_BYTES + 1),
+            ..RequestDraft::default()
+        };
+        let request = RequestRef::new(&PathBuf::from("api.http"), 2, &draft);
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let entry = pending_entry(Some(&request), &draft, Some(&env), now);
+        assert_eq!(entry.request.as_deref(), Some("api.http#2"));
+        assert_eq!(entry.url, "https://api.staging.io/users");
+        assert_eq!(entry.environment.as_deref(), Some("staging"));
+        assert_eq!(entry.sent_at, 1_000);
+        assert!(entry.failed());
+        let snapshot = entry.snapshot.unwrap();
+        assert_eq!(
+            snapshot.headers,
+            vec!["Authorization: ••••••", "Accept: */*"]
+        );
+        assert_eq!(snapshot.body.len(), FailureSnapshot::MAX_BODY_BYTES);
+        assert_eq!(
+            entry.sent.unwrap().headers,
+            "Authorization: Bearer https://api.staging.io\nAccept: */*"
+        );
+    }
+
+    #[test]
+    fn replays_use_literal_values_and_drop_masked_headers() {
+        let entry = HistoryEntry {
+            request: None,
+            sent_at: 1_000,
+            method: "POST".to_string(),
+            url: "https://api.staging.io/users".to_string(),
+            environment: Some("staging".to_string()),
+            status: Some(201),
+            error: None,
+            duration_ms: Some(5),
+            snapshot: None,
+            sent: Some(SentRequest {
+                headers: "Authorization: ••••••\nContent-Type: application/json".to_string(),
+                body: "{\"name\":\"zagel\"}".to_string(),
+                truncated: false,
+            }),
+        };
+        let (draft, masked) = replay_draft(&entry);
+        assert_eq!(draft.method, Method::Post);
+        assert_eq!(draft.url, "https://api.staging.io/users");
+        assert_eq!(draft.headers, "Content-Type: application/json");
+        assert_eq!(draft.body, "{\"name\":\"zagel\"}");
+        assert_eq!(masked, ["Authorization"]);
+    }
+}
@@ -1,4 +1,4 @@
-use iced::{keyboard, Subscription};
+use iced::{Subscription, keyboard};
 
 use super::messages::Message;
 
@@ -8,14 +8,24 @@ pub fn subscription() -> Subscription<Message> {
             keyboard::Key::Character(c) if c == "?" || (c == "/" && modifiers.shift()) => {
                 Some(Message::ToggleShortcutsHelp)
             }
+            keyboard::Key::Character(c)
+                if c.eq_ignore_ascii_case("v") && modifiers.command() && modifiers.shift() =>
+            {
+                Some(Message::ImportCurl)
+            }
             keyboard::Key::Character(c) if c.eq_ignore_ascii_case("s") && modifiers.command() => {
                 Some(Message::Save)
             }
+            keyboard::Key::Character(c) if c.eq_ignore_ascii_case("a") && modifiers.command() => {
+                Some(Message::SelectAllVisible)
+            }
             keyboard::Key::Named(keyboard::key::Named::Enter) if modifiers.command() => {
                 Some(Message::Send)
             }
+            keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Message::EscapePressed),
             _ => None,
         },
-        _ => None,
+        keyboard::Event::ModifiersChanged(modifiers) => Some(Message::ModifiersChanged(modifiers)),
+        keyboard::Event::KeyReleased { .. } => None,
     })
 }
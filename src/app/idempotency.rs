@@ -0,0 +1,101 @@
+use iced::{Task, clipboard};
+use uuid::Uuid;
+
+use super::headers::{has_header, push_header};
+use super::{Message, Zagel};
+use crate::model::RequestDraft;
+
+pub const HEADER_NAME: &str = "Idempotency-Key";
+
+/// Session-wide `Idempotency-Key` injection for sends.
+#[derive(Debug, Clone, Default)]
+pub struct IdempotencyKey {
+    pub enabled: bool,
+    /// Resend `last` instead of generating a new key, e.g. to exercise retries.
+    pub reuse_last: bool,
+    pub last: Option<String>,
+}
+
+impl IdempotencyKey {
+    /// Adds the header to `draft` unless it already sets one; returns the key used.
+    pub fn apply(&mut self, draft: &mut RequestDraft) -> Option<String> {
+        if !self.enabled || has_header(&draft.headers, HEADER_NAME) {
+            return None;
+        }
+        let key = match (&self.last, self.reuse_last) {
+            (Some(last), true) => last.clone(),
+            _ => Uuid::new_v4().to_string(),
+        };
+        push_header(&mut draft.headers, HEADER_NAME, &key);
+        self.last = Some(key.clone());
+        Some(key)
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_idempotency_toggled(&mut self, enabled: bool) -> Task<Message> {
+        self.idempotency.enabled = enabled;
+        Task::none()
+    }
+
+    pub(super) fn handle_idempotency_reuse_toggled(&mut self, reuse: bool) -> Task<Message> {
+        self.idempotency.reuse_last = reuse;
+        Task::none()
+    }
+
+    pub(super) fn handle_copy_idempotency_key(&self) -> Task<Message> {
+        self.idempotency
+            .last
+            .clone()
+            .map_or_else(Task::none, |key| {
+                clipboard::write(key).map(|()| Message::CopyComplete)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdempotencyKey;
+    use crate::model::RequestDraft;
+
+    fn draft(headers: &str) -> RequestDraft {
+        RequestDraft {
+            headers: headers.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fresh_key_per_send_unless_reused() {
+        let mut idempotency = IdempotencyKey {
+            enabled: true,
+            ..IdempotencyKey::default()
+        };
+        let mut first = draft("Accept: application/json");
+        let key = idempotency.apply(&mut first).expect("key injected");
+        assert_eq!(
+            first.headers,
+            format!("Accept: application/json\nIdempotency-Key: {key}")
+        );
+
+        let second = idempotency.apply(&mut draft("")).expect("key injected");
+        assert_ne!(second, key);
+
+        idempotency.reuse_last = true;
+        assert_eq!(idempotency.apply(&mut draft("")), Some(second));
+    }
+
+    #[test]
+    fn explicit_header_or_disabled_toggle_is_left_alone() {
+        let mut idempotency = IdempotencyKey {
+            enabled: true,
+            ..IdempotencyKey::default()
+        };
+        let mut explicit = draft("idempotency-key: mine");
+        assert_eq!(idempotency.apply(&mut explicit), None);
+        assert_eq!(explicit.headers, "idempotency-key: mine");
+
+        idempotency.enabled = false;
+        assert_eq!(idempotency.apply(&mut draft("")), None);
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use iced::Task;
+
+use crate::curl::parse_curl;
+use crate::import::{ImportedWorkspace, parse_bruno, parse_insomnia};
+use crate::model::RequestDraft;
+use crate::parser::{slugify, suggest_http_path, write_http_file};
+
+use super::options::RequestMode;
+use super::{Message, Zagel};
+
+/// Export file or collection folder picked in the Import section of the
+/// settings panel.
+#[derive(Debug, Clone, Default)]
+pub struct ImportForm {
+    pub path: String,
+}
+
+impl Zagel {
+    pub(super) fn handle_import_path_changed(&mut self, path: String) -> Task<Message> {
+        self.import_form.path = path;
+        Task::none()
+    }
+
+    pub(super) fn handle_import_insomnia(&mut self) -> Task<Message> {
+        let path = self.import_form.path.trim().to_string();
+        let imported = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Cannot read {path}: {err}"))
+            .and_then(|raw| parse_insomnia(&raw));
+        match imported {
+            Ok(imported) => self.write_imported_workspace(&imported),
+            Err(err) => {
+                self.update_status_with_missing(&format!("Import failed: {err}"));
+                Task::none()
+            }
+        }
+    }
+
+    pub(super) fn handle_import_bruno(&mut self) -> Task<Message> {
+        let path = self.import_form.path.trim().to_string();
+        match parse_bruno(Path::new(&path)) {
+            Ok(imported) => self.write_imported_workspace(&imported),
+            Err(err) => {
+                self.update_status_with_missing(&format!("Import failed: {err}"));
+                Task::none()
+            }
+        }
+    }
+
+    /// Writes an imported workspace into its own folder in the default
+    /// project folder and reports what could not be carried over.
+    fn write_imported_workspace(&mut self, imported: &ImportedWorkspace) -> Task<Message> {
+        let Some(root) = self.default_project_root() else {
+            self.update_status_with_missing("Add a project folder before importing");
+            return Task::none();
+        };
+        let dir = root.as_path().join(slugify(&imported.name));
+        if let Err(err) = imported.write_to(&dir) {
+            self.update_status_with_missing(&format!("Import failed: {err}"));
+            return Task::none();
+        }
+        let mut status = format!(
+            "Imported {} requests and {} environments into {}",
+            imported.request_count(),
+            imported.environments.len(),
+            dir.display()
+        );
+        if !imported.warnings.is_empty() {
+            status.push_str("; ");
+            status.push_str(&imported.warnings.join("; "));
+        }
+        self.update_status_with_missing(&status);
+        self.rescan_files()
+    }
+
+    pub(super) fn handle_curl_pasted(&mut self, contents: Option<String>) -> Task<Message> {
+        let Some(contents) = contents.filter(|contents| !contents.trim().is_empty()) else {
+            self.update_status_with_missing("The clipboard is empty; copy a curl command first");
+            return Task::none();
+        };
+        match parse_curl(&contents) {
+            Ok(draft) => {
+                self.open_unsaved_draft(draft);
+                self.update_status_with_missing("Imported curl command; save it to keep it");
+            }
+            Err(err) => self.update_status_with_missing(&format!("Cannot import curl: {err}")),
+        }
+        Task::none()
+    }
+
+    /// Shows a request that is not in any file yet in the builder; saving
+    /// writes it to the save path like any new request.
+    pub(super) fn open_unsaved_draft(&mut self, draft: RequestDraft) {
+        self.workspace.clear_selection();
+        self.body_editor = iced::widget::text_editor::Content::with_text(&draft.body);
+        self.notes.load(&draft.notes);
+        self.draft = draft;
+        self.mode = RequestMode::Rest;
+        self.set_header_rows_from_draft();
+        self.response = None;
+        self.json_tree = super::json_tree::JsonTree::default();
+        self.update_response_viewer();
+    }
+
+    /// Saves imported requests as a new `<name>.http` in the default project
+    /// folder; an existing file is never overwritten.
+    pub(super) fn write_imported(&mut self, name: &str, drafts: &[RequestDraft]) -> Task<Message> {
+        let Some(root) = self.default_project_root() else {
+            self.update_status_with_missing("Add a project folder before importing");
+            return Task::none();
+        };
+        let path = suggest_http_path(root.as_path(), name);
+        if drafts.is_empty() {
+            self.update_status_with_missing("Nothing to import");
+            return Task::none();
+        }
+        if path.exists() {
+            self.update_status_with_missing(&format!("{} already exists", path.display()));
+            return Task::none();
+        }
+        if let Err(err) = write_http_file(&path, drafts) {
+            self.update_status_with_missing(&format!("Failed to write {}: {err}", path.display()));
+            return Task::none();
+        }
+        self.update_status_with_missing(&format!(
+            "Imported {} requests into {}",
+            drafts.len(),
+            path.display()
+        ));
+        self.rescan_files()
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+
+use iced::{Task, clipboard};
+use serde_json::Value;
+
+use super::view::ResponseDisplay;
+use super::{Message, Zagel};
+use crate::jsonpath;
+
+/// Rows past this are cut off; expand fewer nodes or use the filter instead.
+pub const MAX_TREE_ROWS: usize = 2_000;
+
+/// Expansion state of the response tree view, keyed by JSON pointer.
+#[derive(Debug, Clone)]
+pub struct JsonTree {
+    pub root: Option<Value>,
+    expanded: HashSet<String>,
+}
+
+impl Default for JsonTree {
+    fn default() -> Self {
+        Self {
+            root: None,
+            expanded: HashSet::from([String::new()]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeNode {
+    Container {
+        open: bool,
+        is_array: bool,
+        len: usize,
+    },
+    Leaf(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeRow {
+    pub depth: usize,
+    pub label: String,
+    /// RFC 6901 pointer, used to look the node up again.
+    pub pointer: String,
+    /// JSON path expression, accepted by the response filter.
+    pub path: String,
+    pub node: TreeNode,
+}
+
+impl JsonTree {
+    pub fn toggle(&mut self, pointer: &str) {
+        if !self.expanded.remove(pointer) {
+            self.expanded.insert(pointer.to_string());
+        }
+    }
+
+    /// Visible rows in document order; children of collapsed nodes are skipped.
+    pub fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        if let Some(root) = &self.root {
+            self.push_rows(root, 0, "$".to_string(), "", "$", &mut rows);
+        }
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        value: &Value,
+        depth: usize,
+        label: String,
+        pointer: &str,
+        path: &str,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        if rows.len() >= MAX_TREE_ROWS {
+            return;
+        }
+        let open = self.expanded.contains(pointer);
+        let node = match value {
+            Value::Array(items) => TreeNode::Container {
+                open,
+                is_array: true,
+                len: items.len(),
+            },
+            Value::Object(map) => TreeNode::Container {
+                open,
+                is_array: false,
+                len: map.len(),
+            },
+            other => TreeNode::Leaf(other.to_string()),
+        };
+        rows.push(TreeRow {
+            depth,
+            label,
+            pointer: pointer.to_string(),
+            path: path.to_string(),
+            node,
+        });
+        if !open {
+            return;
+        }
+        match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.push_rows(
+                        item,
+                        depth + 1,
+                        format!("[{index}]"),
+                        &format!("{pointer}/{index}"),
+                        &format!("{path}[{index}]"),
+                        rows,
+                    );
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map {
+                    self.push_rows(
+                        item,
+                        depth + 1,
+                        key.clone(),
+                        &format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1")),
+                        &format!("{path}{}", path_segment(key)),
+                        rows,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn path_segment(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if is_identifier {
+        format!(".{key}")
+    } else {
+        format!("['{key}']")
+    }
+}
+
+impl Zagel {
+    /// Rebuilds the tree root from the current (filtered) body when the Tree view is active.
+    pub(super) fn refresh_json_tree(&mut self) {
+        self.json_tree.root = None;
+        if self.response_display != ResponseDisplay::Tree {
+            return;
+        }
+        let Some(response) = self
+            .response
+            .as_ref()
+            .filter(|response| response.body.summary().is_none())
+        else {
+            return;
+        };
+        let Ok(value) = serde_json::from_str::<Value>(response.body.raw()) else {
+            return;
+        };
+        let expr = self.response_filter.trim();
+        self.json_tree.root = if expr.is_empty() {
+            Some(value)
+        } else {
+            jsonpath::apply(&value, expr).ok()
+        };
+    }
+
+    pub(super) fn handle_json_tree_toggled(&mut self, pointer: &str) -> Task<Message> {
+        self.json_tree.toggle(pointer);
+        Task::none()
+    }
+
+    pub(super) fn handle_json_tree_copy_value(&self, pointer: &str) -> Task<Message> {
+        let Some(value) = self
+            .json_tree
+            .root
+            .as_ref()
+            .and_then(|root| root.pointer(pointer))
+        else {
+            return Task::none();
+        };
+        let text = match value {
+            Value::String(text) => text.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        clipboard::write(text).map(|()| Message::CopyComplete)
+    }
+
+    pub(super) fn handle_json_tree_copy_path(path: String) -> Task<Message> {
+        clipboard::write(path).map(|()| Message::CopyComplete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{JsonTree, TreeNode};
+
+    #[test]
+    fn collapsed_nodes_hide_children_and_show_counts() {
+        let mut tree = JsonTree {
+            root: Some(json!({"items": [1, 2], "odd key": {"a": true}})),
+            ..JsonTree::default()
+        };
+
+        let rows = tree.rows();
+        assert_eq!(
+            rows.iter().map(|row| row.path.as_str()).collect::<Vec<_>>(),
+            vec!["$", "$.items", "$['odd key']"]
+        );
+        assert_eq!(
+            rows[1].node,
+            TreeNode::Container {
+                open: false,
+                is_array: true,
+                len: 2,
+            }
+        );
+
+        tree.toggle("/items");
+        let rows = tree.rows();
+        assert_eq!(rows[2].path, "$.items[0]");
+        assert_eq!(rows[2].pointer, "/items/0");
+        assert_eq!(rows[2].node, TreeNode::Leaf("1".to_string()));
+    }
+
+    #[test]
+    fn pointers_escape_slashes_and_tildes() {
+        let mut tree = JsonTree {
+            root: Some(json!({"a/b~c": {"x": 1}})),
+            ..JsonTree::default()
+        };
+        tree.toggle("/a~1b~0c");
+        let rows = tree.rows();
+        assert_eq!(rows[1].pointer, "/a~1b~0c");
+        assert_eq!(rows[2].path, "$['a/b~c'].x");
+        assert_eq!(
+            tree.root
+                .as_ref()
+                .and_then(|root| root.pointer(&rows[2].pointer)),
+            Some(&json!(1))
+        );
+    }
+}
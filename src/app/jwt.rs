@@ -0,0 +1,286 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{Engine, engine::general_purpose};
+use iced::{Subscription, Task};
+use serde_json::{Map, Value};
+use time::OffsetDateTime;
+
+use super::options::apply_auth_headers;
+use super::{Message, Zagel};
+use crate::model::apply_environment;
+
+/// Where a token was found, for the inspector's picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JwtSource {
+    pub label: String,
+    pub token: String,
+}
+
+impl std::fmt::Display for JwtSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedJwt {
+    /// Pretty-printed JSON of the header and payload.
+    pub header: String,
+    pub payload: String,
+    pub expires_at: Option<i64>,
+    pub issued_at: Option<i64>,
+    pub not_before: Option<i64>,
+}
+
+/// The header and payload of a `header.payload.signature` token. The
+/// signature is not checked.
+pub fn decode(token: &str) -> Result<DecodedJwt, String> {
+    let mut parts = token.trim().split('.');
+    let (Some(header), Some(payload), Some(_), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err("a JWT has three dot-separated parts".to_string());
+    };
+    let header = decode_part("header", header)?;
+    let payload = decode_part("payload", payload)?;
+    let claim = |name: &str| payload.get(name).and_then(Value::as_i64);
+    let pretty =
+        |object: &Map<String, Value>| serde_json::to_string_pretty(object).unwrap_or_default();
+    Ok(DecodedJwt {
+        expires_at: claim("exp"),
+        issued_at: claim("iat"),
+        not_before: claim("nbf"),
+        header: pretty(&header),
+        payload: pretty(&payload),
+    })
+}
+
+fn decode_part(name: &str, part: &str) -> Result<Map<String, Value>, String> {
+    let bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(part.trim_end_matches('='))
+        .map_err(|err| format!("{name} is not base64url: {err}"))?;
+    match serde_json::from_slice(&bytes) {
+        Ok(Value::Object(object)) => Ok(object),
+        _ => Err(format!("{name} is not a JSON object")),
+    }
+}
+
+/// Whether `value` decodes as a JWT with an `alg` header; the `eyJ` prefix
+/// (`{"` in base64url) rules out most strings before decoding.
+fn looks_like_jwt(value: &str) -> bool {
+    value.starts_with("eyJ")
+        && value.matches('.').count() == 2
+        && decode(value).is_ok_and(|jwt| jwt.header.contains("\"alg\""))
+}
+
+/// JWTs in `Authorization: Bearer` headers and in string fields of a JSON body.
+fn find_tokens(
+    request_headers: &str,
+    response_headers: &[(String, String)],
+    body: &str,
+) -> Vec<JwtSource> {
+    let mut found = Vec::new();
+    let mut push = |label: String, token: &str| {
+        let token = token.trim();
+        if looks_like_jwt(token) && !found.iter().any(|source: &JwtSource| source.token == token) {
+            found.push(JwtSource {
+                label,
+                token: token.to_string(),
+            });
+        }
+    };
+    for line in request_headers.lines() {
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            push(
+                format!("Request {}", name.trim()),
+                value.strip_prefix("Bearer ").unwrap_or(value),
+            );
+        }
+    }
+    for (name, value) in response_headers {
+        push(
+            format!("Response {name}"),
+            value.strip_prefix("Bearer ").unwrap_or(value),
+        );
+    }
+    if let Ok(json) = serde_json::from_str::<Value>(body) {
+        let mut strings = Vec::new();
+        collect_strings(&json, "$".to_string(), &mut strings);
+        for (path, value) in strings {
+            push(format!("Response {path}"), &value);
+        }
+    }
+    found
+}
+
+fn collect_strings(value: &Value, path: String, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(text) => out.push((path, text.clone())),
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_strings(item, format!("{path}[{index}]"), out);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                collect_strings(field, format!("{path}.{key}"), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `expires in 1h 2m 3s` or `expired 5s ago`.
+pub fn expiry_text(expires_at: i64, now: i64) -> String {
+    let left = expires_at - now;
+    let span = format_span(left.unsigned_abs());
+    if left > 0 {
+        format!("expires in {span}")
+    } else {
+        format!("expired {span} ago")
+    }
+}
+
+fn format_span(seconds: u64) -> String {
+    let (days, hours, minutes, seconds) = (
+        seconds / 86_400,
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+    );
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+/// `2026-01-31 09:05:00 UTC`, or the raw number when out of range.
+pub fn format_timestamp(timestamp: i64) -> String {
+    OffsetDateTime::from_unix_timestamp(timestamp).map_or_else(
+        |_| timestamp.to_string(),
+        |at| {
+            format!(
+                "{}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+                at.year(),
+                u8::from(at.month()),
+                at.day(),
+                at.hour(),
+                at.minute(),
+                at.second()
+            )
+        },
+    )
+}
+
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// The open JWT inspector.
+#[derive(Debug, Clone)]
+pub struct JwtInspector {
+    pub sources: Vec<JwtSource>,
+    pub selected: Option<JwtSource>,
+    /// Unix time of the last tick, for the expiry countdown.
+    pub now: i64,
+}
+
+impl Zagel {
+    /// JWTs in the request's resolved headers and auth and in the last response.
+    fn jwt_sources(&self) -> Vec<JwtSource> {
+        let env = self.request_environment(self.workspace.selection());
+        let headers = apply_auth_headers(&self.draft.headers, &self.auth);
+        let headers = env.map_or_else(
+            || headers.clone(),
+            |env| apply_environment(&headers, &env.vars),
+        );
+        let (response_headers, body) = self.response.as_ref().map_or((&[][..], ""), |response| {
+            (response.preview.headers.as_slice(), response.body.raw())
+        });
+        find_tokens(&headers, response_headers, body)
+    }
+
+    /// Ticks the expiry countdown while the inspector is open.
+    pub(super) fn jwt_subscription(&self) -> Option<Subscription<Message>> {
+        self.jwt_inspector
+            .is_some()
+            .then(|| iced::time::every(Duration::from_secs(1)).map(|_| Message::JwtTick))
+    }
+
+    pub(super) fn handle_jwt_inspector_toggled(&mut self) -> Task<Message> {
+        if self.jwt_inspector.take().is_some() {
+            return Task::none();
+        }
+        let sources = self.jwt_sources();
+        if sources.is_empty() {
+            self.update_status_with_missing("No JWT in the request headers or the response");
+            return Task::none();
+        }
+        self.jwt_inspector = Some(JwtInspector {
+            selected: sources.first().cloned(),
+            sources,
+            now: unix_now(),
+        });
+        Task::none()
+    }
+
+    pub(super) fn handle_jwt_source_selected(&mut self, source: JwtSource) -> Task<Message> {
+        if let Some(inspector) = self.jwt_inspector.as_mut() {
+            inspector.selected = Some(source);
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_jwt_tick(&mut self) -> Task<Message> {
+        if let Some(inspector) = self.jwt_inspector.as_mut() {
+            inspector.now = unix_now();
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, expiry_text, find_tokens, format_timestamp};
+
+    // {"alg":"HS256","typ":"JWT"}.{"sub":"42","exp":1700000000}
+    const TOKEN: &str =
+        "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiI0MiIsImV4cCI6MTcwMDAwMDAwMH0.c2ln";
+
+    #[test]
+    fn tokens_are_found_in_headers_and_json_bodies_and_decoded() {
+        let body = format!("{{\"data\": {{\"tokens\": [\"nope\", \"{TOKEN}\"]}}}}");
+        let sources = find_tokens(
+            &format!("Accept: */*\nAuthorization: Bearer {TOKEN}"),
+            &[],
+            &body,
+        );
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].label, "Request Authorization");
+
+        let sources = find_tokens("", &[], &body);
+        assert_eq!(sources[0].label, "Response $.data.tokens[1]");
+
+        let jwt = decode(TOKEN).unwrap();
+        assert_eq!(jwt.expires_at, Some(1_700_000_000));
+        assert!(jwt.payload.contains("\"sub\": \"42\""));
+        assert!(decode("a.b").is_err());
+    }
+
+    #[test]
+    fn expiry_counts_down_and_up() {
+        assert_eq!(expiry_text(1_000 + 3_723, 1_000), "expires in 1h 2m 3s");
+        assert_eq!(expiry_text(1_000, 1_005), "expired 5s ago");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20 UTC");
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
@@ -7,22 +7,23 @@ use iced::{Subscription, Task, Theme, application};
 use reqwest::Client;
 
 use crate::launch::LaunchOptions;
-use crate::model::{RequestDraft, RequestId};
-use crate::parser::{scan_env_files, scan_http_files};
+use crate::model::{Environment, RequestDraft, RequestId, RequestRef};
+use crate::net::{ClientConfig, build_client};
+use crate::parser::{FILE_SCAN_MAX_DEPTH, scan_env_files, scan_http_files};
 use crate::pathing::{GlobalEnvRoot, ProjectRoot, SaveFilePath};
 use crate::state::AppState;
 
 use super::automation::AutomationRuntime;
+use super::benchmark::BenchmarkSession;
 use super::domain::{
     AddRequestPlan, AddRequestPlanError, ProjectConfiguration, SavePlan, SavePlanError, SaveTarget,
     WorkspaceState,
 };
+use super::missing_roots::{MissingRoot, MissingRoots, RootKind, with_missing};
 use super::options::{AuthState, RequestMode};
 use super::status::{default_environment, status_with_missing};
 use super::{EditTarget, Message, hotkeys, view, watcher};
 
-const FILE_SCAN_MAX_DEPTH: usize = 6;
-
 #[derive(Debug, Clone)]
 pub struct HeaderRow {
     pub name: String,
@@ -47,18 +48,15 @@ enum StartupStatus {
 }
 
 impl StartupStatus {
-    const fn from_context(
-        startup_warnings: &[String],
-        configuration: &ProjectConfiguration,
-    ) -> Self {
-        match (startup_warnings.is_empty(), configuration.should_scan()) {
+    const fn from_context(missing: &[MissingRoot], configuration: &ProjectConfiguration) -> Self {
+        match (missing.is_empty(), configuration.should_scan()) {
             (true, true) => Self::Ready,
             (true, false) => Self::NeedsProject,
             (false, true) => Self::WarningReady {
-                ignored: startup_warnings.len(),
+                ignored: missing.len(),
             },
             (false, false) => Self::WarningNeedsProject {
-                ignored: startup_warnings.len(),
+                ignored: missing.len(),
             },
         }
     }
@@ -70,15 +68,16 @@ impl StartupStatus {
                 "No projects configured. Add a project folder to start.".to_string()
             }
             Self::WarningReady { ignored } => {
-                format!("Ignored {ignored} invalid saved folder(s).")
+                format!("{ignored} saved folder(s) are unreachable.")
             }
             Self::WarningNeedsProject { ignored } => {
-                format!("Ignored {ignored} invalid saved folder(s). Add a project folder to start.")
+                format!("{ignored} saved folder(s) are unreachable. Add a project folder to start.")
             }
         }
     }
 }
 
+#[allow(clippy::struct_excessive_bools)] // independent panel toggles
 pub struct Zagel {
     pub(super) workspace: WorkspaceState,
     pub(super) configuration: ProjectConfiguration,
@@ -87,11 +86,25 @@ pub struct Zagel {
     pub(super) body_editor: iced::widget::text_editor::Content,
     pub(super) status_line: String,
     pub(super) response: Option<crate::app::view::ResponseData>,
+    pub(super) pinned_response: Option<super::pin::PinnedResponse>,
+    /// Latest response per request, restored when the selection changes.
+    pub(super) responses: HashMap<RequestId, crate::app::view::ResponseData>,
     pub(super) environments: Vec<crate::model::Environment>,
     pub(super) active_environment: usize,
     pub(super) state: AppState,
     pub(super) client: Client,
+    pub(super) client_config: ClientConfig,
     pub(super) response_viewer: iced::widget::text_editor::Content,
+    /// Body of the latest send while it is still arriving.
+    pub(super) streaming: Option<super::streaming::StreamingBody>,
+    /// Pages of the displayed response text; `response_viewer` holds the current one.
+    pub(super) response_pager: super::pager::Pager,
+    pub(super) response_search: super::search::ResponseSearch,
+    /// JSON path filter for the selected request's body, restored on selection.
+    pub(super) response_filter: String,
+    pub(super) response_header_filter: String,
+    pub(super) jwt_inspector: Option<super::jwt::JwtInspector>,
+    pub(super) json_tree: super::json_tree::JsonTree,
     pub(super) save_path: String,
     pub(super) project_path_input: String,
     pub(super) global_env_path_input: String,
@@ -99,12 +112,28 @@ pub struct Zagel {
     pub(super) auth: AuthState,
     pub(super) graphql_query: iced::widget::text_editor::Content,
     pub(super) graphql_variables: iced::widget::text_editor::Content,
+    pub(super) graphql_schemas: super::graphql_schema::SchemaExplorer,
+    pub(super) graphql_check: super::graphql_schema::QueryCheck,
+    pub(super) graphql_editor: super::graphql_editor::GraphqlEditorState,
+    pub(super) grpc: super::grpc::GrpcState,
+    pub(super) history: super::history::SendHistory,
+    pub(super) env_audit: crate::env_audit::EnvAudit,
+    pub(super) captures: super::captures::PendingCaptures,
+    pub(super) assertions: super::assertions::PendingAssertions,
     pub(super) oauth2_token_cache: Option<crate::net::OAuth2TokenCacheEntry>,
+    pub(super) idempotency: super::idempotency::IdempotencyKey,
+    pub(super) trace_context: super::trace_context::TraceContext,
+    pub(super) cookies: super::cookies::CookieJars,
+    /// Variables of the active session, layered over the environment's.
+    pub(super) session_variables: BTreeMap<String, String>,
+    pub(super) session_name_input: String,
     pub(super) header_rows: Vec<HeaderRow>,
     pub(super) response_display: crate::app::view::ResponseDisplay,
     pub(super) response_tab: crate::app::view::ResponseTab,
     pub(super) icon_set: crate::app::view::IconSet,
     pub(super) show_shortcuts: bool,
+    pub(super) show_settings: bool,
+    pub(super) ca_certificate_input: String,
     pub(super) pending_rescan: bool,
     pub(super) last_scan: Option<Instant>,
     pub(super) panes: pane_grid::State<crate::app::view::PaneContent>,
@@ -112,19 +141,55 @@ pub struct Zagel {
     pub(super) builder_panes: pane_grid::State<crate::app::view::BuilderPane>,
     pub(super) collapsed_collections: BTreeSet<String>,
     pub(super) automation: Option<AutomationRuntime>,
+    pub(super) benchmark: Option<BenchmarkSession>,
+    /// Compare mode; replaces the workspace pane while open.
+    pub(super) comparison: Option<super::compare::Comparison>,
+    pub(super) file_run: Option<super::runner::FileRun>,
+    pub(super) context_menu: Option<super::context_menu::ContextMenu>,
+    pub(super) find_replace: super::find_replace::FindReplace,
+    pub(super) batch_headers: super::batch_headers::BatchHeaders,
+    /// Open duplicates panel with the groups found when it was last refreshed.
+    pub(super) duplicates: Option<Vec<super::duplicates::DuplicateGroup>>,
+    /// Whether the URL is shown split into its parts under the URL field.
+    pub(super) url_builder_open: bool,
+    pub(super) preview_open: bool,
+    pub(super) notes: super::notes::NotesEditor,
+    /// Sidebar shows only the requests with this tag.
+    pub(super) tag_filter: Option<String>,
+    /// Fuzzy query typed above the sidebar tree.
+    pub(super) sidebar_filter: String,
+    pub(super) transfer: super::transfer::TransferForm,
+    /// Held keyboard modifiers, for shift-click range selection.
+    pub(super) modifiers: iced::keyboard::Modifiers,
+    /// Last request toggled in edit mode; shift-click selects from here.
+    pub(super) selection_anchor: Option<RequestId>,
+    pub(super) variables_open: bool,
+    /// Per-request variable values set in the Variables panel; they win over
+    /// every other source and last for the session.
+    pub(super) variable_overrides: HashMap<RequestId, BTreeMap<String, String>>,
+    pub(super) extract_variable: Option<super::extract_variable::ExtractVariable>,
+    pub(super) backup: super::backup::BackupForm,
+    pub(super) plugin_form: super::plugins::PluginForm,
+    pub(super) import_form: super::import::ImportForm,
+    /// Steps of the send in flight, for the response Log tab.
+    pub(super) send_log: Option<super::send_log::SendLog>,
+    pub(super) missing_roots: MissingRoots,
+    pub(super) tokens: super::tokens::TokenStore,
 }
 
-fn load_configured_roots(state: &AppState) -> (ProjectConfiguration, Vec<String>) {
-    let mut startup_warnings = Vec::new();
+/// The saved roots that still exist, and the ones that could not be opened.
+pub(super) fn load_configured_roots(state: &AppState) -> (ProjectConfiguration, Vec<MissingRoot>) {
+    let mut missing = Vec::new();
 
     let mut project_roots = Vec::new();
     for path in &state.project_roots {
         match ProjectRoot::from_stored(path.clone()) {
             Ok(root) => project_roots.push(root),
-            Err(err) => startup_warnings.push(format!(
-                "Ignoring saved project folder {}: {err}",
-                path.display()
-            )),
+            Err(err) => missing.push(MissingRoot {
+                kind: RootKind::Project,
+                path: path.clone(),
+                reason: err.to_string(),
+            }),
         }
     }
 
@@ -132,22 +197,25 @@ fn load_configured_roots(state: &AppState) -> (ProjectConfiguration, Vec<String>
     for path in &state.global_env_roots {
         match GlobalEnvRoot::from_stored(path.clone()) {
             Ok(root) => global_env_roots.push(root),
-            Err(err) => startup_warnings.push(format!(
-                "Ignoring saved global env folder {}: {err}",
-                path.display()
-            )),
+            Err(err) => missing.push(MissingRoot {
+                kind: RootKind::GlobalEnv,
+                path: path.clone(),
+                reason: err.to_string(),
+            }),
         }
     }
 
     (
         ProjectConfiguration::from_loaded(project_roots, global_env_roots),
-        startup_warnings,
+        missing,
     )
 }
 
 impl Zagel {
+    #[allow(clippy::too_many_lines)]
     pub(super) fn init(launch: LaunchOptions) -> (Self, Task<Message>) {
         let mut state = AppState::load();
+        super::editor::ensure_token(&mut state);
         if !launch.project_roots.is_empty() {
             state.project_roots.clone_from(&launch.project_roots);
             state.http_root = state.project_roots.first().cloned();
@@ -155,8 +223,8 @@ impl Zagel {
         if !launch.global_env_roots.is_empty() {
             state.global_env_roots.clone_from(&launch.global_env_roots);
         }
-        let (configuration, startup_warnings) = load_configured_roots(&state);
-        let startup_status = StartupStatus::from_context(&startup_warnings, &configuration);
+        let (configuration, mut missing_roots) = load_configured_roots(&state);
+        let startup_status = StartupStatus::from_context(&missing_roots, &configuration);
         let initial_status_line = startup_status.status_line();
 
         let (mut panes, sidebar) = pane_grid::State::new(super::view::PaneContent::Sidebar);
@@ -190,6 +258,11 @@ impl Zagel {
 
         let workspace = WorkspaceState::from_config(&configuration, state.http_file_order.clone());
 
+        let ca_certificate_input = state
+            .ca_certificate
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
         let mut app = Self {
             workspace,
             configuration,
@@ -198,11 +271,21 @@ impl Zagel {
             body_editor: iced::widget::text_editor::Content::with_text(""),
             status_line: initial_status_line,
             response: None,
+            pinned_response: None,
+            responses: HashMap::new(),
             environments: vec![default_environment()],
             active_environment: 0,
             state,
             client: Client::new(),
+            client_config: ClientConfig::default(),
             response_viewer: iced::widget::text_editor::Content::with_text("No response yet"),
+            streaming: None,
+            response_pager: super::pager::Pager::default(),
+            response_search: super::search::ResponseSearch::default(),
+            response_filter: String::new(),
+            response_header_filter: String::new(),
+            jwt_inspector: None,
+            json_tree: super::json_tree::JsonTree::default(),
             save_path: String::new(),
             project_path_input: String::new(),
             global_env_path_input: String::new(),
@@ -210,12 +293,27 @@ impl Zagel {
             auth: AuthState::default(),
             graphql_query: iced::widget::text_editor::Content::with_text(""),
             graphql_variables: iced::widget::text_editor::Content::with_text("{}"),
+            graphql_schemas: super::graphql_schema::SchemaExplorer::default(),
+            graphql_check: super::graphql_schema::QueryCheck::default(),
+            graphql_editor: super::graphql_editor::GraphqlEditorState::default(),
+            grpc: super::grpc::GrpcState::default(),
+            history: super::history::SendHistory::load(),
+            env_audit: crate::env_audit::EnvAudit::load(),
+            captures: super::captures::PendingCaptures::default(),
+            assertions: super::assertions::PendingAssertions::default(),
             oauth2_token_cache: None,
+            idempotency: super::idempotency::IdempotencyKey::default(),
+            trace_context: super::trace_context::TraceContext::default(),
+            cookies: super::cookies::CookieJars::default(),
+            session_variables: BTreeMap::new(),
+            session_name_input: String::new(),
             header_rows: Vec::new(),
             response_display: crate::app::view::ResponseDisplay::Pretty,
             response_tab: crate::app::view::ResponseTab::Body,
             icon_set: crate::app::view::IconSet::from_env(),
             show_shortcuts: false,
+            show_settings: false,
+            ca_certificate_input,
             pending_rescan: false,
             last_scan: None,
             panes,
@@ -223,15 +321,43 @@ impl Zagel {
             builder_panes,
             collapsed_collections: BTreeSet::new(),
             automation: None,
+            benchmark: None,
+            comparison: None,
+            file_run: None,
+            context_menu: None,
+            batch_headers: super::batch_headers::BatchHeaders::default(),
+            find_replace: super::find_replace::FindReplace::default(),
+            duplicates: None,
+            url_builder_open: false,
+            preview_open: false,
+            notes: super::notes::NotesEditor::default(),
+            tag_filter: None,
+            sidebar_filter: String::new(),
+            transfer: super::transfer::TransferForm::default(),
+            modifiers: iced::keyboard::Modifiers::default(),
+            selection_anchor: None,
+            variables_open: false,
+            variable_overrides: HashMap::new(),
+            extract_variable: None,
+            backup: super::backup::BackupForm::default(),
+            plugin_form: super::plugins::PluginForm::default(),
+            import_form: super::import::ImportForm::default(),
+            send_log: None,
+            missing_roots: MissingRoots::default(),
+            tokens: super::tokens::TokenStore::default(),
         };
 
-        for warning in &startup_warnings {
-            eprintln!("startup: {warning}");
+        for root in &missing_roots {
+            eprintln!("startup: {root}");
+        }
+        if !app.state.forget_missing_roots {
+            app.missing_roots = MissingRoots::new(std::mem::take(&mut missing_roots));
         }
 
         app.refresh_visible_environments();
+        app.restore_active_session();
         let mut task = if app.should_scan() {
-            if startup_warnings.is_empty() {
+            if missing_roots.is_empty() && app.missing_roots.roots.is_empty() {
                 app.update_status_with_missing("Ready");
             }
             app.rescan_files()
@@ -262,10 +388,17 @@ impl Zagel {
         let mut subscriptions = vec![
             hotkeys::subscription(),
             watcher::subscription_many(watch_roots),
+            super::editor::subscription(&state.state),
         ];
         if let Some(automation) = state.automation_subscription() {
             subscriptions.push(automation);
         }
+        if let Some(tick) = state.jwt_subscription() {
+            subscriptions.push(tick);
+        }
+        if let Some(tick) = state.tokens_subscription() {
+            subscriptions.push(tick);
+        }
         Subscription::batch(subscriptions)
     }
 
@@ -292,9 +425,11 @@ impl Zagel {
 
     pub(super) fn persist_state(&mut self) {
         let mut state = self.state.clone();
-        state.project_roots = self.project_root_paths();
-        state.global_env_roots = self.global_env_root_paths();
-        state.http_root = state.project_roots.first().cloned();
+        state.http_root = self.project_root_paths().first().cloned();
+        let missing = &self.missing_roots.roots;
+        state.project_roots = with_missing(self.project_root_paths(), missing, RootKind::Project);
+        state.global_env_roots =
+            with_missing(self.global_env_root_paths(), missing, RootKind::GlobalEnv);
         state
             .http_file_order
             .clone_from(self.workspace.http_file_order());
@@ -338,6 +473,40 @@ impl Zagel {
         }
     }
 
+    /// Returns a client matching the connection settings for `draft`,
+    /// rebuilding the cached one when those settings changed.
+    pub(super) fn http_client(&mut self, draft: &RequestDraft) -> Result<Client, String> {
+        let env = self.environments.get(self.active_environment);
+        let resolved_url = env.map_or_else(
+            || draft.url.clone(),
+            |env| crate::model::apply_environment(&draft.url, &env.vars),
+        );
+        let host = reqwest::Url::parse(resolved_url.trim())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        let project = self.selected_project_root().map(ProjectRoot::as_path);
+        let config = ClientConfig {
+            proxy: self
+                .state
+                .proxy_for(env.map(|env| env.name.as_str()))
+                .cloned(),
+            identity: self
+                .state
+                .client_certificate_for(project, host.as_deref())
+                .cloned(),
+            ca_certificate: self.state.ca_certificate.clone(),
+            skip_tls_verify: self.state.skip_tls_verify,
+            http_version: draft.http_version,
+            user_agent: self.state.user_agent.clone(),
+            no_redirect: draft.options.no_redirect,
+        };
+        if config != self.client_config {
+            self.client = build_client(&config)?;
+            self.client_config = config;
+        }
+        Ok(self.client.clone())
+    }
+
     pub(super) fn project_roots(&self) -> &[ProjectRoot] {
         self.configuration.project_roots()
     }
@@ -395,15 +564,24 @@ impl Zagel {
         });
         self.draft = draft.clone();
         self.body_editor = iced::widget::text_editor::Content::with_text(&draft.body);
+        self.notes.load(&draft.notes);
+        if draft.graphql {
+            self.load_graphql_draft(&draft.body);
+        } else if self.mode == RequestMode::GraphQl {
+            self.mode = RequestMode::Rest;
+        }
         self.set_header_rows_from_draft();
         self.refresh_visible_environments();
         self.save_path = path.display().to_string();
+        self.response = self.responses.get(id).cloned();
+        self.restore_response_filter();
+        self.json_tree = super::json_tree::JsonTree::default();
         self.update_status_with_missing("Ready");
         self.update_response_viewer();
     }
 
     pub(super) fn build_save_plan(&self) -> Result<SavePlan, SavePlanError> {
-        let draft = self.draft.clone();
+        let draft = self.composed_draft();
         if let Some(id) = self.workspace.selection_cloned() {
             let RequestId::HttpFile {
                 path: selected_path,
@@ -477,6 +655,21 @@ impl Zagel {
         }
     }
 
+    /// Adds a header, filling the trailing empty header row if any.
+    pub(super) fn add_header_row(&mut self, name: &str, value: &str) {
+        let row = HeaderRow {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        match self.header_rows.last_mut() {
+            Some(last) if last.name.trim().is_empty() && last.value.trim().is_empty() => {
+                *last = row;
+            }
+            _ => self.header_rows.push(row),
+        }
+        self.rebuild_headers_from_rows();
+    }
+
     pub(super) fn rebuild_headers_from_rows(&mut self) {
         let lines: Vec<String> = self
             .header_rows
@@ -488,8 +681,40 @@ impl Zagel {
     }
 
     pub(super) fn update_response_viewer(&mut self) {
+        let hex = self
+            .response
+            .as_ref()
+            .and_then(|response| response.body.hex_text());
+        if self.response_tab == super::view::ResponseTab::Hex {
+            if let Some(hex) = hex {
+                self.response_pager = super::pager::Pager::new(hex.to_string());
+                self.load_response_page();
+                self.refresh_response_search();
+                return;
+            }
+            self.response_tab = super::view::ResponseTab::Body;
+        }
+
+        let summary = self
+            .response
+            .as_ref()
+            .and_then(|response| response.body.summary());
         let display_text = match (self.response_display, self.response.as_ref()) {
-            (super::view::ResponseDisplay::Pretty, Some(response)) => response
+            (_, Some(_)) if let Some(summary) = summary => summary.to_string(),
+            (_, Some(response))
+                if self.response_tab == super::view::ResponseTab::Body
+                    && !self.response_filter.trim().is_empty()
+                    && let Some(filtered) = super::filter::filtered_body(
+                        response.body.raw(),
+                        &self.response_filter,
+                    ) =>
+            {
+                filtered
+            }
+            (
+                super::view::ResponseDisplay::Pretty | super::view::ResponseDisplay::Tree,
+                Some(response),
+            ) => response
                 .body
                 .pretty_text()
                 .unwrap_or_else(|| response.body.raw())
@@ -498,18 +723,51 @@ impl Zagel {
             (_, None) => "No response yet".to_string(),
         };
 
-        self.response_viewer = iced::widget::text_editor::Content::with_text(&display_text);
+        self.response_pager = super::pager::Pager::new(display_text);
+        self.load_response_page();
+        self.refresh_response_search();
+        self.refresh_json_tree();
+    }
+
+    /// Active environment as seen by `request`, in precedence order: global
+    /// environments of the same name, the active one, the file's `@name = value`
+    /// variables, session variables and the request's own overrides.
+    pub(super) fn request_environment(&self, request: Option<&RequestId>) -> Option<Environment> {
+        let mut env = self
+            .environments
+            .get(self.active_environment)
+            .cloned()?
+            .with_global_fallback(self.workspace.all_environments())
+            .with_file_variables(self.file_variables(request));
+        env = super::sessions::with_session_variables(env, &self.session_variables);
+        if let Some(overrides) = request.and_then(|id| self.variable_overrides.get(id)) {
+            env.vars.extend(overrides.clone());
+        }
+        Some(env)
+    }
+
+    /// Reference to a request that still finds it after its file is reordered.
+    pub(super) fn request_ref(&self, id: &RequestId) -> RequestRef {
+        let RequestId::HttpFile { path, .. } = id;
+        RequestRef::of(id, self.workspace.http_files().get(path))
+    }
+
+    pub(super) fn file_variables(&self, request: Option<&RequestId>) -> &[(String, String)] {
+        request
+            .and_then(|RequestId::HttpFile { path, .. }| self.workspace.http_files().get(path))
+            .map(|file| file.variables.as_slice())
+            .unwrap_or_default()
     }
 
     pub(super) fn update_status_with_missing(&mut self, base: &str) {
-        let env = self.environments.get(self.active_environment);
+        let env = self.request_environment(self.workspace.selection());
         let extras = if self.mode == RequestMode::GraphQl {
-            vec![self.graphql_query.text(), self.graphql_variables.text()]
+            vec![self.graphql_query_text(), self.graphql_variables.text()]
         } else {
             Vec::new()
         };
         let extra_refs: Vec<&str> = extras.iter().map(std::string::String::as_str).collect();
-        self.status_line = status_with_missing(base, &self.draft, env, &extra_refs);
+        self.status_line = status_with_missing(base, &self.draft, env.as_ref(), &extra_refs);
     }
 }
 
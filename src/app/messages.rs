@@ -4,8 +4,11 @@ use std::path::PathBuf;
 use iced::widget::pane_grid;
 use iced::widget::text_editor;
 
-use crate::model::{Environment, HttpFile, Method, RequestId};
+use crate::model::{
+    BodyEncoding, Environment, HttpFile, HttpVersion, Method, RequestDraft, RequestId,
+};
 use crate::pathing::{GlobalEnvRoot, ProjectRoot};
+use crate::state::{PluginConfig, PluginHook};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EditTarget {
@@ -21,11 +24,52 @@ pub enum Message {
     WatcherUnavailable(String),
     Select(RequestId),
     MethodSelected(Method),
+    NotesToggled,
+    NotesEdited(text_editor::Action),
+    /// Typed name of a `Method::Custom`.
+    CustomMethodChanged(String),
+    /// Name and new value of a path parameter of the URL.
+    PathParamChanged(String, String),
+    HttpVersionSelected(HttpVersion),
+    BodyEncodingSelected(BodyEncoding),
+    IdempotencyToggled(bool),
+    IdempotencyReuseToggled(bool),
+    CopyIdempotencyKey,
+    TraceContextToggled(bool),
+    TraceB3Toggled(bool),
+    CopyTraceId,
+    CookieJarSelected(super::cookies::JarChoice),
+    ClearCookies,
+    SessionSelected(String),
+    SessionNameChanged(String),
+    SaveSession,
+    DeleteSession,
     UrlChanged(String),
+    MoveUrlCredentialsToAuth,
+    UrlBuilderToggled,
+    PreviewToggled,
+    UrlPartEdited(super::url_builder::UrlPartEdit),
+    VariablesToggled,
+    JwtInspectorToggled,
+    JwtSourceSelected(super::jwt::JwtSource),
+    JwtTick,
+    VariableOverridden(String, String),
+    VariableOverrideCleared(String),
+    ExtractVariableToggled,
+    ExtractValueChanged(String),
+    ExtractNameChanged(String),
+    ExtractEnvironmentSelected(String),
+    ExtractVariableApplied,
     TitleChanged(String),
+    StepChanged(String),
     BodyEdited(text_editor::Action),
+    ContentTypeAdded(&'static str),
     Send,
-    ResponseReady(Result<crate::net::SendOutcome, String>),
+    ResponseChunk(Option<crate::model::RequestId>, crate::net::BodyChunk),
+    ResponseReady(
+        Option<crate::model::RequestId>,
+        Result<crate::net::SendOutcome, String>,
+    ),
     EnvironmentChanged(String),
     Save,
     Saved(Result<(PathBuf, usize), String>),
@@ -38,14 +82,59 @@ pub enum Message {
     RemoveGlobalEnvRoot(GlobalEnvRoot),
     ModeChanged(crate::app::options::RequestMode),
     GraphqlQueryEdited(text_editor::Action),
+    GraphqlFoldToggled,
+    GraphqlUnfoldAll,
+    GraphqlWrapToggled,
     GraphqlVariablesEdited(text_editor::Action),
+    FetchGraphqlSchema,
+    GraphqlOperationSelected(String),
+    GraphqlSchemaFetched(
+        String,
+        Result<std::sync::Arc<crate::app::graphql_schema::GraphqlSchema>, String>,
+    ),
+    GraphqlSchemaTypeToggled(String),
+    GrpcProtoPathsChanged(String),
+    LoadProtos,
+    ProtosLoaded(Result<std::sync::Arc<crate::proto::ProtoSchema>, String>),
+    GrpcServiceSelected(String),
+    GrpcMethodSelected(String),
+    GrpcTemplate,
     AuthChanged(crate::app::options::AuthState),
+    FileAuthSaved,
+    FileAuthCleared,
     HeaderNameChanged(usize, String),
     HeaderValueChanged(usize, String),
     HeaderAdded,
     HeaderRemoved(usize),
+    AssertionAdded,
+    AssertionRemoved(usize),
+    AssertionKindChanged(usize, crate::model::AssertionKind),
+    AssertionTargetChanged(usize, String),
+    AssertionExpectedChanged(usize, String),
     ResponseViewChanged(crate::app::view::ResponseDisplay),
     ResponseTabChanged(crate::app::view::ResponseTab),
+    ResponseSearchChanged(String),
+    ResponsePageChanged(usize),
+    ResponseWrapToggled,
+    ResponseWhitespaceToggled,
+    ResponseFontSizeChanged(u16),
+    LoadMoreHex,
+    SaveResponseBody,
+    ResponseBodySaved(Result<PathBuf, String>),
+    CopyResponseBodyAsFile,
+    ResponseBodyCopiedAsFile(Result<PathBuf, String>),
+    PrettyFormatted(Option<RequestId>, Option<crate::app::view::PrettyBody>),
+    ResponseFilterChanged(String),
+    JsonTreeToggled(String),
+    JsonTreeCopyPath(String),
+    JsonTreeCopyValue(String),
+    ResponseSearchNext,
+    ResponseSearchPrevious,
+    CopyCorrelationId(String),
+    CopyResponseHeaderValue(String),
+    ResponseHeaderFilterChanged(String),
+    ResponseHeaderAdded(String, String),
+    ResponseHeaderUrlOpened(String),
     CopyResponseRaw,
     CopyResponsePretty,
     CopyComplete,
@@ -53,17 +142,121 @@ pub enum Message {
     WorkspacePaneResized(pane_grid::ResizeEvent),
     BuilderPaneResized(pane_grid::ResizeEvent),
     ToggleCollection(String),
+    SidebarGroupingChanged(crate::state::SidebarGrouping),
+    FailedFilterToggled(bool),
+    EditorOpen(crate::app::editor::OpenRequest),
+    EditorEndpointFailed(String),
     ToggleEditMode,
     ToggleEditSelection(EditTarget),
     DeleteSelected,
+    TransferDestinationChanged(String),
+    TransferDestinationPicked(super::transfer::Destination),
+    TransferRequested(super::transfer::TransferMode),
+    BatchHeaderOpSelected(super::batch_headers::HeaderOp),
+    BatchHeaderNameChanged(String),
+    BatchHeaderValueChanged(String),
+    BatchHeadersApplied,
     MoveCollectionUp(PathBuf),
     MoveCollectionDown(PathBuf),
     MoveRequestUp(RequestId),
     MoveRequestDown(RequestId),
+    SplitHttpFile(PathBuf),
+    ContextMenuOpened(super::context_menu::MenuTarget),
+    ContextMenuClosed,
+    EscapePressed,
+    ModifiersChanged(iced::keyboard::Modifiers),
+    SelectAllVisible,
+    SidebarFilterChanged(String),
+    ContextMenuAction(super::context_menu::MenuAction),
+    ContextMenuRenameChanged(String),
+    ContextMenuRenameSubmitted,
+    FindReplaceToggled,
+    FindQueryChanged(String),
+    ReplaceTextChanged(String),
+    FindRegexToggled(bool),
+    FindReplaceApply,
+    PinResponse,
+    UnpinResponse,
+    DuplicatesToggled,
+    DuplicateKeep(RequestId),
+    ImportCurl,
+    CurlPasted(Option<String>),
+    BackupPathChanged(String),
+    BackupSecretsToggled(bool),
+    ExportBackup,
+    RestoreBackup,
+    RestoreBackupConfirmed,
+    RestoreBackupCancelled,
+    PluginCommandChanged(String),
+    AddPlugin,
+    PluginDescribed(Result<PluginConfig, String>),
+    PluginToggled(usize, bool),
+    RemovePlugin(usize),
+    PluginImportPathChanged(String),
+    RunImporter(usize),
+    PluginImported(String, PathBuf, Result<Vec<RequestDraft>, String>),
+    HookCommandChanged(String),
+    HookTimingChanged(PluginHook),
+    AddHookCommand,
+    HookCommandToggled(usize, bool),
+    RemoveHookCommand(usize),
+    ImportPathChanged(String),
+    ImportInsomnia,
+    ImportBruno,
     AddRequest,
     ToggleShortcutsHelp,
+    ToggleSettings,
+    CaCertificateInputChanged(String),
+    SkipTlsVerifyToggled(bool),
+    ForgetMissingRootsToggled(bool),
+    MissingRootRemoved(usize),
+    MissingRootsRetried,
+    MissingRootRelocateStarted(usize),
+    MissingRootRelocateInputChanged(String),
+    MissingRootRelocated,
+    MissingRootsDismissed,
+    UserAgentChanged(String),
+    BenchmarkOpen,
+    BenchmarkIterationsChanged(String),
+    BenchmarkStart,
+    BenchmarkStop,
+    BenchmarkExportPathChanged(String),
+    BenchmarkExportCsv,
+    BenchmarkExported(Result<(PathBuf, usize), String>),
+    BenchmarkClose,
+    BenchmarkSampleReady {
+        run_id: u64,
+        result: Result<crate::net::SendOutcome, String>,
+    },
+    CompareOpen,
+    CompareSend,
+    CompareClose,
+    ComparePaneResized(pane_grid::ResizeEvent),
+    CompareResponseReady {
+        send_id: u64,
+        side: super::compare::CompareSide,
+        result: Result<crate::net::SendOutcome, String>,
+    },
+    RunFile(PathBuf),
+    RunTag(String),
+    FavoriteToggled(RequestId),
+    TagFilterChanged(super::tags::TagChoice),
+    RunFileAgain,
+    RunFileStop,
+    RunFileClose,
+    TokensRefreshed(RequestId, Result<crate::net::SendOutcome, String>),
+    TokensTick,
+    RunStepReady {
+        run_id: u64,
+        result: Result<crate::net::SendOutcome, String>,
+    },
+    FailureSnapshotToggled,
+    RecentSendsToggled,
+    HistoryEntryReplayed(Box<crate::history::HistoryEntry>),
+    FailureSnapshotDismissed,
     AutomationStart,
     AutomationPoll,
     AutomationWindowResolved(Option<iced::window::Id>),
     AutomationScreenshotCaptured(iced::window::Screenshot),
+    AutomationRegionBounds(Option<iced::Rectangle>),
 }
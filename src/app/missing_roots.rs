@@ -0,0 +1,251 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::pathing::{DirectoryRoot, GlobalEnvRoot, ProjectRoot};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootKind {
+    Project,
+    GlobalEnv,
+}
+
+impl Display for RootKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Project => f.write_str("project folder"),
+            Self::GlobalEnv => f.write_str("global env folder"),
+        }
+    }
+}
+
+/// A saved root that could not be opened at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingRoot {
+    pub kind: RootKind,
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl Display for MissingRoot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Saved {} {} is unreachable: {}",
+            self.kind,
+            self.path.display(),
+            self.reason
+        )
+    }
+}
+
+/// Saved roots that are kept in the state, but not scanned, until they are
+/// removed, relocated or found again.
+#[derive(Debug, Clone, Default)]
+pub struct MissingRoots {
+    pub roots: Vec<MissingRoot>,
+    /// Index of the root being relocated and the new path typed so far.
+    pub relocating: Option<(usize, String)>,
+    /// Hides the banner for the rest of the session.
+    pub dismissed: bool,
+}
+
+impl MissingRoots {
+    pub const fn new(roots: Vec<MissingRoot>) -> Self {
+        Self {
+            roots,
+            relocating: None,
+            dismissed: false,
+        }
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        !self.dismissed && !self.roots.is_empty()
+    }
+
+    fn remove(&mut self, index: usize) -> Option<MissingRoot> {
+        if index >= self.roots.len() {
+            return None;
+        }
+        self.relocating = None;
+        Some(self.roots.remove(index))
+    }
+}
+
+/// `configured` followed by the missing roots of `kind` it does not list, so
+/// saving the state does not forget folders that are only unreachable.
+pub fn with_missing(
+    mut configured: Vec<PathBuf>,
+    missing: &[MissingRoot],
+    kind: RootKind,
+) -> Vec<PathBuf> {
+    for root in missing.iter().filter(|root| root.kind == kind) {
+        if !configured.contains(&root.path) {
+            configured.push(root.path.clone());
+        }
+    }
+    configured
+}
+
+impl Zagel {
+    /// Adds an existing folder at `path` as a root of `kind` and rescans.
+    fn adopt_root(&mut self, kind: RootKind, path: &Path) -> Result<Task<Message>, String> {
+        match kind {
+            RootKind::Project => {
+                let root =
+                    ProjectRoot::from_stored(path.to_path_buf()).map_err(|err| err.to_string())?;
+                self.configuration
+                    .add_project(root)
+                    .map_err(|err| err.to_string())?;
+            }
+            RootKind::GlobalEnv => {
+                let root = GlobalEnvRoot::from_stored(path.to_path_buf())
+                    .map_err(|err| err.to_string())?;
+                self.configuration
+                    .add_global_env(root)
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+        self.workspace.sync_with_configuration(&self.configuration);
+        self.persist_state();
+        self.last_scan = Some(Instant::now());
+        Ok(self.rescan_files())
+    }
+
+    pub(super) fn handle_missing_root_removed(&mut self, index: usize) -> Task<Message> {
+        if let Some(root) = self.missing_roots.remove(index) {
+            self.persist_state();
+            self.update_status_with_missing(&format!(
+                "Removed {} {}",
+                root.kind,
+                root.path.display()
+            ));
+        }
+        Task::none()
+    }
+
+    /// Checks every missing root again and adds the ones that are back.
+    pub(super) fn handle_missing_roots_retried(&mut self) -> Task<Message> {
+        let mut tasks = Vec::new();
+        let mut index = 0;
+        while let Some(root) = self.missing_roots.roots.get(index).cloned() {
+            match self.adopt_root(root.kind, &root.path) {
+                Ok(task) => {
+                    self.missing_roots.remove(index);
+                    tasks.push(task);
+                }
+                Err(err) => {
+                    self.missing_roots.roots[index].reason = err;
+                    index += 1;
+                }
+            }
+        }
+        let left = self.missing_roots.roots.len();
+        self.update_status_with_missing(&if left == 0 {
+            "All saved folders are reachable again".to_string()
+        } else {
+            format!("{left} saved folder(s) are still unreachable")
+        });
+        Task::batch(tasks)
+    }
+
+    pub(super) fn handle_missing_root_relocate_started(&mut self, index: usize) -> Task<Message> {
+        self.missing_roots.relocating = self
+            .missing_roots
+            .roots
+            .get(index)
+            .map(|root| (index, root.path.display().to_string()));
+        Task::none()
+    }
+
+    pub(super) fn handle_missing_root_relocate_input_changed(
+        &mut self,
+        input: String,
+    ) -> Task<Message> {
+        if let Some((_, path)) = self.missing_roots.relocating.as_mut() {
+            *path = input;
+        }
+        Task::none()
+    }
+
+    /// Replaces the missing root being relocated with the typed folder.
+    pub(super) fn handle_missing_root_relocated(&mut self) -> Task<Message> {
+        let Some((index, input)) = self.missing_roots.relocating.clone() else {
+            return Task::none();
+        };
+        let Some(kind) = self.missing_roots.roots.get(index).map(|root| root.kind) else {
+            return Task::none();
+        };
+        let adopted = DirectoryRoot::parse_user_input(&input)
+            .map_err(|err| err.to_string())
+            .and_then(|root| self.adopt_root(kind, root.as_path()));
+        match adopted {
+            Ok(task) => {
+                self.missing_roots.remove(index);
+                self.persist_state();
+                self.update_status_with_missing(&format!("Relocated {kind} to {}", input.trim()));
+                task
+            }
+            Err(err) => {
+                self.update_status_with_missing(&err);
+                Task::none()
+            }
+        }
+    }
+
+    pub(super) fn handle_missing_roots_dismissed(&mut self) -> Task<Message> {
+        self.missing_roots.dismissed = true;
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{MissingRoot, RootKind, with_missing};
+    use crate::app::lifecycle::load_configured_roots;
+    use crate::state::AppState;
+
+    #[test]
+    fn unreachable_roots_are_reported_and_kept_when_saving() {
+        let existing = std::env::temp_dir()
+            .canonicalize()
+            .expect("temp dir resolves");
+        let gone = existing.join("zagel-missing-root-test-does-not-exist");
+        let state = AppState {
+            project_roots: vec![existing.clone(), gone.clone()],
+            global_env_roots: vec![gone.clone()],
+            ..AppState::default()
+        };
+
+        let (configuration, missing) = load_configured_roots(&state);
+        assert_eq!(configuration.project_root_paths(), vec![existing.clone()]);
+        assert_eq!(
+            missing
+                .iter()
+                .map(|root| (root.kind, root.path.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (RootKind::Project, gone.clone()),
+                (RootKind::GlobalEnv, gone.clone())
+            ]
+        );
+        assert!(missing[0].to_string().contains("unreachable"));
+
+        let saved = with_missing(vec![existing.clone()], &missing, RootKind::Project);
+        assert_eq!(saved, vec![existing, gone.clone()]);
+        let other = MissingRoot {
+            kind: RootKind::GlobalEnv,
+            path: PathBuf::from("/elsewhere"),
+            reason: String::new(),
+        };
+        assert_eq!(
+            with_missing(vec![gone.clone()], &[other], RootKind::Project),
+            vec![gone]
+        );
+    }
+}
@@ -1,17 +1,72 @@
+mod assertions;
 mod automation;
+mod backup;
+mod batch_headers;
+mod benchmark;
+mod body_hints;
+mod captures;
+mod compare;
+mod context_menu;
+mod cookies;
 mod domain;
+mod duplicates;
+mod editor;
+mod extract_variable;
+mod favorites;
+mod file_auth;
+mod filter;
+mod find_replace;
+mod graphql_editor;
+mod graphql_schema;
+mod grouping;
+mod grpc;
 mod headers;
+mod history;
 mod hotkeys;
+mod idempotency;
+mod import;
+mod json_tree;
+mod jwt;
 mod lifecycle;
 mod messages;
+mod missing_roots;
+mod notes;
 mod options;
+mod pager;
+mod pin;
+mod placeholders;
+mod plugins;
+mod preview;
+mod range_select;
+mod reanchor;
+mod request_filter;
+mod response_file;
+mod response_headers;
+mod runner;
+mod search;
+mod send_log;
+mod sessions;
+mod split;
 mod status;
+mod streaming;
+mod tags;
+mod tokens;
+mod trace_context;
+mod transfer;
 mod update;
+mod url_builder;
+mod variables;
 mod view;
+mod viewer;
 mod watcher;
 
+pub use assertions::evaluate as evaluate_assertions;
+pub use captures::capture_value;
 pub use lifecycle::{EditState, HeaderRow, Zagel, run};
 pub use messages::{EditTarget, Message};
 pub use options::{
-    AuthState, ClientSecretMethod, OAuth2ClientCredentialsAuthState, apply_auth_headers,
+    AuthState, ClientSecretMethod, HmacAlgorithm, OAuth1SignatureMethod,
+    OAuth2AuthorizationCodeAuthState, OAuth2ClientCredentialsAuthState, SignatureEncoding,
+    apply_auth_headers, inherited_auth,
 };
+pub use runner::RunResult;
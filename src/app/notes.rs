@@ -0,0 +1,33 @@
+use iced::Task;
+use iced::widget::text_editor;
+
+use super::{Message, Zagel};
+
+/// The collapsible Notes section of the builder.
+#[derive(Debug, Default)]
+pub struct NotesEditor {
+    pub open: bool,
+    pub content: text_editor::Content,
+}
+
+impl NotesEditor {
+    /// Reloads the editor for a newly shown request, opening it when the
+    /// request has notes.
+    pub fn load(&mut self, notes: &str) {
+        self.content = text_editor::Content::with_text(notes);
+        self.open = !notes.trim().is_empty();
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_notes_toggled(&mut self) -> Task<Message> {
+        self.notes.open = !self.notes.open;
+        Task::none()
+    }
+
+    pub(super) fn handle_notes_edited(&mut self, action: text_editor::Action) -> Task<Message> {
+        self.notes.content.perform(action);
+        self.draft.notes = self.notes.content.text().trim_end().to_string();
+        Task::none()
+    }
+}
@@ -0,0 +1,250 @@
+use std::ops::Range;
+
+use iced::Task;
+use iced::widget::text_editor::Content;
+
+use super::view::{PrettyBody, ResponseDisplay, format_pretty};
+use super::viewer::mark_whitespace;
+use super::{Message, Zagel};
+use crate::model::RequestId;
+
+/// A page holds at most this many lines...
+pub const PAGE_LINES: usize = 2_000;
+/// ...and at most this many bytes, so a minified one-line body is still split.
+pub const PAGE_BYTES: usize = 256 * 1024;
+
+/// Response text split into pages so the editor only ever holds one page.
+#[derive(Debug, Clone)]
+pub struct Pager {
+    text: String,
+    starts: Vec<usize>,
+    current: usize,
+}
+
+impl Default for Pager {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
+impl Pager {
+    pub fn new(text: String) -> Self {
+        let starts = page_starts(&text);
+        Self {
+            text,
+            starts,
+            current: 0,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub const fn page_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    pub const fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn range(&self, page: usize) -> Range<usize> {
+        let start = self.starts.get(page).copied().unwrap_or(self.text.len());
+        let end = self
+            .starts
+            .get(page + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        start..end
+    }
+
+    pub fn page_text(&self) -> &str {
+        &self.text[self.range(self.current)]
+    }
+
+    /// Page containing byte `offset` of the full text.
+    pub fn page_of(&self, offset: usize) -> usize {
+        self.starts
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1)
+    }
+
+    pub fn set_page(&mut self, page: usize) -> bool {
+        let page = page.min(self.page_count().saturating_sub(1));
+        let changed = page != self.current;
+        self.current = page;
+        changed
+    }
+
+    /// Line and byte column of `offset` within the current page; offsets outside
+    /// it are clamped to the page.
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let range = self.range(self.current);
+        let before = &self.text[range.start..offset.clamp(range.start, range.end)];
+        let line = before.matches('\n').count();
+        let column = before
+            .rfind('\n')
+            .map_or(before.len(), |idx| before.len() - idx - 1);
+        (line, column)
+    }
+
+    /// Like [`Self::position`], but for the page as shown with whitespace
+    /// markers when `whitespace` is set.
+    pub fn display_position(&self, offset: usize, whitespace: bool) -> (usize, usize) {
+        let (line, column) = self.position(offset);
+        if !whitespace {
+            return (line, column);
+        }
+        let prefix = self
+            .page_text()
+            .split('\n')
+            .nth(line)
+            .and_then(|text| text.get(..column))
+            .unwrap_or_default();
+        (line, mark_whitespace(prefix).len())
+    }
+}
+
+/// Start offsets of each page; always at least one page, always on char boundaries.
+fn page_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    let mut page_start = 0;
+    let mut lines = 0;
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let limit = page_start + PAGE_BYTES;
+        let line_end = find_newline(&bytes[pos..]).map_or(bytes.len(), |idx| pos + idx + 1);
+        if line_end <= limit {
+            pos = line_end;
+            lines += 1;
+            if lines == PAGE_LINES && pos < bytes.len() {
+                starts.push(pos);
+                page_start = pos;
+                lines = 0;
+            }
+            continue;
+        }
+        // The line overflows the page: end the page at the last line break, or
+        // cut the line itself when it is the only content on the page.
+        let cut = if pos > page_start {
+            pos
+        } else {
+            floor_char_boundary(text, limit)
+        };
+        starts.push(cut);
+        page_start = cut;
+        pos = cut;
+        lines = 0;
+    }
+    starts
+}
+
+fn find_newline(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().position(|byte| *byte == b'\n')
+}
+
+pub(super) const fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+impl Zagel {
+    /// Loads the pager's current page into the viewer.
+    pub(super) fn load_response_page(&mut self) {
+        let page = self.response_pager.page_text();
+        self.response_viewer = if self.marks_whitespace() {
+            Content::with_text(&mark_whitespace(page))
+        } else {
+            Content::with_text(page)
+        };
+    }
+
+    /// Starts pretty-printing a large body in the background once the Pretty view
+    /// needs it.
+    pub(super) fn ensure_pretty(&mut self) -> Task<Message> {
+        if self.response_display != ResponseDisplay::Pretty {
+            return Task::none();
+        }
+        let Some(response) = self
+            .response
+            .as_mut()
+            .filter(|response| response.body.pretty_deferred())
+        else {
+            return Task::none();
+        };
+        response.body.mark_formatting();
+        let raw = response.body.raw().to_string();
+        let syntax = response.body.syntax();
+        let id = self.workspace.selection_cloned();
+        if let Some(cached) = id.as_ref().and_then(|id| self.responses.get_mut(id)) {
+            cached.body.mark_formatting();
+        }
+        Task::perform(async move { format_pretty(&raw, syntax) }, move |pretty| {
+            Message::PrettyFormatted(id, pretty)
+        })
+    }
+
+    pub(super) fn handle_pretty_formatted(
+        &mut self,
+        id: Option<&RequestId>,
+        pretty: Option<PrettyBody>,
+    ) -> Task<Message> {
+        if let Some(cached) = id.and_then(|id| self.responses.get_mut(id))
+            && cached.body.is_formatting()
+        {
+            cached.body.set_pretty(pretty.clone());
+        }
+        if id == self.workspace.selection()
+            && let Some(response) = self.response.as_mut()
+            && response.body.is_formatting()
+        {
+            response.body.set_pretty(pretty);
+            self.update_response_viewer();
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_response_page_changed(&mut self, page: usize) -> Task<Message> {
+        if self.response_pager.set_page(page) {
+            self.load_response_page();
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PAGE_BYTES, PAGE_LINES, Pager};
+
+    #[test]
+    fn small_text_is_a_single_page() {
+        let pager = Pager::new("a\nb\n".to_string());
+        assert_eq!(pager.page_count(), 1);
+        assert_eq!(pager.page_text(), "a\nb\n");
+        assert_eq!(pager.position(2), (1, 0));
+    }
+
+    #[test]
+    fn pages_split_on_line_count_and_long_lines() {
+        let many_lines = "x\n".repeat(PAGE_LINES + 1);
+        let pager = Pager::new(many_lines);
+        assert_eq!(pager.page_count(), 2);
+        assert_eq!(pager.range(1), PAGE_LINES * 2..(PAGE_LINES + 1) * 2);
+
+        let long_line = format!("{}\nend", "é".repeat(PAGE_BYTES));
+        let mut pager = Pager::new(long_line);
+        assert!(pager.page_count() >= 3);
+        for page in 0..pager.page_count() {
+            pager.set_page(page);
+            assert!(pager.page_text().len() <= PAGE_BYTES);
+        }
+        let last = pager.text().len() - 1;
+        assert_eq!(pager.page_of(last), pager.page_count() - 1);
+        pager.set_page(pager.page_of(last));
+        assert_eq!(pager.position(last), (1, 2));
+    }
+}
@@ -0,0 +1,109 @@
+use iced::Task;
+use iced::widget::text_editor;
+
+use super::view::ResponseData;
+use super::{Message, Zagel};
+
+/// Response kept next to the live one as a known-good reference.
+#[derive(Debug)]
+pub struct PinnedResponse {
+    /// Title of the request it came from.
+    pub title: String,
+    pub response: ResponseData,
+    /// Read-only view of the pinned body.
+    pub viewer: text_editor::Content,
+}
+
+pub(super) fn body_text(response: &ResponseData) -> &str {
+    response
+        .body
+        .pretty_text()
+        .unwrap_or_else(|| response.body.raw())
+}
+
+/// Number of lines that differ position by position, counting lines only
+/// one side has.
+fn differing_lines(pinned: &str, current: &str) -> usize {
+    let (mut pinned, mut current) = (pinned.lines(), current.lines());
+    let mut differing = 0;
+    loop {
+        match (pinned.next(), current.next()) {
+            (None, None) => return differing,
+            (a, b) if a != b => differing += 1,
+            _ => {}
+        }
+    }
+}
+
+/// One line comparing the live response with the pinned one.
+pub fn comparison(pinned: &ResponseData, current: &ResponseData) -> String {
+    let status = |response: &ResponseData| {
+        response
+            .preview
+            .status
+            .map_or_else(|| "none".to_string(), |status| status.to_string())
+    };
+    let (before, after) = (status(pinned), status(current));
+    let status = if before == after {
+        format!("Same status {after}")
+    } else {
+        format!("Status {before} → {after}")
+    };
+    let body = match differing_lines(body_text(pinned), body_text(current)) {
+        0 => "identical body".to_string(),
+        1 => "1 body line differs".to_string(),
+        lines => format!("{lines} body lines differ"),
+    };
+    format!("{status}, {body}")
+}
+
+impl Zagel {
+    /// Pins the shown response, replacing any earlier pin.
+    pub(super) fn handle_pin_response(&mut self) -> Task<Message> {
+        let Some(response) = self.response.clone() else {
+            self.update_status_with_missing("Send a request before pinning its response");
+            return Task::none();
+        };
+        self.pinned_response = Some(PinnedResponse {
+            title: self.draft.title.clone(),
+            viewer: text_editor::Content::with_text(body_text(&response)),
+            response,
+        });
+        self.update_status_with_missing("Pinned response");
+        Task::none()
+    }
+
+    pub(super) fn handle_unpin_response(&mut self) -> Task<Message> {
+        self.pinned_response = None;
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{comparison, differing_lines};
+    use crate::app::view::ResponseData;
+    use crate::model::ResponsePreview;
+
+    fn response(status: u16, body: &str) -> ResponseData {
+        ResponseData::from_preview(ResponsePreview {
+            status: Some(status),
+            body: Some(body.as_bytes().to_vec()),
+            error: None,
+            ..ResponsePreview::error(String::new())
+        })
+    }
+
+    #[test]
+    fn live_responses_are_compared_with_the_pin() {
+        assert_eq!(differing_lines("a\nb\nc", "a\nx\nc\nd"), 2);
+        assert_eq!(
+            comparison(&response(200, "ok"), &response(200, "ok")),
+            "Same status 200, identical body"
+        );
+        assert_eq!(
+            comparison(&response(200, "ok"), &response(500, "error")),
+            "Status 200 → 500, 1 body line differs"
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use iced::advanced::text::highlighter::{self, Highlighter};
+use iced::widget::text_input;
+use iced::{Font, Theme};
+
+use super::Zagel;
+
+/// Whether a `{{placeholder}}` resolves with the active environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderHighlight {
+    Resolved,
+    Missing,
+}
+
+/// `{{name}}` spans in `line`, including the braces. Dynamic variables such as
+/// `{{$uuid}}` always resolve.
+pub fn placeholder_spans(
+    line: &str,
+    defined: &BTreeSet<String>,
+) -> Vec<(Range<usize>, PlaceholderHighlight)> {
+    let mut spans = Vec::new();
+    let mut from = 0;
+    while let Some(open) = line[from..].find("{{").map(|at| from + at) {
+        let Some(close) = line[open + 2..].find("}}").map(|at| open + 2 + at) else {
+            break;
+        };
+        let name = line[open + 2..close].trim();
+        let highlight = if name.starts_with('$') || defined.contains(name) {
+            PlaceholderHighlight::Resolved
+        } else {
+            PlaceholderHighlight::Missing
+        };
+        if !name.is_empty() {
+            spans.push((open..close + 2, highlight));
+        }
+        from = close + 2;
+    }
+    spans
+}
+
+/// `Missing` if any placeholder in `input` is undefined, `Resolved` if all are
+/// defined and `None` without placeholders.
+pub fn field_highlight(input: &str, defined: &BTreeSet<String>) -> Option<PlaceholderHighlight> {
+    input
+        .lines()
+        .flat_map(|line| placeholder_spans(line, defined))
+        .map(|(_, highlight)| highlight)
+        .reduce(|worst, highlight| {
+            if highlight == PlaceholderHighlight::Missing {
+                highlight
+            } else {
+                worst
+            }
+        })
+}
+
+fn highlight_color(highlight: PlaceholderHighlight, theme: &Theme) -> iced::Color {
+    let palette = theme.extended_palette();
+    match highlight {
+        PlaceholderHighlight::Resolved => palette.success.strong.color,
+        PlaceholderHighlight::Missing => palette.danger.base.color,
+    }
+}
+
+/// Colours placeholders in the body editor.
+pub struct PlaceholderHighlighter {
+    defined: BTreeSet<String>,
+    line: usize,
+}
+
+impl Highlighter for PlaceholderHighlighter {
+    type Settings = BTreeSet<String>;
+    type Highlight = PlaceholderHighlight;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, PlaceholderHighlight)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            defined: settings.clone(),
+            line: 0,
+        }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        self.defined.clone_from(new_settings);
+        self.line = 0;
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.line = self.line.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        self.line += 1;
+        placeholder_spans(line, &self.defined).into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.line
+    }
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // signature required by `highlight_with`
+pub fn placeholder_format(
+    highlight: &PlaceholderHighlight,
+    theme: &Theme,
+) -> highlighter::Format<Font> {
+    highlighter::Format {
+        color: Some(highlight_color(*highlight, theme)),
+        font: None,
+    }
+}
+
+/// Input border in the colour of the field's placeholders, since text inputs
+/// cannot colour parts of their value.
+pub fn input_style(
+    highlight: Option<PlaceholderHighlight>,
+) -> impl Fn(&Theme, text_input::Status) -> text_input::Style {
+    move |theme, status| {
+        let mut style = text_input::default(theme, status);
+        if let Some(highlight) = highlight {
+            style.border.color = highlight_color(highlight, theme);
+        }
+        style
+    }
+}
+
+impl Zagel {
+    /// Variable names the selected request can resolve.
+    pub(super) fn defined_variables(&self) -> BTreeSet<String> {
+        self.request_environment(self.workspace.selection())
+            .map(|env| env.vars.into_keys().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{PlaceholderHighlight, field_highlight, placeholder_spans};
+
+    #[test]
+    fn placeholders_are_marked_by_whether_they_resolve() {
+        let defined = BTreeSet::from(["host".to_string()]);
+        assert_eq!(
+            placeholder_spans("{{host}}/{{ id }}?t={{$timestamp}}", &defined),
+            [
+                (0..8, PlaceholderHighlight::Resolved),
+                (9..17, PlaceholderHighlight::Missing),
+                (20..34, PlaceholderHighlight::Resolved),
+            ]
+        );
+        assert_eq!(
+            field_highlight("{{host}}/users", &defined),
+            Some(PlaceholderHighlight::Resolved)
+        );
+        assert_eq!(
+            field_highlight("{{host}}\n{{token}}", &defined),
+            Some(PlaceholderHighlight::Missing)
+        );
+        assert_eq!(field_highlight("https://api.test", &defined), None);
+    }
+}
@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+
+use iced::Task;
+
+use crate::model::RequestDraft;
+use crate::plugins;
+use crate::state::{HookCommand, PluginConfig, PluginHook};
+
+use super::{Message, Zagel};
+
+/// Inputs of the plugin manager in the settings panel.
+#[derive(Debug, Clone, Default)]
+pub struct PluginForm {
+    pub command: String,
+    /// File handed to an import plugin.
+    pub import_path: String,
+    /// Shell command of a new send hook.
+    pub hook_command: String,
+    pub hook: PluginHook,
+}
+
+impl Zagel {
+    pub(super) fn handle_plugin_command_changed(&mut self, command: String) -> Task<Message> {
+        self.plugin_form.command = command;
+        Task::none()
+    }
+
+    pub(super) fn handle_plugin_import_path_changed(&mut self, path: String) -> Task<Message> {
+        self.plugin_form.import_path = path;
+        Task::none()
+    }
+
+    /// Asks the program for its name and hooks in the background.
+    pub(super) fn handle_add_plugin(&mut self) -> Task<Message> {
+        let command = PathBuf::from(self.plugin_form.command.trim());
+        if self
+            .state
+            .plugins
+            .iter()
+            .any(|plugin| plugin.command == command)
+        {
+            self.update_status_with_missing("That plugin is already added");
+            return Task::none();
+        }
+        self.update_status_with_missing(&format!(
+            "Asking {} to describe itself...",
+            command.display()
+        ));
+        Task::perform(plugins::describe(command), Message::PluginDescribed)
+    }
+
+    /// Remembers the plugin `describe` reported.
+    pub(super) fn handle_plugin_described(
+        &mut self,
+        result: Result<PluginConfig, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(plugin) => {
+                let status = format!(
+                    "Added plugin {} ({})",
+                    plugin.name,
+                    plugin
+                        .hooks
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                self.state.plugins.push(plugin);
+                self.plugin_form.command.clear();
+                self.persist_state();
+                self.update_status_with_missing(&status);
+            }
+            Err(err) => self.update_status_with_missing(&format!("Cannot add plugin: {err}")),
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_plugin_toggled(&mut self, index: usize, enabled: bool) -> Task<Message> {
+        if let Some(plugin) = self.state.plugins.get_mut(index) {
+            plugin.enabled = enabled;
+            self.persist_state();
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_remove_plugin(&mut self, index: usize) -> Task<Message> {
+        if index < self.state.plugins.len() {
+            let plugin = self.state.plugins.remove(index);
+            self.persist_state();
+            self.update_status_with_missing(&format!("Removed plugin {}", plugin.name));
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_run_importer(&mut self, index: usize) -> Task<Message> {
+        let Some(plugin) = self.state.plugins.get(index).cloned() else {
+            return Task::none();
+        };
+        let source = PathBuf::from(self.plugin_form.import_path.trim());
+        self.update_status_with_missing(&format!("Importing with {}...", plugin.name));
+        let name = plugin.name.clone();
+        Task::perform(plugins::import(plugin, source.clone()), move |result| {
+            Message::PluginImported(name, source, result)
+        })
+    }
+
+    pub(super) fn handle_plugin_imported(
+        &mut self,
+        plugin: &str,
+        source: &Path,
+        result: Result<Vec<RequestDraft>, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(drafts) => {
+                let stem = source.file_stem().map_or_else(
+                    || plugin.to_string(),
+                    |stem| stem.to_string_lossy().to_string(),
+                );
+                self.write_imported(&stem, &drafts)
+            }
+            Err(err) => {
+                self.update_status_with_missing(&format!("Import failed: {err}"));
+                Task::none()
+            }
+        }
+    }
+
+    pub(super) fn handle_hook_command_changed(&mut self, command: String) -> Task<Message> {
+        self.plugin_form.hook_command = command;
+        Task::none()
+    }
+
+    pub(super) fn handle_hook_timing_changed(&mut self, hook: PluginHook) -> Task<Message> {
+        self.plugin_form.hook = hook;
+        Task::none()
+    }
+
+    pub(super) fn handle_add_hook_command(&mut self) -> Task<Message> {
+        let command = self.plugin_form.hook_command.trim().to_string();
+        if command.is_empty() {
+            return Task::none();
+        }
+        let hook = self.plugin_form.hook;
+        self.update_status_with_missing(&format!("Added {hook} hook `{command}`"));
+        self.state.hook_commands.push(HookCommand {
+            command,
+            hook,
+            enabled: true,
+        });
+        self.plugin_form.hook_command.clear();
+        self.persist_state();
+        Task::none()
+    }
+
+    pub(super) fn handle_hook_command_toggled(
+        &mut self,
+        index: usize,
+        enabled: bool,
+    ) -> Task<Message> {
+        if let Some(command) = self.state.hook_commands.get_mut(index) {
+            command.enabled = enabled;
+            self.persist_state();
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_remove_hook_command(&mut self, index: usize) -> Task<Message> {
+        if index < self.state.hook_commands.len() {
+            let command = self.state.hook_commands.remove(index);
+            self.persist_state();
+            self.update_status_with_missing(&format!("Removed hook `{}`", command.command));
+        }
+        Task::none()
+    }
+}
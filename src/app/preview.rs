@@ -0,0 +1,124 @@
+use iced::Task;
+
+use super::options::{AuthState, apply_auth_headers};
+use super::status::unfilled_path_params;
+use super::{Message, Zagel};
+use crate::model::{Environment, RequestDraft, apply_environment, placeholder_names};
+use crate::net::split_url_credentials;
+use crate::urls::{apply_path_params, encode_unsafe};
+
+/// A request as it would go out, shown by the builder's Preview toggle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPreview {
+    pub request_line: String,
+    pub headers: String,
+    pub body: String,
+    /// Placeholders still left after substitution.
+    pub missing: Vec<String>,
+}
+
+/// Resolves `draft` the way a send does: auth headers first, then the
+/// environment, then URL encoding. Dynamic values such as `{{$uuid}}` get
+/// sample values; the send picks new ones.
+pub fn resolve_preview(
+    draft: &RequestDraft,
+    env: Option<&Environment>,
+    auth: &AuthState,
+) -> ResolvedPreview {
+    let resolve = |input: &str| {
+        env.map_or_else(
+            || input.to_string(),
+            |env| apply_environment(input, &env.vars),
+        )
+    };
+    let (url, _) =
+        split_url_credentials(&resolve(&apply_path_params(&draft.url, &draft.path_params)));
+    let mut headers = apply_auth_headers(&draft.headers, auth);
+    if auth.is_oauth2() {
+        headers.push_str("\nAuthorization: Bearer <token fetched on send>");
+    } else if matches!(auth, AuthState::Digest(_)) {
+        headers.push_str("\nAuthorization: Digest <answered after the server's 401>");
+    } else if matches!(auth, AuthState::OAuth1(_)) {
+        headers.push_str("\nAuthorization: OAuth <signed on send>");
+    } else if let AuthState::Hmac(hmac) = auth {
+        headers.push('\n');
+        headers.push_str(hmac.header_name.trim());
+        headers.push_str(": <signed on send>");
+    }
+    let headers = resolve(headers.trim());
+    let body = resolve(&draft.body);
+    let mut missing = Vec::new();
+    for name in [url.as_str(), headers.as_str(), body.as_str()]
+        .into_iter()
+        .flat_map(placeholder_names)
+    {
+        if !missing.contains(&name) {
+            missing.push(name);
+        }
+    }
+    missing.extend(unfilled_path_params(draft));
+    ResolvedPreview {
+        request_line: format!("{} {}", draft.method, encode_unsafe(url.trim())),
+        headers,
+        body,
+        missing,
+    }
+}
+
+impl Zagel {
+    /// Preview of the request in the builder.
+    pub(super) fn resolved_preview(&self) -> ResolvedPreview {
+        let request = self.workspace.selection();
+        let draft = self.composed_draft();
+        resolve_preview(
+            &draft,
+            self.request_environment(request).as_ref(),
+            &self.effective_auth(request, &draft.headers),
+        )
+    }
+
+    pub(super) fn handle_preview_toggled(&mut self) -> Task<Message> {
+        self.preview_open = !self.preview_open;
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::resolve_preview;
+    use crate::app::options::{AuthState, BearerAuthState};
+    use crate::model::{Environment, EnvironmentScope, Method, RequestDraft};
+
+    #[test]
+    fn previews_resolve_the_environment_and_auth() {
+        let env = Environment {
+            name: "prod".to_string(),
+            vars: BTreeMap::from([
+                ("host".to_string(), "https://api.test".to_string()),
+                ("token".to_string(), "secret".to_string()),
+            ]),
+            scope: EnvironmentScope::Default,
+        };
+        let draft = RequestDraft {
+            method: Method::Post,
+            url: "{{host}}/users/{{id}}?q=a b".to_string(),
+            headers: "Accept: */*".to_string(),
+            body: "{\"at\": \"{{$timestamp}}\"}".to_string(),
+            ..RequestDraft::default()
+        };
+        let auth = AuthState::Bearer(BearerAuthState {
+            token: "{{token}}".to_string(),
+        });
+
+        let preview = resolve_preview(&draft, Some(&env), &auth);
+        assert_eq!(
+            preview.request_line,
+            "POST https://api.test/users/%7B%7Bid%7D%7D?q=a%20b"
+        );
+        assert_eq!(preview.headers, "Accept: */*\nAuthorization: Bearer secret");
+        assert!(!preview.body.contains("{{"));
+        assert_eq!(preview.missing, ["id"]);
+    }
+}
@@ -0,0 +1,91 @@
+use iced::{Task, keyboard};
+
+use super::update::edit_selection_mut;
+use super::view::visible_requests;
+use super::{EditState, EditTarget, Message, Zagel};
+use crate::model::RequestId;
+
+/// Requests from `anchor` to `target` in sidebar order, both included, or
+/// `None` when either is not shown.
+pub fn request_range(
+    visible: &[RequestId],
+    anchor: &RequestId,
+    target: &RequestId,
+) -> Option<Vec<RequestId>> {
+    let from = visible.iter().position(|id| id == anchor)?;
+    let to = visible.iter().position(|id| id == target)?;
+    Some(visible[from.min(to)..=from.max(to)].to_vec())
+}
+
+impl Zagel {
+    pub(super) fn handle_modifiers_changed(
+        &mut self,
+        modifiers: keyboard::Modifiers,
+    ) -> Task<Message> {
+        self.modifiers = modifiers;
+        Task::none()
+    }
+
+    /// Toggles one item, or with Shift held selects every request between
+    /// the last toggled one and `target`.
+    pub(super) fn handle_edit_selection_toggled(&mut self, target: EditTarget) -> Task<Message> {
+        let range = match (&target, &self.selection_anchor) {
+            (EditTarget::Request(id), Some(anchor)) if self.modifiers.shift() => {
+                request_range(&visible_requests(self), anchor, id)
+            }
+            _ => None,
+        };
+        let Some(selection) = edit_selection_mut(&mut self.edit_state) else {
+            return Task::none();
+        };
+        if let Some(range) = range {
+            selection.extend(range.into_iter().map(EditTarget::Request));
+            return Task::none();
+        }
+        if let EditTarget::Request(id) = &target {
+            self.selection_anchor = Some(id.clone());
+        }
+        if !selection.remove(&target) {
+            selection.insert(target);
+        }
+        Task::none()
+    }
+
+    /// Ctrl/Cmd+A in edit mode: selects every request the sidebar shows.
+    pub(super) fn handle_select_all_visible(&mut self) -> Task<Message> {
+        if !matches!(self.edit_state, EditState::On { .. }) {
+            return Task::none();
+        }
+        let visible = visible_requests(self);
+        if let Some(selection) = edit_selection_mut(&mut self.edit_state) {
+            selection.extend(visible.into_iter().map(EditTarget::Request));
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::request_range;
+    use crate::model::RequestId;
+
+    #[test]
+    fn ranges_run_either_way_between_shown_requests() {
+        let id = |index| RequestId::HttpFile {
+            path: PathBuf::from("api.http"),
+            index,
+        };
+        let visible = [id(0), id(1), id(2), id(3)];
+        assert_eq!(
+            request_range(&visible, &id(1), &id(3)),
+            Some(vec![id(1), id(2), id(3)])
+        );
+        assert_eq!(
+            request_range(&visible, &id(2), &id(0)),
+            Some(vec![id(0), id(1), id(2)])
+        );
+        assert_eq!(request_range(&visible, &id(2), &id(9)), None);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::model::{HttpFile, RequestDraft, RequestId};
+
+use super::{EditState, EditTarget, Zagel};
+
+/// Where each request of a file moved to after a rescan rewrote it.
+#[derive(Debug, Default)]
+pub struct Reanchor {
+    files: HashMap<PathBuf, Vec<Option<RequestId>>>,
+}
+
+fn within(path: &Path, moved: Vec<Option<usize>>) -> Vec<Option<RequestId>> {
+    moved
+        .into_iter()
+        .map(|index| {
+            index.map(|index| RequestId::HttpFile {
+                path: path.to_path_buf(),
+                index,
+            })
+        })
+        .collect()
+}
+
+impl Reanchor {
+    pub fn new(old: &HashMap<PathBuf, HttpFile>, new: &HashMap<PathBuf, HttpFile>) -> Self {
+        let files = old
+            .iter()
+            .map(|(path, file)| {
+                let moved = new.get(path).map_or_else(
+                    || vec![None; file.requests.len()],
+                    |next| match_requests(&file.requests, &next.requests),
+                );
+                (path.clone(), within(path, moved))
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Records that request `index` of the file at `path` now lives at
+    /// `moved[index]`, possibly in another file.
+    pub fn set(&mut self, path: &Path, moved: Vec<Option<RequestId>>) {
+        self.files.insert(path.to_path_buf(), moved);
+    }
+
+    /// Requests `a` and `b` of the file at `path`, which holds `len`, traded places.
+    pub fn swapped(path: &Path, len: usize, a: usize, b: usize) -> Self {
+        let moved = (0..len)
+            .map(|index| {
+                Some(if index == a {
+                    b
+                } else if index == b {
+                    a
+                } else {
+                    index
+                })
+            })
+            .collect();
+        let mut reanchor = Self::default();
+        reanchor.set(path, within(path, moved));
+        reanchor
+    }
+
+    /// The id `id` refers to now, or `None` once its request is gone.
+    ///
+    /// Ids the previous scan never saw, such as a request just saved to a
+    /// new file, are passed through unchanged.
+    pub fn map(&self, id: &RequestId) -> Option<RequestId> {
+        let RequestId::HttpFile { path, index } = id;
+        let Some(moved) = self.files.get(path).and_then(|moved| moved.get(*index)) else {
+            return Some(id.clone());
+        };
+        moved.clone()
+    }
+}
+
+/// Matches `old` requests to `new` ones by `# @id` or `# @name`, then
+/// identical content, then a unique title, and only then by position.
+pub fn match_requests(old: &[RequestDraft], new: &[RequestDraft]) -> Vec<Option<usize>> {
+    let mut matched = vec![None; old.len()];
+    let mut claimed = vec![false; new.len()];
+    let passes: [fn(&RequestDraft, &RequestDraft) -> bool; 3] =
+        [same_identity, same_content, same_title];
+    for (pass, matches) in passes.into_iter().enumerate() {
+        for (index, draft) in old.iter().enumerate() {
+            if matched[index].is_some() {
+                continue;
+            }
+            let mut candidates = new
+                .iter()
+                .enumerate()
+                .filter(|(target, next)| !claimed[*target] && matches(draft, next));
+            let found = candidates.next().map(|(target, _)| target);
+            // Titles are often repeated, so only a unique one is trusted.
+            if pass == 2 && candidates.next().is_some() {
+                continue;
+            }
+            if let Some(target) = found {
+                matched[index] = Some(target);
+                claimed[target] = true;
+            }
+        }
+    }
+    for (index, draft) in old.iter().enumerate() {
+        if matched[index].is_none()
+            && draft.name.is_none()
+            && draft.id.is_none()
+            && new
+                .get(index)
+                .is_some_and(|next| next.name.is_none() && next.id.is_none() && !claimed[index])
+        {
+            matched[index] = Some(index);
+            claimed[index] = true;
+        }
+    }
+    matched
+}
+
+fn same_identity(old: &RequestDraft, new: &RequestDraft) -> bool {
+    (old.id.is_some() && old.id == new.id) || (old.name.is_some() && old.name == new.name)
+}
+
+fn same_content(old: &RequestDraft, new: &RequestDraft) -> bool {
+    old.method == new.method
+        && old.url == new.url
+        && old.headers == new.headers
+        && old.body == new.body
+}
+
+fn same_title(old: &RequestDraft, new: &RequestDraft) -> bool {
+    !old.title.trim().is_empty() && old.title == new.title
+}
+
+impl Zagel {
+    /// Points state keyed by request index at the request it meant before the rescan.
+    pub(super) fn reanchor_requests(&mut self, reanchor: &Reanchor) {
+        if let EditState::On { selection } = &mut self.edit_state {
+            *selection = selection
+                .drain()
+                .filter_map(|target| match target {
+                    EditTarget::Request(id) => reanchor.map(&id).map(EditTarget::Request),
+                    collection @ EditTarget::Collection(_) => Some(collection),
+                })
+                .collect();
+        }
+        self.responses = self
+            .responses
+            .drain()
+            .filter_map(|(id, response)| reanchor.map(&id).map(|id| (id, response)))
+            .collect();
+        self.variable_overrides = self
+            .variable_overrides
+            .drain()
+            .filter_map(|(id, overrides)| reanchor.map(&id).map(|id| (id, overrides)))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::match_requests;
+    use crate::model::{Method, RequestDraft};
+
+    fn draft(title: &str, name: Option<&str>, url: &str) -> RequestDraft {
+        RequestDraft {
+            title: title.to_string(),
+            method: Method::Get,
+            url: url.to_string(),
+            name: name.map(str::to_string),
+            ..RequestDraft::default()
+        }
+    }
+
+    #[test]
+    fn requests_follow_their_name_content_or_title_when_one_is_inserted_above() {
+        let old = vec![
+            draft("List users", Some("users"), "/users"),
+            draft("Health", None, "/health"),
+            draft("Orders", None, "/orders"),
+        ];
+        let new = vec![
+            draft("Inserted", None, "/new"),
+            draft("List users", Some("users"), "/v2/users"),
+            draft("Health", None, "/health"),
+            draft("Orders", None, "/orders?page=1"),
+        ];
+
+        assert_eq!(match_requests(&old, &new), vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn a_deleted_named_request_is_not_replaced_by_its_neighbour() {
+        let old = vec![draft("A", Some("a"), "/a"), draft("B", Some("b"), "/b")];
+        let new = vec![draft("B", Some("b"), "/b")];
+
+        assert_eq!(match_requests(&old, &new), vec![None, Some(0)]);
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::model::RequestDraft;
+
+/// Whether the characters of `needle` appear in `haystack` in order,
+/// ignoring case, so `usrget` finds `users/get`.
+pub fn fuzzy_matches(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
+    needle
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|wanted| haystack.any(|found| found == wanted))
+}
+
+/// Whether every word of `query` fuzzy-matches the request's label, URL or
+/// file name.
+pub fn request_matches(query: &str, path: &Path, draft: &RequestDraft) -> bool {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let fields = [draft.label(), &draft.title, &draft.url, &file_name];
+    query
+        .split_whitespace()
+        .all(|term| fields.iter().any(|field| fuzzy_matches(term, field)))
+}
+
+impl Zagel {
+    pub(super) fn handle_sidebar_filter_changed(&mut self, query: String) -> Task<Message> {
+        self.sidebar_filter = query;
+        Task::none()
+    }
+
+    /// Esc closes the context menu first, then clears the sidebar filter.
+    pub(super) fn handle_escape_pressed(&mut self) -> Task<Message> {
+        if self.context_menu.take().is_none() {
+            self.sidebar_filter.clear();
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{fuzzy_matches, request_matches};
+    use crate::model::RequestDraft;
+
+    #[test]
+    fn every_word_must_match_a_field_in_order() {
+        assert!(fuzzy_matches("usrget", "users/GET"));
+        assert!(!fuzzy_matches("getusr", "users/get"));
+        assert!(fuzzy_matches("", "anything"));
+
+        let draft = RequestDraft {
+            name: Some("Create order".to_string()),
+            url: "https://api.test/orders".to_string(),
+            ..RequestDraft::default()
+        };
+        let path = Path::new("shop/checkout.http");
+        assert!(request_matches("crord", path, &draft));
+        assert!(request_matches("chkout api.test", path, &draft));
+        assert!(!request_matches("chkout refund", path, &draft));
+        assert!(!request_matches("shop", path, &draft));
+    }
+}
@@ -0,0 +1,302 @@
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use iced::Task;
+use reqwest::Url;
+
+use super::{Message, Zagel};
+use crate::model::RequestId;
+
+const DEFAULT_FILE_NAME: &str = "response.bin";
+
+/// File name offered by `Content-Disposition`, stripped of any directory part,
+/// else `response` with an extension for the `Content-Type`.
+fn suggested_file_name(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("content-disposition"))
+        .flat_map(|(_, value)| value.split(';'))
+        .find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("filename")
+                .then(|| value.trim().trim_matches('"'))
+        })
+        .and_then(|name| Path::new(name).file_name())
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.is_empty())
+        .map_or_else(
+            || {
+                content_type_extension(headers).map_or_else(
+                    || DEFAULT_FILE_NAME.to_string(),
+                    |extension| format!("response.{extension}"),
+                )
+            },
+            str::to_string,
+        )
+}
+
+fn content_type_extension(headers: &[(String, String)]) -> Option<&'static str> {
+    let (_, value) = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))?;
+    let mime = value.split(';').next()?.trim().to_ascii_lowercase();
+    Some(match mime.as_str() {
+        "application/pdf" => "pdf",
+        "application/json" => "json",
+        "application/zip" => "zip",
+        "application/xml" | "text/xml" => "xml",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "text/html" => "html",
+        "text/csv" => "csv",
+        "text/plain" => "txt",
+        _ => return None,
+    })
+}
+
+/// `value` as a quoted `AppleScript` string literal.
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Puts `path` on the clipboard as a file, the way a file manager's Copy does.
+fn copy_file_to_clipboard(path: &Path) -> Result<(), String> {
+    let uri = Url::from_file_path(path)
+        .map_err(|()| format!("{} is not an absolute path", path.display()))?;
+    let (program, args, input): (&str, Vec<String>, Option<String>) = if cfg!(target_os = "macos") {
+        (
+            "osascript",
+            vec![
+                "-e".to_string(),
+                format!(
+                    "set the clipboard to (POSIX file {})",
+                    applescript_string(&path.display().to_string())
+                ),
+            ],
+            None,
+        )
+    } else if cfg!(target_os = "windows") {
+        (
+            "powershell",
+            vec![
+                "-NoProfile".to_string(),
+                "-Command".to_string(),
+                format!(
+                    "Set-Clipboard -LiteralPath '{}'",
+                    path.display().to_string().replace('\'', "''")
+                ),
+            ],
+            None,
+        )
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        (
+            "wl-copy",
+            vec!["--type".to_string(), "text/uri-list".to_string()],
+            Some(format!("{uri}\r\n")),
+        )
+    } else {
+        (
+            "xclip",
+            ["-selection", "clipboard", "-t", "text/uri-list"]
+                .map(str::to_string)
+                .to_vec(),
+            Some(format!("{uri}\r\n")),
+        )
+    };
+    let mut child = Command::new(program)
+        .args(&args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .spawn()
+        .map_err(|err| format!("Copying files needs {program}: {err}"))?;
+    if let (Some(input), Some(stdin)) = (input, child.stdin.as_mut()) {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|err| format!("Failed to write to {program}: {err}"))?;
+    }
+    drop(child.stdin.take());
+    let status = child
+        .wait()
+        .map_err(|err| format!("{program} failed: {err}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{program} failed with {status}"))
+    }
+}
+
+/// `dir/name`, or `dir/stem-N.ext` for the first N that does not exist yet.
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let path = Path::new(name);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(name);
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| format!(".{ext}"))
+        .unwrap_or_default();
+    (1..=u16::MAX)
+        .map(|n| dir.join(format!("{stem}-{n}{extension}")))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(candidate)
+}
+
+impl Zagel {
+    pub(super) fn handle_load_more_hex(&mut self) -> Task<Message> {
+        let Some(response) = self.response.as_mut() else {
+            return Task::none();
+        };
+        if let Some(bytes) = response.preview.body.as_deref() {
+            response.body.load_more_hex(bytes);
+        }
+        if let Some(cached) = self
+            .workspace
+            .selection()
+            .and_then(|id| self.responses.get_mut(id))
+        {
+            cached.body = response.body.clone();
+        }
+        let page = self.response_pager.current();
+        self.update_response_viewer();
+        if self.response_pager.set_page(page) {
+            self.load_response_page();
+        }
+        Task::none()
+    }
+
+    /// Writes the full body next to the selected request's file.
+    pub(super) fn handle_save_response_body(&mut self) -> Task<Message> {
+        let Some((bytes, name)) = self.response.as_ref().and_then(|response| {
+            let bytes = response.preview.body.clone()?;
+            Some((bytes, suggested_file_name(&response.preview.headers)))
+        }) else {
+            return Task::none();
+        };
+        let dir = self
+            .workspace
+            .selection()
+            .and_then(|RequestId::HttpFile { path, .. }| path.parent().map(Path::to_path_buf))
+            .or_else(|| self.project_root_paths().into_iter().next())
+            .unwrap_or_else(|| PathBuf::from("."));
+        self.update_status_with_missing("Saving response body...");
+        Task::perform(
+            async move {
+                let path = unique_path(&dir, &name);
+                std::fs::write(&path, bytes)
+                    .map(|()| path.clone())
+                    .map_err(|err| format!("Failed to save {}: {err}", path.display()))
+            },
+            Message::ResponseBodySaved,
+        )
+    }
+
+    /// Writes the body to a temporary file and puts that file on the clipboard,
+    /// so it can be pasted into apps that take attachments.
+    pub(super) fn handle_copy_response_body_as_file(&mut self) -> Task<Message> {
+        let Some((bytes, name)) = self.response.as_ref().and_then(|response| {
+            let bytes = response.preview.body.clone()?;
+            Some((bytes, suggested_file_name(&response.preview.headers)))
+        }) else {
+            return Task::none();
+        };
+        self.update_status_with_missing("Copying response body as a file...");
+        Task::perform(
+            async move {
+                let dir = std::env::temp_dir().join("zagel-clipboard");
+                let path = dir.join(&name);
+                std::fs::create_dir_all(&dir)
+                    .and_then(|()| std::fs::write(&path, bytes))
+                    .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+                copy_file_to_clipboard(&path)?;
+                Ok(path)
+            },
+            Message::ResponseBodyCopiedAsFile,
+        )
+    }
+
+    pub(super) fn handle_response_body_copied_as_file(
+        &mut self,
+        result: Result<PathBuf, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(path) => self.update_status_with_missing(&format!(
+                "Copied {} to the clipboard",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            )),
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_response_body_saved(
+        &mut self,
+        result: Result<PathBuf, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(path) => self
+                .update_status_with_missing(&format!("Saved response body to {}", path.display())),
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::{applescript_string, suggested_file_name, unique_path};
+
+    #[test]
+    fn file_name_comes_from_content_disposition() {
+        let headers = |value: &str| vec![("Content-Disposition".to_string(), value.to_string())];
+        assert_eq!(
+            suggested_file_name(&headers("attachment; filename=\"report.pdf\"")),
+            "report.pdf"
+        );
+        assert_eq!(
+            suggested_file_name(&headers("attachment; filename=../../etc/passwd")),
+            "passwd"
+        );
+        assert_eq!(suggested_file_name(&headers("inline")), "response.bin");
+        assert_eq!(suggested_file_name(&[]), "response.bin");
+        assert_eq!(
+            suggested_file_name(&[("content-type".to_string(), "image/JPEG; q=1".to_string())]),
+            "response.jpg"
+        );
+    }
+
+    #[test]
+    fn existing_files_are_not_overwritten() {
+        let dir = tempdir().unwrap();
+        assert_eq!(unique_path(dir.path(), "a.json"), dir.path().join("a.json"));
+        std::fs::write(dir.path().join("a.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("a-1.json"), "{}").unwrap();
+        assert_eq!(
+            unique_path(dir.path(), "a.json"),
+            dir.path().join("a-2.json")
+        );
+    }
+
+    #[test]
+    fn applescript_paths_escape_only_quotes_and_backslashes() {
+        assert_eq!(
+            applescript_string("/tmp/Résumé \"final\"\\.pdf"),
+            "\"/tmp/Résumé \\\"final\\\"\\\\.pdf\""
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::net::open_browser;
+
+/// Response headers whose name or value contains `query`, ignoring case.
+pub fn filter_headers<'a>(
+    headers: &'a [(String, String)],
+    query: &str,
+) -> Vec<&'a (String, String)> {
+    let query = query.trim().to_ascii_lowercase();
+    headers
+        .iter()
+        .filter(|(name, value)| {
+            query.is_empty()
+                || name.to_ascii_lowercase().contains(&query)
+                || value.to_ascii_lowercase().contains(&query)
+        })
+        .collect()
+}
+
+/// The URL a header value points at: the value itself, or the `<...>` part
+/// of a `Link` value.
+pub fn header_url(value: &str) -> Option<&str> {
+    let value = value.trim();
+    let url = value
+        .strip_prefix('<')
+        .and_then(|rest| rest.split_once('>'))
+        .map_or(value, |(url, _)| url);
+    (url.starts_with("http://") || url.starts_with("https://")).then_some(url)
+}
+
+impl Zagel {
+    pub(super) fn handle_response_header_filter_changed(&mut self, query: String) -> Task<Message> {
+        self.response_header_filter = query;
+        Task::none()
+    }
+
+    /// Copies a response header into the request's headers.
+    pub(super) fn handle_response_header_added(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> Task<Message> {
+        self.add_header_row(name, value);
+        self.update_status_with_missing(&format!("Added {name} to the request headers"));
+        Task::none()
+    }
+
+    pub(super) fn handle_response_header_url_opened(&mut self, url: &str) -> Task<Message> {
+        match open_browser(url) {
+            Ok(()) => self.update_status_with_missing(&format!("Opened {url}")),
+            Err(err) => self.update_status_with_missing(&err),
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_headers, header_url};
+
+    #[test]
+    fn headers_filter_by_name_or_value_and_expose_urls() {
+        let headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            (
+                "Link".to_string(),
+                "<https://api.test/users?page=2>; rel=\"next\"".to_string(),
+            ),
+            ("X-Request-Id".to_string(), "abc".to_string()),
+        ];
+
+        let names = |query| {
+            filter_headers(&headers, query)
+                .into_iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("JSON"), vec!["Content-Type"]);
+        assert_eq!(names("x-req"), vec!["X-Request-Id"]);
+        assert_eq!(names(" ").len(), 3);
+
+        assert_eq!(
+            header_url(&headers[1].1),
+            Some("https://api.test/users?page=2")
+        );
+        assert_eq!(header_url(" https://a.test/x "), Some("https://a.test/x"));
+        assert_eq!(header_url("application/json"), None);
+    }
+}
@@ -0,0 +1,421 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use iced::Task;
+
+use super::assertions::AssertionOutcome;
+use super::tags::tagged_requests;
+use super::{Message, Zagel};
+use crate::alerts;
+use crate::model::{Priority, RequestDraft, RequestId, ResponsePreview};
+use crate::net::SendOutcome;
+use crate::plugins::{SendHooks, send_with_plugins};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunPhase {
+    Running { run_id: u64, step: usize },
+    Finished,
+    Stopped,
+}
+
+/// Outcome of one request in a file run.
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    pub label: String,
+    pub status: Option<u16>,
+    pub duration: Option<Duration>,
+    pub error: Option<String>,
+    pub assertions: Vec<AssertionOutcome>,
+    pub priority: Priority,
+}
+
+impl RunResult {
+    pub fn from_preview(
+        label: String,
+        priority: Priority,
+        preview: &ResponsePreview,
+        assertions: Vec<AssertionOutcome>,
+    ) -> Self {
+        Self {
+            label,
+            status: preview.status,
+            duration: preview.duration,
+            error: preview.error.clone(),
+            assertions,
+            priority,
+        }
+    }
+
+    /// Failures of low-priority requests are only logged.
+    pub fn counts_as_failure(&self) -> bool {
+        !self.passed() && self.priority != Priority::Low
+    }
+
+    pub fn escalates(&self) -> bool {
+        !self.passed() && self.priority == Priority::High
+    }
+
+    /// A request passes when it got a response and every assertion held; with
+    /// no assertions the response must be 2xx.
+    pub fn passed(&self) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        if self.assertions.is_empty() {
+            return self
+                .status
+                .is_some_and(|status| (200..300).contains(&status));
+        }
+        self.assertions
+            .iter()
+            .all(|outcome| outcome.failure.is_none())
+    }
+}
+
+/// What a run sends: one `.http` file, or every request with a tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunSource {
+    File(PathBuf),
+    Tag(String),
+}
+
+impl Display for RunSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Tag(tag) => write!(f, "tag {tag}"),
+        }
+    }
+}
+
+/// Sends the requests of a [`RunSource`] in order.
+#[derive(Debug, Clone)]
+pub struct FileRun {
+    pub source: RunSource,
+    pub phase: RunPhase,
+    steps: Vec<(RequestId, RequestDraft)>,
+    results: Vec<RunResult>,
+    last_run_id: u64,
+}
+
+impl FileRun {
+    const fn new(source: RunSource, steps: Vec<(RequestId, RequestDraft)>) -> Self {
+        Self {
+            source,
+            phase: RunPhase::Finished,
+            steps,
+            results: Vec::new(),
+            last_run_id: 0,
+        }
+    }
+
+    pub fn results(&self) -> &[RunResult] {
+        &self.results
+    }
+
+    pub const fn total(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub const fn is_running(&self) -> bool {
+        matches!(self.phase, RunPhase::Running { .. })
+    }
+
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|result| result.passed()).count()
+    }
+
+    fn restart(&mut self) -> u64 {
+        self.last_run_id += 1;
+        self.results.clear();
+        self.phase = RunPhase::Running {
+            run_id: self.last_run_id,
+            step: 0,
+        };
+        self.last_run_id
+    }
+
+    /// Records the outcome of `step` and moves on; `false` once the run is over.
+    fn advance(&mut self, run_id: u64, step: usize, result: RunResult) -> bool {
+        self.results.push(result);
+        let next = step + 1;
+        if next >= self.steps.len() {
+            self.phase = RunPhase::Finished;
+            return false;
+        }
+        self.phase = RunPhase::Running { run_id, step: next };
+        true
+    }
+
+    /// Labels of failed high-priority requests.
+    fn escalated(&self) -> Vec<String> {
+        self.results
+            .iter()
+            .filter(|result| result.escalates())
+            .map(|result| result.label.clone())
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{}/{} requests passed in {}",
+            self.passed(),
+            self.results.len(),
+            self.source
+        )
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_run_file(&mut self, path: PathBuf) -> Task<Message> {
+        if self.file_run.as_ref().is_some_and(FileRun::is_running) {
+            self.update_status_with_missing("A file run is already in progress");
+            return Task::none();
+        }
+        let Some(file) = self.workspace.http_files().get(&path) else {
+            self.update_status_with_missing("File is no longer loaded");
+            return Task::none();
+        };
+        if file.requests.is_empty() {
+            self.update_status_with_missing("File has no requests to run");
+            return Task::none();
+        }
+        let steps = file
+            .requests
+            .iter()
+            .enumerate()
+            .map(|(index, draft)| {
+                let id = RequestId::HttpFile {
+                    path: path.clone(),
+                    index,
+                };
+                (id, draft.clone())
+            })
+            .collect();
+        self.start_run(RunSource::File(path), steps)
+    }
+
+    /// Runs every loaded request tagged `tag`, file by file in sidebar order.
+    pub(super) fn handle_run_tag(&mut self, tag: String) -> Task<Message> {
+        if self.file_run.as_ref().is_some_and(FileRun::is_running) {
+            self.update_status_with_missing("A file run is already in progress");
+            return Task::none();
+        }
+        let steps = tagged_requests(
+            self.workspace.http_files(),
+            self.workspace.http_file_order(),
+            &tag,
+        );
+        if steps.is_empty() {
+            self.update_status_with_missing(&format!("No requests are tagged {tag}"));
+            return Task::none();
+        }
+        self.start_run(RunSource::Tag(tag), steps)
+    }
+
+    fn start_run(
+        &mut self,
+        source: RunSource,
+        steps: Vec<(RequestId, RequestDraft)>,
+    ) -> Task<Message> {
+        let status = format!("Running {source}...");
+        let mut run = FileRun::new(source, steps);
+        run.restart();
+        self.file_run = Some(run);
+        self.update_status_with_missing(&status);
+        self.run_step_task()
+    }
+
+    pub(super) fn handle_run_file_again(&mut self) -> Task<Message> {
+        match self.file_run.as_ref().map(|run| run.source.clone()) {
+            Some(RunSource::File(path)) => self.handle_run_file(path),
+            Some(RunSource::Tag(tag)) => self.handle_run_tag(tag),
+            None => Task::none(),
+        }
+    }
+
+    pub(super) fn handle_run_file_stop(&mut self) -> Task<Message> {
+        if let Some(run) = self.file_run.as_mut()
+            && run.is_running()
+        {
+            run.phase = RunPhase::Stopped;
+            let status = format!("Run stopped; {}", run.summary());
+            self.update_status_with_missing(&status);
+        }
+        Task::none()
+    }
+
+    pub(super) fn handle_run_step_ready(
+        &mut self,
+        run_id: u64,
+        result: Result<SendOutcome, String>,
+    ) -> Task<Message> {
+        let Some(RunPhase::Running {
+            run_id: active_run,
+            step,
+        }) = self.file_run.as_ref().map(|run| run.phase)
+        else {
+            return Task::none();
+        };
+        if active_run != run_id {
+            return Task::none();
+        }
+        let Some(run) = self.file_run.as_ref() else {
+            return Task::none();
+        };
+        let (id, draft) = &run.steps[step];
+        let id = id.clone();
+        let label = draft.step_label();
+        let priority = draft.options.priority;
+
+        let set_cookies = result
+            .as_ref()
+            .map_or(&[][..], |outcome| &outcome.response.headers);
+        self.cookies
+            .store(Some(&id), set_cookies, SystemTime::now());
+        let preview = match result {
+            Ok(outcome) => {
+                if outcome.oauth2_cache.is_some() {
+                    self.oauth2_token_cache = outcome.oauth2_cache;
+                }
+                outcome.response
+            }
+            Err(err) => ResponsePreview::error(err),
+        };
+        self.apply_captures(Some(&id), &preview);
+        self.note_login_response(Some(&id), &preview);
+        let assertions = self.apply_assertions(Some(&id), &preview);
+        self.record_history(Some(&id), &preview, &assertions);
+        let result = RunResult::from_preview(label, priority, &preview, assertions);
+
+        let Some(run) = self.file_run.as_mut() else {
+            return Task::none();
+        };
+        if run.advance(run_id, step, result) {
+            return self.run_step_task();
+        }
+        let mut status = format!("Run finished; {}", run.summary());
+        if let Some((title, body)) =
+            alerts::failure_summary(&run.source.to_string(), &run.escalated())
+            && let Err(err) = alerts::notify(&title, &body)
+        {
+            status = format!("{status}; failed to show a notification: {err}");
+        }
+        self.update_status_with_missing(&status);
+        Task::none()
+    }
+
+    /// Sends the current step the way `Message::Send` would, so captures
+    /// from earlier requests feed the later ones.
+    fn run_step_task(&mut self) -> Task<Message> {
+        let Some(run) = self.file_run.as_ref() else {
+            return Task::none();
+        };
+        let RunPhase::Running { run_id, step } = run.phase else {
+            return Task::none();
+        };
+        let (id, draft) = &run.steps[step];
+        let id = Some(id.clone());
+        let mut draft = draft.clone();
+        draft.resolve_dynamic();
+        let env = self.request_environment(id.as_ref());
+        let auth = self.effective_auth(id.as_ref(), &draft.headers);
+        self.idempotency.apply(&mut draft);
+        self.trace_context.apply(&mut draft);
+        self.cookies
+            .apply(&mut draft, env.as_ref(), id.clone(), SystemTime::now());
+        self.begin_history(id.clone(), &draft, env.as_ref());
+        self.arm_captures(id.clone(), &draft);
+        self.arm_assertions(id, &draft);
+        let client = match self.http_client(&draft) {
+            Ok(client) => client,
+            Err(err) => {
+                return Task::done(Message::RunStepReady {
+                    run_id,
+                    result: Err(err),
+                });
+            }
+        };
+        Task::perform(
+            send_with_plugins(
+                SendHooks::from_state(&self.state),
+                client,
+                draft,
+                env,
+                auth,
+                self.oauth2_token_cache.clone(),
+                None,
+            ),
+            move |result| Message::RunStepReady { run_id, result },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{FileRun, RunPhase, RunResult, RunSource};
+    use crate::app::assertions::AssertionOutcome;
+    use crate::model::{Priority, RequestDraft, RequestId};
+
+    fn result(status: Option<u16>, failure: Option<&str>) -> RunResult {
+        RunResult {
+            label: "req".to_string(),
+            status,
+            duration: None,
+            error: None,
+            assertions: failure
+                .map(|failure| AssertionOutcome {
+                    label: "status == 200".to_string(),
+                    failure: (!failure.is_empty()).then(|| failure.to_string()),
+                })
+                .into_iter()
+                .collect(),
+            priority: Priority::Normal,
+        }
+    }
+
+    #[test]
+    fn requests_pass_on_assertions_or_a_2xx_status() {
+        assert!(result(Some(204), None).passed());
+        assert!(!result(Some(500), None).passed());
+        assert!(result(Some(500), Some("")).passed());
+        assert!(!result(Some(200), Some("got 200")).passed());
+        let mut failed = result(None, None);
+        failed.error = Some("timed out".to_string());
+        assert!(!failed.passed());
+        assert!(failed.counts_as_failure() && !failed.escalates());
+        failed.priority = Priority::Low;
+        assert!(!failed.counts_as_failure());
+        failed.priority = Priority::High;
+        assert!(failed.escalates());
+    }
+
+    #[test]
+    fn runs_step_through_every_request_then_finish() {
+        let step = |index| {
+            let id = RequestId::HttpFile {
+                path: PathBuf::from("api.http"),
+                index,
+            };
+            (id, RequestDraft::default())
+        };
+        let mut run = FileRun::new(
+            RunSource::File(PathBuf::from("api.http")),
+            vec![step(0), step(1)],
+        );
+        let run_id = run.restart();
+        assert!(run.advance(run_id, 0, result(Some(200), None)));
+        assert_eq!(run.phase, RunPhase::Running { run_id, step: 1 });
+        assert!(!run.advance(run_id, 1, result(Some(404), None)));
+        assert_eq!(run.phase, RunPhase::Finished);
+        assert_eq!(run.passed(), 1);
+        assert_eq!(run.summary(), "1/2 requests passed in api.http");
+
+        let rerun = run.restart();
+        assert_ne!(rerun, run_id);
+        assert!(run.results().is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+use std::ops::Range;
+
+use iced::advanced::text::highlighter::{self, Highlighter};
+use iced::widget::text_editor::{Cursor, Position};
+use iced::{Font, Task, Theme};
+
+use super::pager::Pager;
+use super::viewer::mark_whitespace;
+use super::{Message, Zagel};
+
+/// Matches past this are not counted; the counter shows `N+`.
+const MAX_MATCHES: usize = 10_000;
+
+/// The current match as a line and byte column within the displayed page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub line: usize,
+    pub start: usize,
+}
+
+/// Case-insensitive search over the whole response text; matches are byte ranges
+/// so they survive paging.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseSearch {
+    pub query: String,
+    matches: Vec<Range<usize>>,
+    current: usize,
+}
+
+impl ResponseSearch {
+    pub fn refresh(&mut self, text: &str) {
+        self.matches = find_matches(text, &self.query);
+        if self.current >= self.matches.len() {
+            self.current = 0;
+        }
+    }
+
+    pub fn current(&self) -> Option<Range<usize>> {
+        self.matches.get(self.current).cloned()
+    }
+
+    pub fn step(&mut self, forward: bool) -> Option<Range<usize>> {
+        let len = self.matches.len();
+        if len == 0 {
+            return None;
+        }
+        self.current = if forward {
+            (self.current + 1) % len
+        } else {
+            (self.current + len - 1) % len
+        };
+        self.current()
+    }
+
+    pub fn counter(&self) -> String {
+        match self.matches.len() {
+            0 => "0/0".to_string(),
+            MAX_MATCHES => format!("{}/{MAX_MATCHES}+", self.current + 1),
+            len => format!("{}/{len}", self.current + 1),
+        }
+    }
+
+    /// `whitespace` says the viewer shows whitespace markers, so positions and
+    /// the query are mapped to the marked text.
+    pub fn highlight_settings(&self, pager: &Pager, whitespace: bool) -> SearchSettings {
+        let current = self
+            .current()
+            .filter(|found| pager.page_of(found.start) == pager.current())
+            .map(|found| {
+                let (line, start) = pager.display_position(found.start, whitespace);
+                SearchMatch { line, start }
+            });
+        SearchSettings {
+            query: if whitespace {
+                mark_whitespace(&self.query)
+            } else {
+                self.query.clone()
+            },
+            current,
+        }
+    }
+}
+
+/// ASCII case folding keeps byte offsets valid for the editor.
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    match_ranges(text, query).take(MAX_MATCHES).collect()
+}
+
+fn match_ranges<'a>(line: &'a str, query: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+    let haystack = line.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+    let mut from = 0;
+    std::iter::from_fn(move || {
+        if needle.is_empty() {
+            return None;
+        }
+        let start = from + haystack.get(from..)?.find(&needle)?;
+        from = start + needle.len();
+        Some(start..from)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchSettings {
+    query: String,
+    current: Option<SearchMatch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchHighlight {
+    Match,
+    Current,
+}
+
+/// Marks query matches in the response viewer; replaces syntax colouring while a
+/// search is active.
+pub struct SearchHighlighter {
+    settings: SearchSettings,
+    line: usize,
+}
+
+impl Highlighter for SearchHighlighter {
+    type Settings = SearchSettings;
+    type Highlight = SearchHighlight;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, SearchHighlight)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            settings: settings.clone(),
+            line: 0,
+        }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        self.settings = new_settings.clone();
+        self.line = 0;
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.line = self.line.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        let index = self.line;
+        self.line += 1;
+        let current = self.settings.current;
+        match_ranges(line, &self.settings.query)
+            .map(|range| {
+                let is_current =
+                    current.is_some_and(|found| found.line == index && found.start == range.start);
+                let highlight = if is_current {
+                    SearchHighlight::Current
+                } else {
+                    SearchHighlight::Match
+                };
+                (range, highlight)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.line
+    }
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // signature required by `highlight_with`
+pub fn search_format(highlight: &SearchHighlight, theme: &Theme) -> highlighter::Format<Font> {
+    let palette = theme.extended_palette();
+    let color = match highlight {
+        SearchHighlight::Match => palette.primary.strong.color,
+        SearchHighlight::Current => palette.danger.base.color,
+    };
+    highlighter::Format {
+        color: Some(color),
+        font: None,
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_response_search_changed(&mut self, query: String) -> Task<Message> {
+        self.response_search.query = query;
+        self.refresh_response_search();
+        self.select_search_match(self.response_search.current());
+        Task::none()
+    }
+
+    pub(super) fn handle_response_search_step(&mut self, forward: bool) -> Task<Message> {
+        let found = self.response_search.step(forward);
+        self.select_search_match(found);
+        Task::none()
+    }
+
+    pub(super) fn refresh_response_search(&mut self) {
+        self.response_search.refresh(self.response_pager.text());
+    }
+
+    /// Turns to the page holding `found` and selects it in the viewer.
+    fn select_search_match(&mut self, found: Option<Range<usize>>) {
+        let Some(found) = found else {
+            return;
+        };
+        if self
+            .response_pager
+            .set_page(self.response_pager.page_of(found.start))
+        {
+            self.load_response_page();
+        }
+        let whitespace = self.marks_whitespace();
+        let (line, start) = self
+            .response_pager
+            .display_position(found.start, whitespace);
+        let (end_line, end) = self.response_pager.display_position(found.end, whitespace);
+        self.response_viewer.move_to(Cursor {
+            position: Position {
+                line: end_line,
+                column: end,
+            },
+            selection: Some(Position {
+                line,
+                column: start,
+            }),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResponseSearch, find_matches};
+    use crate::app::pager::Pager;
+
+    #[test]
+    fn matches_are_case_insensitive_byte_ranges() {
+        let matches = find_matches("{\n  \"Name\": \"name\"\n}", "name");
+        assert_eq!(matches, vec![5..9, 13..17]);
+        assert!(find_matches("anything", "").is_empty());
+    }
+
+    #[test]
+    fn stepping_wraps_around_and_updates_counter() {
+        let mut search = ResponseSearch {
+            query: "a".to_string(),
+            ..ResponseSearch::default()
+        };
+        search.refresh("a\nb\na");
+        assert_eq!(search.counter(), "1/2");
+
+        assert_eq!(search.step(true), Some(4..5));
+        assert_eq!(search.step(true), Some(0..1));
+        assert_eq!(search.step(false), Some(4..5));
+        assert_eq!(search.counter(), "2/2");
+
+        let settings = search.highlight_settings(&Pager::new("a\nb\na".to_string()), false);
+        assert_eq!(
+            settings.current.map(|found| (found.line, found.start)),
+            Some((2, 0))
+        );
+    }
+}
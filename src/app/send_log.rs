@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+use std::time::{Duration, SystemTime};
+
+use crate::model::{Environment, RequestDraft, RequestId, ResponsePreview, placeholder_names};
+use crate::state::{HookCommand, PluginConfig, PluginHook};
+
+use super::options::AuthState;
+
+/// One step zagel took for a send, shown in the response Log tab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub at: SystemTime,
+    pub message: String,
+}
+
+/// Steps of the send in flight; they move onto the response when it arrives.
+#[derive(Debug, Clone)]
+pub struct SendLog {
+    pub id: Option<RequestId>,
+    pub entries: Vec<LogEntry>,
+    /// Resolved URL the request went to, to spot redirects.
+    sent_url: String,
+}
+
+impl SendLog {
+    pub const fn new(id: Option<RequestId>) -> Self {
+        Self {
+            id,
+            entries: Vec::new(),
+            sent_url: String::new(),
+        }
+    }
+
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.entries.push(LogEntry {
+            at: SystemTime::now(),
+            message: message.into(),
+        });
+    }
+
+    /// Names the variables substituted from `env` and the ones left
+    /// unresolved; values stay out of the log since they are often secrets.
+    pub fn substitution(&mut self, draft: &RequestDraft, env: Option<&Environment>) {
+        if let Some(env) = env {
+            self.push(format!(
+                "Environment {} with {} variables",
+                env.name,
+                env.vars.len()
+            ));
+        }
+        let names = [&draft.url, &draft.headers, &draft.body]
+            .into_iter()
+            .flat_map(|text| placeholder_names(text))
+            .collect::<BTreeSet<_>>();
+        let (resolved, unresolved): (Vec<_>, Vec<_>) = names
+            .into_iter()
+            .partition(|name| env.is_some_and(|env| env.vars.contains_key(name)));
+        if !resolved.is_empty() {
+            self.push(format!("Substituted {}", braced(&resolved)));
+        }
+        if !unresolved.is_empty() {
+            self.push(format!("Left unresolved {}", braced(&unresolved)));
+        }
+    }
+
+    /// Records the headers a step added to the request.
+    pub fn headers_added(&mut self, step: &str, before: &str, after: &str) {
+        let existing = header_names(before);
+        let added = header_names(after)
+            .into_iter()
+            .filter(|name| !existing.contains(name))
+            .collect::<Vec<_>>();
+        if !added.is_empty() {
+            self.push(format!("{step}: added {}", added.join(", ")));
+        }
+    }
+
+    pub fn auth(&mut self, auth: &AuthState) {
+        let step = match auth {
+            AuthState::None => return,
+            AuthState::Bearer(_) => "Auth: added a Bearer Authorization header".to_string(),
+            AuthState::Basic(_) => "Auth: added a Basic Authorization header".to_string(),
+            AuthState::Digest(_) => {
+                "Auth: Digest Authorization answers the server's 401 challenge".to_string()
+            }
+            AuthState::ApiKey(api_key) => {
+                format!("Auth: added API key header {}", api_key.header_name.trim())
+            }
+            AuthState::OAuth1(oauth) => format!(
+                "Auth: OAuth 1.0a {} signature goes into Authorization",
+                oauth.signature_method
+            ),
+            AuthState::Hmac(hmac) => format!(
+                "Auth: {} signature goes into {}",
+                hmac.algorithm,
+                hmac.header_name.trim()
+            ),
+            AuthState::OAuth2ClientCredentials(_) => {
+                "Auth: OAuth2 client credentials token goes into Authorization".to_string()
+            }
+            AuthState::OAuth2AuthorizationCode(_) => {
+                "Auth: OAuth2 authorization code token goes into Authorization".to_string()
+            }
+        };
+        self.push(step);
+    }
+
+    pub fn plugins(&mut self, plugins: &[PluginConfig], commands: &[HookCommand]) {
+        for hook in PluginHook::SEND {
+            let names = plugins
+                .iter()
+                .filter(|plugin| plugin.enabled && plugin.hooks.contains(&hook))
+                .map(|plugin| plugin.name.as_str())
+                .collect::<Vec<_>>();
+            if !names.is_empty() {
+                self.push(format!("Plugins ({hook}): {}", names.join(", ")));
+            }
+            let commands = commands
+                .iter()
+                .filter(|command| command.enabled && command.hook == hook)
+                .map(|command| command.command.as_str())
+                .collect::<Vec<_>>();
+            if !commands.is_empty() {
+                self.push(format!("Hook commands ({hook}): {}", commands.join("; ")));
+            }
+        }
+    }
+
+    pub fn sending(&mut self, method: &str, url: String) {
+        self.push(format!("Sending {method} {url}"));
+        self.sent_url = url;
+    }
+
+    pub fn response(&mut self, preview: &ResponsePreview) {
+        if let Some(url) = &preview.url
+            && url.trim_end_matches('/') != self.sent_url.trim().trim_end_matches('/')
+        {
+            self.push(format!("Followed redirects to {url}"));
+        }
+        match (preview.status, &preview.error) {
+            (Some(status), error) => {
+                let version = preview.version.as_deref().unwrap_or("HTTP");
+                let duration = preview.duration.unwrap_or_default().as_millis();
+                self.push(format!("Received {version} {status} in {duration} ms"));
+                if let Some(error) = error {
+                    self.push(format!("Body incomplete: {error}"));
+                }
+            }
+            (None, Some(error)) => self.push(format!("Failed: {error}")),
+            (None, None) => self.push("No response"),
+        }
+    }
+}
+
+fn braced(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("{{{{{name}}}}}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn header_names(headers: &str) -> Vec<String> {
+    headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, _)| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Lines of the Log tab with the time since the first step, e.g. `+12 ms`.
+pub fn format_entries(entries: &[LogEntry]) -> Vec<String> {
+    let Some(first) = entries.first() else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            let offset = entry
+                .at
+                .duration_since(first.at)
+                .unwrap_or(Duration::ZERO)
+                .as_millis();
+            format!("+{offset} ms  {}", entry.message)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::{Duration, SystemTime};
+
+    use super::{LogEntry, SendLog, format_entries};
+    use crate::model::{Environment, EnvironmentScope, RequestDraft};
+
+    #[test]
+    fn steps_name_variables_and_added_headers() {
+        let env = Environment {
+            name: "dev".to_string(),
+            vars: BTreeMap::from([("base".to_string(), "https://api.test".to_string())]),
+            scope: EnvironmentScope::Default,
+        };
+        let draft = RequestDraft {
+            url: "{{base}}/users".to_string(),
+            headers: "Authorization: Bearer {{token}}".to_string(),
+            ..RequestDraft::default()
+        };
+        let mut log = SendLog::new(None);
+        log.substitution(&draft, Some(&env));
+        log.headers_added(
+            "Trace context",
+            "Accept: */*",
+            "Accept: */*\ntraceparent: 00-abc-def-01",
+        );
+        let messages = log
+            .entries
+            .iter()
+            .map(|entry| entry.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "Environment dev with 1 variables",
+                "Substituted {{base}}",
+                "Left unresolved {{token}}",
+                "Trace context: added traceparent",
+            ]
+        );
+    }
+
+    #[test]
+    fn entries_are_timed_from_the_first_step() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+        let entries = [
+            LogEntry {
+                at: start,
+                message: "Sending GET https://api.test".to_string(),
+            },
+            LogEntry {
+                at: start + Duration::from_millis(42),
+                message: "Received HTTP/1.1 200 in 42 ms".to_string(),
+            },
+        ];
+        assert_eq!(
+            format_entries(&entries),
+            [
+                "+0 ms  Sending GET https://api.test",
+                "+42 ms  Received HTTP/1.1 200 in 42 ms"
+            ]
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::model::Environment;
+use crate::net::OAuth2TokenCacheEntry;
+use crate::state::SavedSession;
+
+/// Layers session variables over the environment's, so they win on conflicts.
+pub(super) fn with_session_variables(
+    mut env: Environment,
+    variables: &BTreeMap<String, String>,
+) -> Environment {
+    env.vars.extend(
+        variables
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+    env
+}
+
+impl Zagel {
+    fn capture_session(&self) -> SavedSession {
+        SavedSession {
+            variables: self.session_variables.clone(),
+            cookies: self.cookies.snapshot(SystemTime::now()),
+            oauth2_token: self
+                .oauth2_token_cache
+                .as_ref()
+                .map(OAuth2TokenCacheEntry::to_stored),
+        }
+    }
+
+    fn load_session(&mut self, session: &SavedSession) {
+        self.session_variables.clone_from(&session.variables);
+        self.cookies.restore(&session.cookies, SystemTime::now());
+        self.oauth2_token_cache = session
+            .oauth2_token
+            .as_ref()
+            .and_then(OAuth2TokenCacheEntry::from_stored);
+    }
+
+    /// Reloads the session that was active when the app last saved its state.
+    pub(super) fn restore_active_session(&mut self) {
+        if let Some(session) = self
+            .state
+            .active_session
+            .as_ref()
+            .and_then(|name| self.state.sessions.get(name))
+            .cloned()
+        {
+            self.load_session(&session);
+        }
+    }
+
+    pub(super) fn handle_session_selected(&mut self, name: &str) -> Task<Message> {
+        let Some(session) = self.state.sessions.get(name).cloned() else {
+            return Task::none();
+        };
+        // Keep what the outgoing session picked up since it was last saved.
+        if let Some(active) = self.state.active_session.clone() {
+            let captured = self.capture_session();
+            self.state.sessions.insert(active, captured);
+        }
+        self.load_session(&session);
+        self.state.active_session = Some(name.to_string());
+        self.persist_state();
+        self.update_status_with_missing(&format!("Switched to session {name}"));
+        Task::none()
+    }
+
+    pub(super) fn handle_session_name_changed(&mut self, name: String) -> Task<Message> {
+        self.session_name_input = name;
+        Task::none()
+    }
+
+    pub(super) fn handle_save_session(&mut self) -> Task<Message> {
+        let typed = self.session_name_input.trim();
+        let Some(name) = (!typed.is_empty())
+            .then(|| typed.to_string())
+            .or_else(|| self.state.active_session.clone())
+        else {
+            self.update_status_with_missing("Name the session before saving it");
+            return Task::none();
+        };
+        let captured = self.capture_session();
+        self.state.sessions.insert(name.clone(), captured);
+        self.state.active_session = Some(name.clone());
+        self.session_name_input.clear();
+        self.persist_state();
+        self.update_status_with_missing(&format!("Saved session {name}"));
+        Task::none()
+    }
+
+    /// Forgets the active session; the current cookies and token stay in use.
+    pub(super) fn handle_delete_session(&mut self) -> Task<Message> {
+        if let Some(name) = self.state.active_session.take() {
+            self.state.sessions.remove(&name);
+            self.persist_state();
+            self.update_status_with_missing(&format!("Deleted session {name}"));
+        }
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::{Duration, SystemTime};
+
+    use super::with_session_variables;
+    use crate::app::cookies::CookieJars;
+    use crate::model::{Environment, EnvironmentScope, RequestDraft, RequestId};
+
+    #[test]
+    fn session_variables_override_environment() {
+        let env = Environment {
+            name: "dev".to_string(),
+            vars: BTreeMap::from([
+                ("host".to_string(), "dev.example.com".to_string()),
+                ("token".to_string(), "env-token".to_string()),
+            ]),
+            scope: EnvironmentScope::Default,
+        };
+        let variables = BTreeMap::from([("token".to_string(), "admin-token".to_string())]);
+
+        let merged = with_session_variables(env, &variables);
+        assert_eq!(merged.vars["host"], "dev.example.com");
+        assert_eq!(merged.vars["token"], "admin-token");
+    }
+
+    #[test]
+    fn cookies_survive_a_snapshot_round_trip() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let id = Some(RequestId::HttpFile {
+            path: "auth.http".into(),
+            index: 0,
+        });
+        let mut jars = CookieJars::default();
+        let mut login = RequestDraft {
+            url: "https://example.com/login".to_string(),
+            ..Default::default()
+        };
+        jars.apply(&mut login, None, id.clone(), now);
+        let set_cookie = [
+            ("set-cookie".to_string(), "sid=admin; Path=/".to_string()),
+            ("set-cookie".to_string(), "short=1; Max-Age=10".to_string()),
+        ];
+        jars.store(id.as_ref(), &set_cookie, now);
+
+        let saved = jars.snapshot(now);
+        let mut restored = CookieJars::default();
+        restored.restore(&saved, now + Duration::from_secs(90));
+        assert_eq!(restored.count(None), 1);
+
+        let mut next = RequestDraft {
+            url: "https://example.com/me".to_string(),
+            headers: String::new(),
+            ..Default::default()
+        };
+        restored.apply(&mut next, None, id, now);
+        assert_eq!(next.headers, "Cookie: sid=admin");
+    }
+}
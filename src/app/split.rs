@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use iced::Task;
+
+use crate::model::{HttpFile, RequestDraft, RequestId};
+use crate::parser::write_http_file_with_header;
+
+use super::{EditState, EditTarget, Message, Zagel};
+
+const ROOT_GROUP: &str = "root";
+
+/// One output file of a split: the requests it receives, keyed by their
+/// index in the original file.
+#[derive(Debug, Clone)]
+pub(super) struct SplitTarget {
+    pub(super) path: PathBuf,
+    pub(super) requests: Vec<(usize, RequestDraft)>,
+}
+
+/// Groups requests by the first path segment of their URL, in order of first
+/// appearance. Returns `None` when every request shares one prefix.
+pub(super) fn plan_split(file: &HttpFile) -> Option<Vec<SplitTarget>> {
+    let mut groups: Vec<(String, Vec<(usize, RequestDraft)>)> = Vec::new();
+    for (index, request) in file.requests.iter().enumerate() {
+        let prefix = path_prefix(&request.url);
+        match groups.iter_mut().find(|(name, _)| *name == prefix) {
+            Some((_, requests)) => requests.push((index, request.clone())),
+            None => groups.push((prefix, vec![(index, request.clone())])),
+        }
+    }
+    if groups.len() < 2 {
+        return None;
+    }
+
+    let stem = file
+        .path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("requests");
+    let dir = file.path.parent().unwrap_or_else(|| Path::new("."));
+    let mut taken = HashSet::new();
+    Some(
+        groups
+            .into_iter()
+            .map(|(prefix, requests)| SplitTarget {
+                path: unique_path(dir, &format!("{stem}-{prefix}"), &mut taken),
+                requests,
+            })
+            .collect(),
+    )
+}
+
+pub(super) fn can_split(file: &HttpFile) -> bool {
+    let mut prefixes = file
+        .requests
+        .iter()
+        .map(|request| path_prefix(&request.url));
+    let first = prefixes.next();
+    prefixes.any(|prefix| Some(prefix) != first)
+}
+
+/// `https://api/users/1`, `{{base}}/users/1` and `/users` all map to `users`.
+fn path_prefix(url: &str) -> String {
+    let mut rest = url.trim();
+    if let Some((_, after_scheme)) = rest.split_once("://") {
+        rest = after_scheme
+            .find('/')
+            .map_or("", |idx| &after_scheme[idx..]);
+    } else if rest.starts_with("{{")
+        && let Some(end) = rest.find("}}")
+    {
+        rest = &rest[end + 2..];
+    }
+    let segment = rest
+        .trim_start_matches('/')
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let slug = segment
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        ROOT_GROUP.to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn unique_path(dir: &Path, base: &str, taken: &mut HashSet<PathBuf>) -> PathBuf {
+    let mut candidate = dir.join(format!("{base}.http"));
+    let mut counter = 2;
+    while candidate.exists() || taken.contains(&candidate) {
+        candidate = dir.join(format!("{base}-{counter}.http"));
+        counter += 1;
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
+
+fn remap_id(id: &RequestId, source: &Path, targets: &[SplitTarget]) -> Option<RequestId> {
+    let RequestId::HttpFile { path, index } = id;
+    if path != source {
+        return None;
+    }
+    targets.iter().find_map(|target| {
+        target
+            .requests
+            .iter()
+            .position(|(old, _)| old == index)
+            .map(|new_index| RequestId::HttpFile {
+                path: target.path.clone(),
+                index: new_index,
+            })
+    })
+}
+
+impl Zagel {
+    pub(super) fn handle_split_file(&mut self, path: &Path) -> Task<Message> {
+        let Some(file) = self.workspace.http_files().get(path) else {
+            return Task::none();
+        };
+        let Some(targets) = plan_split(file) else {
+            self.update_status_with_missing("All requests share one path prefix; nothing to split");
+            return Task::none();
+        };
+
+        let variables = file.variables.clone();
+        let auth = file.auth.clone();
+        let mut written = Vec::new();
+        for target in &targets {
+            let drafts = target
+                .requests
+                .iter()
+                .map(|(_, draft)| draft.clone())
+                .collect::<Vec<_>>();
+            // Every part keeps the file variables and auth its requests may use.
+            if let Err(err) =
+                write_http_file_with_header(&target.path, &variables, auth.as_ref(), &drafts)
+            {
+                for created in &written {
+                    let _ = fs::remove_file(created);
+                }
+                self.update_status_with_missing(&format!(
+                    "Failed to split {}: {err}",
+                    path.display()
+                ));
+                return Task::none();
+            }
+            written.push(target.path.clone());
+        }
+        if let Err(err) = fs::remove_file(path) {
+            self.update_status_with_missing(&format!(
+                "Split into {} files but failed to remove {}: {err}",
+                targets.len(),
+                path.display()
+            ));
+        } else {
+            self.update_status_with_missing(&format!(
+                "Split {} into {} files",
+                path.display(),
+                targets.len()
+            ));
+        }
+
+        let remapped_selection = self
+            .workspace
+            .selection()
+            .and_then(|id| remap_id(id, path, &targets));
+        if let Some(mut workspace) = self.workspace.configured_state() {
+            workspace.http_files_mut().remove(path);
+            for target in &targets {
+                workspace.http_files_mut().insert(
+                    target.path.clone(),
+                    HttpFile {
+                        path: target.path.clone(),
+                        variables: variables.clone(),
+                        auth: auth.clone(),
+                        requests: target
+                            .requests
+                            .iter()
+                            .map(|(_, draft)| draft.clone())
+                            .collect(),
+                    },
+                );
+            }
+            let order = workspace.http_file_order_mut();
+            let position = order.iter().position(|entry| entry == path);
+            order.retain(|entry| entry != path);
+            let at = position.unwrap_or(order.len());
+            order.splice(at..at, targets.iter().map(|target| target.path.clone()));
+            if let Some(id) = remapped_selection {
+                workspace.set_selection(Some(id));
+            }
+        }
+        if let Some(RequestId::HttpFile { path, .. }) = self.workspace.selection() {
+            self.save_path = path.display().to_string();
+        }
+
+        self.responses = std::mem::take(&mut self.responses)
+            .into_iter()
+            .filter_map(|(id, response)| {
+                if let Some(new_id) = remap_id(&id, path, &targets) {
+                    return Some((new_id, response));
+                }
+                let RequestId::HttpFile { path: id_path, .. } = &id;
+                (id_path != path).then_some((id, response))
+            })
+            .collect();
+        if let EditState::On { selection } = &mut self.edit_state {
+            selection.retain(|target| match target {
+                EditTarget::Collection(p)
+                | EditTarget::Request(RequestId::HttpFile { path: p, .. }) => p != path,
+            });
+        }
+        self.persist_state();
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{can_split, path_prefix, plan_split, remap_id};
+    use crate::model::{HttpFile, RequestDraft, RequestId};
+
+    fn file(urls: &[&str]) -> HttpFile {
+        HttpFile {
+            path: PathBuf::from("/nonexistent/api.http"),
+            variables: Vec::new(),
+            auth: None,
+            requests: urls
+                .iter()
+                .map(|url| RequestDraft {
+                    url: (*url).to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn prefix_ignores_scheme_host_and_base_variable() {
+        assert_eq!(path_prefix("https://api.example.com/users/1"), "users");
+        assert_eq!(path_prefix("{{baseUrl}}/Orders?limit=1"), "orders");
+        assert_eq!(path_prefix("/health"), "health");
+        assert_eq!(path_prefix("https://api.example.com"), "root");
+    }
+
+    #[test]
+    fn split_groups_by_prefix_and_keeps_order() {
+        let file = file(&["{{base}}/users", "{{base}}/orders/1", "{{base}}/users/2"]);
+
+        let targets = plan_split(&file).expect("two prefixes should split");
+
+        assert_eq!(targets.len(), 2);
+        assert_eq!(
+            targets[0].path,
+            PathBuf::from("/nonexistent/api-users.http")
+        );
+        assert_eq!(
+            targets[0]
+                .requests
+                .iter()
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+        assert_eq!(
+            remap_id(
+                &RequestId::HttpFile {
+                    path: file.path.clone(),
+                    index: 2,
+                },
+                &file.path,
+                &targets,
+            ),
+            Some(RequestId::HttpFile {
+                path: PathBuf::from("/nonexistent/api-users.http"),
+                index: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn single_prefix_is_not_splittable() {
+        let file = file(&["/users", "/users/1"]);
+        assert!(!can_split(&file));
+        assert!(plan_split(&file).is_none());
+    }
+}
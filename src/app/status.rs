@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use crate::model::{Environment, EnvironmentScope, RequestDraft};
+use crate::model::{Environment, EnvironmentScope, RequestDraft, placeholder_names};
+use crate::urls::path_param_names;
 
 pub(super) fn default_environment() -> Environment {
     Environment {
@@ -42,12 +43,12 @@ fn missing_env_vars(
 ) -> Vec<String> {
     let mut placeholders = BTreeSet::new();
     for text in [&draft.url, &draft.headers, &draft.body] {
-        for name in collect_placeholders(text) {
+        for name in placeholder_names(text) {
             placeholders.insert(name);
         }
     }
     for text in extra_inputs {
-        for name in collect_placeholders(text) {
+        for name in placeholder_names(text) {
             placeholders.insert(name);
         }
     }
@@ -56,27 +57,20 @@ fn missing_env_vars(
     placeholders
         .into_iter()
         .filter(|name| env_vars.is_none_or(|vars| !vars.contains_key(name)))
+        .chain(unfilled_path_params(draft))
         .collect()
 }
 
-fn collect_placeholders(input: &str) -> Vec<String> {
-    let mut names = Vec::new();
-    let mut search_start = 0;
-
-    while let Some(open_rel) = input[search_start..].find("{{") {
-        let open = search_start + open_rel;
-        let after_open = open + 2;
-        if let Some(close_rel) = input[after_open..].find("}}") {
-            let close = after_open + close_rel;
-            let candidate = input[after_open..close].trim();
-            if !candidate.is_empty() {
-                names.push(candidate.to_string());
-            }
-            search_start = close + 2;
-        } else {
-            break;
-        }
-    }
-
-    names
+/// Path parameters of the URL with no value, as `:name`.
+pub(super) fn unfilled_path_params(draft: &RequestDraft) -> Vec<String> {
+    path_param_names(&draft.url)
+        .into_iter()
+        .filter(|name| {
+            !draft
+                .path_params
+                .iter()
+                .any(|(param, value)| param == name && !value.trim().is_empty())
+        })
+        .map(|name| format!(":{name}"))
+        .collect()
 }
@@ -0,0 +1,142 @@
+use iced::Task;
+
+use super::pager::PAGE_BYTES;
+use super::view::{ResponseData, format_size};
+use super::{Message, Zagel};
+use crate::model::{RequestId, ResponsePreview};
+use crate::net::BodyChunk;
+
+/// Chunks buffered between the network task and the UI before the download
+/// waits for the view to catch up.
+pub const CHUNK_BUFFER: usize = 32;
+
+/// A body that is still arriving. Only the last page is kept for display; the
+/// complete body comes with the final response.
+#[derive(Debug, Clone)]
+pub struct StreamingBody {
+    pub id: Option<RequestId>,
+    pub received: usize,
+    tail: Vec<u8>,
+}
+
+impl StreamingBody {
+    pub const fn new(id: Option<RequestId>) -> Self {
+        Self {
+            id,
+            received: 0,
+            tail: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.received += bytes.len();
+        self.tail.extend_from_slice(bytes);
+        if self.tail.len() > PAGE_BYTES {
+            let mut cut = self.tail.len() - PAGE_BYTES;
+            // Do not start the tail in the middle of a UTF-8 sequence.
+            while self
+                .tail
+                .get(cut)
+                .is_some_and(|byte| byte & 0b1100_0000 == 0b1000_0000)
+            {
+                cut += 1;
+            }
+            self.tail.drain(..cut);
+        }
+    }
+
+    /// The tail so far, minus a trailing UTF-8 sequence that is still incomplete.
+    pub fn text_bytes(&self) -> &[u8] {
+        match std::str::from_utf8(&self.tail) {
+            Err(err) if err.error_len().is_none() => &self.tail[..err.valid_up_to()],
+            _ => &self.tail,
+        }
+    }
+
+    pub const fn is_truncated(&self) -> bool {
+        self.received > self.tail.len()
+    }
+
+    fn preview(&self, chunk: &BodyChunk) -> ResponsePreview {
+        ResponsePreview {
+            status: Some(chunk.status),
+            duration: None,
+            body: Some(self.text_bytes().to_vec()),
+            headers: chunk
+                .content_type
+                .iter()
+                .map(|value| ("content-type".to_string(), value.clone()))
+                .collect(),
+            error: None,
+            version: None,
+            url: None,
+        }
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_response_chunk(
+        &mut self,
+        id: Option<&RequestId>,
+        chunk: &BodyChunk,
+    ) -> Task<Message> {
+        let Some(streaming) = self
+            .streaming
+            .as_mut()
+            .filter(|streaming| streaming.id.as_ref() == id)
+        else {
+            // The final response already arrived; it has the whole body.
+            return Task::none();
+        };
+        streaming.push(&chunk.bytes);
+        if id != self.workspace.selection() {
+            return Task::none();
+        }
+        let preview = streaming.preview(chunk);
+        self.status_line = if streaming.is_truncated() {
+            format!(
+                "Receiving... {} so far, showing the last {}",
+                format_size(streaming.received),
+                format_size(streaming.text_bytes().len())
+            )
+        } else {
+            format!("Receiving... {} so far", format_size(streaming.received))
+        };
+        self.response = Some(ResponseData::from_preview(preview));
+        self.update_response_viewer();
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamingBody;
+    use crate::app::pager::PAGE_BYTES;
+
+    #[test]
+    fn keeps_only_the_last_page() {
+        let mut streaming = StreamingBody::new(None);
+        streaming.push(b"data: 1\n");
+        assert_eq!(streaming.text_bytes(), b"data: 1\n");
+        assert!(!streaming.is_truncated());
+
+        streaming.push(&vec![b'x'; PAGE_BYTES]);
+        assert_eq!(streaming.received, PAGE_BYTES + 8);
+        assert_eq!(streaming.text_bytes().len(), PAGE_BYTES);
+        assert!(streaming.is_truncated());
+    }
+
+    #[test]
+    fn tail_stays_on_utf8_boundaries() {
+        let mut streaming = StreamingBody::new(None);
+        let text = "é".repeat(PAGE_BYTES / 2 + 1);
+        let bytes = text.as_bytes();
+        streaming.push(&bytes[..bytes.len() - 1]);
+        assert!(std::str::from_utf8(streaming.text_bytes()).is_ok());
+
+        streaming.push(&bytes[bytes.len() - 1..]);
+        let tail = std::str::from_utf8(streaming.text_bytes()).expect("valid UTF-8");
+        assert!(tail.ends_with('é'));
+        assert!(tail.len() <= PAGE_BYTES);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::model::{HttpFile, RequestDraft, RequestId};
+
+/// An entry of the sidebar's tag filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagChoice {
+    All,
+    Tag(String),
+}
+
+impl Display for TagChoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => f.write_str("All tags"),
+            Self::Tag(tag) => write!(f, "#{tag}"),
+        }
+    }
+}
+
+/// Every tag used by a loaded request, sorted, ignoring case.
+pub fn all_tags(http_files: &HashMap<PathBuf, HttpFile>) -> Vec<String> {
+    let mut tags: Vec<String> = http_files
+        .values()
+        .flat_map(|file| &file.requests)
+        .flat_map(|draft| draft.tags.iter().cloned())
+        .collect();
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    tags.dedup_by(|tag, kept| tag.eq_ignore_ascii_case(kept));
+    tags
+}
+
+/// Requests tagged `tag`, file by file in `order`.
+pub fn tagged_requests(
+    http_files: &HashMap<PathBuf, HttpFile>,
+    order: &[PathBuf],
+    tag: &str,
+) -> Vec<(RequestId, RequestDraft)> {
+    order
+        .iter()
+        .filter_map(|path| http_files.get(path))
+        .flat_map(|file| {
+            file.requests
+                .iter()
+                .enumerate()
+                .filter(|(_, draft)| draft.has_tag(tag))
+                .map(|(index, draft)| {
+                    let id = RequestId::HttpFile {
+                        path: file.path.clone(),
+                        index,
+                    };
+                    (id, draft.clone())
+                })
+        })
+        .collect()
+}
+
+impl Zagel {
+    pub(super) fn handle_tag_filter_changed(&mut self, choice: TagChoice) -> Task<Message> {
+        self.tag_filter = match choice {
+            TagChoice::All => None,
+            TagChoice::Tag(tag) => Some(tag),
+        };
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::{all_tags, tagged_requests};
+    use crate::model::{HttpFile, RequestDraft, RequestId};
+
+    #[test]
+    fn tags_are_listed_once_and_select_their_requests() {
+        let tagged = |tags: &[&str]| RequestDraft {
+            tags: tags.iter().map(ToString::to_string).collect(),
+            ..RequestDraft::default()
+        };
+        let path = PathBuf::from("api.http");
+        let file = HttpFile {
+            path: path.clone(),
+            variables: Vec::new(),
+            auth: None,
+            requests: vec![tagged(&["smoke", "auth"]), tagged(&[]), tagged(&["Smoke"])],
+        };
+        let files = HashMap::from([(path.clone(), file)]);
+
+        assert_eq!(all_tags(&files), ["auth", "smoke"]);
+        let ids = tagged_requests(&files, std::slice::from_ref(&path), "SMOKE")
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [0, 2].map(|index| RequestId::HttpFile {
+                path: path.clone(),
+                index
+            })
+        );
+    }
+}
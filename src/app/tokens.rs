@@ -0,0 +1,394 @@
+use std::collections::BTreeSet;
+use std::time::{Duration, SystemTime};
+
+use iced::{Subscription, Task};
+
+use super::jwt::{decode, expiry_text, unix_now};
+use super::options::{apply_auth_headers, inherited_auth};
+use super::{Message, Zagel};
+use crate::model::{RequestDraft, RequestId, ResponsePreview, placeholder_names};
+use crate::net::SendOutcome;
+use crate::plugins::{SendHooks, send_with_plugins};
+
+/// Tokens are refreshed when they expire within this many seconds.
+const REFRESH_MARGIN_SECS: i64 = 30;
+
+/// What is known about the tokens captured by one `# @login` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenEntry {
+    pub login: RequestId,
+    pub label: String,
+    /// Unix time the tokens expire at, when the response or the directive
+    /// says.
+    pub expires_at: Option<i64>,
+}
+
+/// A login sent ahead of the request that needs its tokens.
+#[derive(Debug, Clone)]
+struct PendingRefresh {
+    login: RequestId,
+    /// The request to send once the login has answered.
+    then: Option<RequestId>,
+}
+
+/// Tokens captured by the login requests of the open projects.
+#[derive(Debug, Default)]
+pub struct TokenStore {
+    pub entries: Vec<TokenEntry>,
+    pending: Option<PendingRefresh>,
+    /// Lets the send that follows a refresh through without checking again.
+    skip_check: bool,
+    /// Unix time of the last tick, for the status bar countdown.
+    pub now: i64,
+}
+
+impl TokenStore {
+    fn entry(&self, login: &RequestId) -> Option<&TokenEntry> {
+        self.entries.iter().find(|entry| &entry.login == login)
+    }
+
+    fn upsert(&mut self, entry: TokenEntry) {
+        self.entries
+            .retain(|existing| existing.login != entry.login);
+        self.entries.push(entry);
+    }
+
+    /// The entry that expires first, for the status bar.
+    pub fn soonest(&self) -> Option<&TokenEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.expires_at.is_some())
+            .min_by_key(|entry| entry.expires_at)
+    }
+
+    /// `Token <label>: expires in 4m 2s`.
+    pub fn status_text(&self) -> Option<String> {
+        let entry = self.soonest()?;
+        let expires_at = entry.expires_at?;
+        Some(format!(
+            "Token {}: {}",
+            entry.label,
+            expiry_text(expires_at, self.now)
+        ))
+    }
+}
+
+/// Whether a login has to run before a send: some of its tokens were never
+/// captured, or they expire within the margin. Tokens without a known expiry
+/// are taken as valid; JWTs carry their own.
+pub fn needs_refresh(entry: Option<&TokenEntry>, values: &[Option<&str>], now: i64) -> bool {
+    if values.iter().any(Option::is_none) {
+        return true;
+    }
+    let expires_at = entry
+        .and_then(|entry| entry.expires_at)
+        .or_else(|| jwt_expiry(values.iter().flatten().copied()));
+    expires_at.is_some_and(|expires_at| expires_at - now <= REFRESH_MARGIN_SECS)
+}
+
+fn jwt_expiry<'a>(values: impl IntoIterator<Item = &'a str>) -> Option<i64> {
+    values
+        .into_iter()
+        .filter_map(|value| decode(value).ok()?.expires_at)
+        .min()
+}
+
+/// When the tokens of a login response expire: the earliest JWT `exp` among
+/// the captured values, else the body's `expires_in`, else the directive's
+/// lifetime.
+pub fn token_expiry(
+    values: &[&str],
+    body: &str,
+    lifetime: Option<Duration>,
+    now: i64,
+) -> Option<i64> {
+    let expires_in = || {
+        serde_json::from_str::<serde_json::Value>(body)
+            .ok()?
+            .get("expires_in")?
+            .as_i64()
+    };
+    let lifetime = || i64::try_from(lifetime?.as_secs()).ok();
+    jwt_expiry(values.iter().copied())
+        .or_else(|| expires_in().or_else(lifetime).map(|secs| now + secs))
+}
+
+impl Zagel {
+    /// Login requests in the same project as `request` whose captures the
+    /// draft refers to.
+    fn logins_for(
+        &self,
+        request: &RequestId,
+        draft: &RequestDraft,
+    ) -> Vec<(RequestId, RequestDraft)> {
+        let RequestId::HttpFile { path, .. } = request;
+        let Some(root) = self.project_root_for_path(path) else {
+            return Vec::new();
+        };
+        let auth = self.effective_auth(Some(request), &draft.headers);
+        let headers = apply_auth_headers(&draft.headers, &auth);
+        let referenced = [draft.url.as_str(), headers.as_str(), draft.body.as_str()]
+            .into_iter()
+            .flat_map(placeholder_names)
+            .collect::<BTreeSet<_>>();
+        let mut logins = Vec::new();
+        for (file_path, file) in self.workspace.http_files() {
+            if !file_path.starts_with(root.as_path()) {
+                continue;
+            }
+            for (index, login) in file.requests.iter().enumerate() {
+                let id = RequestId::HttpFile {
+                    path: file_path.clone(),
+                    index,
+                };
+                if login.options.login
+                    && &id != request
+                    && login
+                        .captures
+                        .iter()
+                        .any(|capture| referenced.contains(&capture.name))
+                {
+                    logins.push((id, login.clone()));
+                }
+            }
+        }
+        logins.sort_by(
+            |(RequestId::HttpFile { path: a, index: i }, _),
+             (RequestId::HttpFile { path: b, index: j }, _)| (a, i).cmp(&(b, j)),
+        );
+        logins
+    }
+
+    fn login_label(&self, login: &RequestId) -> String {
+        self.request_draft(login).map_or_else(
+            || self.request_ref(login).to_string(),
+            |draft| draft.name.unwrap_or(draft.title),
+        )
+    }
+
+    /// Sends the login the selected request depends on first when its tokens
+    /// are missing or about to expire; `None` lets the send go ahead.
+    pub(super) fn refresh_tokens_before_send(
+        &mut self,
+        draft: &RequestDraft,
+    ) -> Option<Task<Message>> {
+        if std::mem::take(&mut self.tokens.skip_check) {
+            return None;
+        }
+        let request = self.workspace.selection_cloned()?;
+        let now = unix_now();
+        let (login, login_draft) =
+            self.logins_for(&request, draft)
+                .into_iter()
+                .find(|(id, login)| {
+                    let values = login
+                        .captures
+                        .iter()
+                        .map(|capture| {
+                            self.session_variables
+                                .get(&capture.name)
+                                .map(String::as_str)
+                        })
+                        .collect::<Vec<_>>();
+                    needs_refresh(self.tokens.entry(id), &values, now)
+                })?;
+        if let Some(pending) = &self.tokens.pending {
+            let label = self.login_label(&pending.login);
+            self.update_status_with_missing(&format!("Waiting for login {label}"));
+            return Some(Task::none());
+        }
+        Some(self.send_login(login, login_draft, Some(request)))
+    }
+
+    fn send_login(
+        &mut self,
+        login: RequestId,
+        mut draft: RequestDraft,
+        then: Option<RequestId>,
+    ) -> Task<Message> {
+        draft.resolve_dynamic();
+        let id = Some(login.clone());
+        let env = self.request_environment(id.as_ref());
+        let auth = inherited_auth(self.file_auth(id.as_ref()), &draft.headers);
+        self.cookies
+            .apply(&mut draft, env.as_ref(), id.clone(), SystemTime::now());
+        self.arm_captures(id, &draft);
+        let label = self.login_label(&login);
+        self.update_status_with_missing(&format!("Refreshing tokens with {label}..."));
+        self.tokens.pending = Some(PendingRefresh {
+            login: login.clone(),
+            then,
+        });
+        let client = match self.http_client(&draft) {
+            Ok(client) => client,
+            Err(err) => return Task::done(Message::TokensRefreshed(login, Err(err))),
+        };
+        Task::perform(
+            send_with_plugins(
+                SendHooks::from_state(&self.state),
+                client,
+                draft,
+                env,
+                auth,
+                self.oauth2_token_cache.clone(),
+                None,
+            ),
+            move |result| Message::TokensRefreshed(login, result),
+        )
+    }
+
+    /// Remembers when the tokens of a login response expire; called for
+    /// every response, it ignores requests without `# @login`.
+    pub(super) fn note_login_response(
+        &mut self,
+        request: Option<&RequestId>,
+        response: &ResponsePreview,
+    ) {
+        let Some(login) = request else {
+            return;
+        };
+        let Some(draft) = self
+            .request_draft(login)
+            .filter(|draft| draft.options.login)
+        else {
+            return;
+        };
+        if !response
+            .status
+            .is_some_and(|status| (200..300).contains(&status))
+        {
+            return;
+        }
+        let values = draft
+            .captures
+            .iter()
+            .filter_map(|capture| self.session_variables.get(&capture.name))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let body = String::from_utf8_lossy(response.body.as_deref().unwrap_or_default());
+        let now = unix_now();
+        self.tokens.now = now;
+        self.tokens.upsert(TokenEntry {
+            login: login.clone(),
+            label: self.login_label(login),
+            expires_at: token_expiry(&values, &body, draft.options.login_lifetime, now),
+        });
+    }
+
+    pub(super) fn handle_tokens_refreshed(
+        &mut self,
+        login: &RequestId,
+        result: Result<SendOutcome, String>,
+    ) -> Task<Message> {
+        let Some(pending) = self
+            .tokens
+            .pending
+            .take_if(|pending| &pending.login == login)
+        else {
+            return Task::none();
+        };
+        let set_cookies = result
+            .as_ref()
+            .map_or(&[][..], |outcome| &outcome.response.headers);
+        self.cookies
+            .store(Some(login), set_cookies, SystemTime::now());
+        let preview = match result {
+            Ok(outcome) => {
+                if outcome.oauth2_cache.is_some() {
+                    self.oauth2_token_cache = outcome.oauth2_cache;
+                }
+                outcome.response
+            }
+            Err(err) => ResponsePreview::error(err),
+        };
+        let summary = self.apply_captures(Some(login), &preview);
+        self.note_login_response(Some(login), &preview);
+        let label = self.login_label(login);
+        let refreshed = preview
+            .status
+            .is_some_and(|status| (200..300).contains(&status));
+        let status = if refreshed {
+            format!("Refreshed tokens with {label}")
+        } else {
+            let reason = preview.error.clone().or(summary).unwrap_or_else(|| {
+                preview.status.map_or_else(
+                    || "no response".to_string(),
+                    |status| format!("status {status}"),
+                )
+            });
+            format!("Login {label} failed ({reason}); sending with the old tokens")
+        };
+        self.update_status_with_missing(&status);
+        if pending.then.is_none() || pending.then != self.workspace.selection_cloned() {
+            return Task::none();
+        }
+        self.tokens.skip_check = true;
+        Task::done(Message::Send)
+    }
+
+    /// Ticks the status bar countdown while a token has a known expiry.
+    pub(super) fn tokens_subscription(&self) -> Option<Subscription<Message>> {
+        self.tokens
+            .soonest()
+            .is_some()
+            .then(|| iced::time::every(Duration::from_secs(1)).map(|_| Message::TokensTick))
+    }
+
+    pub(super) fn handle_tokens_tick(&mut self) -> Task<Message> {
+        self.tokens.now = unix_now();
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::{TokenEntry, needs_refresh, token_expiry};
+    use crate::model::RequestId;
+
+    // {"alg":"HS256"}.{"exp":1700000000}
+    const JWT: &str = "eyJhbGciOiJIUzI1NiJ9.eyJleHAiOjE3MDAwMDAwMDB9.c2ln";
+
+    #[test]
+    fn tokens_refresh_when_missing_or_about_to_expire() {
+        let entry = |expires_at| TokenEntry {
+            login: RequestId::HttpFile {
+                path: PathBuf::from("auth.http"),
+                index: 0,
+            },
+            label: "auth.http#name=login".to_string(),
+            expires_at,
+        };
+        assert!(needs_refresh(None, &[None], 0));
+        assert!(!needs_refresh(None, &[Some("opaque")], 0));
+        assert!(needs_refresh(
+            Some(&entry(Some(1_010))),
+            &[Some("opaque")],
+            1_000
+        ));
+        assert!(!needs_refresh(
+            Some(&entry(Some(1_100))),
+            &[Some("opaque")],
+            1_000
+        ));
+        assert!(needs_refresh(None, &[Some(JWT)], 1_700_000_000 - 5));
+        assert!(!needs_refresh(None, &[Some(JWT)], 1_699_000_000));
+    }
+
+    #[test]
+    fn expiry_comes_from_jwts_then_expires_in_then_the_lifetime() {
+        let lifetime = Some(Duration::from_mins(30));
+        assert_eq!(
+            token_expiry(&[JWT], r#"{"expires_in": 60}"#, lifetime, 0),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            token_expiry(&["opaque"], r#"{"expires_in": 60}"#, lifetime, 1_000),
+            Some(1_060)
+        );
+        assert_eq!(token_expiry(&["opaque"], "", lifetime, 1_000), Some(2_800));
+        assert_eq!(token_expiry(&["opaque"], "", None, 1_000), None);
+    }
+}
@@ -0,0 +1,121 @@
+use iced::{Task, clipboard};
+use uuid::Uuid;
+
+use super::headers::{has_header, push_header};
+use super::{Message, Zagel};
+use crate::model::RequestDraft;
+
+pub const TRACEPARENT: &str = "traceparent";
+pub const B3: &str = "b3";
+
+/// Session-wide W3C trace context (and optional single-header B3) injection.
+#[derive(Debug, Clone, Default)]
+pub struct TraceContext {
+    pub enabled: bool,
+    pub b3: bool,
+    /// Trace id of the last send, shown so it can be looked up in a tracing backend.
+    pub last_trace_id: Option<String>,
+}
+
+/// 32 hex digit trace id and 16 hex digit span id; never all zeros, which the
+/// spec reserves as invalid.
+fn new_ids() -> (String, String) {
+    let trace = Uuid::new_v4().as_u128().max(1);
+    let span = u64::try_from(Uuid::new_v4().as_u128() >> 64)
+        .unwrap_or(1)
+        .max(1);
+    (format!("{trace:032x}"), format!("{span:016x}"))
+}
+
+impl TraceContext {
+    /// Adds fresh trace headers to `draft`, skipping any the request already sets.
+    /// Returns the trace id used.
+    pub fn apply(&mut self, draft: &mut RequestDraft) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let add_traceparent = !has_header(&draft.headers, TRACEPARENT);
+        let add_b3 = self.b3 && !has_header(&draft.headers, B3);
+        if !add_traceparent && !add_b3 {
+            return None;
+        }
+        let (trace_id, span_id) = new_ids();
+        if add_traceparent {
+            let value = format!("00-{trace_id}-{span_id}-01");
+            push_header(&mut draft.headers, TRACEPARENT, &value);
+        }
+        if add_b3 {
+            push_header(&mut draft.headers, B3, &format!("{trace_id}-{span_id}-1"));
+        }
+        self.last_trace_id = Some(trace_id.clone());
+        Some(trace_id)
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_trace_context_toggled(&mut self, enabled: bool) -> Task<Message> {
+        self.trace_context.enabled = enabled;
+        Task::none()
+    }
+
+    pub(super) fn handle_trace_b3_toggled(&mut self, b3: bool) -> Task<Message> {
+        self.trace_context.b3 = b3;
+        Task::none()
+    }
+
+    pub(super) fn handle_copy_trace_id(&self) -> Task<Message> {
+        self.trace_context
+            .last_trace_id
+            .clone()
+            .map_or_else(Task::none, |trace_id| {
+                clipboard::write(trace_id).map(|()| Message::CopyComplete)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TraceContext;
+    use crate::model::RequestDraft;
+
+    #[test]
+    fn injects_traceparent_and_b3_with_shared_ids() {
+        let mut trace = TraceContext {
+            enabled: true,
+            b3: true,
+            ..TraceContext::default()
+        };
+        let mut draft = RequestDraft {
+            headers: String::new(),
+            ..Default::default()
+        };
+
+        let trace_id = trace.apply(&mut draft).expect("headers injected");
+
+        let lines = draft.headers.lines().collect::<Vec<_>>();
+        let traceparent = lines[0].strip_prefix("traceparent: ").unwrap();
+        let parts = traceparent.split('-').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(
+            (parts[0], parts[1], parts[3]),
+            ("00", trace_id.as_str(), "01")
+        );
+        assert_eq!(parts[2].len(), 16);
+        assert_eq!(lines[1], format!("b3: {trace_id}-{}-1", parts[2]));
+        assert_eq!(trace.last_trace_id, Some(trace_id));
+    }
+
+    #[test]
+    fn existing_traceparent_is_kept() {
+        let mut trace = TraceContext {
+            enabled: true,
+            ..TraceContext::default()
+        };
+        let mut draft = RequestDraft {
+            headers: "Traceparent: 00-abc-def-01".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(trace.apply(&mut draft), None);
+        assert_eq!(draft.headers, "Traceparent: 00-abc-def-01");
+    }
+}
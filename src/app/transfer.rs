@@ -0,0 +1,407 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use iced::Task;
+
+use super::reanchor::Reanchor;
+use super::{EditState, EditTarget, Message, Zagel};
+use crate::model::{HttpFile, RequestId, RequestRef};
+use crate::parser::{parse_http_file, write_http_file, write_http_file_with_header};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferMode {
+    Move,
+    Copy,
+}
+
+impl Display for TransferMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Move => f.write_str("Moved"),
+            Self::Copy => f.write_str("Copied"),
+        }
+    }
+}
+
+/// A loaded .http file offered as the destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Destination(pub PathBuf);
+
+impl Display for Destination {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+/// Destination typed or picked in the edit-mode Move/Copy row.
+#[derive(Debug, Clone, Default)]
+pub struct TransferForm {
+    pub destination: String,
+}
+
+/// Files to write for a move or copy, the destination first.
+#[derive(Debug)]
+pub struct TransferPlan {
+    pub files: Vec<HttpFile>,
+    /// Whether the destination is a new file, not one on disk.
+    pub created: bool,
+    /// Where moved requests and their old neighbours are now.
+    pub reanchor: Reanchor,
+    /// Old and new references of the moved requests, for favorites.
+    pub renamed: Vec<(String, String)>,
+}
+
+/// Appends the requests `ids` to `destination`, removing them from their
+/// files when moving. Copies drop their `# @id` so ids stay unique. A destination on disk that
+/// is not loaded, e.g. one below the scan depth, is read first so its
+/// requests are kept.
+pub fn plan_transfer(
+    http_files: &HashMap<PathBuf, HttpFile>,
+    ids: &[RequestId],
+    destination: &Path,
+    mode: TransferMode,
+) -> Result<TransferPlan, String> {
+    let mut by_file: BTreeMap<&PathBuf, Vec<usize>> = BTreeMap::new();
+    for RequestId::HttpFile { path, index } in ids {
+        by_file.entry(path).or_default().push(*index);
+    }
+    let mut sources = Vec::new();
+    for (path, mut indices) in by_file {
+        let file = http_files
+            .get(path)
+            .ok_or_else(|| format!("{} is no longer loaded", path.display()))?;
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.iter().any(|index| *index >= file.requests.len()) {
+            return Err(format!(
+                "{} changed; select the requests again",
+                path.display()
+            ));
+        }
+        sources.push((file, indices));
+    }
+    let Some((first, _)) = sources.first() else {
+        return Err("Select requests to move or copy".to_string());
+    };
+
+    let existing = match http_files.get(destination) {
+        Some(file) => Some(file.clone()),
+        None if destination.exists() => Some(
+            parse_http_file(destination)
+                .map_err(|err| format!("Cannot read {}: {err}", destination.display()))?,
+        ),
+        None => None,
+    };
+    let created = existing.is_none();
+    let mut target = existing.unwrap_or_else(|| HttpFile {
+        path: destination.to_path_buf(),
+        // A new file keeps the variables and auth the requests may use.
+        variables: first.variables.clone(),
+        auth: first.auth.clone(),
+        requests: Vec::new(),
+    });
+    // Index of the first appended request: the destination's requests that
+    // stay.
+    let mut next = target.requests.len()
+        - sources
+            .iter()
+            .find(|(file, _)| mode == TransferMode::Move && file.path == destination)
+            .map_or(0, |(_, indices)| indices.len());
+    let mut files = Vec::new();
+    let mut reanchor = Reanchor::default();
+    let mut renamed = Vec::new();
+    let mut appended = Vec::new();
+    for (file, indices) in &sources {
+        let mut kept = Vec::new();
+        let mut moved = Vec::new();
+        for (index, draft) in file.requests.iter().enumerate() {
+            if !indices.contains(&index) {
+                kept.push(draft.clone());
+                moved.push(Some(RequestId::HttpFile {
+                    path: file.path.clone(),
+                    index: kept.len() - 1,
+                }));
+                continue;
+            }
+            let mut draft = draft.clone();
+            if mode == TransferMode::Copy {
+                draft.id = None;
+            } else {
+                let old = RequestRef::new(&file.path, index, &draft);
+                let new = RequestRef::new(destination, next, &draft);
+                renamed.push((old.to_string(), new.to_string()));
+            }
+            moved.push(Some(RequestId::HttpFile {
+                path: destination.to_path_buf(),
+                index: next,
+            }));
+            next += 1;
+            appended.push(draft);
+        }
+        if mode == TransferMode::Copy {
+            continue;
+        }
+        reanchor.set(&file.path, moved);
+        if file.path == destination {
+            target.requests = kept;
+        } else {
+            files.push(HttpFile {
+                requests: kept,
+                ..(*file).clone()
+            });
+        }
+    }
+    target.requests.extend(appended);
+    files.insert(0, target);
+    Ok(TransferPlan {
+        files,
+        created,
+        reanchor,
+        renamed,
+    })
+}
+
+/// `path` with symlinks and `..` resolved; a file that does not exist yet is
+/// resolved through its folder.
+fn resolve(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            fs::canonicalize(parent).map_or_else(|_| path.to_path_buf(), |parent| parent.join(name))
+        }
+        _ => path.to_path_buf(),
+    })
+}
+
+impl Zagel {
+    pub(super) fn handle_transfer_destination_changed(&mut self, input: String) -> Task<Message> {
+        self.transfer.destination = input;
+        Task::none()
+    }
+
+    /// Moves or copies the requests selected in edit mode to the typed
+    /// destination, writing every changed file.
+    pub(super) fn handle_transfer_requested(&mut self, mode: TransferMode) -> Task<Message> {
+        let ids = match &self.edit_state {
+            EditState::On { selection } => selection
+                .iter()
+                .filter_map(|target| match target {
+                    EditTarget::Request(id) => Some(id.clone()),
+                    EditTarget::Collection(_) => None,
+                })
+                .collect::<Vec<_>>(),
+            EditState::Off => Vec::new(),
+        };
+        let plan = self.transfer_destination().and_then(|destination| {
+            plan_transfer(self.workspace.http_files(), &ids, &destination, mode)
+        });
+        let plan = match plan {
+            Ok(plan) => plan,
+            Err(err) => {
+                self.update_status_with_missing(&err);
+                return Task::none();
+            }
+        };
+        for (position, file) in plan.files.iter().enumerate() {
+            let written = if position == 0 && plan.created {
+                write_http_file_with_header(
+                    &file.path,
+                    &file.variables,
+                    file.auth.as_ref(),
+                    &file.requests,
+                )
+            } else {
+                write_http_file(&file.path, &file.requests)
+            };
+            if let Err(err) = written {
+                self.update_status_with_missing(&format!(
+                    "Failed to update {}: {err}",
+                    file.path.display()
+                ));
+                return Task::none();
+            }
+        }
+        self.apply_transfer(plan, mode, ids.len());
+        Task::none()
+    }
+
+    /// The typed destination, relative paths resolved against the default
+    /// project, spelled like the loaded file when it is one.
+    fn transfer_destination(&self) -> Result<PathBuf, String> {
+        let input = self.transfer.destination.trim();
+        if input.is_empty() {
+            return Err("Enter or pick a destination .http file".to_string());
+        }
+        let mut path = PathBuf::from(input);
+        if path.is_relative()
+            && let Some(root) = self.default_project_root()
+        {
+            path = root.as_path().join(path);
+        }
+        if path.extension().and_then(|ext| ext.to_str()) != Some("http") {
+            return Err("The destination must be a .http file".to_string());
+        }
+        let path = resolve(&path);
+        let path = self
+            .workspace
+            .http_files()
+            .keys()
+            .find(|loaded| resolve(loaded) == path)
+            .cloned()
+            .unwrap_or(path);
+        if self.project_root_for_path(&path).is_none() {
+            return Err(format!("{} is outside every project", path.display()));
+        }
+        Ok(path)
+    }
+
+    fn apply_transfer(&mut self, plan: TransferPlan, mode: TransferMode, count: usize) {
+        let destination = plan.files[0].path.clone();
+        if let Some(mut workspace) = self.workspace.configured_state() {
+            if !workspace.http_file_order_mut().contains(&destination) {
+                workspace.http_file_order_mut().push(destination.clone());
+            }
+            for file in plan.files {
+                workspace.http_files_mut().insert(file.path.clone(), file);
+            }
+            let selection = workspace
+                .selection_cloned()
+                .and_then(|id| plan.reanchor.map(&id));
+            workspace.set_selection(selection);
+        }
+        self.reanchor_requests(&plan.reanchor);
+        for raw in self.state.favorites.values_mut().flatten() {
+            if let Some((_, new)) = plan.renamed.iter().find(|(old, _)| old == raw) {
+                raw.clone_from(new);
+            }
+        }
+        if let Some(RequestId::HttpFile { path, .. }) = self.workspace.selection() {
+            self.save_path = path.display().to_string();
+        }
+        self.persist_state();
+        let noun = if count == 1 { "request" } else { "requests" };
+        self.update_status_with_missing(&format!(
+            "{mode} {count} {noun} to {}",
+            destination.display()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use tempfile::tempdir;
+
+    use super::{TransferMode, plan_transfer, resolve};
+    use crate::model::{HttpFile, RequestDraft, RequestId};
+
+    fn file(path: &str, names: &[&str]) -> (PathBuf, HttpFile) {
+        let file = HttpFile {
+            path: PathBuf::from(path),
+            variables: Vec::new(),
+            auth: None,
+            requests: names
+                .iter()
+                .map(|name| RequestDraft {
+                    name: Some((*name).to_string()),
+                    ..RequestDraft::default()
+                })
+                .collect(),
+        };
+        (file.path.clone(), file)
+    }
+
+    fn id(path: &str, index: usize) -> RequestId {
+        RequestId::HttpFile {
+            path: PathBuf::from(path),
+            index,
+        }
+    }
+
+    fn names(file: &HttpFile) -> Vec<&str> {
+        file.requests
+            .iter()
+            .filter_map(|draft| draft.name.as_deref())
+            .collect()
+    }
+
+    #[test]
+    fn moved_requests_are_appended_and_every_index_is_remapped() {
+        let files = HashMap::from([file("a.http", &["r0", "r1", "r2"]), file("b.http", &["x"])]);
+        let plan = plan_transfer(
+            &files,
+            &[id("a.http", 2), id("a.http", 0)],
+            Path::new("b.http"),
+            TransferMode::Move,
+        )
+        .unwrap();
+
+        assert!(!plan.created);
+        assert_eq!(names(&plan.files[0]), ["x", "r0", "r2"]);
+        assert_eq!(names(&plan.files[1]), ["r1"]);
+        assert_eq!(plan.reanchor.map(&id("a.http", 0)), Some(id("b.http", 1)));
+        assert_eq!(plan.reanchor.map(&id("a.http", 1)), Some(id("a.http", 0)));
+        assert_eq!(plan.reanchor.map(&id("b.http", 0)), Some(id("b.http", 0)));
+        assert_eq!(
+            plan.renamed[1],
+            ("a.http#name=r2".to_string(), "b.http#name=r2".to_string())
+        );
+    }
+
+    #[test]
+    fn copies_leave_the_source_alone_and_drop_their_ids() {
+        let files = HashMap::from([file("a.http", &["r0"])]);
+        let plan = plan_transfer(
+            &files,
+            &[id("a.http", 0)],
+            Path::new("new.http"),
+            TransferMode::Copy,
+        )
+        .unwrap();
+
+        assert!(plan.created);
+        assert_eq!(plan.files.len(), 1);
+        assert_eq!(names(&plan.files[0]), ["r0"]);
+        assert!(plan.files[0].requests[0].id.is_none());
+        assert_eq!(plan.reanchor.map(&id("a.http", 0)), Some(id("a.http", 0)));
+        assert!(plan_transfer(&files, &[], Path::new("b.http"), TransferMode::Copy).is_err());
+    }
+
+    #[test]
+    fn unloaded_destinations_on_disk_keep_their_requests() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+        let deep = root.join("deep.http");
+        fs::write(
+            &deep,
+            "@host = api.test\n\n### kept\nGET https://{{host}}/kept\n",
+        )
+        .unwrap();
+        let destination = resolve(&root.join("sub/../deep.http"));
+        assert_eq!(destination, deep);
+
+        let files = HashMap::from([file("a.http", &["r0"])]);
+        let plan =
+            plan_transfer(&files, &[id("a.http", 0)], &destination, TransferMode::Move).unwrap();
+        assert!(!plan.created);
+        assert_eq!(
+            plan.files[0].variables,
+            [("host".to_string(), "api.test".to_string())]
+        );
+        assert_eq!(plan.files[0].requests.len(), 2);
+        assert_eq!(plan.files[0].requests[0].url, "https://{{host}}/kept");
+        assert_eq!(plan.files[0].requests[1].name.as_deref(), Some("r0"));
+        assert_eq!(
+            plan.reanchor.map(&id("a.http", 0)),
+            Some(RequestId::HttpFile {
+                path: deep,
+                index: 1,
+            })
+        );
+    }
+}
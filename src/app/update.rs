@@ -2,19 +2,25 @@ use std::collections::HashSet;
 use std::fs;
 use std::marker::PhantomData;
 use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
+use iced::futures::channel::mpsc;
 use iced::widget::pane_grid;
 use iced::{Task, clipboard};
 
-use crate::model::{Method, RequestDraft, RequestId, ResponsePreview};
-use crate::net::send_request;
+use crate::env_audit;
+use crate::model::{Method, RequestDraft, RequestId, ResponsePreview, apply_environment};
+use crate::net::split_url_credentials;
 use crate::parser::{persist_request, write_http_file};
 use crate::pathing::{GlobalEnvRoot, ProjectRoot};
+use crate::plugins::{SendHooks, send_with_plugins};
 
 use super::domain::{AddRequestPlan, GlobalEnvChangeOutcome, ProjectChangeOutcome, SavePlan};
-use super::options::{RequestMode, build_graphql_body};
+use super::options::{AuthState, BasicAuthState, RequestMode};
+use super::reanchor::Reanchor;
+use super::send_log::SendLog;
 use super::status::status_with_missing;
+use super::streaming::{CHUNK_BUFFER, StreamingBody};
 use super::{EditState, EditTarget, HeaderRow, Message, Zagel};
 
 const MIN_SPLIT_RATIO: f32 = 0.2;
@@ -97,68 +103,15 @@ fn clamp_ratio(ratio: f32) -> f32 {
     ratio.clamp(MIN_SPLIT_RATIO, 1.0 - MIN_SPLIT_RATIO)
 }
 
-const fn edit_selection_mut(edit_state: &mut EditState) -> Option<&mut HashSet<EditTarget>> {
+pub(super) const fn edit_selection_mut(
+    edit_state: &mut EditState,
+) -> Option<&mut HashSet<EditTarget>> {
     match edit_state {
         EditState::On { selection } => Some(selection),
         EditState::Off => None,
     }
 }
 
-fn remap_edit_selection(edit_state: &mut EditState, mut map: impl FnMut(EditTarget) -> EditTarget) {
-    let Some(selection) = edit_selection_mut(edit_state) else {
-        return;
-    };
-    let mut next = HashSet::with_capacity(selection.len());
-    for item in selection.drain() {
-        next.insert(map(item));
-    }
-    *selection = next;
-}
-
-fn swap_request_indices_in_selection_http(
-    selection: &mut Option<RequestId>,
-    path: &PathBuf,
-    a: usize,
-    b: usize,
-) {
-    if let Some(RequestId::HttpFile {
-        path: sel_path,
-        index,
-    }) = selection.as_mut()
-        && sel_path == path
-    {
-        if *index == a {
-            *index = b;
-        } else if *index == b {
-            *index = a;
-        }
-    }
-}
-
-fn swap_request_indices_in_edit_selection_http(
-    edit_state: &mut EditState,
-    path: &PathBuf,
-    a: usize,
-    b: usize,
-) {
-    remap_edit_selection(edit_state, |item| match item {
-        EditTarget::Request(RequestId::HttpFile { path: p, index }) => {
-            if p == *path {
-                if index == a {
-                    EditTarget::Request(RequestId::HttpFile { path: p, index: b })
-                } else if index == b {
-                    EditTarget::Request(RequestId::HttpFile { path: p, index: a })
-                } else {
-                    EditTarget::Request(RequestId::HttpFile { path: p, index })
-                }
-            } else {
-                EditTarget::Request(RequestId::HttpFile { path: p, index })
-            }
-        }
-        other => other,
-    });
-}
-
 fn remove_edit_targets_for_root(edit_state: &mut EditState, root: &ProjectRoot) {
     if let EditState::On { selection } = edit_state {
         selection.retain(|target| match target {
@@ -203,12 +156,18 @@ impl Zagel {
             Message::AutomationScreenshotCaptured(screenshot) => {
                 self.handle_automation_screenshot(screenshot)
             }
+            Message::AutomationRegionBounds(bounds) => self.handle_automation_region_bounds(bounds),
             Message::HttpFilesLoaded(files) => {
                 if !self.should_scan() {
                     return Task::none();
                 }
                 let mut workspace = self.workspace.ensured_configured_state();
-                workspace.replace_http_files(files);
+                let previous = workspace.replace_http_files(files);
+                let reanchor = Reanchor::new(&previous, workspace.http_files());
+                let selection = workspace
+                    .selection_cloned()
+                    .and_then(|id| reanchor.map(&id));
+                workspace.set_selection(selection);
                 let loaded_paths = workspace
                     .http_files()
                     .keys()
@@ -233,6 +192,7 @@ impl Zagel {
                 {
                     workspace.set_selection(None);
                 }
+                self.reanchor_requests(&reanchor);
                 self.refresh_visible_environments();
                 Task::none()
             }
@@ -248,6 +208,14 @@ impl Zagel {
                 self.builder_panes.resize(split, clamp_ratio(ratio));
                 Task::none()
             }
+            Message::EditorOpen(open) => self.handle_editor_open(&open),
+            Message::EditorEndpointFailed(err) => self.handle_editor_endpoint_failed(&err),
+            Message::FailedFilterToggled(failed_only) => {
+                self.handle_failed_filter_toggled(failed_only)
+            }
+            Message::SidebarGroupingChanged(grouping) => {
+                self.handle_sidebar_grouping_changed(grouping)
+            }
             Message::ToggleCollection(path) => {
                 if !self.collapsed_collections.remove(&path) {
                     self.collapsed_collections.insert(path);
@@ -273,14 +241,15 @@ impl Zagel {
                 }
                 Task::none()
             }
-            Message::ToggleEditSelection(target) => {
-                if let Some(selection) = edit_selection_mut(&mut self.edit_state)
-                    && !selection.remove(&target)
-                {
-                    selection.insert(target);
-                }
-                Task::none()
+            Message::ToggleEditSelection(target) => self.handle_edit_selection_toggled(target),
+            Message::ModifiersChanged(modifiers) => self.handle_modifiers_changed(modifiers),
+            Message::SelectAllVisible => self.handle_select_all_visible(),
+            Message::BatchHeaderOpSelected(op) => self.handle_batch_header_op_selected(op),
+            Message::BatchHeaderNameChanged(name) => self.handle_batch_header_name_changed(name),
+            Message::BatchHeaderValueChanged(value) => {
+                self.handle_batch_header_value_changed(value)
             }
+            Message::BatchHeadersApplied => self.handle_batch_headers_applied(),
             Message::DeleteSelected => {
                 let edit_selection = match &self.edit_state {
                     EditState::On { selection } if !selection.is_empty() => selection.clone(),
@@ -391,6 +360,63 @@ impl Zagel {
 
                 Task::none()
             }
+            Message::SplitHttpFile(path) => self.handle_split_file(&path),
+            Message::ContextMenuOpened(target) => self.handle_context_menu_opened(target),
+            Message::ContextMenuClosed => {
+                self.context_menu = None;
+                Task::none()
+            }
+            Message::EscapePressed => self.handle_escape_pressed(),
+            Message::TransferDestinationChanged(input) => {
+                self.handle_transfer_destination_changed(input)
+            }
+            Message::TransferDestinationPicked(destination) => {
+                self.handle_transfer_destination_changed(destination.to_string())
+            }
+            Message::TransferRequested(mode) => self.handle_transfer_requested(mode),
+            Message::SidebarFilterChanged(query) => self.handle_sidebar_filter_changed(query),
+            Message::ContextMenuAction(action) => self.handle_context_menu_action(action),
+            Message::ContextMenuRenameChanged(name) => {
+                self.handle_context_menu_rename_changed(name)
+            }
+            Message::ContextMenuRenameSubmitted => self.handle_context_menu_rename_submitted(),
+            Message::FindReplaceToggled => self.handle_find_replace_toggled(),
+            Message::FindQueryChanged(query) => self.handle_find_query_changed(query),
+            Message::ReplaceTextChanged(text) => self.handle_replace_text_changed(text),
+            Message::FindRegexToggled(regex) => self.handle_find_regex_toggled(regex),
+            Message::FindReplaceApply => self.handle_find_replace_apply(),
+            Message::PinResponse => self.handle_pin_response(),
+            Message::UnpinResponse => self.handle_unpin_response(),
+            Message::DuplicatesToggled => self.handle_duplicates_toggled(),
+            Message::DuplicateKeep(id) => self.handle_duplicate_keep(&id),
+            Message::ImportCurl => clipboard::read().map(Message::CurlPasted),
+            Message::CurlPasted(contents) => self.handle_curl_pasted(contents),
+            Message::BackupPathChanged(path) => self.handle_backup_path_changed(path),
+            Message::BackupSecretsToggled(secrets) => self.handle_backup_secrets_toggled(secrets),
+            Message::ExportBackup => self.handle_export_backup(),
+            Message::RestoreBackup => self.handle_restore_backup(),
+            Message::RestoreBackupConfirmed => self.handle_restore_backup_confirmed(),
+            Message::RestoreBackupCancelled => self.handle_restore_backup_cancelled(),
+            Message::PluginCommandChanged(command) => self.handle_plugin_command_changed(command),
+            Message::AddPlugin => self.handle_add_plugin(),
+            Message::PluginDescribed(result) => self.handle_plugin_described(result),
+            Message::PluginToggled(index, enabled) => self.handle_plugin_toggled(index, enabled),
+            Message::RemovePlugin(index) => self.handle_remove_plugin(index),
+            Message::PluginImportPathChanged(path) => self.handle_plugin_import_path_changed(path),
+            Message::HookCommandChanged(command) => self.handle_hook_command_changed(command),
+            Message::HookTimingChanged(hook) => self.handle_hook_timing_changed(hook),
+            Message::AddHookCommand => self.handle_add_hook_command(),
+            Message::HookCommandToggled(index, enabled) => {
+                self.handle_hook_command_toggled(index, enabled)
+            }
+            Message::RemoveHookCommand(index) => self.handle_remove_hook_command(index),
+            Message::RunImporter(index) => self.handle_run_importer(index),
+            Message::PluginImported(plugin, source, result) => {
+                self.handle_plugin_imported(&plugin, &source, result)
+            }
+            Message::ImportPathChanged(path) => self.handle_import_path_changed(path),
+            Message::ImportInsomnia => self.handle_import_insomnia(),
+            Message::ImportBruno => self.handle_import_bruno(),
             Message::MoveCollectionUp(path) => {
                 if let Some(mut workspace) = self.workspace.configured_state()
                     && let Some(pos) = workspace.http_file_order().iter().position(|p| p == &path)
@@ -432,22 +458,16 @@ impl Zagel {
                             err
                         ));
                     } else {
-                        new_index = Some(updated_index);
+                        new_index = Some((updated_index, file.requests.len()));
                     }
                 }
-                if let Some(updated_index) = new_index {
-                    swap_request_indices_in_selection_http(
-                        workspace.selection_mut(),
-                        path,
-                        *index,
-                        updated_index,
-                    );
-                    swap_request_indices_in_edit_selection_http(
-                        &mut self.edit_state,
-                        path,
-                        *index,
-                        updated_index,
-                    );
+                if let Some((updated_index, len)) = new_index {
+                    let reanchor = Reanchor::swapped(path, len, *index, updated_index);
+                    let selection = workspace
+                        .selection_cloned()
+                        .and_then(|id| reanchor.map(&id));
+                    workspace.set_selection(selection);
+                    self.reanchor_requests(&reanchor);
                 }
                 if let Some(message) = status_error {
                     self.update_status_with_missing(&message);
@@ -474,22 +494,16 @@ impl Zagel {
                             err
                         ));
                     } else {
-                        new_index = Some(updated_index);
+                        new_index = Some((updated_index, file.requests.len()));
                     }
                 }
-                if let Some(updated_index) = new_index {
-                    swap_request_indices_in_selection_http(
-                        workspace.selection_mut(),
-                        path,
-                        *index,
-                        updated_index,
-                    );
-                    swap_request_indices_in_edit_selection_http(
-                        &mut self.edit_state,
-                        path,
-                        *index,
-                        updated_index,
-                    );
+                if let Some((updated_index, len)) = new_index {
+                    let reanchor = Reanchor::swapped(path, len, *index, updated_index);
+                    let selection = workspace
+                        .selection_cloned()
+                        .and_then(|id| reanchor.map(&id));
+                    workspace.set_selection(selection);
+                    self.reanchor_requests(&reanchor);
                 }
                 if let Some(message) = status_error {
                     self.update_status_with_missing(&message);
@@ -497,6 +511,13 @@ impl Zagel {
                 Task::none()
             }
             Message::EnvironmentsLoaded(envs) => {
+                let changes = env_audit::diff(
+                    self.workspace.all_environments(),
+                    &envs,
+                    "file",
+                    SystemTime::now(),
+                );
+                self.env_audit.record(changes);
                 self.workspace.set_all_environments(envs);
                 self.refresh_visible_environments();
                 self.persist_state();
@@ -505,21 +526,83 @@ impl Zagel {
             }
             Message::Select(id) => {
                 self.apply_selection(&id);
-                Task::none()
+                self.ensure_pretty()
             }
             Message::MethodSelected(method) => {
                 self.draft.method = method;
                 Task::none()
             }
+            Message::NotesToggled => self.handle_notes_toggled(),
+            Message::NotesEdited(action) => self.handle_notes_edited(action),
+            Message::PathParamChanged(name, value) => self.handle_path_param_changed(name, value),
+            Message::CustomMethodChanged(name) => {
+                self.draft.method = Method::Custom(name.trim().to_string());
+                Task::none()
+            }
+            Message::HttpVersionSelected(version) => {
+                self.draft.http_version = version;
+                Task::none()
+            }
+            Message::BodyEncodingSelected(encoding) => {
+                self.draft.body_encoding = encoding;
+                Task::none()
+            }
+            Message::IdempotencyToggled(enabled) => self.handle_idempotency_toggled(enabled),
+            Message::IdempotencyReuseToggled(reuse) => self.handle_idempotency_reuse_toggled(reuse),
+            Message::CopyIdempotencyKey => self.handle_copy_idempotency_key(),
+            Message::TraceContextToggled(enabled) => self.handle_trace_context_toggled(enabled),
+            Message::TraceB3Toggled(b3) => self.handle_trace_b3_toggled(b3),
+            Message::CopyTraceId => self.handle_copy_trace_id(),
+            Message::CookieJarSelected(choice) => self.handle_cookie_jar_selected(choice),
+            Message::ClearCookies => self.handle_clear_cookies(),
+            Message::SessionSelected(name) => self.handle_session_selected(&name),
+            Message::SessionNameChanged(name) => self.handle_session_name_changed(name),
+            Message::SaveSession => self.handle_save_session(),
+            Message::DeleteSession => self.handle_delete_session(),
             Message::UrlChanged(url) => {
                 self.draft.url = url;
                 self.update_status_with_missing("Ready");
                 Task::none()
             }
+            Message::MoveUrlCredentialsToAuth => {
+                let (url, credentials) = split_url_credentials(&self.draft.url);
+                if let Some(credentials) = credentials {
+                    self.draft.url = url;
+                    self.auth = AuthState::Basic(BasicAuthState {
+                        username: credentials.username,
+                        password: credentials.password,
+                    });
+                    self.oauth2_token_cache = None;
+                    self.update_status_with_missing("Moved URL credentials to Basic auth");
+                }
+                Task::none()
+            }
+            Message::UrlBuilderToggled => self.handle_url_builder_toggled(),
+            Message::UrlPartEdited(edit) => self.handle_url_part_edited(edit),
+            Message::PreviewToggled => self.handle_preview_toggled(),
+            Message::VariablesToggled => self.handle_variables_toggled(),
+            Message::JwtInspectorToggled => self.handle_jwt_inspector_toggled(),
+            Message::JwtSourceSelected(source) => self.handle_jwt_source_selected(source),
+            Message::JwtTick => self.handle_jwt_tick(),
+            Message::VariableOverridden(name, value) => {
+                self.handle_variable_overridden(name, value)
+            }
+            Message::VariableOverrideCleared(name) => self.handle_variable_override_cleared(&name),
+            Message::ExtractVariableToggled => self.handle_extract_variable_toggled(),
+            Message::ExtractValueChanged(value) => self.handle_extract_value_changed(value),
+            Message::ExtractNameChanged(name) => self.handle_extract_name_changed(name),
+            Message::ExtractEnvironmentSelected(environment) => {
+                self.handle_extract_environment_selected(environment)
+            }
+            Message::ExtractVariableApplied => self.handle_extract_variable_applied(),
             Message::TitleChanged(title) => {
                 self.draft.title = title;
                 Task::none()
             }
+            Message::StepChanged(step) => {
+                self.draft.step = Some(step).filter(|step| !step.trim().is_empty());
+                Task::none()
+            }
             Message::ModeChanged(mode) => {
                 self.mode = mode;
                 self.update_status_with_missing("Ready");
@@ -531,9 +614,12 @@ impl Zagel {
                 self.update_status_with_missing("Ready");
                 Task::none()
             }
-            Message::GraphqlQueryEdited(action) => {
-                self.graphql_query.perform(action);
-                self.update_status_with_missing("Ready");
+            Message::ContentTypeAdded(content_type) => self.handle_content_type_added(content_type),
+            Message::GraphqlQueryEdited(action) => self.handle_graphql_query_edited(action),
+            Message::GraphqlFoldToggled => self.handle_graphql_fold_toggled(),
+            Message::GraphqlUnfoldAll => self.handle_graphql_unfold_all(),
+            Message::GraphqlWrapToggled => {
+                self.graphql_editor.wrap = !self.graphql_editor.wrap;
                 Task::none()
             }
             Message::GraphqlVariablesEdited(action) => {
@@ -541,6 +627,22 @@ impl Zagel {
                 self.update_status_with_missing("Ready");
                 Task::none()
             }
+            Message::FetchGraphqlSchema => self.handle_fetch_graphql_schema(),
+            Message::GraphqlOperationSelected(name) => self.handle_graphql_operation_selected(name),
+            Message::GraphqlSchemaFetched(endpoint, result) => {
+                self.handle_graphql_schema_fetched(endpoint, result)
+            }
+            Message::GraphqlSchemaTypeToggled(name) => {
+                self.handle_graphql_schema_type_toggled(name)
+            }
+            Message::GrpcProtoPathsChanged(paths) => self.handle_grpc_proto_paths_changed(paths),
+            Message::LoadProtos => self.handle_load_protos(),
+            Message::ProtosLoaded(result) => self.handle_protos_loaded(result),
+            Message::GrpcServiceSelected(service) => self.handle_grpc_service_selected(service),
+            Message::GrpcMethodSelected(method) => self.handle_grpc_method_selected(method),
+            Message::GrpcTemplate => self.handle_grpc_template(),
+            Message::FileAuthSaved => self.handle_file_auth_saved(),
+            Message::FileAuthCleared => self.handle_file_auth_cleared(),
             Message::AuthChanged(new_auth) => {
                 self.auth = new_auth;
                 self.oauth2_token_cache = None;
@@ -579,19 +681,89 @@ impl Zagel {
                 self.update_status_with_missing("Ready");
                 Task::none()
             }
+            Message::AssertionAdded => self.handle_assertion_added(),
+            Message::AssertionRemoved(idx) => self.handle_assertion_removed(idx),
+            Message::AssertionKindChanged(idx, kind) => {
+                self.handle_assertion_kind_changed(idx, kind)
+            }
+            Message::AssertionTargetChanged(idx, target) => {
+                self.handle_assertion_target_changed(idx, target)
+            }
+            Message::AssertionExpectedChanged(idx, expected) => {
+                self.handle_assertion_expected_changed(idx, expected)
+            }
             Message::ResponseViewChanged(display) => {
                 self.response_display = display;
                 self.update_response_viewer();
-                Task::none()
+                self.ensure_pretty()
             }
             Message::ResponseTabChanged(tab) => {
                 self.response_tab = tab;
+                self.update_response_viewer();
                 Task::none()
             }
+            Message::ResponseSearchChanged(query) => self.handle_response_search_changed(query),
+            Message::ResponseFilterChanged(expr) => self.handle_response_filter_changed(expr),
+            Message::JsonTreeToggled(pointer) => self.handle_json_tree_toggled(&pointer),
+            Message::JsonTreeCopyPath(path) => Self::handle_json_tree_copy_path(path),
+            Message::JsonTreeCopyValue(pointer) => self.handle_json_tree_copy_value(&pointer),
+            Message::ResponsePageChanged(page) => self.handle_response_page_changed(page),
+            Message::ResponseWrapToggled => self.handle_response_wrap_toggled(),
+            Message::ResponseWhitespaceToggled => self.handle_response_whitespace_toggled(),
+            Message::ResponseFontSizeChanged(size) => self.handle_response_font_size_changed(size),
+            Message::LoadMoreHex => self.handle_load_more_hex(),
+            Message::SaveResponseBody => self.handle_save_response_body(),
+            Message::ResponseBodySaved(result) => self.handle_response_body_saved(result),
+            Message::CopyResponseBodyAsFile => self.handle_copy_response_body_as_file(),
+            Message::ResponseBodyCopiedAsFile(result) => {
+                self.handle_response_body_copied_as_file(result)
+            }
+            Message::PrettyFormatted(id, pretty) => {
+                self.handle_pretty_formatted(id.as_ref(), pretty)
+            }
+            Message::ResponseSearchNext => self.handle_response_search_step(true),
+            Message::ResponseSearchPrevious => self.handle_response_search_step(false),
             Message::ToggleShortcutsHelp => {
                 self.show_shortcuts = !self.show_shortcuts;
                 Task::none()
             }
+            Message::ToggleSettings => {
+                self.show_settings = !self.show_settings;
+                if !self.show_settings {
+                    self.persist_state();
+                }
+                Task::none()
+            }
+            Message::CaCertificateInputChanged(value) => {
+                self.state.ca_certificate =
+                    Some(PathBuf::from(value.trim())).filter(|path| !path.as_os_str().is_empty());
+                self.ca_certificate_input = value;
+                Task::none()
+            }
+            Message::SkipTlsVerifyToggled(enabled) => {
+                self.state.skip_tls_verify = enabled;
+                self.persist_state();
+                Task::none()
+            }
+            Message::ForgetMissingRootsToggled(enabled) => {
+                self.state.forget_missing_roots = enabled;
+                self.persist_state();
+                Task::none()
+            }
+            Message::MissingRootRemoved(index) => self.handle_missing_root_removed(index),
+            Message::MissingRootsRetried => self.handle_missing_roots_retried(),
+            Message::MissingRootRelocateStarted(index) => {
+                self.handle_missing_root_relocate_started(index)
+            }
+            Message::MissingRootRelocateInputChanged(input) => {
+                self.handle_missing_root_relocate_input_changed(input)
+            }
+            Message::MissingRootRelocated => self.handle_missing_root_relocated(),
+            Message::MissingRootsDismissed => self.handle_missing_roots_dismissed(),
+            Message::UserAgentChanged(value) => {
+                self.state.user_agent = Some(value).filter(|agent| !agent.trim().is_empty());
+                Task::none()
+            }
             Message::CopyResponseRaw => {
                 let text = self.response.as_ref().map_or_else(
                     || self.response_viewer.text(),
@@ -609,7 +781,74 @@ impl Zagel {
                 };
                 clipboard::write(text.to_string()).map(|()| Message::CopyComplete)
             }
+            Message::ResponseHeaderFilterChanged(query) => {
+                self.handle_response_header_filter_changed(query)
+            }
+            Message::ResponseHeaderAdded(name, value) => {
+                self.handle_response_header_added(&name, &value)
+            }
+            Message::ResponseHeaderUrlOpened(url) => self.handle_response_header_url_opened(&url),
+            Message::CopyCorrelationId(value) | Message::CopyResponseHeaderValue(value) => {
+                self.update_status_with_missing(&format!("Copied {value}"));
+                clipboard::write(value).map(|()| Message::CopyComplete)
+            }
             Message::CopyComplete => Task::none(),
+            Message::BenchmarkOpen => self.handle_benchmark_open(),
+            Message::BenchmarkIterationsChanged(value) => {
+                if let Some(session) = self.benchmark.as_mut() {
+                    session.iterations_input = value;
+                }
+                Task::none()
+            }
+            Message::BenchmarkStart => self.handle_benchmark_start(),
+            Message::BenchmarkStop => self.handle_benchmark_stop(),
+            Message::BenchmarkExportPathChanged(value) => {
+                if let Some(session) = self.benchmark.as_mut() {
+                    session.export_path_input = value;
+                }
+                Task::none()
+            }
+            Message::BenchmarkExportCsv => self.handle_benchmark_export(),
+            Message::BenchmarkExported(result) => self.handle_benchmark_exported(result),
+            Message::BenchmarkClose => {
+                self.benchmark = None;
+                Task::none()
+            }
+            Message::BenchmarkSampleReady { run_id, result } => {
+                self.handle_benchmark_sample(run_id, result)
+            }
+            Message::CompareOpen => self.handle_compare_open(),
+            Message::CompareSend => self.handle_compare_send(),
+            Message::CompareClose => {
+                self.comparison = None;
+                Task::none()
+            }
+            Message::ComparePaneResized(pane_grid::ResizeEvent { split, ratio }) => {
+                if let Some(comparison) = self.comparison.as_mut() {
+                    comparison.panes.resize(split, clamp_ratio(ratio));
+                }
+                Task::none()
+            }
+            Message::CompareResponseReady {
+                send_id,
+                side,
+                result,
+            } => self.handle_compare_response_ready(send_id, side, result),
+            Message::RunFile(path) => self.handle_run_file(path),
+            Message::RunTag(tag) => self.handle_run_tag(tag),
+            Message::FavoriteToggled(id) => self.handle_favorite_toggled(&id),
+            Message::TagFilterChanged(choice) => self.handle_tag_filter_changed(choice),
+            Message::RunFileAgain => self.handle_run_file_again(),
+            Message::RunFileStop => self.handle_run_file_stop(),
+            Message::RunFileClose => {
+                self.file_run = None;
+                Task::none()
+            }
+            Message::RunStepReady { run_id, result } => self.handle_run_step_ready(run_id, result),
+            Message::FailureSnapshotToggled => self.handle_failure_snapshot_toggled(),
+            Message::RecentSendsToggled => self.handle_recent_sends_toggled(),
+            Message::HistoryEntryReplayed(entry) => self.handle_history_entry_replayed(&entry),
+            Message::FailureSnapshotDismissed => self.handle_failure_snapshot_dismissed(),
             Message::AddRequest => {
                 let planned = match AddRequestFlow::<Unplanned>::from_app(self) {
                     Ok(flow) => flow.into_planned(),
@@ -647,52 +886,156 @@ impl Zagel {
                 )
             }
             Message::Send => {
-                let env = self.environments.get(self.active_environment).cloned();
-                let mut draft = self.draft.clone();
-                let mut extra_inputs: Vec<String> = Vec::new();
-                if self.mode == RequestMode::GraphQl {
-                    draft.method = Method::Post;
-                    let query = self.graphql_query.text();
-                    let variables = self.graphql_variables.text();
-                    extra_inputs.push(query.clone());
-                    extra_inputs.push(variables.clone());
-                    draft.body = build_graphql_body(&query, &variables);
-                    if !draft.headers.contains("Content-Type") {
-                        draft.headers.push_str("\nContent-Type: application/json");
-                    }
+                if self.mode == RequestMode::Grpc {
+                    return self.send_grpc();
+                }
+                let mut draft = self.composed_draft();
+                if let Some(refresh) = self.refresh_tokens_before_send(&draft) {
+                    return refresh;
                 }
+                let env = self.request_environment(self.workspace.selection());
+                draft.resolve_dynamic();
+                let extra_inputs = if draft.graphql {
+                    vec![self.graphql_query_text(), self.graphql_variables.text()]
+                } else {
+                    Vec::new()
+                };
+                let request_id = self.workspace.selection_cloned();
+                let mut log = SendLog::new(request_id.clone());
+                log.substitution(&draft, env.as_ref());
+                let headers = draft.headers.clone();
+                self.idempotency.apply(&mut draft);
+                log.headers_added("Idempotency", &headers, &draft.headers);
+                let headers = draft.headers.clone();
+                self.trace_context.apply(&mut draft);
+                log.headers_added("Trace context", &headers, &draft.headers);
+                let headers = draft.headers.clone();
+                self.cookies.apply(
+                    &mut draft,
+                    env.as_ref(),
+                    request_id.clone(),
+                    SystemTime::now(),
+                );
+                log.headers_added("Cookie jar", &headers, &draft.headers);
+                let auth = self.effective_auth(request_id.as_ref(), &draft.headers);
+                log.auth(&auth);
+                log.plugins(&self.state.plugins, &self.state.hook_commands);
+                log.sending(
+                    draft.method.as_str(),
+                    env.as_ref().map_or_else(
+                        || draft.url.trim().to_string(),
+                        |env| apply_environment(draft.url.trim(), &env.vars),
+                    ),
+                );
+                self.send_log = Some(log);
+                self.begin_history(request_id.clone(), &draft, env.as_ref());
+                self.arm_captures(request_id.clone(), &draft);
+                self.arm_assertions(request_id.clone(), &draft);
+                let client = match self.http_client(&draft) {
+                    Ok(client) => client,
+                    Err(err) => return Task::done(Message::ResponseReady(request_id, Err(err))),
+                };
                 let extra_refs: Vec<&str> = extra_inputs.iter().map(String::as_str).collect();
                 self.status_line =
                     status_with_missing("Sending...", &draft, env.as_ref(), &extra_refs);
+                let (chunks, received) = mpsc::channel(CHUNK_BUFFER);
+                self.streaming = Some(StreamingBody::new(request_id.clone()));
+                let chunk_id = request_id.clone();
+                let progress = Task::run(received, move |chunk| {
+                    Message::ResponseChunk(chunk_id.clone(), chunk)
+                });
                 let send = Task::perform(
-                    send_request(
-                        self.client.clone(),
+                    send_with_plugins(
+                        SendHooks::from_state(&self.state),
+                        client,
                         draft,
                         env,
-                        self.auth.clone(),
+                        auth,
                         self.oauth2_token_cache.clone(),
+                        Some(chunks),
                     ),
-                    Message::ResponseReady,
+                    move |result| Message::ResponseReady(request_id, result),
                 );
-                Task::batch([send, self.automation_pulse_task()])
+                Task::batch([send, progress, self.automation_pulse_task()])
             }
-            Message::ResponseReady(result) => {
-                match result {
+            Message::TokensRefreshed(login, result) => self.handle_tokens_refreshed(&login, result),
+            Message::TokensTick => self.handle_tokens_tick(),
+            Message::ResponseChunk(request_id, chunk) => {
+                self.handle_response_chunk(request_id.as_ref(), &chunk)
+            }
+            Message::ResponseReady(request_id, result) => {
+                if self
+                    .streaming
+                    .as_ref()
+                    .is_some_and(|streaming| streaming.id == request_id)
+                {
+                    self.streaming = None;
+                }
+                let set_cookies = result
+                    .as_ref()
+                    .map_or(&[][..], |outcome| &outcome.response.headers);
+                self.cookies
+                    .store(request_id.as_ref(), set_cookies, SystemTime::now());
+                let mut log = self
+                    .send_log
+                    .take_if(|log| log.id == request_id)
+                    .unwrap_or_else(|| SendLog::new(request_id.clone()));
+                let (preview, hook_output, mut status) = match result {
                     Ok(outcome) => {
-                        self.update_status_with_missing("Received response");
+                        if let (Some(previous), Some(current)) =
+                            (&self.oauth2_token_cache, &outcome.oauth2_cache)
+                        {
+                            log.push(if previous.same_token(current) {
+                                "OAuth2: reused the cached access token"
+                            } else {
+                                "OAuth2: fetched a new access token"
+                            });
+                        }
                         self.oauth2_token_cache = outcome.oauth2_cache;
-                        self.response = Some(crate::app::view::ResponseData::from_preview(
+                        (
                             outcome.response,
-                        ));
-                    }
-                    Err(err) => {
-                        self.update_status_with_missing("Request failed");
-                        self.response = Some(crate::app::view::ResponseData::from_preview(
-                            ResponsePreview::error(err),
-                        ));
+                            outcome.hook_output,
+                            "Received response".to_string(),
+                        )
                     }
+                    Err(err) => (
+                        ResponsePreview::error(err),
+                        Vec::new(),
+                        "Request failed".to_string(),
+                    ),
+                };
+                log.response(&preview);
+                for line in hook_output {
+                    log.push(line);
+                }
+                if let Some(summary) = self.apply_captures(request_id.as_ref(), &preview) {
+                    log.push(format!("Captures: {summary}"));
+                    status = format!("{status}; {summary}");
+                }
+                self.note_login_response(request_id.as_ref(), &preview);
+                let outcomes = self.apply_assertions(request_id.as_ref(), &preview);
+                if !outcomes.is_empty() {
+                    let summary = super::assertions::summary(&outcomes);
+                    log.push(format!("Assertions: {summary}"));
+                    status = format!("{status}; {summary}");
+                }
+                if self.record_history(request_id.as_ref(), &preview, &outcomes) {
+                    log.push("History: saved a failure snapshot");
+                    status.push_str("; failure snapshot saved");
+                }
+                self.update_status_with_missing(&status);
+                let mut response = crate::app::view::ResponseData::from_preview(preview);
+                response.assertions = outcomes;
+                response.log = log.entries;
+                let is_current = request_id == self.workspace.selection_cloned();
+                if let Some(id) = request_id {
+                    self.responses.insert(id, response.clone());
+                }
+                if is_current {
+                    self.response = Some(response);
+                    self.update_response_viewer();
+                    return self.ensure_pretty();
                 }
-                self.update_response_viewer();
                 Task::none()
             }
             Message::EnvironmentChanged(name) => {
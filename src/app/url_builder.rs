@@ -0,0 +1,105 @@
+use iced::Task;
+
+use crate::urls::UrlParts;
+
+use super::{Message, Zagel};
+
+/// One change made in the URL builder panel.
+#[derive(Debug, Clone)]
+pub enum UrlPartEdit {
+    Scheme(String),
+    Host(String),
+    Segment(usize, String),
+    AddSegment,
+    RemoveSegment(usize),
+    QueryName(usize, String),
+    QueryValue(usize, String),
+    AddQuery,
+    RemoveQuery(usize),
+}
+
+fn apply_edit(parts: &mut UrlParts, edit: UrlPartEdit) {
+    match edit {
+        UrlPartEdit::Scheme(scheme) => parts.scheme = scheme,
+        UrlPartEdit::Host(host) => parts.host = host,
+        UrlPartEdit::Segment(index, segment) => {
+            if let Some(existing) = parts.segments.get_mut(index) {
+                *existing = segment;
+            }
+        }
+        UrlPartEdit::AddSegment => parts.segments.push(String::new()),
+        UrlPartEdit::RemoveSegment(index) => {
+            if index < parts.segments.len() {
+                parts.segments.remove(index);
+            }
+        }
+        UrlPartEdit::QueryName(index, name) => {
+            if let Some((existing, _)) = parts.query.get_mut(index) {
+                *existing = name;
+            }
+        }
+        UrlPartEdit::QueryValue(index, value) => {
+            if let Some((_, existing)) = parts.query.get_mut(index) {
+                *existing = value;
+            }
+        }
+        UrlPartEdit::AddQuery => parts.query.push((String::new(), String::new())),
+        UrlPartEdit::RemoveQuery(index) => {
+            if index < parts.query.len() {
+                parts.query.remove(index);
+            }
+        }
+    }
+}
+
+impl Zagel {
+    pub(super) fn handle_url_builder_toggled(&mut self) -> Task<Message> {
+        self.url_builder_open = !self.url_builder_open;
+        Task::none()
+    }
+
+    pub(super) fn handle_path_param_changed(
+        &mut self,
+        name: String,
+        value: String,
+    ) -> Task<Message> {
+        match self
+            .draft
+            .path_params
+            .iter_mut()
+            .find(|(param, _)| *param == name)
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.draft.path_params.push((name, value)),
+        }
+        self.update_status_with_missing("Ready");
+        Task::none()
+    }
+
+    /// Re-parses the URL on every edit so typing in the URL field and the
+    /// builder never get out of step.
+    pub(super) fn handle_url_part_edited(&mut self, edit: UrlPartEdit) -> Task<Message> {
+        let mut parts = UrlParts::parse(&self.draft.url);
+        apply_edit(&mut parts, edit);
+        self.draft.url = parts.to_url();
+        self.update_status_with_missing("Ready");
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UrlPartEdit, apply_edit};
+    use crate::urls::UrlParts;
+
+    #[test]
+    fn edits_rewrite_the_matching_part() {
+        let mut parts = UrlParts::parse("https://api.test/users?page=1");
+        apply_edit(&mut parts, UrlPartEdit::Segment(0, "orders".to_string()));
+        apply_edit(&mut parts, UrlPartEdit::AddQuery);
+        apply_edit(&mut parts, UrlPartEdit::QueryName(1, "sort".to_string()));
+        apply_edit(&mut parts, UrlPartEdit::QueryValue(1, "date".to_string()));
+        apply_edit(&mut parts, UrlPartEdit::RemoveQuery(0));
+        assert_eq!(parts.to_url(), "https://api.test/orders?sort=date");
+    }
+}
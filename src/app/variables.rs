@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use iced::Task;
+
+use super::{Message, Zagel};
+use crate::model::{Environment, EnvironmentScope, VariableSource};
+
+/// One row of the merged variable table for a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedVariable {
+    pub name: String,
+    pub value: String,
+    pub source: VariableSource,
+}
+
+/// The layers behind a request's environment, used to tell where each merged
+/// value came from.
+pub(super) struct VariableLayers<'a> {
+    pub active: &'a Environment,
+    pub file: &'a [(String, String)],
+    pub session: &'a BTreeMap<String, String>,
+    pub overrides: Option<&'a BTreeMap<String, String>>,
+}
+
+impl VariableLayers<'_> {
+    fn source(&self, name: &str) -> VariableSource {
+        if self
+            .overrides
+            .is_some_and(|overrides| overrides.contains_key(name))
+        {
+            VariableSource::Override
+        } else if self.session.contains_key(name) {
+            VariableSource::Session
+        } else if self.file.iter().any(|(file_name, _)| file_name == name) {
+            VariableSource::File
+        } else if self.active.vars.contains_key(name)
+            && matches!(self.active.scope, EnvironmentScope::Project(_))
+        {
+            VariableSource::Project
+        } else {
+            VariableSource::Global
+        }
+    }
+}
+
+/// `env` (the merge result) with the source of every value.
+pub(super) fn merged_variables(env: &Environment, layers: &VariableLayers) -> Vec<MergedVariable> {
+    env.vars
+        .iter()
+        .map(|(name, value)| MergedVariable {
+            name: name.clone(),
+            value: value.clone(),
+            source: layers.source(name),
+        })
+        .collect()
+}
+
+impl Zagel {
+    /// Variables the selected request sees, with their sources.
+    pub(super) fn selected_variables(&self) -> Vec<MergedVariable> {
+        let request = self.workspace.selection();
+        let (Some(env), Some(active)) = (
+            self.request_environment(request),
+            self.environments.get(self.active_environment),
+        ) else {
+            return Vec::new();
+        };
+        merged_variables(
+            &env,
+            &VariableLayers {
+                active,
+                file: self.file_variables(request),
+                session: &self.session_variables,
+                overrides: request.and_then(|id| self.variable_overrides.get(id)),
+            },
+        )
+    }
+
+    pub(super) fn handle_variables_toggled(&mut self) -> Task<Message> {
+        self.variables_open = !self.variables_open;
+        Task::none()
+    }
+
+    pub(super) fn handle_variable_overridden(
+        &mut self,
+        name: String,
+        value: String,
+    ) -> Task<Message> {
+        let Some(id) = self.workspace.selection_cloned() else {
+            self.status_line = "Save the request to override its variables".to_string();
+            return Task::none();
+        };
+        self.variable_overrides
+            .entry(id)
+            .or_default()
+            .insert(name, value);
+        self.update_status_with_missing("Ready");
+        Task::none()
+    }
+
+    pub(super) fn handle_variable_override_cleared(&mut self, name: &str) -> Task<Message> {
+        if let Some(id) = self.workspace.selection_cloned()
+            && let Some(overrides) = self.variable_overrides.get_mut(&id)
+        {
+            overrides.remove(name);
+            if overrides.is_empty() {
+                self.variable_overrides.remove(&id);
+            }
+        }
+        self.update_status_with_missing("Ready");
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use super::{VariableLayers, merged_variables};
+    use crate::model::{Environment, EnvironmentScope, VariableSource};
+
+    fn env(name: &str, scope: EnvironmentScope, vars: &[(&str, &str)]) -> Environment {
+        Environment {
+            name: name.to_string(),
+            vars: vars
+                .iter()
+                .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                .collect(),
+            scope,
+        }
+    }
+
+    #[test]
+    fn file_beats_project_beats_global() {
+        let project = env(
+            "[project:/p] dev.env",
+            EnvironmentScope::Project(PathBuf::from("/p")),
+            &[("host", "project.test"), ("user", "alice")],
+        );
+        let global = env(
+            "[global:/g] dev.env",
+            EnvironmentScope::Global,
+            &[("host", "global.test"), ("token", "t"), ("user", "bob")],
+        );
+        let file = [("user".to_string(), "carol".to_string())];
+        let overrides = BTreeMap::from([("token".to_string(), "mine".to_string())]);
+
+        let mut merged = project
+            .clone()
+            .with_global_fallback(&[global])
+            .with_file_variables(&file);
+        merged.vars.extend(overrides.clone());
+        let rows = merged_variables(
+            &merged,
+            &VariableLayers {
+                active: &project,
+                file: &file,
+                session: &BTreeMap::new(),
+                overrides: Some(&overrides),
+            },
+        );
+        let table: Vec<_> = rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.value.as_str(), row.source))
+            .collect();
+        assert_eq!(
+            table,
+            [
+                ("host", "project.test", VariableSource::Project),
+                ("token", "mine", VariableSource::Override),
+                ("user", "carol", VariableSource::File),
+            ]
+        );
+    }
+}
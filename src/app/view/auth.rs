@@ -1,26 +1,64 @@
-use iced::widget::{column, pick_list, text, text_input};
-use iced::{Element, Length};
+use iced::widget::{button, column, pick_list, row, text, text_input};
+use iced::{Alignment, Element, Length};
 
 use super::super::Message;
 use crate::app::options::{
     ApiKeyAuthState, AuthKind, AuthState, BasicAuthState, BearerAuthState, ClientSecretMethod,
-    OAuth2ClientCredentialsAuthState,
+    HmacAlgorithm, HmacAuthState, OAuth1AuthState, OAuth1SignatureMethod,
+    OAuth2AuthorizationCodeAuthState, OAuth2ClientCredentialsAuthState, SignatureEncoding,
 };
+use crate::model::FileAuth;
 
-pub fn auth_editor(auth: &AuthState) -> Element<'_, Message> {
+/// `in_file` is set for requests saved in an .http file, whose `# @auth`
+/// default is `file_auth`.
+pub fn auth_editor<'a>(
+    auth: &'a AuthState,
+    file_auth: Option<&FileAuth>,
+    in_file: bool,
+) -> Element<'a, Message> {
     let kind_pick = pick_list(AuthKind::ALL.to_vec(), Some(auth.kind()), |kind| {
         Message::AuthChanged(auth.with_kind(kind))
     });
 
     let fields: Element<'_, Message> = match auth {
-        AuthState::None => text("No authentication").into(),
+        AuthState::None => text(file_auth.map_or_else(
+            || "No authentication".to_string(),
+            |inherited| {
+                format!(
+                    "Inherited from the file: {}",
+                    AuthState::from(inherited).kind()
+                )
+            },
+        ))
+        .into(),
         AuthState::Bearer(bearer) => bearer_fields(bearer),
         AuthState::ApiKey(api_key) => api_key_fields(api_key),
-        AuthState::Basic(basic) => basic_fields(basic),
+        AuthState::Basic(basic) => basic_fields(basic, AuthState::Basic),
+        AuthState::Digest(digest) => basic_fields(digest, AuthState::Digest),
+        AuthState::OAuth1(oauth) => oauth1_fields(oauth),
+        AuthState::Hmac(hmac) => hmac_fields(hmac),
         AuthState::OAuth2ClientCredentials(oauth) => oauth2_client_credentials_fields(oauth),
+        AuthState::OAuth2AuthorizationCode(oauth) => oauth2_authorization_code_fields(oauth),
     };
 
-    column![kind_pick, fields].spacing(4).into()
+    let mut editor = column![kind_pick, fields].spacing(4);
+    if in_file {
+        editor = editor.push(
+            row![
+                button(text("Use as file default").size(12))
+                    .padding([2, 8])
+                    .style(button::secondary)
+                    .on_press_maybe(auth.to_file_auth().map(|_| Message::FileAuthSaved)),
+                button(text("Clear file default").size(12))
+                    .padding([2, 8])
+                    .style(button::secondary)
+                    .on_press_maybe(file_auth.map(|_| Message::FileAuthCleared)),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+        );
+    }
+    editor.into()
 }
 
 fn bearer_fields(bearer: &BearerAuthState) -> Element<'_, Message> {
@@ -56,11 +94,14 @@ fn api_key_fields(api_key: &ApiKeyAuthState) -> Element<'_, Message> {
     .into()
 }
 
-fn basic_fields(basic: &BasicAuthState) -> Element<'_, Message> {
+fn basic_fields(
+    basic: &BasicAuthState,
+    wrap: fn(BasicAuthState) -> AuthState,
+) -> Element<'_, Message> {
     column![
         text_input("Username", &basic.username)
-            .on_input(|username| {
-                Message::AuthChanged(AuthState::Basic(BasicAuthState {
+            .on_input(move |username| {
+                Message::AuthChanged(wrap(BasicAuthState {
                     username,
                     password: basic.password.clone(),
                 }))
@@ -69,8 +110,8 @@ fn basic_fields(basic: &BasicAuthState) -> Element<'_, Message> {
             .width(Length::Fill),
         text_input("Password", &basic.password)
             .secure(true)
-            .on_input(|password| {
-                Message::AuthChanged(AuthState::Basic(BasicAuthState {
+            .on_input(move |password| {
+                Message::AuthChanged(wrap(BasicAuthState {
                     username: basic.username.clone(),
                     password,
                 }))
@@ -82,6 +123,112 @@ fn basic_fields(basic: &BasicAuthState) -> Element<'_, Message> {
     .into()
 }
 
+fn oauth1_fields(oauth: &OAuth1AuthState) -> Element<'_, Message> {
+    let field = |placeholder, value: &str, update: fn(&mut OAuth1AuthState, String)| {
+        text_input(placeholder, value)
+            .on_input(move |input| {
+                let mut next = oauth.clone();
+                update(&mut next, input);
+                Message::AuthChanged(AuthState::OAuth1(next))
+            })
+            .padding(4)
+            .width(Length::Fill)
+    };
+    let method_pick = pick_list(
+        OAuth1SignatureMethod::ALL.to_vec(),
+        Some(oauth.signature_method),
+        |signature_method| {
+            Message::AuthChanged(AuthState::OAuth1(OAuth1AuthState {
+                signature_method,
+                ..oauth.clone()
+            }))
+        },
+    )
+    .width(Length::Fill);
+
+    column![
+        field("Consumer key", &oauth.consumer_key, |oauth, value| {
+            oauth.consumer_key = value;
+        }),
+        field("Consumer secret", &oauth.consumer_secret, |oauth, value| {
+            oauth.consumer_secret = value;
+        })
+        .secure(true),
+        field("Token (optional)", &oauth.token, |oauth, value| {
+            oauth.token = value;
+        }),
+        field(
+            "Token secret (optional)",
+            &oauth.token_secret,
+            |oauth, value| {
+                oauth.token_secret = value;
+            }
+        )
+        .secure(true),
+        method_pick,
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn hmac_fields(hmac: &HmacAuthState) -> Element<'_, Message> {
+    let field = |placeholder, value: &str, update: fn(&mut HmacAuthState, String)| {
+        text_input(placeholder, value)
+            .on_input(move |input| {
+                let mut next = hmac.clone();
+                update(&mut next, input);
+                Message::AuthChanged(AuthState::Hmac(next))
+            })
+            .padding(4)
+            .width(Length::Fill)
+    };
+    let algorithm_pick = pick_list(
+        HmacAlgorithm::ALL.to_vec(),
+        Some(hmac.algorithm),
+        |algorithm| {
+            Message::AuthChanged(AuthState::Hmac(HmacAuthState {
+                algorithm,
+                ..hmac.clone()
+            }))
+        },
+    )
+    .width(Length::Fill);
+    let encoding_pick = pick_list(
+        SignatureEncoding::ALL.to_vec(),
+        Some(hmac.encoding),
+        |encoding| {
+            Message::AuthChanged(AuthState::Hmac(HmacAuthState {
+                encoding,
+                ..hmac.clone()
+            }))
+        },
+    )
+    .width(Length::Fill);
+
+    column![
+        field("Secret", &hmac.secret, |hmac, value| hmac.secret = value).secure(true),
+        row![algorithm_pick, encoding_pick].spacing(4),
+        field("String to sign", &hmac.template, |hmac, value| {
+            hmac.template = value;
+        }),
+        text(
+            "{method} {path} {host} {date} {timestamp} {nonce} {body_sha256} {header:X-Name}, \\n for a line break"
+        )
+        .size(12),
+        row![
+            field("Header", &hmac.header_name, |hmac, value| {
+                hmac.header_name = value;
+            }),
+            field("Value, e.g. HMAC {signature}", &hmac.header_value, |hmac, value| {
+                hmac.header_value = value;
+            }),
+        ]
+        .spacing(4),
+    ]
+    .spacing(4)
+    .into()
+}
+
 fn oauth2_client_credentials_fields(
     oauth: &OAuth2ClientCredentialsAuthState,
 ) -> Element<'_, Message> {
@@ -137,3 +284,50 @@ fn oauth2_client_credentials_fields(
     .spacing(4)
     .into()
 }
+
+fn oauth2_authorization_code_fields(
+    oauth: &OAuth2AuthorizationCodeAuthState,
+) -> Element<'_, Message> {
+    let field =
+        |placeholder, value: &str, update: fn(&mut OAuth2AuthorizationCodeAuthState, String)| {
+            text_input(placeholder, value)
+                .on_input(move |input| {
+                    let mut next = oauth.clone();
+                    update(&mut next, input);
+                    Message::AuthChanged(AuthState::OAuth2AuthorizationCode(next))
+                })
+                .padding(4)
+                .width(Length::Fill)
+        };
+
+    column![
+        field("Authorization URL", &oauth.authorize_url, |oauth, value| {
+            oauth.authorize_url = value;
+        }),
+        field("Token URL", &oauth.token_url, |oauth, value| {
+            oauth.token_url = value;
+        }),
+        field("Client ID", &oauth.client_id, |oauth, value| {
+            oauth.client_id = value;
+        }),
+        field(
+            "Client secret (optional)",
+            &oauth.client_secret,
+            |oauth, value| oauth.client_secret = value,
+        )
+        .secure(true),
+        field("Scope (optional)", &oauth.scope, |oauth, value| {
+            oauth.scope = value;
+        }),
+        field(
+            "Redirect port (empty: any free port)",
+            &oauth.redirect_port,
+            |oauth, value| {
+                oauth.redirect_port = value;
+            }
+        ),
+        text("The browser opens to sign in on the first send; the token is cached.").size(12),
+    ]
+    .spacing(4)
+    .into()
+}
@@ -0,0 +1,199 @@
+use std::time::Duration;
+
+use iced::widget::{button, column, container, row, rule, scrollable, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use crate::app::benchmark::{
+    BenchmarkPhase, BenchmarkSession, BenchmarkVariant, Histogram, LatencyStats, LatencySummary,
+};
+use crate::theme;
+
+const METRIC_WIDTH: f32 = 90.0;
+const VALUE_WIDTH: f32 = 110.0;
+const BAR_WIDTH: usize = 24;
+
+type Metric = fn(&LatencyStats) -> Duration;
+
+pub fn benchmark_panel(session: &BenchmarkSession) -> Element<'_, Message> {
+    let header = row![
+        text("Benchmark A/B").size(16).width(Length::Fill),
+        button("Close").on_press(Message::BenchmarkClose),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+
+    let targets = BenchmarkVariant::ALL.into_iter().map(|variant| {
+        let target = session.target(variant);
+        text(format!("{variant}: {}", target.draft.step_label()))
+            .size(13)
+            .into()
+    });
+
+    let run_button = if session.is_running() {
+        button("Stop").on_press(Message::BenchmarkStop)
+    } else {
+        button("Run").on_press(Message::BenchmarkStart)
+    };
+    let controls = row![
+        text("Iterations").size(13),
+        text_input("20", &session.iterations_input)
+            .on_input(Message::BenchmarkIterationsChanged)
+            .padding(4)
+            .width(Length::Fixed(80.0)),
+        run_button,
+        text(progress_label(session)).size(13),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+
+    let summary_a = session.summary(BenchmarkVariant::A);
+    let summary_b = session.summary(BenchmarkVariant::B);
+
+    let mut body = column![
+        header,
+        column(targets).spacing(2),
+        controls,
+        rule::horizontal(1),
+        summary_table(&summary_a, &summary_b),
+    ]
+    .spacing(8);
+    for variant in BenchmarkVariant::ALL {
+        if let Some(err) = session.last_error(variant) {
+            body = body.push(text(format!("{variant} last error: {err}")).size(12));
+        }
+    }
+    if let Some(histogram) = session.histogram() {
+        body = body
+            .push(rule::horizontal(1))
+            .push(histogram_view(&histogram));
+    }
+    body = body.push(export_row(session));
+
+    container(scrollable(body).height(Length::Shrink))
+        .padding(12)
+        .max_width(560.0)
+        .style(theme::overlay_container_style)
+        .into()
+}
+
+fn export_row(session: &BenchmarkSession) -> Element<'_, Message> {
+    let export = button("Export CSV").on_press_maybe(
+        (session.has_timings() && !session.is_running()).then_some(Message::BenchmarkExportCsv),
+    );
+    row![
+        text_input("benchmark-timings.csv", &session.export_path_input)
+            .on_input(Message::BenchmarkExportPathChanged)
+            .padding(4)
+            .width(Length::Fill),
+        export,
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8)
+    .into()
+}
+
+fn progress_label(session: &BenchmarkSession) -> String {
+    match session.phase {
+        BenchmarkPhase::Configuring => "Not started".to_string(),
+        BenchmarkPhase::Running { step, total, .. } => format!("{step}/{total} sends"),
+        BenchmarkPhase::Finished => "Finished".to_string(),
+        BenchmarkPhase::Stopped => "Stopped".to_string(),
+    }
+}
+
+fn summary_table<'a>(a: &LatencySummary, b: &LatencySummary) -> Element<'a, Message> {
+    let mut table = column![table_row("", "A", "B", "Δ B vs A")].spacing(2);
+    table = table.push(table_row(
+        "responses",
+        &a.responses.to_string(),
+        &b.responses.to_string(),
+        "",
+    ));
+    table = table.push(table_row(
+        "non-2xx",
+        &a.non_success.to_string(),
+        &b.non_success.to_string(),
+        "",
+    ));
+    table = table.push(table_row(
+        "errors",
+        &a.failures.to_string(),
+        &b.failures.to_string(),
+        "",
+    ));
+
+    let metrics: [(&str, Metric); 6] = [
+        ("min", |stats| stats.min),
+        ("mean", |stats| stats.mean),
+        ("p50", |stats| stats.p50),
+        ("p90", |stats| stats.p90),
+        ("p99", |stats| stats.p99),
+        ("max", |stats| stats.max),
+    ];
+    for (label, metric) in metrics {
+        let value_a = a.latency.as_ref().map(metric);
+        let value_b = b.latency.as_ref().map(metric);
+        table = table.push(table_row(
+            label,
+            &value_a.map_or_else(|| "-".to_string(), format_millis),
+            &value_b.map_or_else(|| "-".to_string(), format_millis),
+            &value_a
+                .zip(value_b)
+                .map_or_else(String::new, |(a, b)| format_delta(a, b)),
+        ));
+    }
+
+    table.into()
+}
+
+fn table_row<'a>(label: &str, a: &str, b: &str, delta: &str) -> Element<'a, Message> {
+    row![
+        text(label.to_string())
+            .size(13)
+            .width(Length::Fixed(METRIC_WIDTH)),
+        text(a.to_string())
+            .size(13)
+            .width(Length::Fixed(VALUE_WIDTH)),
+        text(b.to_string())
+            .size(13)
+            .width(Length::Fixed(VALUE_WIDTH)),
+        text(delta.to_string()).size(13),
+    ]
+    .spacing(6)
+    .into()
+}
+
+fn histogram_view<'a>(histogram: &Histogram) -> Element<'a, Message> {
+    let max_count = histogram.max_count().max(1);
+    let bar = |count: usize| "█".repeat(count * BAR_WIDTH / max_count);
+
+    let mut rows = column![text("Latency distribution").size(14)].spacing(2);
+    for bucket in &histogram.buckets {
+        rows = rows.push(
+            row![
+                text(format!("≤ {}", format_millis(bucket.upper)))
+                    .size(12)
+                    .width(Length::Fixed(METRIC_WIDTH)),
+                column![
+                    text(format!("A {} {}", bar(bucket.count_a), bucket.count_a)).size(12),
+                    text(format!("B {} {}", bar(bucket.count_b), bucket.count_b)).size(12),
+                ],
+            ]
+            .spacing(6),
+        );
+    }
+    rows.into()
+}
+
+fn format_millis(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn format_delta(a: Duration, b: Duration) -> String {
+    let a = a.as_secs_f64();
+    if a <= f64::EPSILON {
+        return String::new();
+    }
+    format!("{:+.1}%", (b.as_secs_f64() - a) / a * 100.0)
+}
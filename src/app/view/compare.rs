@@ -0,0 +1,86 @@
+use iced::widget::pane_grid::{self, PaneGrid};
+use iced::widget::{button, column, container, row, rule, scrollable, text, text_editor};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use super::response::status_header;
+use super::section;
+use crate::app::compare::{CompareSide, CompareSlot, Comparison};
+use crate::app::pin::comparison as compare_responses;
+
+/// Height of the request summary, fixed so both responses start on the
+/// same line.
+const REQUEST_HEIGHT: f32 = 180.0;
+
+pub fn compare_view(comparison: &Comparison) -> Element<'_, Message> {
+    let send = if comparison.is_sending() {
+        button("Sending...")
+    } else {
+        button("Send both").on_press(Message::CompareSend)
+    };
+    let mut header = row![
+        text("Compare").size(18),
+        send,
+        button("Close").on_press(Message::CompareClose),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+    if let (Some(a), Some(b)) = (&comparison.a.response, &comparison.b.response) {
+        header = header.push(text(format!("B vs A: {}", compare_responses(a, b))).size(13));
+    }
+
+    let grid = PaneGrid::new(&comparison.panes, |_, side, _| {
+        pane_grid::Content::new(side_view(*side, comparison.slot(*side)))
+    })
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .spacing(8.0)
+    .on_resize(6, Message::ComparePaneResized);
+
+    container(column![header, grid].spacing(8))
+        .padding(8)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+fn side_view(side: CompareSide, slot: &CompareSlot) -> Element<'_, Message> {
+    let draft = &slot.draft;
+    let mut request = column![
+        text(format!("{side}: {}", draft.label())).size(15),
+        text(format!("{} {}", draft.method, draft.url)).size(13),
+    ]
+    .spacing(4);
+    if !draft.headers.trim().is_empty() {
+        request = request.push(text(draft.headers.clone()).size(12));
+    }
+    if !draft.body.trim().is_empty() {
+        request = request.push(text(draft.body.clone()).size(12));
+    }
+    let request = container(scrollable(request).height(Length::Fill))
+        .height(Length::Fixed(REQUEST_HEIGHT))
+        .width(Length::Fill);
+
+    let response: Element<'_, Message> = if slot.sending {
+        text("Sending...").size(13).into()
+    } else if let Some(response) = &slot.response {
+        let mut response_view = column![status_header(&response.preview)].spacing(6);
+        if let Some(error) = &response.preview.error {
+            response_view = response_view.push(text(error.clone()).size(12).style(text::danger));
+        }
+        response_view
+            .push(text_editor(&slot.viewer).height(Length::Fill))
+            .height(Length::Fill)
+            .into()
+    } else {
+        text("No response yet").size(13).into()
+    };
+
+    section(
+        "Request",
+        column![request, rule::horizontal(1), response]
+            .spacing(8)
+            .height(Length::Fill)
+            .into(),
+    )
+}
@@ -0,0 +1,52 @@
+use iced::widget::{button, column, container, row, rule, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use crate::app::duplicates::DuplicateGroup;
+use crate::model::RequestId;
+use crate::theme;
+
+pub fn duplicates_panel(groups: &[DuplicateGroup]) -> Element<'_, Message> {
+    let header = row![
+        text("Duplicate requests").size(16).width(Length::Fill),
+        button("Close").on_press(Message::DuplicatesToggled),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+
+    let mut body = column![header, rule::horizontal(1)].spacing(8);
+    if groups.is_empty() {
+        body = body.push(text("No two requests share a method and URL").size(13));
+    }
+    for group in groups {
+        let mut entries =
+            column![text(format!("{} {}", group.method, group.url)).size(14)].spacing(4);
+        for id in &group.requests {
+            let RequestId::HttpFile { path, index } = id;
+            entries = entries.push(
+                row![
+                    text(format!("{} #{index}", path.display()))
+                        .size(12)
+                        .width(Length::Fill),
+                    button(text("Open").size(12))
+                        .padding([2, 6])
+                        .style(button::secondary)
+                        .on_press(Message::Select(id.clone())),
+                    button(text("Keep this one").size(12))
+                        .padding([2, 6])
+                        .style(button::danger)
+                        .on_press(Message::DuplicateKeep(id.clone())),
+                ]
+                .align_y(Alignment::Center)
+                .spacing(6),
+            );
+        }
+        body = body.push(entries);
+    }
+
+    container(scrollable(body).height(Length::Shrink))
+        .padding(12)
+        .max_width(640.0)
+        .style(theme::overlay_container_style)
+        .into()
+}
@@ -0,0 +1,113 @@
+use iced::widget::{button, checkbox, column, container, row, rule, scrollable, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use crate::app::find_replace::{FindReplace, Replacement};
+use crate::model::RequestId;
+use crate::theme;
+
+/// Requests listed in the preview; the rest are only counted.
+const PREVIEW_LIMIT: usize = 50;
+
+pub fn find_replace_panel(find: &FindReplace) -> Element<'_, Message> {
+    let header = row![
+        text("Find and replace in all requests")
+            .size(16)
+            .width(Length::Fill),
+        button("Close").on_press(Message::FindReplaceToggled),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+
+    let inputs = column![
+        text_input("Find", &find.query)
+            .on_input(Message::FindQueryChanged)
+            .padding(4),
+        text_input("Replace with", &find.replacement)
+            .on_input(Message::ReplaceTextChanged)
+            .on_submit(Message::FindReplaceApply)
+            .padding(4),
+        checkbox(find.regex)
+            .label("Regular expression ($1 refers to groups)")
+            .on_toggle(Message::FindRegexToggled),
+    ]
+    .spacing(6);
+
+    let mut body = column![header, inputs, rule::horizontal(1)].spacing(8);
+    match &find.preview {
+        Err(err) => body = body.push(text(err.as_str()).size(13).style(text::danger)),
+        Ok(planned) if planned.is_empty() => {
+            body = body.push(text("No matches in request URLs, headers or bodies").size(13));
+        }
+        Ok(planned) => {
+            let matches = planned
+                .iter()
+                .map(|replacement| replacement.matches)
+                .sum::<usize>();
+            body = body.push(
+                row![
+                    text(format!("{matches} matches in {} requests", planned.len()))
+                        .size(13)
+                        .width(Length::Fill),
+                    button("Apply").on_press(Message::FindReplaceApply),
+                ]
+                .align_y(Alignment::Center),
+            );
+            for replacement in planned.iter().take(PREVIEW_LIMIT) {
+                body = body.push(preview_row(replacement));
+            }
+            if planned.len() > PREVIEW_LIMIT {
+                body = body
+                    .push(text(format!("… and {} more", planned.len() - PREVIEW_LIMIT)).size(12));
+            }
+        }
+    }
+
+    container(scrollable(body).height(Length::Shrink))
+        .padding(12)
+        .max_width(640.0)
+        .style(theme::overlay_container_style)
+        .into()
+}
+
+fn preview_row(replacement: &Replacement) -> Element<'_, Message> {
+    let RequestId::HttpFile { path, .. } = &replacement.id;
+    let file = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    let mut cell = column![
+        text(format!(
+            "{file} • {} {} ({} matches)",
+            replacement.before.method, replacement.before.title, replacement.matches
+        ))
+        .size(13)
+    ]
+    .spacing(2);
+    if replacement.before.url != replacement.after.url {
+        cell = cell
+            .push(
+                text(format!("- {}", replacement.before.url))
+                    .size(12)
+                    .style(text::danger),
+            )
+            .push(
+                text(format!("+ {}", replacement.after.url))
+                    .size(12)
+                    .style(text::success),
+            );
+    }
+    for (field, before, after) in [
+        (
+            "headers",
+            &replacement.before.headers,
+            &replacement.after.headers,
+        ),
+        ("body", &replacement.before.body, &replacement.after.body),
+    ] {
+        if before != after {
+            cell = cell.push(text(format!("  {field} changed")).size(12));
+        }
+    }
+    cell.into()
+}
@@ -0,0 +1,90 @@
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+use super::super::graphql_schema::{SchemaField, SchemaType};
+use super::super::{Message, Zagel};
+
+fn field_entry(field: &SchemaField) -> Element<'_, Message> {
+    let mut entry = column![text(field.signature()).size(13)];
+    if let Some(description) = &field.description {
+        entry = entry.push(text(description).size(12));
+    }
+    if let Some(reason) = &field.deprecation {
+        let note = if reason.is_empty() {
+            "Deprecated".to_string()
+        } else {
+            format!("Deprecated: {reason}")
+        };
+        entry = entry.push(text(note).size(12));
+    }
+    entry.padding([0, 8]).into()
+}
+
+fn type_entry(ty: &SchemaType, expanded: bool) -> Element<'_, Message> {
+    let marker = if expanded { "-" } else { "+" };
+    let mut entry = column![
+        button(text(format!("{marker} {} ({})", ty.name, ty.kind.to_lowercase())).size(13))
+            .style(button::text)
+            .padding([2, 4])
+            .on_press(Message::GraphqlSchemaTypeToggled(ty.name.clone()))
+    ];
+    if expanded {
+        if let Some(description) = &ty.description {
+            entry = entry.push(text(description).size(12));
+        }
+        for field in &ty.fields {
+            entry = entry.push(field_entry(field));
+        }
+        if !ty.enum_values.is_empty() {
+            entry = entry.push(text(ty.enum_values.join(" | ")).size(13));
+        }
+    }
+    entry.spacing(2).into()
+}
+
+/// Queries, mutations and types of the endpoint's cached schema.
+pub fn schema_explorer(app: &Zagel) -> Element<'_, Message> {
+    let schema = app.graphql_schema();
+    let label = if schema.is_some() {
+        "Refresh"
+    } else {
+        "Fetch schema"
+    };
+    let header = row![
+        text("Schema").width(Length::Fill),
+        button(text(label).size(12))
+            .on_press_maybe((!app.graphql_schemas.fetching).then_some(Message::FetchGraphqlSchema)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6);
+
+    let Some(schema) = schema else {
+        return column![
+            header,
+            text("Fetch the schema to browse its queries, mutations and types.").size(12)
+        ]
+        .spacing(6)
+        .into();
+    };
+
+    let expanded = app.graphql_schemas.expanded.as_deref();
+    let mut entries = column![].spacing(4);
+    for (label, root) in schema.operations() {
+        entries = entries.push(text(label).size(14));
+        for field in &root.fields {
+            entries = entries.push(field_entry(field));
+        }
+    }
+    entries = entries.push(text("Types").size(14));
+    for ty in schema.browsable_types() {
+        entries = entries.push(type_entry(ty, expanded == Some(ty.name.as_str())));
+    }
+
+    column![
+        header,
+        container(scrollable(entries).height(Length::Fill)).height(Length::Fill)
+    ]
+    .height(Length::Fill)
+    .spacing(6)
+    .into()
+}
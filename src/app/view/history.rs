@@ -0,0 +1,124 @@
+use iced::widget::{button, column, container, row, rule, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use crate::history::HistoryEntry;
+use crate::theme;
+
+/// Newest sends shown in the recent sends panel.
+const RECENT_LIMIT: usize = 50;
+
+fn replay_button(entry: &HistoryEntry) -> Element<'_, Message> {
+    button(text("Edit & resend").size(12))
+        .padding([2, 8])
+        .on_press_maybe(
+            entry
+                .sent
+                .is_some()
+                .then(|| Message::HistoryEntryReplayed(Box::new(entry.clone()))),
+        )
+        .into()
+}
+
+pub fn recent_sends_panel(entries: &[HistoryEntry]) -> Element<'_, Message> {
+    let header = row![
+        text("Recent sends").size(16).width(Length::Fill),
+        button("Close").on_press(Message::RecentSendsToggled),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+    let mut list = column![].spacing(4);
+    for entry in entries.iter().rev().take(RECENT_LIMIT) {
+        let outcome = entry
+            .status
+            .map_or_else(|| "no response".to_string(), |status| status.to_string());
+        let outcome = if entry.failed() {
+            text(outcome).size(12).style(text::danger)
+        } else {
+            text(outcome).size(12)
+        };
+        list = list.push(
+            row![
+                outcome.width(Length::Fixed(80.0)),
+                text(format!("{} {}", entry.method, entry.url))
+                    .size(12)
+                    .width(Length::Fill),
+                text(entry.environment.as_deref().unwrap_or("-")).size(12),
+                replay_button(entry),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(8),
+        );
+    }
+    if entries.is_empty() {
+        list = list.push(text("Nothing has been sent yet").size(12));
+    }
+
+    container(
+        column![
+            header,
+            rule::horizontal(1),
+            scrollable(list).height(Length::Shrink)
+        ]
+        .spacing(6),
+    )
+    .padding(12)
+    .max_width(720.0)
+    .style(theme::overlay_container_style)
+    .into()
+}
+
+pub fn failure_snapshot_panel(entry: &HistoryEntry) -> Element<'_, Message> {
+    let header = row![
+        text("Failure snapshot").size(16).width(Length::Fill),
+        replay_button(entry),
+        button("Dismiss").on_press(Message::FailureSnapshotDismissed),
+        button("Close").on_press(Message::FailureSnapshotToggled),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+
+    let outcome = entry.status.map_or_else(
+        || "no response".to_string(),
+        |status| format!("status {status}"),
+    );
+    let timing = entry
+        .duration_ms
+        .map_or_else(|| "-".to_string(), |ms| format!("{ms} ms"));
+    let mut body = column![
+        header,
+        text(format!("{} {}", entry.method, entry.url)).size(13),
+        text(format!(
+            "Environment: {} · {outcome} · {timing}",
+            entry.environment.as_deref().unwrap_or("none")
+        ))
+        .size(12),
+        rule::horizontal(1),
+    ]
+    .spacing(6);
+    let failures = entry.error.iter().chain(
+        entry
+            .snapshot
+            .iter()
+            .flat_map(|snapshot| &snapshot.assertion_failures),
+    );
+    for failure in failures {
+        body = body.push(text(failure.as_str()).size(12).style(text::danger));
+    }
+    if let Some(snapshot) = &entry.snapshot {
+        if !snapshot.headers.is_empty() {
+            body = body.push(text("Headers").size(13));
+            body = body.push(text(snapshot.headers.join("\n")).size(12));
+        }
+        if !snapshot.body.is_empty() {
+            body = body.push(text("Body").size(13));
+            body = body.push(text(snapshot.body.as_str()).size(12));
+        }
+    }
+
+    container(scrollable(body).height(Length::Shrink))
+        .padding(12)
+        .max_width(640.0)
+        .style(theme::overlay_container_style)
+        .into()
+}
@@ -0,0 +1,87 @@
+use iced::widget::{button, column, container, pick_list, row, rule, scrollable, text};
+use iced::{Alignment, Element, Font, Length};
+
+use super::super::Message;
+use super::super::jwt::{DecodedJwt, JwtInspector, decode, expiry_text, format_timestamp};
+use crate::theme;
+
+/// Decoded header and claims of a JWT from the request or the last response,
+/// with a live expiry countdown.
+pub fn jwt_panel(inspector: &JwtInspector) -> Element<'_, Message> {
+    let mut header = row![text("JWT").size(16).width(Length::Fill)]
+        .align_y(Alignment::Center)
+        .spacing(8);
+    if inspector.sources.len() > 1 {
+        header = header.push(pick_list(
+            inspector.sources.clone(),
+            inspector.selected.clone(),
+            Message::JwtSourceSelected,
+        ));
+    }
+    header = header.push(button("Close").on_press(Message::JwtInspectorToggled));
+
+    let body: Element<'_, Message> = match inspector
+        .selected
+        .as_ref()
+        .map(|source| decode(&source.token))
+    {
+        Some(Ok(jwt)) => decoded_view(jwt, inspector.now),
+        Some(Err(err)) => text(err).size(12).style(text::danger).into(),
+        None => text("No JWT selected").size(12).into(),
+    };
+
+    container(
+        column![
+            header,
+            text("The signature is not verified.").size(12),
+            rule::horizontal(1),
+            body
+        ]
+        .spacing(6),
+    )
+    .padding(12)
+    .max_width(720.0)
+    .style(theme::overlay_container_style)
+    .into()
+}
+
+fn decoded_view<'a>(jwt: DecodedJwt, now: i64) -> Element<'a, Message> {
+    let mut times = column![].spacing(2);
+    match jwt.expires_at {
+        Some(exp) => {
+            let line = text(format!(
+                "exp {} ({})",
+                format_timestamp(exp),
+                expiry_text(exp, now)
+            ))
+            .size(12);
+            times = times.push(if exp > now {
+                line.style(text::success)
+            } else {
+                line.style(text::danger)
+            });
+        }
+        None => times = times.push(text("No exp claim: the token does not expire").size(12)),
+    }
+    if let Some(iat) = jwt.issued_at {
+        times = times.push(text(format!("iat {}", format_timestamp(iat))).size(12));
+    }
+    if let Some(nbf) = jwt.not_before {
+        let line = text(format!("nbf {}", format_timestamp(nbf))).size(12);
+        times = times.push(if nbf > now {
+            line.style(text::warning)
+        } else {
+            line
+        });
+    }
+    let json = |value: String| text(value).size(12).font(Font::MONOSPACE);
+    column![
+        times,
+        text("Header").size(14),
+        json(jwt.header),
+        text("Payload").size(14),
+        scrollable(json(jwt.payload)).height(Length::Shrink),
+    ]
+    .spacing(6)
+    .into()
+}
@@ -0,0 +1,58 @@
+use iced::widget::{button, column, container, row, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use super::super::missing_roots::MissingRoots;
+
+/// Saved folders that could not be opened at startup, with actions to
+/// remove, relocate or check them again.
+pub fn missing_roots_banner(missing: &MissingRoots) -> Element<'_, Message> {
+    let mut rows = column![
+        row![
+            text("Some saved folders are unreachable")
+                .size(14)
+                .width(Length::Fill),
+            button("Retry").on_press(Message::MissingRootsRetried),
+            button("Dismiss").on_press(Message::MissingRootsDismissed),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6)
+    ]
+    .spacing(4);
+    for (index, root) in missing.roots.iter().enumerate() {
+        let relocating = missing
+            .relocating
+            .as_ref()
+            .filter(|(relocating, _)| *relocating == index);
+        let line = row![
+            text(format!(
+                "{} {}: {}",
+                root.kind,
+                root.path.display(),
+                root.reason
+            ))
+            .size(12)
+            .width(Length::Fill),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6);
+        let line = if let Some((_, input)) = relocating {
+            line.push(
+                text_input("New folder", input)
+                    .on_input(Message::MissingRootRelocateInputChanged)
+                    .on_submit(Message::MissingRootRelocated)
+                    .padding(4)
+                    .width(Length::FillPortion(1)),
+            )
+            .push(button("Use folder").on_press(Message::MissingRootRelocated))
+        } else {
+            line.push(button("Relocate").on_press(Message::MissingRootRelocateStarted(index)))
+        };
+        rows = rows.push(line.push(button("Remove").on_press(Message::MissingRootRemoved(index))));
+    }
+    container(rows)
+        .padding(8)
+        .width(Length::Fill)
+        .style(container::warning)
+        .into()
+}
@@ -1,17 +1,35 @@
 mod auth;
+mod benchmark;
+mod compare;
+mod duplicates;
+mod find_replace;
+mod graphql_schema;
+mod history;
+mod jwt;
+mod missing_roots;
+mod pin;
 mod response;
+mod runner;
+mod settings;
 mod sidebar;
+mod variables;
 mod workspace;
 
 use iced::widget::pane_grid::{self, PaneGrid};
-use iced::widget::{column, container, row, rule, space, text};
+use iced::widget::{button, column, container, row, rule, space, text};
 use iced::{Element, Length};
 
+use super::automation::ScreenshotRegion;
 use super::{Message, Zagel};
+use crate::model::RequestId;
+use compare::compare_view;
+use missing_roots::missing_roots_banner;
 use sidebar::{SidebarContext, sidebar};
 use workspace::workspace;
 
-pub use response::{ResponseData, ResponseDisplay, ResponseTab};
+pub use response::{
+    PrettyBody, ResponseData, ResponseDisplay, ResponseTab, format_pretty, format_size,
+};
 pub use sidebar::IconSet;
 pub use workspace::{BuilderPane, WorkspacePane};
 
@@ -32,35 +50,65 @@ pub fn section<'a, Message: 'a>(
         .into()
 }
 
+fn sidebar_context(app: &Zagel) -> SidebarContext<'_> {
+    SidebarContext {
+        http_files: app.workspace.http_files(),
+        http_file_order: app.workspace.http_file_order(),
+        selection: app.workspace.selection(),
+        collapsed: &app.collapsed_collections,
+        project_roots: app.project_roots(),
+        global_env_roots: app.global_env_roots(),
+        project_path_input: &app.project_path_input,
+        global_env_path_input: &app.global_env_path_input,
+        edit_state: &app.edit_state,
+        icon_set: app.icon_set,
+        split_threshold: app.state.split_threshold(),
+        grouping: app.state.sidebar_grouping,
+        environment: app.environments.get(app.active_environment),
+        history: &app.history,
+        env_audit: &app.env_audit,
+        context_menu: app.context_menu.as_ref(),
+        batch_headers: &app.batch_headers,
+        tag_filter: app.tag_filter.as_deref(),
+        filter: &app.sidebar_filter,
+        favorites: &app.state.favorites,
+        transfer: &app.transfer,
+    }
+}
+
+/// Requests the sidebar shows, top to bottom, ignoring the Favorites group.
+pub fn visible_requests(app: &Zagel) -> Vec<RequestId> {
+    sidebar::visible_requests(&sidebar_context(app))
+}
+
 pub fn view(app: &Zagel) -> Element<'_, Message> {
     let app_ref = app;
 
     let grid = PaneGrid::new(&app_ref.panes, move |_, pane, _| match pane {
-        PaneContent::Sidebar => pane_grid::Content::new(sidebar(SidebarContext {
-            http_files: app_ref.workspace.http_files(),
-            http_file_order: app_ref.workspace.http_file_order(),
-            selection: app_ref.workspace.selection(),
-            collapsed: &app_ref.collapsed_collections,
-            project_roots: app_ref.project_roots(),
-            global_env_roots: app_ref.global_env_roots(),
-            project_path_input: &app_ref.project_path_input,
-            global_env_path_input: &app_ref.global_env_path_input,
-            edit_state: &app_ref.edit_state,
-            icon_set: app_ref.icon_set,
-        })),
-        PaneContent::Workspace => pane_grid::Content::new(workspace(app_ref)),
+        PaneContent::Sidebar => pane_grid::Content::new(
+            container(sidebar(sidebar_context(app_ref))).id(ScreenshotRegion::Sidebar.widget_id()),
+        ),
+        PaneContent::Workspace => pane_grid::Content::new(
+            app_ref
+                .comparison
+                .as_ref()
+                .map_or_else(|| workspace(app_ref), compare_view),
+        ),
     })
     .width(Length::Fill)
     .height(Length::Fill)
     .spacing(8.0)
     .on_resize(6, Message::PaneResized);
 
-    column![
-        container(grid).height(Length::Fill),
-        rule::horizontal(1),
-        status_bar(app_ref)
-    ]
-    .into()
+    let mut layout = column![];
+    if app_ref.missing_roots.is_visible() {
+        layout = layout.push(missing_roots_banner(&app_ref.missing_roots));
+    }
+    layout
+        .push(container(grid).height(Length::Fill))
+        .push(rule::horizontal(1))
+        .push(status_bar(app_ref))
+        .into()
 }
 
 fn status_bar(app: &Zagel) -> Element<'_, Message> {
@@ -70,12 +118,41 @@ fn status_bar(app: &Zagel) -> Element<'_, Message> {
         "Press ? for shortcuts"
     };
 
-    let content = row![
+    let mut content = row![
+        button(text("Settings").size(12))
+            .padding([2, 8])
+            .on_press(Message::ToggleSettings),
         text(hint).size(12),
-        space().width(Length::Fill),
-        text(format!("Status: {}", app.status_line)).size(12),
     ]
+    .align_y(iced::Alignment::Center)
     .spacing(8);
+    if app.state.skip_tls_verify {
+        content = content.push(
+            text("TLS VERIFICATION DISABLED - certificates are not checked")
+                .size(12)
+                .style(text::danger),
+        );
+    }
+    let mut content = content.push(space().width(Length::Fill));
+    if let Some(tokens) = app.tokens.status_text() {
+        content = content.push(text(tokens).size(12));
+    }
+    let mut content = content
+        .push(text(format!("Status: {}", app.status_line)).size(12))
+        .push(
+            button(text("History").size(12))
+                .padding([2, 8])
+                .style(button::secondary)
+                .on_press(Message::RecentSendsToggled),
+        );
+    if app.history.last_failure.is_some() {
+        content = content.push(
+            button(text("Failure snapshot").size(12))
+                .padding([2, 8])
+                .style(button::danger)
+                .on_press(Message::FailureSnapshotToggled),
+        );
+    }
 
     container(content).padding([6, 12]).into()
 }
@@ -0,0 +1,30 @@
+use iced::widget::{button, column, row, text, text_editor};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use super::response::{ResponseData, status_header};
+use crate::app::pin::{PinnedResponse, comparison};
+
+/// Read-only pane with the pinned response and how the live one compares.
+pub fn pinned_pane<'a>(
+    pinned: &'a PinnedResponse,
+    current: Option<&ResponseData>,
+) -> Element<'a, Message> {
+    let header = row![
+        text(format!("Pinned: {}", pinned.title))
+            .size(14)
+            .width(Length::Fill),
+        button(text("Unpin").size(12))
+            .padding([2, 8])
+            .on_press(Message::UnpinResponse),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+    let mut pane = column![header, status_header(&pinned.response.preview)].spacing(6);
+    if let Some(current) = current {
+        pane = pane.push(text(comparison(&pinned.response, current)).size(12));
+    }
+    pane.push(text_editor(&pinned.viewer).height(Length::Fill))
+        .height(Length::Fill)
+        .into()
+}
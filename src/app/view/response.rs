@@ -1,14 +1,26 @@
+use std::fmt::Write as _;
+
 use ego_tree::NodeRef;
 use iced::widget::text::Wrapping;
+use iced::widget::text_editor::TextEditor;
 use iced::widget::{
-    button, column, container, pick_list, row, rule, scrollable, text, text_editor,
+    Row, button, column, container, pick_list, row, rule, scrollable, space, text, text_editor,
+    text_input, tooltip,
 };
-use iced::{Element, Length};
+use iced::{Alignment, Element, Length};
 use iced_highlighter::Theme as HighlightTheme;
 use scraper::{Html, Node};
 
 use super::super::Message;
+use super::super::assertions::AssertionOutcome;
+use super::super::json_tree::{JsonTree, MAX_TREE_ROWS, TreeNode};
+use super::super::pager::Pager;
+use super::super::response_headers::{filter_headers, header_url};
+use super::super::search::{ResponseSearch, SearchHighlighter, SearchSettings, search_format};
+use super::super::send_log::{LogEntry, format_entries};
 use crate::model::ResponsePreview;
+use crate::state::ResponseViewSettings;
+use crate::theme;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyntaxKind {
@@ -66,43 +78,101 @@ impl PrettyBody {
     }
 }
 
+/// Bodies above this size are pretty-printed in the background, and only when the
+/// Pretty view is picked.
+const EAGER_PRETTY_BYTES: usize = 1024 * 1024;
+/// The Hex tab dumps this many leading bytes, and this many more per "Load more".
+const HEX_STEP_BYTES: usize = 64 * 1024;
+const HEX_ROW_BYTES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PrettyState {
+    Ready,
+    Deferred,
+    Formatting,
+}
+
 #[derive(Debug, Clone)]
 pub struct ResponseBodyData {
     raw: String,
     syntax: SyntaxKind,
     pretty: Option<PrettyBody>,
+    pretty_state: PrettyState,
+    hex: Option<String>,
+    /// Leading bytes covered by `hex`.
+    hex_limit: usize,
+    summary: Option<String>,
 }
 
 impl ResponseBodyData {
     pub fn from_response(resp: &ResponsePreview) -> Self {
-        let raw = resp
-            .error
-            .clone()
-            .or_else(|| resp.body.clone())
-            .unwrap_or_else(|| "No body".to_string());
+        if let Some(error) = &resp.error {
+            return Self::plain(error.clone());
+        }
+        let Some(bytes) = resp.body.as_deref() else {
+            return Self::plain("No body".to_string());
+        };
+
+        if is_binary(resp.content_type(), bytes) {
+            let summary = format!(
+                "Binary body, {} ({}). Open the Hex tab to inspect it.",
+                format_size(bytes.len()),
+                resp.content_type().unwrap_or("unknown content type")
+            );
+            return Self {
+                hex: Some(hex_dump(bytes, HEX_STEP_BYTES)),
+                summary: Some(summary.clone()),
+                ..Self::plain(summary)
+            };
+        }
+
+        let raw = String::from_utf8_lossy(bytes).into_owned();
         let syntax = response_syntax_kind(resp);
-        let pretty_json = pretty_json(&raw).map(|pretty| PrettyBody::Json { pretty });
-        let pretty = pretty_json.or_else(|| {
-            if syntax == SyntaxKind::Html {
-                let mode = html_parse_mode(&raw);
-                let pretty = pretty_html(&raw, mode);
-                if pretty.is_empty() {
-                    None
-                } else {
-                    Some(PrettyBody::Html { pretty })
-                }
-            } else {
-                None
-            }
-        });
+        if bytes.len() > EAGER_PRETTY_BYTES {
+            return Self {
+                syntax,
+                pretty_state: PrettyState::Deferred,
+                ..Self::plain(raw)
+            };
+        }
 
         Self {
-            raw,
             syntax,
-            pretty,
+            pretty: format_pretty(&raw, syntax),
+            ..Self::plain(raw)
+        }
+    }
+
+    const fn plain(raw: String) -> Self {
+        Self {
+            raw,
+            syntax: SyntaxKind::Text,
+            pretty: None,
+            pretty_state: PrettyState::Ready,
+            hex: None,
+            hex_limit: HEX_STEP_BYTES,
+            summary: None,
         }
     }
 
+    /// Whether the pretty text still has to be computed with [`format_pretty`].
+    pub fn pretty_deferred(&self) -> bool {
+        self.pretty_state == PrettyState::Deferred
+    }
+
+    pub fn is_formatting(&self) -> bool {
+        self.pretty_state == PrettyState::Formatting
+    }
+
+    pub const fn mark_formatting(&mut self) {
+        self.pretty_state = PrettyState::Formatting;
+    }
+
+    pub fn set_pretty(&mut self, pretty: Option<PrettyBody>) {
+        self.pretty = pretty;
+        self.pretty_state = PrettyState::Ready;
+    }
+
     pub fn raw(&self) -> &str {
         &self.raw
     }
@@ -118,18 +188,55 @@ impl ResponseBodyData {
     pub fn pretty_kind(&self) -> Option<PrettyKind> {
         self.pretty.as_ref().map(PrettyBody::kind)
     }
+
+    /// JSON by content, or by content type while a large body is not parsed yet.
+    pub fn is_json(&self) -> bool {
+        self.pretty_kind() == Some(PrettyKind::Json)
+            || (self.pretty_state != PrettyState::Ready && self.syntax == SyntaxKind::Json)
+    }
+
+    /// Hex dump for binary bodies; `None` for text.
+    pub fn hex_text(&self) -> Option<&str> {
+        self.hex.as_deref()
+    }
+
+    /// Bytes the hex dump covers when it stops short of a `total`-byte body.
+    pub fn hex_truncation(&self, total: usize) -> Option<usize> {
+        (self.hex.is_some() && total > self.hex_limit).then_some(self.hex_limit)
+    }
+
+    /// Extends the hex dump by another step of `bytes`, the full body.
+    pub fn load_more_hex(&mut self, bytes: &[u8]) {
+        if self.hex.is_some() {
+            self.hex_limit += HEX_STEP_BYTES;
+            self.hex = Some(hex_dump(bytes, self.hex_limit));
+        }
+    }
+
+    /// Replaces the body in the viewer when it is binary or too large to show.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ResponseData {
     pub preview: ResponsePreview,
     pub body: ResponseBodyData,
+    pub assertions: Vec<AssertionOutcome>,
+    /// Steps taken for the send, oldest first.
+    pub log: Vec<LogEntry>,
 }
 
 impl ResponseData {
     pub fn from_preview(preview: ResponsePreview) -> Self {
         let body = ResponseBodyData::from_response(&preview);
-        Self { preview, body }
+        Self {
+            preview,
+            body,
+            assertions: Vec::new(),
+            log: Vec::new(),
+        }
     }
 }
 
@@ -137,10 +244,11 @@ impl ResponseData {
 pub enum ResponseDisplay {
     Raw,
     Pretty,
+    Tree,
 }
 
 impl ResponseDisplay {
-    pub const ALL: [Self; 2] = [Self::Raw, Self::Pretty];
+    pub const ALL: [Self; 3] = [Self::Raw, Self::Pretty, Self::Tree];
 }
 
 impl std::fmt::Display for ResponseDisplay {
@@ -148,6 +256,7 @@ impl std::fmt::Display for ResponseDisplay {
         match self {
             Self::Raw => f.write_str("Raw"),
             Self::Pretty => f.write_str("Pretty"),
+            Self::Tree => f.write_str("Tree"),
         }
     }
 }
@@ -156,6 +265,8 @@ impl std::fmt::Display for ResponseDisplay {
 pub enum ResponseTab {
     Body,
     Headers,
+    Hex,
+    Log,
 }
 
 impl std::fmt::Display for ResponseTab {
@@ -163,28 +274,34 @@ impl std::fmt::Display for ResponseTab {
         match self {
             Self::Body => f.write_str("Body"),
             Self::Headers => f.write_str("Headers"),
+            Self::Hex => f.write_str("Hex"),
+            Self::Log => f.write_str("Log"),
         }
     }
 }
 
-/// Creates a toggle widget for switching between Body and Headers tabs in the response view.
-pub fn response_tab_toggle(current: ResponseTab) -> Element<'static, Message> {
-    let body = button(text("Body"))
-        .style(if current == ResponseTab::Body {
-            button::primary
-        } else {
-            button::secondary
-        })
-        .on_press(Message::ResponseTabChanged(ResponseTab::Body));
-    let headers = button(text("Headers"))
-        .style(if current == ResponseTab::Headers {
-            button::primary
-        } else {
-            button::secondary
-        })
-        .on_press(Message::ResponseTabChanged(ResponseTab::Headers));
+/// Creates a toggle widget for switching between the Body, Headers and (for binary
+/// bodies) Hex tabs in the response view.
+pub fn response_tab_toggle(current: ResponseTab, show_hex: bool) -> Element<'static, Message> {
+    let tab_button = |tab: ResponseTab| {
+        button(text(tab.to_string()))
+            .style(if current == tab {
+                button::primary
+            } else {
+                button::secondary
+            })
+            .on_press(Message::ResponseTabChanged(tab))
+    };
 
-    row![body, headers].spacing(6).into()
+    let mut tabs = row![
+        tab_button(ResponseTab::Body),
+        tab_button(ResponseTab::Headers)
+    ];
+    if show_hex {
+        tabs = tabs.push(tab_button(ResponseTab::Hex));
+    }
+    tabs = tabs.push(tab_button(ResponseTab::Log));
+    tabs.spacing(6).into()
 }
 
 /// Creates a pick list widget for switching between Raw and Pretty response display modes.
@@ -197,87 +314,618 @@ pub fn response_view_toggle(current: ResponseDisplay) -> Element<'static, Messag
     .into()
 }
 
+/// Search box for the Body and Hex tabs: Enter jumps to the next match.
+pub fn response_search_bar(search: &ResponseSearch) -> Element<'_, Message> {
+    let has_query = !search.query.is_empty();
+    let nav = |label: &'static str, message: Message| {
+        button(text(label).size(12))
+            .padding([2, 8])
+            .on_press_maybe(has_query.then_some(message))
+    };
+    row![
+        text_input("Search body", &search.query)
+            .on_input(Message::ResponseSearchChanged)
+            .on_submit(Message::ResponseSearchNext)
+            .padding(4)
+            .width(Length::Fixed(180.0)),
+        nav("↑", Message::ResponseSearchPrevious),
+        nav("↓", Message::ResponseSearchNext),
+        text(if has_query {
+            search.counter()
+        } else {
+            String::new()
+        })
+        .size(12),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(4)
+    .into()
+}
+
+pub struct ResponsePanelContext<'a> {
+    pub content: &'a text_editor::Content,
+    pub display: ResponseDisplay,
+    pub tab: ResponseTab,
+    pub highlight_theme: HighlightTheme,
+    pub search: &'a ResponseSearch,
+    pub filter: &'a str,
+    pub header_filter: &'a str,
+    pub tree: &'a JsonTree,
+    pub pager: &'a Pager,
+    pub viewer: ResponseViewSettings,
+}
+
 /// Creates the main response panel UI element.
 ///
 /// Displays the HTTP response status, duration, and either the body or headers
 /// based on the selected tab. Supports both raw and pretty-printed views for
-/// JSON and HTML content.
+/// JSON and HTML content. While a search is active, matches replace the syntax
+/// highlighting.
 pub fn response_panel<'a>(
     response: Option<&ResponseData>,
-    content: &'a text_editor::Content,
-    display: ResponseDisplay,
-    tab: ResponseTab,
-    highlight_theme: HighlightTheme,
+    context: &ResponsePanelContext<'a>,
 ) -> Element<'a, Message> {
+    let &ResponsePanelContext {
+        content,
+        display,
+        tab,
+        highlight_theme: _,
+        search,
+        filter,
+        header_filter,
+        tree,
+        pager,
+        viewer,
+    } = context;
     response.map_or_else(
         || text("No response yet").into(),
         |response| {
             let resp = &response.preview;
             let body = &response.body;
-            let header = match (resp.status, resp.duration) {
-                (Some(status), Some(duration)) => {
-                    format!("HTTP {status} in {} ms", duration.as_millis())
-                }
-                (Some(status), None) => format!("HTTP {status}"),
-                _ => "No response".to_string(),
-            };
 
-            let mut headers_view = column![];
-            if resp.headers.is_empty() {
-                headers_view = headers_view.push(text("No headers").size(12));
-            } else {
-                for (name, value) in &resp.headers {
-                    headers_view = headers_view.push(text(format!("{name}: {value}")).size(12));
-                }
-            }
+            let body_editor = body_editor(context, body.syntax());
 
-            let pretty_kind = body.pretty_kind();
-            let syntax = body.syntax();
-            let body_editor = text_editor(content)
-                .height(Length::Fill)
-                .highlight(syntax.as_str(), highlight_theme)
-                .wrapping(Wrapping::None);
-
-            let body_section: Element<'_, Message> = column![
-                text(format!(
-                    "Body ({})",
-                    match (display, pretty_kind) {
-                        (ResponseDisplay::Pretty, Some(PrettyKind::Html)) => {
-                            "pretty (HTML; formatted view)"
-                        }
-                        (ResponseDisplay::Pretty, Some(PrettyKind::Json)) => "pretty (JSON)",
-                        (ResponseDisplay::Pretty, None) => "pretty (raw shown)",
-                        (ResponseDisplay::Raw, _) => "raw",
-                    }
-                ))
-                .size(14),
-                body_editor,
+            let mode = body_mode(body, display, filter, tree.root.is_some());
+            let mut body_section = column![
+                row![
+                    text(format!("Body ({mode})")).size(14),
+                    space().width(Length::Fill),
+                    viewer_controls(viewer),
+                ]
+                .align_y(Alignment::Center)
             ]
-            .spacing(6)
-            .into();
-
-            let headers_section: Element<'_, Message> = column![
-                text("Headers").size(14),
-                scrollable(headers_view.spacing(4)).height(Length::Fill),
-            ]
-            .spacing(6)
-            .into();
+            .spacing(6);
+            if let Some(pages) = page_bar(pager) {
+                body_section = body_section.push(pages);
+            }
+            let body_section: Element<'_, Message> = body_section.push(body_editor).into();
 
             let tab_view: Element<'_, Message> = match tab {
                 ResponseTab::Body => body_section,
-                ResponseTab::Headers => headers_section,
+                ResponseTab::Headers => headers_section(&resp.headers, header_filter),
+                ResponseTab::Log => log_section(&response.log),
+                ResponseTab::Hex => hex_section(
+                    content,
+                    search.highlight_settings(pager, false),
+                    pager,
+                    resp.body
+                        .as_deref()
+                        .and_then(|bytes| Some((body.hex_truncation(bytes.len())?, bytes.len()))),
+                ),
             };
 
-            column![
-                text(header).size(16),
-                rule::horizontal(1),
-                container(tab_view).height(Length::Fill),
+            let mut status_line = status_header(resp);
+            for (name, value) in correlation_ids(&resp.headers) {
+                status_line = status_line.push(
+                    button(text(format!("{name}: {value}")).size(12))
+                        .padding([2, 8])
+                        .style(button::secondary)
+                        .on_press(Message::CopyCorrelationId(value.to_string())),
+                );
+            }
+
+            let mut panel = column![status_line].spacing(6).height(Length::Fill);
+            if let Some(assertions) = assertion_summary(&response.assertions) {
+                panel = panel.push(assertions);
+            }
+            panel
+                .push(rule::horizontal(1))
+                .push(container(tab_view).height(Length::Fill))
+                .into()
+        },
+    )
+}
+
+/// Headers matching the filter box, each with copy, add-to-request and, for
+/// URL values, open actions.
+fn headers_section<'a>(headers: &[(String, String)], filter: &'a str) -> Element<'a, Message> {
+    let shown = filter_headers(headers, filter);
+    let count = if shown.len() == headers.len() {
+        format!("Headers ({})", headers.len())
+    } else {
+        format!("Headers ({} of {})", shown.len(), headers.len())
+    };
+    let action = |label: &'a str, message: Message| {
+        button(text(label).size(11))
+            .padding([1, 6])
+            .style(button::text)
+            .on_press(message)
+    };
+    let mut list = column![].spacing(4);
+    if shown.is_empty() {
+        list = list.push(
+            text(if headers.is_empty() {
+                "No headers"
+            } else {
+                "No matching headers"
+            })
+            .size(12),
+        );
+    }
+    for (name, value) in shown {
+        let mut line = row![
+            text(format!("{name}: {value}"))
+                .size(12)
+                .width(Length::Fill),
+            action("Copy", Message::CopyResponseHeaderValue(value.clone())),
+            action(
+                "Add to request",
+                Message::ResponseHeaderAdded(name.clone(), value.clone())
+            ),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(4);
+        if let Some(url) = header_url(value) {
+            line = line.push(action(
+                "Open",
+                Message::ResponseHeaderUrlOpened(url.to_string()),
+            ));
+        }
+        list = list.push(line);
+    }
+    column![
+        row![
+            text(count).size(14).width(Length::Fill),
+            text_input("Filter headers", filter)
+                .on_input(Message::ResponseHeaderFilterChanged)
+                .padding(4)
+                .size(12)
+                .width(Length::Fixed(220.0)),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6),
+        scrollable(list).height(Length::Fill),
+    ]
+    .spacing(6)
+    .into()
+}
+
+/// Protocol, a colored status chip with the reason phrase (its tooltip says
+/// what the code means) and the duration.
+pub(super) fn status_header<'a>(resp: &ResponsePreview) -> Row<'a, Message> {
+    let Some(status) = resp.status else {
+        return row![text("No response").size(16)];
+    };
+    let protocol = resp.version.as_deref().unwrap_or("HTTP");
+    let (reason, explanation) = status_reason(status).unwrap_or(("", "Non-standard status code"));
+    let chip = container(text(format!("{status} {reason}").trim_end().to_string()).size(14))
+        .padding([2, 10])
+        .style(theme::status_chip_style(status));
+    let mut line = row![
+        text(protocol.to_string()).size(16),
+        tooltip(
+            chip,
+            container(text(explanation).size(12))
+                .padding(6)
+                .style(theme::overlay_container_style),
+            tooltip::Position::Bottom,
+        ),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+    if let Some(duration) = resp.duration {
+        line = line.push(text(format!("in {} ms", duration.as_millis())).size(16));
+    }
+    line
+}
+
+/// Standard reason phrase and a one-line explanation of a status code.
+pub const fn status_reason(status: u16) -> Option<(&'static str, &'static str)> {
+    let reason = match status {
+        100 => ("Continue", "The server got the headers; send the body"),
+        101 => (
+            "Switching Protocols",
+            "The connection switches to the protocol in Upgrade",
+        ),
+        200 => ("OK", "The request succeeded"),
+        201 => ("Created", "The request succeeded and created a resource"),
+        202 => (
+            "Accepted",
+            "The request was accepted but is not processed yet",
+        ),
+        204 => ("No Content", "The request succeeded and there is no body"),
+        206 => (
+            "Partial Content",
+            "Only the requested range of the body is sent",
+        ),
+        301 => (
+            "Moved Permanently",
+            "The resource has a new permanent URL in Location",
+        ),
+        302 => (
+            "Found",
+            "The resource is temporarily at the URL in Location",
+        ),
+        303 => (
+            "See Other",
+            "Fetch the result with GET from the URL in Location",
+        ),
+        304 => ("Not Modified", "The cached copy is still valid"),
+        307 => (
+            "Temporary Redirect",
+            "Repeat the request, same method, at Location",
+        ),
+        308 => (
+            "Permanent Redirect",
+            "Repeat this and future requests at Location",
+        ),
+        400 => ("Bad Request", "The server could not understand the request"),
+        401 => ("Unauthorized", "Authentication is missing or invalid"),
+        403 => ("Forbidden", "Authenticated, but not allowed to do this"),
+        404 => ("Not Found", "Nothing exists at this URL"),
+        405 => ("Method Not Allowed", "The URL does not support this method"),
+        406 => (
+            "Not Acceptable",
+            "No representation matches the Accept headers",
+        ),
+        408 => (
+            "Request Timeout",
+            "The server gave up waiting for the request",
+        ),
+        409 => (
+            "Conflict",
+            "The request conflicts with the resource's current state",
+        ),
+        410 => ("Gone", "The resource was removed for good"),
+        412 => (
+            "Precondition Failed",
+            "An If-* header condition did not hold",
+        ),
+        413 => (
+            "Content Too Large",
+            "The body is larger than the server accepts",
+        ),
+        415 => (
+            "Unsupported Media Type",
+            "The server does not accept this Content-Type",
+        ),
+        422 => (
+            "Unprocessable Content",
+            "The body is well-formed but fails validation",
+        ),
+        429 => ("Too Many Requests", "Rate limited; see Retry-After"),
+        500 => (
+            "Internal Server Error",
+            "The server failed while handling the request",
+        ),
+        501 => (
+            "Not Implemented",
+            "The server does not support this functionality",
+        ),
+        502 => (
+            "Bad Gateway",
+            "A proxy or gateway got an invalid upstream response",
+        ),
+        503 => (
+            "Service Unavailable",
+            "The server is overloaded or down for maintenance",
+        ),
+        504 => (
+            "Gateway Timeout",
+            "A proxy or gateway timed out waiting upstream",
+        ),
+        _ => return None,
+    };
+    Some(reason)
+}
+
+/// Green when every assertion passed; otherwise red with one line per failure.
+fn assertion_summary<'a>(outcomes: &[AssertionOutcome]) -> Option<Element<'a, Message>> {
+    if outcomes.is_empty() {
+        return None;
+    }
+    let failures = outcomes
+        .iter()
+        .filter_map(|outcome| Some((&outcome.label, outcome.failure.as_ref()?)))
+        .collect::<Vec<_>>();
+    let headline = text(format!(
+        "Assertions: {}/{} passed",
+        outcomes.len() - failures.len(),
+        outcomes.len()
+    ))
+    .size(13)
+    .style(if failures.is_empty() {
+        text::success
+    } else {
+        text::danger
+    });
+    let mut list = column![headline].spacing(2);
+    for (label, failure) in failures {
+        list = list.push(
+            text(format!("✕ {label}: {failure}"))
+                .size(12)
+                .style(text::danger),
+        );
+    }
+    Some(list.into())
+}
+
+/// Describes what the Body tab currently shows.
+fn body_mode(
+    body: &ResponseBodyData,
+    display: ResponseDisplay,
+    filter: &str,
+    tree_shown: bool,
+) -> String {
+    let filter = filter.trim();
+    match (display, body.pretty_kind()) {
+        (ResponseDisplay::Tree, _) if !tree_shown => "tree (JSON only; pretty shown)".to_string(),
+        _ if body.is_json() && !filter.is_empty() => format!("filtered by {filter}"),
+        (ResponseDisplay::Pretty, Some(PrettyKind::Html)) => {
+            "pretty (HTML; formatted view)".to_string()
+        }
+        (ResponseDisplay::Pretty, Some(PrettyKind::Json)) => "pretty (JSON)".to_string(),
+        (ResponseDisplay::Pretty, None) if body.is_formatting() || body.pretty_deferred() => {
+            "pretty (formatting…; raw shown)".to_string()
+        }
+        (ResponseDisplay::Pretty, None) => "pretty (raw shown)".to_string(),
+        (ResponseDisplay::Tree, _) => "tree".to_string(),
+        (ResponseDisplay::Raw, _) => "raw".to_string(),
+    }
+}
+
+fn tree_view(tree: &JsonTree) -> Element<'_, Message> {
+    const INDENT: f32 = 16.0;
+    let rows = tree.rows();
+    let truncated = rows.len() >= MAX_TREE_ROWS;
+    let mut list = column![].spacing(2);
+    for row_data in rows {
+        let toggle: Element<'_, Message> = match &row_data.node {
+            TreeNode::Container { open, .. } => {
+                button(text(if *open { "▾" } else { "▸" }).size(12))
+                    .padding([0, 4])
+                    .style(button::text)
+                    .on_press(Message::JsonTreeToggled(row_data.pointer.clone()))
+                    .into()
+            }
+            TreeNode::Leaf(_) => space().width(Length::Fixed(INDENT)).into(),
+        };
+        let summary = match &row_data.node {
+            TreeNode::Container {
+                open: false,
+                is_array: true,
+                len,
+            } => format!("[…] {len} items"),
+            TreeNode::Container {
+                open: false,
+                is_array: false,
+                len,
+            } => format!("{{…}} {len} keys"),
+            TreeNode::Container { is_array, .. } => if *is_array { "[" } else { "{" }.to_string(),
+            TreeNode::Leaf(value) => value.clone(),
+        };
+        let action = |label: &'static str, message: Message| {
+            button(text(label).size(11))
+                .padding([0, 6])
+                .style(button::secondary)
+                .on_press(message)
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let indent = row_data.depth as f32 * INDENT;
+        list = list.push(
+            row![
+                space().width(Length::Fixed(indent)),
+                toggle,
+                text(format!("{}:", row_data.label)).size(13),
+                text(summary).size(13).font(iced::Font::MONOSPACE),
+                space().width(Length::Fill),
+                action("Copy path", Message::JsonTreeCopyPath(row_data.path)),
+                action("Copy value", Message::JsonTreeCopyValue(row_data.pointer)),
             ]
-            .spacing(6)
+            .align_y(Alignment::Center)
+            .spacing(6),
+        );
+    }
+    if truncated {
+        list = list.push(
+            text(format!(
+                "Showing the first {MAX_TREE_ROWS} rows; collapse nodes or use the filter to see more"
+            ))
+            .size(12),
+        );
+    }
+    scrollable(list).height(Length::Fill).into()
+}
+
+fn log_section<'a>(entries: &[LogEntry]) -> Element<'a, Message> {
+    let mut lines = column![].spacing(2);
+    if entries.is_empty() {
+        lines = lines.push(text("No log for this response").size(12));
+    }
+    for line in format_entries(entries) {
+        lines = lines.push(text(line).size(12).font(iced::Font::MONOSPACE));
+    }
+    column![text("Log").size(14), scrollable(lines).height(Length::Fill)]
+        .spacing(6)
+        .into()
+}
+
+/// `truncation` is `(shown, total)` bytes when the dump stops short of the body.
+fn hex_section<'a>(
+    content: &'a text_editor::Content,
+    settings: SearchSettings,
+    pager: &Pager,
+    truncation: Option<(usize, usize)>,
+) -> Element<'a, Message> {
+    let mut section = column![text("Hex").size(14)].spacing(6);
+    if let Some((shown, total)) = truncation {
+        section = section.push(truncation_banner(shown, total));
+    }
+    if let Some(pages) = page_bar(pager) {
+        section = section.push(pages);
+    }
+    section
+        .push(search_editor(content, settings, iced::Font::MONOSPACE))
+        .into()
+}
+
+fn truncation_banner(shown: usize, total: usize) -> Element<'static, Message> {
+    container(
+        row![
+            text(format!(
+                "Showing the first {} of {}",
+                format_size(shown),
+                format_size(total)
+            ))
+            .size(12)
+            .width(Length::Fill),
+            button(text("Load more").size(12))
+                .padding([2, 8])
+                .on_press(Message::LoadMoreHex),
+            button(text("Save to disk").size(12))
+                .padding([2, 8])
+                .on_press(Message::SaveResponseBody),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6),
+    )
+    .padding([4, 8])
+    .style(container::rounded_box)
+    .into()
+}
+
+/// Page navigation, shown only when the text does not fit on one page.
+fn page_bar(pager: &Pager) -> Option<Element<'static, Message>> {
+    let count = pager.page_count();
+    if count < 2 {
+        return None;
+    }
+    let current = pager.current();
+    let range = pager.range(current);
+    Some(
+        row![
+            button(text("◀").size(12))
+                .padding([2, 8])
+                .on_press_maybe(current.checked_sub(1).map(Message::ResponsePageChanged)),
+            text(format!(
+                "Page {} of {count} (bytes {}–{} of {})",
+                current + 1,
+                range.start,
+                range.end,
+                format_size(pager.text().len())
+            ))
+            .size(12),
+            button(text("▶").size(12)).padding([2, 8]).on_press_maybe(
+                (current + 1 < count).then_some(Message::ResponsePageChanged(current + 1))
+            ),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6)
+        .into(),
+    )
+}
+
+fn search_editor(
+    content: &text_editor::Content,
+    settings: SearchSettings,
+    font: iced::Font,
+) -> TextEditor<'_, SearchHighlighter, Message> {
+    text_editor(content)
+        .height(Length::Fill)
+        .font(font)
+        .wrapping(Wrapping::None)
+        .highlight_with::<SearchHighlighter>(settings, search_format)
+}
+
+fn body_editor<'a>(context: &ResponsePanelContext<'a>, syntax: SyntaxKind) -> Element<'a, Message> {
+    let viewer = context.viewer;
+    if context.display == ResponseDisplay::Tree && context.tree.root.is_some() {
+        tree_view(context.tree)
+    } else if context.search.query.is_empty() {
+        text_editor(context.content)
             .height(Length::Fill)
+            .font(iced::Font::MONOSPACE)
+            .size(f32::from(viewer.font_size))
+            .highlight(syntax.as_str(), context.highlight_theme)
+            .wrapping(body_wrapping(viewer))
             .into()
-        },
-    )
+    } else {
+        search_editor(
+            context.content,
+            context
+                .search
+                .highlight_settings(context.pager, viewer.show_whitespace),
+            iced::Font::MONOSPACE,
+        )
+        .size(f32::from(viewer.font_size))
+        .wrapping(body_wrapping(viewer))
+        .into()
+    }
+}
+
+const fn body_wrapping(viewer: ResponseViewSettings) -> Wrapping {
+    if viewer.wrap {
+        Wrapping::WordOrGlyph
+    } else {
+        Wrapping::None
+    }
+}
+
+/// Wrap, whitespace and font size toggles for the body editor.
+fn viewer_controls<'a>(viewer: ResponseViewSettings) -> Element<'a, Message> {
+    let toggle = |label: &'a str, on: bool, message: Message| {
+        button(text(label).size(12))
+            .padding([2, 8])
+            .style(if on {
+                button::primary
+            } else {
+                button::secondary
+            })
+            .on_press(message)
+    };
+    row![
+        toggle("Wrap", viewer.wrap, Message::ResponseWrapToggled),
+        toggle(
+            "Whitespace",
+            viewer.show_whitespace,
+            Message::ResponseWhitespaceToggled
+        ),
+        button(text("A−").size(12)).padding([2, 8]).on_press_maybe(
+            (viewer.font_size > ResponseViewSettings::MIN_FONT_SIZE)
+                .then(|| Message::ResponseFontSizeChanged(viewer.font_size - 1))
+        ),
+        text(viewer.font_size.to_string()).size(12),
+        button(text("A+").size(12)).padding([2, 8]).on_press_maybe(
+            (viewer.font_size < ResponseViewSettings::MAX_FONT_SIZE)
+                .then(|| Message::ResponseFontSizeChanged(viewer.font_size + 1))
+        ),
+    ]
+    .spacing(4)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+/// Pretty-prints JSON, or HTML when `syntax` says so; `None` when neither applies.
+pub fn format_pretty(raw: &str, syntax: SyntaxKind) -> Option<PrettyBody> {
+    pretty_json(raw)
+        .map(|pretty| PrettyBody::Json { pretty })
+        .or_else(|| {
+            if syntax != SyntaxKind::Html {
+                return None;
+            }
+            let pretty = pretty_html(raw, html_parse_mode(raw));
+            (!pretty.is_empty()).then_some(PrettyBody::Html { pretty })
+        })
 }
 
 /// Attempts to format a JSON string with proper indentation.
@@ -550,10 +1198,8 @@ fn format_node(
 
 fn response_syntax_kind(resp: &ResponsePreview) -> SyntaxKind {
     let content_type = resp
-        .headers
-        .iter()
-        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
-        .map(|(_, value)| value.to_ascii_lowercase())
+        .content_type()
+        .map(str::to_ascii_lowercase)
         .unwrap_or_default();
 
     if content_type.contains("json") {
@@ -571,6 +1217,109 @@ fn response_syntax_kind(resp: &ResponsePreview) -> SyntaxKind {
     }
 }
 
+/// Response headers that identify the request in server logs or traces, in the
+/// order they are shown next to the status line.
+const CORRELATION_HEADERS: &[&str] = &[
+    "x-request-id",
+    "x-correlation-id",
+    "request-id",
+    "x-amzn-requestid",
+    "x-amz-request-id",
+    "x-amzn-trace-id",
+    "x-cloud-trace-context",
+    "cf-ray",
+    "traceparent",
+    "x-b3-traceid",
+    "x-trace-id",
+];
+
+pub fn correlation_ids(headers: &[(String, String)]) -> Vec<(&str, &str)> {
+    CORRELATION_HEADERS
+        .iter()
+        .filter_map(|wanted| {
+            headers
+                .iter()
+                .find(|(name, value)| name.eq_ignore_ascii_case(wanted) && !value.trim().is_empty())
+                .map(|(name, value)| (name.as_str(), value.trim()))
+        })
+        .collect()
+}
+
+/// Content types are trusted when present; otherwise the bytes are sniffed.
+fn is_binary(content_type: Option<&str>, bytes: &[u8]) -> bool {
+    const TEXT_MARKERS: &[&str] = &[
+        "text/",
+        "json",
+        "xml",
+        "html",
+        "javascript",
+        "css",
+        "csv",
+        "yaml",
+        "graphql",
+        "x-www-form-urlencoded",
+        "charset=",
+    ];
+
+    content_type.map_or_else(
+        || bytes.contains(&0) || std::str::from_utf8(bytes).is_err(),
+        |content_type| {
+            let content_type = content_type.to_ascii_lowercase();
+            !TEXT_MARKERS
+                .iter()
+                .any(|marker| content_type.contains(marker))
+        },
+    )
+}
+
+/// Dumps the first `limit` bytes; the Hex tab shows a banner for the rest.
+fn hex_dump(bytes: &[u8], limit: usize) -> String {
+    let mut out = String::new();
+    let shown = &bytes[..bytes.len().min(limit)];
+    for (row, chunk) in shown.chunks(HEX_ROW_BYTES).enumerate() {
+        let _ = write!(out, "{:08x} ", row * HEX_ROW_BYTES);
+        for idx in 0..HEX_ROW_BYTES {
+            if idx % 8 == 0 {
+                out.push(' ');
+            }
+            match chunk.get(idx) {
+                Some(byte) => {
+                    let _ = write!(out, "{byte:02x} ");
+                }
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str(" |");
+        out.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                char::from(byte)
+            } else {
+                '.'
+            }
+        }));
+        out.push_str("|\n");
+    }
+    out
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
 fn html_parse_mode(raw: &str) -> HtmlParseMode {
     let sniff = raw.trim_start().to_ascii_lowercase();
     if sniff.contains("<!doctype") || sniff.contains("<html") {
@@ -582,7 +1331,92 @@ fn html_parse_mode(raw: &str) -> HtmlParseMode {
 
 #[cfg(test)]
 mod tests {
-    use super::{HtmlParseMode, html_parse_mode, pretty_html};
+    use super::{
+        HEX_ROW_BYTES, HEX_STEP_BYTES, HtmlParseMode, ResponseBodyData, correlation_ids, hex_dump,
+        html_parse_mode, is_binary, pretty_html, status_reason,
+    };
+    use crate::model::ResponsePreview;
+
+    #[test]
+    fn binary_detection_prefers_content_type_then_sniffs() {
+        assert!(is_binary(Some("image/png"), b"plain"));
+        assert!(!is_binary(Some("application/problem+json"), &[0xff]));
+        assert!(!is_binary(
+            Some("application/octet-stream; charset=utf-8"),
+            b"ok"
+        ));
+        assert!(is_binary(None, &[0x89, b'P', b'N', b'G', 0x00]));
+        assert!(!is_binary(None, "héllo".as_bytes()));
+    }
+
+    #[test]
+    fn binary_body_keeps_bytes_for_hex_view() {
+        let preview = ResponsePreview {
+            status: Some(200),
+            duration: None,
+            body: Some(vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a]),
+            headers: vec![("Content-Type".to_string(), "image/png".to_string())],
+            error: None,
+            version: None,
+            url: None,
+        };
+
+        let body = ResponseBodyData::from_response(&preview);
+
+        assert!(
+            body.summary()
+                .is_some_and(|summary| summary.contains("6 B"))
+        );
+        assert_eq!(
+            body.hex_text(),
+            Some("00000000  89 50 4e 47 0d 0a                                 |.PNG..|\n")
+        );
+        assert_eq!(hex_dump(&[], HEX_STEP_BYTES), "");
+    }
+
+    #[test]
+    fn hex_dump_reports_truncation_until_fully_loaded() {
+        let bytes = vec![0u8; HEX_STEP_BYTES + 16];
+        let preview = ResponsePreview {
+            status: Some(200),
+            duration: None,
+            body: Some(bytes.clone()),
+            headers: vec![(
+                "Content-Type".to_string(),
+                "application/octet-stream".to_string(),
+            )],
+            error: None,
+            version: None,
+            url: None,
+        };
+        let mut body = ResponseBodyData::from_response(&preview);
+        assert_eq!(body.hex_truncation(bytes.len()), Some(HEX_STEP_BYTES));
+        assert_eq!(
+            body.hex_text().map(|hex| hex.lines().count()),
+            Some(HEX_STEP_BYTES / HEX_ROW_BYTES)
+        );
+
+        body.load_more_hex(&bytes);
+        assert_eq!(body.hex_truncation(bytes.len()), None);
+        assert_eq!(
+            body.hex_text().map(|hex| hex.lines().count()),
+            Some(HEX_STEP_BYTES / HEX_ROW_BYTES + 1)
+        );
+    }
+
+    #[test]
+    fn correlation_ids_follow_preferred_order() {
+        let headers = vec![
+            ("content-type".to_string(), "application/json".to_string()),
+            ("traceparent".to_string(), "00-abc-def-01".to_string()),
+            ("X-Request-Id".to_string(), "req-1".to_string()),
+            ("x-correlation-id".to_string(), " ".to_string()),
+        ];
+        assert_eq!(
+            correlation_ids(&headers),
+            vec![("X-Request-Id", "req-1"), ("traceparent", "00-abc-def-01")]
+        );
+    }
 
     #[test]
     fn html_parse_mode_detects_document_markers() {
@@ -600,4 +1434,17 @@ mod tests {
         let formatted = pretty_html(input, HtmlParseMode::Fragment);
         assert_eq!(formatted, input);
     }
+
+    #[test]
+    fn status_codes_have_reason_phrases() {
+        assert_eq!(
+            status_reason(404).map(|(reason, _)| reason),
+            Some("Not Found")
+        );
+        assert_eq!(
+            status_reason(503).map(|(reason, _)| reason),
+            Some("Service Unavailable")
+        );
+        assert!(status_reason(299).is_none());
+    }
 }
@@ -0,0 +1,122 @@
+use iced::widget::{button, column, container, row, rule, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+use super::super::Message;
+use crate::app::runner::{FileRun, RunPhase, RunResult};
+use crate::theme;
+
+const INDEX_WIDTH: f32 = 32.0;
+const STATUS_WIDTH: f32 = 70.0;
+const TIME_WIDTH: f32 = 80.0;
+
+pub fn runner_panel(run: &FileRun) -> Element<'_, Message> {
+    let header = row![
+        text(format!("Run {}", run.source))
+            .size(16)
+            .width(Length::Fill),
+        button("Close").on_press(Message::RunFileClose),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+
+    let run_button = if run.is_running() {
+        button("Stop").on_press(Message::RunFileStop)
+    } else {
+        button("Run again").on_press(Message::RunFileAgain)
+    };
+    let controls = row![run_button, text(progress_label(run)).size(13)]
+        .align_y(Alignment::Center)
+        .spacing(8);
+
+    let mut table = column![table_row("#", "Request", "Status", "Time", "Result")].spacing(2);
+    for (index, result) in run.results().iter().enumerate() {
+        table = table.push(result_row(index, result));
+    }
+
+    let body = column![header, controls, rule::horizontal(1), table].spacing(8);
+    container(scrollable(body).height(Length::Shrink))
+        .padding(12)
+        .max_width(640.0)
+        .style(theme::overlay_container_style)
+        .into()
+}
+
+fn progress_label(run: &FileRun) -> String {
+    let done = run.results().len();
+    let total = run.total();
+    let passed = run.passed();
+    match run.phase {
+        RunPhase::Running { .. } => format!("{done}/{total} sent, {passed} passed"),
+        RunPhase::Finished => format!("Finished: {passed}/{total} passed"),
+        RunPhase::Stopped => format!("Stopped after {done}/{total}: {passed} passed"),
+    }
+}
+
+fn result_row(index: usize, result: &RunResult) -> Element<'_, Message> {
+    let status = result
+        .status
+        .map_or_else(|| "-".to_string(), |status| status.to_string());
+    let time = result.duration.map_or_else(
+        || "-".to_string(),
+        |duration| format!("{} ms", duration.as_millis()),
+    );
+    let verdict = if result.passed() {
+        "✓ pass"
+    } else if result.escalates() {
+        "✕ fail (high)"
+    } else if result.counts_as_failure() {
+        "✕ fail"
+    } else {
+        "! logged (low)"
+    };
+    let mut cell = column![table_row(
+        &index.to_string(),
+        &result.label,
+        &status,
+        &time,
+        verdict
+    )]
+    .spacing(2);
+    let details =
+        result
+            .error
+            .iter()
+            .cloned()
+            .chain(result.assertions.iter().filter_map(|outcome| {
+                Some(format!("{}: {}", outcome.label, outcome.failure.as_ref()?))
+            }));
+    for detail in details {
+        cell = cell.push(
+            row![
+                iced::widget::space().width(Length::Fixed(INDEX_WIDTH)),
+                text(detail).size(12).style(text::danger),
+            ]
+            .spacing(6),
+        );
+    }
+    cell.into()
+}
+
+fn table_row<'a>(
+    index: &str,
+    label: &str,
+    status: &str,
+    time: &str,
+    verdict: &str,
+) -> Element<'a, Message> {
+    row![
+        text(index.to_string())
+            .size(13)
+            .width(Length::Fixed(INDEX_WIDTH)),
+        text(label.to_string()).size(13).width(Length::Fill),
+        text(status.to_string())
+            .size(13)
+            .width(Length::Fixed(STATUS_WIDTH)),
+        text(time.to_string())
+            .size(13)
+            .width(Length::Fixed(TIME_WIDTH)),
+        text(verdict.to_string()).size(13),
+    ]
+    .spacing(6)
+    .into()
+}
@@ -0,0 +1,248 @@
+use std::fmt::Write as _;
+
+use iced::widget::{button, checkbox, column, container, pick_list, row, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use super::super::{Message, Zagel};
+use crate::net::DEFAULT_USER_AGENT;
+use crate::state::PluginHook;
+use crate::theme;
+
+pub fn settings_panel(app: &Zagel) -> Element<'_, Message> {
+    let header = row![
+        text("Settings").size(16).width(Length::Fill),
+        button("Close").on_press(Message::ToggleSettings),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+
+    let tls = column![
+        text("TLS").size(14),
+        text_input("/path/to/ca-bundle.pem", &app.ca_certificate_input)
+            .on_input(Message::CaCertificateInputChanged)
+            .padding(4),
+        text("Custom CA certificate (PEM bundle or DER), trusted in addition to the system roots")
+            .size(12),
+        checkbox(app.state.skip_tls_verify)
+            .label("Skip TLS certificate verification (insecure)")
+            .on_toggle(Message::SkipTlsVerifyToggled),
+    ]
+    .spacing(6);
+
+    let user_agent = column![
+        text("User-Agent").size(14),
+        text_input(
+            DEFAULT_USER_AGENT,
+            app.state.user_agent.as_deref().unwrap_or_default()
+        )
+        .on_input(Message::UserAgentChanged)
+        .padding(4),
+        text("Sent with every request; a User-Agent header on the request overrides it").size(12),
+    ]
+    .spacing(6);
+
+    let startup = column![
+        text("Startup").size(14),
+        checkbox(app.state.forget_missing_roots)
+            .label("Forget saved folders that no longer exist")
+            .on_toggle(Message::ForgetMissingRootsToggled),
+    ]
+    .spacing(6);
+
+    container(
+        column![
+            header,
+            startup,
+            tls,
+            user_agent,
+            import(app),
+            plugins(app),
+            hook_commands(app),
+            backup(app)
+        ]
+        .spacing(10),
+    )
+    .padding(12)
+    .max_width(480.0)
+    .style(theme::overlay_container_style)
+    .into()
+}
+
+fn backup(app: &Zagel) -> Element<'_, Message> {
+    let form = &app.backup;
+    let mut section = column![
+        text("Backup").size(14),
+        text_input("/path/to/zagel-backup.json", &form.path)
+            .on_input(Message::BackupPathChanged)
+            .padding(4),
+        checkbox(form.secrets)
+            .label("Include env values, proxy passwords and sessions")
+            .on_toggle(Message::BackupSecretsToggled),
+        row![
+            button(text("Export backup").size(12)).on_press(Message::ExportBackup),
+            button(text("Restore...").size(12))
+                .style(button::secondary)
+                .on_press(Message::RestoreBackup),
+        ]
+        .spacing(6),
+    ]
+    .spacing(6);
+    if let Some((pending, state)) = &form.pending {
+        let mut note = format!(
+            "Overwrite {} files and replace the current settings?",
+            pending.restorable(state).count()
+        );
+        if !pending.secrets {
+            note.push_str(" Existing env files are kept since the backup has no secrets.");
+        }
+        let refused = pending.refused(state).count();
+        if refused > 0 {
+            let _ = write!(
+                note,
+                " {refused} files outside the backed up folders are skipped."
+            );
+        }
+        section = section.push(
+            row![
+                text(note).size(12).width(Length::Fill),
+                button(text("Restore").size(12))
+                    .style(button::danger)
+                    .on_press(Message::RestoreBackupConfirmed),
+                button(text("Cancel").size(12))
+                    .style(button::secondary)
+                    .on_press(Message::RestoreBackupCancelled),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+        );
+    }
+    section.into()
+}
+
+fn import(app: &Zagel) -> Element<'_, Message> {
+    let path = &app.import_form.path;
+    let ready = !path.trim().is_empty();
+    column![
+        text("Import").size(14),
+        text_input("/path/to/export.json or /path/to/bruno-collection", path)
+            .on_input(Message::ImportPathChanged)
+            .padding(4),
+        row![
+            button(text("Insomnia export").size(12))
+                .on_press_maybe(ready.then_some(Message::ImportInsomnia)),
+            button(text("Bruno collection").size(12))
+                .on_press_maybe(ready.then_some(Message::ImportBruno)),
+        ]
+        .spacing(6),
+        text("Writes .http and env files into a new folder in the first project folder").size(12),
+    ]
+    .spacing(6)
+    .into()
+}
+
+fn plugins(app: &Zagel) -> Element<'_, Message> {
+    let form = &app.plugin_form;
+    let mut section = column![text("Plugins").size(14)].spacing(6);
+    let has_importer = app
+        .state
+        .plugins
+        .iter()
+        .any(|plugin| plugin.hooks.contains(&PluginHook::Import));
+    for (index, plugin) in app.state.plugins.iter().enumerate() {
+        let hooks = plugin
+            .hooks
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut entry = row![
+            checkbox(plugin.enabled)
+                .label(plugin.name.as_str())
+                .on_toggle(move |enabled| Message::PluginToggled(index, enabled)),
+            text(hooks).size(12).width(Length::Fill),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6);
+        if plugin.hooks.contains(&PluginHook::Import) {
+            entry = entry.push(
+                button(text("Import file").size(12))
+                    .style(button::secondary)
+                    .on_press_maybe(
+                        (plugin.enabled && !form.import_path.trim().is_empty())
+                            .then_some(Message::RunImporter(index)),
+                    ),
+            );
+        }
+        entry = entry.push(
+            button(text("Remove").size(12))
+                .style(button::danger)
+                .on_press(Message::RemovePlugin(index)),
+        );
+        section = section.push(entry);
+    }
+    if has_importer {
+        section = section.push(
+            text_input("/path/to/file/to/import", &form.import_path)
+                .on_input(Message::PluginImportPathChanged)
+                .padding(4),
+        );
+    }
+    section = section.push(
+        row![
+            text_input("/path/to/plugin", &form.command)
+                .on_input(Message::PluginCommandChanged)
+                .on_submit(Message::AddPlugin)
+                .padding(4),
+            button(text("Add plugin").size(12))
+                .on_press_maybe((!form.command.trim().is_empty()).then_some(Message::AddPlugin)),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6),
+    );
+    section.into()
+}
+
+fn hook_commands(app: &Zagel) -> Element<'_, Message> {
+    let form = &app.plugin_form;
+    let mut section = column![
+        text("Send hooks").size(14),
+        text("Shell commands run around every send, with the request and response as JSON on stdin; a failing before-send command cancels the send").size(12),
+    ]
+    .spacing(6);
+    for (index, command) in app.state.hook_commands.iter().enumerate() {
+        section = section.push(
+            row![
+                checkbox(command.enabled)
+                    .label(command.command.as_str())
+                    .on_toggle(move |enabled| Message::HookCommandToggled(index, enabled))
+                    .width(Length::Fill),
+                text(command.hook.to_string()).size(12),
+                button(text("Remove").size(12))
+                    .style(button::danger)
+                    .on_press(Message::RemoveHookCommand(index)),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+        );
+    }
+    section
+        .push(
+            row![
+                text_input("notify-send \"sent\"", &form.hook_command)
+                    .on_input(Message::HookCommandChanged)
+                    .on_submit(Message::AddHookCommand)
+                    .padding(4),
+                pick_list(
+                    &PluginHook::SEND[..],
+                    Some(form.hook),
+                    Message::HookTimingChanged
+                ),
+                button(text("Add hook").size(12)).on_press_maybe(
+                    (!form.hook_command.trim().is_empty()).then_some(Message::AddHookCommand)
+                ),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+        )
+        .into()
+}
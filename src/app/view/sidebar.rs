@@ -1,16 +1,32 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
 
-use iced::widget::{Space, button, column, container, row, scrollable, text, text_input};
+use iced::widget::{
+    Space, button, checkbox, column, container, mouse_area, pick_list, row, scrollable, text,
+    text_input, tooltip,
+};
 use iced::{Alignment, Element, Length};
 
+use crate::env_audit::EnvAudit;
 use crate::pathing::{GlobalEnvRoot, ProjectRoot};
-
+use crate::state::SidebarGrouping;
+use crate::theme;
+
+use super::super::batch_headers::{BatchHeaders, HeaderOp};
+use super::super::context_menu::{ContextMenu, MenuAction, MenuTarget};
+use super::super::favorites::favorite_requests;
+use super::super::grouping::{NO_HOST, request_host};
+use super::super::history::SendHistory;
+use super::super::request_filter::request_matches;
+use super::super::split::can_split;
+use super::super::tags::{TagChoice, all_tags};
+use super::super::transfer::{Destination, TransferForm, TransferMode};
 use super::super::{EditState, EditTarget, Message};
 use super::section;
-use crate::model::{HttpFile, RequestDraft, RequestId};
+use crate::model::{Environment, HttpFile, RequestDraft, RequestId, RequestRef};
 
 const INDENT: i16 = 10;
+const ENV_CHANGES_SHOWN: usize = 8;
 
 #[derive(Debug, Clone, Copy)]
 pub enum IconSet {
@@ -40,6 +56,8 @@ impl IconSet {
                 move_up: "↑",
                 move_down: "↓",
                 selected: "→",
+                starred: "★",
+                unstarred: "☆",
             },
             Self::Ascii => Icons {
                 collapsed: ">",
@@ -49,6 +67,8 @@ impl IconSet {
                 move_up: "^",
                 move_down: "v",
                 selected: ">",
+                starred: "*",
+                unstarred: ".",
             },
         }
     }
@@ -63,6 +83,8 @@ pub struct Icons {
     move_up: &'static str,
     move_down: &'static str,
     selected: &'static str,
+    starred: &'static str,
+    unstarred: &'static str,
 }
 
 #[derive(Clone, Copy)]
@@ -77,6 +99,41 @@ pub struct SidebarContext<'a> {
     pub global_env_path_input: &'a str,
     pub edit_state: &'a EditState,
     pub icon_set: IconSet,
+    pub split_threshold: Option<usize>,
+    pub grouping: SidebarGrouping,
+    /// Active environment, used to resolve hosts when grouping by host.
+    pub environment: Option<&'a Environment>,
+    pub history: &'a SendHistory,
+    pub env_audit: &'a EnvAudit,
+    pub context_menu: Option<&'a ContextMenu>,
+    pub batch_headers: &'a BatchHeaders,
+    pub tag_filter: Option<&'a str>,
+    /// Fuzzy query over request titles, URLs and file names.
+    pub filter: &'a str,
+    pub favorites: &'a BTreeMap<PathBuf, Vec<String>>,
+    pub transfer: &'a TransferForm,
+}
+
+impl<'a> SidebarContext<'a> {
+    fn shows(&self, path: &std::path::Path, index: usize, draft: &RequestDraft) -> bool {
+        self.tag_filter.is_none_or(|tag| draft.has_tag(tag))
+            && request_matches(self.filter, path, draft)
+            && self.history.shows(&RequestRef::new(path, index, draft))
+    }
+
+    /// Collections drawn collapsed; none while filtering, so every match
+    /// is visible.
+    fn shown_collapsed(&self) -> &'a BTreeSet<String> {
+        if self.filter.trim().is_empty() {
+            self.collapsed
+        } else {
+            &NONE_COLLAPSED
+        }
+    }
+
+    fn filtered(&self) -> bool {
+        self.history.failed_only || self.tag_filter.is_some() || !self.filter.trim().is_empty()
+    }
 }
 
 struct RenderContext<'a> {
@@ -85,6 +142,10 @@ struct RenderContext<'a> {
     editing: bool,
     edit_selection: Option<&'a HashSet<EditTarget>>,
     icons: Icons,
+    http_files: &'a HashMap<PathBuf, HttpFile>,
+    split_threshold: Option<usize>,
+    context_menu: Option<&'a ContextMenu>,
+    favorites: Vec<RequestId>,
 }
 
 #[derive(Default)]
@@ -94,11 +155,39 @@ struct TreeNode {
     file_path: Option<PathBuf>,
 }
 
+impl TreeNode {
+    /// Requests in drawing order, skipping collapsed collections.
+    fn collect_visible(&self, path: &str, collapsed: &BTreeSet<String>, ids: &mut Vec<RequestId>) {
+        for child in &self.children {
+            let full_path = if path.is_empty() {
+                child.name.clone()
+            } else {
+                format!("{path}/{}", child.name)
+            };
+            if !collapsed.contains(&full_path) {
+                child.node.collect_visible(&full_path, collapsed, ids);
+            }
+        }
+        ids.extend(self.requests.iter().map(|item| item.id.clone()));
+    }
+
+    fn request_count(&self) -> usize {
+        self.requests.len()
+            + self
+                .children
+                .iter()
+                .map(|child| child.node.request_count())
+                .sum::<usize>()
+    }
+}
+
 struct TreeChild {
     name: String,
     node: TreeNode,
 }
 
+static NONE_COLLAPSED: BTreeSet<String> = BTreeSet::new();
+
 struct RequestItem {
     id: RequestId,
     draft: RequestDraft,
@@ -155,13 +244,56 @@ pub fn sidebar(ctx: SidebarContext<'_>) -> Element<'_, Message> {
             );
         }
     }
+    let mut changes = ctx.env_audit.recent(ENV_CHANGES_SHOWN).peekable();
+    if changes.peek().is_some() {
+        global_env_roots = global_env_roots.push(text("Recent variable changes").size(13));
+        for change in changes {
+            global_env_roots = global_env_roots.push(
+                text(format!(
+                    "{} {change} ({}, {})",
+                    change.changed_at_label(),
+                    change.user.as_deref().unwrap_or("unknown user"),
+                    change.source
+                ))
+                .size(12),
+            );
+        }
+    }
 
     let mut header = row![
         text("Requests").size(20),
-        button("Add").on_press(Message::AddRequest)
+        button("Add").on_press(Message::AddRequest),
+        button("Import curl").on_press(Message::ImportCurl),
+        button("Replace").on_press(Message::FindReplaceToggled),
+        button("Duplicates").on_press(Message::DuplicatesToggled),
+        pick_list(
+            SidebarGrouping::ALL.to_vec(),
+            Some(ctx.grouping),
+            Message::SidebarGroupingChanged,
+        ),
+        checkbox(ctx.history.failed_only)
+            .label(format!("Failed ({})", ctx.history.store.failed_count()))
+            .on_toggle(Message::FailedFilterToggled)
     ]
     .align_y(Alignment::Center)
     .spacing(6);
+    let tags = all_tags(ctx.http_files);
+    if !tags.is_empty() {
+        let choices = std::iter::once(TagChoice::All)
+            .chain(tags.into_iter().map(TagChoice::Tag))
+            .collect::<Vec<_>>();
+        let selected = ctx
+            .tag_filter
+            .map_or(TagChoice::All, |tag| TagChoice::Tag(tag.to_string()));
+        header = header.push(pick_list(
+            choices,
+            Some(selected),
+            Message::TagFilterChanged,
+        ));
+        if let Some(tag) = ctx.tag_filter {
+            header = header.push(button("Run tagged").on_press(Message::RunTag(tag.to_string())));
+        }
+    }
     if editing {
         let selection_empty = edit_selection.is_none_or(HashSet::is_empty);
         let delete_button = if selection_empty {
@@ -169,13 +301,180 @@ pub fn sidebar(ctx: SidebarContext<'_>) -> Element<'_, Message> {
         } else {
             button("Delete").on_press(Message::DeleteSelected)
         };
+        let selected_requests = edit_selection.map_or(0, |selection| {
+            selection
+                .iter()
+                .filter(|target| matches!(target, EditTarget::Request(_)))
+                .count()
+        });
+        let (benchmark_button, compare_button) = if selected_requests == 2 {
+            (
+                button("Benchmark A/B").on_press(Message::BenchmarkOpen),
+                button("Compare").on_press(Message::CompareOpen),
+            )
+        } else {
+            (button("Benchmark A/B"), button("Compare"))
+        };
         header = header
             .push(delete_button)
+            .push(benchmark_button)
+            .push(compare_button)
             .push(button("Done").on_press(Message::ToggleEditMode));
     } else {
         header = header.push(button("Edit").on_press(Message::ToggleEditMode));
     }
 
+    let favorites = favorite_requests(ctx.favorites, ctx.http_files);
+    let mut tree = favorites_tree(&ctx, &favorites);
+    tree.children.extend(grouped_tree(&ctx).children);
+
+    let filtering = !ctx.filter.trim().is_empty();
+    let render_ctx = RenderContext {
+        selection: ctx.selection,
+        collapsed: ctx.shown_collapsed(),
+        editing,
+        edit_selection,
+        icons,
+        http_files: ctx.http_files,
+        split_threshold: ctx.split_threshold,
+        context_menu: ctx.context_menu,
+        favorites,
+    };
+    let list = render_tree(column![], &tree, "", 0, &render_ctx).spacing(4);
+    let project_section = section("Projects", project_roots.into());
+    let global_env_section = section("Global Environments", global_env_roots.into());
+    let collections_section = section("Collections", list.into());
+
+    let mut edit_controls = column![header].spacing(6);
+    if let Some(selection) = edit_selection.filter(|selection| !selection.is_empty()) {
+        edit_controls = edit_controls.push(batch_headers_row(ctx.batch_headers, selection.len()));
+        if selection
+            .iter()
+            .any(|target| matches!(target, EditTarget::Request(_)))
+        {
+            edit_controls = edit_controls.push(transfer_row(ctx.transfer, ctx.http_file_order));
+        }
+    }
+
+    let filter = filter_row(ctx.filter, filtering.then(|| tree.request_count()));
+    let list = scrollable(
+        column![
+            project_section,
+            global_env_section,
+            edit_controls,
+            collections_section
+        ]
+        .spacing(10),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    container(column![filter, list].spacing(8))
+        .padding(8)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// Fuzzy filter over the request tree; `matches` is shown while a query is
+/// typed.
+fn filter_row(query: &str, matches: Option<usize>) -> Element<'_, Message> {
+    let mut filter = row![
+        text_input("Filter requests (Esc to clear)", query)
+            .on_input(Message::SidebarFilterChanged)
+            .padding(4)
+            .width(Length::Fill)
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6);
+    if let Some(matches) = matches {
+        let label = if matches == 1 {
+            "1 match".to_string()
+        } else {
+            format!("{matches} matches")
+        };
+        filter = filter
+            .push(text(label).size(12))
+            .push(button("Clear").on_press(Message::SidebarFilterChanged(String::new())));
+    }
+    filter.into()
+}
+
+fn grouped_tree(ctx: &SidebarContext<'_>) -> TreeNode {
+    match ctx.grouping {
+        SidebarGrouping::Files => file_tree(ctx),
+        SidebarGrouping::Host => host_tree(ctx),
+    }
+}
+
+/// Requests the tree shows, top to bottom, for range selection; the
+/// Favorites copies are left out.
+pub fn visible_requests(ctx: &SidebarContext<'_>) -> Vec<RequestId> {
+    let mut ids = Vec::new();
+    grouped_tree(ctx).collect_visible("", ctx.shown_collapsed(), &mut ids);
+    ids
+}
+
+/// Moves or copies the selected requests to a loaded or new .http file.
+fn transfer_row<'a>(form: &'a TransferForm, files: &[PathBuf]) -> Element<'a, Message> {
+    let destinations = files.iter().cloned().map(Destination).collect::<Vec<_>>();
+    let picked = destinations
+        .iter()
+        .find(|destination| destination.to_string() == form.destination)
+        .cloned();
+    row![
+        pick_list(destinations, picked, Message::TransferDestinationPicked)
+            .placeholder("Destination file")
+            .width(Length::FillPortion(2)),
+        text_input("or a new path/to/file.http", &form.destination)
+            .on_input(Message::TransferDestinationChanged)
+            .padding(4)
+            .width(Length::FillPortion(2)),
+        button(text("Move").size(12)).on_press(Message::TransferRequested(TransferMode::Move)),
+        button(text("Copy").size(12)).on_press(Message::TransferRequested(TransferMode::Copy)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6)
+    .into()
+}
+
+/// Adds, replaces or removes one header on every selected request.
+fn batch_headers_row(batch: &BatchHeaders, selected: usize) -> Element<'_, Message> {
+    let mut fields = row![
+        pick_list(
+            HeaderOp::ALL.to_vec(),
+            Some(batch.op),
+            Message::BatchHeaderOpSelected
+        ),
+        text_input("X-Api-Version", &batch.name)
+            .on_input(Message::BatchHeaderNameChanged)
+            .padding(4)
+            .width(Length::Fill),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6);
+    if batch.op != HeaderOp::Remove {
+        fields = fields.push(
+            text_input("Value", &batch.value)
+                .on_input(Message::BatchHeaderValueChanged)
+                .padding(4)
+                .width(Length::Fill),
+        );
+    }
+    let label = if selected == 1 {
+        "Apply to 1 selected item".to_string()
+    } else {
+        format!("Apply to {selected} selected items")
+    };
+    column![
+        fields,
+        button(text(label).size(12)).on_press(Message::BatchHeadersApplied)
+    ]
+    .spacing(4)
+    .into()
+}
+
+fn file_tree(ctx: &SidebarContext<'_>) -> TreeNode {
     let mut tree = TreeNode::default();
     for root in ctx.project_roots {
         let label = root.as_path().display().to_string();
@@ -194,6 +493,22 @@ pub fn sidebar(ctx: SidebarContext<'_>) -> Element<'_, Message> {
         let Some(project_root) = project_root_for_file(&file.path, ctx.project_roots) else {
             continue;
         };
+        let requests = file
+            .requests
+            .iter()
+            .enumerate()
+            .filter(|(r_idx, draft)| ctx.shows(&file.path, *r_idx, draft))
+            .map(|(r_idx, draft)| RequestItem {
+                id: RequestId::HttpFile {
+                    path: file.path.clone(),
+                    index: r_idx,
+                },
+                draft: draft.clone(),
+            })
+            .collect::<Vec<_>>();
+        if requests.is_empty() && ctx.filtered() {
+            continue;
+        }
         let rel_path = file
             .path
             .strip_prefix(project_root.as_path())
@@ -214,48 +529,63 @@ pub fn sidebar(ctx: SidebarContext<'_>) -> Element<'_, Message> {
             &mut tree,
             &segments.iter().map(String::as_str).collect::<Vec<_>>(),
             Some(&file.path),
-            file.requests
-                .iter()
-                .enumerate()
-                .map(|(r_idx, draft)| RequestItem {
-                    id: RequestId::HttpFile {
-                        path: file.path.clone(),
-                        index: r_idx,
-                    },
-                    draft: draft.clone(),
-                }),
+            requests.into_iter(),
         );
     }
+    tree
+}
 
-    let render_ctx = RenderContext {
-        selection: ctx.selection,
-        collapsed: ctx.collapsed,
-        editing,
-        edit_selection,
-        icons,
-    };
-    let list = render_tree(column![], &tree, "", 0, &render_ctx).spacing(4);
-    let project_section = section("Projects", project_roots.into());
-    let global_env_section = section("Global Environments", global_env_roots.into());
-    let collections_section = section("Collections", list.into());
-
-    let list = scrollable(
-        column![
-            project_section,
-            global_env_section,
-            header,
-            collections_section
-        ]
-        .spacing(10),
-    )
-    .width(Length::Fill)
-    .height(Length::Fill);
+/// The Favorites collection, shown above the others when anything is
+/// starred.
+fn favorites_tree(ctx: &SidebarContext<'_>, favorites: &[RequestId]) -> TreeNode {
+    let mut tree = TreeNode::default();
+    let requests = favorites
+        .iter()
+        .filter_map(|id| {
+            let RequestId::HttpFile { path, index } = id;
+            let draft = ctx.http_files.get(path)?.requests.get(*index)?;
+            ctx.shows(path, *index, draft).then(|| RequestItem {
+                id: id.clone(),
+                draft: draft.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    if !requests.is_empty() {
+        insert_collection(&mut tree, &["Favorites"], None, requests.into_iter());
+    }
+    tree
+}
 
-    container(list)
-        .padding(8)
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .into()
+/// One collection per target host, in host order with unresolved ones last.
+fn host_tree(ctx: &SidebarContext<'_>) -> TreeNode {
+    let mut hosts: BTreeMap<(bool, String), Vec<RequestItem>> = BTreeMap::new();
+    for path in ctx.http_file_order {
+        let Some(file) = ctx.http_files.get(path) else {
+            continue;
+        };
+        for (index, draft) in file.requests.iter().enumerate() {
+            if !ctx.shows(&file.path, index, draft) {
+                continue;
+            }
+            let id = RequestId::HttpFile {
+                path: file.path.clone(),
+                index,
+            };
+            let host = request_host(&draft.url, ctx.environment);
+            hosts
+                .entry((host == NO_HOST, host))
+                .or_default()
+                .push(RequestItem {
+                    id,
+                    draft: draft.clone(),
+                });
+        }
+    }
+    let mut tree = TreeNode::default();
+    for ((_, host), requests) in hosts {
+        insert_collection(&mut tree, &[host.as_str()], None, requests.into_iter());
+    }
+    tree
 }
 
 fn project_root_for_file<'a>(
@@ -335,6 +665,12 @@ fn render_child<'a>(
     let is_collapsed = ctx.collapsed.contains(&full_path);
     let row_widgets = collection_row(child, depth, ctx, &full_path, is_collapsed);
     column = column.push(row_widgets);
+    if let Some(menu) = ctx.context_menu
+        && let Some(path) = &child.node.file_path
+        && menu.target == MenuTarget::File(path.clone())
+    {
+        column = column.push(context_menu(menu, depth + 1));
+    }
 
     if !is_collapsed {
         column = render_tree(column, &child.node, &full_path, depth + 1, ctx);
@@ -352,6 +688,11 @@ fn render_requests<'a>(
     for item in &node.requests {
         let row_widgets = request_row(item, depth, ctx);
         column = column.push(row_widgets);
+        if let Some(menu) = ctx.context_menu
+            && menu.target == MenuTarget::Request(item.id.clone())
+        {
+            column = column.push(context_menu(menu, depth + 2));
+        }
     }
 
     column
@@ -404,6 +745,17 @@ fn collection_row<'a>(
             );
     }
 
+    let split_suggestion = child.node.file_path.as_ref().and_then(|file_path| {
+        let file = ctx.http_files.get(file_path)?;
+        let limit = ctx.split_threshold?;
+        (file.requests.len() > limit && can_split(file)).then(|| {
+            button(text(format!("Split ({})", file.requests.len())).size(12))
+                .style(button::secondary)
+                .padding([2, 6])
+                .on_press(Message::SplitHttpFile(file_path.clone()))
+        })
+    });
+
     row_widgets = if let Some(file_path) = &child.node.file_path {
         let is_selected = ctx
             .selection
@@ -415,18 +767,36 @@ fn collection_row<'a>(
         };
 
         row_widgets.push(
-            button(text(child.name.clone()).size(14))
-                .style(if is_selected {
-                    button::primary
-                } else {
-                    button::secondary
-                })
-                .width(Length::Fill)
-                .on_press(Message::Select(select_id)),
+            mouse_area(
+                button(text(child.name.clone()).size(14))
+                    .style(if is_selected {
+                        button::primary
+                    } else {
+                        button::secondary
+                    })
+                    .width(Length::Fill)
+                    .on_press(Message::Select(select_id)),
+            )
+            .on_right_press(Message::ContextMenuOpened(MenuTarget::File(
+                file_path.clone(),
+            ))),
         )
     } else {
         row_widgets.push(text(child.name.clone()).size(14))
     };
+    if let Some(split) = split_suggestion {
+        row_widgets = row_widgets.push(split);
+    }
+    if !ctx.editing
+        && let Some(file_path) = &child.node.file_path
+    {
+        row_widgets = row_widgets.push(
+            button(text("Run").size(12))
+                .style(button::secondary)
+                .padding([2, 6])
+                .on_press(Message::RunFile(file_path.clone())),
+        );
+    }
 
     row_widgets.spacing(4)
 }
@@ -440,10 +810,12 @@ fn request_row<'a>(
     let label = if is_selected {
         format!(
             "{} {} • {}",
-            ctx.icons.selected, item.draft.method, item.draft.title
+            ctx.icons.selected,
+            item.draft.method,
+            item.draft.label()
         )
     } else {
-        format!("{} • {}", item.draft.method, item.draft.title)
+        format!("{} • {}", item.draft.method, item.draft.label())
     };
     let mut row_widgets = row![Space::new().width(Length::Fixed(indent_px(depth + 1)))];
     if ctx.editing
@@ -472,16 +844,102 @@ fn request_row<'a>(
                     .on_press(Message::MoveRequestDown(item.id.clone())),
             );
     }
-    row_widgets = row_widgets.push(
-        button(text(label))
-            .style(if is_selected {
-                button::primary
+    if !ctx.editing {
+        let starred = ctx.favorites.contains(&item.id);
+        row_widgets = row_widgets.push(
+            button(text(if starred {
+                ctx.icons.starred
             } else {
-                button::secondary
-            })
-            .width(Length::Fill)
-            .on_press(Message::Select(item.id.clone())),
+                ctx.icons.unstarred
+            }))
+            .style(button::text)
+            .padding([2, 4])
+            .on_press(Message::FavoriteToggled(item.id.clone())),
+        );
+    }
+    let select = button(text(label))
+        .style(if is_selected {
+            button::primary
+        } else {
+            button::secondary
+        })
+        .width(Length::Fill)
+        .on_press(Message::Select(item.id.clone()));
+    let select: Element<'a, Message> = if item.draft.notes.trim().is_empty() {
+        select.into()
+    } else {
+        tooltip(
+            select,
+            container(text(item.draft.notes.clone()).size(12))
+                .padding(6)
+                .max_width(360.0)
+                .style(container::rounded_box),
+            tooltip::Position::Bottom,
+        )
+        .into()
+    };
+    row_widgets = row_widgets.push(
+        mouse_area(select).on_right_press(Message::ContextMenuOpened(MenuTarget::Request(
+            item.id.clone(),
+        ))),
     );
+    for tag in &item.draft.tags {
+        row_widgets = row_widgets.push(
+            container(text(tag.clone()).size(11))
+                .padding([1, 6])
+                .style(theme::tag_chip_style),
+        );
+    }
 
     row_widgets.spacing(4)
 }
+
+/// Actions for a right-clicked row, shown right under it.
+fn context_menu<'a>(menu: &ContextMenu, depth: usize) -> Element<'a, Message> {
+    let mut items = row![Space::new().width(Length::Fixed(indent_px(depth)))]
+        .align_y(Alignment::Center)
+        .spacing(4);
+    if let Some(name) = &menu.rename {
+        items = items
+            .push(
+                text_input("New name", name)
+                    .on_input(Message::ContextMenuRenameChanged)
+                    .on_submit(Message::ContextMenuRenameSubmitted)
+                    .padding(4)
+                    .size(12),
+            )
+            .push(
+                button(text("Rename").size(12))
+                    .padding([2, 6])
+                    .on_press(Message::ContextMenuRenameSubmitted),
+            );
+    } else {
+        let actions = match menu.target {
+            MenuTarget::Request(_) => &MenuAction::REQUEST[..],
+            MenuTarget::File(_) => &MenuAction::FILE[..],
+        };
+        for action in actions {
+            items = items.push(
+                button(text(action.to_string()).size(12))
+                    .style(if *action == MenuAction::Delete {
+                        button::danger
+                    } else {
+                        button::secondary
+                    })
+                    .padding([2, 6])
+                    .on_press(Message::ContextMenuAction(*action)),
+            );
+        }
+    }
+    items = items.push(
+        button(text("Cancel").size(12))
+            .style(button::text)
+            .padding([2, 6])
+            .on_press(Message::ContextMenuClosed),
+    );
+    container(items.wrap())
+        .padding(4)
+        .width(Length::Fill)
+        .style(container::bordered_box)
+        .into()
+}
@@ -0,0 +1,82 @@
+use iced::widget::{button, column, container, row, rule, scrollable, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use super::super::variables::MergedVariable;
+use super::super::{Message, Zagel};
+use crate::model::VariableSource;
+use crate::theme;
+
+const NAME_WIDTH: f32 = 160.0;
+const SOURCE_WIDTH: f32 = 90.0;
+
+fn variable_row(variable: MergedVariable) -> Element<'static, Message> {
+    let MergedVariable {
+        name,
+        value,
+        source,
+    } = variable;
+    let overridden = source == VariableSource::Override;
+    let source = text(source.as_str()).size(12);
+    let edited = name.clone();
+    let reset = Message::VariableOverrideCleared(name.clone());
+    row![
+        text(name).size(12).width(Length::Fixed(NAME_WIDTH)),
+        text_input("", &value)
+            .on_input(move |value| Message::VariableOverridden(edited.clone(), value))
+            .size(12)
+            .padding(4)
+            .width(Length::Fill),
+        if overridden {
+            source.style(text::warning)
+        } else {
+            source
+        }
+        .width(Length::Fixed(SOURCE_WIDTH)),
+        button(text("Reset").size(12))
+            .padding([2, 8])
+            .style(button::secondary)
+            .on_press_maybe(overridden.then_some(reset)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8)
+    .into()
+}
+
+/// The merged variables of the selected request, where they come from and
+/// per-request overrides.
+pub fn variables_panel(app: &Zagel) -> Element<'_, Message> {
+    let header = row![
+        text("Variables").size(16).width(Length::Fill),
+        button("Close").on_press(Message::VariablesToggled),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+    let precedence = text(
+        "Precedence: override > session > file > project env > global env. \
+         Editing a value overrides it for this request until Reset.",
+    )
+    .size(12);
+
+    let variables = app.selected_variables();
+    let mut list = column![].spacing(4);
+    if variables.is_empty() {
+        list = list.push(text("The active environment defines no variables").size(12));
+    }
+    for variable in variables {
+        list = list.push(variable_row(variable));
+    }
+
+    container(
+        column![
+            header,
+            precedence,
+            rule::horizontal(1),
+            scrollable(list).height(Length::Shrink)
+        ]
+        .spacing(6),
+    )
+    .padding(12)
+    .max_width(720.0)
+    .style(theme::overlay_container_style)
+    .into()
+}
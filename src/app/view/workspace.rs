@@ -1,16 +1,46 @@
+use std::collections::BTreeSet;
+
 use iced::widget::pane_grid::{self, PaneGrid};
+use iced::widget::text::Wrapping;
 use iced::widget::{
-    button, column, container, pick_list, row, scrollable, stack, text, text_editor, text_input,
+    button, checkbox, column, container, pick_list, row, scrollable, stack, text, text_editor,
+    text_input, tooltip,
 };
 use iced::{Alignment, Element, Length, Theme, alignment};
 
-use super::super::{Message, Zagel, headers};
+use super::super::automation::ScreenshotRegion;
+use super::super::body_hints::{content_length_mismatch, content_type_hint};
+use super::super::cookies::JarChoice;
+use super::super::graphql_editor::{GraphqlHighlighter, graphql_format};
+use super::super::placeholders::{
+    PlaceholderHighlight, PlaceholderHighlighter, field_highlight, input_style, placeholder_format,
+    placeholder_spans,
+};
+use super::super::preview::ResolvedPreview;
+use super::super::url_builder::UrlPartEdit;
+use super::super::{Message, Zagel, assertions, headers};
 use super::auth::auth_editor;
-use super::response::{response_panel, response_tab_toggle, response_view_toggle};
-use super::section;
+use super::benchmark::benchmark_panel;
+use super::duplicates::duplicates_panel;
+use super::find_replace::find_replace_panel;
+use super::graphql_schema::schema_explorer;
+use super::history::{failure_snapshot_panel, recent_sends_panel};
+use super::jwt::jwt_panel;
+use super::pin::pinned_pane;
+use super::response::{
+    ResponsePanelContext, ResponseTab, response_panel, response_search_bar, response_tab_toggle,
+    response_view_toggle,
+};
+use super::runner::runner_panel;
+use super::settings::settings_panel;
+use super::variables::variables_panel;
+use super::{format_size, section};
 use crate::app::options::RequestMode;
-use crate::model::{Method, RequestId};
+use crate::model::{BodyEncoding, HttpVersion, Method, RequestId};
+use crate::net::split_url_credentials;
+use crate::parser::env_file_path;
 use crate::theme;
+use crate::urls::{UrlParts, encoding_warning, path_param_names};
 
 #[derive(Debug, Clone, Copy)]
 pub enum WorkspacePane {
@@ -26,14 +56,73 @@ pub enum BuilderPane {
 
 const ENV_PICK_MAX_WIDTH: f32 = 180.0;
 const MODE_PICK_MAX_WIDTH: f32 = 150.0;
+const JAR_PICK_MAX_WIDTH: f32 = 210.0;
 const METHOD_PICK_MAX_WIDTH: f32 = 120.0;
+const VERSION_PICK_WIDTH: f32 = 100.0;
+const ENCODING_PICK_WIDTH: f32 = 160.0;
 const ACTION_WIDTH: f32 = 84.0;
 const LABEL_WIDTH: f32 = 80.0;
+const PREVIEW_MAX_HEIGHT: f32 = 240.0;
+const NOTES_HEIGHT: f32 = 120.0;
+
+/// An entry of the method picker; `Custom` reveals a field for the name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MethodChoice {
+    Standard(Method),
+    Custom,
+}
+
+impl std::fmt::Display for MethodChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Standard(method) => method.fmt(f),
+            Self::Custom => f.write_str("Custom..."),
+        }
+    }
+}
+
+fn method_picker(method: &Method) -> Element<'_, Message> {
+    let mut choices = Method::ALL
+        .into_iter()
+        .map(MethodChoice::Standard)
+        .collect::<Vec<_>>();
+    choices.push(MethodChoice::Custom);
+    let selected = match method {
+        Method::Custom(_) => MethodChoice::Custom,
+        standard => MethodChoice::Standard(standard.clone()),
+    };
+    let pick = container(
+        pick_list(choices, Some(selected), |choice| match choice {
+            MethodChoice::Standard(method) => Message::MethodSelected(method),
+            MethodChoice::Custom => Message::CustomMethodChanged(String::new()),
+        })
+        .width(Length::Fill),
+    )
+    .width(Length::FillPortion(2))
+    .max_width(METHOD_PICK_MAX_WIDTH);
+    match method {
+        Method::Custom(name) => row![
+            pick,
+            text_input("PROPFIND", name)
+                .on_input(Message::CustomMethodChanged)
+                .padding(6)
+                .width(Length::Fixed(METHOD_PICK_MAX_WIDTH)),
+        ]
+        .spacing(6)
+        .width(Length::FillPortion(4))
+        .into(),
+        _ => pick.into(),
+    }
+}
 
 pub fn workspace(app: &Zagel) -> Element<'_, Message> {
     let workspace_grid = PaneGrid::new(&app.workspace_panes, move |_, pane, _| match pane {
-        WorkspacePane::Builder => pane_grid::Content::new(builder(app)),
-        WorkspacePane::Response => pane_grid::Content::new(response(app)),
+        WorkspacePane::Builder => pane_grid::Content::new(
+            container(builder(app)).id(ScreenshotRegion::Builder.widget_id()),
+        ),
+        WorkspacePane::Response => pane_grid::Content::new(
+            container(response(app)).id(ScreenshotRegion::Response.widget_id()),
+        ),
     })
     .width(Length::Fill)
     .height(Length::Fill)
@@ -66,6 +155,7 @@ fn builder(app: &Zagel) -> Element<'_, Message> {
     builder_grid.into()
 }
 
+#[allow(clippy::too_many_lines)]
 fn builder_form(app: &Zagel) -> Element<'_, Message> {
     let env_pick = container(
         pick_list(
@@ -81,19 +171,38 @@ fn builder_form(app: &Zagel) -> Element<'_, Message> {
     .width(Length::FillPortion(2))
     .max_width(ENV_PICK_MAX_WIDTH);
 
-    let method_pick = container(
+    let variables_button = tooltip(
+        button(text("Variables").size(12))
+            .style(button::text)
+            .on_press(Message::VariablesToggled),
+        container(text("Merged variables of this request and where they come from").size(12))
+            .padding(4)
+            .style(theme::overlay_container_style),
+        tooltip::Position::Bottom,
+    );
+
+    let active_env = app.environments.get(app.active_environment);
+    let jar_pick = container(
         pick_list(
-            Method::ALL.to_vec(),
-            Some(app.draft.method),
-            Message::MethodSelected,
+            JarChoice::ALL.to_vec(),
+            Some(app.cookies.choice),
+            Message::CookieJarSelected,
         )
         .width(Length::Fill),
     )
-    .width(Length::FillPortion(2))
-    .max_width(METHOD_PICK_MAX_WIDTH);
+    .width(Length::FillPortion(3))
+    .max_width(JAR_PICK_MAX_WIDTH);
+    let cookie_count = app.cookies.count(active_env);
+    let clear_cookies = button(text(format!("Clear ({cookie_count})")).size(12))
+        .style(button::text)
+        .on_press_maybe((cookie_count > 0).then_some(Message::ClearCookies));
 
+    let method_pick = method_picker(&app.draft.method);
+
+    let defined = app.defined_variables();
     let url_input = text_input("https://api.example.com", &app.draft.url)
         .on_input(Message::UrlChanged)
+        .style(input_style(field_highlight(&app.draft.url, &defined)))
         .padding(6)
         .width(Length::FillPortion(6));
 
@@ -133,7 +242,8 @@ fn builder_form(app: &Zagel) -> Element<'_, Message> {
     .width(Length::FillPortion(2))
     .max_width(MODE_PICK_MAX_WIDTH);
 
-    let auth_view = auth_editor(&app.auth);
+    let selection = app.workspace.selection();
+    let auth_view = auth_editor(&app.auth, app.file_auth(selection), selection.is_some());
 
     let meta_section = column![
         row![
@@ -145,27 +255,133 @@ fn builder_form(app: &Zagel) -> Element<'_, Message> {
         .align_y(Alignment::Center)
         .spacing(6),
         save_path_row,
-        row![env_pick, mode_pick]
-            .align_y(Alignment::Center)
-            .spacing(6),
+        step_row(app),
+        row![
+            env_pick,
+            variables_button,
+            tooltip(
+                button(text("Decode JWT").size(12))
+                    .style(button::text)
+                    .on_press(Message::JwtInspectorToggled),
+                container(
+                    text("Decode a JWT from the Authorization header or the last response")
+                        .size(12)
+                )
+                .padding(4)
+                .style(theme::overlay_container_style),
+                tooltip::Position::Bottom,
+            ),
+            jar_pick,
+            clear_cookies,
+            mode_pick
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6),
+        session_row(app),
     ]
     .spacing(6);
 
     let request_section = row![
         method_pick,
         url_input,
+        pick_list(
+            HttpVersion::PICKABLE.to_vec(),
+            Some(app.draft.http_version),
+            Message::HttpVersionSelected,
+        )
+        .width(Length::Fixed(VERSION_PICK_WIDTH)),
+        tooltip(
+            button(text("Parts").size(12))
+                .padding([6, 8])
+                .style(if app.url_builder_open {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(Message::UrlBuilderToggled),
+            container(text("Edit the URL by parts").size(12))
+                .padding(4)
+                .style(theme::overlay_container_style),
+            tooltip::Position::Bottom,
+        ),
+        tooltip(
+            button(text("Preview").size(12))
+                .padding([6, 8])
+                .style(if app.preview_open {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(Message::PreviewToggled),
+            container(text("Show the request as it would be sent").size(12))
+                .padding(4)
+                .style(theme::overlay_container_style),
+            tooltip::Position::Bottom,
+        ),
+        tooltip(
+            button(text("{{ }}").size(12))
+                .padding([6, 8])
+                .style(button::secondary)
+                .on_press(Message::ExtractVariableToggled),
+            container(text("Extract to variable…").size(12))
+                .padding(4)
+                .style(theme::overlay_container_style),
+            tooltip::Position::Bottom,
+        ),
         button("Send")
             .on_press(Message::Send)
             .width(Length::Fixed(ACTION_WIDTH)),
     ]
     .align_y(Alignment::Center)
     .spacing(6);
+    let mut request_section = column![request_section].spacing(6);
+    if let Some(chips) = placeholder_chips(&app.draft.url, &defined) {
+        request_section = request_section.push(chips);
+    }
+    if let Some(warning) = encoding_warning(&app.draft.url) {
+        request_section = request_section.push(text(warning).size(12).style(text::warning));
+    }
+    if let Some(params) = path_params_table(app) {
+        request_section = request_section.push(params);
+    }
+    if app.url_builder_open {
+        request_section = request_section.push(url_builder(&app.draft.url));
+    }
+    if app.preview_open {
+        request_section = request_section.push(preview_panel(app.resolved_preview()));
+    }
+    if let Some(extract) = extract_variable_row(app) {
+        request_section = request_section.push(extract);
+    }
+    if split_url_credentials(&app.draft.url).1.is_some() {
+        request_section = request_section.push(
+            row![
+                text("The URL contains credentials; they are sent as Basic auth").size(12),
+                button(text("Move to Auth").size(12))
+                    .padding([2, 8])
+                    .on_press(Message::MoveUrlCredentialsToAuth),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+        );
+    }
 
     let form_content = column![
         section("Meta", meta_section.into()),
+        notes_section(app),
         section("Request", request_section.into()),
-        section("Headers", headers::editor(&app.header_rows)),
+        section(
+            "Headers",
+            column![
+                headers::editor(&app.header_rows, &defined),
+                idempotency_row(app),
+                trace_context_row(app)
+            ]
+            .spacing(6)
+            .into()
+        ),
         section("Auth", auth_view),
+        section("Assertions", assertions::editor(&app.draft.assertions)),
     ]
     .spacing(10);
 
@@ -180,39 +396,139 @@ fn builder_form(app: &Zagel) -> Element<'_, Message> {
         .into()
 }
 
+/// Query and variables editors next to the schema explorer.
+fn graphql_body(app: &Zagel) -> Element<'_, Message> {
+    let wrapping = if app.graphql_editor.wrap {
+        Wrapping::WordOrGlyph
+    } else {
+        Wrapping::None
+    };
+    let query_editor: iced::widget::TextEditor<'_, GraphqlHighlighter, _, Theme> =
+        text_editor(&app.graphql_query)
+            .on_action(Message::GraphqlQueryEdited)
+            .height(Length::FillPortion(3))
+            .wrapping(wrapping)
+            .highlight_with::<GraphqlHighlighter>(app.graphql_highlight_settings(), graphql_format);
+    let vars_editor: iced::widget::TextEditor<'_, PlaceholderHighlighter, _, Theme> =
+        text_editor(&app.graphql_variables)
+            .on_action(Message::GraphqlVariablesEdited)
+            .height(Length::FillPortion(2))
+            .wrapping(wrapping)
+            .highlight_with::<PlaceholderHighlighter>(app.defined_variables(), placeholder_format);
+    let check = &app.graphql_check;
+    let small_button = |label| button(text(label).size(12)).padding([2, 8]);
+    let mut query_header = row![
+        text("Query").width(Length::Fill),
+        tooltip(
+            small_button("Fold")
+                .style(button::secondary)
+                .on_press(Message::GraphqlFoldToggled),
+            text("Fold the { } at the cursor, or unfold the one under it").size(12),
+            tooltip::Position::Bottom,
+        ),
+        small_button("Unfold all")
+            .style(button::secondary)
+            .on_press_maybe(
+                app.graphql_editor
+                    .has_folds()
+                    .then_some(Message::GraphqlUnfoldAll)
+            ),
+        small_button("Wrap")
+            .style(if app.graphql_editor.wrap {
+                button::primary
+            } else {
+                button::secondary
+            })
+            .on_press(Message::GraphqlWrapToggled),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6);
+    if check.operations.len() > 1 {
+        query_header = query_header.push(
+            pick_list(
+                check.operations.clone(),
+                check.operation.clone(),
+                Message::GraphqlOperationSelected,
+            )
+            .placeholder("Operation"),
+        );
+    }
+    let mut editors = column![query_header, query_editor]
+        .width(Length::FillPortion(3))
+        .height(Length::Fill)
+        .spacing(6);
+    for diagnostic in &check.diagnostics {
+        editors = editors.push(text(diagnostic).size(12).style(text::danger));
+    }
+    let editors = editors.push(text("Variables")).push(vars_editor);
+    row![
+        editors,
+        container(schema_explorer(app))
+            .width(Length::FillPortion(2))
+            .height(Length::Fill)
+    ]
+    .height(Length::Fill)
+    .spacing(8)
+    .into()
+}
+
 fn builder_body(app: &Zagel) -> Element<'_, Message> {
     let body_title = match app.mode {
         RequestMode::GraphQl => "GraphQL",
+        RequestMode::Grpc => "gRPC",
         RequestMode::Rest => "REST",
     };
 
     let body_panel: Element<'_, Message> = match app.mode {
-        RequestMode::GraphQl => {
-            let query_editor: iced::widget::TextEditor<'_, _, _, Theme> =
-                text_editor(&app.graphql_query)
-                    .on_action(Message::GraphqlQueryEdited)
-                    .height(Length::FillPortion(3));
-            let vars_editor: iced::widget::TextEditor<'_, _, _, Theme> =
-                text_editor(&app.graphql_variables)
-                    .on_action(Message::GraphqlVariablesEdited)
-                    .height(Length::FillPortion(2));
-            column![text("Query"), query_editor, text("Variables"), vars_editor,]
+        RequestMode::GraphQl => graphql_body(app),
+        RequestMode::Grpc => {
+            let body_editor: iced::widget::TextEditor<'_, PlaceholderHighlighter, _, Theme> =
+                text_editor(&app.body_editor)
+                    .on_action(Message::BodyEdited)
+                    .height(Length::Fill)
+                    .highlight_with::<PlaceholderHighlighter>(
+                        app.defined_variables(),
+                        placeholder_format,
+                    );
+            column![grpc_controls(app), text("Message (JSON)"), body_editor]
                 .height(Length::Fill)
                 .spacing(6)
                 .into()
         }
         RequestMode::Rest => {
-            let body_editor: iced::widget::TextEditor<'_, _, _, Theme> =
+            let body_editor: iced::widget::TextEditor<'_, PlaceholderHighlighter, _, Theme> =
                 text_editor(&app.body_editor)
                     .on_action(Message::BodyEdited)
-                    .height(Length::Fill);
-            column![text("Body"), body_editor]
+                    .height(Length::Fill)
+                    .highlight_with::<PlaceholderHighlighter>(
+                        app.defined_variables(),
+                        placeholder_format,
+                    );
+            column![text("Body"), body_editor, body_hints_row(app)]
                 .height(Length::Fill)
                 .spacing(6)
                 .into()
         }
     };
 
+    let encoding_row = row![
+        text("Encoding").size(14),
+        pick_list(
+            BodyEncoding::ALL.to_vec(),
+            Some(app.draft.body_encoding),
+            Message::BodyEncodingSelected,
+        )
+        .width(Length::Fixed(ENCODING_PICK_WIDTH)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6);
+    let mut body_panel = column![body_panel].height(Length::Fill).spacing(6);
+    // gRPC frames its own messages; a body encoding does not apply.
+    if app.mode != RequestMode::Grpc {
+        body_panel = body_panel.push(encoding_row);
+    }
+    let body_panel = body_panel.into();
+
     let body_section = section(body_title, body_panel);
 
     container(column![body_section].padding(8).height(Length::Fill))
@@ -224,10 +540,21 @@ fn builder_body(app: &Zagel) -> Element<'_, Message> {
 fn response(app: &Zagel) -> Element<'_, Message> {
     let mut status_row = row![
         response_view_toggle(app.response_display),
-        response_tab_toggle(app.response_tab),
+        response_tab_toggle(
+            app.response_tab,
+            app.response
+                .as_ref()
+                .is_some_and(|response| response.body.hex_text().is_some()),
+        ),
     ]
     .spacing(8);
 
+    let tree_shown = app.response_tab == super::response::ResponseTab::Body
+        && app.response_display == super::response::ResponseDisplay::Tree
+        && app.json_tree.root.is_some();
+    if matches!(app.response_tab, ResponseTab::Body | ResponseTab::Hex) && !tree_shown {
+        status_row = status_row.push(response_search_bar(&app.response_search));
+    }
     if matches!(app.response_tab, super::response::ResponseTab::Body) {
         status_row = status_row.push(button("Copy raw").on_press(Message::CopyResponseRaw));
         if app.response_display == super::response::ResponseDisplay::Pretty
@@ -242,20 +569,62 @@ fn response(app: &Zagel) -> Element<'_, Message> {
         }
     }
 
+    if matches!(app.response_tab, ResponseTab::Body | ResponseTab::Hex)
+        && app
+            .response
+            .as_ref()
+            .is_some_and(|response| response.preview.body.is_some())
+    {
+        status_row = status_row.push(tooltip(
+            button("Copy as file").on_press(Message::CopyResponseBodyAsFile),
+            text("Put the body on the clipboard as a file, to paste into chat or mail").size(12),
+            tooltip::Position::Bottom,
+        ));
+    }
+    if app.response.is_some() {
+        status_row = status_row.push(button("Pin").on_press(Message::PinResponse));
+    }
+
+    let mut controls = column![status_row].spacing(6);
+    if let Some(trace_id) = trace_id_row(app) {
+        controls = controls.push(trace_id);
+    }
+    if let Some(filter) = filter_row(app) {
+        controls = controls.push(filter);
+    }
+
     let response_view = response_panel(
         app.response.as_ref(),
-        &app.response_viewer,
-        app.response_display,
-        app.response_tab,
-        app.state.theme.highlight_theme(),
+        &ResponsePanelContext {
+            content: &app.response_viewer,
+            display: app.response_display,
+            tab: app.response_tab,
+            highlight_theme: app.state.theme.highlight_theme(),
+            search: &app.response_search,
+            filter: &app.response_filter,
+            header_filter: &app.response_header_filter,
+            tree: &app.json_tree,
+            pager: &app.response_pager,
+            viewer: app.state.response_view,
+        },
     );
 
+    let live = column![controls, response_view]
+        .spacing(6)
+        .height(Length::Fill);
     let response_section = section(
         "Response",
-        column![status_row, response_view]
-            .spacing(6)
-            .height(Length::Fill)
+        match &app.pinned_response {
+            Some(pinned) => row![
+                live.width(Length::FillPortion(1)),
+                container(pinned_pane(pinned, app.response.as_ref()))
+                    .width(Length::FillPortion(1))
+                    .height(Length::Fill),
+            ]
+            .spacing(8)
             .into(),
+            None => live.into(),
+        },
     );
 
     let base = container(response_section)
@@ -263,20 +632,506 @@ fn response(app: &Zagel) -> Element<'_, Message> {
         .width(Length::Fill)
         .height(Length::Fill)
         .into();
+    with_overlays(app, base)
+}
 
+/// Panels shown over the response pane.
+fn with_overlays<'a>(app: &'a Zagel, base: Element<'a, Message>) -> Element<'a, Message> {
+    let mut layers = vec![base];
+    if let Some(session) = app.benchmark.as_ref() {
+        layers.push(centered_overlay(benchmark_panel(session)));
+    }
+    if let Some(run) = app.file_run.as_ref() {
+        layers.push(centered_overlay(runner_panel(run)));
+    }
+    if let Some(entry) = app.history.last_failure.as_ref()
+        && app.history.show_snapshot
+    {
+        layers.push(centered_overlay(failure_snapshot_panel(entry)));
+    }
+    if app.history.show_recent {
+        layers.push(centered_overlay(recent_sends_panel(
+            app.history.store.entries(),
+        )));
+    }
+    if let Some(groups) = &app.duplicates {
+        layers.push(centered_overlay(duplicates_panel(groups)));
+    }
+    if app.find_replace.open {
+        layers.push(centered_overlay(find_replace_panel(&app.find_replace)));
+    }
+    if app.variables_open {
+        layers.push(centered_overlay(variables_panel(app)));
+    }
+    if let Some(inspector) = &app.jwt_inspector {
+        layers.push(centered_overlay(jwt_panel(inspector)));
+    }
+    if app.show_settings {
+        layers.push(centered_overlay(settings_panel(app)));
+    }
     if app.show_shortcuts {
-        let overlay = container(shortcuts_panel())
+        layers.push(
+            container(shortcuts_panel())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(alignment::Horizontal::Right)
+                .align_y(alignment::Vertical::Top)
+                .padding(12)
+                .into(),
+        );
+    }
+
+    if layers.len() == 1 {
+        return layers.remove(0);
+    }
+    stack(layers).into()
+}
+
+fn centered_overlay(panel: Element<'_, Message>) -> Element<'_, Message> {
+    container(panel)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(alignment::Horizontal::Center)
+        .align_y(alignment::Vertical::Top)
+        .padding(12)
+        .into()
+}
+
+fn idempotency_row(app: &Zagel) -> Element<'_, Message> {
+    let mut controls = row![
+        checkbox(app.idempotency.enabled)
+            .label("Add Idempotency-Key on send")
+            .on_toggle(Message::IdempotencyToggled),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8);
+    if let Some(last) = &app.idempotency.last {
+        controls = controls.push(
+            checkbox(app.idempotency.reuse_last)
+                .label("Reuse last")
+                .on_toggle_maybe(
+                    app.idempotency
+                        .enabled
+                        .then_some(Message::IdempotencyReuseToggled),
+                ),
+        );
+        controls = controls.push(text(format!("Last: {last}")).size(12));
+        controls = controls.push(
+            button(text("Copy").size(12))
+                .padding([2, 8])
+                .on_press(Message::CopyIdempotencyKey),
+        );
+    }
+    controls.into()
+}
+
+/// JSON path filter input, shown on the Body tab for JSON responses.
+fn filter_row(app: &Zagel) -> Option<Element<'_, Message>> {
+    let is_json = app
+        .response
+        .as_ref()
+        .is_some_and(|response| response.body.is_json());
+    if !is_json || app.response_tab != super::response::ResponseTab::Body {
+        return None;
+    }
+    Some(
+        row![
+            text("Filter").size(14),
+            text_input("$.items[*].id", &app.response_filter)
+                .on_input(Message::ResponseFilterChanged)
+                .padding(4)
+                .width(Length::Fill),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6)
+        .into(),
+    )
+}
+
+fn trace_context_row(app: &Zagel) -> Element<'_, Message> {
+    row![
+        checkbox(app.trace_context.enabled)
+            .label("Add traceparent on send")
+            .on_toggle(Message::TraceContextToggled),
+        checkbox(app.trace_context.b3)
+            .label("Also send B3")
+            .on_toggle_maybe(app.trace_context.enabled.then_some(Message::TraceB3Toggled)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(8)
+    .into()
+}
+
+/// Trace id of the last send, shown above the response so it can be searched
+/// for in a tracing backend.
+fn trace_id_row(app: &Zagel) -> Option<Element<'_, Message>> {
+    let trace_id = app.trace_context.last_trace_id.as_ref()?;
+    Some(
+        row![
+            text("Trace ID").size(14),
+            text(trace_id).size(16).font(iced::Font::MONOSPACE),
+            button(text("Copy").size(12))
+                .padding([2, 8])
+                .on_press(Message::CopyTraceId),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(8)
+        .into(),
+    )
+}
+
+/// Body size, plus warnings for a missing `Content-Type` (with a button
+/// adding the sniffed one) or a `Content-Length` that does not match.
+fn body_hints_row(app: &Zagel) -> Element<'_, Message> {
+    let (headers, body) = (&app.draft.headers, &app.draft.body);
+    let mut hints = row![text(format_size(body.len())).size(12)]
+        .align_y(Alignment::Center)
+        .spacing(8);
+    if let Some(content_type) = content_type_hint(headers, body) {
+        hints = hints
+            .push(text("No Content-Type header").size(12).style(text::warning))
+            .push(
+                button(text(format!("Add Content-Type: {content_type}")).size(12))
+                    .padding([2, 8])
+                    .style(button::secondary)
+                    .on_press(Message::ContentTypeAdded(content_type)),
+            );
+    }
+    if let Some(declared) = content_length_mismatch(headers, body) {
+        hints = hints.push(
+            text(format!(
+                "Content-Length says {declared} bytes but the body has {}",
+                body.len()
+            ))
+            .size(12)
+            .style(text::warning),
+        );
+    }
+    hints.into()
+}
+
+fn url_part_input<'a>(
+    label: &str,
+    value: &str,
+    on_input: impl Fn(String) -> UrlPartEdit + 'a,
+) -> Element<'a, Message> {
+    text_input(label, value)
+        .on_input(move |value| Message::UrlPartEdited(on_input(value)))
+        .padding(4)
+        .width(Length::Fill)
+        .into()
+}
+
+fn remove_part_button<'a>(edit: UrlPartEdit) -> Element<'a, Message> {
+    button(text("x").size(12))
+        .padding([2, 8])
+        .style(button::text)
+        .on_press(Message::UrlPartEdited(edit))
+        .into()
+}
+
+/// The URL's placeholders in the colours of the body editor, since the URL
+/// input itself can only colour its border.
+fn placeholder_chips<'a>(url: &str, defined: &BTreeSet<String>) -> Option<Element<'a, Message>> {
+    let spans = placeholder_spans(url, defined);
+    if spans.is_empty() {
+        return None;
+    }
+    let mut chips = row![].spacing(6).align_y(Alignment::Center);
+    for (range, highlight) in spans {
+        let chip = text(url[range].to_string()).size(12);
+        chips = chips.push(match highlight {
+            PlaceholderHighlight::Resolved => chip.style(text::success),
+            PlaceholderHighlight::Missing => chip.style(text::danger),
+        });
+    }
+    Some(chips.into())
+}
+
+/// The request with variables and auth filled in, as plain monospace text.
+fn preview_panel<'a>(preview: ResolvedPreview) -> Element<'a, Message> {
+    let mut resolved = preview.request_line;
+    if !preview.headers.is_empty() {
+        resolved.push('\n');
+        resolved.push_str(&preview.headers);
+    }
+    if !preview.body.is_empty() {
+        resolved.push_str("\n\n");
+        resolved.push_str(&preview.body);
+    }
+    let mut panel = column![
+        text(resolved)
+            .size(12)
+            .font(iced::Font::MONOSPACE)
             .width(Length::Fill)
-            .height(Length::Fill)
-            .align_x(alignment::Horizontal::Right)
-            .align_y(alignment::Vertical::Top)
-            .padding(12)
-            .into();
+    ]
+    .spacing(4);
+    if !preview.missing.is_empty() {
+        panel = panel.push(
+            text(format!("Unresolved: {}", preview.missing.join(", ")))
+                .size(12)
+                .style(text::danger),
+        );
+    }
+    container(scrollable(panel).height(Length::Shrink))
+        .padding(8)
+        .max_height(PREVIEW_MAX_HEIGHT)
+        .width(Length::Fill)
+        .style(theme::overlay_container_style)
+        .into()
+}
 
-        return stack([base, overlay]).into();
+/// The URL split into scheme, host, path segments and query pairs; each
+/// edit is written straight back into the URL field.
+fn url_builder(url: &str) -> Element<'_, Message> {
+    let parts = UrlParts::parse(url);
+    let labeled = |label, field| {
+        row![
+            container(text(label).size(12)).width(Length::Fixed(LABEL_WIDTH)),
+            field
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6)
+    };
+    let mut builder = column![
+        labeled(
+            "Scheme",
+            url_part_input("https", &parts.scheme, UrlPartEdit::Scheme)
+        ),
+        labeled(
+            "Host",
+            url_part_input("api.example.com", &parts.host, UrlPartEdit::Host)
+        ),
+    ]
+    .spacing(4);
+    for (index, segment) in parts.segments.iter().enumerate() {
+        builder = builder.push(labeled(
+            "Path",
+            row![
+                url_part_input("segment", segment, move |value| {
+                    UrlPartEdit::Segment(index, value)
+                }),
+                remove_part_button(UrlPartEdit::RemoveSegment(index)),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6)
+            .into(),
+        ));
     }
+    for (index, (name, value)) in parts.query.iter().enumerate() {
+        builder = builder.push(labeled(
+            "Query",
+            row![
+                url_part_input("name", name, move |name| UrlPartEdit::QueryName(
+                    index, name
+                )),
+                url_part_input("value", value, move |value| {
+                    UrlPartEdit::QueryValue(index, value)
+                }),
+                remove_part_button(UrlPartEdit::RemoveQuery(index)),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6)
+            .into(),
+        ));
+    }
+    builder
+        .push(
+            row![
+                button(text("Add segment").size(12))
+                    .padding([2, 8])
+                    .on_press(Message::UrlPartEdited(UrlPartEdit::AddSegment)),
+                button(text("Add query parameter").size(12))
+                    .padding([2, 8])
+                    .on_press(Message::UrlPartEdited(UrlPartEdit::AddQuery)),
+            ]
+            .spacing(6),
+        )
+        .into()
+}
+
+/// Collapsible markdown notes of the request, saved as comments in the file.
+fn notes_section(app: &Zagel) -> Element<'_, Message> {
+    let first_line = app
+        .draft
+        .notes
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("No notes");
+    let toggle = button(text(if app.notes.open { "Hide" } else { "Show" }).size(12))
+        .padding([2, 8])
+        .style(button::secondary)
+        .on_press(Message::NotesToggled);
+    let mut notes = column![
+        row![
+            text("Notes").size(14),
+            text(first_line).size(12).width(Length::Fill),
+            toggle
+        ]
+        .align_y(Alignment::Center)
+        .spacing(8)
+    ]
+    .spacing(6);
+    if app.notes.open {
+        notes = notes.push(
+            text_editor(&app.notes.content)
+                .placeholder("Markdown notes, saved as # comments above the request")
+                .on_action(Message::NotesEdited)
+                .height(Length::Fixed(NOTES_HEIGHT)),
+        );
+    }
+    notes.into()
+}
+
+/// Values of the URL's `:id`/`{id}` path parameters, filled in on send.
+fn path_params_table(app: &Zagel) -> Option<Element<'_, Message>> {
+    let names = path_param_names(&app.draft.url);
+    if names.is_empty() {
+        return None;
+    }
+    let defined = app.defined_variables();
+    let mut table = column![text("Path parameters").size(12)].spacing(4);
+    for name in names {
+        let value = app
+            .draft
+            .path_params
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map_or("", |(_, value)| value.as_str());
+        table = table.push(
+            row![
+                container(
+                    text(format!(":{name}"))
+                        .size(12)
+                        .font(iced::Font::MONOSPACE)
+                )
+                .width(Length::Fixed(LABEL_WIDTH)),
+                text_input("value", value)
+                    .on_input(move |value| Message::PathParamChanged(name.clone(), value))
+                    .style(input_style(field_highlight(value, &defined)))
+                    .padding(4)
+                    .width(Length::Fill),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(6),
+        );
+    }
+    Some(table.into())
+}
+
+fn extract_variable_row(app: &Zagel) -> Option<Element<'_, Message>> {
+    let extract = app.extract_variable.as_ref()?;
+    let environments = app
+        .environments
+        .iter()
+        .filter(|env| env_file_path(env).is_some())
+        .map(|env| env.name.clone())
+        .collect::<Vec<_>>();
+    Some(
+        row![
+            text_input("Value to extract", &extract.value)
+                .on_input(Message::ExtractValueChanged)
+                .padding(4)
+                .width(Length::FillPortion(3)),
+            text_input("Variable name", &extract.name)
+                .on_input(Message::ExtractNameChanged)
+                .on_submit(Message::ExtractVariableApplied)
+                .padding(4)
+                .width(Length::FillPortion(2)),
+            pick_list(
+                environments,
+                extract.environment.clone(),
+                Message::ExtractEnvironmentSelected
+            )
+            .placeholder("Environment")
+            .width(Length::FillPortion(2)),
+            button(text("Extract").size(12))
+                .padding([2, 8])
+                .on_press(Message::ExtractVariableApplied),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6)
+        .into(),
+    )
+}
+
+fn step_row(app: &Zagel) -> Element<'_, Message> {
+    row![
+        container(text("Step").size(14)).width(Length::Fixed(LABEL_WIDTH)),
+        text_input(
+            "Description shown in run output",
+            app.draft.step.as_deref().unwrap_or_default()
+        )
+        .on_input(Message::StepChanged)
+        .padding(4)
+        .width(Length::Fill),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6)
+    .into()
+}
+
+fn session_row(app: &Zagel) -> Element<'_, Message> {
+    let sessions = app.state.sessions.keys().cloned().collect::<Vec<_>>();
+    let active = app.state.active_session.clone();
+    row![
+        container(text("Session").size(14)).width(Length::Fixed(LABEL_WIDTH)),
+        pick_list(sessions, active.clone(), Message::SessionSelected)
+            .placeholder("No session")
+            .width(Length::FillPortion(2)),
+        text_input(
+            active.as_deref().unwrap_or("New session name"),
+            &app.session_name_input
+        )
+        .on_input(Message::SessionNameChanged)
+        .on_submit(Message::SaveSession)
+        .padding(4)
+        .width(Length::FillPortion(2)),
+        button(text("Save").size(12)).on_press(Message::SaveSession),
+        button(text("Delete").size(12))
+            .style(button::text)
+            .on_press_maybe(active.map(|_| Message::DeleteSession)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6)
+    .into()
+}
 
-    base
+fn grpc_controls(app: &Zagel) -> Element<'_, Message> {
+    let grpc = &app.grpc;
+    let protos = row![
+        text_input("api/service.proto, other.proto", &grpc.proto_paths)
+            .on_input(Message::GrpcProtoPathsChanged)
+            .on_submit(Message::LoadProtos)
+            .padding(4)
+            .width(Length::Fill),
+        button(text("Load").size(12)).on_press(Message::LoadProtos),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6);
+    let method = row![
+        pick_list(
+            grpc.services(),
+            grpc.service.clone(),
+            Message::GrpcServiceSelected
+        )
+        .placeholder("Service")
+        .width(Length::FillPortion(3)),
+        pick_list(
+            grpc.methods(),
+            grpc.method.clone(),
+            Message::GrpcMethodSelected
+        )
+        .placeholder("Method")
+        .width(Length::FillPortion(2)),
+        button(text("Template").size(12))
+            .on_press_maybe(grpc.method.as_ref().map(|_| Message::GrpcTemplate)),
+    ]
+    .align_y(Alignment::Center)
+    .spacing(6);
+    column![text("Proto files"), protos, method]
+        .spacing(6)
+        .into()
 }
 
 fn shortcuts_panel() -> Element<'static, Message> {
@@ -290,6 +1145,10 @@ fn shortcuts_panel() -> Element<'static, Message> {
         text("? - Toggle shortcuts help").size(14),
         text("Ctrl/Cmd+S - Save request").size(14),
         text("Ctrl/Cmd+Enter - Send request").size(14),
+        text("Ctrl/Cmd+Shift+V - Import curl command from clipboard").size(14),
+        text("Esc - Close menus, then clear the sidebar filter").size(14),
+        text("Shift+click - Select a range of requests in edit mode").size(14),
+        text("Ctrl/Cmd+A - Select every shown request in edit mode").size(14),
     ]
     .spacing(2);
 
@@ -0,0 +1,62 @@
+use iced::Task;
+
+use super::view::ResponseTab;
+use super::{Message, Zagel};
+use crate::state::ResponseViewSettings;
+
+/// The body with spaces drawn as `·` and tabs as `→`; line breaks are kept.
+pub fn mark_whitespace(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ' ' => '·',
+            '\t' => '→',
+            other => other,
+        })
+        .collect()
+}
+
+impl Zagel {
+    /// Markers only make sense for the body; hex dumps are spaced on purpose.
+    pub(super) fn marks_whitespace(&self) -> bool {
+        self.state.response_view.show_whitespace && self.response_tab == ResponseTab::Body
+    }
+
+    pub(super) fn handle_response_wrap_toggled(&mut self) -> Task<Message> {
+        self.state.response_view.wrap = !self.state.response_view.wrap;
+        self.persist_state();
+        Task::none()
+    }
+
+    pub(super) fn handle_response_whitespace_toggled(&mut self) -> Task<Message> {
+        self.state.response_view.show_whitespace = !self.state.response_view.show_whitespace;
+        self.persist_state();
+        self.load_response_page();
+        Task::none()
+    }
+
+    pub(super) fn handle_response_font_size_changed(&mut self, size: u16) -> Task<Message> {
+        self.state.response_view.font_size = size.clamp(
+            ResponseViewSettings::MIN_FONT_SIZE,
+            ResponseViewSettings::MAX_FONT_SIZE,
+        );
+        self.persist_state();
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mark_whitespace;
+    use crate::app::pager::Pager;
+
+    #[test]
+    fn whitespace_markers_keep_search_positions_aligned() {
+        assert_eq!(mark_whitespace("a b\tc\nd"), "a·b→c\nd");
+
+        let pager = Pager::new("x\n\ta b".to_string());
+        assert_eq!(pager.display_position(5, false), (1, 3));
+        let (line, column) = pager.display_position(5, true);
+        assert_eq!((line, column), (1, "→a·".len()));
+        assert!(mark_whitespace("\ta b")[column..].starts_with('b'));
+    }
+}
@@ -0,0 +1,375 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::parser::{FILE_SCAN_MAX_DEPTH, is_env_file, is_env_json, is_private_env_json};
+use crate::state::AppState;
+
+/// Format version of backup archives written by this build.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Every .http and env file of the workspace plus the app state, in one JSON
+/// document so it can be inspected or diffed by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    /// Unix timestamp in seconds.
+    pub created: u64,
+    /// Whether env values, proxy passwords and sessions were kept.
+    pub secrets: bool,
+    pub files: Vec<BackupFile>,
+    /// `state.toml` as it was when the backup was taken.
+    pub state: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Snapshot of the files under the state's project and global env roots.
+/// Without secrets, env files keep their keys but not their values.
+pub fn create(state: &AppState, secrets: bool) -> Result<Backup, String> {
+    let mut files = Vec::new();
+    for root in &state.project_roots {
+        collect(root, true, secrets, &mut files)?;
+    }
+    for root in &state.global_env_roots {
+        collect(root, false, secrets, &mut files)?;
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files.dedup_by(|a, b| a.path == b.path);
+
+    let mut state = state.clone();
+    if !secrets {
+        redact_state(&mut state);
+    }
+    let state = toml::to_string(&state).map_err(|err| format!("Cannot encode state: {err}"))?;
+    let created = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    Ok(Backup {
+        version: BACKUP_VERSION,
+        created,
+        secrets,
+        files,
+        state,
+    })
+}
+
+fn collect(
+    root: &Path,
+    http: bool,
+    secrets: bool,
+    files: &mut Vec<BackupFile>,
+) -> Result<(), String> {
+    for entry in WalkDir::new(root)
+        .follow_links(true)
+        .max_depth(FILE_SCAN_MAX_DEPTH)
+        .into_iter()
+        .flatten()
+    {
+        let path = entry.path();
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let is_http = http && path.extension().is_some_and(|ext| ext == "http");
+        if !is_http && !is_env_file(path) && !is_env_json(path) {
+            continue;
+        }
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+        let contents = if secrets {
+            contents
+        } else if is_env_file(path) {
+            redact_env(&contents)
+        } else if is_private_env_json(path) {
+            let Some(redacted) = redact_env_json(&contents) else {
+                continue;
+            };
+            redacted
+        } else {
+            contents
+        };
+        files.push(BackupFile {
+            path: path.to_path_buf(),
+            contents,
+        });
+    }
+    Ok(())
+}
+
+fn redact_env(contents: &str) -> String {
+    contents
+        .lines()
+        .map(|line| match line.split_once('=') {
+            Some((key, _)) if !line.trim_start().starts_with('#') => format!("{key}="),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The private JSON env with every value blanked and its environment and
+/// variable names kept. `None` when it is not valid JSON.
+fn redact_env_json(contents: &str) -> Option<String> {
+    fn blank(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(fields) => fields.values_mut().for_each(blank),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(blank),
+            serde_json::Value::Null => {}
+            _ => *value = serde_json::Value::String(String::new()),
+        }
+    }
+    let mut value = serde_json::from_str(contents).ok()?;
+    blank(&mut value);
+    serde_json::to_string_pretty(&value).ok()
+}
+
+/// Env files whose values are only backed up with secrets.
+fn holds_secrets(path: &Path) -> bool {
+    is_env_file(path) || is_private_env_json(path)
+}
+
+fn redact_state(state: &mut AppState) {
+    for proxy in state
+        .proxy
+        .iter_mut()
+        .chain(state.environment_proxies.values_mut())
+    {
+        proxy.password = None;
+    }
+    state.sessions.clear();
+    state.active_session = None;
+    state.editor_token = None;
+}
+
+pub fn write(backup: &Backup, path: &Path) -> Result<(), String> {
+    let raw = serde_json::to_string_pretty(backup)
+        .map_err(|err| format!("Cannot encode backup: {err}"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| format!("Cannot create {}: {err}", dir.display()))?;
+    }
+    fs::write(path, raw).map_err(|err| format!("Cannot write {}: {err}", path.display()))
+}
+
+pub fn read(path: &Path) -> Result<Backup, String> {
+    let raw =
+        fs::read_to_string(path).map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+    let backup: Backup = serde_json::from_str(&raw)
+        .map_err(|err| format!("{} is not a zagel backup: {err}", path.display()))?;
+    if backup.version > BACKUP_VERSION {
+        return Err(format!(
+            "{} was written by a newer zagel (backup version {})",
+            path.display(),
+            backup.version
+        ));
+    }
+    Ok(backup)
+}
+
+/// Whether restoring may write `path`: an .http file under one of the
+/// state's project roots, or an env file under a project or global env root.
+fn is_allowed(path: &Path, state: &AppState) -> bool {
+    if !path.is_absolute()
+        || path
+            .components()
+            .any(|component| component == Component::ParentDir)
+    {
+        return false;
+    }
+    let under = |roots: &[PathBuf]| roots.iter().any(|root| path.starts_with(root));
+    if is_env_file(path) || is_env_json(path) {
+        under(&state.project_roots) || under(&state.global_env_roots)
+    } else {
+        path.extension().is_some_and(|ext| ext == "http") && under(&state.project_roots)
+    }
+}
+
+impl Backup {
+    /// The app state saved in the backup.
+    pub fn state(&self) -> Result<AppState, String> {
+        AppState::parse(&self.state)
+            .map(|parsed| parsed.state)
+            .map_err(|err| format!("The backed up state is invalid: {}", err.message()))
+    }
+
+    /// Files that restoring would write. A backup without secrets leaves
+    /// existing env files alone so their values are not blanked.
+    pub fn restorable<'a>(&'a self, state: &'a AppState) -> impl Iterator<Item = &'a BackupFile> {
+        self.files.iter().filter(move |file| {
+            is_allowed(&file.path, state)
+                && (self.secrets || !holds_secrets(&file.path) || !file.path.exists())
+        })
+    }
+
+    /// Files that are not .http or env files under the backed up folders.
+    /// Restoring never writes them.
+    pub fn refused<'a>(&'a self, state: &'a AppState) -> impl Iterator<Item = &'a BackupFile> {
+        self.files
+            .iter()
+            .filter(move |file| !is_allowed(&file.path, state))
+    }
+
+    /// Writes the files back to where they were taken from and returns the
+    /// number written.
+    pub fn restore(&self, state: &AppState) -> Result<usize, String> {
+        let mut written = 0;
+        for file in self.restorable(state) {
+            if let Some(dir) = file.path.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|err| format!("Cannot create {}: {err}", dir.display()))?;
+            }
+            fs::write(&file.path, &file.contents)
+                .map_err(|err| format!("Cannot write {}: {err}", file.path.display()))?;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{Backup, BackupFile, create, read, write};
+    use crate::state::{AppState, ProxySettings};
+
+    #[test]
+    fn restore_brings_back_files_and_keeps_existing_secrets() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("api");
+        fs::create_dir_all(project.join("nested")).unwrap();
+        fs::write(
+            project.join("nested/users.http"),
+            "GET https://api.test/users\n",
+        )
+        .unwrap();
+        fs::write(project.join(".env"), "# dev\nTOKEN=secret\n").unwrap();
+        fs::write(project.join("notes.txt"), "not backed up").unwrap();
+        let state = AppState {
+            project_roots: vec![project.clone()],
+            proxy: Some(ProxySettings {
+                url: "http://proxy.test".to_string(),
+                username: Some("me".to_string()),
+                password: Some("hunter2".to_string()),
+            }),
+            ..AppState::default()
+        };
+
+        let backup = create(&state, false).unwrap();
+        assert_eq!(backup.files.len(), 2);
+        assert!(!backup.state.contains("hunter2"));
+        let env = backup
+            .files
+            .iter()
+            .find(|file| file.path.ends_with(".env"))
+            .unwrap();
+        assert_eq!(env.contents, "# dev\nTOKEN=");
+
+        let archive = dir.path().join("backup.json");
+        write(&backup, &archive).unwrap();
+        fs::write(
+            project.join("nested/users.http"),
+            "GET https://api.test/changed\n",
+        )
+        .unwrap();
+        fs::write(project.join(".env"), "TOKEN=rotated\n").unwrap();
+
+        let backup = read(&archive).unwrap();
+        let restored = backup.state().unwrap();
+        assert_eq!(backup.restore(&restored).unwrap(), 1);
+        assert_eq!(restored.project_roots, vec![project.clone()]);
+        assert_eq!(
+            fs::read_to_string(project.join("nested/users.http")).unwrap(),
+            "GET https://api.test/users\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join(".env")).unwrap(),
+            "TOKEN=rotated\n"
+        );
+    }
+
+    #[test]
+    fn restore_refuses_files_outside_the_backed_up_folders() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("api");
+        fs::create_dir_all(&project).unwrap();
+        let state = AppState {
+            project_roots: vec![project.clone()],
+            ..AppState::default()
+        };
+        let outside = dir.path().join("bashrc");
+        let escaped = project.join("../escaped.http");
+        let inside = project.join("users.http");
+        let backup = Backup {
+            version: 1,
+            created: 0,
+            secrets: true,
+            files: [&outside, &escaped, &project.join("notes.txt"), &inside]
+                .into_iter()
+                .map(|path| BackupFile {
+                    path: path.clone(),
+                    contents: "GET https://api.test/users\n".to_string(),
+                })
+                .collect(),
+            state: toml::to_string(&state).unwrap(),
+        };
+
+        let restored = backup.state().unwrap();
+        assert_eq!(backup.refused(&restored).count(), 3);
+        assert_eq!(backup.restore(&restored).unwrap(), 1);
+        assert!(inside.exists());
+        assert!(!outside.exists());
+        assert!(!dir.path().join("escaped.http").exists());
+        assert!(!project.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn json_envs_round_trip_with_private_values_redacted() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("api");
+        fs::create_dir_all(&project).unwrap();
+        let public = project.join("http-client.env.json");
+        let private = project.join("http-client.private.env.json");
+        let public_json = r#"{"dev": {"host": "api.test"}}"#;
+        fs::write(&public, public_json).unwrap();
+        fs::write(&private, r#"{"dev": {"token": "secret", "port": 8443}}"#).unwrap();
+        let state = AppState {
+            project_roots: vec![project],
+            ..AppState::default()
+        };
+
+        let backup = create(&state, false).unwrap();
+        assert_eq!(backup.files.len(), 2);
+        let redacted = backup
+            .files
+            .iter()
+            .find(|file| file.path == private)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&redacted.contents).unwrap();
+        assert_eq!(value, serde_json::json!({"dev": {"token": "", "port": ""}}));
+
+        fs::remove_file(&public).unwrap();
+        fs::remove_file(&private).unwrap();
+        let restored = backup.state().unwrap();
+        assert_eq!(backup.restore(&restored).unwrap(), 2);
+        assert_eq!(fs::read_to_string(&public).unwrap(), public_json);
+        assert!(!fs::read_to_string(&private).unwrap().contains("secret"));
+
+        fs::write(&private, r#"{"dev": {"token": "secret"}}"#).unwrap();
+        let with_secrets = create(&state, true).unwrap();
+        fs::write(&private, "{}").unwrap();
+        with_secrets.restore(&restored).unwrap();
+        assert_eq!(
+            fs::read_to_string(&private).unwrap(),
+            r#"{"dev": {"token": "secret"}}"#
+        );
+    }
+}
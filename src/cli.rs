@@ -2,8 +2,13 @@ use std::ffi::OsString;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::launch::{AutomationOptions, LaunchOptions};
+use crate::launch::{
+    AutomationOptions, FmtOptions, LaunchOptions, LintOptions, ListOptions, OutputFormat,
+    RunOptions, RunReport, SendOptions, SendTarget,
+};
+use crate::model::{RequestKey, RequestRef};
 
 const DEFAULT_SCREENSHOT_DIR: &str = "artifacts/ui";
 
@@ -15,6 +20,26 @@ pub enum CliError {
     NonUtf8Flag,
     CurrentDirectory(io::Error),
     MissingAutomationScenario,
+    AutomationNotSupported(&'static str),
+    MissingSendTarget,
+    InvalidSendTarget(String),
+    InvalidOutputFormat(String),
+    MissingRunTarget,
+    InvalidReport(String),
+    MissingLintTarget,
+    MissingFmtTarget,
+    InvalidAutomationTimeout(String),
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Launch(LaunchOptions),
+    Doctor(LaunchOptions),
+    Send(SendOptions),
+    List(ListOptions),
+    Run(RunOptions),
+    Lint(LintOptions),
+    Fmt(FmtOptions),
 }
 
 impl Display for CliError {
@@ -30,6 +55,36 @@ impl Display for CliError {
             Self::MissingAutomationScenario => {
                 f.write_str("automation flags were provided without --automation <scenario.toml>")
             }
+            Self::AutomationNotSupported(command) => {
+                write!(f, "automation flags cannot be used with `zagel {command}`")
+            }
+            Self::MissingSendTarget => f.write_str(
+                "`zagel send` needs a request: pass `<file.http>#<index|name=…|id=…>` or `-` for stdin",
+            ),
+            Self::InvalidSendTarget(target) => write!(
+                f,
+                "invalid send target {target}: use `<file.http>#<index|name=…|id=…>` or `-` for stdin"
+            ),
+            Self::InvalidOutputFormat(format) => {
+                write!(f, "unknown output format {format}: use `text` or `json`")
+            }
+            Self::MissingRunTarget => {
+                f.write_str("`zagel run` needs an .http file or a directory of them")
+            }
+            Self::InvalidReport(report) => write!(
+                f,
+                "unsupported report {report}: use `json`, `<file>.json` or `<file>.xml` (JUnit)"
+            ),
+            Self::InvalidAutomationTimeout(value) => write!(
+                f,
+                "invalid --automation-timeout {value}: use a whole number of seconds above zero"
+            ),
+            Self::MissingLintTarget => {
+                f.write_str("`zagel lint` needs an .http file or a directory of them")
+            }
+            Self::MissingFmtTarget => {
+                f.write_str("`zagel fmt` needs an .http file or a directory of them")
+            }
         }
     }
 }
@@ -37,7 +92,21 @@ impl Display for CliError {
 impl std::error::Error for CliError {}
 
 pub const fn usage() -> &'static str {
-    "Usage: zagel [OPTIONS]\n\n\
+    "Usage: zagel [OPTIONS]\n\
+       zagel doctor [--state-file <path>] [--project-root <path>] [--global-env-root <path>]\n\
+       zagel send <file.http>#<index|name=…|id=…>|- [--env <name>] [--include] [--state-file <path>] [--project-root <path>] [--global-env-root <path>]\n\
+       zagel run <dir-or-file> [--env <name>] [--tag <tag>] [--report junit.xml|json|<file>.json] [--state-file <path>] [--project-root <path>] [--global-env-root <path>]\n\
+       zagel lint <dir-or-file> [--env <name>] [--state-file <path>] [--project-root <path>] [--global-env-root <path>]\n\
+       zagel fmt <dir-or-file> [--check]\n\
+       zagel list [--output text|json] [--state-file <path>] [--project-root <path>] [--global-env-root <path>]\n\n\
+Commands:\n\
+  doctor                       Check roots, state file, watcher and network, then exit\n\
+  send <file.http>#<index>     Send one request (or #name=<name>, #id=<id>) of a file and print the response body\n\
+  send -                       Send the .http request block read from stdin instead\n\
+  run <dir-or-file>            Send every request and check its assertions; exit 1 if any fail\n\
+  lint <dir-or-file>           Report malformed requests, unknown directives and unresolved variables\n\
+  fmt <dir-or-file>            Rewrite .http files in canonical form; --check only lists them\n\
+  list                         Print the parsed files, requests and environments, then exit\n\n\
 Options:\n\
   --state-file <path>          Override persisted state path\n\
   --project-root <path>        Add project root override (repeatable)\n\
@@ -45,12 +114,220 @@ Options:\n\
   --automation <path>          Run automation scenario from TOML file\n\
   --screenshot-dir <path>      Output directory for automation screenshots\n\
   --automation-state-out <path> Write full automation state snapshot (JSON)\n\
+  --automation-timeout <secs>  Fail the scenario if it runs longer than this\n\
   --exit-when-done             Exit app when automation scenario completes\n\
   -h, --help                   Show this help\n"
 }
 
-pub fn parse_env() -> Result<LaunchOptions, CliError> {
-    parse_args(std::env::args_os().skip(1))
+pub fn parse_env() -> Result<Command, CliError> {
+    parse_command(std::env::args_os().skip(1))
+}
+
+pub fn parse_command(args: impl IntoIterator<Item = OsString>) -> Result<Command, CliError> {
+    let mut iter = args.into_iter().peekable();
+    if iter.peek().and_then(|arg| arg.to_str()) == Some("doctor") {
+        iter.next();
+        let options = parse_args(iter)?;
+        if options.automation.is_some() {
+            return Err(CliError::AutomationNotSupported("doctor"));
+        }
+        return Ok(Command::Doctor(options));
+    }
+    if iter.peek().and_then(|arg| arg.to_str()) == Some("send") {
+        iter.next();
+        let target = match iter.next() {
+            None => return Err(CliError::MissingSendTarget),
+            Some(raw) => parse_send_target(raw)?,
+        };
+        let mut env = None;
+        let mut include = false;
+        let mut rest = Vec::new();
+        while let Some(arg) = iter.next() {
+            match arg.to_str() {
+                Some("--env") => {
+                    let name = iter.next().and_then(|value| value.into_string().ok());
+                    env = Some(name.ok_or(CliError::MissingValue("--env"))?);
+                }
+                Some("-i" | "--include") => include = true,
+                _ => rest.push(arg),
+            }
+        }
+        let launch = parse_args(rest)?;
+        if launch.automation.is_some() {
+            return Err(CliError::AutomationNotSupported("send"));
+        }
+        return Ok(Command::Send(SendOptions {
+            launch,
+            target,
+            env,
+            include,
+        }));
+    }
+    if iter.peek().and_then(|arg| arg.to_str()) == Some("run") {
+        iter.next();
+        return parse_run(iter);
+    }
+    if iter.peek().and_then(|arg| arg.to_str()) == Some("lint") {
+        iter.next();
+        return parse_lint(iter);
+    }
+    if iter.peek().and_then(|arg| arg.to_str()) == Some("fmt") {
+        iter.next();
+        return parse_fmt(iter);
+    }
+    if iter.peek().and_then(|arg| arg.to_str()) == Some("list") {
+        iter.next();
+        let mut output = OutputFormat::default();
+        let mut rest = Vec::new();
+        while let Some(arg) = iter.next() {
+            if arg.to_str() != Some("--output") {
+                rest.push(arg);
+                continue;
+            }
+            output = match iter.next().as_ref().and_then(|value| value.to_str()) {
+                Some("text") => OutputFormat::Text,
+                Some("json") => OutputFormat::Json,
+                Some(format) => return Err(CliError::InvalidOutputFormat(format.to_string())),
+                None => return Err(CliError::MissingValue("--output")),
+            };
+        }
+        let launch = parse_args(rest)?;
+        if launch.automation.is_some() {
+            return Err(CliError::AutomationNotSupported("list"));
+        }
+        return Ok(Command::List(ListOptions { launch, output }));
+    }
+    parse_args(iter).map(Command::Launch)
+}
+
+fn parse_run(mut iter: impl Iterator<Item = OsString>) -> Result<Command, CliError> {
+    let target = match iter.next() {
+        Some(raw) if !raw.to_str().is_some_and(|arg| arg.starts_with('-')) => resolve_path(raw)?,
+        _ => return Err(CliError::MissingRunTarget),
+    };
+    let mut env = None;
+    let mut report = None;
+    let mut tag = None;
+    let mut rest = Vec::new();
+    while let Some(arg) = iter.next() {
+        match arg.to_str() {
+            Some("--env") => {
+                let name = iter.next().and_then(|value| value.into_string().ok());
+                env = Some(name.ok_or(CliError::MissingValue("--env"))?);
+            }
+            Some("--tag") => {
+                let name = iter.next().and_then(|value| value.into_string().ok());
+                tag = Some(name.ok_or(CliError::MissingValue("--tag"))?);
+            }
+            Some("--report") => {
+                let value = iter.next().ok_or(CliError::MissingValue("--report"))?;
+                report = Some(parse_report(value)?);
+            }
+            _ => rest.push(arg),
+        }
+    }
+    let launch = parse_args(rest)?;
+    if launch.automation.is_some() {
+        return Err(CliError::AutomationNotSupported("run"));
+    }
+    Ok(Command::Run(RunOptions {
+        launch,
+        target,
+        env,
+        report,
+        tag,
+    }))
+}
+
+fn parse_lint(mut iter: impl Iterator<Item = OsString>) -> Result<Command, CliError> {
+    let target = match iter.next() {
+        Some(raw) if !raw.to_str().is_some_and(|arg| arg.starts_with('-')) => resolve_path(raw)?,
+        _ => return Err(CliError::MissingLintTarget),
+    };
+    let mut env = None;
+    let mut rest = Vec::new();
+    while let Some(arg) = iter.next() {
+        if arg.to_str() == Some("--env") {
+            let name = iter.next().and_then(|value| value.into_string().ok());
+            env = Some(name.ok_or(CliError::MissingValue("--env"))?);
+        } else {
+            rest.push(arg);
+        }
+    }
+    let launch = parse_args(rest)?;
+    if launch.automation.is_some() {
+        return Err(CliError::AutomationNotSupported("lint"));
+    }
+    Ok(Command::Lint(LintOptions {
+        launch,
+        target,
+        env,
+    }))
+}
+
+fn parse_fmt(mut iter: impl Iterator<Item = OsString>) -> Result<Command, CliError> {
+    let target = match iter.next() {
+        Some(raw) if !raw.to_str().is_some_and(|arg| arg.starts_with('-')) => resolve_path(raw)?,
+        _ => return Err(CliError::MissingFmtTarget),
+    };
+    let mut check = false;
+    let mut rest = Vec::new();
+    for arg in iter {
+        if arg.to_str() == Some("--check") {
+            check = true;
+        } else {
+            rest.push(arg);
+        }
+    }
+    let launch = parse_args(rest)?;
+    if launch.automation.is_some() {
+        return Err(CliError::AutomationNotSupported("fmt"));
+    }
+    Ok(Command::Fmt(FmtOptions {
+        launch,
+        target,
+        check,
+    }))
+}
+
+/// `-` for stdin, otherwise `<path>[#<index>|#name=<name>|#id=<id>]` with the
+/// first request as the default.
+fn parse_send_target(raw: OsString) -> Result<SendTarget, CliError> {
+    let raw = raw.into_string().map_err(|_| CliError::NonUtf8Flag)?;
+    if raw == "-" {
+        return Ok(SendTarget::Stdin);
+    }
+    if raw.starts_with('-') {
+        return Err(CliError::InvalidSendTarget(raw));
+    }
+    let request = if raw.contains('#') {
+        RequestRef::parse(&raw)
+            .filter(|request| !request.path.as_os_str().is_empty())
+            .ok_or_else(|| CliError::InvalidSendTarget(raw.clone()))?
+    } else {
+        RequestRef {
+            path: PathBuf::from(&raw),
+            key: RequestKey::Index(0),
+        }
+    };
+    Ok(SendTarget::File(RequestRef {
+        path: resolve_path(request.path.into_os_string())?,
+        key: request.key,
+    }))
+}
+
+fn parse_report(raw: OsString) -> Result<RunReport, CliError> {
+    let value = raw.into_string().map_err(|_| CliError::NonUtf8Flag)?;
+    let extension = std::path::Path::new(&value)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match (value.as_str(), extension.as_deref()) {
+        ("json", _) => Ok(RunReport::Json(None)),
+        (_, Some("json")) => Ok(RunReport::Json(Some(resolve_path(value.into())?))),
+        (_, Some("xml")) => Ok(RunReport::Junit(resolve_path(value.into())?)),
+        _ => Err(CliError::InvalidReport(value)),
+    }
 }
 
 fn resolve_path(raw: OsString) -> Result<PathBuf, CliError> {
@@ -81,6 +358,7 @@ pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<LaunchOpti
     let mut screenshot_dir: Option<PathBuf> = None;
     let mut state_output_path: Option<PathBuf> = None;
     let mut exit_when_done = false;
+    let mut timeout: Option<Duration> = None;
 
     let mut iter = args.into_iter();
     while let Some(raw_flag) = iter.next() {
@@ -112,6 +390,18 @@ pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<LaunchOpti
             "--automation-state-out" => {
                 state_output_path = Some(next_path(&mut iter, "--automation-state-out")?);
             }
+            "--automation-timeout" => {
+                let raw = iter
+                    .next()
+                    .ok_or(CliError::MissingValue("--automation-timeout"))?;
+                let raw = raw.to_string_lossy();
+                let seconds = raw
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|seconds| *seconds > 0)
+                    .ok_or_else(|| CliError::InvalidAutomationTimeout(raw.to_string()))?;
+                timeout = Some(Duration::from_secs(seconds));
+            }
             "--exit-when-done" => {
                 exit_when_done = true;
             }
@@ -124,6 +414,7 @@ pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<LaunchOpti
     if automation_scenario.is_some()
         || screenshot_dir.is_some()
         || state_output_path.is_some()
+        || timeout.is_some()
         || exit_when_done
     {
         let scenario_path = automation_scenario.ok_or(CliError::MissingAutomationScenario)?;
@@ -136,6 +427,7 @@ pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<LaunchOpti
             screenshot_dir,
             state_output_path,
             exit_when_done,
+            timeout,
         });
     }
 
@@ -145,8 +437,11 @@ pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<LaunchOpti
 #[cfg(test)]
 mod tests {
     use std::ffi::OsString;
+    use std::path::PathBuf;
 
-    use super::{CliError, parse_args};
+    use super::{CliError, Command, parse_args, parse_command};
+    use crate::launch::{OutputFormat, RunReport, SendTarget};
+    use crate::model::{RequestKey, RequestRef};
 
     #[test]
     fn parses_automation_state_output_flag() {
@@ -155,6 +450,8 @@ mod tests {
             OsString::from("./tests/ui/scenarios/smoke.toml"),
             OsString::from("--automation-state-out"),
             OsString::from("./artifacts/ui/state.json"),
+            OsString::from("--automation-timeout"),
+            OsString::from("90"),
             OsString::from("--exit-when-done"),
         ];
 
@@ -172,6 +469,37 @@ mod tests {
             Some("state.json")
         );
         assert!(automation.exit_when_done);
+        assert_eq!(automation.timeout, Some(std::time::Duration::from_secs(90)));
+
+        let err =
+            parse_args(["--automation", "s.toml", "--automation-timeout", "0"].map(OsString::from))
+                .expect_err("zero timeout should fail");
+        assert!(matches!(err, CliError::InvalidAutomationTimeout(_)));
+    }
+
+    #[test]
+    fn lint_takes_a_target_and_an_environment() {
+        let args = ["lint", "requests", "--env", "dev", "--project-root", "."].map(OsString::from);
+        let Command::Lint(options) = parse_command(args).expect("parse lint") else {
+            panic!("expected lint command");
+        };
+        assert!(options.target.ends_with("requests"));
+        assert_eq!(options.env.as_deref(), Some("dev"));
+        assert_eq!(options.launch.project_roots.len(), 1);
+
+        let err = parse_command(["lint", "--env", "dev"].map(OsString::from))
+            .expect_err("missing target should fail");
+        assert!(matches!(err, CliError::MissingLintTarget));
+    }
+
+    #[test]
+    fn fmt_takes_a_target_and_check_flag() {
+        let args = ["fmt", "requests", "--check"].map(OsString::from);
+        let Command::Fmt(options) = parse_command(args).expect("parse fmt") else {
+            panic!("expected fmt command");
+        };
+        assert!(options.target.ends_with("requests"));
+        assert!(options.check);
     }
 
     #[test]
@@ -196,4 +524,158 @@ mod tests {
         let err = parse_args(args).expect_err("missing value should fail");
         assert!(matches!(err, CliError::MissingValue("--state-file")));
     }
+
+    #[test]
+    fn doctor_subcommand_accepts_root_overrides() {
+        let args = vec![
+            OsString::from("doctor"),
+            OsString::from("--project-root"),
+            OsString::from("/tmp/project"),
+        ];
+
+        let Command::Doctor(options) = parse_command(args).expect("parse doctor") else {
+            panic!("expected doctor command");
+        };
+        assert_eq!(options.project_roots.len(), 1);
+    }
+
+    #[test]
+    fn doctor_subcommand_rejects_automation_flags() {
+        let args = vec![
+            OsString::from("doctor"),
+            OsString::from("--automation"),
+            OsString::from("scenario.toml"),
+        ];
+
+        let err = parse_command(args).expect_err("automation with doctor should fail");
+        assert!(matches!(err, CliError::AutomationNotSupported("doctor")));
+    }
+
+    #[test]
+    fn send_subcommand_reads_stdin_when_given_a_dash() {
+        let args = vec![
+            OsString::from("send"),
+            OsString::from("-"),
+            OsString::from("--state-file"),
+            OsString::from("/tmp/state.toml"),
+        ];
+
+        let Command::Send(options) = parse_command(args).expect("parse send") else {
+            panic!("expected send command");
+        };
+        assert_eq!(options.target, SendTarget::Stdin);
+        assert!(options.launch.state_file.is_some());
+
+        let err = parse_command(vec![OsString::from("send")]).expect_err("missing target");
+        assert!(matches!(err, CliError::MissingSendTarget));
+    }
+
+    #[test]
+    fn send_subcommand_targets_a_request_in_a_file() {
+        let args = vec![
+            OsString::from("send"),
+            OsString::from("/tmp/api.http#2"),
+            OsString::from("--env"),
+            OsString::from("staging"),
+            OsString::from("-i"),
+        ];
+
+        let Command::Send(options) = parse_command(args).expect("parse send") else {
+            panic!("expected send command");
+        };
+        assert_eq!(
+            options.target,
+            SendTarget::File(RequestRef {
+                path: PathBuf::from("/tmp/api.http"),
+                key: RequestKey::Index(2),
+            })
+        );
+        assert_eq!(options.env.as_deref(), Some("staging"));
+        assert!(options.include);
+
+        let err = parse_command(vec![OsString::from("send"), OsString::from("api.http#two")])
+            .expect_err("bad index");
+        assert!(matches!(err, CliError::InvalidSendTarget(_)));
+
+        let Command::Send(options) =
+            parse_command(["send", "/tmp/api.http#name=login"].map(OsString::from))
+                .expect("parse named send")
+        else {
+            panic!("expected send command");
+        };
+        assert_eq!(
+            options.target,
+            SendTarget::File(RequestRef {
+                path: PathBuf::from("/tmp/api.http"),
+                key: RequestKey::Name("login".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn run_subcommand_parses_env_and_report() {
+        let args = vec![
+            OsString::from("run"),
+            OsString::from("/tmp/requests"),
+            OsString::from("--report"),
+            OsString::from("/tmp/junit.xml"),
+            OsString::from("--env"),
+            OsString::from("ci"),
+        ];
+
+        let Command::Run(options) = parse_command(args).expect("parse run") else {
+            panic!("expected run command");
+        };
+        assert_eq!(options.target, PathBuf::from("/tmp/requests"));
+        assert_eq!(options.env.as_deref(), Some("ci"));
+        assert_eq!(
+            options.report,
+            Some(RunReport::Junit(PathBuf::from("/tmp/junit.xml")))
+        );
+
+        let args = vec![
+            OsString::from("run"),
+            OsString::from("/tmp/requests"),
+            OsString::from("--report"),
+            OsString::from("json"),
+        ];
+        let Command::Run(options) = parse_command(args).expect("parse run") else {
+            panic!("expected run command");
+        };
+        assert_eq!(options.report, Some(RunReport::Json(None)));
+
+        let args = vec![
+            OsString::from("run"),
+            OsString::from("/tmp/requests"),
+            OsString::from("--report"),
+            OsString::from("report.html"),
+        ];
+        let err = parse_command(args).expect_err("unsupported report");
+        assert!(matches!(err, CliError::InvalidReport(_)));
+    }
+
+    #[test]
+    fn list_subcommand_parses_output_format() {
+        let args = vec![
+            OsString::from("list"),
+            OsString::from("--project-root"),
+            OsString::from("/tmp/project"),
+            OsString::from("--output"),
+            OsString::from("json"),
+        ];
+
+        let Command::List(options) = parse_command(args).expect("parse list") else {
+            panic!("expected list command");
+        };
+        assert_eq!(options.output, OutputFormat::Json);
+        assert_eq!(options.launch.project_roots.len(), 1);
+
+        let err = parse_command(vec![
+            OsString::from("list"),
+            OsString::from("--output"),
+            OsString::from("yaml"),
+        ])
+        .expect_err("unknown format");
+        assert!(matches!(err, CliError::InvalidOutputFormat(_)));
+    }
 }
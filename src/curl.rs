@@ -0,0 +1,300 @@
+use std::fmt::Write as _;
+
+use base64::{Engine, engine::general_purpose};
+
+use crate::model::{HttpVersion, Method, RequestDraft};
+
+/// Boundary of multipart bodies built from `--form` fields.
+pub const FORM_BOUNDARY: &str = "zagel-form-boundary";
+
+/// Quotes a shell word with single quotes, which the shell never expands.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// One `curl` command reproducing the request, continued over lines with
+/// `\` like curl snippets in docs.
+pub fn to_curl(draft: &RequestDraft) -> String {
+    let mut command = String::from("curl");
+    if draft.method != Method::Get || !draft.body.is_empty() {
+        let _ = write!(command, " -X {}", draft.method);
+    }
+    match draft.http_version {
+        HttpVersion::Auto => {}
+        HttpVersion::Http10 => command.push_str(" --http1.0"),
+        HttpVersion::Http11 => command.push_str(" --http1.1"),
+        HttpVersion::Http2 => command.push_str(" --http2"),
+        HttpVersion::Http3 => command.push_str(" --http3"),
+    }
+    let _ = write!(command, " {}", quote(draft.url.trim()));
+    for header in draft.headers.lines().filter(|line| !line.trim().is_empty()) {
+        let _ = write!(command, " \\\n  -H {}", quote(header.trim()));
+    }
+    if !draft.body.is_empty() {
+        let _ = write!(command, " \\\n  --data-raw {}", quote(&draft.body));
+    }
+    command
+}
+
+/// Every request of a file as a shell script, each command under a comment
+/// with the request title.
+pub fn to_curl_script(drafts: &[RequestDraft]) -> String {
+    let mut script = String::from("#!/bin/sh\n");
+    for draft in drafts {
+        let _ = write!(script, "\n# {}\n{}\n", draft.title, to_curl(draft));
+    }
+    script
+}
+
+/// Splits a command line into words the way a POSIX shell would, without
+/// expanding anything: quotes group, backslashes escape and a backslash
+/// before a newline continues the line.
+fn split_words(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\n') | None => {}
+                Some(escaped) => word.get_or_insert_default().push(escaped),
+            },
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Builds a request from a curl command line as found in docs and bug
+/// reports. Options that only change how curl itself behaves are ignored.
+pub fn parse_curl(command: &str) -> Result<RequestDraft, String> {
+    let mut words = split_words(command.trim())?.into_iter();
+    if words.next().as_deref() != Some("curl") {
+        return Err("Not a curl command".to_string());
+    }
+    let mut draft = RequestDraft::default();
+    let mut method = None;
+    let mut url = None;
+    let mut headers = Vec::new();
+    let mut data = Vec::new();
+    let mut form = Vec::new();
+    let mut get = false;
+    while let Some(word) = words.next() {
+        let (flag, inline) = match word.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ if word.len() > 2 && word.starts_with('-') && !word.starts_with("--") => {
+                (word[..2].to_string(), Some(word[2..].to_string()))
+            }
+            _ => (word.clone(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| words.next())
+                .ok_or_else(|| format!("{flag} needs a value"))
+        };
+        match flag.as_str() {
+            "-X" | "--request" => {
+                let name = value()?;
+                method =
+                    Some(Method::parse(&name).ok_or_else(|| format!("Invalid method {name}"))?);
+            }
+            "-H" | "--header" => headers.push(value()?),
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii"
+            | "--data-urlencode" | "--json" => {
+                if flag == "--json" {
+                    headers.push("Content-Type: application/json".to_string());
+                    headers.push("Accept: application/json".to_string());
+                }
+                data.push(value()?);
+            }
+            "-F" | "--form" => form.push(value()?),
+            "-u" | "--user" => {
+                let credentials = general_purpose::STANDARD.encode(value()?);
+                headers.push(format!("Authorization: Basic {credentials}"));
+            }
+            "-A" | "--user-agent" => headers.push(format!("User-Agent: {}", value()?)),
+            "-b" | "--cookie" => headers.push(format!("Cookie: {}", value()?)),
+            "-e" | "--referer" => headers.push(format!("Referer: {}", value()?)),
+            "-I" | "--head" => method = Some(Method::Head),
+            "-G" | "--get" => get = true,
+            "--url" => url = Some(value()?),
+            "--http1.0" => draft.http_version = HttpVersion::Http10,
+            "--http1.1" => draft.http_version = HttpVersion::Http11,
+            "--http2" => draft.http_version = HttpVersion::Http2,
+            "--http3" => draft.http_version = HttpVersion::Http3,
+            "-o" | "--output" | "-m" | "--max-time" | "--connect-timeout" | "-x" | "--proxy"
+            | "-w" | "--write-out" | "--retry" | "--cacert" | "-E" | "--cert" | "--key" => {
+                value()?;
+            }
+            _ if flag.starts_with('-') && flag.len() > 1 => {}
+            _ => url = Some(word),
+        }
+    }
+    draft.url = url.ok_or_else(|| "The command has no URL".to_string())?;
+
+    if !form.is_empty() {
+        headers.push(format!(
+            "Content-Type: multipart/form-data; boundary={FORM_BOUNDARY}"
+        ));
+        draft.body = form_body(&form)?;
+    } else if get && !data.is_empty() {
+        let separator = if draft.url.contains('?') { '&' } else { '?' };
+        draft.url = format!("{}{separator}{}", draft.url, data.join("&"));
+    } else if !data.is_empty() {
+        if !headers
+            .iter()
+            .any(|header| is_header(header, "content-type"))
+        {
+            headers.push("Content-Type: application/x-www-form-urlencoded".to_string());
+        }
+        draft.body = data.join("&");
+    }
+    let has_body = !draft.body.is_empty();
+    draft.method = method.unwrap_or(if has_body { Method::Post } else { Method::Get });
+    draft.headers = headers.join("\n");
+    draft.title = format!("{} {}", draft.method, draft.url);
+    Ok(draft)
+}
+
+fn is_header(header: &str, name: &str) -> bool {
+    header
+        .split_once(':')
+        .is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+}
+
+/// Multipart body for `name=value` fields; file uploads cannot be
+/// represented in a .http request.
+pub fn form_body(fields: &[String]) -> Result<String, String> {
+    let mut body = String::new();
+    for field in fields {
+        let (name, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("Form field {field} has no value"))?;
+        if value.starts_with('@') || value.starts_with('<') {
+            return Err(format!(
+                "Form field {name} uploads a file, which is not supported"
+            ));
+        }
+        let _ = write!(
+            body,
+            "--{FORM_BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+        );
+    }
+    let _ = write!(body, "--{FORM_BOUNDARY}--\r\n");
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_curl, to_curl};
+    use crate::model::{HttpVersion, Method, RequestDraft};
+
+    #[test]
+    fn commands_quote_headers_and_body() {
+        let draft = RequestDraft {
+            method: Method::Post,
+            url: "https://example.com/users?q=a b".to_string(),
+            headers: "Content-Type: application/json\n\nX-Note: it's".to_string(),
+            body: "{\"name\":\"O'Neil\"}".to_string(),
+            http_version: HttpVersion::Http2,
+            ..RequestDraft::default()
+        };
+        assert_eq!(
+            to_curl(&draft),
+            "curl -X POST --http2 'https://example.com/users?q=a b' \\\n  -H 'Content-Type: application/json' \\\n  -H 'X-Note: it'\\''s' \\\n  --data-raw '{\"name\":\"O'\\''Neil\"}'"
+        );
+        assert_eq!(
+            to_curl(&RequestDraft::default()),
+            "curl 'https://example.com'"
+        );
+    }
+
+    #[test]
+    fn pasted_commands_become_drafts() {
+        let draft = parse_curl(
+            "curl -X PUT 'https://api.test/users/1' \\\n  -H \"Accept: application/json\" \\\n  -u admin:secret --data-raw '{\"name\":\"O'\\''Neil\"}' -s",
+        )
+        .unwrap();
+        assert_eq!(draft.method, Method::Put);
+        assert_eq!(draft.url, "https://api.test/users/1");
+        assert_eq!(
+            draft.headers,
+            "Accept: application/json\nAuthorization: Basic YWRtaW46c2VjcmV0\nContent-Type: application/x-www-form-urlencoded"
+        );
+        assert_eq!(draft.body, "{\"name\":\"O'Neil\"}");
+
+        let draft = parse_curl("curl https://api.test/search -G -d q=zagel -d page=2").unwrap();
+        assert_eq!(draft.method, Method::Get);
+        assert_eq!(draft.url, "https://api.test/search?q=zagel&page=2");
+
+        let draft = parse_curl("curl --form name=zagel https://api.test/upload").unwrap();
+        assert_eq!(draft.method, Method::Post);
+        assert!(draft.body.contains("name=\"name\"\r\n\r\nzagel\r\n"));
+        assert!(parse_curl("curl -F file=@a.png https://api.test").is_err());
+        assert!(parse_curl("wget https://api.test").is_err());
+        assert!(parse_curl("curl -H 'Accept: */*'").is_err());
+    }
+
+    #[test]
+    fn round_trips_through_to_curl() {
+        let draft = RequestDraft {
+            method: Method::Post,
+            url: "https://example.com/users".to_string(),
+            headers: "Content-Type: application/json\nX-Note: it's".to_string(),
+            body: "{\"a\": 1}".to_string(),
+            http_version: HttpVersion::Http2,
+            ..RequestDraft::default()
+        };
+        let parsed = parse_curl(&to_curl(&draft)).unwrap();
+        assert_eq!(
+            (
+                parsed.method,
+                parsed.url,
+                parsed.headers,
+                parsed.body,
+                parsed.http_version
+            ),
+            (
+                draft.method,
+                draft.url,
+                draft.headers,
+                draft.body,
+                draft.http_version
+            )
+        );
+    }
+}
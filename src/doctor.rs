@@ -0,0 +1,429 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Write as _};
+use std::fs;
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use reqwest::Url;
+
+use crate::launch::LaunchOptions;
+use crate::model::{Environment, apply_environment};
+use crate::parser::{FILE_SCAN_MAX_DEPTH, load_env_files, load_http_files};
+use crate::pathing::{GlobalEnvRoot, ProjectRoot, RootPathError};
+use crate::state::{self, AppState, STATE_VERSION};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_HOSTS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::Ok => "ok",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        };
+        f.pad(label)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    pub check: &'static str,
+    pub message: String,
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    fn ok(&mut self, check: &'static str, message: String) {
+        self.push(Severity::Ok, check, message, None);
+    }
+
+    fn warn(&mut self, check: &'static str, message: String, fix: impl Into<String>) {
+        self.push(Severity::Warn, check, message, Some(fix.into()));
+    }
+
+    fn fail(&mut self, check: &'static str, message: String, fix: impl Into<String>) {
+        self.push(Severity::Fail, check, message, Some(fix.into()));
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        check: &'static str,
+        message: String,
+        fix: Option<String>,
+    ) {
+        self.findings.push(Finding {
+            severity,
+            check,
+            message,
+            fix,
+        });
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|finding| finding.severity == Severity::Fail)
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for finding in &self.findings {
+            let _ = writeln!(
+                out,
+                "[{:>4}] {:<10} {}",
+                finding.severity, finding.check, finding.message
+            );
+            if let Some(fix) = &finding.fix {
+                let _ = writeln!(out, "       fix: {fix}");
+            }
+        }
+        let count = |severity| {
+            self.findings
+                .iter()
+                .filter(|finding| finding.severity == severity)
+                .count()
+        };
+        let _ = writeln!(
+            out,
+            "\n{} ok, {} warnings, {} failures",
+            count(Severity::Ok),
+            count(Severity::Warn),
+            count(Severity::Fail)
+        );
+        out
+    }
+}
+
+/// Prints the report and returns the process exit code.
+pub fn run(launch: &LaunchOptions) -> i32 {
+    let report = diagnose(launch);
+    print!("{}", report.render());
+    i32::from(report.has_failures())
+}
+
+pub fn diagnose(launch: &LaunchOptions) -> Report {
+    let mut report = Report::default();
+
+    let mut state = check_state_file(&mut report, state::state_file_path().as_deref());
+    if !launch.project_roots.is_empty() {
+        state.project_roots.clone_from(&launch.project_roots);
+    }
+    if !launch.global_env_roots.is_empty() {
+        state.global_env_roots.clone_from(&launch.global_env_roots);
+    }
+
+    let project_roots = check_roots(&mut report, "project", &state.project_roots, |path| {
+        ProjectRoot::from_stored(path).map(|root| root.to_path_buf())
+    });
+    if state.project_roots.is_empty() {
+        report.warn(
+            "project",
+            "no project roots configured".to_string(),
+            "add a folder in the sidebar or pass --project-root <path>",
+        );
+    }
+    let env_roots = check_roots(&mut report, "env root", &state.global_env_roots, |path| {
+        GlobalEnvRoot::from_stored(path).map(|root| root.to_path_buf())
+    });
+
+    let watched = project_roots
+        .iter()
+        .chain(&env_roots)
+        .cloned()
+        .collect::<Vec<_>>();
+    check_watcher(&mut report, &watched);
+
+    let environments = load_env_files(project_roots.clone(), env_roots, FILE_SCAN_MAX_DEPTH);
+    let environment = check_active_environment(&mut report, &state, &environments);
+    let urls = collect_urls(&project_roots, environment);
+    check_network(&mut report, &state, environment, &urls);
+
+    report
+}
+
+fn check_state_file(report: &mut Report, path: Option<&Path>) -> AppState {
+    let Some(path) = path else {
+        report.warn(
+            "config",
+            "no config directory available".to_string(),
+            "set HOME / XDG_CONFIG_HOME or pass --state-file <path>",
+        );
+        return AppState::default();
+    };
+
+    match fs::read_to_string(path) {
+        Ok(raw) => match AppState::parse(&raw) {
+            Ok(parsed) => {
+                let version = parsed.source_version;
+                match version.cmp(&STATE_VERSION) {
+                    Ordering::Less => report.warn(
+                        "config",
+                        format!("{} uses format v{version}", path.display()),
+                        format!(
+                            "start zagel once to migrate it to v{STATE_VERSION}; the original is kept as a .v{version}.bak file"
+                        ),
+                    ),
+                    Ordering::Greater => report.warn(
+                        "config",
+                        format!(
+                            "{} was written by a newer zagel (format v{version})",
+                            path.display()
+                        ),
+                        "upgrade zagel; settings added by the newer version will be dropped",
+                    ),
+                    Ordering::Equal => {
+                        report.ok("config", format!("{} is valid", path.display()));
+                    }
+                }
+                parsed.state
+            }
+            Err(err) => {
+                report.fail(
+                    "config",
+                    format!("{} is not valid TOML: {}", path.display(), err.message()),
+                    "fix the file by hand; on launch zagel moves it to a .invalid.bak file and starts with defaults",
+                );
+                AppState::default()
+            }
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            report.ok(
+                "config",
+                format!("{} does not exist yet; defaults apply", path.display()),
+            );
+            AppState::default()
+        }
+        Err(err) => {
+            report.fail(
+                "config",
+                format!("cannot read {}: {err}", path.display()),
+                "check the file permissions",
+            );
+            AppState::default()
+        }
+    }
+}
+
+fn check_roots(
+    report: &mut Report,
+    check: &'static str,
+    paths: &[PathBuf],
+    validate: impl Fn(PathBuf) -> Result<PathBuf, RootPathError>,
+) -> Vec<PathBuf> {
+    let mut valid = Vec::new();
+    for path in paths {
+        match validate(path.clone()) {
+            Ok(root) => {
+                report.ok(check, root.display().to_string());
+                valid.push(root);
+            }
+            Err(err) => report.fail(
+                check,
+                err.to_string(),
+                "restore the folder, or remove it from the sidebar so it is no longer saved",
+            ),
+        }
+    }
+    valid
+}
+
+fn check_watcher(report: &mut Report, roots: &[PathBuf]) {
+    let mut watcher = match RecommendedWatcher::new(|_| {}, Config::default()) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            report.fail(
+                "watcher",
+                format!("cannot create a file watcher: {err}"),
+                "file changes will not be picked up automatically; restart zagel after editing files",
+            );
+            return;
+        }
+    };
+
+    for root in roots {
+        if let Err(err) = watcher.watch(root, RecursiveMode::Recursive) {
+            report.fail(
+                "watcher",
+                format!("cannot watch {}: {err}", root.display()),
+                "on Linux raise the inotify limit, e.g. `sysctl fs.inotify.max_user_watches=524288`",
+            );
+            return;
+        }
+    }
+    report.ok("watcher", format!("watching {} root(s)", roots.len()));
+}
+
+fn check_active_environment<'a>(
+    report: &mut Report,
+    state: &AppState,
+    environments: &'a [Environment],
+) -> Option<&'a Environment> {
+    let name = state.active_environment.as_deref()?;
+    let found = environments.iter().find(|env| env.name == name);
+    if found.is_some() {
+        report.ok("env", format!("active environment {name}"));
+    } else {
+        report.warn(
+            "env",
+            format!("active environment {name} was not found"),
+            "pick an environment in the request builder, or restore its .env file",
+        );
+    }
+    found
+}
+
+fn collect_urls(project_roots: &[PathBuf], environment: Option<&Environment>) -> Vec<Url> {
+    let empty = std::collections::BTreeMap::new();
+    let vars = environment.map_or(&empty, |env| &env.vars);
+    let env_values = vars.values().cloned();
+    let request_urls = load_http_files(project_roots.to_vec(), FILE_SCAN_MAX_DEPTH)
+        .into_values()
+        .flat_map(|file| file.requests)
+        .map(|request| apply_environment(&request.url, vars));
+
+    env_values
+        .chain(request_urls)
+        .filter(|raw| !raw.contains("{{"))
+        .filter_map(|raw| Url::parse(raw.trim()).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .collect()
+}
+
+fn check_network(
+    report: &mut Report,
+    state: &AppState,
+    environment: Option<&Environment>,
+    urls: &[Url],
+) {
+    if let Some(proxy) = state.proxy_for(environment.map(|env| env.name.as_str())) {
+        match Url::parse(&proxy.url) {
+            Ok(url) => check_endpoints(report, &endpoints(std::iter::once(&url))),
+            Err(err) => report.fail(
+                "network",
+                format!("proxy url {} is invalid: {err}", proxy.url),
+                "fix the proxy url in the state file",
+            ),
+        }
+        return;
+    }
+
+    let endpoints = endpoints(urls.iter());
+    if endpoints.is_empty() {
+        report.ok(
+            "network",
+            "no resolvable request hosts to check".to_string(),
+        );
+        return;
+    }
+    check_endpoints(report, &endpoints);
+}
+
+fn endpoints<'a>(urls: impl Iterator<Item = &'a Url>) -> Vec<(String, u16)> {
+    urls.filter_map(|url| Some((url.host_str()?.to_string(), url.port_or_known_default()?)))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .take(MAX_HOSTS)
+        .collect()
+}
+
+fn check_endpoints(report: &mut Report, endpoints: &[(String, u16)]) {
+    for (host, port) in endpoints {
+        match probe(host, *port) {
+            Ok(()) => report.ok("network", format!("{host}:{port} is reachable")),
+            Err(err) => report.fail(
+                "network",
+                format!("{host}:{port} is unreachable: {err}"),
+                "check VPN/DNS and firewall rules, or configure a proxy",
+            ),
+        }
+    }
+}
+
+fn probe(host: &str, port: u16) -> Result<(), String> {
+    let addrs = (host, port)
+        .to_socket_addrs()
+        .map_err(|err| format!("DNS lookup failed ({err})"))?;
+    let mut last_error = "no addresses resolved".to_string();
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(_) => return Ok(()),
+            Err(err) => last_error = err.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use tempfile::tempdir;
+
+    use super::{Report, Severity, check_endpoints, check_roots, check_state_file};
+    use crate::pathing::ProjectRoot;
+
+    #[test]
+    fn invalid_state_file_fails_with_fix() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("state.toml");
+        std::fs::write(&path, "project_roots = [").expect("write state");
+
+        let mut report = Report::default();
+        check_state_file(&mut report, Some(&path));
+
+        assert!(report.has_failures());
+        assert!(report.render().contains("fix: "));
+    }
+
+    #[test]
+    fn missing_project_root_is_reported() {
+        let dir = tempdir().expect("tempdir");
+        let missing = dir.path().join("gone");
+
+        let mut report = Report::default();
+        let valid = check_roots(
+            &mut report,
+            "project",
+            &[dir.path().to_path_buf(), missing],
+            |path| ProjectRoot::from_stored(path).map(|root| root.to_path_buf()),
+        );
+
+        assert_eq!(valid.len(), 1);
+        let severities = report
+            .findings
+            .iter()
+            .map(|finding| finding.severity)
+            .collect::<Vec<_>>();
+        assert_eq!(severities, vec![Severity::Ok, Severity::Fail]);
+    }
+
+    #[test]
+    fn local_listener_is_reachable() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+
+        let mut report = Report::default();
+        check_endpoints(&mut report, &[("127.0.0.1".to_string(), port)]);
+
+        assert!(!report.has_failures());
+    }
+}
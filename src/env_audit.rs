@@ -0,0 +1,277 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Environment;
+use crate::state::state_file_path;
+
+const MAX_ENTRIES: usize = 500;
+const AUDIT_FILE: &str = "env-audit.jsonl";
+const MASK: &str = "••••••";
+/// Variable names containing one of these have their values masked.
+const SECRET_MARKERS: [&str; 7] = [
+    "secret",
+    "token",
+    "password",
+    "passwd",
+    "key",
+    "credential",
+    "auth",
+];
+
+/// One variable change; a missing `old` means added and a missing `new`
+/// means removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch.
+    pub changed_at: u64,
+    pub user: Option<String>,
+    /// Where the change came from, e.g. `file` for an edit on disk.
+    pub source: String,
+    pub environment: String,
+    pub variable: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl std::fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = |value: &Option<String>| value.as_deref().unwrap_or("(unset)").to_string();
+        write!(
+            f,
+            "{} {}: {} → {}",
+            self.environment,
+            self.variable,
+            value(&self.old),
+            value(&self.new)
+        )
+    }
+}
+
+impl AuditEntry {
+    /// `YYYY-MM-DD HH:MM` in UTC.
+    pub fn changed_at_label(&self) -> String {
+        i64::try_from(self.changed_at)
+            .ok()
+            .and_then(|secs| time::OffsetDateTime::from_unix_timestamp(secs).ok())
+            .map_or_else(String::new, |at| {
+                format!(
+                    "{}-{:02}-{:02} {:02}:{:02}",
+                    at.year(),
+                    u8::from(at.month()),
+                    at.day(),
+                    at.hour(),
+                    at.minute()
+                )
+            })
+    }
+}
+
+fn is_secret(variable: &str) -> bool {
+    let variable = variable.to_ascii_lowercase();
+    SECRET_MARKERS
+        .iter()
+        .any(|marker| variable.contains(marker))
+}
+
+fn masked(variable: &str, value: &str) -> String {
+    if is_secret(variable) {
+        MASK.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Variable changes between two loads of the same environments. Environments
+/// that appear or disappear (e.g. a project was added) are not changes to
+/// their variables and are skipped.
+pub fn diff(
+    before: &[Environment],
+    after: &[Environment],
+    source: &str,
+    now: SystemTime,
+) -> Vec<AuditEntry> {
+    let changed_at = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok();
+    let before = before
+        .iter()
+        .map(|env| (env.name.as_str(), &env.vars))
+        .collect::<BTreeMap<_, _>>();
+    let mut entries = Vec::new();
+    for env in after {
+        let Some(old_vars) = before.get(env.name.as_str()) else {
+            continue;
+        };
+        let names = old_vars
+            .keys()
+            .chain(env.vars.keys())
+            .collect::<BTreeSet<_>>();
+        for name in names {
+            let (old, new) = (old_vars.get(name), env.vars.get(name));
+            if old == new {
+                continue;
+            }
+            entries.push(AuditEntry {
+                changed_at,
+                user: user.clone(),
+                source: source.to_string(),
+                environment: env.name.clone(),
+                variable: name.clone(),
+                old: old.map(|value| masked(name, value)),
+                new: new.map(|value| masked(name, value)),
+            });
+        }
+    }
+    entries
+}
+
+/// Environment variable changes, appended to `env-audit.jsonl` next to the
+/// state file.
+#[derive(Debug, Clone, Default)]
+pub struct EnvAudit {
+    entries: Vec<AuditEntry>,
+    path: Option<PathBuf>,
+}
+
+impl EnvAudit {
+    pub fn load() -> Self {
+        state_file_path()
+            .and_then(|path| path.parent().map(|dir| dir.join(AUDIT_FILE)))
+            .map_or_else(Self::default, |path| Self::load_from(&path))
+    }
+
+    fn load_from(path: &Path) -> Self {
+        let mut entries = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+            .collect::<Vec<_>>();
+        if entries.len() > MAX_ENTRIES {
+            entries.drain(..entries.len() - MAX_ENTRIES);
+            let lines = entries
+                .iter()
+                .filter_map(|entry| serde_json::to_string(entry).ok())
+                .map(|line| line + "\n")
+                .collect::<String>();
+            let _ = fs::write(path, lines);
+        }
+        Self {
+            entries,
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    pub fn record(&mut self, entries: Vec<AuditEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                for entry in &entries {
+                    if let Ok(line) = serde_json::to_string(entry) {
+                        let _ = writeln!(file, "{line}");
+                    }
+                }
+            }
+        }
+        self.entries.extend(entries);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+    }
+
+    /// Newest first.
+    pub fn recent(&self, limit: usize) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter().rev().take(limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::{Duration, SystemTime};
+
+    use tempfile::tempdir;
+
+    use super::{EnvAudit, diff};
+    use crate::model::{Environment, EnvironmentScope};
+
+    fn env(name: &str, vars: &[(&str, &str)]) -> Environment {
+        Environment {
+            name: name.to_string(),
+            vars: vars
+                .iter()
+                .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                .collect::<BTreeMap<_, _>>(),
+            scope: EnvironmentScope::Default,
+        }
+    }
+
+    #[test]
+    fn changes_are_diffed_per_variable_with_secrets_masked() {
+        let before = vec![
+            env(
+                "dev",
+                &[
+                    ("base_url", "http://a"),
+                    ("api_token", "old"),
+                    ("gone", "x"),
+                ],
+            ),
+            env("removed", &[("base_url", "http://r")]),
+        ];
+        let after = vec![
+            env(
+                "dev",
+                &[
+                    ("base_url", "http://b"),
+                    ("api_token", "new"),
+                    ("added", "y"),
+                ],
+            ),
+            env("new", &[("base_url", "http://n")]),
+        ];
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let entries = diff(&before, &after, "file", now);
+        let lines = entries.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "dev added: (unset) → y",
+                "dev api_token: •••••• → ••••••",
+                "dev base_url: http://a → http://b",
+                "dev gone: x → (unset)",
+            ]
+        );
+        assert!(entries.iter().all(|entry| entry.changed_at == 1_000));
+        assert!(entries.iter().all(|entry| entry.source == "file"));
+    }
+
+    #[test]
+    fn recorded_changes_survive_a_reload() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("env-audit.jsonl");
+        let mut audit = EnvAudit::load_from(&path);
+        let now = SystemTime::UNIX_EPOCH;
+        audit.record(diff(
+            &[env("dev", &[("base_url", "http://a")])],
+            &[env("dev", &[("base_url", "http://b")])],
+            "file",
+            now,
+        ));
+        let reloaded = EnvAudit::load_from(&path);
+        assert_eq!(reloaded.entries, audit.entries);
+        assert_eq!(reloaded.recent(5).count(), 1);
+    }
+}
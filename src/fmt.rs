@@ -0,0 +1,51 @@
+use std::fs;
+
+use crate::launch::FmtOptions;
+use crate::parser::format_http_text;
+use crate::run::{collect_files, display_name};
+
+/// Formats every .http file under the target and returns the process exit
+/// code: 0 when done (or, with `--check`, when nothing would change), 1 when
+/// `--check` found unformatted files or a file could not be formatted, and 2
+/// when the target could not be read.
+pub fn run(options: &FmtOptions) -> i32 {
+    let files = match collect_files(&options.target) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("fmt: {err}");
+            return 2;
+        }
+    };
+    let mut failed = false;
+    let mut changed = 0;
+    for file in &files {
+        let name = display_name(&file.path, &options.target);
+        let formatted = fs::read_to_string(&file.path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| {
+                format_http_text(&content).map(|formatted| (formatted != content, formatted))
+            });
+        match formatted {
+            Ok((false, _)) => {}
+            Ok((true, _)) if options.check => {
+                println!("{name}");
+                changed += 1;
+            }
+            Ok((true, formatted)) => match fs::write(&file.path, formatted) {
+                Ok(()) => {
+                    println!("formatted {name}");
+                    changed += 1;
+                }
+                Err(err) => {
+                    eprintln!("fmt: {name}: {err}");
+                    failed = true;
+                }
+            },
+            Err(err) => {
+                eprintln!("fmt: {name}: {err}");
+                failed = true;
+            }
+        }
+    }
+    i32::from(failed || (options.check && changed > 0))
+}
@@ -0,0 +1,501 @@
+/// Line and column (both 1-based) of a token in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl std::fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Query => f.write_str("query"),
+            Self::Mutation => f.write_str("mutation"),
+            Self::Subscription => f.write_str("subscription"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    Field {
+        name: String,
+        arguments: Vec<String>,
+        selections: Vec<Self>,
+        position: Position,
+    },
+    FragmentSpread {
+        name: String,
+        position: Position,
+    },
+    InlineFragment {
+        type_condition: Option<String>,
+        selections: Vec<Self>,
+        position: Position,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub kind: OperationKind,
+    pub name: Option<String>,
+    pub selections: Vec<Selection>,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    pub name: String,
+    pub type_condition: String,
+    pub selections: Vec<Selection>,
+    pub position: Position,
+}
+
+/// The executable definitions of a GraphQL document. Argument values and
+/// directives are checked for syntax but not kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Document {
+    pub operations: Vec<Operation>,
+    pub fragments: Vec<Fragment>,
+}
+
+impl Document {
+    /// Names of the named operations, in document order.
+    pub fn operation_names(&self) -> Vec<String> {
+        self.operations
+            .iter()
+            .filter_map(|operation| operation.name.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Punct(&'static str),
+    Value,
+}
+
+struct Lexed {
+    token: Token,
+    position: Position,
+}
+
+const PUNCTUATORS: [&str; 14] = [
+    "...", "!", "$", "&", "(", ")", ":", "=", "@", "[", "]", "{", "|", "}",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Lexed>, String> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let (mut pos, mut line, mut line_start) = (0, 1, 0);
+    while pos < chars.len() {
+        let ch = chars[pos];
+        let position = Position {
+            line,
+            column: pos - line_start + 1,
+        };
+        if ch == '\n' {
+            pos += 1;
+            line += 1;
+            line_start = pos;
+        } else if ch.is_whitespace() || ch == ',' || ch == '\u{feff}' {
+            pos += 1;
+        } else if ch == '#' {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+        } else if ch == '"' {
+            let block = chars[pos..].starts_with(&['"', '"', '"']);
+            pos += if block { 3 } else { 1 };
+            loop {
+                let Some(&next) = chars.get(pos) else {
+                    return Err(format!("{position}: unterminated string"));
+                };
+                if block && chars[pos..].starts_with(&['"', '"', '"']) {
+                    pos += 3;
+                    break;
+                }
+                if !block && next == '"' {
+                    pos += 1;
+                    break;
+                }
+                if !block && next == '\n' {
+                    return Err(format!("{position}: unterminated string"));
+                }
+                if next == '\n' {
+                    line += 1;
+                    line_start = pos + 1;
+                }
+                pos += if next == '\\' { 2 } else { 1 };
+            }
+            tokens.push(Lexed {
+                token: Token::Value,
+                position,
+            });
+        } else if ch.is_ascii_digit() || ch == '-' {
+            pos += 1;
+            while pos < chars.len()
+                && (chars[pos].is_ascii_alphanumeric() || matches!(chars[pos], '.' | '+' | '-'))
+            {
+                pos += 1;
+            }
+            tokens.push(Lexed {
+                token: Token::Value,
+                position,
+            });
+        } else if ch.is_ascii_alphabetic() || ch == '_' {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_') {
+                pos += 1;
+            }
+            tokens.push(Lexed {
+                token: Token::Name(chars[start..pos].iter().collect()),
+                position,
+            });
+        } else if let Some(punct) = PUNCTUATORS.iter().find(|punct| {
+            let punct = punct.chars().collect::<Vec<_>>();
+            chars[pos..].starts_with(&punct)
+        }) {
+            pos += punct.len();
+            tokens.push(Lexed {
+                token: Token::Punct(punct),
+                position,
+            });
+        } else {
+            return Err(format!("{position}: unexpected character '{ch}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Lexed>,
+    pos: usize,
+    end: Position,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|lexed| &lexed.token)
+    }
+
+    fn position(&self) -> Position {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |lexed| lexed.position)
+    }
+
+    fn error(&self, expected: &str) -> String {
+        let found = match self.peek() {
+            Some(Token::Name(name)) => format!("\"{name}\""),
+            Some(Token::Punct(punct)) => format!("\"{punct}\""),
+            Some(Token::Value) => "a value".to_string(),
+            None => "the end of the document".to_string(),
+        };
+        format!("{}: expected {expected}, found {found}", self.position())
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Punct(found)) if *found == punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("\"{punct}\"")))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        if let Some(Token::Name(name)) = self.peek() {
+            let name = name.clone();
+            self.pos += 1;
+            Ok(name)
+        } else {
+            Err(self.error("a name"))
+        }
+    }
+
+    fn peek_name(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Token::Name(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn document(&mut self) -> Result<Document, String> {
+        let mut document = Document::default();
+        while self.peek().is_some() {
+            let position = self.position();
+            if matches!(self.peek(), Some(Token::Punct("{"))) {
+                document.operations.push(Operation {
+                    kind: OperationKind::Query,
+                    name: None,
+                    selections: self.selection_set()?,
+                    position,
+                });
+                continue;
+            }
+            let kind = match self.peek_name() {
+                Some("query") => OperationKind::Query,
+                Some("mutation") => OperationKind::Mutation,
+                Some("subscription") => OperationKind::Subscription,
+                Some("fragment") => {
+                    self.pos += 1;
+                    let name = self.name()?;
+                    if self.peek_name() != Some("on") {
+                        return Err(self.error("\"on\""));
+                    }
+                    self.pos += 1;
+                    let type_condition = self.name()?;
+                    self.directives()?;
+                    document.fragments.push(Fragment {
+                        name,
+                        type_condition,
+                        selections: self.selection_set()?,
+                        position,
+                    });
+                    continue;
+                }
+                _ => return Err(self.error("an operation or fragment")),
+            };
+            self.pos += 1;
+            let name = self
+                .peek_name()
+                .is_some()
+                .then(|| self.name())
+                .transpose()?;
+            if self.eat("(") {
+                while !self.eat(")") {
+                    self.expect("$")?;
+                    self.name()?;
+                    self.expect(":")?;
+                    self.type_ref()?;
+                    if self.eat("=") {
+                        self.value()?;
+                    }
+                    self.directives()?;
+                }
+            }
+            self.directives()?;
+            document.operations.push(Operation {
+                kind,
+                name,
+                selections: self.selection_set()?,
+                position,
+            });
+        }
+        Ok(document)
+    }
+
+    fn type_ref(&mut self) -> Result<(), String> {
+        if self.eat("[") {
+            self.type_ref()?;
+            self.expect("]")?;
+        } else {
+            self.name()?;
+        }
+        self.eat("!");
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<(), String> {
+        if self.eat("$") {
+            return self.name().map(drop);
+        }
+        if self.eat("[") {
+            while !self.eat("]") {
+                self.value()?;
+            }
+            return Ok(());
+        }
+        if self.eat("{") {
+            while !self.eat("}") {
+                self.name()?;
+                self.expect(":")?;
+                self.value()?;
+            }
+            return Ok(());
+        }
+        match self.peek() {
+            Some(Token::Value | Token::Name(_)) => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(self.error("a value")),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<String>, String> {
+        let mut names = Vec::new();
+        if self.eat("(") {
+            while !self.eat(")") {
+                names.push(self.name()?);
+                self.expect(":")?;
+                self.value()?;
+            }
+        }
+        Ok(names)
+    }
+
+    fn directives(&mut self) -> Result<(), String> {
+        while self.eat("@") {
+            self.name()?;
+            self.arguments()?;
+        }
+        Ok(())
+    }
+
+    fn selection_set(&mut self) -> Result<Vec<Selection>, String> {
+        self.expect("{")?;
+        let mut selections = Vec::new();
+        while !self.eat("}") {
+            if self.peek().is_none() {
+                return Err(self.error("\"}\""));
+            }
+            selections.push(self.selection()?);
+        }
+        Ok(selections)
+    }
+
+    fn selection(&mut self) -> Result<Selection, String> {
+        let position = self.position();
+        if self.eat("...") {
+            if self.peek_name().is_some_and(|name| name != "on") {
+                let name = self.name()?;
+                self.directives()?;
+                return Ok(Selection::FragmentSpread { name, position });
+            }
+            let type_condition = if self.peek_name() == Some("on") {
+                self.pos += 1;
+                Some(self.name()?)
+            } else {
+                None
+            };
+            self.directives()?;
+            return Ok(Selection::InlineFragment {
+                type_condition,
+                selections: self.selection_set()?,
+                position,
+            });
+        }
+        let alias_or_name = self.name()?;
+        // An alias does not change which field is queried.
+        let name = if self.eat(":") {
+            self.name()?
+        } else {
+            alias_or_name
+        };
+        let arguments = self.arguments()?;
+        self.directives()?;
+        let selections = if matches!(self.peek(), Some(Token::Punct("{"))) {
+            self.selection_set()?
+        } else {
+            Vec::new()
+        };
+        Ok(Selection::Field {
+            name,
+            arguments,
+            selections,
+            position,
+        })
+    }
+}
+
+/// Parses an executable GraphQL document (operations and fragments).
+pub fn parse(source: &str) -> Result<Document, String> {
+    let lines = source.lines().count().max(1);
+    let end = Position {
+        line: lines,
+        column: source.lines().last().map_or(0, |line| line.chars().count()) + 1,
+    };
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        end,
+    };
+    let document = parser.document()?;
+    if document.operations.is_empty() {
+        return Err("The document has no operation".to_string());
+    }
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OperationKind, Position, Selection, parse};
+
+    #[test]
+    fn parses_operations_fragments_and_arguments() {
+        let document = parse(
+            r#"
+            # Two operations sharing a fragment.
+            query Users($first: Int = 10, $role: [Role!]!) @cached {
+              list: users(first: $first, filter: {role: $role, note: """multi
+              line"""}) { ...UserFields ... on Admin { level } }
+            }
+            mutation Rename { rename(id: "1", name: "x") { id } }
+            fragment UserFields on User { id name }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(document.operation_names(), vec!["Users", "Rename"]);
+        assert_eq!(document.operations[1].kind, OperationKind::Mutation);
+        assert_eq!(document.fragments[0].type_condition, "User");
+        let Selection::Field {
+            name,
+            arguments,
+            selections,
+            position,
+        } = &document.operations[0].selections[0]
+        else {
+            panic!("expected a field");
+        };
+        assert_eq!(name, "users");
+        assert_eq!(arguments, &vec!["first".to_string(), "filter".to_string()]);
+        assert_eq!(selections.len(), 2);
+        assert_eq!(
+            *position,
+            Position {
+                line: 4,
+                column: 15
+            }
+        );
+
+        assert_eq!(parse("{ me { id } }").unwrap().operations[0].name, None);
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_token() {
+        assert_eq!(
+            parse("query {\n  me(id: ) { id }\n}"),
+            Err("2:10: expected a value, found \")\"".to_string())
+        );
+        assert_eq!(
+            parse("{ me { id }"),
+            Err("1:12: expected \"}\", found the end of the document".to_string())
+        );
+        assert_eq!(
+            parse("   "),
+            Err("The document has no operation".to_string())
+        );
+    }
+}
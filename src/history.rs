@@ -0,0 +1,288 @@
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::state_file_path;
+
+/// Older entries are dropped from the file when it is loaded.
+const MAX_ENTRIES: usize = 1_000;
+const HISTORY_FILE: &str = "history.jsonl";
+/// Header values that are masked before anything is written to disk.
+const SENSITIVE_HEADERS: [&str; 5] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+    "x-auth-token",
+];
+pub const MASK: &str = "••••••";
+
+pub fn masked_header(line: &str) -> String {
+    match line.split_once(':') {
+        Some((name, _))
+            if SENSITIVE_HEADERS.contains(&name.trim().to_ascii_lowercase().as_str()) =>
+        {
+            format!("{name}: {MASK}")
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// One completed send, as it went out after variable substitution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Reference to the saved request (`path#id=…`, `path#name=…` or
+    /// `path#index`); `None` for unsaved drafts.
+    pub request: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub sent_at: u64,
+    pub method: String,
+    pub url: String,
+    pub environment: Option<String>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: Option<u64>,
+    /// Kept only for sends that failed to go out or failed an assertion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<FailureSnapshot>,
+    /// What went out, for "Edit & resend"; masked and cut like a snapshot
+    /// when written to disk, exact for sends made in this session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent: Option<SentRequest>,
+}
+
+/// Resolved headers and body of a send.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SentRequest {
+    pub headers: String,
+    pub body: String,
+    /// The body was cut at `FailureSnapshot::MAX_BODY_BYTES` on disk.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl SentRequest {
+    fn for_disk(&self) -> Self {
+        let mut body = self.body.clone();
+        let mut cut = body.len().min(FailureSnapshot::MAX_BODY_BYTES);
+        while !body.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        body.truncate(cut);
+        Self {
+            headers: self
+                .headers
+                .lines()
+                .map(masked_header)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            truncated: self.truncated || body.len() < self.body.len(),
+            body,
+        }
+    }
+}
+
+/// The resolved request behind a failed send, for inspecting it later.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct FailureSnapshot {
+    /// Resolved header lines; credentials are masked.
+    pub headers: Vec<String>,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_failures: Vec<String>,
+}
+
+impl FailureSnapshot {
+    /// Bodies are cut at this many bytes so the history file stays small.
+    pub const MAX_BODY_BYTES: usize = 16 * 1024;
+}
+
+impl HistoryEntry {
+    /// Transport errors and non-2xx statuses count as failures.
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+            || !self
+                .status
+                .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+/// Send history, kept in memory and appended to `history.jsonl` next to the
+/// state file.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    /// Requests whose latest entry failed.
+    failed: BTreeSet<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    pub fn load() -> Self {
+        state_file_path()
+            .and_then(|path| path.parent().map(|dir| dir.join(HISTORY_FILE)))
+            .map_or_else(Self::default, |path| Self::load_from(&path))
+    }
+
+    fn load_from(path: &Path) -> Self {
+        let mut entries = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+            .collect::<Vec<_>>();
+        let mut history = Self {
+            path: Some(path.to_path_buf()),
+            ..Self::default()
+        };
+        if entries.len() > MAX_ENTRIES {
+            entries.drain(..entries.len() - MAX_ENTRIES);
+            history.rewrite(&entries);
+        }
+        for entry in &entries {
+            history.track(entry);
+        }
+        history.entries = entries;
+        history
+    }
+
+    fn rewrite(&self, entries: &[HistoryEntry]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let lines = entries
+            .iter()
+            .filter_map(|entry| serde_json::to_string(entry).ok())
+            .map(|line| line + "\n")
+            .collect::<String>();
+        let _ = fs::write(path, lines);
+    }
+
+    fn track(&mut self, entry: &HistoryEntry) {
+        if let Some(request) = &entry.request {
+            if entry.failed() {
+                self.failed.insert(request.clone());
+            } else {
+                self.failed.remove(request);
+            }
+        }
+    }
+
+    pub fn record(&mut self, entry: HistoryEntry) {
+        self.track(&entry);
+        let on_disk = HistoryEntry {
+            sent: entry.sent.as_ref().map(SentRequest::for_disk),
+            ..entry.clone()
+        };
+        if let Some(path) = &self.path
+            && let Ok(line) = serde_json::to_string(&on_disk)
+        {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(file, "{line}");
+            }
+        }
+        self.entries.push(entry);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Whether the latest recorded send of `request` failed.
+    pub fn last_failed(&self, request: &str) -> bool {
+        self.failed.contains(request)
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.failed.len()
+    }
+
+    /// Oldest first.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::{FailureSnapshot, History, HistoryEntry, MAX_ENTRIES, SentRequest};
+
+    fn entry(request: &str, status: Option<u16>, error: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            request: Some(request.to_string()),
+            sent_at: 1_700_000_000,
+            method: "GET".to_string(),
+            url: "https://example.com/".to_string(),
+            environment: Some("staging".to_string()),
+            status,
+            error: error.map(str::to_string),
+            duration_ms: Some(12),
+            snapshot: None,
+            sent: None,
+        }
+    }
+
+    #[test]
+    fn only_the_latest_send_decides_failure() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load_from(&path);
+        history.record(entry("a.http#0", Some(500), None));
+        history.record(entry("a.http#1", None, Some("connection refused")));
+        history.record(entry("a.http#2", Some(404), None));
+        history.record(entry("a.http#2", Some(200), None));
+        assert!(history.last_failed("a.http#0"));
+        assert!(history.last_failed("a.http#1"));
+        assert!(!history.last_failed("a.http#2"));
+
+        let reloaded = History::load_from(&path);
+        assert_eq!(reloaded.entries, history.entries);
+        assert_eq!(reloaded.failed_count(), 2);
+    }
+
+    #[test]
+    fn sent_requests_are_masked_and_cut_on_disk_only() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load_from(&path);
+        let sent = SentRequest {
+            headers: "Authorization: Bearer secret\nAccept: */*".to_string(),
+            body: "x".repeat(FailureSnapshot::MAX_BODY_BYTES + 1),
+            truncated: false,
+        };
+        history.record(HistoryEntry {
+            sent: Some(sent.clone()),
+            ..entry("a.http#0", Some(200), None)
+        });
+        assert_eq!(history.entries()[0].sent.as_ref(), Some(&sent));
+
+        let reloaded = History::load_from(&path);
+        let on_disk = reloaded.entries()[0].sent.as_ref().unwrap();
+        assert_eq!(on_disk.headers, "Authorization: ••••••\nAccept: */*");
+        assert_eq!(on_disk.body.len(), FailureSnapshot::MAX_BODY_BYTES);
+        assert!(on_disk.truncated);
+    }
+
+    #[test]
+    fn loading_trims_old_entries() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = History::load_from(&path);
+        for idx in 0..=MAX_ENTRIES {
+            history.record(entry(&format!("a.http#{idx}"), Some(500), None));
+        }
+        let reloaded = History::load_from(&path);
+        assert_eq!(reloaded.entries.len(), MAX_ENTRIES);
+        assert!(!reloaded.last_failed("a.http#0"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap().lines().count(),
+            MAX_ENTRIES
+        );
+    }
+}
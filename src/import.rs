@@ -0,0 +1,93 @@
+mod bruno;
+mod insomnia;
+mod yaml;
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::{Engine, engine::general_purpose};
+
+use crate::model::RequestDraft;
+use crate::parser::write_http_file;
+
+pub use bruno::parse_bruno;
+pub use insomnia::parse_insomnia;
+
+/// Requests and environments converted from another tool's export, ready to
+/// be written as .http and env files.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedWorkspace {
+    pub name: String,
+    pub files: Vec<ImportedFile>,
+    pub environments: Vec<ImportedEnvironment>,
+    /// Parts of the export that could not be carried over.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedFile {
+    /// Relative to the folder the workspace is written to.
+    pub path: PathBuf,
+    pub requests: Vec<RequestDraft>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedEnvironment {
+    pub name: String,
+    pub vars: BTreeMap<String, String>,
+}
+
+impl ImportedWorkspace {
+    pub fn request_count(&self) -> usize {
+        self.files.iter().map(|file| file.requests.len()).sum()
+    }
+
+    /// Writes every file under `dir`. Nothing is written when one of the
+    /// files already exists.
+    pub fn write_to(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        let env_paths = self
+            .environments
+            .iter()
+            .map(|env| dir.join(format!("{}.env", crate::parser::slugify(&env.name))));
+        let paths = self
+            .files
+            .iter()
+            .map(|file| dir.join(&file.path))
+            .chain(env_paths)
+            .collect::<Vec<_>>();
+        if let Some(existing) = paths.iter().find(|path| path.exists()) {
+            return Err(format!("{} already exists", existing.display()));
+        }
+        for (file, path) in self.files.iter().zip(&paths) {
+            write_http_file(path, &file.requests)
+                .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+        }
+        for (env, path) in self.environments.iter().zip(&paths[self.files.len()..]) {
+            let mut contents = String::new();
+            for (key, value) in &env.vars {
+                let _ = writeln!(contents, "{key}={value}");
+            }
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("Cannot create {}: {err}", dir.display()))?;
+            fs::write(path, contents)
+                .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+        }
+        Ok(paths)
+    }
+}
+
+/// Basic auth as an `Authorization` header, or as URL credentials when
+/// they use variables, which cannot be encoded ahead of time; URL
+/// credentials are sent as Basic auth after substitution.
+fn basic_auth(url: &mut String, headers: &mut Vec<String>, username: &str, password: &str) {
+    if username.contains("{{") || password.contains("{{") {
+        if let Some(scheme_end) = url.find("://") {
+            url.insert_str(scheme_end + 3, &format!("{username}:{password}@"));
+        }
+    } else {
+        let credentials = general_purpose::STANDARD.encode(format!("{username}:{password}"));
+        headers.push(format!("Authorization: Basic {credentials}"));
+    }
+}
@@ -0,0 +1,472 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use super::{ImportedEnvironment, ImportedFile, ImportedWorkspace, basic_auth};
+use crate::curl::{FORM_BOUNDARY, form_body};
+use crate::model::{Method, RequestDraft};
+use crate::parser::slugify;
+
+/// Folder-level and collection-level settings, not requests.
+const SETTINGS_FILES: [&str; 2] = ["collection.bru", "folder.bru"];
+
+/// One `name { ... }` or `name [ ... ]` block of a .bru file, with the
+/// two-space indent of its lines removed.
+#[derive(Debug)]
+struct Block {
+    name: String,
+    lines: Vec<String>,
+}
+
+impl Block {
+    /// `key: value` lines; `~` marks disabled entries, which are skipped.
+    fn pairs(&self) -> Vec<(String, String)> {
+        self.lines
+            .iter()
+            .filter(|line| !line.starts_with('~'))
+            .filter_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                let key = key.trim();
+                (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+            })
+            .collect()
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        self.pairs()
+            .into_iter()
+            .find_map(|(name, value)| (name == key).then_some(value))
+    }
+
+    fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+fn parse_blocks(raw: &str) -> Result<Vec<Block>, String> {
+    let mut blocks = Vec::new();
+    let mut lines = raw.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (name, close) = if let Some(name) = line.strip_suffix('{') {
+            (name, "}")
+        } else if let Some(name) = line.strip_suffix('[') {
+            (name, "]")
+        } else {
+            return Err(format!("Unexpected line: {line}"));
+        };
+        let mut block = Block {
+            name: name.trim().to_string(),
+            lines: Vec::new(),
+        };
+        loop {
+            match lines.next() {
+                Some(line) if line.trim_end() == close => break,
+                Some(line) => {
+                    let line = line.trim_end();
+                    block
+                        .lines
+                        .push(line.strip_prefix("  ").unwrap_or(line).to_string());
+                }
+                None => return Err(format!("Block {} is not closed", block.name)),
+            }
+        }
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+fn block<'a>(blocks: &'a [Block], name: &str) -> Option<&'a Block> {
+    blocks.iter().find(|block| block.name == name)
+}
+
+/// Converts a Bruno collection folder: each folder of `.bru` requests
+/// becomes a .http file (nested folders become folders) and each file in
+/// `environments/` becomes an env file.
+pub fn parse_bruno(dir: &Path) -> Result<ImportedWorkspace, String> {
+    let manifest = fs::read_to_string(dir.join("bruno.json"))
+        .map_err(|err| format!("{} is not a Bruno collection: {err}", dir.display()))?;
+    let manifest: serde_json::Value =
+        serde_json::from_str(&manifest).map_err(|err| format!("Invalid bruno.json: {err}"))?;
+    let mut imported = ImportedWorkspace {
+        name: manifest
+            .get("name")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("bruno")
+            .to_string(),
+        ..ImportedWorkspace::default()
+    };
+
+    let mut folders: BTreeMap<PathBuf, Vec<(u64, RequestDraft)>> = BTreeMap::new();
+    let env_dir = dir.join("environments");
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry.map_err(|err| format!("Cannot read {}: {err}", dir.display()))?;
+        let path = entry.path();
+        let is_bru = path.extension().is_some_and(|ext| ext == "bru");
+        if !entry.file_type().is_file() || !is_bru || path.starts_with(&env_dir) {
+            continue;
+        }
+        let relative = path.strip_prefix(dir).unwrap_or(path);
+        let raw = fs::read_to_string(path)
+            .map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+        let blocks = parse_blocks(&raw).map_err(|err| format!("{}: {err}", relative.display()))?;
+        let file_name = relative.file_name().unwrap_or_default().to_string_lossy();
+        if SETTINGS_FILES.contains(&file_name.as_ref()) {
+            if block(&blocks, "headers").is_some()
+                || blocks.iter().any(|b| b.name.starts_with("auth:"))
+            {
+                imported.warnings.push(format!(
+                    "{}: folder headers and auth were not applied",
+                    relative.display()
+                ));
+            }
+            continue;
+        }
+        if let Some((seq, draft)) = request(&blocks, &mut imported.warnings) {
+            let folder = relative.parent().map(Path::to_path_buf).unwrap_or_default();
+            folders.entry(folder).or_default().push((seq, draft));
+        }
+    }
+
+    let collection_slug = slugify(&imported.name);
+    imported.files = folders
+        .into_iter()
+        .map(|(folder, mut requests)| {
+            requests.sort_by_key(|(seq, _)| *seq);
+            let mut path = folder
+                .iter()
+                .map(|part| slugify(&part.to_string_lossy()))
+                .collect::<PathBuf>();
+            let stem = path.file_name().map_or_else(
+                || collection_slug.clone(),
+                |name| name.to_string_lossy().to_string(),
+            );
+            path.set_file_name(format!("{stem}.http"));
+            ImportedFile {
+                path,
+                requests: requests.into_iter().map(|(_, draft)| draft).collect(),
+            }
+        })
+        .collect();
+    imported.environments = environments(&env_dir, &mut imported.warnings)?;
+    Ok(imported)
+}
+
+/// The request and its `seq`, or `None` for request types .http files
+/// cannot hold.
+fn request(blocks: &[Block], warnings: &mut Vec<String>) -> Option<(u64, RequestDraft)> {
+    let meta = block(blocks, "meta");
+    let title = meta
+        .and_then(|meta| meta.get("name"))
+        .unwrap_or_else(|| "request".to_string());
+    let kind = meta
+        .and_then(|meta| meta.get("type"))
+        .unwrap_or_else(|| "http".to_string());
+    if kind != "http" && kind != "graphql" {
+        warnings.push(format!(
+            "Skipped {title}: {kind} requests are not supported"
+        ));
+        return None;
+    }
+    let seq = meta
+        .and_then(|meta| meta.get("seq"))
+        .and_then(|seq| seq.parse().ok())
+        .unwrap_or_default();
+    let Some((method_block, method)) = blocks.iter().find_map(|block| {
+        let method = block.name.to_uppercase();
+        Method::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == method)
+            .map(|method| (block, method))
+    }) else {
+        warnings.push(format!("Skipped {title}: its method is not supported"));
+        return None;
+    };
+
+    let mut url = method_block.get("url").unwrap_or_default();
+    if let Some(params) = block(blocks, "params:path") {
+        for (name, value) in params.pairs() {
+            url = url.replace(&format!(":{name}"), &value);
+        }
+    }
+    let mut headers = block(blocks, "headers")
+        .map(Block::pairs)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect::<Vec<_>>();
+    let auth = method_block.get("auth").unwrap_or_default();
+    apply_auth(&title, &auth, blocks, &mut url, &mut headers, warnings);
+
+    let mut draft = RequestDraft {
+        title,
+        method,
+        url,
+        graphql: kind == "graphql",
+        ..RequestDraft::default()
+    };
+    let body = method_block.get("body").unwrap_or_default();
+    apply_body(&mut draft, &body, blocks, &mut headers, warnings);
+    draft.headers = headers.join("\n");
+    Some((seq, draft))
+}
+
+fn apply_auth(
+    title: &str,
+    mode: &str,
+    blocks: &[Block],
+    url: &mut String,
+    headers: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    let field = |key: &str| {
+        block(blocks, &format!("auth:{mode}"))
+            .and_then(|auth| auth.get(key))
+            .unwrap_or_default()
+    };
+    match mode {
+        "" | "none" => {}
+        "bearer" => headers.push(format!("Authorization: Bearer {}", field("token"))),
+        "basic" => basic_auth(url, headers, &field("username"), &field("password")),
+        "apikey" => {
+            let (key, value) = (field("key"), field("value"));
+            if field("placement") == "queryparams" {
+                let separator = if url.contains('?') { '&' } else { '?' };
+                *url = format!("{url}{separator}{key}={value}");
+            } else {
+                headers.push(format!("{key}: {value}"));
+            }
+        }
+        "inherit" => warnings.push(format!(
+            "{title}: auth inherited from the collection was not applied"
+        )),
+        other => warnings.push(format!("{title}: {other} auth is not supported")),
+    }
+}
+
+fn apply_body(
+    draft: &mut RequestDraft,
+    mode: &str,
+    blocks: &[Block],
+    headers: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    let content_type = match mode {
+        "" | "none" => return,
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "text" => "text/plain",
+        "formUrlEncoded" => {
+            draft.body = block(blocks, "body:form-urlencoded")
+                .map(Block::pairs)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("&");
+            "application/x-www-form-urlencoded"
+        }
+        "multipartForm" => {
+            let fields = block(blocks, "body:multipart-form")
+                .map(Block::pairs)
+                .unwrap_or_default();
+            if fields.iter().any(|(_, value)| value.starts_with("@file(")) {
+                warnings.push(format!("{}: file fields were left out", draft.title));
+            }
+            let fields = fields
+                .into_iter()
+                .filter(|(_, value)| !value.starts_with("@file("))
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>();
+            draft.body = form_body(&fields).unwrap_or_default();
+            headers.retain(|header| {
+                !header
+                    .split_once(':')
+                    .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+            });
+            headers.push(format!(
+                "Content-Type: multipart/form-data; boundary={FORM_BOUNDARY}"
+            ));
+            return;
+        }
+        "graphql" => {
+            let query = block(blocks, "body:graphql")
+                .map(Block::text)
+                .unwrap_or_default();
+            let variables = block(blocks, "body:graphql:vars")
+                .and_then(|vars| serde_json::from_str(&vars.text()).ok())
+                .unwrap_or_else(|| serde_json::json!({}));
+            draft.graphql = true;
+            draft.body = serde_json::json!({ "query": query, "variables": variables }).to_string();
+            "application/json"
+        }
+        other => {
+            warnings.push(format!("{}: {other} bodies are not supported", draft.title));
+            return;
+        }
+    };
+    if draft.body.is_empty() {
+        draft.body = block(blocks, &format!("body:{mode}"))
+            .map(Block::text)
+            .unwrap_or_default();
+    }
+    let has_content_type = headers.iter().any(|header| {
+        header
+            .split_once(':')
+            .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+    });
+    if !has_content_type {
+        headers.push(format!("Content-Type: {content_type}"));
+    }
+}
+
+/// `vars` of each environment file; secret variables have no value in the
+/// collection, so they are written empty.
+fn environments(
+    env_dir: &Path,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<ImportedEnvironment>, String> {
+    let Ok(entries) = fs::read_dir(env_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bru"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut imported = Vec::new();
+    for path in paths {
+        let raw = fs::read_to_string(&path)
+            .map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+        let blocks = parse_blocks(&raw).map_err(|err| format!("{}: {err}", path.display()))?;
+        let name = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mut vars = block(&blocks, "vars")
+            .map(Block::pairs)
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        if let Some(secrets) = block(&blocks, "vars:secret") {
+            let secrets = secrets
+                .lines
+                .iter()
+                .map(|line| line.trim().trim_end_matches(',').to_string())
+                .filter(|line| !line.is_empty() && !line.starts_with('~'))
+                .collect::<Vec<_>>();
+            if !secrets.is_empty() {
+                warnings.push(format!(
+                    "{name}: fill in the secret variables {}",
+                    secrets.join(", ")
+                ));
+            }
+            for secret in secrets {
+                vars.entry(secret).or_default();
+            }
+        }
+        imported.push(ImportedEnvironment { name, vars });
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tempfile::tempdir;
+
+    use super::parse_bruno;
+    use crate::model::Method;
+
+    const CREATE_USER: &str = "meta {
+  name: Create user
+  type: http
+  seq: 2
+}
+
+post {
+  url: {{baseUrl}}/users/:team
+  body: json
+  auth: bearer
+}
+
+params:path {
+  team: core
+}
+
+headers {
+  Accept: application/json
+  ~X-Debug: 1
+}
+
+auth:bearer {
+  token: {{token}}
+}
+
+body:json {
+  {
+    \"name\": \"{{user}}\"
+  }
+}
+";
+
+    #[test]
+    fn folders_become_files_and_environments_env_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("bruno.json"),
+            r#"{"version":"1","name":"Shop API"}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("health.bru"),
+            "meta {\n  name: Health\n  seq: 1\n}\n\nget {\n  url: https://shop.test/health\n  auth: basic\n}\n\nauth:basic {\n  username: admin\n  password: secret\n}\n",
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("Users")).unwrap();
+        fs::write(root.join("Users").join("create.bru"), CREATE_USER).unwrap();
+        fs::create_dir_all(root.join("environments")).unwrap();
+        fs::write(
+            root.join("environments").join("dev.bru"),
+            "vars {\n  baseUrl: http://localhost:8080\n}\nvars:secret [\n  token\n]\n",
+        )
+        .unwrap();
+
+        let imported = parse_bruno(root).unwrap();
+        assert_eq!(imported.name, "Shop API");
+        assert_eq!(imported.files.len(), 2);
+        assert_eq!(imported.files[0].path, PathBuf::from("shop-api.http"));
+        assert_eq!(
+            imported.files[0].requests[0].headers,
+            "Authorization: Basic YWRtaW46c2VjcmV0"
+        );
+
+        let users = &imported.files[1];
+        assert_eq!(users.path, PathBuf::from("users.http"));
+        let create = &users.requests[0];
+        assert_eq!(create.method, Method::Post);
+        assert_eq!(create.url, "{{baseUrl}}/users/core");
+        assert_eq!(
+            create.headers,
+            "Accept: application/json\nAuthorization: Bearer {{token}}\nContent-Type: application/json"
+        );
+        assert_eq!(create.body, "{\n  \"name\": \"{{user}}\"\n}");
+
+        let dev = &imported.environments[0];
+        assert_eq!(dev.name, "dev");
+        assert_eq!(dev.vars["baseUrl"], "http://localhost:8080");
+        assert_eq!(dev.vars["token"], "");
+        assert_eq!(imported.warnings.len(), 1);
+        assert!(parse_bruno(&root.join("Users")).is_err());
+    }
+}
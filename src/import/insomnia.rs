@@ -0,0 +1,463 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use regex::Regex;
+use serde_json::Value;
+
+use super::{ImportedEnvironment, ImportedFile, ImportedWorkspace, basic_auth, yaml};
+use crate::curl::{FORM_BOUNDARY, form_body};
+use crate::model::{Method, RequestDraft};
+use crate::parser::slugify;
+
+/// Converts an Insomnia v4 export (JSON or YAML). Request groups become
+/// folders and files, sub environments become env files that include the
+/// base environment, and auth turns into headers.
+pub fn parse_insomnia(raw: &str) -> Result<ImportedWorkspace, String> {
+    let export: Value = if raw.trim_start().starts_with('{') {
+        serde_json::from_str(raw).map_err(|err| format!("Invalid JSON: {err}"))?
+    } else {
+        yaml::parse(raw).map_err(|err| format!("Invalid YAML: {err}"))?
+    };
+    if export.get("__export_format").and_then(Value::as_u64) != Some(4) {
+        return Err("Not an Insomnia v4 export (expected __export_format: 4)".to_string());
+    }
+    let resources = export
+        .get("resources")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "The export has no resources".to_string())?;
+    let by_id = resources
+        .iter()
+        .filter_map(|resource| Some((text(resource, "_id")?, resource)))
+        .collect::<HashMap<_, _>>();
+    let workspace = resources
+        .iter()
+        .find(|resource| kind(resource) == "workspace");
+    let mut imported = ImportedWorkspace {
+        name: workspace
+            .and_then(|workspace| text(workspace, "name"))
+            .unwrap_or("insomnia")
+            .to_string(),
+        ..ImportedWorkspace::default()
+    };
+
+    let mut requests = resources
+        .iter()
+        .filter(|resource| kind(resource).ends_with("request"))
+        .collect::<Vec<_>>();
+    requests.sort_by(|a, b| sort_key(a).total_cmp(&sort_key(b)));
+    let mut files: BTreeMap<Vec<String>, Vec<RequestDraft>> = BTreeMap::new();
+    for resource in requests {
+        let name = text(resource, "name").unwrap_or("request");
+        if kind(resource) != "request" {
+            imported.warnings.push(format!(
+                "Skipped {name}: {} is not supported",
+                kind(resource)
+            ));
+            continue;
+        }
+        let draft = request(resource, &mut imported.warnings);
+        files
+            .entry(group_path(resource, &by_id))
+            .or_default()
+            .push(draft);
+    }
+    let workspace_slug = slugify(&imported.name);
+    imported.files = files
+        .into_iter()
+        .map(|(groups, requests)| {
+            let mut path = groups
+                .iter()
+                .map(|group| slugify(group))
+                .collect::<PathBuf>();
+            let stem = path.file_name().map_or_else(
+                || workspace_slug.clone(),
+                |name| name.to_string_lossy().to_string(),
+            );
+            path.set_file_name(format!("{stem}.http"));
+            ImportedFile { path, requests }
+        })
+        .collect();
+    imported.environments = environments(resources, workspace, &mut imported.warnings);
+    Ok(imported)
+}
+
+fn text<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn kind(resource: &Value) -> &str {
+    text(resource, "_type").unwrap_or_default()
+}
+
+fn sort_key(resource: &Value) -> f64 {
+    resource
+        .get("metaSortKey")
+        .and_then(Value::as_f64)
+        .unwrap_or_default()
+}
+
+fn is_enabled(value: &Value) -> bool {
+    value.get("disabled").and_then(Value::as_bool) != Some(true)
+}
+
+/// Names of the request groups above `resource`, outermost first.
+fn group_path(resource: &Value, by_id: &HashMap<&str, &Value>) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut parent = text(resource, "parentId");
+    while let Some(group) = parent.and_then(|id| by_id.get(id)) {
+        if kind(group) != "request_group" {
+            break;
+        }
+        path.push(text(group, "name").unwrap_or("group").to_string());
+        parent = text(group, "parentId");
+    }
+    path.reverse();
+    path
+}
+
+/// `{{ _.base_url }}` becomes `{{base_url}}`.
+fn template(value: &str) -> String {
+    static VARIABLE: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    VARIABLE
+        .get_or_init(|| Regex::new(r"\{\{\s*(?:_\.)?([\w.-]+)\s*\}\}").expect("valid pattern"))
+        .replace_all(value, "{{$1}}")
+        .into_owned()
+}
+
+fn pairs(value: Option<&Value>) -> Vec<(String, String)> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|pair| is_enabled(pair))
+        .filter_map(|pair| {
+            let name = text(pair, "name")?.trim();
+            (!name.is_empty()).then(|| {
+                (
+                    template(name),
+                    template(text(pair, "value").unwrap_or_default()),
+                )
+            })
+        })
+        .collect()
+}
+
+fn request(resource: &Value, warnings: &mut Vec<String>) -> RequestDraft {
+    let name = text(resource, "name").unwrap_or("request").to_string();
+    let method_name = text(resource, "method").unwrap_or("GET").to_uppercase();
+    let method = Method::parse(&method_name).unwrap_or_else(|| {
+        warnings.push(format!(
+            "{name}: {method_name} is not a valid method; imported as GET"
+        ));
+        Method::Get
+    });
+    let mut url = template(text(resource, "url").unwrap_or_default());
+    let mut query = pairs(resource.get("parameters"))
+        .into_iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>();
+    let mut headers = pairs(resource.get("headers"))
+        .into_iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect::<Vec<_>>();
+    if let Some(auth) = resource
+        .get("authentication")
+        .filter(|auth| is_enabled(auth))
+    {
+        apply_auth(&name, auth, &mut url, &mut headers, &mut query, warnings);
+    }
+    if !query.is_empty() {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url = format!("{url}{separator}{}", query.join("&"));
+    }
+    let mut draft = RequestDraft {
+        title: name,
+        method,
+        url,
+        ..RequestDraft::default()
+    };
+    if let Some(body) = resource.get("body") {
+        apply_body(&mut draft, body, &mut headers, warnings);
+    }
+    if draft.url.contains("{%") || draft.body.contains("{%") {
+        warnings.push(format!(
+            "{}: Insomnia template tags ({{% ... %}}) were kept as text",
+            draft.title
+        ));
+    }
+    draft.headers = headers.join("\n");
+    draft
+}
+
+fn apply_auth(
+    name: &str,
+    auth: &Value,
+    url: &mut String,
+    headers: &mut Vec<String>,
+    query: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    let field = |key| template(text(auth, key).unwrap_or_default());
+    match text(auth, "type").unwrap_or_default() {
+        "" | "none" => {}
+        "bearer" => {
+            let prefix = Some(field("prefix")).filter(|prefix| !prefix.is_empty());
+            headers.push(format!(
+                "Authorization: {} {}",
+                prefix.as_deref().unwrap_or("Bearer"),
+                field("token")
+            ));
+        }
+        "basic" => basic_auth(url, headers, &field("username"), &field("password")),
+        "apikey" => {
+            let (key, value) = (field("key"), field("value"));
+            if text(auth, "addTo") == Some("queryParams") {
+                query.push(format!("{key}={value}"));
+            } else {
+                headers.push(format!("{key}: {value}"));
+            }
+        }
+        other => warnings.push(format!("{name}: {other} auth is not supported")),
+    }
+}
+
+fn apply_body(
+    draft: &mut RequestDraft,
+    body: &Value,
+    headers: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    let mime = text(body, "mimeType").unwrap_or_default();
+    let params = body.get("params").and_then(Value::as_array);
+    match mime {
+        "application/x-www-form-urlencoded" => {
+            draft.body = pairs(body.get("params"))
+                .into_iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("&");
+        }
+        "multipart/form-data" => {
+            if params
+                .into_iter()
+                .flatten()
+                .any(|param| text(param, "type") == Some("file"))
+            {
+                warnings.push(format!("{}: file fields were left out", draft.title));
+            }
+            let fields = params
+                .into_iter()
+                .flatten()
+                .filter(|param| is_enabled(param) && text(param, "type") != Some("file"))
+                .filter_map(|param| {
+                    Some(format!(
+                        "{}={}",
+                        template(text(param, "name")?),
+                        template(text(param, "value").unwrap_or_default())
+                    ))
+                })
+                .collect::<Vec<_>>();
+            headers.retain(|header| {
+                !header
+                    .split_once(':')
+                    .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+            });
+            headers.push(format!(
+                "Content-Type: multipart/form-data; boundary={FORM_BOUNDARY}"
+            ));
+            draft.body = form_body(&fields).unwrap_or_default();
+        }
+        _ => {
+            draft.graphql = mime == "application/graphql";
+            draft.body = template(text(body, "text").unwrap_or_default());
+        }
+    }
+}
+
+/// Sub environments with the base environment's variables underneath; the
+/// base environment alone when there are none.
+fn environments(
+    resources: &[Value],
+    workspace: Option<&Value>,
+    warnings: &mut Vec<String>,
+) -> Vec<ImportedEnvironment> {
+    let workspace_id = workspace.and_then(|workspace| text(workspace, "_id"));
+    let envs = resources
+        .iter()
+        .filter(|resource| kind(resource) == "environment")
+        .collect::<Vec<_>>();
+    let Some(base) = envs
+        .iter()
+        .find(|env| workspace_id.is_none() || text(env, "parentId") == workspace_id)
+    else {
+        return Vec::new();
+    };
+    let base_vars = variables(base, warnings);
+    let base_id = text(base, "_id");
+    let mut imported = envs
+        .iter()
+        .filter(|env| base_id.is_some() && text(env, "parentId") == base_id)
+        .map(|env| {
+            let mut vars = base_vars.clone();
+            vars.extend(variables(env, warnings));
+            ImportedEnvironment {
+                name: text(env, "name").unwrap_or("environment").to_string(),
+                vars,
+            }
+        })
+        .collect::<Vec<_>>();
+    if imported.is_empty() && !base_vars.is_empty() {
+        imported.push(ImportedEnvironment {
+            name: "base".to_string(),
+            vars: base_vars,
+        });
+    }
+    imported
+}
+
+/// Nested objects are flattened to `parent.child` keys, matching how
+/// `{{ _.parent.child }}` is rewritten.
+fn variables(env: &Value, warnings: &mut Vec<String>) -> BTreeMap<String, String> {
+    fn flatten(
+        prefix: &str,
+        value: &Value,
+        vars: &mut BTreeMap<String, String>,
+        warnings: &mut Vec<String>,
+    ) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    flatten(&key, value, vars, warnings);
+                }
+            }
+            Value::String(text) if text.contains('\n') => {
+                warnings.push(format!(
+                    "Variable {prefix} spans several lines and was left out"
+                ));
+            }
+            Value::String(text) => {
+                vars.insert(prefix.to_string(), template(text));
+            }
+            Value::Null => {
+                vars.insert(prefix.to_string(), String::new());
+            }
+            other => {
+                vars.insert(prefix.to_string(), other.to_string());
+            }
+        }
+    }
+    let mut vars = BTreeMap::new();
+    if let Some(data) = env.get("data") {
+        flatten("", data, &mut vars, warnings);
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::parse_insomnia;
+    use crate::model::Method;
+
+    const EXPORT: &str = r#"
+_type: export
+__export_format: 4
+resources:
+  - _id: wrk_1
+    _type: workspace
+    name: Shop API
+  - _id: fld_1
+    _type: request_group
+    parentId: wrk_1
+    name: Users
+  - _id: req_2
+    _type: request
+    parentId: fld_1
+    name: Create user
+    method: POST
+    metaSortKey: -1
+    url: "{{ _.base_url }}/users"
+    headers:
+      - name: Content-Type
+        value: application/json
+      - name: X-Debug
+        value: "1"
+        disabled: true
+    body:
+      mimeType: application/json
+      text: |-
+        {"name": "{{ _.user }}"}
+    authentication:
+      type: bearer
+      token: "{{ _.token }}"
+  - _id: req_1
+    _type: request
+    parentId: wrk_1
+    name: Health
+    method: GET
+    url: https://shop.test/health
+    parameters:
+      - name: verbose
+        value: "true"
+    authentication:
+      type: basic
+      username: admin
+      password: secret
+  - _id: env_base
+    _type: environment
+    parentId: wrk_1
+    name: Base Environment
+    data:
+      base_url: https://shop.test
+      auth:
+        token: base-token
+  - _id: env_dev
+    _type: environment
+    parentId: env_base
+    name: Dev
+    data:
+      base_url: http://localhost:8080
+"#;
+
+    #[test]
+    fn groups_become_files_and_sub_environments_include_the_base() {
+        let imported = parse_insomnia(EXPORT).unwrap();
+        assert_eq!(imported.name, "Shop API");
+        assert_eq!(imported.files.len(), 2);
+
+        let root = &imported.files[0];
+        assert_eq!(root.path, PathBuf::from("shop-api.http"));
+        assert_eq!(
+            root.requests[0].url,
+            "https://shop.test/health?verbose=true"
+        );
+        assert_eq!(
+            root.requests[0].headers,
+            "Authorization: Basic YWRtaW46c2VjcmV0"
+        );
+
+        let users = &imported.files[1];
+        assert_eq!(users.path, PathBuf::from("users.http"));
+        let create = &users.requests[0];
+        assert_eq!(create.method, Method::Post);
+        assert_eq!(create.url, "{{base_url}}/users");
+        assert_eq!(
+            create.headers,
+            "Content-Type: application/json\nAuthorization: Bearer {{token}}"
+        );
+        assert_eq!(create.body, r#"{"name": "{{user}}"}"#);
+
+        assert_eq!(imported.environments.len(), 1);
+        let dev = &imported.environments[0];
+        assert_eq!(dev.name, "Dev");
+        assert_eq!(dev.vars["base_url"], "http://localhost:8080");
+        assert_eq!(dev.vars["auth.token"], "base-token");
+        assert!(imported.warnings.is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+use serde_json::{Map, Number, Value};
+
+/// Parses the block-style YAML that Insomnia writes: nested mappings and
+/// sequences, plain and quoted scalars (folded over several lines too) and
+/// `|`/`>` block scalars. Anchors, tags and multi-document files are not
+/// supported.
+pub fn parse(input: &str) -> Result<Value, String> {
+    let lines = input
+        .lines()
+        .enumerate()
+        .map(|(number, line)| Line {
+            number: number + 1,
+            indent: line.len() - line.trim_start_matches(' ').len(),
+            text: line.trim_start_matches(' ').trim_end().to_string(),
+        })
+        .collect();
+    let mut parser = Parser { lines, pos: 0 };
+    parser.skip_ignored();
+    if parser.peek().is_some_and(|line| line.text == "---") {
+        parser.pos += 1;
+    }
+    let Some(indent) = parser.peek().map(|line| line.indent) else {
+        return Ok(Value::Null);
+    };
+    let value = parser.node(indent)?;
+    if let Some(line) = parser.peek() {
+        return Err(format!("line {}: unexpected content", line.number));
+    }
+    Ok(value)
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    number: usize,
+    indent: usize,
+    text: String,
+}
+
+struct Parser {
+    lines: Vec<Line>,
+    pos: usize,
+}
+
+impl Parser {
+    fn skip_ignored(&mut self) {
+        while self
+            .lines
+            .get(self.pos)
+            .is_some_and(|line| line.text.is_empty() || line.text.starts_with('#'))
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<&Line> {
+        self.skip_ignored();
+        self.lines.get(self.pos)
+    }
+
+    fn node(&mut self, indent: usize) -> Result<Value, String> {
+        let Some(line) = self.peek().cloned() else {
+            return Ok(Value::Null);
+        };
+        if is_item(&line.text) {
+            self.sequence(line.indent)
+        } else if split_key(&line.text).is_some() {
+            self.mapping(line.indent)
+        } else if line.indent >= indent {
+            self.pos += 1;
+            self.scalar(&line.text, indent)
+        } else {
+            Ok(Value::Null)
+        }
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Value, String> {
+        let mut items = Vec::new();
+        while let Some(line) = self.peek().cloned() {
+            if line.indent != indent || !is_item(&line.text) {
+                break;
+            }
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() {
+                self.pos += 1;
+                let nested = self.peek().map_or(0, |next| next.indent);
+                items.push(if nested > indent {
+                    self.node(nested)?
+                } else {
+                    Value::Null
+                });
+            } else {
+                // `- key: value` starts a mapping indented to where `key` is.
+                let offset = line.text.len() - rest.len();
+                self.lines[self.pos] = Line {
+                    indent: indent + offset,
+                    text: rest.to_string(),
+                    ..line
+                };
+                items.push(self.node(indent + offset)?);
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Value, String> {
+        let mut map = Map::new();
+        while let Some(line) = self.peek().cloned() {
+            if line.indent != indent {
+                if line.indent > indent {
+                    return Err(format!("line {}: unexpected indentation", line.number));
+                }
+                break;
+            }
+            let Some((key, rest)) = split_key(&line.text) else {
+                break;
+            };
+            self.pos += 1;
+            let value = match rest {
+                "" => match self.peek().cloned() {
+                    Some(next) if next.indent > indent => self.node(next.indent)?,
+                    Some(next) if next.indent == indent && is_item(&next.text) => {
+                        self.sequence(indent)?
+                    }
+                    _ => Value::Null,
+                },
+                block if block.starts_with('|') || block.starts_with('>') => {
+                    Value::String(self.block_scalar(block, indent))
+                }
+                rest => self.scalar(rest, indent + 1)?,
+            };
+            map.insert(unquote(key)?, value);
+        }
+        Ok(Value::Object(map))
+    }
+
+    /// `text` plus any continuation lines indented at least `indent`.
+    fn scalar(&mut self, text: &str, indent: usize) -> Result<Value, String> {
+        let mut text = text.to_string();
+        let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'');
+        while let Some(next) = self.lines.get(self.pos) {
+            let open = quote.is_some_and(|quote| !is_closed(&text, quote));
+            if !open && (next.indent < indent || next.text.is_empty()) {
+                break;
+            }
+            if !open && (quote.is_some() || split_key(&next.text).is_some() || is_item(&next.text))
+            {
+                break;
+            }
+            text.push(' ');
+            text.push_str(&next.text);
+            self.pos += 1;
+        }
+        if quote.is_some() {
+            return unquote(&text).map(Value::String);
+        }
+        Ok(plain(&text))
+    }
+
+    fn block_scalar(&mut self, header: &str, indent: usize) -> String {
+        let mut raw = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if !line.text.is_empty() && line.indent <= indent {
+                break;
+            }
+            raw.push(line.clone());
+            self.pos += 1;
+        }
+        while raw.last().is_some_and(|line| line.text.is_empty()) {
+            raw.pop();
+        }
+        let base = raw
+            .iter()
+            .filter(|line| !line.text.is_empty())
+            .map(|line| line.indent)
+            .min()
+            .unwrap_or(0);
+        let lines = raw.iter().map(|line| {
+            if line.text.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", " ".repeat(line.indent - base), line.text)
+            }
+        });
+        let mut text = if header.starts_with('>') {
+            lines
+                .collect::<Vec<_>>()
+                .join("\n")
+                .split("\n\n")
+                .map(|paragraph| paragraph.replace('\n', " "))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            lines.collect::<Vec<_>>().join("\n")
+        };
+        if !header.contains('-') && !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Splits `key: value` at the first `: ` outside quotes.
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    if text.starts_with('-') && is_item(text) {
+        return None;
+    }
+    let mut quote = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') if index == 0 => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, ':') => {
+                let rest = &text[index + 1..];
+                if rest.is_empty() || rest.starts_with(' ') {
+                    return Some((&text[..index], rest.trim()));
+                }
+            }
+            (None, '#') if index > 0 && text[..index].ends_with(' ') => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_closed(text: &str, quote: char) -> bool {
+    let body = &text[1..];
+    if quote == '\'' {
+        return body.replace("''", "").contains('\'');
+    }
+    let mut escaped = false;
+    for c in body.chars() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return true,
+            _ => escaped = false,
+        }
+    }
+    false
+}
+
+fn unquote(text: &str) -> Result<String, String> {
+    if let Some(inner) = text.strip_prefix('\'') {
+        let end = inner
+            .rfind('\'')
+            .ok_or_else(|| format!("unterminated quote in {text}"))?;
+        return Ok(inner[..end].replace("''", "'"));
+    }
+    if text.starts_with('"') {
+        let end = text.rfind('"').filter(|end| *end > 0);
+        let quoted = end.map_or(text, |end| &text[..=end]);
+        return serde_json::from_str(quoted).map_err(|err| format!("invalid string {text}: {err}"));
+    }
+    Ok(text.to_string())
+}
+
+fn plain(text: &str) -> Value {
+    let text = text
+        .find(" #")
+        .map_or(text, |comment| &text[..comment])
+        .trim();
+    match text {
+        "" | "~" | "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "{}" => Value::Object(Map::new()),
+        "[]" => Value::Array(Vec::new()),
+        _ => text
+            .parse::<i64>()
+            .map(Number::from)
+            .ok()
+            .or_else(|| text.parse::<f64>().ok().and_then(Number::from_f64))
+            .map_or_else(|| Value::String(text.to_string()), Value::Number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::parse;
+
+    #[test]
+    fn parses_nested_blocks_and_scalars() {
+        let yaml = r#"
+_type: export
+__export_format: 4
+resources:
+  - _id: req_1
+    name: 'It''s here'
+    headers:
+      - name: Accept
+        value: "application/json" # trailing comment
+    body:
+      text: |-
+        {
+          "a": 1
+        }
+    folded: a long
+      plain value
+    parameters: []
+  - _id: env_1
+    data:
+      base_url: http://localhost:3000
+      retries: 3
+      enabled: true
+"#;
+        assert_eq!(
+            parse(yaml).unwrap(),
+            json!({
+                "_type": "export",
+                "__export_format": 4,
+                "resources": [
+                    {
+                        "_id": "req_1",
+                        "name": "It's here",
+                        "headers": [{"name": "Accept", "value": "application/json"}],
+                        "body": {"text": "{\n  \"a\": 1\n}"},
+                        "folded": "a long plain value",
+                        "parameters": [],
+                    },
+                    {
+                        "_id": "env_1",
+                        "data": {"base_url": "http://localhost:3000", "retries": 3, "enabled": true},
+                    },
+                ],
+            })
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selector {
+    Key(String),
+    Index(i64),
+    Wildcard,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    recursive: bool,
+    selector: Selector,
+}
+
+/// Applies a JSON path subset to `value`: `$.a.b`, `$.items[0]`, `$.items[-1]`,
+/// `$.items[*].id`, `$['odd key']` and recursive `$..id`. The leading `$` is
+/// optional, so jq-style `.items[0].id` works too.
+///
+/// A single match is returned as-is; several matches are collected into an array.
+pub fn apply(value: &Value, expr: &str) -> Result<Value, String> {
+    let steps = parse(expr)?;
+    let mut matches = select(value, &steps);
+    match matches.len() {
+        0 => Err(format!("No matches for {}", expr.trim())),
+        1 => Ok(matches.remove(0).clone()),
+        _ => Ok(Value::Array(matches.into_iter().cloned().collect())),
+    }
+}
+
+fn parse(expr: &str) -> Result<Vec<Step>, String> {
+    let expr = expr.trim();
+    let rest = expr.strip_prefix('$').unwrap_or(expr);
+    let chars = rest.chars().collect::<Vec<_>>();
+    let mut steps = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let mut recursive = false;
+        match chars[pos] {
+            '.' => {
+                pos += 1;
+                if chars.get(pos) == Some(&'.') {
+                    recursive = true;
+                    pos += 1;
+                }
+                if chars.get(pos) == Some(&'[') {
+                    let (selector, next) = parse_bracket(&chars, pos)?;
+                    steps.push(Step {
+                        recursive,
+                        selector,
+                    });
+                    pos = next;
+                    continue;
+                }
+            }
+            '[' => {
+                let (selector, next) = parse_bracket(&chars, pos)?;
+                steps.push(Step {
+                    recursive,
+                    selector,
+                });
+                pos = next;
+                continue;
+            }
+            _ if pos == 0 => {}
+            other => return Err(format!("Unexpected '{other}' at position {pos}")),
+        }
+
+        let start = pos;
+        while pos < chars.len() && !matches!(chars[pos], '.' | '[') {
+            pos += 1;
+        }
+        let name = chars[start..pos].iter().collect::<String>();
+        let selector = match name.trim() {
+            "" => return Err(format!("Missing key at position {start}")),
+            "*" => Selector::Wildcard,
+            key => Selector::Key(key.to_string()),
+        };
+        steps.push(Step {
+            recursive,
+            selector,
+        });
+    }
+    Ok(steps)
+}
+
+/// Parses `[...]` starting at `open`; returns the selector and the position after `]`.
+fn parse_bracket(chars: &[char], open: usize) -> Result<(Selector, usize), String> {
+    let close = chars[open..]
+        .iter()
+        .position(|ch| *ch == ']')
+        .map(|offset| open + offset)
+        .ok_or_else(|| format!("Unclosed '[' at position {open}"))?;
+    let inner = chars[open + 1..close].iter().collect::<String>();
+    let inner = inner.trim();
+    let selector = if inner == "*" {
+        Selector::Wildcard
+    } else if let Some(key) = inner
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+        .or_else(|| {
+            inner
+                .strip_prefix('"')
+                .and_then(|rest| rest.strip_suffix('"'))
+        })
+    {
+        Selector::Key(key.to_string())
+    } else {
+        Selector::Index(
+            inner
+                .parse()
+                .map_err(|_| format!("Invalid index '{inner}' at position {open}"))?,
+        )
+    };
+    Ok((selector, close + 1))
+}
+
+fn select<'a>(root: &'a Value, steps: &[Step]) -> Vec<&'a Value> {
+    let mut current = vec![root];
+    for step in steps {
+        let mut next = Vec::new();
+        for value in current {
+            if step.recursive {
+                let mut descendants = Vec::new();
+                collect_descendants(value, &mut descendants);
+                for descendant in descendants {
+                    apply_selector(descendant, &step.selector, &mut next);
+                }
+            } else {
+                apply_selector(value, &step.selector, &mut next);
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+fn collect_descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(value);
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_descendants(item, out)),
+        Value::Object(map) => map.values().for_each(|item| collect_descendants(item, out)),
+        _ => {}
+    }
+}
+
+fn apply_selector<'a>(value: &'a Value, selector: &Selector, out: &mut Vec<&'a Value>) {
+    match (selector, value) {
+        (Selector::Key(key), Value::Object(map)) => out.extend(map.get(key)),
+        (Selector::Index(index), Value::Array(items)) => {
+            let resolved = if *index < 0 {
+                usize::try_from(index.unsigned_abs())
+                    .ok()
+                    .and_then(|back| items.len().checked_sub(back))
+            } else {
+                usize::try_from(*index).ok()
+            };
+            out.extend(resolved.and_then(|idx| items.get(idx)));
+        }
+        (Selector::Wildcard, Value::Array(items)) => out.extend(items),
+        (Selector::Wildcard, Value::Object(map)) => out.extend(map.values()),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::apply;
+
+    #[test]
+    fn selects_keys_indexes_and_wildcards() {
+        let body = json!({
+            "items": [{"id": 1, "tags": ["a"]}, {"id": 2, "tags": []}],
+            "odd key": true
+        });
+
+        assert_eq!(apply(&body, "$.items[0].id"), Ok(json!(1)));
+        assert_eq!(apply(&body, ".items[-1].id"), Ok(json!(2)));
+        assert_eq!(apply(&body, "$.items[*].id"), Ok(json!([1, 2])));
+        assert_eq!(apply(&body, "$['odd key']"), Ok(json!(true)));
+        assert_eq!(apply(&body, "items.*.tags[0]"), Ok(json!("a")));
+    }
+
+    #[test]
+    fn recursive_descent_finds_nested_keys() {
+        let body = json!({"a": {"id": 1, "b": [{"id": 2}]}});
+        assert_eq!(apply(&body, "$..id"), Ok(json!([1, 2])));
+    }
+
+    #[test]
+    fn reports_syntax_errors_and_empty_results() {
+        let body = json!({"a": 1});
+        assert!(apply(&body, "$.a[").is_err());
+        assert!(apply(&body, "$.a[x]").is_err());
+        assert_eq!(
+            apply(&body, "$.missing"),
+            Err("No matches for $.missing".to_string())
+        );
+    }
+}
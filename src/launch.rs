@@ -1,4 +1,7 @@
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::model::RequestRef;
 
 #[derive(Debug, Clone, Default)]
 pub struct LaunchOptions {
@@ -14,4 +17,75 @@ pub struct AutomationOptions {
     pub screenshot_dir: PathBuf,
     pub state_output_path: Option<PathBuf>,
     pub exit_when_done: bool,
+    /// Fails the whole scenario once it has run for this long.
+    pub timeout: Option<Duration>,
+}
+
+/// Where `zagel send` reads its request from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendTarget {
+    Stdin,
+    /// A request of an `.http` file, written `<path>#<index>`,
+    /// `<path>#name=<name>` or `<path>#id=<id>`.
+    File(RequestRef),
+}
+
+#[derive(Debug, Clone)]
+pub struct SendOptions {
+    pub launch: LaunchOptions,
+    pub target: SendTarget,
+    /// Environment to resolve `{{variables}}` from.
+    pub env: Option<String>,
+    /// Print the status line and headers before the body on stdout.
+    pub include: bool,
+}
+
+/// Machine-readable report written by `zagel run --report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunReport {
+    Junit(PathBuf),
+    /// JSON to the given file, or to stdout in place of the text summary.
+    Json(Option<PathBuf>),
+}
+
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    pub launch: LaunchOptions,
+    /// An `.http` file or a directory searched for them.
+    pub target: PathBuf,
+    pub env: Option<String>,
+    pub report: Option<RunReport>,
+    /// Only sends the requests with this `# @tags` tag.
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    pub launch: LaunchOptions,
+    /// An `.http` file or a directory searched for them.
+    pub target: PathBuf,
+    /// Checks variables against this environment instead of all of them.
+    pub env: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FmtOptions {
+    pub launch: LaunchOptions,
+    /// An `.http` file or a directory searched for them.
+    pub target: PathBuf,
+    /// Only report files that would change, without writing them.
+    pub check: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListOptions {
+    pub launch: LaunchOptions,
+    pub output: OutputFormat,
 }
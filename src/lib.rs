@@ -0,0 +1,6 @@
+//! Library side of zagel: helpers shared with scenario authors and
+//! downstream integration tests. The desktop app itself lives in the binary.
+
+#![allow(clippy::multiple_crate_versions)]
+
+pub mod testing;
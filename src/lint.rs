@@ -0,0 +1,68 @@
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+
+use crate::launch::LintOptions;
+use crate::list::workspace_roots;
+use crate::model::Environment;
+use crate::parser::{FILE_SCAN_MAX_DEPTH, lint_http_file, load_env_files};
+use crate::run::{collect_files, display_name};
+use crate::send::{find_environment, project_for};
+use crate::state::AppState;
+
+/// Lints every .http file under the target and returns the process exit code:
+/// 0 when clean, 1 when any issue was found and 2 when linting could not start.
+pub fn run(options: &LintOptions) -> i32 {
+    match execute(options) {
+        Ok((report, issues)) => {
+            let _ = io::stdout().lock().write_all(report.as_bytes());
+            i32::from(issues > 0)
+        }
+        Err(err) => {
+            eprintln!("lint: {err}");
+            2
+        }
+    }
+}
+
+fn execute(options: &LintOptions) -> Result<(String, usize), String> {
+    let state = AppState::load();
+    let (mut project_roots, global_env_roots) = workspace_roots(&options.launch, &state);
+    let files = collect_files(&options.target)?;
+    let project = project_for(&options.target, &mut project_roots);
+    let envs = load_env_files(project_roots, global_env_roots, FILE_SCAN_MAX_DEPTH);
+    let known = match &options.env {
+        Some(name) => known_variables([find_environment(&envs, name, Some(&project))?]),
+        None => known_variables(
+            envs.iter()
+                .filter(|env| env.visible_for_project(Some(&project))),
+        ),
+    };
+
+    let mut report = String::new();
+    let mut issues = 0;
+    for file in &files {
+        let name = display_name(&file.path, &options.target);
+        let found = lint_http_file(&file.path, &known).map_err(|err| format!("{err:#}"))?;
+        for issue in &found {
+            let _ = writeln!(report, "{name}:{issue}");
+        }
+        issues += found.len();
+    }
+    let _ = writeln!(
+        report,
+        "{issues} issue{} in {} file{}",
+        if issues == 1 { "" } else { "s" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" }
+    );
+    Ok((report, issues))
+}
+
+/// Without `--env` a variable only has to be defined in one of the
+/// environments the files could be sent with.
+fn known_variables<'a>(envs: impl IntoIterator<Item = &'a Environment>) -> BTreeSet<String> {
+    envs.into_iter()
+        .flat_map(|env| env.vars.keys().cloned())
+        .collect()
+}
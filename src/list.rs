@@ -0,0 +1,243 @@
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::launch::{LaunchOptions, ListOptions, OutputFormat};
+use crate::model::{Environment, EnvironmentScope, HttpFile};
+use crate::parser::{FILE_SCAN_MAX_DEPTH, load_env_files, load_http_files};
+use crate::pathing::{GlobalEnvRoot, ProjectRoot};
+use crate::state::AppState;
+
+#[derive(Debug, Serialize)]
+struct Listing {
+    project_roots: Vec<PathBuf>,
+    global_env_roots: Vec<PathBuf>,
+    files: Vec<FileEntry>,
+    environments: Vec<EnvironmentEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct FileEntry {
+    path: PathBuf,
+    requests: Vec<RequestEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct RequestEntry {
+    index: usize,
+    title: String,
+    method: String,
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    step: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    http_version: Option<&'static str>,
+    graphql: bool,
+}
+
+/// Variable values are left out so listings can be shared without secrets.
+#[derive(Debug, Serialize)]
+struct EnvironmentEntry {
+    name: String,
+    scope: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    root: Option<PathBuf>,
+    variables: Vec<String>,
+}
+
+/// Prints the workspace zagel sees and returns the process exit code.
+pub fn run(options: &ListOptions) -> i32 {
+    let listing = scan(options);
+    let out = match options.output {
+        OutputFormat::Text => render_text(&listing),
+        OutputFormat::Json => match serde_json::to_string_pretty(&listing) {
+            Ok(json) => json + "\n",
+            Err(err) => {
+                eprintln!("list: {err}");
+                return 1;
+            }
+        },
+    };
+    // A closed pipe (e.g. `| head`) is not an error worth reporting.
+    let _ = io::stdout().lock().write_all(out.as_bytes());
+    0
+}
+
+/// Project and global env roots from the state file, replaced by any given on
+/// the command line; roots that no longer exist are dropped.
+pub fn workspace_roots(launch: &LaunchOptions, state: &AppState) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let project_roots = if launch.project_roots.is_empty() {
+        &state.project_roots
+    } else {
+        &launch.project_roots
+    };
+    let global_env_roots = if launch.global_env_roots.is_empty() {
+        &state.global_env_roots
+    } else {
+        &launch.global_env_roots
+    };
+    (
+        project_roots
+            .iter()
+            .filter_map(|path| ProjectRoot::from_stored(path.clone()).ok())
+            .map(|root| root.to_path_buf())
+            .collect(),
+        global_env_roots
+            .iter()
+            .filter_map(|path| GlobalEnvRoot::from_stored(path.clone()).ok())
+            .map(|root| root.to_path_buf())
+            .collect(),
+    )
+}
+
+fn scan(options: &ListOptions) -> Listing {
+    let (project_roots, global_env_roots) = workspace_roots(&options.launch, &AppState::load());
+
+    let mut files = load_http_files(project_roots.clone(), FILE_SCAN_MAX_DEPTH)
+        .into_values()
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let environments = load_env_files(
+        project_roots.clone(),
+        global_env_roots.clone(),
+        FILE_SCAN_MAX_DEPTH,
+    );
+    build_listing(project_roots, global_env_roots, &files, &environments)
+}
+
+fn build_listing(
+    project_roots: Vec<PathBuf>,
+    global_env_roots: Vec<PathBuf>,
+    files: &[HttpFile],
+    environments: &[Environment],
+) -> Listing {
+    let files = files
+        .iter()
+        .map(|file| FileEntry {
+            path: file.path.clone(),
+            requests: file
+                .requests
+                .iter()
+                .enumerate()
+                .map(|(index, request)| RequestEntry {
+                    index,
+                    title: request.title.clone(),
+                    method: request.method.as_str().to_string(),
+                    url: request.url.clone(),
+                    step: request.step.clone(),
+                    http_version: request.http_version.token(),
+                    graphql: request.graphql,
+                })
+                .collect(),
+        })
+        .collect();
+    let environments = environments
+        .iter()
+        .map(|env| {
+            let (scope, root) = match &env.scope {
+                EnvironmentScope::Project(root) => ("project", Some(root.clone())),
+                EnvironmentScope::Global => ("global", None),
+                EnvironmentScope::Default => ("default", None),
+            };
+            EnvironmentEntry {
+                name: env.name.clone(),
+                scope,
+                root,
+                variables: env.vars.keys().cloned().collect(),
+            }
+        })
+        .collect();
+    Listing {
+        project_roots,
+        global_env_roots,
+        files,
+        environments,
+    }
+}
+
+fn render_text(listing: &Listing) -> String {
+    let mut out = String::new();
+    for file in &listing.files {
+        let _ = writeln!(out, "{}", display_path(&file.path, &listing.project_roots));
+        for request in &file.requests {
+            let _ = writeln!(
+                out,
+                "  #{:<3} {:<7} {}",
+                request.index, request.method, request.url
+            );
+        }
+    }
+    if !listing.environments.is_empty() {
+        let _ = writeln!(out, "\nEnvironments:");
+        for env in &listing.environments {
+            let _ = writeln!(out, "  {}", env.name);
+        }
+    }
+    out
+}
+
+fn display_path(path: &Path, roots: &[PathBuf]) -> String {
+    roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use super::build_listing;
+    use crate::model::{Environment, EnvironmentScope, HttpFile, Method, RequestDraft};
+
+    #[test]
+    fn json_listing_describes_files_requests_and_environments() {
+        let files = vec![HttpFile {
+            path: PathBuf::from("/proj/users.http"),
+            variables: Vec::new(),
+            auth: None,
+            requests: vec![RequestDraft {
+                title: "list users".to_string(),
+                method: Method::Get,
+                url: "{{base}}/users".to_string(),
+                ..RequestDraft::default()
+            }],
+        }];
+        let environments = vec![Environment {
+            name: "[project:/proj] .env".to_string(),
+            vars: BTreeMap::from([("base".to_string(), "https://secret.test".to_string())]),
+            scope: EnvironmentScope::Project(PathBuf::from("/proj")),
+        }];
+        let listing = build_listing(
+            vec![PathBuf::from("/proj")],
+            Vec::new(),
+            &files,
+            &environments,
+        );
+        let json = serde_json::to_value(&listing).unwrap();
+        assert_eq!(
+            json["files"][0]["requests"][0],
+            serde_json::json!({
+                "index": 0,
+                "title": "list users",
+                "method": "GET",
+                "url": "{{base}}/users",
+                "graphql": false
+            })
+        );
+        assert_eq!(
+            json["environments"][0],
+            serde_json::json!({
+                "name": "[project:/proj] .env",
+                "scope": "project",
+                "root": "/proj",
+                "variables": ["base"]
+            })
+        );
+    }
+}
@@ -1,18 +1,36 @@
 #![allow(clippy::multiple_crate_versions)]
 
+mod alerts;
 mod app;
+mod backup;
 mod cli;
+mod curl;
+mod doctor;
+mod env_audit;
+mod fmt;
+mod graphql;
+mod history;
+mod import;
+mod jsonpath;
 mod launch;
+mod lint;
+mod list;
 mod model;
 mod net;
 mod parser;
 mod pathing;
+mod plugins;
+mod proto;
+mod run;
+mod send;
 mod state;
 mod theme;
+mod timings;
+mod urls;
 
 fn main() -> iced::Result {
-    let launch = match cli::parse_env() {
-        Ok(launch) => launch,
+    let command = match cli::parse_env() {
+        Ok(command) => command,
         Err(cli::CliError::HelpRequested) => {
             println!("{}", cli::usage());
             return Ok(());
@@ -24,6 +42,14 @@ fn main() -> iced::Result {
         }
     };
 
+    let launch = match &command {
+        cli::Command::Launch(launch) | cli::Command::Doctor(launch) => launch,
+        cli::Command::Send(options) => &options.launch,
+        cli::Command::List(options) => &options.launch,
+        cli::Command::Run(options) => &options.launch,
+        cli::Command::Lint(options) => &options.launch,
+        cli::Command::Fmt(options) => &options.launch,
+    };
     if let Some(path) = launch.state_file.clone()
         && let Err(_existing) = state::set_state_file_override(path)
     {
@@ -31,5 +57,13 @@ fn main() -> iced::Result {
         std::process::exit(2);
     }
 
-    app::run(launch)
+    match command {
+        cli::Command::Launch(launch) => app::run(launch),
+        cli::Command::Doctor(launch) => std::process::exit(doctor::run(&launch)),
+        cli::Command::Send(options) => std::process::exit(send::run(&options)),
+        cli::Command::List(options) => std::process::exit(list::run(&options)),
+        cli::Command::Run(options) => std::process::exit(run::run(&options)),
+        cli::Command::Lint(options) => std::process::exit(lint::run(&options)),
+        cli::Command::Fmt(options) => std::process::exit(fmt::run(&options)),
+    }
 }
@@ -4,8 +4,10 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Method {
     Get,
     Post,
@@ -13,19 +15,26 @@ pub enum Method {
     Delete,
     Patch,
     Head,
+    Options,
+    Trace,
+    /// Any other method token, e.g. `PROPFIND` or `PURGE`, sent as written.
+    Custom(String),
 }
 
 impl Method {
-    pub const ALL: [Self; 6] = [
+    /// The standard methods, without `Custom`.
+    pub const ALL: [Self; 8] = [
         Self::Get,
         Self::Post,
         Self::Put,
         Self::Delete,
         Self::Patch,
         Self::Head,
+        Self::Options,
+        Self::Trace,
     ];
 
-    pub const fn as_str(self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Get => "GET",
             Self::Post => "POST",
@@ -33,7 +42,26 @@ impl Method {
             Self::Delete => "DELETE",
             Self::Patch => "PATCH",
             Self::Head => "HEAD",
+            Self::Options => "OPTIONS",
+            Self::Trace => "TRACE",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// A standard method matched case-insensitively, or a `Custom` one when
+    /// `token` is a valid HTTP token; `None` otherwise.
+    pub fn parse(token: &str) -> Option<Self> {
+        if let Some(method) = Self::ALL
+            .into_iter()
+            .find(|method| method.as_str().eq_ignore_ascii_case(token))
+        {
+            return Some(method);
         }
+        let is_token = !token.is_empty()
+            && token
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte));
+        is_token.then(|| Self::Custom(token.to_string()))
     }
 }
 
@@ -44,14 +72,229 @@ impl std::fmt::Display for Method {
 }
 
 impl From<&str> for Method {
+    /// Falls back to `GET` for anything that is not a method token.
     fn from(value: &str) -> Self {
-        match value.to_uppercase().as_str() {
-            "POST" => Self::Post,
-            "PUT" => Self::Put,
-            "DELETE" => Self::Delete,
-            "PATCH" => Self::Patch,
-            "HEAD" => Self::Head,
-            _ => Self::Get,
+        Self::parse(value).unwrap_or(Self::Get)
+    }
+}
+
+/// Protocol version requested for a send; `Auto` lets ALPN negotiate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum HttpVersion {
+    #[default]
+    Auto,
+    /// Kept so `HTTP/1.0` request lines round-trip; sent as HTTP/1.1.
+    Http10,
+    Http11,
+    Http2,
+    Http3,
+}
+
+impl HttpVersion {
+    /// Versions offered by the picker. HTTP/1.0 is only read from files and
+    /// HTTP/3 needs reqwest's `http3` feature, which this build lacks.
+    pub const PICKABLE: [Self; 3] = [Self::Auto, Self::Http11, Self::Http2];
+
+    /// Token used on the `.http` request line; `None` for `Auto`.
+    pub const fn token(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::Http10 => Some("HTTP/1.0"),
+            Self::Http11 => Some("HTTP/1.1"),
+            Self::Http2 => Some("HTTP/2"),
+            Self::Http3 => Some("HTTP/3"),
+        }
+    }
+
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token.to_ascii_uppercase().as_str() {
+            "HTTP/1.0" => Some(Self::Http10),
+            "HTTP/1.1" => Some(Self::Http11),
+            "HTTP/2" | "HTTP/2.0" => Some(Self::Http2),
+            "HTTP/3" | "HTTP/3.0" => Some(Self::Http3),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http10 => f.write_str("HTTP/1.0 (sent as 1.1)"),
+            _ => f.write_str(self.token().unwrap_or("Auto")),
+        }
+    }
+}
+
+/// Compression applied to the request body before it is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum BodyEncoding {
+    #[default]
+    Identity,
+    Gzip,
+}
+
+impl BodyEncoding {
+    pub const ALL: [Self; 2] = [Self::Identity, Self::Gzip];
+
+    /// Value of the `# @body-encoding` directive and the `Content-Encoding` header;
+    /// `None` for an uncompressed body.
+    pub const fn token(self) -> Option<&'static str> {
+        match self {
+            Self::Identity => None,
+            Self::Gzip => Some("gzip"),
+        }
+    }
+
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token.to_ascii_lowercase().as_str() {
+            "identity" | "none" => Some(Self::Identity),
+            "gzip" => Some(Self::Gzip),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for BodyEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.token().unwrap_or("No compression"))
+    }
+}
+
+/// Part of a response read by a `# @capture` directive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptureSource {
+    /// JSON path into the body; empty for the whole body text.
+    Body(String),
+    Header(String),
+    Status,
+}
+
+/// `# @capture name = source`: stores a value from a successful response as
+/// a session variable, e.g. `token = body.$.access_token`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capture {
+    pub name: String,
+    pub source: CaptureSource,
+}
+
+impl Capture {
+    /// Parses the directive value: `name = body[.<json path>]`,
+    /// `name = header.<Name>` or `name = status`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (name, source) = value.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return None;
+        }
+        let source = source.trim();
+        let source = if source == "body" {
+            CaptureSource::Body(String::new())
+        } else if let Some(path) = source.strip_prefix("body.") {
+            CaptureSource::Body(path.trim().to_string())
+        } else if let Some(header) = source.strip_prefix("header.") {
+            CaptureSource::Header(header.trim().to_string())
+        } else if source == "status" {
+            CaptureSource::Status
+        } else {
+            return None;
+        };
+        Some(Self {
+            name: name.to_string(),
+            source,
+        })
+    }
+}
+
+impl std::fmt::Display for Capture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            CaptureSource::Body(path) if path.is_empty() => write!(f, "{} = body", self.name),
+            CaptureSource::Body(path) => write!(f, "{} = body.{path}", self.name),
+            CaptureSource::Header(name) => write!(f, "{} = header.{name}", self.name),
+            CaptureSource::Status => write!(f, "{} = status", self.name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AssertionKind {
+    #[default]
+    Status,
+    Header,
+    BodyContains,
+    JsonPath,
+}
+
+impl AssertionKind {
+    pub const ALL: [Self; 4] = [
+        Self::Status,
+        Self::Header,
+        Self::BodyContains,
+        Self::JsonPath,
+    ];
+}
+
+impl std::fmt::Display for AssertionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Status => "Status is",
+            Self::Header => "Header equals",
+            Self::BodyContains => "Body contains",
+            Self::JsonPath => "JSON path equals",
+        })
+    }
+}
+
+/// `# @assert` directive checked against every response. `target` is the
+/// header name or JSON path; it is unused for status and body checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Assertion {
+    pub kind: AssertionKind,
+    pub target: String,
+    pub expected: String,
+}
+
+impl Assertion {
+    /// Parses `status == 200`, `header.<Name> == value`, `body contains text`
+    /// or `body.<json path> == value`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(text) = value.strip_prefix("body contains ") {
+            return Some(Self {
+                kind: AssertionKind::BodyContains,
+                target: String::new(),
+                expected: text.trim().to_string(),
+            });
+        }
+        let (subject, expected) = value.split_once("==")?;
+        let subject = subject.trim();
+        let (kind, target) = if subject == "status" {
+            (AssertionKind::Status, "")
+        } else if let Some(name) = subject.strip_prefix("header.") {
+            (AssertionKind::Header, name)
+        } else if let Some(path) = subject.strip_prefix("body.") {
+            (AssertionKind::JsonPath, path)
+        } else {
+            return None;
+        };
+        Some(Self {
+            kind,
+            target: target.trim().to_string(),
+            expected: expected.trim().to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for Assertion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let expected = self.expected.trim();
+        let target = self.target.trim();
+        match self.kind {
+            AssertionKind::Status => write!(f, "status == {expected}"),
+            AssertionKind::Header => write!(f, "header.{target} == {expected}"),
+            AssertionKind::BodyContains => write!(f, "body contains {expected}"),
+            AssertionKind::JsonPath => write!(f, "body.{target} == {expected}"),
         }
     }
 }
@@ -59,22 +302,207 @@ impl From<&str> for Method {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestDraft {
     pub title: String,
+    /// Markdown description, saved as the plain comment lines under the
+    /// `###` separator.
+    #[serde(default)]
+    pub notes: String,
     pub method: Method,
     pub url: String,
     pub headers: String,
     pub body: String,
+    /// From a `# @name` directive, as in REST Client; later requests refer
+    /// to the request by it.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// From a `# @id` directive. Zagel only writes one when asked to with
+    /// **Add stable id**; copies drop it so ids stay unique.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Human-readable description from a `# @step` directive.
+    #[serde(default)]
+    pub step: Option<String>,
+    #[serde(default)]
+    pub http_version: HttpVersion,
+    /// Set from a `# @body-encoding` directive.
+    #[serde(default)]
+    pub body_encoding: BodyEncoding,
+    /// Set from a `# @graphql` directive; the body is the JSON payload with
+    /// `query` and `variables`, reopened in GraphQL mode.
+    #[serde(default)]
+    pub graphql: bool,
+    /// From `# @capture` directives, applied after a successful send.
+    #[serde(default)]
+    pub captures: Vec<Capture>,
+    /// From `# @assert` directives, checked after every send.
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+    #[serde(default)]
+    pub options: RequestOptions,
+    /// Values of `:id`/`{id}` path parameters from `# @path-param`
+    /// directives, filled into the URL on send.
+    #[serde(default)]
+    pub path_params: Vec<(String, String)>,
+    /// From `# @tags`, for filtering the sidebar and running a group.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Per-request behavior set by directives, so the .http file decides how a
+/// request is sent rather than app settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestOptions {
+    /// `# @no-redirect`: return 3xx responses instead of following them.
+    #[serde(default)]
+    pub no_redirect: bool,
+    /// `# @no-cookie-jar`: neither send nor store cookies from the jar.
+    #[serde(default)]
+    pub no_cookie_jar: bool,
+    /// `# @timeout 5s`: give up when the whole exchange takes longer.
+    #[serde(default)]
+    pub timeout: Option<Duration>,
+    #[serde(default)]
+    pub priority: Priority,
+    /// `# @login [lifetime]`: the request signs in, and its captures are
+    /// tokens refreshed by sending it again before they expire.
+    #[serde(default)]
+    pub login: bool,
+    /// How long the login's tokens last when neither a JWT `exp` claim nor an
+    /// `expires_in` field in the response says.
+    #[serde(default)]
+    pub login_lifetime: Option<Duration>,
+}
+
+/// `# @priority`: how loudly runners report a failure of the request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Priority {
+    /// Failures are only logged and do not fail `zagel run`.
+    Low,
+    #[default]
+    Normal,
+    /// Failures also raise an OS notification.
+    High,
+}
+
+impl Priority {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
+
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token.to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "normal" => Some(Self::Normal),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl RequestOptions {
+    /// `500ms`, `5s` or `2m`; a bare number is seconds.
+    pub fn parse_timeout(value: &str) -> Option<Duration> {
+        let value = value.trim();
+        let (number, unit) = value
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or((value, ""), |at| value.split_at(at));
+        let number = number.parse::<u64>().ok().filter(|number| *number > 0)?;
+        match unit.trim() {
+            "ms" => Some(Duration::from_millis(number)),
+            "" | "s" => Some(Duration::from_secs(number)),
+            "m" => Some(Duration::from_secs(number * 60)),
+            _ => None,
+        }
+    }
+
+    /// Directive value for `login`: the lifetime in seconds, if any.
+    pub fn login_token(&self) -> Option<String> {
+        self.login_lifetime
+            .map(|lifetime| format!("{}s", lifetime.as_secs()))
+    }
+
+    /// Directive value for `timeout`, the inverse of [`Self::parse_timeout`].
+    pub fn timeout_token(&self) -> Option<String> {
+        self.timeout.map(|timeout| {
+            if timeout.subsec_millis() == 0 {
+                format!("{}s", timeout.as_secs())
+            } else {
+                format!("{}ms", timeout.as_millis())
+            }
+        })
+    }
 }
 
 impl Default for RequestDraft {
     fn default() -> Self {
         Self {
             title: "Untitled request".to_string(),
+            notes: String::new(),
             method: Method::Get,
             url: String::from("https://example.com"),
             headers: String::new(),
             body: String::new(),
+            name: None,
+            id: None,
+            step: None,
+            http_version: HttpVersion::Auto,
+            body_encoding: BodyEncoding::Identity,
+            graphql: false,
+            captures: Vec::new(),
+            assertions: Vec::new(),
+            options: RequestOptions::default(),
+            path_params: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl RequestDraft {
+    /// Fixes the `{{$…}}` values before a send, so history and the send log
+    /// show the values that went out.
+    pub fn resolve_dynamic(&mut self) {
+        let now = OffsetDateTime::now_utc();
+        for field in [&mut self.url, &mut self.headers, &mut self.body] {
+            *field = resolve_dynamic(field, now);
         }
     }
+
+    /// Sidebar label: the `@name` when present, otherwise the title.
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.title)
+    }
+
+    /// Label used by runners and reports: the step description when present,
+    /// otherwise `METHOD url`.
+    pub fn step_label(&self) -> String {
+        self.step
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", self.method, self.url))
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// Tags of a `# @tags` value, separated by spaces or commas, without repeats.
+pub fn parse_tags(value: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in value.split([' ', ',', '\t']).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|known| known.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
 }
 
 #[derive(Debug, Clone)]
@@ -98,26 +526,267 @@ impl Environment {
             EnvironmentScope::Global | EnvironmentScope::Default => true,
         }
     }
+
+    /// Layers an .http file's variables over this environment's own. A
+    /// value may use environment variables and the file variables above it.
+    pub fn with_file_variables(mut self, variables: &[(String, String)]) -> Self {
+        let mut layered = BTreeMap::new();
+        for (name, value) in variables {
+            let value = apply_environment(&apply_environment(value, &layered), &self.vars);
+            layered.insert(name.clone(), value);
+        }
+        self.vars.append(&mut layered);
+        self
+    }
+
+    /// Fills variables a project environment leaves out from the global
+    /// environments of the same short name (`dev` for `dev.env`), so project
+    /// values win over global ones.
+    pub fn with_global_fallback(mut self, envs: &[Self]) -> Self {
+        if !matches!(self.scope, EnvironmentScope::Project(_)) {
+            return self;
+        }
+        let name = short_env_name(&self.name).to_string();
+        for global in envs.iter().filter(|env| {
+            env.scope == EnvironmentScope::Global && short_env_name(&env.name) == name
+        }) {
+            for (key, value) in &global.vars {
+                self.vars
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+        self
+    }
+}
+
+/// `[project:/p] envs/dev.env` → `dev`; `.env.dev` → `dev`; `.env` stays `.env`;
+/// `http-client.env.json#dev` → `dev`.
+pub fn short_env_name(label: &str) -> &str {
+    let relative = label
+        .split_once("] ")
+        .map_or(label, |(_, relative)| relative);
+    let file = relative.rsplit(['/', '\\']).next().unwrap_or(relative);
+    if let Some((_, name)) = file.split_once(".env.json#") {
+        return name;
+    }
+    file.strip_prefix(".env.")
+        .or_else(|| file.strip_suffix(".env").filter(|stem| !stem.is_empty()))
+        .unwrap_or(file)
+}
+
+/// Where a variable's value comes from, lowest precedence first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VariableSource {
+    Global,
+    Project,
+    File,
+    Session,
+    Override,
+}
+
+impl VariableSource {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global env",
+            Self::Project => "project env",
+            Self::File => "file",
+            Self::Session => "session",
+            Self::Override => "override",
+        }
+    }
+}
+
+impl std::fmt::Display for VariableSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct HttpFile {
     pub path: PathBuf,
+    /// `@name = value` declarations at the top of the file, in order.
+    pub variables: Vec<(String, String)>,
+    /// `# @auth` at the top of the file, inherited by its requests.
+    pub auth: Option<FileAuth>,
     pub requests: Vec<RequestDraft>,
 }
 
+/// Default auth of an .http file. Values may use `{{variables}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileAuth {
+    Bearer(String),
+    Basic { username: String, password: String },
+    ApiKey { header: String, value: String },
+}
+
+impl FileAuth {
+    /// Parses the value of `# @auth`: `bearer <token>`, `basic <user> <password>`
+    /// or `apikey <header> <value>`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (scheme, rest) = value
+            .split_once(char::is_whitespace)
+            .map_or((value, ""), |(scheme, rest)| (scheme, rest.trim()));
+        let pair = |what: &str| {
+            rest.split_once(char::is_whitespace)
+                .map(|(first, second)| (first.to_string(), second.trim().to_string()))
+                .ok_or_else(|| format!("@auth {scheme} needs {what}"))
+        };
+        match scheme.to_ascii_lowercase().as_str() {
+            "bearer" if !rest.is_empty() => Ok(Self::Bearer(rest.to_string())),
+            "bearer" => Err("@auth bearer needs a token".to_string()),
+            "basic" => pair("a username and a password")
+                .map(|(username, password)| Self::Basic { username, password }),
+            "apikey" => pair("a header name and a value")
+                .map(|(header, value)| Self::ApiKey { header, value }),
+            _ => Err(format!("unknown auth scheme {scheme:?}")),
+        }
+    }
+
+    /// The header this auth sets; a request that sets it itself overrides
+    /// the file's auth.
+    pub fn header_name(&self) -> &str {
+        match self {
+            Self::Bearer(_) | Self::Basic { .. } => "Authorization",
+            Self::ApiKey { header, .. } => header,
+        }
+    }
+}
+
+impl std::fmt::Display for FileAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bearer(token) => write!(f, "bearer {token}"),
+            Self::Basic { username, password } => write!(f, "basic {username} {password}"),
+            Self::ApiKey { header, value } => write!(f, "apikey {header} {value}"),
+        }
+    }
+}
+
+/// Position of a request in the loaded workspace. It is remapped when a
+/// rescan or edit moves requests around; [`RequestRef`] is what gets
+/// persisted, and survives reordering for requests with an id or name.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RequestId {
     HttpFile { path: PathBuf, index: usize },
 }
 
+/// What identifies a request within its file: its `# @id`, else its
+/// `# @name`, else its position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RequestKey {
+    Id(String),
+    Name(String),
+    Index(usize),
+}
+
+/// Stable reference to a saved request, written as `path#id=…`,
+/// `path#name=…` or `path#index` in history and state.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestRef {
+    pub path: PathBuf,
+    pub key: RequestKey,
+}
+
+impl RequestRef {
+    pub fn new(path: &Path, index: usize, draft: &RequestDraft) -> Self {
+        let key = draft.id.clone().map_or_else(
+            || {
+                draft
+                    .name
+                    .clone()
+                    .map_or(RequestKey::Index(index), RequestKey::Name)
+            },
+            RequestKey::Id,
+        );
+        Self {
+            path: path.to_path_buf(),
+            key,
+        }
+    }
+
+    /// Reference to `id` in `file`, which must be the file it points into.
+    pub fn of(id: &RequestId, file: Option<&HttpFile>) -> Self {
+        let RequestId::HttpFile { path, index } = id;
+        file.and_then(|file| file.requests.get(*index)).map_or_else(
+            || Self {
+                path: path.clone(),
+                key: RequestKey::Index(*index),
+            },
+            |draft| Self::new(path, *index, draft),
+        )
+    }
+
+    /// Where the request is now, if `file` still has it.
+    pub fn resolve(&self, file: &HttpFile) -> Option<RequestId> {
+        let index = match &self.key {
+            RequestKey::Id(id) => file
+                .requests
+                .iter()
+                .position(|draft| draft.id.as_ref() == Some(id))?,
+            RequestKey::Name(name) => file
+                .requests
+                .iter()
+                .position(|draft| draft.name.as_ref() == Some(name))?,
+            RequestKey::Index(index) => (*index < file.requests.len()).then_some(*index)?,
+        };
+        Some(RequestId::HttpFile {
+            path: self.path.clone(),
+            index,
+        })
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        // Names and ids may contain `#`, so split at the key marker rather
+        // than the last `#`.
+        let marked = ["#id=", "#name="]
+            .into_iter()
+            .filter_map(|marker| raw.find(marker).map(|at| (at, marker)))
+            .min();
+        let (path, key) = if let Some((at, marker)) = marked {
+            let value = raw[at + marker.len()..].to_string();
+            let key = if marker == "#id=" {
+                RequestKey::Id(value)
+            } else {
+                RequestKey::Name(value)
+            };
+            (&raw[..at], key)
+        } else {
+            let (path, index) = raw.rsplit_once('#')?;
+            (path, RequestKey::Index(index.parse().ok()?))
+        };
+        Some(Self {
+            path: PathBuf::from(path),
+            key,
+        })
+    }
+}
+
+impl std::fmt::Display for RequestRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self.path.display();
+        match &self.key {
+            RequestKey::Id(id) => write!(f, "{path}#id={id}"),
+            RequestKey::Name(name) => write!(f, "{path}#name={name}"),
+            RequestKey::Index(index) => write!(f, "{path}#{index}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ResponsePreview {
     pub status: Option<u16>,
     pub duration: Option<Duration>,
-    pub body: Option<String>,
+    /// Body bytes exactly as received, so binary payloads survive intact.
+    pub body: Option<Vec<u8>>,
     pub headers: Vec<(String, String)>,
     pub error: Option<String>,
+    /// Negotiated protocol version, e.g. `HTTP/2.0`.
+    pub version: Option<String>,
+    /// URL the response came from, after any redirects.
+    pub url: Option<String>,
 }
 
 impl ResponsePreview {
@@ -128,8 +797,17 @@ impl ResponsePreview {
             body: None,
             headers: Vec::new(),
             error: Some(message),
+            version: None,
+            url: None,
         }
     }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.as_str())
+    }
 }
 
 pub fn apply_environment(input: &str, vars: &BTreeMap<String, String>) -> String {
@@ -138,5 +816,146 @@ pub fn apply_environment(input: &str, vars: &BTreeMap<String, String>) -> String
         let needle = format!("{{{{{key}}}}}");
         out = out.replace(&needle, value);
     }
+    resolve_dynamic(&out, OffsetDateTime::now_utc())
+}
+
+/// Replaces built-in `{{$…}}` placeholders; every occurrence gets its own
+/// value. Unknown or malformed ones are left as they are.
+fn resolve_dynamic(input: &str, now: OffsetDateTime) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(open) = rest.find("{{$") {
+        let Some(close) = rest[open..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[open..open + close + 2];
+        out.push_str(&rest[..open]);
+        match dynamic_value(&placeholder[2..placeholder.len() - 2], now) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(placeholder),
+        }
+        rest = &rest[open + close + 2..];
+    }
+    out.push_str(rest);
     out
 }
+
+/// Value of a dynamic placeholder as in REST Client: `$uuid` (or `$guid`),
+/// `$timestamp` (Unix seconds), `$randomInt <min> <max>` (max exclusive)
+/// and `$datetime iso8601|rfc1123`.
+fn dynamic_value(expression: &str, now: OffsetDateTime) -> Option<String> {
+    let mut parts = expression.split_whitespace();
+    let name = parts.next()?;
+    let args = parts.collect::<Vec<_>>();
+    match (name, args.as_slice()) {
+        ("$uuid" | "$guid", []) => Some(Uuid::new_v4().to_string()),
+        ("$timestamp", []) => Some(now.unix_timestamp().to_string()),
+        ("$randomInt", [min, max]) => {
+            let (min, max) = (min.parse::<i64>().ok()?, max.parse::<i64>().ok()?);
+            let span = u128::try_from(max.checked_sub(min)?)
+                .ok()
+                .filter(|span| *span > 0)?;
+            let offset = i64::try_from(Uuid::new_v4().as_u128() % span).ok()?;
+            Some((min + offset).to_string())
+        }
+        ("$datetime", [] | ["iso8601"]) => Some(format!(
+            "{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second(),
+            now.millisecond()
+        )),
+        ("$datetime", ["rfc1123"]) => Some(format!(
+            "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            &now.weekday().to_string()[..3],
+            now.day(),
+            &now.month().to_string()[..3],
+            now.year(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        )),
+        _ => None,
+    }
+}
+
+/// Names inside `{{...}}` placeholders, in order of appearance. Built-in
+/// `{{$…}}` values are not variables and are skipped.
+pub fn placeholder_names(input: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut search_start = 0;
+
+    while let Some(open_rel) = input[search_start..].find("{{") {
+        let open = search_start + open_rel;
+        let after_open = open + 2;
+        if let Some(close_rel) = input[after_open..].find("}}") {
+            let close = after_open + close_rel;
+            let candidate = input[after_open..close].trim();
+            if !candidate.is_empty() && !candidate.starts_with('$') {
+                names.push(candidate.to_string());
+            }
+            search_start = close + 2;
+        } else {
+            break;
+        }
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use time::macros::datetime;
+
+    use super::{RequestKey, RequestRef, apply_environment, placeholder_names, resolve_dynamic};
+
+    #[test]
+    fn request_refs_round_trip_names_with_hashes() {
+        let refs = [
+            RequestKey::Name("issue #12 #name=x".to_string()),
+            RequestKey::Id("a#b".to_string()),
+            RequestKey::Index(3),
+        ]
+        .map(|key| RequestRef {
+            path: PathBuf::from("/c#/api.http"),
+            key,
+        });
+        for reference in refs {
+            assert_eq!(
+                RequestRef::parse(&reference.to_string()),
+                Some(reference.clone())
+            );
+        }
+        assert_eq!(RequestRef::parse("api.http#first"), None);
+    }
+
+    #[test]
+    fn dynamic_placeholders_resolve_at_send_time() {
+        let now = datetime!(2024-03-05 07:08:09.123 UTC);
+        assert_eq!(
+            resolve_dynamic("{{$timestamp}} {{$datetime iso8601}}", now),
+            "1709622489 2024-03-05T07:08:09.123Z"
+        );
+        assert_eq!(
+            resolve_dynamic("{{$datetime rfc1123}}", now),
+            "Tue, 05 Mar 2024 07:08:09 GMT"
+        );
+        assert_eq!(resolve_dynamic("{{$randomInt 5 6}}", now), "5");
+        assert_eq!(
+            resolve_dynamic("{{$randomInt 6 5}} {{$nope}}", now),
+            "{{$randomInt 6 5}} {{$nope}}"
+        );
+
+        let ids = apply_environment("{{$uuid}}/{{$uuid}}", &BTreeMap::new());
+        let (first, second) = ids.split_once('/').unwrap();
+        assert_eq!(first.len(), 36);
+        assert_ne!(first, second);
+        assert_eq!(placeholder_names("{{$uuid}} {{token}}"), ["token"]);
+    }
+}
@@ -0,0 +1,267 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use reqwest::Client;
+use serde_json::{Map, Value};
+
+use super::{SendOutcome, percent_decode};
+use crate::app::{AuthState, apply_auth_headers};
+use crate::model::{Environment, ResponsePreview, apply_environment};
+use crate::proto::{Method, ProtoSchema};
+
+/// A call as laid out in the builder, before environment substitution.
+#[derive(Debug, Clone)]
+pub struct GrpcCall {
+    /// Server address, e.g. `http://localhost:50051`.
+    pub base_url: String,
+    /// Fully qualified service name.
+    pub service: String,
+    pub method: String,
+    /// JSON message; a JSON array sends one message per element to
+    /// client-streaming methods.
+    pub message: String,
+    /// `Name: value` lines sent as request metadata.
+    pub metadata: String,
+}
+
+/// Prefixes a message with the gRPC length-prefixed framing (uncompressed).
+fn frame(message: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+    let len = u32::try_from(message.len()).map_err(|_| "Message is too large to send")?;
+    out.push(0);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(message);
+    Ok(())
+}
+
+fn unframe(mut body: &[u8]) -> Result<Vec<&[u8]>, String> {
+    let mut messages = Vec::new();
+    while !body.is_empty() {
+        let Some((&[compressed, a, b, c, d], rest)) = body.split_first_chunk::<5>() else {
+            return Err("Truncated gRPC frame header".to_string());
+        };
+        if compressed != 0 {
+            return Err("Compressed gRPC messages are not supported".to_string());
+        }
+        let len = usize::try_from(u32::from_be_bytes([a, b, c, d]))
+            .map_err(|_| "gRPC frame is too large")?;
+        if len > rest.len() {
+            return Err("Truncated gRPC frame".to_string());
+        }
+        let (message, rest) = rest.split_at(len);
+        messages.push(message);
+        body = rest;
+    }
+    Ok(messages)
+}
+
+const fn status_name(code: u32) -> &'static str {
+    match code {
+        0 => "OK",
+        1 => "CANCELLED",
+        2 => "UNKNOWN",
+        3 => "INVALID_ARGUMENT",
+        4 => "DEADLINE_EXCEEDED",
+        5 => "NOT_FOUND",
+        6 => "ALREADY_EXISTS",
+        7 => "PERMISSION_DENIED",
+        8 => "RESOURCE_EXHAUSTED",
+        9 => "FAILED_PRECONDITION",
+        10 => "ABORTED",
+        11 => "OUT_OF_RANGE",
+        12 => "UNIMPLEMENTED",
+        13 => "INTERNAL",
+        14 => "UNAVAILABLE",
+        15 => "DATA_LOSS",
+        16 => "UNAUTHENTICATED",
+        _ => "UNRECOGNIZED",
+    }
+}
+
+/// Error reported by `grpc-status`/`grpc-message`. Only trailers-only responses
+/// (the usual shape of a failed call) carry these as headers that reqwest can
+/// see; a status sent in real trailers is not visible here.
+fn grpc_error(headers: &[(String, String)]) -> Option<String> {
+    let header = |wanted: &str| {
+        headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+            .map(|(_, value)| value.trim())
+    };
+    let code = header("grpc-status")?.parse::<u32>().ok()?;
+    if code == 0 {
+        return None;
+    }
+    let message = header("grpc-message")
+        .map(percent_decode)
+        .unwrap_or_default();
+    Some(
+        format!("{} ({code}): {message}", status_name(code))
+            .trim_end_matches(": ")
+            .to_string(),
+    )
+}
+
+/// Frames the JSON request; client-streaming methods take an array of messages.
+fn encode_messages(
+    schema: &ProtoSchema,
+    method: &Method,
+    message: &str,
+) -> Result<Vec<u8>, String> {
+    let json = if message.trim().is_empty() {
+        Value::Object(Map::new())
+    } else {
+        serde_json::from_str(message)
+            .map_err(|err| format!("Request message is not valid JSON: {err}"))?
+    };
+    let messages = match json {
+        Value::Array(items) if method.client_streaming => items,
+        other => vec![other],
+    };
+    let mut body = Vec::new();
+    for message in &messages {
+        frame(&schema.encode(&method.input, message)?, &mut body)?;
+    }
+    Ok(body)
+}
+
+/// Makes a unary or streaming call over HTTP/2 and decodes the response
+/// messages to JSON. Streaming responses are shown once the call completes.
+pub async fn call(
+    client: Client,
+    schema: Arc<ProtoSchema>,
+    call: GrpcCall,
+    env: Option<Environment>,
+    auth: AuthState,
+) -> Result<SendOutcome, String> {
+    if auth.is_oauth2()
+        || matches!(
+            auth,
+            AuthState::Digest(_) | AuthState::OAuth1(_) | AuthState::Hmac(_)
+        )
+    {
+        return Err(format!("{} is not supported for gRPC calls", auth.kind()));
+    }
+    let vars = env.map(|env| env.vars).unwrap_or_default();
+    let method = schema
+        .method(&call.service, &call.method)
+        .ok_or_else(|| format!("Unknown method {}/{}", call.service, call.method))?
+        .clone();
+
+    let body = encode_messages(&schema, &method, &apply_environment(&call.message, &vars))?;
+
+    let base_url = apply_environment(&call.base_url, &vars);
+    let url = format!(
+        "{}/{}/{}",
+        base_url.trim().trim_end_matches('/'),
+        call.service,
+        method.name
+    );
+    let mut request = client
+        .post(&url)
+        .header(reqwest::header::CONTENT_TYPE, "application/grpc")
+        .header(reqwest::header::TE, "trailers");
+    let metadata = apply_auth_headers(&apply_environment(&call.metadata, &vars), &auth);
+    for line in metadata.lines() {
+        if let Some((name, value)) = line.split_once(':') {
+            request = request.header(name.trim(), value.trim());
+        }
+    }
+
+    let start = Instant::now();
+    let response = request
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| format!("gRPC call to {url} failed: {err}"))?;
+    let status = response.status().as_u16();
+    let version = format!("{:?}", response.version());
+    let mut headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.to_string(), value.to_string()))
+        })
+        .collect();
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|err| format!("Failed to read gRPC response: {err}"))?;
+    let duration = start.elapsed();
+
+    let mut error = grpc_error(&headers);
+    let decoded = unframe(&bytes).and_then(|frames| {
+        frames
+            .into_iter()
+            .map(|frame| schema.decode(&method.output, frame))
+            .collect::<Result<Vec<_>, _>>()
+    });
+    let body = match decoded {
+        Ok(values) if values.is_empty() => None,
+        Ok(mut values) => {
+            let value = if method.server_streaming {
+                Value::Array(values)
+            } else {
+                values.swap_remove(0)
+            };
+            // The viewer shows the decoded JSON, not the protobuf frames.
+            headers.retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+            headers.push((
+                "content-type".to_string(),
+                "application/json (decoded from application/grpc)".to_string(),
+            ));
+            serde_json::to_vec_pretty(&value).ok()
+        }
+        Err(err) => {
+            error = Some(error.map_or_else(|| err.clone(), |error| format!("{error}; {err}")));
+            Some(bytes.to_vec())
+        }
+    };
+
+    Ok(SendOutcome {
+        response: ResponsePreview {
+            status: Some(status),
+            duration: Some(duration),
+            body,
+            headers,
+            error,
+            version: Some(version),
+            url: None,
+        },
+        oauth2_cache: None,
+        hook_output: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{frame, grpc_error, unframe};
+
+    #[test]
+    fn frames_round_trip() {
+        let mut body = Vec::new();
+        frame(b"\x0a\x02hi", &mut body).unwrap();
+        frame(b"", &mut body).unwrap();
+        assert_eq!(&body[..5], &[0, 0, 0, 0, 4]);
+        assert_eq!(unframe(&body).unwrap(), vec![&b"\x0a\x02hi"[..], &b""[..]]);
+        assert!(unframe(&body[..7]).is_err());
+    }
+
+    #[test]
+    fn status_headers_become_errors() {
+        let headers = |code: &str| {
+            vec![
+                ("grpc-status".to_string(), code.to_string()),
+                ("grpc-message".to_string(), "no%20such%20user".to_string()),
+            ]
+        };
+        assert_eq!(grpc_error(&headers("0")), None);
+        assert_eq!(
+            grpc_error(&headers("5")),
+            Some("NOT_FOUND (5): no such user".to_string())
+        );
+        assert_eq!(grpc_error(&[]), None);
+    }
+}
@@ -0,0 +1,193 @@
+//! OAuth 1.0a request signing (RFC 5849) with HMAC-SHA1 or HMAC-SHA256.
+
+use std::fmt::Write as _;
+
+use base64::{Engine, engine::general_purpose};
+use reqwest::Url;
+use ring::hmac;
+use uuid::Uuid;
+
+use crate::app::OAuth1SignatureMethod as SignatureMethod;
+
+const fn algorithm(method: SignatureMethod) -> hmac::Algorithm {
+    match method {
+        SignatureMethod::HmacSha1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+        SignatureMethod::HmacSha256 => hmac::HMAC_SHA256,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuth1Credentials {
+    pub consumer_key: String,
+    pub consumer_secret: String,
+    /// Empty for two-legged requests signed with the consumer alone.
+    pub token: String,
+    pub token_secret: String,
+    pub signature_method: SignatureMethod,
+}
+
+/// RFC 3986 percent-encoding: everything but unreserved characters.
+fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Decoded `name=value` pairs of a query string or form body.
+fn form_pairs(input: &str) -> Vec<(String, String)> {
+    let mut query = Url::parse("http://localhost/").expect("static URL parses");
+    query.set_query(Some(input));
+    query.query_pairs().into_owned().collect()
+}
+
+impl OAuth1Credentials {
+    fn protocol_params(&self, nonce: &str, timestamp: u64) -> Vec<(String, String)> {
+        let mut params = vec![
+            ("oauth_consumer_key", self.consumer_key.clone()),
+            ("oauth_nonce", nonce.to_string()),
+            ("oauth_signature_method", self.signature_method.to_string()),
+            ("oauth_timestamp", timestamp.to_string()),
+            ("oauth_version", "1.0".to_string()),
+        ];
+        if !self.token.is_empty() {
+            params.push(("oauth_token", self.token.clone()));
+        }
+        params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    /// The `Authorization` value signing `method` and `url`; `form_body` is
+    /// an `application/x-www-form-urlencoded` body, whose fields are signed
+    /// too.
+    fn authorization_with(
+        &self,
+        method: &str,
+        url: &str,
+        form_body: Option<&str>,
+        nonce: &str,
+        timestamp: u64,
+    ) -> Result<String, String> {
+        let url = Url::parse(url).map_err(|err| format!("Invalid URL {url}: {err}"))?;
+        let mut base_url = url.clone();
+        base_url.set_query(None);
+        base_url.set_fragment(None);
+
+        let protocol = self.protocol_params(nonce, timestamp);
+        let mut params = url
+            .query_pairs()
+            .into_owned()
+            .chain(form_body.map(form_pairs).unwrap_or_default())
+            .chain(protocol.iter().cloned())
+            .map(|(name, value)| (encode(&name), encode(&value)))
+            .collect::<Vec<_>>();
+        params.sort();
+        let normalized = params
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("&");
+        let base_string = format!(
+            "{}&{}&{}",
+            method.to_ascii_uppercase(),
+            encode(base_url.as_str()),
+            encode(&normalized)
+        );
+        let key = format!(
+            "{}&{}",
+            encode(&self.consumer_secret),
+            encode(&self.token_secret)
+        );
+        let key = hmac::Key::new(algorithm(self.signature_method), key.as_bytes());
+        let signature =
+            general_purpose::STANDARD.encode(hmac::sign(&key, base_string.as_bytes()).as_ref());
+
+        let mut header = String::from("OAuth ");
+        for (index, (name, value)) in protocol
+            .iter()
+            .chain(std::iter::once(&("oauth_signature".to_string(), signature)))
+            .enumerate()
+        {
+            if index > 0 {
+                header.push_str(", ");
+            }
+            let _ = write!(header, "{name}=\"{}\"", encode(value));
+        }
+        Ok(header)
+    }
+
+    /// Signs the request with a fresh nonce and the current time.
+    pub fn authorization(
+        &self,
+        method: &str,
+        url: &str,
+        form_body: Option<&str>,
+    ) -> Result<String, String> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.authorization_with(
+            method,
+            url,
+            form_body,
+            &Uuid::new_v4().simple().to_string(),
+            timestamp,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OAuth1Credentials, SignatureMethod, encode};
+
+    #[test]
+    fn signature_matches_the_twitter_documentation_example() {
+        let credentials = OAuth1Credentials {
+            consumer_key: "xvz1evFS4wEEPTGEFPHBog".to_string(),
+            consumer_secret: "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw".to_string(),
+            token: "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb".to_string(),
+            token_secret: "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE".to_string(),
+            signature_method: SignatureMethod::HmacSha1,
+        };
+        let header = credentials
+            .authorization_with(
+                "post",
+                "https://api.twitter.com/1.1/statuses/update.json?include_entities=true",
+                Some(
+                    "status=Hello%20Ladies%20%2b%20Gentlemen%2c%20a%20signed%20OAuth%20request%21",
+                ),
+                "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg",
+                1_318_622_958,
+            )
+            .unwrap();
+        assert!(header.starts_with("OAuth oauth_consumer_key=\"xvz1evFS4wEEPTGEFPHBog\""));
+        assert!(
+            header.contains("oauth_token=\"370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb\"")
+        );
+        assert!(header.ends_with("oauth_signature=\"hCtSmYh%2BiHYCEqBWrE7C7hYmtUk%3D\""));
+    }
+
+    #[test]
+    fn two_legged_requests_omit_the_token() {
+        let credentials = OAuth1Credentials {
+            consumer_key: "key".to_string(),
+            consumer_secret: "secret".to_string(),
+            token: String::new(),
+            token_secret: String::new(),
+            signature_method: SignatureMethod::HmacSha256,
+        };
+        let header = credentials
+            .authorization_with("GET", "https://api.test/a b", None, "n", 1)
+            .unwrap();
+        assert!(!header.contains("oauth_token="));
+        assert!(header.contains("oauth_signature_method=\"HMAC-SHA256\""));
+        assert_eq!(encode("a b+~*"), "a%20b%2B~%2A");
+    }
+}
@@ -0,0 +1,232 @@
+//! Browser sign-in for the `OAuth2` authorization code flow with PKCE (RFC 7636).
+
+use std::io::{BufRead as _, BufReader, ErrorKind, Write as _};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use base64::{Engine, engine::general_purpose};
+use iced::futures::channel::oneshot;
+use reqwest::Url;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const CALLBACK_PATH: &str = "/callback";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Self {
+        Self::from_verifier(format!(
+            "{}{}",
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        ))
+    }
+
+    /// Derives the `S256` challenge of `verifier`.
+    fn from_verifier(verifier: String) -> Self {
+        let challenge =
+            general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// The sign-in page the browser is sent to.
+pub fn authorize_url(
+    base: &str,
+    client_id: &str,
+    redirect_uri: &str,
+    scope: &str,
+    state: &str,
+    challenge: &str,
+) -> Result<String, String> {
+    let mut url = Url::parse(base)
+        .map_err(|err| format!("Invalid OAuth2 authorization URL {base}: {err}"))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", client_id)
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("state", state)
+            .append_pair("code_challenge", challenge)
+            .append_pair("code_challenge_method", "S256");
+        if !scope.is_empty() {
+            query.append_pair("scope", scope);
+        }
+    }
+    Ok(url.into())
+}
+
+/// Loopback listener that catches the redirect back from the sign-in page.
+#[derive(Debug)]
+pub struct RedirectListener {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl RedirectListener {
+    /// Port `0` picks a free one.
+    pub fn bind(port: u16) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).map_err(|err| {
+            format!("Failed to listen for the OAuth2 redirect on port {port}: {err}")
+        })?;
+        let port = listener
+            .local_addr()
+            .map_err(|err| format!("Failed to read the OAuth2 redirect port: {err}"))?
+            .port();
+        Ok(Self { listener, port })
+    }
+
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}{CALLBACK_PATH}", self.port)
+    }
+
+    /// Waits on a background thread for the redirect and returns its `code`.
+    pub async fn wait_for_code(self, state: String, timeout: Duration) -> Result<String, String> {
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            let _ = sender.send(self.accept_code(&state, timeout));
+        });
+        receiver
+            .await
+            .map_err(|_| "OAuth2 redirect listener stopped unexpectedly".to_string())?
+    }
+
+    fn accept_code(&self, state: &str, timeout: Duration) -> Result<String, String> {
+        self.listener
+            .set_nonblocking(true)
+            .map_err(|err| format!("Failed to poll the OAuth2 redirect listener: {err}"))?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if let Some(result) = handle_redirect(stream, state) {
+                        return result;
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Err(format!(
+                            "Timed out after {}s waiting for the OAuth2 sign-in redirect",
+                            timeout.as_secs()
+                        ));
+                    }
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(err) => return Err(format!("OAuth2 redirect listener failed: {err}")),
+            }
+        }
+    }
+}
+
+/// `None` for requests that are not the callback, such as a favicon.
+fn handle_redirect(mut stream: TcpStream, state: &str) -> Option<Result<String, String>> {
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT)).ok()?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line).ok()?;
+    let Some(result) = parse_redirect(&request_line, state) else {
+        let _ = stream
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        return None;
+    };
+    let message = match &result {
+        Ok(_) => "Signed in. You can close this tab and return to Zagel.".to_string(),
+        Err(err) => format!("Sign-in failed: {err}"),
+    };
+    let page = format!("<!doctype html><title>Zagel</title><p>{message}</p>");
+    let _ = write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+        page.len()
+    );
+    Some(result)
+}
+
+/// Reads the `code` from a `GET /callback?...` request line, checking `state`.
+fn parse_redirect(request_line: &str, state: &str) -> Option<Result<String, String>> {
+    let target = request_line.split_whitespace().nth(1)?;
+    let url = Url::parse(&format!("http://127.0.0.1{target}")).ok()?;
+    if url.path() != CALLBACK_PATH {
+        return None;
+    }
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    if let Some(error) = param("error") {
+        let description = param("error_description")
+            .map_or_else(String::new, |description| format!(": {description}"));
+        return Some(Err(format!(
+            "authorization server returned {error}{description}"
+        )));
+    }
+    if param("state").as_deref() != Some(state) {
+        return Some(Err("OAuth2 redirect state did not match".to_string()));
+    }
+    Some(param("code").ok_or_else(|| "OAuth2 redirect had no code".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pkce, authorize_url, parse_redirect};
+
+    #[test]
+    fn challenge_matches_the_rfc_example() {
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string());
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(Pkce::generate().verifier.len(), 64);
+
+        let url = authorize_url(
+            "https://id.example.com/authorize?audience=api",
+            "app",
+            "http://127.0.0.1:8765/callback",
+            "read write",
+            "xyz",
+            &pkce.challenge,
+        )
+        .unwrap();
+        assert!(
+            url.starts_with("https://id.example.com/authorize?audience=api&response_type=code")
+        );
+        assert!(url.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A8765%2Fcallback"));
+        assert!(url.contains("scope=read+write"));
+    }
+
+    #[test]
+    fn redirect_yields_the_code_only_for_the_expected_state() {
+        assert_eq!(
+            parse_redirect("GET /callback?code=abc&state=xyz HTTP/1.1\r\n", "xyz"),
+            Some(Ok("abc".to_string()))
+        );
+        assert_eq!(parse_redirect("GET /favicon.ico HTTP/1.1\r\n", "xyz"), None);
+        assert!(matches!(
+            parse_redirect("GET /callback?code=abc&state=evil HTTP/1.1\r\n", "xyz"),
+            Some(Err(_))
+        ));
+        assert_eq!(
+            parse_redirect(
+                "GET /callback?error=access_denied&error_description=User+cancelled&state=xyz HTTP/1.1",
+                "xyz"
+            ),
+            Some(Err(
+                "authorization server returned access_denied: User cancelled".to_string()
+            ))
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Write as FmtWrite;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -6,9 +6,54 @@ use std::path::{Path, PathBuf};
 use anyhow::Context;
 use walkdir::WalkDir;
 
-use crate::model::{Environment, EnvironmentScope, HttpFile, Method, RequestDraft, RequestId};
+use crate::model::{
+    Assertion, BodyEncoding, Capture, Environment, EnvironmentScope, FileAuth, HttpFile,
+    HttpVersion, Method, Priority, RequestDraft, RequestId, RequestOptions, parse_tags,
+    placeholder_names,
+};
+use crate::urls::path_param_names;
+
+pub const FILE_SCAN_MAX_DEPTH: usize = 6;
+/// Environment files of the IDE and VS Code REST clients; each holds several
+/// named environments, and the private one is merged over the public one.
+const ENV_JSON: &str = "http-client.env.json";
+const PRIVATE_ENV_JSON: &str = "http-client.private.env.json";
+/// `# @` directives understood in front of a request line.
+const DIRECTIVES: [&str; 14] = [
+    "name",
+    "id",
+    "step",
+    "body-encoding",
+    "graphql",
+    "capture",
+    "assert",
+    "no-redirect",
+    "no-cookie-jar",
+    "timeout",
+    "priority",
+    "login",
+    "path-param",
+    "tags",
+];
+
+/// A problem found by [`lint_http_text`], at a 1-based line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.line, self.message)
+    }
+}
 
 pub async fn scan_http_files(roots: Vec<PathBuf>, max_depth: usize) -> HashMap<PathBuf, HttpFile> {
+    load_http_files(roots, max_depth)
+}
+
+pub fn load_http_files(roots: Vec<PathBuf>, max_depth: usize) -> HashMap<PathBuf, HttpFile> {
     let mut files = HashMap::new();
     for root in dedup_roots(roots) {
         if !root.exists() || !root.is_dir() {
@@ -38,6 +83,14 @@ pub async fn scan_env_files(
     project_roots: Vec<PathBuf>,
     global_env_roots: Vec<PathBuf>,
     max_depth: usize,
+) -> Vec<Environment> {
+    load_env_files(project_roots, global_env_roots, max_depth)
+}
+
+pub fn load_env_files(
+    project_roots: Vec<PathBuf>,
+    global_env_roots: Vec<PathBuf>,
+    max_depth: usize,
 ) -> Vec<Environment> {
     let mut envs = Vec::new();
     let mut seen = HashSet::new();
@@ -94,6 +147,18 @@ fn scan_env_root(
             continue;
         }
 
+        if let Some(public) = env_json_pair(entry.path()) {
+            if seen.insert(public.clone()) {
+                let relative = public.strip_prefix(root).unwrap_or(&public).display();
+                envs.extend(parse_env_json(
+                    &public,
+                    &format!("{label_prefix} {relative}"),
+                    scope,
+                ));
+            }
+            continue;
+        }
+
         if !is_env_file(entry.path()) {
             continue;
         }
@@ -119,28 +184,14 @@ fn scan_env_root(
 pub fn parse_http_file(path: &Path) -> anyhow::Result<HttpFile> {
     let content = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read {}", path.display()))?;
-    let mut blocks = Vec::new();
-    let mut current = Vec::new();
-
-    for line in content.lines() {
-        if line.trim_start().starts_with("###") {
-            if !current.is_empty() {
-                trim_trailing_empty(&mut current);
-                blocks.push(std::mem::take(&mut current));
-            }
-        } else {
-            current.push(line.to_string());
-        }
-    }
-    trim_trailing_empty(&mut current);
-    if !current.is_empty() {
-        blocks.push(current);
-    }
-
+    let header = file_header(&content);
     let mut requests = Vec::new();
-    for (idx, block) in blocks.into_iter().enumerate() {
+    for (idx, (title, block)) in split_blocks(&skip_lines(&content, header.lines))
+        .into_iter()
+        .enumerate()
+    {
         if let Some(req) = parse_request_block(&block) {
-            requests.push(req);
+            requests.push(with_title(req, title));
         } else {
             requests.push(RequestDraft {
                 title: format!("Untitled {}", idx + 1),
@@ -151,10 +202,297 @@ pub fn parse_http_file(path: &Path) -> anyhow::Result<HttpFile> {
 
     Ok(HttpFile {
         path: path.to_path_buf(),
+        variables: header.variables,
+        auth: header.auth,
         requests,
     })
 }
 
+/// `@name = value`, as declared at the top of an .http file.
+fn variable_declaration(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.trim().strip_prefix('@')?.split_once('=')?;
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    valid.then(|| (name, value.trim()))
+}
+
+/// The value of a `# @auth` line.
+fn auth_declaration(line: &str) -> Option<&str> {
+    comment_text(line.trim())
+        .and_then(parse_directive)
+        .and_then(|(key, value)| (key == "auth").then_some(value))
+}
+
+/// What an .http file declares before its first request.
+#[derive(Debug, Default)]
+struct FileHeader {
+    variables: Vec<(String, String)>,
+    auth: Option<FileAuth>,
+    /// Lines the declarations and the blank lines around them take up.
+    lines: usize,
+}
+
+fn file_header(content: &str) -> FileHeader {
+    let mut header = FileHeader::default();
+    let mut declared = false;
+    for (index, line) in content.lines().enumerate() {
+        if let Some((name, value)) = variable_declaration(line) {
+            header.variables.push((name.to_string(), value.to_string()));
+            declared = true;
+        } else if let Some(value) = auth_declaration(line) {
+            header.auth = FileAuth::parse(value).ok();
+            declared = true;
+        } else if !line.trim().is_empty() {
+            break;
+        }
+        if declared {
+            header.lines = index + 1;
+        }
+    }
+    header
+}
+
+fn skip_lines(content: &str, count: usize) -> String {
+    content.lines().skip(count).collect::<Vec<_>>().join("\n")
+}
+
+fn format_header(variables: &[(String, String)], auth: Option<&FileAuth>) -> String {
+    let mut content = String::new();
+    for (name, value) in variables {
+        writeln!(content, "@{name} = {value}").ok();
+    }
+    if let Some(auth) = auth {
+        writeln!(content, "# @auth {auth}").ok();
+    }
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content
+}
+
+/// Parses text holding exactly one request block, e.g. piped on stdin.
+pub fn parse_request_text(content: &str) -> Result<RequestDraft, String> {
+    let blocks = split_blocks(content);
+    match blocks.as_slice() {
+        [] => Err("no request found".to_string()),
+        [(title, block)] => parse_request_block(block)
+            .map(|req| with_title(req, title.clone()))
+            .ok_or_else(|| "no request line found".to_string()),
+        _ => Err(format!(
+            "found {} request blocks; pass a single request",
+            blocks.len()
+        )),
+    }
+}
+
+/// Strict counterpart of [`parse_http_file`]: reports what the lenient
+/// parser would silently skip or default.
+pub fn lint_http_file(
+    path: &Path,
+    known_vars: &BTreeSet<String>,
+) -> anyhow::Result<Vec<LintIssue>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(lint_http_text(&content, known_vars))
+}
+
+/// Variables are resolved when they are in `known_vars` or captured by an
+/// earlier request of the same text.
+pub fn lint_http_text(content: &str, known_vars: &BTreeSet<String>) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut known = known_vars.clone();
+    let mut names = HashMap::new();
+    // First line of each `# @name` and `# @id` value, keyed by directive.
+    let mut request_keys = HashMap::new();
+    let mut captured = Vec::new();
+    // Line of the block's `###` separator, and whether its request line was seen.
+    let mut block_start = 1;
+    let mut seen_request = false;
+    let mut block_has_content = false;
+    let mut issue = |line: usize, message: String| issues.push(LintIssue { line, message });
+
+    let declarations = lint_file_header(content, &mut known, &mut issue);
+    let lines = content.lines().chain(std::iter::once("###"));
+    for (index, line) in lines.enumerate().skip(declarations) {
+        let number = index + 1;
+        let trimmed = line.trim();
+        if let Some(separator) = trimmed.strip_prefix("###") {
+            if block_has_content && !seen_request {
+                issue(block_start, "request block has no request line".to_string());
+            }
+            known.extend(std::mem::take(&mut captured));
+            let name = separator.trim();
+            if !name.is_empty()
+                && let Some(first) = names.insert(name.to_string(), number)
+            {
+                issue(
+                    number,
+                    format!("duplicate request name {name} (first on line {first})"),
+                );
+            }
+            block_start = number;
+            seen_request = false;
+            block_has_content = false;
+            continue;
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+        block_has_content = true;
+        if !seen_request && let Some(comment) = comment_text(trimmed) {
+            let Some((key, value)) = parse_directive(comment) else {
+                continue;
+            };
+            match key {
+                "name" | "id" if value.is_empty() || value.contains(char::is_whitespace) => {
+                    issue(number, format!("malformed request {key}: {value}"));
+                }
+                "name" | "id" => {
+                    if let Some(first) = request_keys.insert((key, value.to_string()), number) {
+                        issue(
+                            number,
+                            format!("duplicate @{key} {value} (first on line {first})"),
+                        );
+                    }
+                }
+                "capture" => match Capture::parse(value) {
+                    Some(capture) => captured.push(capture.name),
+                    None => issue(number, format!("malformed capture: {value}")),
+                },
+                _ => {
+                    if let Some(message) = directive_issue(key, value) {
+                        issue(number, message);
+                    }
+                }
+            }
+            continue;
+        }
+        if !seen_request {
+            seen_request = true;
+            let mut parts = trimmed.split_whitespace();
+            let method = parts.next().unwrap_or_default();
+            if parts.next().is_none() {
+                issue(
+                    number,
+                    format!("malformed request line: expected `<METHOD> <url>`, got `{trimmed}`"),
+                );
+            } else {
+                match Method::parse(method) {
+                    None => issue(number, format!("invalid method {method}")),
+                    // Sent as written, but most likely a typo like `GTE`.
+                    Some(Method::Custom(_)) => {
+                        issue(number, format!("non-standard method {method}"));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        for name in placeholder_names(line) {
+            if !known.contains(&name) {
+                issue(number, format!("unresolved variable {{{{{name}}}}}"));
+            }
+        }
+    }
+    issues
+}
+
+/// What is wrong with the value of a `# @key value` directive, if anything.
+fn directive_issue(key: &str, value: &str) -> Option<String> {
+    match key {
+        "body-encoding" if BodyEncoding::from_token(value).is_none() => {
+            Some(format!("unknown body encoding {value}"))
+        }
+        "timeout" if RequestOptions::parse_timeout(value).is_none() => {
+            Some(format!("malformed timeout: {value}"))
+        }
+        "priority" if Priority::from_token(value).is_none() => {
+            Some(format!("unknown priority {value}"))
+        }
+        "login" if !value.is_empty() && RequestOptions::parse_timeout(value).is_none() => {
+            Some(format!("malformed login lifetime: {value}"))
+        }
+        "path-param" if value.is_empty() => Some("path-param needs a name".to_string()),
+        "tags" if parse_tags(value).is_empty() => Some("tags needs at least one tag".to_string()),
+        "assert" if Assertion::parse(value).is_none() => {
+            Some(format!("malformed assertion: {value}"))
+        }
+        _ if !DIRECTIVES.contains(&key) => Some(format!("unknown directive @{key}")),
+        _ => None,
+    }
+}
+
+/// Declared file variables become known for the rest of the file and the
+/// `# @auth` line is checked; returns how many lines the declarations take up.
+fn lint_file_header(
+    content: &str,
+    known: &mut BTreeSet<String>,
+    issue: &mut impl FnMut(usize, String),
+) -> usize {
+    let declarations = file_header(content).lines;
+    for (index, line) in content.lines().take(declarations).enumerate() {
+        if let Some(value) = auth_declaration(line) {
+            if let Err(err) = FileAuth::parse(value) {
+                issue(index + 1, err);
+            }
+            for placeholder in placeholder_names(value) {
+                if !known.contains(&placeholder) {
+                    issue(
+                        index + 1,
+                        format!("unresolved variable {{{{{placeholder}}}}}"),
+                    );
+                }
+            }
+        }
+        if let Some((name, value)) = variable_declaration(line) {
+            for placeholder in placeholder_names(value) {
+                if !known.contains(&placeholder) {
+                    issue(
+                        index + 1,
+                        format!("unresolved variable {{{{{placeholder}}}}}"),
+                    );
+                }
+            }
+            known.insert(name.to_string());
+        }
+    }
+    declarations
+}
+
+/// Names a request after its `### <title>` separator, falling back to the URL.
+fn with_title(mut req: RequestDraft, title: String) -> RequestDraft {
+    if !title.is_empty() {
+        req.title = title;
+    }
+    req
+}
+
+/// Request blocks with the text of the `###` separator in front of each.
+fn split_blocks(content: &str) -> Vec<(String, Vec<String>)> {
+    let mut blocks = Vec::new();
+    let mut title = String::new();
+    let mut current = Vec::new();
+
+    for line in content.lines() {
+        if let Some(separator) = line.trim_start().strip_prefix("###") {
+            if !current.is_empty() {
+                trim_trailing_empty(&mut current);
+                blocks.push((std::mem::take(&mut title), std::mem::take(&mut current)));
+            }
+            title = separator.trim().to_string();
+        } else {
+            current.push(line.to_string());
+        }
+    }
+    trim_trailing_empty(&mut current);
+    if !current.is_empty() {
+        blocks.push((title, current));
+    }
+    blocks
+}
+
 pub async fn persist_request(
     http_root: PathBuf,
     selection: Option<RequestId>,
@@ -190,11 +528,34 @@ pub async fn persist_request(
     Ok((path, index))
 }
 
+/// Keeps the `@name = value` declarations and `# @auth` already at the top
+/// of the file.
 pub fn write_http_file(path: &Path, requests: &[RequestDraft]) -> anyhow::Result<()> {
+    let header = fs::read_to_string(path)
+        .map(|content| file_header(&content))
+        .unwrap_or_default();
+    write_http_file_with_header(path, &header.variables, header.auth.as_ref(), requests)
+}
+
+pub fn write_http_file_with_header(
+    path: &Path,
+    variables: &[(String, String)],
+    auth: Option<&FileAuth>,
+    requests: &[RequestDraft],
+) -> anyhow::Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).ok();
     }
 
+    let content = format_header(variables, auth) + &format_http_file(requests);
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write requests to {}", path.display()))
+}
+
+/// Canonical .http text: a `### <title>` separator per request, directives,
+/// the request line, headers in `Content-Type` casing, one blank line, then
+/// the body.
+pub fn format_http_file(requests: &[RequestDraft]) -> String {
     let mut content = String::new();
     for (idx, req) in requests.iter().enumerate() {
         if idx > 0 {
@@ -203,9 +564,60 @@ pub fn write_http_file(path: &Path, requests: &[RequestDraft]) -> anyhow::Result
         writeln!(content, "### {}", req.title).ok();
         content.push_str(&format_request_block(req));
     }
+    content
+}
 
-    fs::write(path, content)
-        .with_context(|| format!("Failed to write requests to {}", path.display()))
+/// Reformats .http text, refusing files the parser can't represent fully:
+/// plain comments and unknown directives would be dropped on the way.
+pub fn format_http_text(content: &str) -> Result<String, String> {
+    let header = file_header(content);
+    let mut requests = Vec::new();
+    for (index, (title, block)) in split_blocks(&skip_lines(content, header.lines))
+        .into_iter()
+        .enumerate()
+    {
+        let leading = block
+            .iter()
+            .map(|line| line.trim())
+            .take_while(|line| line.is_empty() || comment_text(line).is_some())
+            .filter_map(comment_text);
+        for comment in leading {
+            match parse_directive(comment) {
+                Some((key, _)) if DIRECTIVES.contains(&key) => {}
+                Some((key, _)) => return Err(format!("unknown directive @{key} would be dropped")),
+                None => return Err(format!("comment `{comment}` would be dropped")),
+            }
+        }
+        let Some(req) = parse_request_block(&block) else {
+            return Err(format!("request block {} has no request line", index + 1));
+        };
+        requests.push(with_title(req, title));
+    }
+    Ok(format_header(&header.variables, header.auth.as_ref()) + &format_http_file(&requests))
+}
+
+/// `content-type` and `CONTENT-TYPE` become `Content-Type`.
+fn canonical_header(line: &str) -> String {
+    let Some((name, value)) = line.split_once(':') else {
+        return line.to_string();
+    };
+    if name.trim_start().starts_with('#')
+        || name.contains(char::is_whitespace)
+        || name.contains("{{")
+    {
+        return line.to_string();
+    }
+    let name = name
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("-");
+    format!("{name}: {}", value.trim())
 }
 
 fn trim_trailing_empty(lines: &mut Vec<String>) {
@@ -216,10 +628,67 @@ fn trim_trailing_empty(lines: &mut Vec<String>) {
 
 fn format_request_block(req: &RequestDraft) -> String {
     let mut block = String::new();
-    writeln!(block, "{} {}", req.method.as_str(), req.url).ok();
-    let headers = req.headers.trim_end();
-    if !headers.is_empty() {
-        block.push_str(headers);
+    for line in req.notes.trim_matches('\n').lines() {
+        if line.trim().is_empty() {
+            block.push_str("#\n");
+        } else {
+            writeln!(block, "# {}", escape_note(line.trim_end())).ok();
+        }
+    }
+    if let Some(name) = &req.name {
+        writeln!(block, "# @name {name}").ok();
+    }
+    if let Some(id) = &req.id {
+        writeln!(block, "# @id {id}").ok();
+    }
+    if let Some(step) = &req.step {
+        writeln!(block, "# @step {step}").ok();
+    }
+    if !req.tags.is_empty() {
+        writeln!(block, "# @tags {}", req.tags.join(" ")).ok();
+    }
+    if let Some(token) = req.body_encoding.token() {
+        writeln!(block, "# @body-encoding {token}").ok();
+    }
+    if req.graphql {
+        block.push_str("# @graphql\n");
+    }
+    for capture in &req.captures {
+        writeln!(block, "# @capture {capture}").ok();
+    }
+    for assertion in &req.assertions {
+        writeln!(block, "# @assert {assertion}").ok();
+    }
+    if req.options.no_redirect {
+        block.push_str("# @no-redirect\n");
+    }
+    if req.options.no_cookie_jar {
+        block.push_str("# @no-cookie-jar\n");
+    }
+    if let Some(timeout) = req.options.timeout_token() {
+        writeln!(block, "# @timeout {timeout}").ok();
+    }
+    if req.options.priority != Priority::Normal {
+        writeln!(block, "# @priority {}", req.options.priority).ok();
+    }
+    if req.options.login {
+        match req.options.login_token() {
+            Some(lifetime) => writeln!(block, "# @login {lifetime}").ok(),
+            None => writeln!(block, "# @login").ok(),
+        };
+    }
+    let used = path_param_names(&req.url);
+    for (name, value) in &req.path_params {
+        if used.contains(name) && !value.is_empty() {
+            writeln!(block, "# @path-param {name} {value}").ok();
+        }
+    }
+    match req.http_version.token() {
+        Some(token) => writeln!(block, "{} {} {token}", req.method.as_str(), req.url).ok(),
+        None => writeln!(block, "{} {}", req.method.as_str(), req.url).ok(),
+    };
+    for header in req.headers.lines().filter(|line| !line.trim().is_empty()) {
+        block.push_str(&canonical_header(header.trim()));
         block.push('\n');
     }
     block.push('\n');
@@ -231,6 +700,11 @@ fn format_request_block(req: &RequestDraft) -> String {
 }
 
 pub fn suggest_http_path(root: &Path, title: &str) -> PathBuf {
+    root.join(format!("{}.http", slugify(title)))
+}
+
+/// Lowercase ASCII file name stem for a title, `request` when nothing is left.
+pub fn slugify(title: &str) -> String {
     let mut slug = title
         .chars()
         .map(|c| {
@@ -255,16 +729,85 @@ pub fn suggest_http_path(root: &Path, title: &str) -> PathBuf {
     if slug.is_empty() {
         slug = "request".to_string();
     }
+    slug
+}
 
-    root.join(format!("{slug}.http"))
+/// `METHOD url [HTTP/x]`, the first non-comment line of a request.
+fn parse_request_line(line: &str) -> Option<(&str, String, HttpVersion)> {
+    let mut parts = line.trim().splitn(2, ' ');
+    let method = parts.next()?;
+    let target = parts.next().unwrap_or_default().trim();
+    let (url, http_version) = match target.rsplit_once(char::is_whitespace) {
+        Some((url, token)) => HttpVersion::from_token(token).map_or_else(
+            || (target.to_string(), HttpVersion::Auto),
+            |version| (url.trim_end().to_string(), version),
+        ),
+        None => (target.to_string(), HttpVersion::Auto),
+    };
+    Some((method, url, http_version))
 }
 
 fn parse_request_block(lines: &[String]) -> Option<RequestDraft> {
     let mut lines_iter = lines.iter().skip_while(|l| l.trim().is_empty());
-    let first = lines_iter.next()?;
-    let mut parts = first.trim().splitn(2, ' ');
-    let method = parts.next()?;
-    let url = parts.next().unwrap_or_default().to_string();
+    let mut name = None;
+    let mut id = None;
+    let mut step = None;
+    let mut body_encoding = BodyEncoding::Identity;
+    let mut graphql = false;
+    let mut captures = Vec::new();
+    let mut assertions = Vec::new();
+    let mut options = RequestOptions::default();
+    let mut path_params = Vec::new();
+    let mut notes = Vec::new();
+    let mut tags = Vec::new();
+    let first = loop {
+        let line = lines_iter.next()?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(comment) = comment_text(trimmed) else {
+            break line;
+        };
+        if parse_directive(comment).is_none() {
+            notes.push(unescape_note(note_text(trimmed)));
+            continue;
+        }
+        match parse_directive(comment) {
+            Some(("name", value)) => {
+                name = Some(value.to_string()).filter(|value| !value.is_empty());
+            }
+            Some(("id", value)) => {
+                id = Some(value.to_string()).filter(|value| !value.is_empty());
+            }
+            Some(("step", value)) => {
+                step = Some(value.to_string()).filter(|value| !value.is_empty());
+            }
+            Some(("body-encoding", value)) => {
+                body_encoding = BodyEncoding::from_token(value).unwrap_or_default();
+            }
+            Some(("graphql", _)) => graphql = true,
+            Some(("capture", value)) => captures.extend(Capture::parse(value)),
+            Some(("assert", value)) => assertions.extend(Assertion::parse(value)),
+            Some(("no-redirect", _)) => options.no_redirect = true,
+            Some(("no-cookie-jar", _)) => options.no_cookie_jar = true,
+            Some(("timeout", value)) => options.timeout = RequestOptions::parse_timeout(value),
+            Some(("priority", value)) => {
+                options.priority = Priority::from_token(value).unwrap_or_default();
+            }
+            Some(("login", value)) => {
+                options.login = true;
+                options.login_lifetime = RequestOptions::parse_timeout(value);
+            }
+            Some(("tags", value)) => tags.push(value),
+            Some(("path-param", value)) if !value.is_empty() => {
+                let (name, value) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+                path_params.push((name.to_string(), value.trim().to_string()));
+            }
+            _ => {}
+        }
+    };
+    let (method, url, http_version) = parse_request_line(first)?;
 
     let mut headers = Vec::new();
     let mut body = Vec::new();
@@ -284,13 +827,76 @@ fn parse_request_block(lines: &[String]) -> Option<RequestDraft> {
 
     Some(RequestDraft {
         title: url.clone(),
+        notes: notes.join("\n").trim_matches('\n').to_string(),
         method: Method::from(method),
         url,
         headers: headers.join("\n"),
         body: body.join("\n"),
+        name,
+        id,
+        step,
+        http_version,
+        body_encoding,
+        graphql,
+        captures,
+        assertions,
+        options,
+        path_params,
+        tags: parse_tags(&tags.join(" ")),
     })
 }
 
+/// A comment line as written, without its `#` or `//` and one space, so
+/// indented markdown in notes survives.
+fn note_text(line: &str) -> &str {
+    let text = line
+        .strip_prefix('#')
+        .or_else(|| line.strip_prefix("//"))
+        .unwrap_or(line);
+    text.strip_prefix(' ').unwrap_or(text).trim_end()
+}
+
+/// Whether a note line would read back as a directive, or already carries
+/// the `\` that keeps one from doing so: `@` after any number of `\`.
+fn note_needs_escape(line: &str) -> bool {
+    line.trim_start().trim_start_matches('\\').starts_with('@')
+}
+
+/// Puts a `\` before note lines starting with `@` so "@todo check auth"
+/// stays a note rather than an unknown directive.
+fn escape_note(line: &str) -> String {
+    if note_needs_escape(line) {
+        let indent = line.len() - line.trim_start().len();
+        format!("{}\\{}", &line[..indent], &line[indent..])
+    } else {
+        line.to_string()
+    }
+}
+
+fn unescape_note(line: &str) -> String {
+    let indent = line.len() - line.trim_start().len();
+    if note_needs_escape(line) && line[indent..].starts_with('\\') {
+        format!("{}{}", &line[..indent], &line[indent + 1..])
+    } else {
+        line.to_string()
+    }
+}
+
+fn comment_text(line: &str) -> Option<&str> {
+    line.strip_prefix('#')
+        .or_else(|| line.strip_prefix("//"))
+        .map(str::trim)
+}
+
+/// Splits `@key value` comments into `(key, value)`.
+fn parse_directive(comment: &str) -> Option<(&str, &str)> {
+    let directive = comment.strip_prefix('@')?;
+    let (key, value) = directive
+        .split_once(char::is_whitespace)
+        .unwrap_or((directive, ""));
+    Some((key, value.trim()))
+}
+
 fn parse_env_file(
     path: &Path,
     name: String,
@@ -314,7 +920,127 @@ fn parse_env_file(
     Ok(Environment { name, vars, scope })
 }
 
-fn is_env_file(path: &Path) -> bool {
+/// The public `http-client.env.json` for either file of the pair.
+fn env_json_pair(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    (name == ENV_JSON || name == PRIVATE_ENV_JSON).then(|| path.with_file_name(ENV_JSON))
+}
+
+/// Variables of one environment object; nested objects such as the IDE's
+/// `Security` auth settings are not variables and are skipped.
+fn json_env_vars(value: Option<&serde_json::Value>) -> BTreeMap<String, String> {
+    let Some(serde_json::Value::Object(vars)) = value else {
+        return BTreeMap::new();
+    };
+    vars.iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(text) => text.clone(),
+                serde_json::Value::Number(_) | serde_json::Value::Bool(_) => value.to_string(),
+                _ => return None,
+            };
+            Some((name.clone(), value))
+        })
+        .collect()
+}
+
+/// One environment per name in `public` and the private file next to it,
+/// named `<label>#<name>`. `$shared` values apply to every environment and
+/// private values win over public ones. A file that is not valid JSON is
+/// ignored.
+fn parse_env_json(public: &Path, label: &str, scope: &EnvironmentScope) -> Vec<Environment> {
+    let read = |path: &Path| -> serde_json::Map<String, serde_json::Value> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    };
+    let public_envs = read(public);
+    let private_envs = read(&public.with_file_name(PRIVATE_ENV_JSON));
+    let names = public_envs
+        .keys()
+        .chain(private_envs.keys())
+        .filter(|name| !name.starts_with('$'))
+        .collect::<BTreeSet<_>>();
+    names
+        .into_iter()
+        .map(|name| {
+            let mut vars = json_env_vars(public_envs.get("$shared"));
+            vars.extend(json_env_vars(private_envs.get("$shared")));
+            vars.extend(json_env_vars(public_envs.get(name)));
+            vars.extend(json_env_vars(private_envs.get(name)));
+            Environment {
+                name: format!("{label}#{name}"),
+                vars,
+                scope: scope.clone(),
+            }
+        })
+        .collect()
+}
+
+/// File an environment was loaded from, recovered from its
+/// `[project:<root>] <relative>` or `[global:<root>] <relative>` name.
+/// Environments from `http-client.env.json` have none, since they cannot
+/// be edited as `KEY=VALUE` lines.
+pub fn env_file_path(env: &Environment) -> Option<PathBuf> {
+    let (root, relative) = env.name.strip_prefix('[')?.split_once("] ")?;
+    if relative.contains(&format!("{ENV_JSON}#")) {
+        return None;
+    }
+    let root = match env.scope {
+        EnvironmentScope::Project(_) => root.strip_prefix("project:")?,
+        EnvironmentScope::Global => root.strip_prefix("global:")?,
+        EnvironmentScope::Default => return None,
+    };
+    Some(Path::new(root).join(relative))
+}
+
+/// Sets `name=value` in an env file, replacing the first existing
+/// definition and keeping every other line as it was.
+pub fn set_env_var(path: &Path, name: &str, value: &str) -> std::io::Result<()> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let line = format!("{name}={value}");
+    let mut replaced = false;
+    let mut lines = raw
+        .lines()
+        .map(|existing| {
+            let trimmed = existing.trim();
+            let defines = !trimmed.starts_with('#')
+                && trimmed
+                    .split_once('=')
+                    .is_some_and(|(key, _)| key.trim() == name);
+            if defines && !replaced {
+                replaced = true;
+                line.clone()
+            } else {
+                existing.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    if !replaced {
+        lines.push(line);
+    }
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    std::fs::write(path, contents)
+}
+
+/// Whether `path` is `http-client.env.json` or its private counterpart.
+pub fn is_env_json(path: &Path) -> bool {
+    env_json_pair(path).is_some()
+}
+
+/// Whether `path` is the `http-client.private.env.json` that holds secrets.
+pub fn is_private_env_json(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == PRIVATE_ENV_JSON)
+}
+
+pub fn is_env_file(path: &Path) -> bool {
     let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
     if file_name.starts_with(".env") {
         return true;
@@ -328,7 +1054,9 @@ fn is_env_file(path: &Path) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{AssertionKind, CaptureSource};
     use futures::executor::block_on;
+    use std::time::Duration;
     use tempfile::tempdir;
 
     #[test]
@@ -349,6 +1077,7 @@ mod tests {
             url: "https://example.com".into(),
             headers: "Content-Type: application/json".into(),
             body: "{\"ok\":true}".into(),
+            ..Default::default()
         };
 
         let (path, idx) = block_on(persist_request(
@@ -365,7 +1094,7 @@ mod tests {
         let parsed = parse_http_file(&path).expect("parse saved file");
         assert_eq!(parsed.requests.len(), 1);
         let saved = &parsed.requests[0];
-        assert_eq!(saved.title, draft.url);
+        assert_eq!(saved.title, draft.title);
         assert_eq!(saved.method, draft.method);
         assert_eq!(saved.url, draft.url);
         assert_eq!(saved.headers.trim(), draft.headers.trim());
@@ -384,6 +1113,7 @@ mod tests {
             url: "https://example.com/old".into(),
             headers: String::new(),
             body: String::new(),
+            ..Default::default()
         };
         write_http_file(&path, &[original]).expect("write original");
 
@@ -393,6 +1123,7 @@ mod tests {
             url: "https://example.com/new".into(),
             headers: "Authorization: test".into(),
             body: "hi".into(),
+            ..Default::default()
         };
 
         let selection = Some(RequestId::HttpFile {
@@ -407,10 +1138,509 @@ mod tests {
         let parsed = parse_http_file(&path).expect("parse updated");
         assert_eq!(parsed.requests.len(), 1);
         let saved = &parsed.requests[0];
-        assert_eq!(saved.title, updated.url);
+        assert_eq!(saved.title, updated.title);
         assert_eq!(saved.method, updated.method);
         assert_eq!(saved.url, updated.url);
         assert_eq!(saved.headers.trim(), updated.headers.trim());
         assert_eq!(saved.body.trim(), updated.body.trim());
     }
+
+    #[test]
+    fn extended_and_custom_methods_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("methods.http");
+        fs::write(
+            &path,
+            "options https://example.com/\n\n###\nTRACE https://example.com/\n\n###\nPROPFIND https://example.com/dav\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse methods");
+        let methods = |requests: &[RequestDraft]| {
+            requests
+                .iter()
+                .map(|request| request.method.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            methods(&parsed.requests),
+            vec![
+                Method::Options,
+                Method::Trace,
+                Method::Custom("PROPFIND".to_string())
+            ]
+        );
+
+        write_http_file(&path, &parsed.requests).expect("rewrite methods");
+        let reparsed = parse_http_file(&path).expect("reparse methods");
+        assert_eq!(methods(&reparsed.requests), methods(&parsed.requests));
+        assert_eq!(Method::parse("BAD METHOD"), None);
+    }
+
+    #[test]
+    fn step_directive_is_parsed_and_round_tripped() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("steps.http");
+        fs::write(
+            &path,
+            "### login\n# @step Log in as the demo user\n// plain comment\nPOST https://example.com/login\n\n### ping\nGET https://example.com/ping\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse steps");
+        assert_eq!(parsed.requests.len(), 2);
+        assert_eq!(
+            parsed.requests[0].step.as_deref(),
+            Some("Log in as the demo user")
+        );
+        assert_eq!(parsed.requests[0].method, Method::Post);
+        assert_eq!(parsed.requests[0].url, "https://example.com/login");
+        assert_eq!(parsed.requests[1].step, None);
+        assert_eq!(
+            parsed.requests[1].step_label(),
+            "GET https://example.com/ping"
+        );
+
+        write_http_file(&path, &parsed.requests).expect("rewrite steps");
+        let reparsed = parse_http_file(&path).expect("reparse steps");
+        assert_eq!(reparsed.requests[0].step, parsed.requests[0].step);
+    }
+
+    #[test]
+    fn notes_are_kept_as_comment_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notes.http");
+        fs::write(
+            &path,
+            "### users\n# Lists users.\n#\n# - needs `admin`\n#   - or `support`\n# @name users\n// Paged.\nGET https://example.com/users\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse notes");
+        let notes = "Lists users.\n\n- needs `admin`\n  - or `support`\nPaged.";
+        assert_eq!(parsed.requests[0].notes, notes);
+        assert_eq!(parsed.requests[0].name.as_deref(), Some("users"));
+
+        write_http_file(&path, &parsed.requests).expect("rewrite notes");
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("### users\n# Lists users.\n#\n# - needs"));
+        assert_eq!(parse_http_file(&path).unwrap().requests[0].notes, notes);
+    }
+
+    #[test]
+    fn notes_starting_with_at_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notes.http");
+        let notes = "@todo check auth\n  @name is not a directive here\n\\@literal\nplain";
+        let request = RequestDraft {
+            notes: notes.to_string(),
+            url: "https://example.com/users".to_string(),
+            ..RequestDraft::default()
+        };
+
+        write_http_file(&path, &[request]).expect("write notes");
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("# \\@todo check auth\n#   \\@name is"));
+        assert!(written.contains("# \\\\@literal\n# plain\n"));
+        let parsed = parse_http_file(&path).expect("parse notes");
+        assert_eq!(parsed.requests[0].notes, notes);
+        assert_eq!(parsed.requests[0].name, None);
+    }
+
+    #[test]
+    fn http_version_token_is_split_from_url() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("versions.http");
+        fs::write(
+            &path,
+            "GET https://example.com/a HTTP/2\n\n###\nGET https://example.com/b\n\n###\nGET https://example.com/c HTTP/1.0\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse versions");
+        assert_eq!(parsed.requests[0].url, "https://example.com/a");
+        assert_eq!(parsed.requests[0].http_version, HttpVersion::Http2);
+        assert_eq!(parsed.requests[1].http_version, HttpVersion::Auto);
+        assert_eq!(parsed.requests[2].http_version, HttpVersion::Http10);
+
+        write_http_file(&path, &parsed.requests).expect("rewrite versions");
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("GET https://example.com/a HTTP/2\n"));
+        assert!(raw.contains("GET https://example.com/b\n"));
+        assert!(raw.contains("GET https://example.com/c HTTP/1.0\n"));
+    }
+
+    #[test]
+    fn body_encoding_directive_round_trips() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("upload.http");
+        fs::write(
+            &path,
+            "# @body-encoding gzip\nPOST https://example.com/upload\n\n{\"a\":1}\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse upload");
+        assert_eq!(parsed.requests[0].body_encoding, BodyEncoding::Gzip);
+
+        write_http_file(&path, &parsed.requests).expect("rewrite upload");
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("# @body-encoding gzip\nPOST https://example.com/upload\n"));
+    }
+
+    #[test]
+    fn request_text_must_hold_a_single_request() {
+        let draft = parse_request_text(
+            "### ping\nPOST https://example.com/ping\nAccept: text/plain\n\nhello\n",
+        )
+        .unwrap();
+        assert_eq!(draft.method, Method::Post);
+        assert_eq!(draft.headers, "Accept: text/plain");
+        assert_eq!(draft.body, "hello");
+        assert_eq!(parse_request_text("\n\n").unwrap_err(), "no request found");
+        assert_eq!(
+            parse_request_text("# just a comment\n").unwrap_err(),
+            "no request line found"
+        );
+        assert!(parse_request_text("GET https://a.test\n###\nGET https://b.test\n").is_err());
+    }
+
+    #[test]
+    fn separator_text_titles_requests_and_the_url_is_the_fallback() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("titles.http");
+        fs::write(
+            &path,
+            "GET https://a.test/first\n\n###   List users  \nGET https://a.test/users\n\n###\nGET https://a.test/bare\n",
+        )
+        .unwrap();
+        let titles = parse_http_file(&path)
+            .unwrap()
+            .requests
+            .into_iter()
+            .map(|request| request.title)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            ["https://a.test/first", "List users", "https://a.test/bare"]
+        );
+        assert_eq!(
+            parse_request_text("### ping\nGET https://a.test/ping\n")
+                .unwrap()
+                .title,
+            "ping"
+        );
+    }
+
+    #[test]
+    fn capture_directives_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("login.http");
+        fs::write(
+            &path,
+            "# @capture token = body.$.access_token\n# @capture next = header.Location\n# @capture bogus token\nPOST https://example.com/login\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse captures");
+        assert_eq!(
+            parsed.requests[0].captures,
+            vec![
+                Capture {
+                    name: "token".to_string(),
+                    source: CaptureSource::Body("$.access_token".to_string()),
+                },
+                Capture {
+                    name: "next".to_string(),
+                    source: CaptureSource::Header("Location".to_string()),
+                },
+            ]
+        );
+
+        write_http_file(&path, &parsed.requests).expect("rewrite captures");
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains(
+            "# @capture token = body.$.access_token\n# @capture next = header.Location\nPOST"
+        ));
+    }
+
+    #[test]
+    fn assert_directives_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("checks.http");
+        fs::write(
+            &path,
+            "# @assert status == 200\n# @assert header.Content-Type == application/json\n# @assert body contains \"ok\"\n# @assert body.$.items[0].id == 7\n# @assert nonsense\nGET https://example.com/items\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse assertions");
+        let kinds = parsed.requests[0]
+            .assertions
+            .iter()
+            .map(|assertion| (assertion.kind, assertion.target.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (AssertionKind::Status, ""),
+                (AssertionKind::Header, "Content-Type"),
+                (AssertionKind::BodyContains, ""),
+                (AssertionKind::JsonPath, "$.items[0].id"),
+            ]
+        );
+        assert_eq!(parsed.requests[0].assertions[2].expected, "\"ok\"");
+
+        write_http_file(&path, &parsed.requests).expect("rewrite assertions");
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains(
+            "# @assert status == 200\n# @assert header.Content-Type == application/json\n# @assert body contains \"ok\"\n# @assert body.$.items[0].id == 7\nGET"
+        ));
+    }
+
+    #[test]
+    fn lint_reports_what_the_parser_would_skip() {
+        let content = "# header\n### users\n# @capture id = body.$.id\n# @retry 3\nGET {{base}}/users\n\n### users\nFETCH {{base}}/users/{{id}}?q={{query}}\n\n###\n# @assert nonsense\n{{base}}\n";
+        let known = BTreeSet::from(["base".to_string()]);
+        let issues = lint_http_text(content, &known)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            vec![
+                "1: request block has no request line",
+                "4: unknown directive @retry",
+                "7: duplicate request name users (first on line 2)",
+                "8: non-standard method FETCH",
+                "8: unresolved variable {{query}}",
+                "11: malformed assertion: nonsense",
+                "12: malformed request line: expected `<METHOD> <url>`, got `{{base}}`",
+            ]
+        );
+    }
+
+    #[test]
+    fn formatting_is_canonical_and_stable() {
+        let content = "GET https://example.com/users\ncontent-type:application/json\nX-API-KEY:  {{key}}\n\n\n###   login  \n# @step Log in\npost https://example.com/login\n\n{\"a\":1}\n\n\n";
+        let formatted = format_http_text(content).unwrap();
+        assert_eq!(
+            formatted,
+            "### https://example.com/users\nGET https://example.com/users\nContent-Type: application/json\nX-Api-Key: {{key}}\n\n\n### login\n# @step Log in\nPOST https://example.com/login\n\n{\"a\":1}\n"
+        );
+        assert_eq!(format_http_text(&formatted).unwrap(), formatted);
+
+        assert!(format_http_text("# keep me\nGET https://example.com\n").is_err());
+        assert!(format_http_text("# @retry 3\nGET https://example.com\n").is_err());
+    }
+
+    #[test]
+    fn graphql_directive_round_trips() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("graphql.http");
+        let body = r#"{"query":"query { me { id } }","variables":{}}"#;
+        fs::write(
+            &path,
+            format!("### me\n# @graphql\nPOST https://example.com/graphql\n\n{body}\n"),
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse graphql");
+        assert!(parsed.requests[0].graphql);
+        assert_eq!(parsed.requests[0].body, body);
+
+        write_http_file(&path, &parsed.requests).expect("rewrite graphql");
+        let reparsed = parse_http_file(&path).expect("reparse graphql");
+        assert!(reparsed.requests[0].graphql);
+        assert_eq!(reparsed.requests[0].body, body);
+    }
+
+    #[test]
+    fn extracted_variables_land_in_the_environment_file() {
+        let dir = tempdir().unwrap();
+        let envs = load_env_files(vec![dir.path().to_path_buf()], Vec::new(), 2);
+        assert!(envs.is_empty());
+        fs::create_dir(dir.path().join("envs")).unwrap();
+        let path = dir.path().join("envs").join("dev.env");
+        fs::write(&path, "# dev\nhost=old\ntoken = a\n").unwrap();
+
+        let envs = load_env_files(vec![dir.path().to_path_buf()], Vec::new(), 2);
+        assert_eq!(env_file_path(&envs[0]), Some(path.clone()));
+        set_env_var(&path, "token", "b").unwrap();
+        set_env_var(&path, "user", "ada").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# dev\nhost=old\ntoken=b\nuser=ada\n"
+        );
+    }
+
+    #[test]
+    fn name_and_id_directives_round_trip_and_are_linted() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("auth.http");
+        fs::write(
+            &path,
+            "### Log in\n# @name login\nPOST https://example.com/login\n\n### Me\n# @id 7f3a\nGET https://example.com/me\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse named requests");
+        assert_eq!(parsed.requests[0].name.as_deref(), Some("login"));
+        assert_eq!(parsed.requests[0].label(), "login");
+        assert_eq!(parsed.requests[1].label(), "Me");
+
+        write_http_file(&path, &parsed.requests).expect("rewrite named requests");
+        let reparsed = parse_http_file(&path).expect("reparse named requests");
+        assert_eq!(reparsed.requests[0].name.as_deref(), Some("login"));
+        assert_eq!(reparsed.requests[1].id.as_deref(), Some("7f3a"));
+
+        let issues = lint_http_text(
+            "# @name login\n# @id x\nGET https://a.test\n###\n# @name login\n# @id x\nGET https://b.test\n",
+            &BTreeSet::new(),
+        );
+        let lines: Vec<_> = issues.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, vec![5, 6]);
+    }
+
+    #[test]
+    fn request_options_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("options.http");
+        fs::write(
+            &path,
+            "# @tags smoke, auth\n# @tags Smoke\n# @no-redirect\n# @no-cookie-jar\n# @timeout 1500ms\n# @priority HIGH\n# @login 30m\n# @path-param tenant {{tenant}}\n# @path-param gone 1\nGET https://example.com/:tenant/login\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse options");
+        let options = parsed.requests[0].options;
+        assert!(options.no_redirect && options.no_cookie_jar);
+        assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(options.priority, Priority::High);
+        assert!(options.login);
+        assert_eq!(options.login_lifetime, Some(Duration::from_mins(30)));
+
+        write_http_file(&path, &parsed.requests).expect("rewrite options");
+        let reparsed = parse_http_file(&path).expect("reparse options");
+        assert_eq!(reparsed.requests[0].options, options);
+        assert_eq!(reparsed.requests[0].tags, ["smoke", "auth"]);
+        assert!(reparsed.requests[0].has_tag("AUTH"));
+        assert_eq!(
+            reparsed.requests[0].path_params,
+            vec![("tenant".to_string(), "{{tenant}}".to_string())]
+        );
+
+        assert_eq!(
+            RequestOptions::parse_timeout("2m"),
+            Some(Duration::from_mins(2))
+        );
+        assert_eq!(RequestOptions::parse_timeout("soon"), None);
+        assert_eq!(
+            lint_http_text("# @timeout 0\nGET https://a.test\n", &BTreeSet::new()).len(),
+            1
+        );
+        assert_eq!(
+            lint_http_text("# @priority urgent\nGET https://a.test\n", &BTreeSet::new()).len(),
+            1
+        );
+        assert_eq!(
+            lint_http_text("# @login forever\nGET https://a.test\n", &BTreeSet::new()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn file_auth_is_parsed_kept_and_linted() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("auth.http");
+        fs::write(
+            &path,
+            "@host = https://api.test\n# @auth bearer {{token}}\n\n### me\nGET {{host}}/me\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse auth");
+        assert_eq!(parsed.auth, Some(FileAuth::Bearer("{{token}}".to_string())));
+        assert_eq!(parsed.requests.len(), 1);
+
+        write_http_file(&path, &parsed.requests).expect("rewrite auth");
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("@host = https://api.test\n# @auth bearer {{token}}\n\n###"));
+
+        let issues = lint_http_text("# @auth digest x\n\nGET https://a.test\n", &BTreeSet::new());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "unknown auth scheme \"digest\"");
+    }
+
+    #[test]
+    fn file_variables_are_parsed_kept_and_layered_under_the_environment() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("vars.http");
+        fs::write(
+            &path,
+            "@host = https://{{region}}.api.test\n\n@users = {{host}}/users\n\n### list\nGET {{users}}\n",
+        )
+        .unwrap();
+
+        let parsed = parse_http_file(&path).expect("parse variables");
+        assert_eq!(parsed.requests.len(), 1);
+        assert_eq!(
+            parsed.variables[1],
+            ("users".to_string(), "{{host}}/users".to_string())
+        );
+
+        write_http_file(&path, &parsed.requests).expect("rewrite variables");
+        let reparsed = parse_http_file(&path).expect("reparse variables");
+        assert_eq!(reparsed.variables, parsed.variables);
+
+        let env = Environment {
+            name: "eu".to_string(),
+            vars: BTreeMap::from([
+                ("region".to_string(), "eu".to_string()),
+                ("users".to_string(), "https://mock.test/users".to_string()),
+            ]),
+            scope: EnvironmentScope::Default,
+        }
+        .with_file_variables(&parsed.variables);
+        assert_eq!(env.vars["host"], "https://eu.api.test");
+        assert_eq!(env.vars["users"], "https://eu.api.test/users");
+
+        let issues = lint_http_text(&fs::read_to_string(&path).unwrap(), &BTreeSet::new());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "unresolved variable {{region}}");
+    }
+
+    #[test]
+    fn env_json_files_merge_shared_public_and_private_values() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(ENV_JSON),
+            r#"{"$shared": {"version": "v1"}, "dev": {"host": "localhost", "port": 8080, "Security": {"Auth": {}}}, "prod": {"host": "api.test"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(PRIVATE_ENV_JSON),
+            r#"{"dev": {"token": "secret", "host": "127.0.0.1"}, "local": {"token": "x"}}"#,
+        )
+        .unwrap();
+
+        let envs = load_env_files(vec![dir.path().to_path_buf()], Vec::new(), 2);
+        let names = envs.iter().map(|env| env.name.as_str()).collect::<Vec<_>>();
+        let label = format!("[project:{}] {ENV_JSON}", dir.path().display());
+        assert_eq!(
+            names,
+            [
+                format!("{label}#dev"),
+                format!("{label}#local"),
+                format!("{label}#prod")
+            ]
+        );
+        let dev = &envs[0].vars;
+        assert_eq!(dev["host"], "127.0.0.1");
+        assert_eq!(dev["port"], "8080");
+        assert_eq!(dev["version"], "v1");
+        assert_eq!(dev["token"], "secret");
+        assert!(!dev.contains_key("Security"));
+        assert_eq!(env_file_path(&envs[0]), None);
+    }
 }
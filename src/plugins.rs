@@ -0,0 +1,543 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use iced::futures::channel::mpsc;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::io::AsyncWriteExt as _;
+use tokio::process::Command;
+
+use crate::app::AuthState;
+use crate::model::{Environment, Method, RequestDraft, ResponsePreview, apply_environment};
+use crate::net::{BodyChunk, OAuth2TokenCacheEntry, SendOutcome, send_request};
+use crate::state::{AppState, HookCommand, PluginConfig, PluginHook};
+
+/// Request as plugins see it: variables substituted, headers split.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginRequest {
+    #[serde(default)]
+    pub title: String,
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<PluginHeader>,
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl PluginRequest {
+    fn from_draft(draft: &RequestDraft) -> Self {
+        Self {
+            title: draft.title.clone(),
+            method: draft.method.to_string(),
+            url: draft.url.clone(),
+            headers: draft
+                .headers
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(name, value)| PluginHeader {
+                    name: name.trim().to_string(),
+                    value: value.trim().to_string(),
+                })
+                .collect(),
+            body: draft.body.clone(),
+        }
+    }
+
+    /// Copies the plugin's changes onto `draft`, keeping its other settings.
+    fn apply_to(self, draft: &mut RequestDraft) -> Result<(), String> {
+        draft.method =
+            Method::parse(&self.method).ok_or_else(|| format!("invalid method {}", self.method))?;
+        if !self.title.is_empty() {
+            draft.title = self.title;
+        }
+        draft.url = self.url;
+        draft.headers = self
+            .headers
+            .iter()
+            .map(|header| format!("{}: {}", header.name, header.value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        draft.body = self.body;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Description {
+    name: String,
+    hooks: Vec<PluginHook>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Imported {
+    requests: Vec<PluginRequest>,
+}
+
+/// How long a plugin or hook command may run before it is killed.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Plugins and hook commands that wrap every send.
+#[derive(Debug, Clone)]
+pub struct SendHooks {
+    pub plugins: Vec<PluginConfig>,
+    pub commands: Vec<HookCommand>,
+    /// Limit for each plugin or hook command run.
+    pub timeout: Duration,
+}
+
+impl SendHooks {
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            plugins: state.plugins.clone(),
+            commands: state.hook_commands.clone(),
+            timeout: HOOK_TIMEOUT,
+        }
+    }
+}
+
+/// Runs `command` with `input` as JSON on stdin and returns its trimmed
+/// stdout; a non-zero exit is an error carrying stderr. The process is
+/// killed when it outlives `timeout`.
+async fn pipe(
+    mut command: Command,
+    label: &str,
+    input: &serde_json::Value,
+    timeout: Duration,
+) -> Result<String, String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("cannot start {label}: {err}"))?;
+    let stdin = child.stdin.take();
+    let input = input.to_string();
+    let run = async move {
+        if let Some(mut stdin) = stdin {
+            // A plugin may exit without reading its input.
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+        child.wait_with_output().await
+    };
+    let output = tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| format!("{label} did not finish within {} s", timeout.as_secs_f32()))?
+        .map_err(|err| format!("{label} failed: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "{label} exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Runs `command <hook>` with `input` as JSON on stdin.
+async fn invoke(
+    command: &Path,
+    hook: &str,
+    input: &serde_json::Value,
+    timeout: Duration,
+) -> Result<String, String> {
+    let mut program = Command::new(command);
+    program.arg(hook);
+    pipe(
+        program,
+        &format!("{} {hook}", command.display()),
+        input,
+        timeout,
+    )
+    .await
+}
+
+/// Runs a command line through the system shell.
+async fn run_shell(
+    command: &str,
+    input: &serde_json::Value,
+    timeout: Duration,
+) -> Result<String, String> {
+    let mut program = if cfg!(target_os = "windows") {
+        let mut program = Command::new("cmd");
+        program.arg("/C");
+        program
+    } else {
+        let mut program = Command::new("sh");
+        program.arg("-c");
+        program
+    };
+    program.arg(command);
+    pipe(program, &format!("`{command}`"), input, timeout).await
+}
+
+/// Runs the enabled hook commands for `hook` in order and returns the log
+/// lines of what they printed. A failing before-send command stops the
+/// send; after-response failures are only logged, like plugin output.
+pub async fn run_hook_commands(
+    hooks: &SendHooks,
+    hook: PluginHook,
+    draft: &RequestDraft,
+    env: Option<&Environment>,
+    response: Option<&ResponsePreview>,
+) -> Result<Vec<String>, String> {
+    let hooked = hooks
+        .commands
+        .iter()
+        .filter(|command| command.enabled && command.hook == hook)
+        .collect::<Vec<_>>();
+    if hooked.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut request = draft.clone();
+    if let Some(env) = env {
+        request.url = apply_environment(&request.url, &env.vars);
+        request.headers = apply_environment(&request.headers, &env.vars);
+        request.body = apply_environment(&request.body, &env.vars);
+    }
+    let mut input = json!({ "request": PluginRequest::from_draft(&request) });
+    if let Some(response) = response {
+        input["response"] = response_json(response);
+    }
+    let mut lines = Vec::new();
+    for command in hooked {
+        match run_shell(&command.command, &input, hooks.timeout).await {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => lines.push(format!("Hook `{}`: {output}", command.command)),
+            Err(err) if hook == PluginHook::BeforeSend => {
+                return Err(format!("Before-send hook {err}"));
+            }
+            Err(err) => lines.push(format!("Hook {err}")),
+        }
+    }
+    Ok(lines)
+}
+
+fn response_json(response: &ResponsePreview) -> serde_json::Value {
+    json!({
+        "status": response.status,
+        "headers": response
+            .headers
+            .iter()
+            .map(|(name, value)| PluginHeader { name: name.clone(), value: value.clone() })
+            .collect::<Vec<_>>(),
+        "body": response.body.as_deref().map(String::from_utf8_lossy),
+        "error": response.error,
+    })
+}
+
+/// Asks the program at `command` for its name and hooks (`command describe`).
+pub async fn describe(command: PathBuf) -> Result<PluginConfig, String> {
+    let output = invoke(&command, "describe", &json!({}), HOOK_TIMEOUT).await?;
+    let description: Description = serde_json::from_str(&output)
+        .map_err(|err| format!("{} describe printed invalid JSON: {err}", command.display()))?;
+    Ok(PluginConfig {
+        name: description.name,
+        command,
+        hooks: description.hooks,
+        enabled: true,
+    })
+}
+
+fn with_hook(plugins: &[PluginConfig], hook: PluginHook) -> impl Iterator<Item = &PluginConfig> {
+    plugins
+        .iter()
+        .filter(move |plugin| plugin.enabled && plugin.hooks.contains(&hook))
+}
+
+/// Passes the request through every enabled before-send plugin in order.
+/// Variables are substituted first so plugins can sign the final request;
+/// a plugin that prints nothing leaves the request unchanged.
+pub async fn before_send(
+    hooks: &SendHooks,
+    mut draft: RequestDraft,
+    env: Option<&Environment>,
+) -> Result<RequestDraft, String> {
+    let hooked = with_hook(&hooks.plugins, PluginHook::BeforeSend).collect::<Vec<_>>();
+    if hooked.is_empty() {
+        return Ok(draft);
+    }
+    if let Some(env) = env {
+        draft.url = apply_environment(&draft.url, &env.vars);
+        draft.headers = apply_environment(&draft.headers, &env.vars);
+        draft.body = apply_environment(&draft.body, &env.vars);
+    }
+    for plugin in hooked {
+        let input = json!({ "request": PluginRequest::from_draft(&draft) });
+        let output = invoke(
+            &plugin.command,
+            PluginHook::BeforeSend.as_str(),
+            &input,
+            hooks.timeout,
+        )
+        .await
+        .map_err(|err| format!("Plugin {}: {err}", plugin.name))?;
+        if output.is_empty() {
+            continue;
+        }
+        serde_json::from_str::<PluginRequest>(&output)
+            .map_err(|err| format!("Plugin {} returned an invalid request: {err}", plugin.name))?
+            .apply_to(&mut draft)
+            .map_err(|err| format!("Plugin {} returned an invalid request: {err}", plugin.name))?;
+    }
+    Ok(draft)
+}
+
+/// Hands the request and response to every enabled after-response plugin
+/// and returns the log lines of what they print or how they failed.
+pub async fn after_response(
+    hooks: &SendHooks,
+    draft: &RequestDraft,
+    response: &ResponsePreview,
+) -> Vec<String> {
+    let hooked = with_hook(&hooks.plugins, PluginHook::AfterResponse).collect::<Vec<_>>();
+    let mut lines = Vec::new();
+    for plugin in hooked {
+        let input = json!({
+            "request": PluginRequest::from_draft(draft),
+            "response": response_json(response),
+        });
+        match invoke(
+            &plugin.command,
+            PluginHook::AfterResponse.as_str(),
+            &input,
+            hooks.timeout,
+        )
+        .await
+        {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => lines.push(format!("Plugin {}: {output}", plugin.name)),
+            Err(err) => lines.push(format!("Plugin {}: {err}", plugin.name)),
+        }
+    }
+    lines
+}
+
+/// Converts `path` with an import plugin.
+pub async fn import(plugin: PluginConfig, path: PathBuf) -> Result<Vec<RequestDraft>, String> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+    let input = json!({ "path": path, "contents": contents });
+    let output = invoke(
+        &plugin.command,
+        PluginHook::Import.as_str(),
+        &input,
+        HOOK_TIMEOUT,
+    )
+    .await?;
+    let imported: Imported = serde_json::from_str(&output)
+        .map_err(|err| format!("Plugin {} returned invalid requests: {err}", plugin.name))?;
+    imported
+        .requests
+        .into_iter()
+        .map(|request| {
+            let mut draft = RequestDraft::default();
+            request.apply_to(&mut draft)?;
+            Ok(draft)
+        })
+        .collect()
+}
+
+/// `send_request` wrapped in the before-send and after-response plugins and
+/// hook commands, whose output ends up in `SendOutcome::hook_output`.
+pub async fn send_with_plugins(
+    hooks: SendHooks,
+    client: Client,
+    draft: RequestDraft,
+    env: Option<Environment>,
+    auth: AuthState,
+    oauth2_cache: Option<OAuth2TokenCacheEntry>,
+    chunks: Option<mpsc::Sender<BodyChunk>>,
+) -> Result<SendOutcome, String> {
+    let draft = before_send(&hooks, draft, env.as_ref()).await?;
+    let mut hook_output =
+        run_hook_commands(&hooks, PluginHook::BeforeSend, &draft, env.as_ref(), None).await?;
+    let mut outcome = send_request(
+        client,
+        draft.clone(),
+        env.clone(),
+        auth,
+        oauth2_cache,
+        chunks,
+    )
+    .await?;
+    hook_output.extend(after_response(&hooks, &draft, &outcome.response).await);
+    hook_output.extend(
+        run_hook_commands(
+            &hooks,
+            PluginHook::AfterResponse,
+            &draft,
+            env.as_ref(),
+            Some(&outcome.response),
+        )
+        .await
+        .unwrap_or_else(|err| vec![err]),
+    );
+    outcome.hook_output = hook_output;
+    Ok(outcome)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt as _;
+    use std::time::Duration;
+
+    use tempfile::tempdir;
+
+    use super::{SendHooks, before_send, describe, import, run_hook_commands};
+    use crate::model::{Environment, EnvironmentScope, Method, RequestDraft, ResponsePreview};
+    use crate::state::{HookCommand, PluginHook};
+
+    const SCRIPT: &str = r#"#!/bin/sh
+input=$(cat)
+case "$1" in
+  describe) echo '{"name": "signer", "hooks": ["before-send", "import"]}' ;;
+  before-send)
+    case "$input" in
+      *dav.test*) echo "$input" | sed 's/^{"request"://; s/}$//' ;;
+      *api.test*) echo '{"method": "POST", "url": "https://api.test/signed", "headers": [{"name": "X-Signature", "value": "abc"}], "body": "{}"}' ;;
+    esac ;;
+  import) echo '{"requests": [{"title": "Imported", "method": "put", "url": "https://api.test/x"}]}' ;;
+  *) echo "unknown hook" >&2; exit 3 ;;
+esac
+"#;
+
+    fn runtime() -> iced::executor::Default {
+        iced::executor::Default::new().expect("start the async runtime")
+    }
+
+    #[test]
+    fn plugins_describe_rewrite_requests_and_import() {
+        let runtime = runtime();
+        let dir = tempdir().unwrap();
+        let command = dir.path().join("signer");
+        fs::write(&command, SCRIPT).unwrap();
+        fs::set_permissions(&command, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let plugin = runtime.block_on(describe(command)).unwrap();
+        assert_eq!(plugin.name, "signer");
+        let hooks = SendHooks {
+            plugins: vec![plugin.clone()],
+            commands: Vec::new(),
+            timeout: Duration::from_secs(10),
+        };
+
+        let env = Environment {
+            name: "dev".to_string(),
+            vars: [("host".to_string(), "api.test".to_string())].into(),
+            scope: EnvironmentScope::Default,
+        };
+        let draft = RequestDraft {
+            url: "https://{{host}}/users".to_string(),
+            ..RequestDraft::default()
+        };
+        let sent = runtime
+            .block_on(before_send(&hooks, draft.clone(), Some(&env)))
+            .unwrap();
+        assert_eq!(sent.method, Method::Post);
+        assert_eq!(sent.url, "https://api.test/signed");
+        assert_eq!(sent.headers, "X-Signature: abc");
+        let unchanged = runtime
+            .block_on(before_send(&hooks, draft.clone(), None))
+            .unwrap();
+        assert_eq!(unchanged.url, draft.url);
+        let propfind = RequestDraft {
+            method: Method::Custom("PROPFIND".to_string()),
+            url: "https://dav.test/files".to_string(),
+            ..RequestDraft::default()
+        };
+        let echoed = runtime
+            .block_on(before_send(&hooks, propfind.clone(), None))
+            .unwrap();
+        assert_eq!(echoed.method, propfind.method);
+        assert_eq!(echoed.url, propfind.url);
+
+        let source = dir.path().join("collection.json");
+        fs::write(&source, "{}").unwrap();
+        let imported = runtime.block_on(import(plugin, source)).unwrap();
+        assert_eq!(imported[0].title, "Imported");
+        assert_eq!(imported[0].method, Method::Put);
+    }
+
+    #[test]
+    fn hook_commands_see_the_send_and_can_cancel_it() {
+        let runtime = runtime();
+        let dir = tempdir().unwrap();
+        let log = dir.path().join("hook.json");
+        let hook = |command: String, hook| HookCommand {
+            command,
+            hook,
+            enabled: true,
+        };
+        let mut hooks = SendHooks {
+            plugins: Vec::new(),
+            commands: vec![
+                hook(
+                    format!("cat > {}", log.display()),
+                    PluginHook::AfterResponse,
+                ),
+                hook("echo notified".to_string(), PluginHook::AfterResponse),
+                hook("exit 4".to_string(), PluginHook::AfterResponse),
+                hook("exit 3".to_string(), PluginHook::BeforeSend),
+            ],
+            timeout: Duration::from_secs(10),
+        };
+        let draft = RequestDraft {
+            url: "https://api.test/users".to_string(),
+            ..RequestDraft::default()
+        };
+
+        let response = ResponsePreview::error("offline".to_string());
+        let lines = runtime
+            .block_on(run_hook_commands(
+                &hooks,
+                PluginHook::AfterResponse,
+                &draft,
+                None,
+                Some(&response),
+            ))
+            .unwrap();
+        assert_eq!(lines[0], "Hook `echo notified`: notified");
+        assert!(lines[1].starts_with("Hook `exit 4` exited"), "{lines:?}");
+        let logged = fs::read_to_string(&log).unwrap();
+        assert!(logged.contains("https://api.test/users") && logged.contains("offline"));
+
+        let err = runtime
+            .block_on(run_hook_commands(
+                &hooks,
+                PluginHook::BeforeSend,
+                &draft,
+                None,
+                None,
+            ))
+            .unwrap_err();
+        assert!(err.contains("`exit 3` exited"), "{err}");
+
+        hooks.commands = vec![hook("sleep 5".to_string(), PluginHook::BeforeSend)];
+        hooks.timeout = Duration::from_millis(200);
+        let err = runtime
+            .block_on(run_hook_commands(
+                &hooks,
+                PluginHook::BeforeSend,
+                &draft,
+                None,
+                None,
+            ))
+            .unwrap_err();
+        assert!(
+            err.contains("`sleep 5` did not finish within 0.2 s"),
+            "{err}"
+        );
+    }
+}
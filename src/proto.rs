@@ -0,0 +1,1334 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use base64::{Engine, engine::general_purpose};
+use serde_json::{Map, Number, Value};
+
+/// Nested messages deeper than this are left empty in generated templates, so
+/// recursive types terminate.
+const TEMPLATE_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scalar {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => Self::Double,
+            "float" => Self::Float,
+            "int32" => Self::Int32,
+            "int64" => Self::Int64,
+            "uint32" => Self::Uint32,
+            "uint64" => Self::Uint64,
+            "sint32" => Self::Sint32,
+            "sint64" => Self::Sint64,
+            "fixed32" => Self::Fixed32,
+            "fixed64" => Self::Fixed64,
+            "sfixed32" => Self::Sfixed32,
+            "sfixed64" => Self::Sfixed64,
+            "bool" => Self::Bool,
+            "string" => Self::String,
+            "bytes" => Self::Bytes,
+            _ => return None,
+        })
+    }
+
+    const fn wire_type(self) -> u8 {
+        match self {
+            Self::Double | Self::Fixed64 | Self::Sfixed64 => WIRE_FIXED64,
+            Self::Float | Self::Fixed32 | Self::Sfixed32 => WIRE_FIXED32,
+            Self::String | Self::Bytes => WIRE_LEN,
+            _ => WIRE_VARINT,
+        }
+    }
+
+    /// 64-bit integers are strings in the protobuf JSON mapping.
+    const fn is_64_bit(self) -> bool {
+        matches!(
+            self,
+            Self::Int64 | Self::Uint64 | Self::Sint64 | Self::Fixed64 | Self::Sfixed64
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    Scalar(Scalar),
+    /// Fully qualified message name.
+    Message(String),
+    /// Fully qualified enum name.
+    Enum(String),
+    Map(Box<Self>, Box<Self>),
+    /// A type name as written, with the scope it appeared in; replaced by
+    /// `Message` or `Enum` once every file is loaded.
+    Unresolved {
+        name: String,
+        scope: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub json_name: String,
+    pub number: u32,
+    pub ty: FieldType,
+    pub repeated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageType {
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumType {
+    pub name: String,
+    pub values: Vec<(String, i32)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Method {
+    pub name: String,
+    pub input: String,
+    pub output: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    /// Fully qualified, e.g. `helloworld.Greeter`.
+    pub name: String,
+    pub methods: Vec<Method>,
+}
+
+/// Messages, enums and services from a set of `.proto` files, enough to encode
+/// requests from JSON and decode responses back to JSON.
+#[derive(Debug, Clone, Default)]
+pub struct ProtoSchema {
+    messages: BTreeMap<String, MessageType>,
+    enums: BTreeMap<String, EnumType>,
+    pub services: Vec<Service>,
+}
+
+/// Loads `paths` and everything they import. Imports are looked up next to the
+/// importing file and in the directories of `paths`; the common
+/// `google/protobuf` types are built in.
+pub fn load(paths: &[PathBuf]) -> Result<ProtoSchema, String> {
+    if paths.is_empty() {
+        return Err("No .proto files given".to_string());
+    }
+    let roots = paths
+        .iter()
+        .filter_map(|path| path.parent().map(Path::to_path_buf))
+        .collect::<Vec<_>>();
+    let mut schema = ProtoSchema::with_well_known_types();
+    let mut queue = paths.iter().cloned().collect::<VecDeque<_>>();
+    let mut seen = BTreeSet::new();
+    while let Some(path) = queue.pop_front() {
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+        let imports = schema
+            .add_source(&source)
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        for import in imports {
+            if import.starts_with("google/protobuf/") {
+                continue;
+            }
+            let candidates = path
+                .parent()
+                .into_iter()
+                .chain(roots.iter().map(PathBuf::as_path))
+                .map(|dir| dir.join(&import));
+            let found = candidates
+                .into_iter()
+                .find(|candidate| candidate.is_file())
+                .ok_or_else(|| format!("{}: import \"{import}\" not found", path.display()))?;
+            queue.push_back(found);
+        }
+    }
+    schema.resolve()?;
+    Ok(schema)
+}
+
+impl ProtoSchema {
+    fn with_well_known_types() -> Self {
+        let mut schema = Self::default();
+        let scalar = |name: &str, number, scalar| Field {
+            name: name.to_string(),
+            json_name: name.to_string(),
+            number,
+            ty: FieldType::Scalar(scalar),
+            repeated: false,
+        };
+        let mut add = |name: &str, fields| {
+            let name = format!("google.protobuf.{name}");
+            schema
+                .messages
+                .insert(name.clone(), MessageType { name, fields });
+        };
+        add("Empty", Vec::new());
+        for name in ["Timestamp", "Duration"] {
+            add(
+                name,
+                vec![
+                    scalar("seconds", 1, Scalar::Int64),
+                    scalar("nanos", 2, Scalar::Int32),
+                ],
+            );
+        }
+        for (name, ty) in [
+            ("DoubleValue", Scalar::Double),
+            ("FloatValue", Scalar::Float),
+            ("Int64Value", Scalar::Int64),
+            ("UInt64Value", Scalar::Uint64),
+            ("Int32Value", Scalar::Int32),
+            ("UInt32Value", Scalar::Uint32),
+            ("BoolValue", Scalar::Bool),
+            ("StringValue", Scalar::String),
+            ("BytesValue", Scalar::Bytes),
+        ] {
+            add(name, vec![scalar("value", 1, ty)]);
+        }
+        schema
+    }
+
+    /// Parses one file into the schema and returns its imports. Call
+    /// [`ProtoSchema::resolve`] once every file is added.
+    pub fn add_source(&mut self, source: &str) -> Result<Vec<String>, String> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+        };
+        parser.parse_file(self)
+    }
+
+    fn resolve(&mut self) -> Result<(), String> {
+        let names = self.clone();
+        for message in self.messages.values_mut() {
+            for field in &mut message.fields {
+                names
+                    .resolve_field_type(&mut field.ty)
+                    .map_err(|err| format!("{err} (field {} of {})", field.name, message.name))?;
+            }
+        }
+        for service in &mut self.services {
+            for method in &mut service.methods {
+                for name in [&mut method.input, &mut method.output] {
+                    let (scope, written) = name
+                        .split_once('\u{0}')
+                        .map(|(scope, written)| (scope.to_string(), written.to_string()))
+                        .unwrap_or_default();
+                    match names.lookup(&written, &scope) {
+                        Some(FieldType::Message(full)) => *name = full,
+                        _ => {
+                            return Err(format!(
+                                "Unknown message type {written} in {}.{}",
+                                service.name, method.name
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_field_type(&self, ty: &mut FieldType) -> Result<(), String> {
+        match ty {
+            FieldType::Unresolved { name, scope } => {
+                *ty = self
+                    .lookup(name, scope)
+                    .ok_or_else(|| format!("Unknown type {name}"))?;
+                Ok(())
+            }
+            FieldType::Map(key, value) => {
+                self.resolve_field_type(key)?;
+                self.resolve_field_type(value)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolves `name` the way protoc does: innermost scope first, then outwards.
+    fn lookup(&self, name: &str, scope: &str) -> Option<FieldType> {
+        let found = |full: &str| {
+            if self.messages.contains_key(full) {
+                Some(FieldType::Message(full.to_string()))
+            } else if self.enums.contains_key(full) {
+                Some(FieldType::Enum(full.to_string()))
+            } else {
+                None
+            }
+        };
+        if let Some(absolute) = name.strip_prefix('.') {
+            return found(absolute);
+        }
+        let parts = scope
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>();
+        (0..=parts.len()).rev().find_map(|depth| {
+            let prefix = parts[..depth].join(".");
+            if prefix.is_empty() {
+                found(name)
+            } else {
+                found(&format!("{prefix}.{name}"))
+            }
+        })
+    }
+
+    pub fn method(&self, service: &str, method: &str) -> Option<&Method> {
+        self.services
+            .iter()
+            .find(|candidate| candidate.name == service)?
+            .methods
+            .iter()
+            .find(|candidate| candidate.name == method)
+    }
+
+    fn message(&self, name: &str) -> Result<&MessageType, String> {
+        self.messages
+            .get(name)
+            .ok_or_else(|| format!("Unknown message type {name}"))
+    }
+
+    /// Encodes a JSON object as the binary form of `message`. Fields may use
+    /// their proto or lowerCamelCase JSON names.
+    pub fn encode(&self, message: &str, value: &Value) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        self.encode_message(self.message(message)?, value, &mut out)?;
+        Ok(out)
+    }
+
+    fn encode_message(
+        &self,
+        message: &MessageType,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> Result<(), String> {
+        let object = match value {
+            Value::Null => return Ok(()),
+            Value::Object(object) => object,
+            other => {
+                return Err(format!(
+                    "Expected an object for {}, got {other}",
+                    message.name
+                ));
+            }
+        };
+        for (key, value) in object {
+            let field = message
+                .fields
+                .iter()
+                .find(|field| field.name == *key || field.json_name == *key)
+                .ok_or_else(|| format!("Unknown field {key} in {}", message.name))?;
+            if value.is_null() {
+                continue;
+            }
+            let context = || format!("{}.{}", message.name, field.name);
+            match (&field.ty, field.repeated) {
+                (FieldType::Map(key_ty, value_ty), _) => {
+                    let entries = value
+                        .as_object()
+                        .ok_or_else(|| format!("Expected an object for map {}", context()))?;
+                    for (entry_key, entry_value) in entries {
+                        let mut entry = Vec::new();
+                        let key_value = map_key_value(key_ty, entry_key);
+                        self.encode_field(1, key_ty, &key_value, &mut entry)
+                            .map_err(|err| format!("{}: {err}", context()))?;
+                        self.encode_field(2, value_ty, entry_value, &mut entry)
+                            .map_err(|err| format!("{}: {err}", context()))?;
+                        write_tag(out, field.number, WIRE_LEN);
+                        write_bytes(out, &entry);
+                    }
+                }
+                (ty, true) => {
+                    let items = value
+                        .as_array()
+                        .ok_or_else(|| format!("Expected an array for {}", context()))?;
+                    for item in items {
+                        self.encode_field(field.number, ty, item, out)
+                            .map_err(|err| format!("{}: {err}", context()))?;
+                    }
+                }
+                (ty, false) => self
+                    .encode_field(field.number, ty, value, out)
+                    .map_err(|err| format!("{}: {err}", context()))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_field(
+        &self,
+        number: u32,
+        ty: &FieldType,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> Result<(), String> {
+        match ty {
+            FieldType::Scalar(scalar) => {
+                write_tag(out, number, scalar.wire_type());
+                encode_scalar(*scalar, value, out)
+            }
+            FieldType::Enum(name) => {
+                let value = match value {
+                    Value::String(label) => self
+                        .enums
+                        .get(name)
+                        .and_then(|ty| ty.values.iter().find(|(value, _)| value == label))
+                        .map(|(_, number)| *number)
+                        .ok_or_else(|| format!("Unknown {name} value {label}"))?,
+                    other => i32::try_from(json_i64(other)?)
+                        .map_err(|_| format!("Enum value {other} is out of range"))?,
+                };
+                write_tag(out, number, WIRE_VARINT);
+                write_varint(out, i64::from(value).cast_unsigned());
+                Ok(())
+            }
+            FieldType::Message(name) => {
+                let mut nested = Vec::new();
+                self.encode_message(self.message(name)?, value, &mut nested)?;
+                write_tag(out, number, WIRE_LEN);
+                write_bytes(out, &nested);
+                Ok(())
+            }
+            FieldType::Map(..) | FieldType::Unresolved { .. } => {
+                Err("Nested maps are not supported".to_string())
+            }
+        }
+    }
+
+    /// Decodes the binary form of `message` into protobuf JSON (lowerCamelCase
+    /// names, 64-bit integers as strings, bytes as base64). Fields left at their
+    /// default value are absent, as on the wire.
+    pub fn decode(&self, message: &str, bytes: &[u8]) -> Result<Value, String> {
+        self.decode_message(self.message(message)?, bytes)
+    }
+
+    fn decode_message(&self, message: &MessageType, mut bytes: &[u8]) -> Result<Value, String> {
+        let mut object = Map::new();
+        while !bytes.is_empty() {
+            let tag = read_varint(&mut bytes)?;
+            let number = u32::try_from(tag >> 3).map_err(|_| "Field number out of range")?;
+            let wire_type = u8::try_from(tag & 0b111).unwrap_or_default();
+            let Some(field) = message.fields.iter().find(|field| field.number == number) else {
+                skip_field(wire_type, &mut bytes)?;
+                continue;
+            };
+            match &field.ty {
+                FieldType::Map(key_ty, value_ty) => {
+                    let entry = read_len(&mut bytes)?;
+                    let (key, value) = self.decode_map_entry(key_ty, value_ty, entry)?;
+                    object
+                        .entry(field.json_name.clone())
+                        .or_insert_with(|| Value::Object(Map::new()))
+                        .as_object_mut()
+                        .map(|map| map.insert(key, value));
+                }
+                ty if field.repeated => {
+                    let items = object
+                        .entry(field.json_name.clone())
+                        .or_insert_with(|| Value::Array(Vec::new()));
+                    let packed = wire_type == WIRE_LEN
+                        && match ty {
+                            FieldType::Scalar(scalar) => scalar.wire_type() != WIRE_LEN,
+                            FieldType::Enum(_) => true,
+                            _ => false,
+                        };
+                    if packed {
+                        let mut packed_bytes = read_len(&mut bytes)?;
+                        while !packed_bytes.is_empty() {
+                            let item = self.decode_value(ty, &mut packed_bytes)?;
+                            if let Value::Array(items) = items {
+                                items.push(item);
+                            }
+                        }
+                    } else {
+                        let item = self.decode_value(ty, &mut bytes)?;
+                        if let Value::Array(items) = items {
+                            items.push(item);
+                        }
+                    }
+                }
+                ty => {
+                    let value = self.decode_value(ty, &mut bytes)?;
+                    object.insert(field.json_name.clone(), value);
+                }
+            }
+        }
+        Ok(Value::Object(object))
+    }
+
+    fn decode_map_entry(
+        &self,
+        key_ty: &FieldType,
+        value_ty: &FieldType,
+        mut entry: &[u8],
+    ) -> Result<(String, Value), String> {
+        let mut key = String::new();
+        let mut value = Value::Null;
+        while !entry.is_empty() {
+            let tag = read_varint(&mut entry)?;
+            match tag >> 3 {
+                1 => {
+                    key = match self.decode_value(key_ty, &mut entry)? {
+                        Value::String(text) => text,
+                        other => other.to_string(),
+                    };
+                }
+                2 => value = self.decode_value(value_ty, &mut entry)?,
+                _ => skip_field(u8::try_from(tag & 0b111).unwrap_or_default(), &mut entry)?,
+            }
+        }
+        Ok((key, value))
+    }
+
+    fn decode_value(&self, ty: &FieldType, bytes: &mut &[u8]) -> Result<Value, String> {
+        match ty {
+            FieldType::Scalar(scalar) => decode_scalar(*scalar, bytes),
+            FieldType::Enum(name) => {
+                let number = varint_to_i32(read_varint(bytes)?);
+                Ok(self
+                    .enums
+                    .get(name)
+                    .and_then(|ty| ty.values.iter().find(|(_, value)| *value == number))
+                    .map_or_else(
+                        || Value::from(number),
+                        |(label, _)| Value::String(label.clone()),
+                    ))
+            }
+            FieldType::Message(name) => {
+                let nested = read_len(bytes)?;
+                self.decode_message(self.message(name)?, nested)
+            }
+            FieldType::Map(..) | FieldType::Unresolved { .. } => {
+                Err("Nested maps are not supported".to_string())
+            }
+        }
+    }
+
+    /// JSON skeleton of `message` with every field at its default, for editing.
+    pub fn template(&self, message: &str) -> Value {
+        self.messages
+            .get(message)
+            .map_or(Value::Null, |message| self.template_message(message, 0))
+    }
+
+    fn template_message(&self, message: &MessageType, depth: usize) -> Value {
+        let mut object = Map::new();
+        if depth < TEMPLATE_DEPTH {
+            for field in &message.fields {
+                let value = match &field.ty {
+                    FieldType::Map(..) => Value::Object(Map::new()),
+                    ty if field.repeated => Value::Array(vec![self.template_value(ty, depth)]),
+                    ty => self.template_value(ty, depth),
+                };
+                object.insert(field.json_name.clone(), value);
+            }
+        }
+        Value::Object(object)
+    }
+
+    fn template_value(&self, ty: &FieldType, depth: usize) -> Value {
+        match ty {
+            FieldType::Scalar(Scalar::Bool) => Value::Bool(false),
+            FieldType::Scalar(Scalar::String | Scalar::Bytes) => Value::String(String::new()),
+            FieldType::Scalar(scalar) if scalar.is_64_bit() => Value::String("0".to_string()),
+            FieldType::Scalar(_) => Value::from(0),
+            FieldType::Enum(name) => self
+                .enums
+                .get(name)
+                .and_then(|ty| ty.values.first())
+                .map_or_else(|| Value::from(0), |(label, _)| Value::String(label.clone())),
+            FieldType::Message(name) => self.messages.get(name).map_or(Value::Null, |message| {
+                self.template_message(message, depth + 1)
+            }),
+            FieldType::Map(..) | FieldType::Unresolved { .. } => Value::Null,
+        }
+    }
+}
+
+/// Map keys are strings in JSON; integer and bool keys are parsed back.
+fn map_key_value(key_ty: &FieldType, key: &str) -> Value {
+    if *key_ty == FieldType::Scalar(Scalar::Bool) {
+        Value::Bool(key == "true")
+    } else {
+        Value::String(key.to_string())
+    }
+}
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+const WIRE_FIXED32: u8 = 5;
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(u8::try_from(value & 0x7f).unwrap_or_default() | 0x80);
+        value >>= 7;
+    }
+    out.push(u8::try_from(value).unwrap_or_default());
+}
+
+fn write_tag(out: &mut Vec<u8>, number: u32, wire_type: u8) {
+    write_varint(out, (u64::from(number) << 3) | u64::from(wire_type));
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes
+            .split_first()
+            .ok_or("Truncated message: varint runs past the end")?;
+        *bytes = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("Malformed varint".to_string())
+}
+
+fn read_fixed<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], String> {
+    let (head, rest) = bytes
+        .split_first_chunk::<N>()
+        .ok_or("Truncated message: fixed-width value runs past the end")?;
+    *bytes = rest;
+    Ok(*head)
+}
+
+fn read_len<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let len = usize::try_from(read_varint(bytes)?).map_err(|_| "Length out of range")?;
+    if len > bytes.len() {
+        return Err("Truncated message: length runs past the end".to_string());
+    }
+    let (head, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(head)
+}
+
+fn skip_field(wire_type: u8, bytes: &mut &[u8]) -> Result<(), String> {
+    match wire_type {
+        WIRE_VARINT => read_varint(bytes).map(drop),
+        WIRE_FIXED64 => read_fixed::<8>(bytes).map(drop),
+        WIRE_LEN => read_len(bytes).map(drop),
+        WIRE_FIXED32 => read_fixed::<4>(bytes).map(drop),
+        other => Err(format!("Unsupported wire type {other}")),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn varint_to_i32(value: u64) -> i32 {
+    value as i32
+}
+
+fn json_i64(value: &Value) -> Result<i64, String> {
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .or_else(|| {
+                number
+                    .as_f64()
+                    .filter(|float| float.fract() == 0.0)
+                    .map(|float| {
+                        #[allow(clippy::cast_possible_truncation)]
+                        let int = float as i64;
+                        int
+                    })
+            })
+            .ok_or_else(|| format!("Expected an integer, got {number}")),
+        Value::String(text) => text
+            .trim()
+            .parse()
+            .map_err(|_| format!("Expected an integer, got \"{text}\"")),
+        other => Err(format!("Expected an integer, got {other}")),
+    }
+}
+
+fn json_u64(value: &Value) -> Result<u64, String> {
+    match value {
+        Value::Number(number) => number
+            .as_u64()
+            .ok_or_else(|| format!("Expected an unsigned integer, got {number}")),
+        Value::String(text) => text
+            .trim()
+            .parse()
+            .map_err(|_| format!("Expected an unsigned integer, got \"{text}\"")),
+        other => Err(format!("Expected an unsigned integer, got {other}")),
+    }
+}
+
+fn json_f64(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Number(number) => number
+            .as_f64()
+            .ok_or_else(|| format!("Expected a number, got {number}")),
+        Value::String(text) => match text.trim() {
+            "NaN" => Ok(f64::NAN),
+            "Infinity" => Ok(f64::INFINITY),
+            "-Infinity" => Ok(f64::NEG_INFINITY),
+            other => other
+                .parse()
+                .map_err(|_| format!("Expected a number, got \"{text}\"")),
+        },
+        other => Err(format!("Expected a number, got {other}")),
+    }
+}
+
+fn out_of_range(value: &Value) -> String {
+    format!("{value} is out of range")
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn encode_scalar(scalar: Scalar, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+    match scalar {
+        Scalar::Double => out.extend_from_slice(&json_f64(value)?.to_le_bytes()),
+        Scalar::Float => out.extend_from_slice(&(json_f64(value)? as f32).to_le_bytes()),
+        Scalar::Int32 => {
+            let int = i32::try_from(json_i64(value)?).map_err(|_| out_of_range(value))?;
+            write_varint(out, i64::from(int).cast_unsigned());
+        }
+        Scalar::Int64 => write_varint(out, json_i64(value)?.cast_unsigned()),
+        Scalar::Uint32 => {
+            let int = u32::try_from(json_u64(value)?).map_err(|_| out_of_range(value))?;
+            write_varint(out, u64::from(int));
+        }
+        Scalar::Uint64 => write_varint(out, json_u64(value)?),
+        Scalar::Sint32 => {
+            let int = i32::try_from(json_i64(value)?).map_err(|_| out_of_range(value))?;
+            write_varint(out, u64::from(((int << 1) ^ (int >> 31)).cast_unsigned()));
+        }
+        Scalar::Sint64 => {
+            let int = json_i64(value)?;
+            write_varint(out, ((int << 1) ^ (int >> 63)).cast_unsigned());
+        }
+        Scalar::Fixed32 => {
+            let int = u32::try_from(json_u64(value)?).map_err(|_| out_of_range(value))?;
+            out.extend_from_slice(&int.to_le_bytes());
+        }
+        Scalar::Fixed64 => out.extend_from_slice(&json_u64(value)?.to_le_bytes()),
+        Scalar::Sfixed32 => {
+            let int = i32::try_from(json_i64(value)?).map_err(|_| out_of_range(value))?;
+            out.extend_from_slice(&int.to_le_bytes());
+        }
+        Scalar::Sfixed64 => out.extend_from_slice(&json_i64(value)?.to_le_bytes()),
+        Scalar::Bool => {
+            let flag = value
+                .as_bool()
+                .ok_or_else(|| format!("Expected true or false, got {value}"))?;
+            write_varint(out, u64::from(flag));
+        }
+        Scalar::String => {
+            let text = value
+                .as_str()
+                .ok_or_else(|| format!("Expected a string, got {value}"))?;
+            write_bytes(out, text.as_bytes());
+        }
+        Scalar::Bytes => {
+            let text = value
+                .as_str()
+                .ok_or_else(|| format!("Expected base64 bytes, got {value}"))?;
+            let bytes = general_purpose::STANDARD
+                .decode(text)
+                .or_else(|_| general_purpose::URL_SAFE.decode(text))
+                .map_err(|err| format!("Invalid base64 bytes: {err}"))?;
+            write_bytes(out, &bytes);
+        }
+    }
+    Ok(())
+}
+
+fn float_value(value: f64) -> Value {
+    Number::from_f64(value).map_or_else(
+        || {
+            Value::String(
+                if value.is_nan() {
+                    "NaN"
+                } else if value > 0.0 {
+                    "Infinity"
+                } else {
+                    "-Infinity"
+                }
+                .to_string(),
+            )
+        },
+        Value::Number,
+    )
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn decode_scalar(scalar: Scalar, bytes: &mut &[u8]) -> Result<Value, String> {
+    Ok(match scalar {
+        Scalar::Double => float_value(f64::from_le_bytes(read_fixed(bytes)?)),
+        Scalar::Float => float_value(f64::from(f32::from_le_bytes(read_fixed(bytes)?))),
+        Scalar::Int32 => Value::from(varint_to_i32(read_varint(bytes)?)),
+        Scalar::Int64 => Value::String(read_varint(bytes)?.cast_signed().to_string()),
+        Scalar::Uint32 => Value::from(read_varint(bytes)? as u32),
+        Scalar::Uint64 => Value::String(read_varint(bytes)?.to_string()),
+        Scalar::Sint32 => {
+            let raw = read_varint(bytes)? as u32;
+            Value::from((raw >> 1).cast_signed() ^ -((raw & 1).cast_signed()))
+        }
+        Scalar::Sint64 => {
+            let raw = read_varint(bytes)?;
+            Value::String(((raw >> 1).cast_signed() ^ -((raw & 1).cast_signed())).to_string())
+        }
+        Scalar::Fixed32 => Value::from(u32::from_le_bytes(read_fixed(bytes)?)),
+        Scalar::Fixed64 => Value::String(u64::from_le_bytes(read_fixed(bytes)?).to_string()),
+        Scalar::Sfixed32 => Value::from(i32::from_le_bytes(read_fixed(bytes)?)),
+        Scalar::Sfixed64 => Value::String(i64::from_le_bytes(read_fixed(bytes)?).to_string()),
+        Scalar::Bool => Value::Bool(read_varint(bytes)? != 0),
+        Scalar::String => Value::String(
+            std::str::from_utf8(read_len(bytes)?)
+                .map_err(|_| "String field is not valid UTF-8")?
+                .to_string(),
+        ),
+        Scalar::Bytes => Value::String(general_purpose::STANDARD.encode(read_len(bytes)?)),
+    })
+}
+
+/// `foo_bar` -> `fooBar`, as protoc derives `json_name`.
+fn json_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for ch in name.chars() {
+        if ch == '_' {
+            upper = true;
+        } else if upper {
+            out.push(ch.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Str(String),
+    Symbol(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        let ch = chars[pos];
+        if ch.is_whitespace() {
+            pos += 1;
+        } else if ch == '/' && chars.get(pos + 1) == Some(&'/') {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+        } else if ch == '/' && chars.get(pos + 1) == Some(&'*') {
+            pos += 2;
+            while pos < chars.len() && !(chars[pos] == '*' && chars.get(pos + 1) == Some(&'/')) {
+                pos += 1;
+            }
+            pos += 2;
+        } else if ch == '"' || ch == '\'' {
+            let start = pos + 1;
+            pos = start;
+            while pos < chars.len() && chars[pos] != ch {
+                if chars[pos] == '\\' {
+                    pos += 1;
+                }
+                pos += 1;
+            }
+            if pos >= chars.len() {
+                return Err("Unterminated string".to_string());
+            }
+            tokens.push(Token::Str(chars[start..pos].iter().collect()));
+            pos += 1;
+        } else if ch.is_ascii_digit()
+            || (ch == '-' && chars.get(pos + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = pos;
+            pos += 1;
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '.') {
+                pos += 1;
+            }
+            tokens.push(Token::Number(chars[start..pos].iter().collect()));
+        } else if ch.is_alphabetic() || ch == '_' || ch == '.' {
+            let start = pos;
+            pos += 1;
+            while pos < chars.len()
+                && (chars[pos].is_alphanumeric() || chars[pos] == '_' || chars[pos] == '.')
+            {
+                pos += 1;
+            }
+            tokens.push(Token::Ident(chars[start..pos].iter().collect()));
+        } else {
+            tokens.push(Token::Symbol(ch));
+            pos += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or("Unexpected end of file")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Ident(ident) => Ok(ident),
+            other => Err(format!("Expected a name, found {other:?}")),
+        }
+    }
+
+    fn symbol(&mut self, symbol: char) -> Result<(), String> {
+        match self.next()? {
+            Token::Symbol(found) if found == symbol => Ok(()),
+            other => Err(format!("Expected '{symbol}', found {other:?}")),
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Ident(found)) if found == ident);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Skips to the end of the current statement: past its `;`, or past its
+    /// `{ ... }` block when it has one.
+    fn skip_statement(&mut self) -> Result<(), String> {
+        let mut depth = 0usize;
+        loop {
+            match self.next()? {
+                Token::Symbol(';') if depth == 0 => return Ok(()),
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_file(&mut self, schema: &mut ProtoSchema) -> Result<Vec<String>, String> {
+        let mut package = String::new();
+        let mut imports = Vec::new();
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Symbol(';') => self.pos += 1,
+                Token::Ident(keyword) => {
+                    self.pos += 1;
+                    match keyword.as_str() {
+                        "package" => {
+                            package = self.ident()?;
+                            self.symbol(';')?;
+                        }
+                        "import" => {
+                            let _ = self.eat_ident("public") || self.eat_ident("weak");
+                            match self.next()? {
+                                Token::Str(path) => imports.push(path),
+                                other => {
+                                    return Err(format!(
+                                        "Expected an import path, found {other:?}"
+                                    ));
+                                }
+                            }
+                            self.symbol(';')?;
+                        }
+                        "message" => self.parse_message(schema, &package)?,
+                        "enum" => self.parse_enum(schema, &package)?,
+                        "service" => self.parse_service(schema, &package)?,
+                        _ => self.skip_statement()?,
+                    }
+                }
+                other => return Err(format!("Unexpected {other:?}")),
+            }
+        }
+        Ok(imports)
+    }
+
+    fn parse_message(&mut self, schema: &mut ProtoSchema, scope: &str) -> Result<(), String> {
+        let name = qualify(scope, &self.ident()?);
+        self.symbol('{')?;
+        let mut fields = Vec::new();
+        self.parse_message_body(schema, &name, &mut fields)?;
+        schema
+            .messages
+            .insert(name.clone(), MessageType { name, fields });
+        Ok(())
+    }
+
+    fn parse_message_body(
+        &mut self,
+        schema: &mut ProtoSchema,
+        name: &str,
+        fields: &mut Vec<Field>,
+    ) -> Result<(), String> {
+        loop {
+            match self.next()? {
+                Token::Symbol('}') => return Ok(()),
+                Token::Symbol(';') => {}
+                Token::Ident(keyword) => match keyword.as_str() {
+                    "message" => self.parse_message(schema, name)?,
+                    "enum" => self.parse_enum(schema, name)?,
+                    "oneof" => {
+                        self.ident()?;
+                        self.symbol('{')?;
+                        self.parse_message_body(schema, name, fields)?;
+                    }
+                    "option" | "reserved" | "extensions" | "extend" => self.skip_statement()?,
+                    "group" => return Err(format!("Groups are not supported (in {name})")),
+                    "map" if self.peek() == Some(&Token::Symbol('<')) => {
+                        self.pos += 1;
+                        let key = self.field_type(name)?;
+                        self.symbol(',')?;
+                        let value = self.field_type(name)?;
+                        self.symbol('>')?;
+                        let ty = FieldType::Map(Box::new(key), Box::new(value));
+                        fields.push(self.field_rest(ty, false)?);
+                    }
+                    _ => {
+                        let repeated = keyword == "repeated";
+                        let type_name =
+                            if matches!(keyword.as_str(), "repeated" | "optional" | "required") {
+                                self.ident()?
+                            } else {
+                                keyword
+                            };
+                        let ty = type_from_name(&type_name, name);
+                        fields.push(self.field_rest(ty, repeated)?);
+                    }
+                },
+                other => return Err(format!("Unexpected {other:?} in message {name}")),
+            }
+        }
+    }
+
+    fn field_type(&mut self, scope: &str) -> Result<FieldType, String> {
+        Ok(type_from_name(&self.ident()?, scope))
+    }
+
+    /// `name = number [options];`
+    fn field_rest(&mut self, ty: FieldType, repeated: bool) -> Result<Field, String> {
+        let name = self.ident()?;
+        self.symbol('=')?;
+        let number = match self.next()? {
+            Token::Number(number) => parse_int(&number)
+                .and_then(|number| u32::try_from(number).ok())
+                .ok_or_else(|| format!("Invalid field number {number} for {name}"))?,
+            other => {
+                return Err(format!(
+                    "Expected a field number for {name}, found {other:?}"
+                ));
+            }
+        };
+        if self.eat_symbol('[') {
+            while !self.eat_symbol(']') {
+                self.next()?;
+            }
+        }
+        self.symbol(';')?;
+        Ok(Field {
+            json_name: json_name(&name),
+            name,
+            number,
+            ty,
+            repeated,
+        })
+    }
+
+    fn parse_enum(&mut self, schema: &mut ProtoSchema, scope: &str) -> Result<(), String> {
+        let name = qualify(scope, &self.ident()?);
+        self.symbol('{')?;
+        let mut values = Vec::new();
+        loop {
+            match self.next()? {
+                Token::Symbol('}') => break,
+                Token::Symbol(';') => {}
+                Token::Ident(keyword) if matches!(keyword.as_str(), "option" | "reserved") => {
+                    self.skip_statement()?;
+                }
+                Token::Ident(label) => {
+                    self.symbol('=')?;
+                    let number = match self.next()? {
+                        Token::Number(number) => parse_int(&number)
+                            .and_then(|number| i32::try_from(number).ok())
+                            .ok_or_else(|| format!("Invalid enum value {number}"))?,
+                        other => return Err(format!("Expected an enum number, found {other:?}")),
+                    };
+                    if self.eat_symbol('[') {
+                        while !self.eat_symbol(']') {
+                            self.next()?;
+                        }
+                    }
+                    self.symbol(';')?;
+                    values.push((label, number));
+                }
+                other => return Err(format!("Unexpected {other:?} in enum {name}")),
+            }
+        }
+        schema.enums.insert(name.clone(), EnumType { name, values });
+        Ok(())
+    }
+
+    fn parse_service(&mut self, schema: &mut ProtoSchema, package: &str) -> Result<(), String> {
+        let name = qualify(package, &self.ident()?);
+        self.symbol('{')?;
+        let mut methods = Vec::new();
+        loop {
+            match self.next()? {
+                Token::Symbol('}') => break,
+                Token::Symbol(';') => {}
+                Token::Ident(keyword) if keyword == "rpc" => {
+                    let method = self.ident()?;
+                    let (client_streaming, input) = self.rpc_type()?;
+                    if !self.eat_ident("returns") {
+                        return Err(format!("Expected 'returns' after {method}"));
+                    }
+                    let (server_streaming, output) = self.rpc_type()?;
+                    if !self.eat_symbol(';') {
+                        self.symbol('{')?;
+                        self.pos -= 1;
+                        self.skip_statement()?;
+                    }
+                    // Resolved later, once every message is known.
+                    methods.push(Method {
+                        name: method,
+                        input: format!("{package}\u{0}{input}"),
+                        output: format!("{package}\u{0}{output}"),
+                        client_streaming,
+                        server_streaming,
+                    });
+                }
+                Token::Ident(_) => self.skip_statement()?,
+                other => return Err(format!("Unexpected {other:?} in service {name}")),
+            }
+        }
+        schema.services.push(Service { name, methods });
+        Ok(())
+    }
+
+    /// `( [stream] Type )`
+    fn rpc_type(&mut self) -> Result<(bool, String), String> {
+        self.symbol('(')?;
+        let mut name = self.ident()?;
+        let streaming = name == "stream" && matches!(self.peek(), Some(Token::Ident(_)));
+        if streaming {
+            name = self.ident()?;
+        }
+        self.symbol(')')?;
+        Ok((streaming, name))
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+fn type_from_name(name: &str, scope: &str) -> FieldType {
+    Scalar::from_name(name).map_or_else(
+        || FieldType::Unresolved {
+            name: name.to_string(),
+            scope: scope.to_string(),
+        },
+        FieldType::Scalar,
+    )
+}
+
+fn parse_int(text: &str) -> Option<i64> {
+    let (negative, digits) = text
+        .strip_prefix('-')
+        .map_or((false, text), |digits| (true, digits));
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::ProtoSchema;
+
+    const GREETER: &str = r#"
+        syntax = "proto3";
+        package demo.v1;
+        import "google/protobuf/timestamp.proto";
+
+        // Says hello.
+        service Greeter {
+          rpc SayHello (HelloRequest) returns (HelloReply);
+          rpc Watch (HelloRequest) returns (stream HelloReply) {}
+        }
+
+        message HelloRequest {
+          string name = 1;
+          repeated int32 lucky_numbers = 2 [packed = true];
+          Mood mood = 3;
+          map<string, int64> scores = 4;
+          oneof target {
+            Address address = 5;
+            bytes raw = 6;
+          }
+          google.protobuf.Timestamp sent_at = 7;
+          sint32 offset = 8;
+          message Address { string city = 1; }
+        }
+
+        enum Mood { MOOD_UNSPECIFIED = 0; HAPPY = 1; }
+
+        message HelloReply { string message = 1; HelloRequest echo = 2; }
+    "#;
+
+    fn schema() -> ProtoSchema {
+        let mut schema = ProtoSchema::with_well_known_types();
+        let imports = schema.add_source(GREETER).unwrap();
+        assert_eq!(imports, vec!["google/protobuf/timestamp.proto"]);
+        schema.resolve().unwrap();
+        schema
+    }
+
+    #[test]
+    fn parses_services_and_resolves_types() {
+        let schema = schema();
+        let method = schema.method("demo.v1.Greeter", "Watch").unwrap();
+        assert_eq!(method.input, "demo.v1.HelloRequest");
+        assert_eq!(method.output, "demo.v1.HelloReply");
+        assert!(method.server_streaming && !method.client_streaming);
+        assert_eq!(
+            schema.template("demo.v1.HelloRequest"),
+            json!({
+                "name": "",
+                "luckyNumbers": [0],
+                "mood": "MOOD_UNSPECIFIED",
+                "scores": {},
+                "address": {"city": ""},
+                "raw": "",
+                "sentAt": {"seconds": "0", "nanos": 0},
+                "offset": 0
+            })
+        );
+    }
+
+    #[test]
+    fn json_round_trips_through_the_wire_format() {
+        let schema = schema();
+        let request = json!({
+            "name": "zagel",
+            "lucky_numbers": [7, -1],
+            "mood": "HAPPY",
+            "scores": {"a": "12"},
+            "address": {"city": "Cairo"},
+            "sentAt": {"seconds": 1_700_000_000, "nanos": 5},
+            "offset": -3
+        });
+        let bytes = schema.encode("demo.v1.HelloRequest", &request).unwrap();
+        assert_eq!(
+            schema
+                .encode("demo.v1.HelloRequest", &json!({"name": "zagel"}))
+                .unwrap(),
+            b"\x0a\x05zagel"
+        );
+        assert_eq!(
+            schema.decode("demo.v1.HelloRequest", &bytes).unwrap(),
+            json!({
+                "name": "zagel",
+                "luckyNumbers": [7, -1],
+                "mood": "HAPPY",
+                "scores": {"a": "12"},
+                "address": {"city": "Cairo"},
+                "sentAt": {"seconds": "1700000000", "nanos": 5},
+                "offset": -3
+            })
+        );
+        // Packed encoding from other implementations decodes the same.
+        assert_eq!(
+            schema
+                .decode("demo.v1.HelloRequest", b"\x12\x02\x07\x08")
+                .unwrap(),
+            json!({"luckyNumbers": [7, 8]})
+        );
+    }
+
+    #[test]
+    fn reports_unknown_fields_and_types() {
+        let schema = schema();
+        assert_eq!(
+            schema.encode("demo.v1.HelloRequest", &json!({"nope": 1})),
+            Err("Unknown field nope in demo.v1.HelloRequest".to_string())
+        );
+        let mut broken = ProtoSchema::default();
+        broken.add_source("message A { Missing b = 1; }").unwrap();
+        assert_eq!(
+            broken.resolve(),
+            Err("Unknown type Missing (field b of A)".to_string())
+        );
+    }
+}
@@ -0,0 +1,473 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::alerts;
+use crate::app::{RunResult, capture_value, evaluate_assertions, inherited_auth};
+use crate::launch::{RunOptions, RunReport};
+use crate::list::workspace_roots;
+use crate::model::{Environment, EnvironmentScope, HttpFile, RequestDraft, ResponsePreview};
+use crate::net::build_client;
+use crate::parser::{FILE_SCAN_MAX_DEPTH, load_env_files, load_http_files, parse_http_file};
+use crate::plugins::{SendHooks, send_with_plugins};
+use crate::send::{client_config, find_environment, project_for};
+use crate::state::AppState;
+
+struct FileOutcome {
+    /// Shown relative to the run target.
+    name: String,
+    requests: Vec<RunResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    passed: usize,
+    failed: usize,
+    files: Vec<JsonFile<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonFile<'a> {
+    path: &'a str,
+    requests: Vec<JsonRequest<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRequest<'a> {
+    index: usize,
+    title: &'a str,
+    status: Option<u16>,
+    duration_ms: Option<u128>,
+    priority: &'static str,
+    passed: bool,
+    failures: Vec<String>,
+}
+
+/// Sends every request under the target and returns the process exit code:
+/// 0 when all passed, 1 when any failed and 2 when the run could not start.
+/// Failed low-priority requests are reported but do not fail the run; failed
+/// high-priority ones also raise an OS notification.
+pub fn run(options: &RunOptions) -> i32 {
+    let outcomes = match execute(options) {
+        Ok(outcomes) => outcomes,
+        Err(err) => {
+            eprintln!("run: {err}");
+            return 2;
+        }
+    };
+    let failed = outcomes
+        .iter()
+        .flat_map(|file| &file.requests)
+        .filter(|result| result.counts_as_failure())
+        .count();
+    let escalated = outcomes
+        .iter()
+        .flat_map(|file| {
+            file.requests
+                .iter()
+                .filter(|result| result.escalates())
+                .map(|result| format!("{}: {}", file.name, result.label))
+        })
+        .collect::<Vec<_>>();
+    if let Some((title, body)) =
+        alerts::failure_summary(&options.target.display().to_string(), &escalated)
+        && let Err(err) = alerts::notify(&title, &body)
+    {
+        eprintln!("run: failed to show a notification: {err}");
+    }
+
+    let summary = match &options.report {
+        Some(RunReport::Json(None)) => json_report(&outcomes),
+        _ => render_text(&outcomes),
+    };
+    let _ = io::stdout().lock().write_all(summary.as_bytes());
+    let written = match &options.report {
+        Some(RunReport::Junit(path)) => Some((path, junit_report(&outcomes))),
+        Some(RunReport::Json(Some(path))) => Some((path, json_report(&outcomes))),
+        Some(RunReport::Json(None)) | None => None,
+    };
+    if let Some((path, report)) = written
+        && let Err(err) = fs::write(path, report)
+    {
+        eprintln!("run: failed to write {}: {err}", path.display());
+        return 2;
+    }
+    i32::from(failed > 0)
+}
+
+fn execute(options: &RunOptions) -> Result<Vec<FileOutcome>, String> {
+    let state = AppState::load();
+    let (mut project_roots, global_env_roots) = workspace_roots(&options.launch, &state);
+    let files = collect_files(&options.target)?;
+    let project = project_for(&options.target, &mut project_roots);
+    let base_env = match &options.env {
+        Some(name) => {
+            let envs = load_env_files(project_roots, global_env_roots, FILE_SCAN_MAX_DEPTH);
+            Some(
+                find_environment(&envs, name, Some(&project))?
+                    .clone()
+                    .with_global_fallback(&envs),
+            )
+        }
+        None => None,
+    };
+    let runtime = iced::executor::Default::new()
+        .map_err(|err| format!("failed to start the async runtime: {err}"))?;
+
+    // Captured values carry over to later requests, across files.
+    let mut captured = BTreeMap::new();
+    let mut outcomes = Vec::with_capacity(files.len());
+    for file in files {
+        let mut requests = Vec::with_capacity(file.requests.len());
+        let selected = file
+            .requests
+            .iter()
+            .filter(|draft| options.tag.as_ref().is_none_or(|tag| draft.has_tag(tag)));
+        for draft in selected {
+            let env = (base_env.is_some() || !captured.is_empty() || !file.variables.is_empty())
+                .then(|| {
+                    let mut env = base_env
+                        .clone()
+                        .unwrap_or_else(|| Environment {
+                            name: "No environment".to_string(),
+                            vars: BTreeMap::new(),
+                            scope: EnvironmentScope::Default,
+                        })
+                        .with_file_variables(&file.variables);
+                    env.vars.extend(captured.clone());
+                    env
+                });
+            let config = client_config(&state, draft, env.as_ref(), Some(&project));
+            let response = match build_client(&config) {
+                Ok(client) => runtime
+                    .block_on(send_with_plugins(
+                        SendHooks::from_state(&state),
+                        client,
+                        draft.clone(),
+                        env,
+                        inherited_auth(file.auth.as_ref(), &draft.headers),
+                        None,
+                        None,
+                    ))
+                    .map_or_else(ResponsePreview::error, |outcome| {
+                        for line in &outcome.hook_output {
+                            eprintln!("{line}");
+                        }
+                        outcome.response
+                    }),
+                Err(err) => ResponsePreview::error(err),
+            };
+            if response.error.is_none()
+                && response
+                    .status
+                    .is_some_and(|status| (200..300).contains(&status))
+            {
+                for capture in &draft.captures {
+                    if let Ok(value) = capture_value(&capture.source, &response) {
+                        captured.insert(capture.name.clone(), value);
+                    }
+                }
+            }
+            let assertions = evaluate_assertions(&draft.assertions, &response);
+            requests.push(RunResult::from_preview(
+                label(requests.len(), draft),
+                draft.options.priority,
+                &response,
+                assertions,
+            ));
+        }
+        if requests.is_empty() && options.tag.is_some() {
+            continue;
+        }
+        outcomes.push(FileOutcome {
+            name: display_name(&file.path, &options.target),
+            requests,
+        });
+    }
+    Ok(outcomes)
+}
+
+pub fn collect_files(target: &Path) -> Result<Vec<HttpFile>, String> {
+    if target.is_file() {
+        return parse_http_file(target)
+            .map(|file| vec![file])
+            .map_err(|err| format!("{err:#}"));
+    }
+    if !target.is_dir() {
+        return Err(format!("{} does not exist", target.display()));
+    }
+    let mut files = load_http_files(vec![target.to_path_buf()], FILE_SCAN_MAX_DEPTH)
+        .into_values()
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return Err(format!("no .http files found in {}", target.display()));
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn label(index: usize, draft: &RequestDraft) -> String {
+    format!("#{index} {}", draft.step_label())
+}
+
+pub fn display_name(path: &Path, target: &Path) -> String {
+    if path == target {
+        return path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().to_string(),
+        );
+    }
+    path.strip_prefix(target)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Why a request failed, one line per reason.
+fn failures(result: &RunResult) -> Vec<String> {
+    if let Some(err) = &result.error {
+        return vec![err.clone()];
+    }
+    let mut reasons = result
+        .assertions
+        .iter()
+        .filter_map(|outcome| Some(format!("{}: {}", outcome.label, outcome.failure.as_ref()?)))
+        .collect::<Vec<_>>();
+    if reasons.is_empty() && !result.passed() {
+        reasons.push(result.status.map_or_else(
+            || "no response".to_string(),
+            |status| format!("unexpected status {status}"),
+        ));
+    }
+    reasons
+}
+
+fn millis(duration: Option<Duration>) -> String {
+    duration.map_or_else(
+        || "-".to_string(),
+        |duration| format!("{} ms", duration.as_millis()),
+    )
+}
+
+fn render_text(outcomes: &[FileOutcome]) -> String {
+    let mut out = String::new();
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut logged = 0;
+    for file in outcomes {
+        let _ = writeln!(out, "{}", file.name);
+        for result in &file.requests {
+            let mark = if result.passed() {
+                passed += 1;
+                "✓"
+            } else if result.counts_as_failure() {
+                failed += 1;
+                "✕"
+            } else {
+                logged += 1;
+                "!"
+            };
+            let status = result
+                .status
+                .map_or_else(|| "-".to_string(), |status| status.to_string());
+            let _ = writeln!(
+                out,
+                "  {mark} {}  {status}  {}",
+                result.label,
+                millis(result.duration)
+            );
+            for reason in failures(result) {
+                let _ = writeln!(out, "      {reason}");
+            }
+        }
+    }
+    let _ = write!(out, "\n{passed} passed, {failed} failed");
+    if logged > 0 {
+        let _ = write!(out, ", {logged} low-priority failed");
+    }
+    out.push('\n');
+    out
+}
+
+fn json_report(outcomes: &[FileOutcome]) -> String {
+    let files = outcomes
+        .iter()
+        .map(|file| JsonFile {
+            path: &file.name,
+            requests: file
+                .requests
+                .iter()
+                .enumerate()
+                .map(|(index, result)| JsonRequest {
+                    index,
+                    title: &result.label,
+                    status: result.status,
+                    duration_ms: result.duration.map(|duration| duration.as_millis()),
+                    priority: result.priority.as_str(),
+                    passed: result.passed(),
+                    failures: failures(result),
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    let total = files.iter().map(|file| file.requests.len()).sum::<usize>();
+    let passed = files
+        .iter()
+        .flat_map(|file| &file.requests)
+        .filter(|request| request.passed)
+        .count();
+    let report = JsonReport {
+        passed,
+        failed: total - passed,
+        files,
+    };
+    serde_json::to_string_pretty(&report).unwrap_or_default() + "\n"
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn seconds(results: &[RunResult]) -> f64 {
+    results
+        .iter()
+        .filter_map(|result| result.duration)
+        .sum::<Duration>()
+        .as_secs_f64()
+}
+
+fn junit_report(outcomes: &[FileOutcome]) -> String {
+    let all = outcomes
+        .iter()
+        .flat_map(|file| file.requests.iter().cloned())
+        .collect::<Vec<_>>();
+    let failed = |results: &[RunResult]| results.iter().filter(|result| !result.passed()).count();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"zagel\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+        all.len(),
+        failed(&all),
+        seconds(&all)
+    );
+    for file in outcomes {
+        let name = xml_escape(&file.name);
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+            file.requests.len(),
+            failed(&file.requests),
+            seconds(&file.requests)
+        );
+        for result in &file.requests {
+            let time = result.duration.unwrap_or_default().as_secs_f64();
+            let _ = write!(
+                out,
+                "    <testcase name=\"{}\" classname=\"{name}\" time=\"{time:.3}\"",
+                xml_escape(&result.label)
+            );
+            let reasons = failures(result);
+            if reasons.is_empty() {
+                out.push_str("/>\n");
+                continue;
+            }
+            let message = xml_escape(&reasons.join("; "));
+            let _ = writeln!(
+                out,
+                ">\n      <failure message=\"{message}\">{}</failure>\n    </testcase>",
+                xml_escape(&reasons.join("\n"))
+            );
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{FileOutcome, json_report, junit_report};
+    use crate::app::{RunResult, evaluate_assertions};
+    use crate::model::{Assertion, Priority, ResponsePreview};
+
+    fn outcomes() -> Vec<FileOutcome> {
+        vec![FileOutcome {
+            name: "users.http".to_string(),
+            requests: vec![
+                RunResult {
+                    label: "#0 list <users>".to_string(),
+                    status: Some(200),
+                    duration: Some(Duration::from_millis(12)),
+                    error: None,
+                    assertions: Vec::new(),
+                    priority: Priority::Normal,
+                },
+                RunResult {
+                    label: "#1 create".to_string(),
+                    status: Some(500),
+                    duration: Some(Duration::from_millis(30)),
+                    error: None,
+                    assertions: evaluate_assertions(
+                        &[Assertion::parse("status == 201").unwrap()],
+                        &ResponsePreview {
+                            status: Some(500),
+                            duration: None,
+                            body: None,
+                            headers: Vec::new(),
+                            error: None,
+                            version: None,
+                            url: None,
+                        },
+                    ),
+                    priority: Priority::High,
+                },
+            ],
+        }]
+    }
+
+    #[test]
+    fn junit_report_lists_failures_per_file() {
+        assert_eq!(
+            junit_report(&outcomes()),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <testsuites name=\"zagel\" tests=\"2\" failures=\"1\" time=\"0.042\">\n  \
+             <testsuite name=\"users.http\" tests=\"2\" failures=\"1\" time=\"0.042\">\n    \
+             <testcase name=\"#0 list &lt;users&gt;\" classname=\"users.http\" time=\"0.012\"/>\n    \
+             <testcase name=\"#1 create\" classname=\"users.http\" time=\"0.030\">\n      \
+             <failure message=\"status == 201: got 500\">status == 201: got 500</failure>\n    \
+             </testcase>\n  \
+             </testsuite>\n\
+             </testsuites>\n"
+        );
+    }
+
+    #[test]
+    fn json_report_counts_passed_and_failed_requests() {
+        let report: serde_json::Value = serde_json::from_str(&json_report(&outcomes())).unwrap();
+        assert_eq!(report["passed"], 1);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(
+            report["files"][0]["requests"][1],
+            serde_json::json!({
+                "index": 1,
+                "title": "#1 create",
+                "status": 500,
+                "duration_ms": 30,
+                "priority": "high",
+                "passed": false,
+                "failures": ["status == 201: got 500"]
+            })
+        );
+    }
+}
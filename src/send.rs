@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+
+use crate::app::inherited_auth;
+use crate::launch::{SendOptions, SendTarget};
+use crate::list::workspace_roots;
+use crate::model::{
+    Environment, EnvironmentScope, RequestDraft, RequestId, ResponsePreview, apply_environment,
+    short_env_name,
+};
+use crate::net::{ClientConfig, build_client};
+use crate::parser::{FILE_SCAN_MAX_DEPTH, load_env_files, parse_http_file, parse_request_text};
+use crate::plugins::{SendHooks, send_with_plugins};
+use crate::state::AppState;
+
+/// Sends the request and returns the process exit code. The response body goes
+/// to stdout and the status line to stderr, so the output can be piped; with
+/// `--include` the status line and headers precede the body on stdout.
+pub fn run(options: &SendOptions) -> i32 {
+    match execute(options) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("send: {err}");
+            1
+        }
+    }
+}
+
+fn execute(options: &SendOptions) -> Result<(), String> {
+    let state = AppState::load();
+    let (mut project_roots, global_env_roots) = workspace_roots(&options.launch, &state);
+    let (draft, project, variables, file_auth) = match &options.target {
+        SendTarget::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| format!("failed to read stdin: {err}"))?;
+            (parse_request_text(&input)?, None, Vec::new(), None)
+        }
+        SendTarget::File(request) => {
+            let path = &request.path;
+            let file = parse_http_file(path).map_err(|err| format!("{err:#}"))?;
+            let Some(RequestId::HttpFile { index, .. }) = request.resolve(&file) else {
+                return Err(format!(
+                    "{} has {} requests; {request} does not exist",
+                    path.display(),
+                    file.requests.len()
+                ));
+            };
+            let draft = file.requests[index].clone();
+            let project = project_for(path, &mut project_roots);
+            (draft, Some(project), file.variables, file.auth)
+        }
+    };
+    let env = match &options.env {
+        Some(name) => {
+            let envs = load_env_files(project_roots, global_env_roots, FILE_SCAN_MAX_DEPTH);
+            Some(
+                find_environment(&envs, name, project.as_deref())?
+                    .clone()
+                    .with_global_fallback(&envs),
+            )
+        }
+        None => None,
+    };
+    let env = if variables.is_empty() {
+        env
+    } else {
+        Some(
+            env.unwrap_or_else(|| Environment {
+                name: "No environment".to_string(),
+                vars: BTreeMap::new(),
+                scope: EnvironmentScope::Default,
+            })
+            .with_file_variables(&variables),
+        )
+    };
+    let client = build_client(&client_config(
+        &state,
+        &draft,
+        env.as_ref(),
+        project.as_deref(),
+    ))?;
+    let auth = inherited_auth(file_auth.as_ref(), &draft.headers);
+    let runtime = iced::executor::Default::new()
+        .map_err(|err| format!("failed to start the async runtime: {err}"))?;
+    let outcome = runtime.block_on(send_with_plugins(
+        SendHooks::from_state(&state),
+        client,
+        draft,
+        env,
+        auth,
+        None,
+        None,
+    ))?;
+    for line in &outcome.hook_output {
+        eprintln!("{line}");
+    }
+    let response = outcome.response;
+    if let Some(err) = response.error {
+        return Err(err);
+    }
+    let mut stdout = io::stdout().lock();
+    if options.include {
+        let mut head = status_line(&response);
+        for (name, value) in &response.headers {
+            let _ = write!(head, "\n{name}: {value}");
+        }
+        head.push_str("\n\n");
+        stdout
+            .write_all(head.as_bytes())
+            .map_err(|err| format!("failed to write the response: {err}"))?;
+    } else {
+        eprintln!("{}", status_line(&response));
+    }
+    stdout
+        .write_all(response.body.as_deref().unwrap_or_default())
+        .and_then(|()| stdout.flush())
+        .map_err(|err| format!("failed to write the response: {err}"))
+}
+
+/// The configured project containing `path`; a file or directory outside
+/// every project is treated as its own project so env files next to it are
+/// found.
+pub fn project_for(path: &Path, project_roots: &mut Vec<PathBuf>) -> PathBuf {
+    if let Some(root) = project_roots.iter().find(|root| path.starts_with(root)) {
+        return root.clone();
+    }
+    let root = if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+    project_roots.push(root.clone());
+    root
+}
+
+/// Matches the full environment name or its short form (`dev` for `dev.env`
+/// or `.env.dev`) among the environments visible to `project`; every
+/// environment is a candidate when there is no project.
+pub fn find_environment<'a>(
+    envs: &'a [Environment],
+    name: &str,
+    project: Option<&Path>,
+) -> Result<&'a Environment, String> {
+    let visible = envs
+        .iter()
+        .filter(|env| project.is_none() || env.visible_for_project(project))
+        .collect::<Vec<_>>();
+    let matches = visible
+        .iter()
+        .copied()
+        .filter(|env| env.name == name || short_env_name(&env.name) == name)
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [env] => Ok(env),
+        [] => Err(format!(
+            "no environment named {name}; available: {}",
+            visible
+                .iter()
+                .map(|env| short_env_name(&env.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        _ => Err(format!(
+            "environment {name} is ambiguous: {}",
+            matches
+                .iter()
+                .map(|env| env.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+pub fn client_config(
+    state: &AppState,
+    draft: &RequestDraft,
+    env: Option<&Environment>,
+    project: Option<&Path>,
+) -> ClientConfig {
+    let url = env.map_or_else(
+        || draft.url.clone(),
+        |env| apply_environment(&draft.url, &env.vars),
+    );
+    let host = reqwest::Url::parse(url.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string));
+    ClientConfig {
+        proxy: state.proxy_for(env.map(|env| env.name.as_str())).cloned(),
+        identity: state
+            .client_certificate_for(project, host.as_deref())
+            .cloned(),
+        ca_certificate: state.ca_certificate.clone(),
+        skip_tls_verify: state.skip_tls_verify,
+        http_version: draft.http_version,
+        user_agent: state.user_agent.clone(),
+        no_redirect: draft.options.no_redirect,
+    }
+}
+
+fn status_line(response: &ResponsePreview) -> String {
+    let mut line = response
+        .version
+        .clone()
+        .unwrap_or_else(|| "HTTP".to_string());
+    if let Some(status) = response.status {
+        let _ = write!(line, " {status}");
+    }
+    if let Some(duration) = response.duration {
+        let _ = write!(line, " ({} ms)", duration.as_millis());
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    use super::{find_environment, status_line};
+    use crate::model::{Environment, EnvironmentScope, ResponsePreview};
+
+    #[test]
+    fn status_line_shows_version_status_and_timing() {
+        let response = ResponsePreview {
+            status: Some(404),
+            duration: Some(Duration::from_millis(42)),
+            body: None,
+            headers: Vec::new(),
+            error: None,
+            version: Some("HTTP/2.0".to_string()),
+            url: None,
+        };
+        assert_eq!(status_line(&response), "HTTP/2.0 404 (42 ms)");
+    }
+
+    #[test]
+    fn environments_match_by_short_name_within_the_project() {
+        let env = |name: &str, scope: EnvironmentScope| Environment {
+            name: name.to_string(),
+            vars: BTreeMap::new(),
+            scope,
+        };
+        let project = || EnvironmentScope::Project(PathBuf::from("/proj"));
+        let envs = vec![
+            env("[project:/proj] envs/dev.env", project()),
+            env("[project:/proj] .env.staging", project()),
+            env(
+                "[project:/other] dev.env",
+                EnvironmentScope::Project(PathBuf::from("/other")),
+            ),
+            env("[global:/g] shared.env", EnvironmentScope::Global),
+        ];
+        let found = |name: &str| {
+            find_environment(&envs, name, Some(Path::new("/proj"))).map(|env| env.name.as_str())
+        };
+        assert_eq!(found("dev"), Ok("[project:/proj] envs/dev.env"));
+        assert_eq!(found("staging"), Ok("[project:/proj] .env.staging"));
+        assert_eq!(found("shared"), Ok("[global:/g] shared.env"));
+        assert_eq!(
+            found("prod"),
+            Err("no environment named prod; available: dev, staging, shared".to_string())
+        );
+        assert!(find_environment(&envs, "dev", None).is_err());
+    }
+}
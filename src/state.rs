@@ -1,5 +1,6 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
@@ -8,8 +9,29 @@ use crate::theme::ThemeChoice;
 
 static STATE_FILE_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
 
+/// Format version written by this build. Bump it together with a new entry in
+/// `MIGRATIONS` whenever the on-disk layout changes.
+pub const STATE_VERSION: u32 = 1;
+
+type Migration = fn(&mut toml::Table);
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: [Migration; STATE_VERSION as usize] = [migrate_v0_http_root];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StateVersion(pub u32);
+
+impl Default for StateVersion {
+    fn default() -> Self {
+        Self(STATE_VERSION)
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AppState {
+    #[serde(default)]
+    pub version: StateVersion,
     pub active_environment: Option<String>,
     #[serde(default)]
     pub project_roots: Vec<PathBuf>,
@@ -21,33 +43,343 @@ pub struct AppState {
     pub theme: ThemeChoice,
     #[serde(default)]
     pub http_file_order: Vec<PathBuf>,
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    #[serde(default)]
+    pub environment_proxies: BTreeMap<String, ProxySettings>,
+    #[serde(default)]
+    pub client_certificates: Vec<ClientCertificate>,
+    /// Extra PEM (bundle) or DER root certificate trusted alongside the built-in roots.
+    #[serde(default)]
+    pub ca_certificate: Option<PathBuf>,
+    #[serde(default)]
+    pub skip_tls_verify: bool,
+    /// Drops saved folders that are unreachable at startup instead of
+    /// listing them in a banner until they are removed or relocated.
+    #[serde(default)]
+    pub forget_missing_roots: bool,
+    /// Replaces the built-in `zagel/<version>` User-Agent for every request.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Files with more requests than this get a "Split" suggestion; `0` disables it.
+    #[serde(default)]
+    pub max_requests_per_file: Option<usize>,
+    /// JSON path filters for the response viewer, keyed by request reference
+    /// (`path#id=…`, `path#name=…` or `path#index`).
+    #[serde(default)]
+    pub response_filters: BTreeMap<String, String>,
+    #[serde(default)]
+    pub sessions: BTreeMap<String, SavedSession>,
+    #[serde(default)]
+    pub active_session: Option<String>,
+    #[serde(default)]
+    pub sidebar_grouping: SidebarGrouping,
+    /// Local port for the editor integration endpoint; off when unset.
+    #[serde(default)]
+    pub editor_port: Option<u16>,
+    /// Secret editors send as `X-Zagel-Token` to `POST /open`; generated
+    /// when the endpoint is first enabled.
+    #[serde(default)]
+    pub editor_token: Option<String>,
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>,
+    #[serde(default)]
+    pub hook_commands: Vec<HookCommand>,
+    #[serde(default)]
+    pub response_view: ResponseViewSettings,
+    /// Starred requests per project root, as request references.
+    #[serde(default)]
+    pub favorites: BTreeMap<PathBuf, Vec<String>>,
+}
+
+/// How the response body editor lays out text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseViewSettings {
+    #[serde(default)]
+    pub wrap: bool,
+    /// Draws spaces as `·` and tabs as `→`.
+    #[serde(default)]
+    pub show_whitespace: bool,
+    #[serde(default = "default_response_font_size")]
+    pub font_size: u16,
+}
+
+impl ResponseViewSettings {
+    pub const MIN_FONT_SIZE: u16 = 8;
+    pub const MAX_FONT_SIZE: u16 = 32;
+}
+
+const fn default_response_font_size() -> u16 {
+    14
+}
+
+impl Default for ResponseViewSettings {
+    fn default() -> Self {
+        Self {
+            wrap: false,
+            show_whitespace: false,
+            font_size: default_response_font_size(),
+        }
+    }
+}
+
+/// How the sidebar lists requests: by `.http` file, or by the host each request
+/// targets once variables are substituted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SidebarGrouping {
+    #[default]
+    Files,
+    Host,
+}
+
+impl SidebarGrouping {
+    pub const ALL: [Self; 2] = [Self::Files, Self::Host];
+}
+
+impl std::fmt::Display for SidebarGrouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Files => f.write_str("By file"),
+            Self::Host => f.write_str("By host"),
+        }
+    }
+}
+
+/// External program that zagel runs at the hooks it declared when it was added.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginConfig {
+    pub name: String,
+    pub command: PathBuf,
+    #[serde(default)]
+    pub hooks: Vec<PluginHook>,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+const fn enabled() -> bool {
+    true
+}
+
+/// A shell command run around every send, with the request (and after the
+/// send, the response) as JSON on stdin. Unlike a plugin it cannot change
+/// the request; a failing before-send command cancels the send.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookCommand {
+    pub command: String,
+    /// `BeforeSend` or `AfterResponse`.
+    pub hook: PluginHook,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginHook {
+    /// Receives the resolved request and may return a changed one.
+    #[default]
+    BeforeSend,
+    /// Receives the request and its response; output is only logged.
+    AfterResponse,
+    /// Turns a file in another format into requests.
+    Import,
+}
+
+impl PluginHook {
+    /// The hooks a [`HookCommand`] can run at.
+    pub const SEND: [Self; 2] = [Self::BeforeSend, Self::AfterResponse];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::BeforeSend => "before-send",
+            Self::AfterResponse => "after-response",
+            Self::Import => "import",
+        }
+    }
+}
+
+impl std::fmt::Display for PluginHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Variables, cookies and cached token saved under a name, e.g. "admin" and
+/// "user", so a login can be switched back to without signing in again.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSession {
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    /// Cookies per jar: `env:<environment>` or `shared`.
+    #[serde(default)]
+    pub cookies: BTreeMap<String, Vec<StoredCookie>>,
+    #[serde(default)]
+    pub oauth2_token: Option<crate::net::StoredOAuth2Token>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    #[serde(default)]
+    pub host_only: bool,
+    pub path: String,
+    #[serde(default)]
+    pub secure: bool,
+    /// Unix timestamp in seconds; session cookies have none.
+    #[serde(default)]
+    pub expires: Option<u64>,
+}
+
+const DEFAULT_MAX_REQUESTS_PER_FILE: usize = 40;
+
+/// Proxy used for outgoing requests. `url` accepts `http://`, `https://`,
+/// `socks5://` and `socks5h://` schemes; an empty `url` means a direct connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxySettings {
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// PEM client certificate presented for mutual TLS. Applies to requests whose
+/// host equals `host` and/or whose collection lives under `project`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientCertificate {
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    pub cert: PathBuf,
+    /// Private key file; may be omitted when `cert` also contains the key.
+    #[serde(default)]
+    pub key: Option<PathBuf>,
+}
+
+impl ClientCertificate {
+    /// `None` when the entry does not apply, otherwise how specific the match is.
+    fn match_rank(&self, project: Option<&Path>, host: Option<&str>) -> Option<u8> {
+        let host_match = match (&self.host, host) {
+            (None, _) => Some(0),
+            (Some(expected), Some(actual)) if expected.eq_ignore_ascii_case(actual) => Some(2),
+            (Some(_), _) => None,
+        }?;
+        let project_match = match (&self.project, project) {
+            (None, _) => Some(0),
+            (Some(expected), Some(actual)) if expected == actual => Some(1),
+            (Some(_), _) => None,
+        }?;
+        Some(host_match + project_match).filter(|rank| *rank > 0)
+    }
 }
 
 impl AppState {
     pub fn load() -> Self {
-        let Some(path) = state_file_path() else {
+        state_file_path().map_or_else(Self::default, |path| Self::load_from(&path))
+    }
+
+    /// Migrates older files in place, keeping a `.v<N>.bak` copy of the original.
+    /// Unreadable files are moved aside to `.invalid.bak` instead of being overwritten.
+    fn load_from(path: &Path) -> Self {
+        let Ok(raw) = fs::read_to_string(path) else {
             return Self::default();
         };
 
-        let mut state = fs::read_to_string(&path).map_or_else(
-            |_| Self::default(),
-            |raw| toml::from_str(&raw).unwrap_or_default(),
-        );
+        let mut parsed = match Self::parse(&raw) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                let backup = backup_path(path, "invalid");
+                eprintln!(
+                    "state: {} is invalid ({}); moved to {}",
+                    path.display(),
+                    err.message(),
+                    backup.display()
+                );
+                let _ = fs::rename(path, backup);
+                return Self::default();
+            }
+        };
 
-        if state.project_roots.is_empty()
-            && let Some(root) = state.http_root.take()
-        {
-            state.project_roots.push(root);
+        if parsed.source_version != STATE_VERSION {
+            let backup = backup_path(path, &format!("v{}", parsed.source_version));
+            if let Err(err) = fs::write(&backup, &raw) {
+                eprintln!("state: failed to back up {}: {err}", path.display());
+                return parsed.state;
+            }
+            if parsed.source_version < STATE_VERSION {
+                parsed.state.save_to(path);
+            } else {
+                parsed.state.version = StateVersion::default();
+                eprintln!(
+                    "state: {} was written by a newer zagel (format v{}); backed up to {}",
+                    path.display(),
+                    parsed.source_version,
+                    backup.display()
+                );
+            }
         }
+        parsed.state
+    }
 
-        state
+    pub fn parse(raw: &str) -> Result<ParsedState, toml::de::Error> {
+        let mut table: toml::Table = toml::from_str(raw)?;
+        let source_version = table
+            .get("version")
+            .and_then(toml::Value::as_integer)
+            .and_then(|version| u32::try_from(version).ok())
+            .unwrap_or(0);
+        for migration in MIGRATIONS.iter().skip(source_version as usize) {
+            migration(&mut table);
+        }
+        if source_version < STATE_VERSION {
+            table.insert("version".to_string(), STATE_VERSION.into());
+        }
+        let state = toml::Value::Table(table).try_into()?;
+        Ok(ParsedState {
+            state,
+            source_version,
+        })
+    }
+
+    /// Per-environment proxy entries take precedence over the global one.
+    pub fn proxy_for(&self, environment: Option<&str>) -> Option<&ProxySettings> {
+        environment
+            .and_then(|name| self.environment_proxies.get(name))
+            .or(self.proxy.as_ref())
+            .filter(|proxy| !proxy.url.trim().is_empty())
+    }
+
+    /// Host-specific entries win over project-wide ones.
+    pub fn client_certificate_for(
+        &self,
+        project: Option<&Path>,
+        host: Option<&str>,
+    ) -> Option<&ClientCertificate> {
+        self.client_certificates
+            .iter()
+            .filter_map(|entry| entry.match_rank(project, host).map(|rank| (rank, entry)))
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, entry)| entry)
+    }
+
+    pub const fn split_threshold(&self) -> Option<usize> {
+        match self.max_requests_per_file {
+            None => Some(DEFAULT_MAX_REQUESTS_PER_FILE),
+            Some(0) => None,
+            Some(limit) => Some(limit),
+        }
     }
 
     pub fn save(&self) {
-        let Some(path) = state_file_path() else {
-            return;
-        };
+        if let Some(path) = state_file_path() {
+            self.save_to(&path);
+        }
+    }
 
+    fn save_to(&self, path: &Path) {
         if let Some(dir) = path.parent() {
             let _ = fs::create_dir_all(dir);
         }
@@ -58,13 +390,175 @@ impl AppState {
     }
 }
 
+pub struct ParsedState {
+    pub state: AppState,
+    /// Version recorded in the file; `0` for files written before versioning.
+    pub source_version: u32,
+}
+
+/// v0 kept a single `http_root`; v1 stores a list of `project_roots`.
+fn migrate_v0_http_root(table: &mut toml::Table) {
+    let has_projects = table
+        .get("project_roots")
+        .and_then(toml::Value::as_array)
+        .is_some_and(|roots| !roots.is_empty());
+    if has_projects {
+        return;
+    }
+    if let Some(root) = table.remove("http_root") {
+        table.insert("project_roots".to_string(), toml::Value::Array(vec![root]));
+    }
+}
+
+fn backup_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{suffix}.bak"));
+    path.with_file_name(name)
+}
+
 pub fn set_state_file_override(path: PathBuf) -> Result<(), PathBuf> {
     STATE_FILE_OVERRIDE.set(path)
 }
 
-fn state_file_path() -> Option<PathBuf> {
+pub fn state_file_path() -> Option<PathBuf> {
     STATE_FILE_OVERRIDE
         .get()
         .cloned()
         .or_else(|| dirs::config_dir().map(|dir| dir.join("zagel").join("state.toml")))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use tempfile::tempdir;
+
+    use super::{AppState, ClientCertificate, ProxySettings, STATE_VERSION};
+
+    fn proxy(url: &str) -> ProxySettings {
+        ProxySettings {
+            url: url.to_string(),
+            ..ProxySettings::default()
+        }
+    }
+
+    #[test]
+    fn environment_proxy_overrides_global_proxy() {
+        let mut state = AppState {
+            proxy: Some(proxy("http://global:8080")),
+            ..AppState::default()
+        };
+        state
+            .environment_proxies
+            .insert("staging".to_string(), proxy("socks5://staging:1080"));
+
+        assert_eq!(
+            state.proxy_for(Some("staging")).map(|p| p.url.as_str()),
+            Some("socks5://staging:1080")
+        );
+        assert_eq!(
+            state.proxy_for(Some("dev")).map(|p| p.url.as_str()),
+            Some("http://global:8080")
+        );
+        assert_eq!(
+            state.proxy_for(None).map(|p| p.url.as_str()),
+            Some("http://global:8080")
+        );
+    }
+
+    #[test]
+    fn empty_environment_proxy_url_disables_global_proxy() {
+        let mut state = AppState {
+            proxy: Some(proxy("http://global:8080")),
+            ..AppState::default()
+        };
+        state
+            .environment_proxies
+            .insert("local".to_string(), proxy(""));
+
+        assert!(state.proxy_for(Some("local")).is_none());
+    }
+
+    #[test]
+    fn proxy_settings_parse_from_toml() {
+        let raw = r#"
+[proxy]
+url = "http://proxy.corp:3128"
+username = "alice"
+password = "secret"
+
+[environment_proxies.local]
+url = ""
+"#;
+        let state: AppState = toml::from_str(raw).expect("state should parse");
+        let global = state.proxy.expect("global proxy");
+        assert_eq!(global.username.as_deref(), Some("alice"));
+        assert_eq!(global.password.as_deref(), Some("secret"));
+        assert!(state.environment_proxies.contains_key("local"));
+    }
+
+    #[test]
+    fn host_client_certificate_wins_over_project_certificate() {
+        let entry = |host: Option<&str>, project: Option<&str>, cert: &str| ClientCertificate {
+            host: host.map(str::to_string),
+            project: project.map(PathBuf::from),
+            cert: PathBuf::from(cert),
+            key: None,
+        };
+        let state = AppState {
+            client_certificates: vec![
+                entry(None, Some("/work/api"), "project.pem"),
+                entry(Some("internal.example.com"), None, "host.pem"),
+            ],
+            ..AppState::default()
+        };
+        let project = Some(Path::new("/work/api"));
+
+        let cert_for = |project, host| {
+            state
+                .client_certificate_for(project, host)
+                .map(|entry| entry.cert.clone())
+        };
+        assert_eq!(
+            cert_for(project, Some("INTERNAL.example.com")),
+            Some(PathBuf::from("host.pem"))
+        );
+        assert_eq!(
+            cert_for(project, Some("public.example.com")),
+            Some(PathBuf::from("project.pem"))
+        );
+        assert_eq!(cert_for(None, Some("public.example.com")), None);
+    }
+
+    #[test]
+    fn legacy_state_is_migrated_and_backed_up() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("state.toml");
+        let legacy = "http_root = \"/work/api\"\n";
+        std::fs::write(&path, legacy).expect("write legacy state");
+
+        let state = AppState::load_from(&path);
+
+        assert_eq!(state.project_roots, vec![PathBuf::from("/work/api")]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("state.toml.v0.bak")).expect("backup"),
+            legacy
+        );
+        let migrated = std::fs::read_to_string(&path).expect("migrated state");
+        let reparsed = AppState::parse(&migrated).expect("parse migrated state");
+        assert_eq!(reparsed.source_version, STATE_VERSION);
+    }
+
+    #[test]
+    fn invalid_state_is_moved_aside() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("state.toml");
+        std::fs::write(&path, "project_roots = [").expect("write state");
+
+        let state = AppState::load_from(&path);
+
+        assert!(state.project_roots.is_empty());
+        assert!(!path.exists());
+        assert!(dir.path().join("state.toml.invalid.bak").exists());
+    }
+}
@@ -0,0 +1,281 @@
+//! Fixtures for integration tests.
+//!
+//! [`StubServer`] is a tiny HTTP/1.1 server that answers canned responses on a
+//! local port, so `.http` files and automation scenarios can run without the
+//! network:
+//!
+//! ```no_run
+//! use zagel::testing::{StubResponse, StubServer};
+//!
+//! let server = StubServer::builder()
+//!     .route("GET", "/get", StubResponse::json(200, r#"{"ok":true}"#))
+//!     .start()
+//!     .expect("start stub server");
+//! println!("{}", server.url("/get"));
+//! ```
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A canned response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StubResponse {
+    pub status: u16,
+    pub content_type: String,
+    pub body: String,
+}
+
+impl StubResponse {
+    pub fn new(status: u16, content_type: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: content_type.into(),
+            body: body.into(),
+        }
+    }
+
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        Self::new(status, "application/json", body)
+    }
+
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self::new(status, "text/plain", body)
+    }
+
+    fn to_http(&self) -> String {
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+    }
+}
+
+const fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Route {
+    /// `None` matches any method.
+    method: Option<String>,
+    path: String,
+    response: StubResponse,
+}
+
+/// Configures routes and the address before starting a [`StubServer`].
+#[derive(Debug, Clone)]
+pub struct StubServerBuilder {
+    addr: String,
+    routes: Vec<Route>,
+    fallback: StubResponse,
+}
+
+impl Default for StubServerBuilder {
+    fn default() -> Self {
+        Self {
+            addr: "127.0.0.1:0".to_string(),
+            routes: Vec::new(),
+            fallback: StubResponse::json(404, r#"{"error":"not found"}"#),
+        }
+    }
+}
+
+impl StubServerBuilder {
+    /// Address to listen on; defaults to a free port on `127.0.0.1`.
+    #[must_use]
+    pub fn bind(mut self, addr: impl Into<String>) -> Self {
+        self.addr = addr.into();
+        self
+    }
+
+    /// Answers `method path` with `response`. The path is matched without its
+    /// query string, and `*` as the method matches any method.
+    #[must_use]
+    pub fn route(mut self, method: &str, path: impl Into<String>, response: StubResponse) -> Self {
+        self.routes.push(Route {
+            method: (method != "*").then(|| method.to_ascii_uppercase()),
+            path: path.into(),
+            response,
+        });
+        self
+    }
+
+    /// Response for requests no route matches; a JSON 404 by default.
+    #[must_use]
+    pub fn fallback(mut self, response: StubResponse) -> Self {
+        self.fallback = response;
+        self
+    }
+
+    /// Binds the listener and serves requests on a background thread until
+    /// the returned server is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error when the address cannot be bound.
+    pub fn start(self) -> io::Result<StubServer> {
+        let listener = TcpListener::bind(&self.addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&received);
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            while stop_rx.try_recv().is_err() {
+                match listener.accept() {
+                    Ok((mut stream, _addr)) => self.serve(&mut stream, &log),
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(POLL_INTERVAL);
+                    }
+                    Err(_err) => break,
+                }
+            }
+        });
+        Ok(StubServer {
+            addr,
+            received,
+            stop_tx,
+            handle: Some(handle),
+        })
+    }
+
+    fn respond(&self, method: &str, path: &str) -> &StubResponse {
+        let path = path.split_once('?').map_or(path, |(path, _)| path);
+        self.routes
+            .iter()
+            .find(|route| {
+                route.path == path
+                    && route
+                        .method
+                        .as_deref()
+                        .is_none_or(|expected| expected.eq_ignore_ascii_case(method))
+            })
+            .map_or(&self.fallback, |route| &route.response)
+    }
+
+    fn serve(&self, stream: &mut TcpStream, log: &Mutex<Vec<String>>) {
+        let _ = stream.set_nonblocking(false);
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let mut buffer = [0_u8; 4096];
+        let read = stream.read(&mut buffer).unwrap_or(0);
+        let request = String::from_utf8_lossy(&buffer[..read]);
+        let request_line = request.lines().next().unwrap_or_default();
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("GET");
+        let path = parts.next().unwrap_or("/");
+        if let Ok(mut log) = log.lock() {
+            log.push(format!("{method} {path}"));
+        }
+        let _ = stream.write_all(self.respond(method, path).to_http().as_bytes());
+        let _ = stream.flush();
+    }
+}
+
+/// A running stub server; stops when dropped.
+#[derive(Debug)]
+pub struct StubServer {
+    addr: SocketAddr,
+    received: Arc<Mutex<Vec<String>>>,
+    stop_tx: mpsc::Sender<()>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl StubServer {
+    #[must_use]
+    pub fn builder() -> StubServerBuilder {
+        StubServerBuilder::default()
+    }
+
+    #[must_use]
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// `http://` URL for `path` on this server.
+    #[must_use]
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.addr)
+    }
+
+    /// Request lines served so far, as `METHOD /path?query`.
+    #[must_use]
+    pub fn received(&self) -> Vec<String> {
+        self.received
+            .lock()
+            .map(|log| log.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for StubServer {
+    fn drop(&mut self) {
+        let _ = self.stop_tx.send(());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use super::{StubResponse, StubServer};
+
+    fn fetch(server: &StubServer, request_line: &str) -> String {
+        let mut stream = TcpStream::connect(server.addr()).expect("connect to stub");
+        write!(stream, "{request_line} HTTP/1.1\r\nHost: stub\r\n\r\n").expect("write request");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read response");
+        response
+    }
+
+    #[test]
+    fn routes_answer_by_method_and_path() {
+        let server = StubServer::builder()
+            .route("GET", "/get", StubResponse::json(200, r#"{"ok":true}"#))
+            .route("*", "/echo", StubResponse::text(201, "made"))
+            .start()
+            .expect("start stub");
+
+        let ok = fetch(&server, "GET /get?page=2");
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(ok.ends_with(r#"{"ok":true}"#));
+        assert!(fetch(&server, "POST /get").starts_with("HTTP/1.1 404 Not Found"));
+        assert!(fetch(&server, "PUT /echo").ends_with("\r\n\r\nmade"));
+        assert_eq!(
+            server.received(),
+            ["GET /get?page=2", "POST /get", "PUT /echo"]
+        );
+        assert!(server.url("/get").starts_with("http://127.0.0.1:"));
+    }
+}
@@ -1,5 +1,5 @@
 use iced::widget::container;
-use iced::{border, Theme};
+use iced::{Theme, border};
 use iced_highlighter::Theme as HighlightTheme;
 use serde::{Deserialize, Serialize};
 
@@ -41,3 +41,35 @@ pub fn overlay_container_style(theme: &Theme) -> container::Style {
         ..container::Style::default()
     }
 }
+
+/// Small rounded chip behind a request tag in the sidebar.
+pub fn tag_chip_style(theme: &Theme) -> container::Style {
+    let palette = theme.extended_palette();
+    container::Style {
+        background: Some(palette.background.strong.color.into()),
+        text_color: Some(palette.background.strong.text),
+        border: border::rounded(8.0),
+        ..container::Style::default()
+    }
+}
+
+/// Chip behind a response status code: green for 2xx, blue for 3xx, orange
+/// for 4xx and red for 5xx.
+pub fn status_chip_style(status: u16) -> impl Fn(&Theme) -> container::Style {
+    move |theme| {
+        let palette = theme.extended_palette();
+        let pair = match status {
+            200..=299 => palette.success.base,
+            300..=399 => palette.primary.base,
+            400..=499 => palette.warning.base,
+            500..=599 => palette.danger.base,
+            _ => palette.background.strong,
+        };
+        container::Style {
+            background: Some(pair.color.into()),
+            text_color: Some(pair.text),
+            border: border::rounded(10.0),
+            ..container::Style::default()
+        }
+    }
+}
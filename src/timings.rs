@@ -0,0 +1,96 @@
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CSV_HEADER: &str = "timestamp_ms,request,status,duration_ms,error";
+
+/// One timed send, as exported for external graphing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimingRecord {
+    pub timestamp: SystemTime,
+    pub request: String,
+    pub status: Option<u16>,
+    pub duration: Option<Duration>,
+    pub error: Option<String>,
+}
+
+pub fn to_csv(records: &[TimingRecord]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for record in records {
+        let timestamp = record
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_millis());
+        let status = record.status.map(|status| status.to_string());
+        let duration = record
+            .duration
+            .map(|duration| format!("{:.3}", duration.as_secs_f64() * 1000.0));
+        writeln!(
+            csv,
+            "{timestamp},{},{},{},{}",
+            escape_field(&record.request),
+            status.unwrap_or_default(),
+            duration.unwrap_or_default(),
+            escape_field(record.error.as_deref().unwrap_or_default()),
+        )
+        .ok();
+    }
+    csv
+}
+
+fn escape_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{TimingRecord, to_csv};
+
+    #[test]
+    fn csv_has_header_and_one_row_per_record() {
+        let records = vec![
+            TimingRecord {
+                timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+                request: "A: GET https://example.com".to_string(),
+                status: Some(200),
+                duration: Some(Duration::from_micros(12_345)),
+                error: None,
+            },
+            TimingRecord {
+                timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_456),
+                request: "B: GET https://example.com/slow".to_string(),
+                status: None,
+                duration: None,
+                error: Some("timed out".to_string()),
+            },
+        ];
+
+        assert_eq!(
+            to_csv(&records),
+            "timestamp_ms,request,status,duration_ms,error\n\
+             1700000000123,A: GET https://example.com,200,12.345,\n\
+             1700000000456,B: GET https://example.com/slow,,,timed out\n"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_separators() {
+        let records = vec![TimingRecord {
+            timestamp: UNIX_EPOCH,
+            request: "Create \"user\", then log in".to_string(),
+            status: Some(500),
+            duration: Some(Duration::from_millis(1)),
+            error: Some("line one\nline two".to_string()),
+        }];
+
+        let csv = to_csv(&records);
+        assert!(csv.contains("\"Create \"\"user\"\", then log in\""));
+        assert!(csv.contains("\"line one\nline two\""));
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt::Write as _;
+
+/// ASCII characters that are not allowed unescaped in a URL.
+const UNSAFE_ASCII: &[char] = &[' ', '"', '<', '>', '\\', '^', '`', '{', '|', '}'];
+
+/// A URL split into the parts the builder edits. Parsing is lenient so
+/// `{{base}}/users` splits too; joining gives back the same text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UrlParts {
+    /// Without `://`; empty when the URL starts with a placeholder.
+    pub scheme: String,
+    /// Host with port and userinfo, if any.
+    pub host: String,
+    /// Segments between `/`; an empty last one keeps a trailing slash.
+    pub segments: Vec<String>,
+    /// `name=value` pairs; an empty value is written as just `name`.
+    pub query: Vec<(String, String)>,
+    pub fragment: Option<String>,
+}
+
+impl UrlParts {
+    pub fn parse(url: &str) -> Self {
+        let url = url.trim();
+        let (url, fragment) = match url.split_once('#') {
+            Some((url, fragment)) => (url, Some(fragment.to_string())),
+            None => (url, None),
+        };
+        let (url, query) = url.split_once('?').unwrap_or((url, ""));
+        let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+        let (host, path) = rest.find('/').map_or((rest, ""), |at| rest.split_at(at));
+        let segments = path
+            .strip_prefix('/')
+            .map(|path| path.split('/').map(str::to_string).collect())
+            .unwrap_or_default();
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (name.to_string(), value.to_string())
+            })
+            .collect();
+        Self {
+            scheme: scheme.to_string(),
+            host: host.to_string(),
+            segments,
+            query,
+            fragment,
+        }
+    }
+
+    pub fn to_url(&self) -> String {
+        let mut url = String::new();
+        if !self.scheme.is_empty() {
+            let _ = write!(url, "{}://", self.scheme);
+        }
+        url.push_str(&self.host);
+        for segment in &self.segments {
+            url.push('/');
+            url.push_str(segment);
+        }
+        for (index, (name, value)) in self.query.iter().enumerate() {
+            url.push(if index == 0 { '?' } else { '&' });
+            url.push_str(name);
+            if !value.is_empty() {
+                url.push('=');
+                url.push_str(value);
+            }
+        }
+        if let Some(fragment) = &self.fragment {
+            url.push('#');
+            url.push_str(fragment);
+        }
+        url
+    }
+}
+
+fn is_unsafe(c: char) -> bool {
+    !c.is_ascii() || c.is_ascii_control() || UNSAFE_ASCII.contains(&c)
+}
+
+/// Where the host ends: unicode hosts are converted to punycode by the HTTP
+/// client and must not be percent-encoded.
+fn path_start(url: &str) -> usize {
+    let authority = url.find("://").map_or(0, |at| at + 3);
+    url[authority..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |at| authority + at)
+}
+
+/// Percent-encodes spaces, non-ASCII and other unsafe characters after the
+/// host, leaving existing `%xx` escapes alone.
+pub fn encode_unsafe(url: &str) -> String {
+    let start = path_start(url);
+    let mut encoded = url[..start].to_string();
+    for c in url[start..].chars() {
+        if is_unsafe(c) {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+    encoded
+}
+
+/// Rewrites the `:name` and `{name}` path parameters of `url` with
+/// `replace`, which keeps a parameter as written by returning `None`.
+/// `{{variables}}`, the host and the query are left alone.
+fn map_path_params(url: &str, mut replace: impl FnMut(&str) -> Option<String>) -> String {
+    let start = path_start(url);
+    let end = url[start..]
+        .find(['?', '#'])
+        .map_or(url.len(), |at| start + at);
+    let mut mapped = url[..start].to_string();
+    for (index, segment) in url[start..end].split('/').enumerate() {
+        if index > 0 {
+            mapped.push('/');
+        }
+        if let Some(rest) = segment.strip_prefix(':') {
+            let name_end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            if name_end > 0 {
+                let name = &rest[..name_end];
+                mapped.push_str(&replace(name).unwrap_or_else(|| format!(":{name}")));
+                mapped.push_str(&rest[name_end..]);
+                continue;
+            }
+        }
+        let mut rest = segment;
+        while let Some(open) = rest.find('{') {
+            mapped.push_str(&rest[..open]);
+            rest = &rest[open..];
+            if rest.starts_with("{{") {
+                let close = rest.find("}}").map_or(rest.len(), |at| at + 2);
+                mapped.push_str(&rest[..close]);
+                rest = &rest[close..];
+                continue;
+            }
+            let name = rest[1..]
+                .find('}')
+                .map(|close| &rest[1..=close])
+                .filter(|name| {
+                    !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                });
+            if let Some(name) = name {
+                mapped.push_str(&replace(name).unwrap_or_else(|| format!("{{{name}}}")));
+                rest = &rest[name.len() + 2..];
+            } else {
+                mapped.push('{');
+                rest = &rest[1..];
+            }
+        }
+        mapped.push_str(rest);
+    }
+    mapped.push_str(&url[end..]);
+    mapped
+}
+
+/// Names of the `:id`-style and `{id}`-style path parameters of `url`, in
+/// order and without repeats.
+pub fn path_param_names(url: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    map_path_params(url.trim(), |name| {
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+    names
+}
+
+/// `url` with its path parameters replaced by their values in `params`;
+/// parameters without a value are left as written.
+pub fn apply_path_params(url: &str, params: &[(String, String)]) -> String {
+    if params.is_empty() {
+        return url.to_string();
+    }
+    map_path_params(url, |name| {
+        params
+            .iter()
+            .find(|(param, value)| param == name && !value.is_empty())
+            .map(|(_, value)| value.clone())
+    })
+}
+
+fn without_placeholders(text: &str) -> String {
+    let mut rest = text;
+    let mut kept = String::new();
+    while let Some(open) = rest.find("{{") {
+        kept.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find("}}") else {
+            rest = &rest[open..];
+            break;
+        };
+        rest = &rest[open + close + 2..];
+    }
+    kept.push_str(rest);
+    kept
+}
+
+/// Warning for a URL that only works because it is encoded on send.
+/// Placeholders are left out since their braces are not sent.
+pub fn encoding_warning(url: &str) -> Option<&'static str> {
+    let url = url.trim();
+    let outside_placeholders = without_placeholders(url);
+    if outside_placeholders.contains(' ') {
+        Some("The URL contains spaces; they are sent as %20")
+    } else if !outside_placeholders.is_ascii() {
+        Some("The URL contains non-ASCII characters; they are sent percent-encoded")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UrlParts, apply_path_params, encode_unsafe, encoding_warning, path_param_names};
+
+    #[test]
+    fn urls_split_into_parts_and_join_back() {
+        let url = "https://api.test:8443/v1/users/?q=a%20b&flag&page=2#top";
+        let parts = UrlParts::parse(url);
+        assert_eq!(parts.scheme, "https");
+        assert_eq!(parts.host, "api.test:8443");
+        assert_eq!(parts.segments, ["v1", "users", ""]);
+        assert_eq!(parts.query[1], ("flag".to_string(), String::new()));
+        assert_eq!(parts.fragment.as_deref(), Some("top"));
+        assert_eq!(parts.to_url(), url);
+
+        let parts = UrlParts::parse("{{base}}/users");
+        assert_eq!(parts.host, "{{base}}");
+        assert_eq!(parts.to_url(), "{{base}}/users");
+    }
+
+    #[test]
+    fn unsafe_characters_are_encoded_after_the_host() {
+        assert_eq!(
+            encode_unsafe("https://bücher.test/a b/ü?q=x|y&r=50%25"),
+            "https://bücher.test/a%20b/%C3%BC?q=x%7Cy&r=50%25"
+        );
+        assert!(encoding_warning("https://api.test/a b").is_some());
+        assert!(encoding_warning("https://api.test/straße").is_some());
+        assert_eq!(encoding_warning("{{base}}/users?q={{ term }}"), None);
+    }
+
+    #[test]
+    fn path_params_are_found_and_filled() {
+        let url = "https://api.test:8443/users/:id/posts/{post_id}.json?sort=:x#:y";
+        assert_eq!(path_param_names(url), vec!["id", "post_id"]);
+        assert_eq!(
+            path_param_names("{{base}}/orgs/{{org}}/:id/{id}"),
+            vec!["id"]
+        );
+        assert_eq!(
+            apply_path_params(
+                url,
+                &[
+                    ("id".to_string(), "42".to_string()),
+                    ("post_id".to_string(), String::new())
+                ]
+            ),
+            "https://api.test:8443/users/42/posts/{post_id}.json?sort=:x#:y"
+        );
+    }
+}
@@ -1,15 +1,12 @@
 use std::fs;
-use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
-use std::sync::mpsc;
 use std::thread;
-use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
 use serde::Deserialize;
 use tempfile::{TempDir, tempdir};
+use zagel::testing::{StubResponse, StubServer};
 
 const LOCAL_STUB_ADDR: &str = "127.0.0.1:18080";
 const SCENARIO_TIMEOUT: Duration = Duration::from_secs(60);
@@ -44,20 +41,6 @@ struct ScenarioRunPaths {
     state_json: PathBuf,
 }
 
-struct LocalStubServer {
-    stop_tx: mpsc::Sender<()>,
-    handle: Option<JoinHandle<()>>,
-}
-
-impl Drop for LocalStubServer {
-    fn drop(&mut self) {
-        let _ = self.stop_tx.send(());
-        if let Some(handle) = self.handle.take() {
-            let _ = handle.join();
-        }
-    }
-}
-
 fn e2e_enabled() -> bool {
     std::env::var("ZAGEL_E2E")
         .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
@@ -177,60 +160,17 @@ fn read_snapshot(path: &Path) -> StateSnapshot {
         .expect("parse automation state snapshot json")
 }
 
-fn handle_local_stub_connection(stream: &mut TcpStream) {
-    let _ = stream.set_read_timeout(Some(Duration::from_millis(500)));
-
-    let mut buffer = [0_u8; 4096];
-    let read = stream.read(&mut buffer).unwrap_or(0);
-    let request = String::from_utf8_lossy(&buffer[..read]);
-    let path = request
-        .lines()
-        .next()
-        .and_then(|line| line.split_whitespace().nth(1))
-        .unwrap_or("/");
-
-    let (status, body) = match path {
-        "/get" => ("200 OK", r#"{"ok":true}"#),
-        "/uuid" => ("200 OK", r#"{"uuid":"local-test-uuid"}"#),
-        _ => ("404 Not Found", r#"{"error":"not found"}"#),
-    };
-
-    let response = format!(
-        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
-        body.len()
-    );
-    let _ = stream.write_all(response.as_bytes());
-    let _ = stream.flush();
-}
-
-fn start_local_stub_server() -> LocalStubServer {
-    let listener = TcpListener::bind(LOCAL_STUB_ADDR)
-        .expect("bind local stub server (port 18080 must be free)");
-    listener
-        .set_nonblocking(true)
-        .expect("set local stub listener non-blocking");
-
-    let (stop_tx, stop_rx) = mpsc::channel::<()>();
-    let handle = thread::spawn(move || {
-        loop {
-            if stop_rx.try_recv().is_ok() {
-                break;
-            }
-
-            match listener.accept() {
-                Ok((mut stream, _addr)) => handle_local_stub_connection(&mut stream),
-                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
-                    thread::sleep(Duration::from_millis(20));
-                }
-                Err(_err) => break,
-            }
-        }
-    });
-
-    LocalStubServer {
-        stop_tx,
-        handle: Some(handle),
-    }
+fn start_local_stub_server() -> StubServer {
+    StubServer::builder()
+        .bind(LOCAL_STUB_ADDR)
+        .route("GET", "/get", StubResponse::json(200, r#"{"ok":true}"#))
+        .route(
+            "GET",
+            "/uuid",
+            StubResponse::json(200, r#"{"uuid":"local-test-uuid"}"#),
+        )
+        .start()
+        .expect("bind local stub server (port 18080 must be free)")
 }
 
 #[test]
@@ -261,6 +201,7 @@ fn automation_navigation_scenario_emits_screenshots_and_state() {
 
     require_file(&paths.artifacts.join("02-selected.png"));
     require_file(&paths.artifacts.join("04-ready.png"));
+    require_file(&paths.artifacts.join("05-sidebar-pane.png"));
     require_file(&paths.state_json);
 
     let snapshot = read_snapshot(&paths.state_json);